@@ -0,0 +1,297 @@
+//! Scheduling of oracle attestation requests.
+//!
+//! The [`crate::manager::Manager`] looks for the attestations of every
+//! confirmed contract on each periodic check. The [`AttestationScheduler`]
+//! keeps that from turning into a request per contract per tick:
+//!
+//! - an oracle is not asked before the announced `event_maturity_epoch`,
+//! - a failed request is retried with exponential backoff,
+//! - an attestation is cached once found, until its contract is closed.
+//!
+//! Every request is also accounted in the [`OracleHealth`] of its oracle.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use ddk_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
+use secp256k1_zkp::XOnlyPublicKey;
+
+/// Delays between the attempts to fetch an attestation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AttestationBackoff {
+    /// Delay after the first failed attempt, in seconds.
+    pub initial_delay: u64,
+    /// Upper bound of the delay, in seconds.
+    pub max_delay: u64,
+}
+
+impl Default for AttestationBackoff {
+    fn default() -> Self {
+        AttestationBackoff {
+            initial_delay: 30,
+            max_delay: 60 * 60,
+        }
+    }
+}
+
+impl AttestationBackoff {
+    /// The delay after `attempts` failed attempts.
+    fn delay(&self, attempts: u32) -> u64 {
+        let exponent = attempts.saturating_sub(1).min(63);
+        self.initial_delay
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_delay)
+    }
+}
+
+/// Request statistics of an oracle.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OracleHealth {
+    /// Number of attestation requests made to the oracle.
+    pub requests: u64,
+    /// Number of requests that failed or returned an invalid attestation.
+    pub failures: u64,
+    /// Number of failures since the last successful request.
+    pub consecutive_failures: u32,
+    /// Latency of the last request.
+    pub last_latency: Option<Duration>,
+    /// Average latency of all requests.
+    pub average_latency: Option<Duration>,
+    /// Time of the last successful request, in seconds since the epoch.
+    pub last_success: Option<u64>,
+    /// Error of the last failed request.
+    pub last_error: Option<String>,
+    total_latency: Duration,
+}
+
+impl OracleHealth {
+    fn record(&mut self, latency: Duration) {
+        self.requests += 1;
+        self.total_latency += latency;
+        self.last_latency = Some(latency);
+        self.average_latency = Some(Duration::from_nanos(
+            (self.total_latency.as_nanos() / self.requests as u128) as u64,
+        ));
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Retry {
+    attempts: u32,
+    next_attempt: u64,
+}
+
+type EventKey = (XOnlyPublicKey, String);
+
+/// Decides when to ask oracles for attestations and remembers the answers.
+#[derive(Debug, Default)]
+pub struct AttestationScheduler {
+    backoff: AttestationBackoff,
+    retries: HashMap<EventKey, Retry>,
+    attestations: HashMap<EventKey, OracleAttestation>,
+    health: HashMap<XOnlyPublicKey, OracleHealth>,
+}
+
+impl AttestationScheduler {
+    /// Creates a scheduler retrying failed requests with `backoff`.
+    pub fn new(backoff: AttestationBackoff) -> Self {
+        AttestationScheduler {
+            backoff,
+            ..Default::default()
+        }
+    }
+
+    /// Changes the delays between attempts. Attempts already scheduled keep
+    /// their time.
+    pub fn set_backoff(&mut self, backoff: AttestationBackoff) {
+        self.backoff = backoff;
+    }
+
+    /// The attestation for an announcement, if it was already found.
+    pub fn attestation(&self, announcement: &OracleAnnouncement) -> Option<&OracleAttestation> {
+        self.attestations.get(&event_key(announcement))
+    }
+
+    /// Whether the oracle should be asked for the attestation at `now`: the
+    /// event matured, and the backoff after the last failed attempt passed.
+    pub fn is_due(&self, announcement: &OracleAnnouncement, now: u64) -> bool {
+        if (announcement.oracle_event.event_maturity_epoch as u64) > now {
+            return false;
+        }
+        let key = event_key(announcement);
+        if self.attestations.contains_key(&key) {
+            return false;
+        }
+        self.retries
+            .get(&key)
+            .is_none_or(|retry| retry.next_attempt <= now)
+    }
+
+    /// Records a valid attestation returned for `announcement`.
+    pub fn record_success(
+        &mut self,
+        announcement: &OracleAnnouncement,
+        attestation: OracleAttestation,
+        latency: Duration,
+        now: u64,
+    ) {
+        let health = self
+            .health
+            .entry(announcement.oracle_public_key)
+            .or_default();
+        health.record(latency);
+        health.consecutive_failures = 0;
+        health.last_success = Some(now);
+
+        let key = event_key(announcement);
+        self.retries.remove(&key);
+        self.attestations.insert(key, attestation);
+    }
+
+    /// Records a failed request for the attestation of `announcement` and
+    /// schedules the next attempt.
+    pub fn record_failure(
+        &mut self,
+        announcement: &OracleAnnouncement,
+        error: String,
+        latency: Duration,
+        now: u64,
+    ) {
+        let health = self
+            .health
+            .entry(announcement.oracle_public_key)
+            .or_default();
+        health.record(latency);
+        health.failures += 1;
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+        health.last_error = Some(error);
+
+        let retry = self
+            .retries
+            .entry(event_key(announcement))
+            .or_insert(Retry {
+                attempts: 0,
+                next_attempt: now,
+            });
+        retry.attempts = retry.attempts.saturating_add(1);
+        retry.next_attempt = now.saturating_add(self.backoff.delay(retry.attempts));
+    }
+
+    /// Forgets the attestation and retry state of an announcement, once no
+    /// contract needs it anymore.
+    pub fn forget(&mut self, announcement: &OracleAnnouncement) {
+        let key = event_key(announcement);
+        self.retries.remove(&key);
+        self.attestations.remove(&key);
+    }
+
+    /// The request statistics of every oracle asked so far.
+    pub fn health(&self) -> &HashMap<XOnlyPublicKey, OracleHealth> {
+        &self.health
+    }
+}
+
+fn event_key(announcement: &OracleAnnouncement) -> EventKey {
+    (
+        announcement.oracle_public_key,
+        announcement.oracle_event.event_id.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ddk_messages::oracle_msgs::{EnumEventDescriptor, EventDescriptor, OracleEvent};
+    use secp256k1_zkp::schnorr::Signature;
+
+    use super::*;
+
+    const MATURITY: u64 = 1_000;
+
+    fn announcement() -> OracleAnnouncement {
+        let pubkey = XOnlyPublicKey::from_str(
+            "e6642fd69bd211f93f7f1f36ca51a26a5290eb2dd1b0d8279a87bb0d480c8443",
+        )
+        .unwrap();
+        OracleAnnouncement {
+            announcement_signature: Signature::from_slice(&[1; 64]).unwrap(),
+            oracle_public_key: pubkey,
+            oracle_event: OracleEvent {
+                oracle_nonces: vec![pubkey],
+                event_maturity_epoch: MATURITY as u32,
+                event_descriptor: EventDescriptor::EnumEvent(EnumEventDescriptor {
+                    outcomes: vec!["1".to_string(), "2".to_string()],
+                }),
+                event_id: "event".to_string(),
+            },
+        }
+    }
+
+    fn attestation(announcement: &OracleAnnouncement) -> OracleAttestation {
+        OracleAttestation {
+            event_id: announcement.oracle_event.event_id.clone(),
+            oracle_public_key: announcement.oracle_public_key,
+            signatures: vec![Signature::from_slice(&[2; 64]).unwrap()],
+            outcomes: vec!["1".to_string()],
+        }
+    }
+
+    #[test]
+    fn waits_for_maturity_and_backs_off() {
+        let announcement = announcement();
+        let mut scheduler = AttestationScheduler::new(AttestationBackoff {
+            initial_delay: 10,
+            max_delay: 25,
+        });
+        let latency = Duration::from_millis(20);
+        assert!(!scheduler.is_due(&announcement, MATURITY - 1));
+        assert!(scheduler.is_due(&announcement, MATURITY));
+
+        let mut now = MATURITY;
+        for delay in [10, 20, 25, 25] {
+            scheduler.record_failure(&announcement, "not attested".to_string(), latency, now);
+            assert!(!scheduler.is_due(&announcement, now + delay - 1));
+            assert!(scheduler.is_due(&announcement, now + delay));
+            now += delay;
+        }
+        let health = &scheduler.health()[&announcement.oracle_public_key];
+        assert_eq!(health.consecutive_failures, 4);
+        assert_eq!(health.last_error.as_deref(), Some("not attested"));
+    }
+
+    #[test]
+    fn caches_attestations_and_tracks_health() {
+        let announcement = announcement();
+        let mut scheduler = AttestationScheduler::default();
+        scheduler.record_failure(
+            &announcement,
+            "timeout".to_string(),
+            Duration::from_millis(300),
+            MATURITY,
+        );
+        scheduler.record_success(
+            &announcement,
+            attestation(&announcement),
+            Duration::from_millis(100),
+            MATURITY + 60,
+        );
+
+        assert_eq!(
+            scheduler.attestation(&announcement),
+            Some(&attestation(&announcement))
+        );
+        assert!(!scheduler.is_due(&announcement, MATURITY + 120));
+        let health = &scheduler.health()[&announcement.oracle_public_key];
+        assert_eq!(health.requests, 2);
+        assert_eq!(health.failures, 1);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_success, Some(MATURITY + 60));
+        assert_eq!(health.last_latency, Some(Duration::from_millis(100)));
+        assert_eq!(health.average_latency, Some(Duration::from_millis(200)));
+
+        scheduler.forget(&announcement);
+        assert!(scheduler.attestation(&announcement).is_none());
+        assert!(scheduler.is_due(&announcement, MATURITY + 120));
+    }
+}
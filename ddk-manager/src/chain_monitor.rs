@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use bitcoin::{Block, OutPoint, Transaction, Txid};
+use bitcoin::{Amount, Block, OutPoint, Transaction, Txid};
 use ddk_messages::ser_impls::{
     read_ecdsa_adaptor_signature, read_hash_map, write_ecdsa_adaptor_signature, write_hash_map,
 };
@@ -38,6 +38,9 @@ pub(crate) enum TxType {
         own_adaptor_signature: EcdsaAdaptorSignature,
         is_offer: bool,
         revoked_tx_type: RevokedTxType,
+        /// Value of the output of the revoked transaction claimed when
+        /// punishing its publication.
+        revoked_value: Amount,
     },
     BufferTx,
     CollaborativeClose,
@@ -50,7 +53,8 @@ impl_dlc_writeable_enum!(TxType,;
         (update_idx, writeable),
         (own_adaptor_signature, {cb_writeable, write_ecdsa_adaptor_signature, read_ecdsa_adaptor_signature}),
         (is_offer, writeable),
-        (revoked_tx_type, writeable)
+        (revoked_tx_type, writeable),
+        (revoked_value, writeable)
     });;
     (1, BufferTx), (2, CollaborativeClose), (3, SettleTx), (4, Cet)
 );
@@ -63,6 +67,17 @@ pub(crate) enum RevokedTxType {
 
 impl_dlc_writeable_enum!(RevokedTxType,;;;(0, Buffer), (1, Settle));
 
+impl RevokedTxType {
+    /// Returns the value of the output of `revoked_tx` claimed when punishing
+    /// its publication.
+    pub(crate) fn revoked_value(&self, revoked_tx: &Transaction, is_offer: bool) -> Amount {
+        match self {
+            RevokedTxType::Buffer => revoked_tx.output[0].value,
+            RevokedTxType::Settle => revoked_tx.output[usize::from(is_offer)].value,
+        }
+    }
+}
+
 impl ChainMonitor {
     /// Returns a new [`ChainMonitor`] with fields properly initialized.
     pub fn new(init_height: u64) -> Self {
@@ -141,6 +156,35 @@ impl ChainMonitor {
         self.last_height += 1;
     }
 
+    /// Adds the watched transactions of `other` that are not watched yet,
+    /// and rewinds to the lowest of both last heights so the blocks seen by
+    /// only one of them are processed again.
+    pub fn merge(&mut self, other: ChainMonitor) {
+        for (txid, state) in other.watched_tx {
+            self.watched_tx.entry(txid).or_insert(state);
+        }
+        for (outpoint, state) in other.watched_txo {
+            self.watched_txo.entry(outpoint).or_insert(state);
+        }
+        self.last_height = self.last_height.min(other.last_height);
+    }
+
+    /// All the currently watched revoked transactions which have not been
+    /// seen in a block.
+    pub(crate) fn unconfirmed_revoked_txs(&self) -> Vec<(Txid, ChannelInfo)> {
+        self.watched_tx
+            .iter()
+            .filter_map(|(txid, state)| match state {
+                WatchState::Registered { channel_info }
+                    if matches!(channel_info.tx_type, TxType::Revoked { .. }) =>
+                {
+                    Some((*txid, *channel_info))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     /// All the currently watched transactions which have been confirmed.
     pub(crate) fn confirmed_txs(&self) -> Vec<(Transaction, ChannelInfo)> {
         (self.watched_tx.values())
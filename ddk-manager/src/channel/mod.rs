@@ -58,7 +58,16 @@ pub enum Channel {
 
 impl std::fmt::Debug for Channel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let state = match self {
+        f.debug_struct("Contract")
+            .field("state", &self.state_name())
+            .finish()
+    }
+}
+
+impl Channel {
+    /// Returns the name of the state of the channel.
+    pub fn state_name(&self) -> &'static str {
+        match self {
             Channel::Offered(_) => "offered",
             Channel::Accepted(_) => "accepted",
             Channel::Signed(_) => "signed",
@@ -70,12 +79,9 @@ impl std::fmt::Debug for Channel {
             Channel::CounterClosed(_) => "counter closed",
             Channel::ClosedPunished(_) => "closed punished",
             Channel::CollaborativelyClosed(_) => "collaboratively closed",
-        };
-        f.debug_struct("Contract").field("state", &state).finish()
+        }
     }
-}
 
-impl Channel {
     /// Returns the public key of the counter party's node.
     pub fn get_counter_party_id(&self) -> PublicKey {
         match self {
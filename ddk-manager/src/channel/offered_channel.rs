@@ -124,6 +124,9 @@ impl OfferedChannel {
             total_collateral: offer_channel.contract_info.get_total_collateral(),
             contract_flags: offer_channel.contract_flags,
             keys_id,
+            taproot: None,
+            taproot_nonces: None,
+            batch_funding_group: None,
         };
 
         Ok((channel, contract))
@@ -337,6 +337,7 @@ typed_enum!(
     },
     /// Enum automatically generated associating a number to each signed channel
     /// state.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     SignedChannelStateType,
 );
 
@@ -102,6 +102,7 @@ where
         total_collateral,
         vec![],
         contract.fee_rate,
+        0,
         wallet,
         &signer,
         blockchain,
@@ -173,6 +174,7 @@ where
         total_collateral,
         vec![],
         offered_contract.fee_rate_per_vb,
+        0,
         wallet,
         &signer,
         blockchain,
@@ -1250,6 +1252,9 @@ where
         refund_locktime: renew_offer.refund_locktime,
         contract_flags: 0,
         keys_id,
+        taproot: None,
+        taproot_nonces: None,
+        batch_funding_group: None,
     };
 
     let mut state = SignedChannelState::RenewOffered {
@@ -4,6 +4,8 @@ use super::offered_contract::OfferedContract;
 use super::AdaptorInfo;
 use bitcoin::{Amount, SignedAmount, Transaction};
 use ddk_dlc::{DlcTransactions, PartyParams};
+use ddk_messages::taproot_msgs::TaprootAccept;
+use ddk_messages::tlv_stream::TlvStream;
 use ddk_messages::{AcceptDlc, FundingInput};
 use secp256k1_zkp::ecdsa::Signature;
 use secp256k1_zkp::EcdsaAdaptorSignature;
@@ -29,6 +31,18 @@ pub struct AcceptedContract {
     pub accept_refund_signature: Signature,
     /// The bitcoin set of bitcoin transactions for the contract.
     pub dlc_transactions: DlcTransactions,
+    /// The MuSig2 nonces and partial signatures of the accepting party, set
+    /// if and only if the contract is Taproot funded. The adaptor signatures
+    /// are then empty and the refund signature is unused.
+    pub taproot: Option<TaprootAccept>,
+}
+
+impl std::fmt::Debug for AcceptedContract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AcceptedContract")
+            .field("contract_id", &self.get_contract_id_string())
+            .finish()
+    }
 }
 
 impl AcceptedContract {
@@ -72,6 +86,8 @@ impl AcceptedContract {
             cet_adaptor_signatures: ecdsa_adaptor_signatures.into(),
             refund_signature: self.accept_refund_signature,
             negotiation_fields: None,
+            taproot: self.taproot.clone(),
+            tlvs: TlvStream::default(),
         }
     }
 
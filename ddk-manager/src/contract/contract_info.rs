@@ -274,6 +274,30 @@ impl ContractInfo {
         }
     }
 
+    /// Generate the adaptor info for the contract together with the CET index
+    /// and adaptor point of each adaptor signature, ordered by adaptor index.
+    /// Used by Taproot funded contracts, whose CET signatures are MuSig2
+    /// partial adaptor signatures rather than ECDSA adaptor signatures.
+    pub fn get_adaptor_points<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        total_collateral: Amount,
+        adaptor_index_start: usize,
+    ) -> Result<(AdaptorInfo, Vec<(usize, PublicKey)>), Error> {
+        match &self.contract_descriptor {
+            ContractDescriptor::Enum(e) => Ok((
+                AdaptorInfo::Enum,
+                e.get_adaptor_points(secp, &self.get_oracle_infos(), self.threshold)?,
+            )),
+            ContractDescriptor::Numerical(n) => n.get_adaptor_points(
+                total_collateral,
+                self.threshold,
+                &self.precompute_points(secp)?,
+                adaptor_index_start,
+            ),
+        }
+    }
+
     fn precompute_points<C: Verification>(
         &self,
         secp: &Secp256k1<C>,
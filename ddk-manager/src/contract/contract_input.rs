@@ -81,7 +81,9 @@ pub struct ContractInput {
     pub accept_collateral: Amount,
     /// The fee rate used to construct the transactions.
     pub fee_rate: u64,
-    /// Feature flags for the contract (bit 0: refund to accepter).
+    /// Feature flags for the contract (bit 0: refund to accepter, bit 2:
+    /// Taproot funding, see [`ddk_dlc::TAPROOT_FUNDING_FLAG`], bit 3: anchor
+    /// outputs, see [`ddk_dlc::ANCHOR_OUTPUTS_FLAG`]).
     #[cfg_attr(feature = "use-serde", serde(default))]
     pub contract_flags: u8,
     /// The set of contract that make up the DLC (a single DLC can be based
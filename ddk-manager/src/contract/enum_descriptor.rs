@@ -231,6 +231,25 @@ impl EnumDescriptor {
         Ok(adaptor_sigs)
     }
 
+    /// Returns the CET index and adaptor point of each adaptor signature.
+    pub fn get_adaptor_points<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        oracle_infos: &[OracleInfo],
+        threshold: usize,
+    ) -> Result<Vec<(usize, PublicKey)>, ddk_dlc::Error> {
+        let mut adaptor_points = Vec::new();
+        let mut callback =
+            |adaptor_point: &PublicKey, cet_index: usize| -> Result<(), ddk_dlc::Error> {
+                adaptor_points.push((cet_index, *adaptor_point));
+                Ok(())
+            };
+
+        self.iter_outcomes(secp, oracle_infos, threshold, &mut callback)?;
+
+        Ok(adaptor_points)
+    }
+
     fn iter_outcomes<C: Verification, F>(
         &self,
         secp: &Secp256k1<C>,
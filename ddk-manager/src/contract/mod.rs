@@ -25,6 +25,7 @@ pub mod numerical_descriptor;
 pub mod offered_contract;
 pub mod ser;
 pub mod signed_contract;
+pub mod taproot;
 pub(crate) mod utils;
 
 /// Converts wire-level contract information into the execution information
@@ -58,6 +59,9 @@ pub enum Contract {
     FailedSign(FailedSignContract),
     /// A contract that was rejected by the party to whom it was offered.
     Rejected(offered_contract::OfferedContract),
+    /// A contract offered or accepted that was not answered by the counter
+    /// party before the offer expired.
+    Expired(offered_contract::OfferedContract),
 }
 
 impl std::fmt::Debug for Contract {
@@ -73,6 +77,7 @@ impl std::fmt::Debug for Contract {
             Contract::FailedAccept(_) => "failed accept",
             Contract::FailedSign(_) => "failed sign",
             Contract::Rejected(_) => "rejected",
+            Contract::Expired(_) => "expired",
         };
         f.debug_struct("Contract").field("state", &state).finish()
     }
@@ -83,7 +88,7 @@ impl Contract {
     /// and failed accept contracts.
     pub fn get_id(&self) -> ContractId {
         match self {
-            Contract::Offered(o) | Contract::Rejected(o) => o.id,
+            Contract::Offered(o) | Contract::Rejected(o) | Contract::Expired(o) => o.id,
             Contract::Accepted(o) => o.get_contract_id(),
             Contract::Signed(o) | Contract::Confirmed(o) | Contract::Refunded(o) => {
                 o.accepted_contract.get_contract_id()
@@ -110,7 +115,7 @@ impl Contract {
     /// Returns the temporary contract id of a contract.
     pub fn get_temporary_id(&self) -> ContractId {
         match self {
-            Contract::Offered(o) | Contract::Rejected(o) => o.id,
+            Contract::Offered(o) | Contract::Rejected(o) | Contract::Expired(o) => o.id,
             Contract::Accepted(o) => o.offered_contract.id,
             Contract::Signed(o) | Contract::Confirmed(o) | Contract::Refunded(o) => {
                 o.accepted_contract.offered_contract.id
@@ -125,7 +130,7 @@ impl Contract {
     /// Returns the public key of the counter party's node.
     pub fn get_counter_party_id(&self) -> PublicKey {
         match self {
-            Contract::Offered(o) | Contract::Rejected(o) => o.counter_party,
+            Contract::Offered(o) | Contract::Rejected(o) | Contract::Expired(o) => o.counter_party,
             Contract::Accepted(a) => a.offered_contract.counter_party,
             Contract::Signed(s) | Contract::Confirmed(s) | Contract::Refunded(s) => {
                 s.accepted_contract.offered_contract.counter_party
@@ -145,7 +150,7 @@ impl Contract {
     /// Checks if the contract is the offer party.
     pub fn is_offer_party(&self) -> bool {
         match self {
-            Contract::Offered(o) | Contract::Rejected(o) => o.is_offer_party,
+            Contract::Offered(o) | Contract::Rejected(o) | Contract::Expired(o) => o.is_offer_party,
             Contract::Accepted(a) => a.offered_contract.is_offer_party,
             Contract::Signed(s) | Contract::Confirmed(s) | Contract::Refunded(s) => {
                 s.accepted_contract.offered_contract.is_offer_party
@@ -210,7 +215,9 @@ impl Contract {
                     .total_collateral,
             ),
             Contract::Closed(_) => (Amount::ZERO, Amount::ZERO, Amount::ZERO),
-            Contract::Rejected(_) => (Amount::ZERO, Amount::ZERO, Amount::ZERO),
+            Contract::Rejected(_) | Contract::Expired(_) => {
+                (Amount::ZERO, Amount::ZERO, Amount::ZERO)
+            }
         }
     }
 
@@ -231,7 +238,7 @@ impl Contract {
             Contract::Refunded(r) => r.accepted_contract.offered_contract.cet_locktime,
             Contract::FailedAccept(f) => f.offered_contract.cet_locktime,
             Contract::FailedSign(f) => f.accepted_contract.offered_contract.cet_locktime,
-            Contract::Rejected(_) => 0,
+            Contract::Rejected(_) | Contract::Expired(_) => 0,
         }
     }
 
@@ -252,14 +259,16 @@ impl Contract {
             Contract::Refunded(r) => r.accepted_contract.offered_contract.refund_locktime,
             Contract::FailedAccept(f) => f.offered_contract.refund_locktime,
             Contract::FailedSign(f) => f.accepted_contract.offered_contract.refund_locktime,
-            Contract::Rejected(_) => 0,
+            Contract::Rejected(_) | Contract::Expired(_) => 0,
         }
     }
 
     /// Get the fee rate per virtual byte for a contract.
     pub fn get_fee_rate_per_vb(&self) -> u64 {
         match self {
-            Contract::Offered(o) | Contract::Rejected(o) => o.fee_rate_per_vb,
+            Contract::Offered(o) | Contract::Rejected(o) | Contract::Expired(o) => {
+                o.fee_rate_per_vb
+            }
             Contract::Accepted(a) => a.offered_contract.fee_rate_per_vb,
             Contract::Signed(s) | Contract::Confirmed(s) | Contract::Refunded(s) => {
                 s.accepted_contract.offered_contract.fee_rate_per_vb
@@ -291,7 +300,7 @@ impl Contract {
             Contract::Refunded(_) => SignedAmount::ZERO,
             Contract::FailedAccept(_) => SignedAmount::ZERO,
             Contract::FailedSign(_) => SignedAmount::ZERO,
-            Contract::Rejected(_) => SignedAmount::ZERO,
+            Contract::Rejected(_) | Contract::Expired(_) => SignedAmount::ZERO,
         }
     }
 
@@ -315,7 +324,7 @@ impl Contract {
             Contract::Refunded(r) => Some(r.accepted_contract.dlc_transactions.fund.compute_txid()),
             Contract::FailedAccept(_) => None,
             Contract::FailedSign(_) => None,
-            Contract::Rejected(_) => None,
+            Contract::Rejected(_) | Contract::Expired(_) => None,
         }
     }
 
@@ -354,7 +363,9 @@ impl Contract {
                 f.accepted_contract.offered_contract.contract_info[0].oracle_announcements[0]
                     .clone(),
             ),
-            Contract::Rejected(r) => Some(r.contract_info[0].oracle_announcements[0].clone()),
+            Contract::Rejected(r) | Contract::Expired(r) => {
+                Some(r.contract_info[0].oracle_announcements[0].clone())
+            }
         }
     }
 
@@ -370,7 +381,7 @@ impl Contract {
             Contract::Refunded(_) => None,
             Contract::FailedAccept(_) => None,
             Contract::FailedSign(_) => None,
-            Contract::Rejected(_) => None,
+            Contract::Rejected(_) | Contract::Expired(_) => None,
         }
     }
 }
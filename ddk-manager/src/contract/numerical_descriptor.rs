@@ -131,6 +131,46 @@ impl NumericalDescriptor {
         }
     }
 
+    /// Generate the adaptor info together with the CET index and adaptor
+    /// point of each adaptor signature.
+    pub fn get_adaptor_points(
+        &self,
+        total_collateral: Amount,
+        threshold: usize,
+        precomputed_points: &[Vec<Vec<PublicKey>>],
+        adaptor_index_start: usize,
+    ) -> Result<(AdaptorInfo, Vec<(usize, PublicKey)>), Error> {
+        let range_payouts = self.get_range_payouts(total_collateral)?;
+        match &self.difference_params {
+            Some(params) => {
+                let mut multi_trie = MultiOracleTrieWithDiff::new(
+                    &self.oracle_numeric_infos,
+                    threshold,
+                    params.min_support_exp,
+                    params.max_error_exp,
+                )?;
+                let adaptor_points = multi_trie.generate_adaptor_points(
+                    &range_payouts,
+                    precomputed_points,
+                    adaptor_index_start,
+                )?;
+                Ok((
+                    AdaptorInfo::NumericalWithDifference(multi_trie),
+                    adaptor_points,
+                ))
+            }
+            None => {
+                let mut trie = MultiOracleTrie::new(&self.oracle_numeric_infos, threshold)?;
+                let adaptor_points = trie.generate_adaptor_points(
+                    &range_payouts,
+                    precomputed_points,
+                    adaptor_index_start,
+                )?;
+                Ok((AdaptorInfo::Numerical(trie), adaptor_points))
+            }
+        }
+    }
+
     /// Generate the set of adaptor signatures and the adaptor info.
     #[allow(clippy::too_many_arguments)]
     pub fn get_adaptor_info(
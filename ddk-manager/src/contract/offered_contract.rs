@@ -8,15 +8,25 @@ use crate::utils::get_new_serial_id;
 
 use super::contract_info::ContractInfo;
 use super::contract_input::ContractInput;
+use super::taproot::OfferNonces;
 use super::ContractDescriptor;
 use crate::{ContractId, KeysId};
 use bitcoin::Amount;
 use ddk_dlc::PartyParams;
+use ddk_messages::batch_msgs::BatchFundingGroup;
 use ddk_messages::oracle_msgs::OracleAnnouncement;
+use ddk_messages::taproot_msgs::TaprootOffer;
+use ddk_messages::tlv_stream::TlvStream;
 use ddk_messages::{FundingInput, OfferDlc};
 use secp256k1_zkp::PublicKey;
 
 /// Contains information about a contract that was offered.
+///
+/// The serde representation, meant for display and export, leaves out the
+/// secret MuSig2 nonces of a Taproot funded contract we offered: such an offer
+/// read back from it can no longer sign the accept of the counter party.
+/// Storage goes through [`Serializable`](crate::contract::ser::Serializable),
+/// which keeps them.
 #[derive(Clone, Debug)]
 #[cfg_attr(
     feature = "use-serde",
@@ -47,11 +57,31 @@ pub struct OfferedContract {
     pub cet_locktime: u32,
     /// The time at which the contract becomes refundable.
     pub refund_locktime: u32,
-    /// Feature flags for the contract (bit 0: refund to accepter).
+    /// Feature flags for the contract (bit 0: refund to accepter, bit 2:
+    /// Taproot funding, bit 3: anchor outputs, bit 4: batch funding).
     #[cfg_attr(feature = "use-serde", serde(default))]
     pub contract_flags: u8,
     /// Keys Id for generating the signers
     pub(crate) keys_id: KeysId,
+    /// The MuSig2 nonces of the offer party, set if and only if the contract
+    /// is Taproot funded.
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub taproot: Option<TaprootOffer>,
+    /// The secret MuSig2 nonces behind `taproot`, set if and only if we
+    /// offered a Taproot funded contract. They sign a single accept message.
+    /// Secret, so left out of the serde representation.
+    #[cfg_attr(feature = "use-serde", serde(skip))]
+    pub(crate) taproot_nonces: Option<OfferNonces>,
+    /// The offers funded by the same fund transaction as this one, set if and
+    /// only if the contract is batch funded.
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub batch_funding_group: Option<BatchFundingGroup>,
 }
 
 impl OfferedContract {
@@ -126,9 +156,23 @@ impl OfferedContract {
             contract_flags: contract.contract_flags,
             counter_party: *counter_party,
             keys_id,
+            taproot: None,
+            taproot_nonces: None,
+            batch_funding_group: None,
         }
     }
 
+    /// Returns whether the contract is Taproot funded.
+    pub fn is_taproot(&self) -> bool {
+        self.contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0
+    }
+
+    /// Returns whether the contract shares its fund transaction with other
+    /// contracts.
+    pub fn is_batch_funded(&self) -> bool {
+        self.batch_funding_group.is_some()
+    }
+
     /// Convert an [`OfferDlc`] message to an [`OfferedContract`].
     pub fn try_from_offer_dlc(
         offer_dlc: &OfferDlc,
@@ -139,6 +183,15 @@ impl OfferedContract {
 
         let (inputs, input_amount) = get_tx_input_infos(&offer_dlc.funding_inputs)?;
         let dlc_inputs = get_dlc_inputs_from_funding_inputs(&offer_dlc.funding_inputs);
+        let batch_funding_group = offer_dlc
+            .tlvs
+            .get::<BatchFundingGroup>()
+            .map_err(|_| crate::conversion_utils::Error::InvalidParameters)?;
+        let contract_flags = if batch_funding_group.is_some() {
+            offer_dlc.contract_flags | ddk_dlc::BATCH_FUNDING_FLAG
+        } else {
+            offer_dlc.contract_flags & !ddk_dlc::BATCH_FUNDING_FLAG
+        };
 
         Ok(OfferedContract {
             id: offer_dlc.temporary_contract_id,
@@ -161,19 +214,27 @@ impl OfferedContract {
             fund_output_serial_id: offer_dlc.fund_output_serial_id,
             funding_inputs: offer_dlc.funding_inputs.clone(),
             total_collateral: offer_dlc.contract_info.get_total_collateral(),
-            contract_flags: offer_dlc.contract_flags,
+            contract_flags,
             counter_party,
             keys_id,
+            taproot: offer_dlc.taproot.clone(),
+            taproot_nonces: None,
+            batch_funding_group,
         })
     }
 }
 
 impl From<&OfferedContract> for OfferDlc {
     fn from(offered_contract: &OfferedContract) -> OfferDlc {
+        let mut tlvs = TlvStream::default();
+        if let Some(batch_funding_group) = &offered_contract.batch_funding_group {
+            tlvs.set(batch_funding_group);
+        }
         OfferDlc {
             protocol_version: PROTOCOL_VERSION,
             temporary_contract_id: offered_contract.id,
-            contract_flags: offered_contract.contract_flags,
+            // The batch funding group travels as a record of its own.
+            contract_flags: offered_contract.contract_flags & !ddk_dlc::BATCH_FUNDING_FLAG,
             chain_hash: BITCOIN_CHAINHASH,
             contract_info: offered_contract.into(),
             funding_pubkey: offered_contract.offer_params.fund_pubkey,
@@ -187,6 +248,8 @@ impl From<&OfferedContract> for OfferDlc {
             refund_locktime: offered_contract.refund_locktime,
             fee_rate_per_vb: offered_contract.fee_rate_per_vb,
             fund_output_serial_id: offered_contract.fund_output_serial_id,
+            taproot: offered_contract.taproot.clone(),
+            tlvs,
         }
     }
 }
@@ -262,4 +325,91 @@ mod tests {
             "../../test_inputs/offer_numerical_empty_rounding_interval.json"
         ));
     }
+
+    #[test]
+    fn taproot_offer_roundtrips_through_storage() {
+        use crate::contract::ser::Serializable;
+
+        let mut offer_dlc: OfferDlc =
+            serde_json::from_str(include_str!("../../test_inputs/offer_contract.json")).unwrap();
+        let fund_secret_key = secp256k1_zkp::SecretKey::from_slice(&[3; 32]).unwrap();
+        offer_dlc.contract_flags |= ddk_dlc::TAPROOT_FUNDING_FLAG;
+        let (taproot_offer, mut nonces) =
+            crate::contract::taproot::create_offer(&fund_secret_key, 2);
+        offer_dlc.taproot = Some(taproot_offer);
+        let counter_party =
+            secp256k1_zkp::PublicKey::from_secret_key(secp256k1_zkp::SECP256K1, &fund_secret_key);
+        let mut offered_contract =
+            OfferedContract::try_from_offer_dlc(&offer_dlc, counter_party, [1; 32]).unwrap();
+        offered_contract.taproot_nonces = Some(nonces.clone());
+
+        let bytes = offered_contract.serialize().unwrap();
+        let deserialized = OfferedContract::deserialize(&mut bytes.as_slice()).unwrap();
+        assert!(deserialized.is_taproot());
+        assert_eq!(deserialized.taproot, offer_dlc.taproot);
+        assert_eq!(deserialized.taproot_nonces, Some(nonces.clone()));
+
+        // Used nonces stay used after a restart.
+        nonces.take().unwrap();
+        offered_contract.taproot_nonces = Some(nonces);
+        let bytes = offered_contract.serialize().unwrap();
+        let deserialized = OfferedContract::deserialize(&mut bytes.as_slice()).unwrap();
+        assert!(deserialized.taproot_nonces.as_ref().unwrap().is_used());
+        assert_eq!(
+            OfferDlc::from(&deserialized).taproot,
+            offered_contract.taproot
+        );
+    }
+
+    #[test]
+    fn taproot_offer_loses_its_nonces_through_serde() {
+        use crate::contract::ser::Serializable;
+
+        let mut offer_dlc: OfferDlc =
+            serde_json::from_str(include_str!("../../test_inputs/offer_contract.json")).unwrap();
+        let fund_secret_key = secp256k1_zkp::SecretKey::from_slice(&[3; 32]).unwrap();
+        offer_dlc.contract_flags |= ddk_dlc::TAPROOT_FUNDING_FLAG;
+        let (taproot_offer, nonces) = crate::contract::taproot::create_offer(&fund_secret_key, 2);
+        offer_dlc.taproot = Some(taproot_offer);
+        let counter_party =
+            secp256k1_zkp::PublicKey::from_secret_key(secp256k1_zkp::SECP256K1, &fund_secret_key);
+        let mut offered_contract =
+            OfferedContract::try_from_offer_dlc(&offer_dlc, counter_party, [1; 32]).unwrap();
+        offered_contract.taproot_nonces = Some(nonces.clone());
+
+        let json = serde_json::to_string(&offered_contract).unwrap();
+        let exported: OfferedContract = serde_json::from_str(&json).unwrap();
+        assert_eq!(exported.taproot, offered_contract.taproot);
+        assert_eq!(exported.taproot_nonces, None);
+
+        let bytes = offered_contract.serialize().unwrap();
+        let stored = OfferedContract::deserialize(&mut bytes.as_slice()).unwrap();
+        assert_eq!(stored.taproot_nonces, Some(nonces));
+    }
+
+    #[test]
+    fn batch_offer_roundtrips_through_storage() {
+        use crate::contract::ser::Serializable;
+        use ddk_messages::batch_msgs::BatchFundingGroup;
+
+        let mut offer_dlc: OfferDlc =
+            serde_json::from_str(include_str!("../../test_inputs/offer_contract.json")).unwrap();
+        let mut second = offer_dlc.clone();
+        second.temporary_contract_id = [7; 32];
+        let group = BatchFundingGroup::new(&[offer_dlc.clone(), second]);
+        offer_dlc.tlvs.set(&group);
+        let counter_party = secp256k1_zkp::PublicKey::from_secret_key(
+            secp256k1_zkp::SECP256K1,
+            &secp256k1_zkp::SecretKey::from_slice(&[3; 32]).unwrap(),
+        );
+        let offered_contract =
+            OfferedContract::try_from_offer_dlc(&offer_dlc, counter_party, [1; 32]).unwrap();
+        assert!(offered_contract.is_batch_funded());
+
+        let bytes = offered_contract.serialize().unwrap();
+        let deserialized = OfferedContract::deserialize(&mut bytes.as_slice()).unwrap();
+        assert_eq!(deserialized.batch_funding_group, Some(group));
+        // The flag only marks stored contracts, the record travels on the wire.
+        assert_eq!(OfferDlc::from(&deserialized), offer_dlc);
+    }
 }
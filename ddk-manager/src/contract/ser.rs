@@ -20,9 +20,11 @@ use bitcoin::Amount;
 use ddk_dlc::DlcTransactions;
 use ddk_messages::impl_dlc_writeable;
 use ddk_messages::ser_impls::{
-    read_ecdsa_adaptor_signatures, read_option_cb, read_usize, read_vec, read_vec_cb,
-    write_ecdsa_adaptor_signatures, write_option_cb, write_usize, write_vec, write_vec_cb,
+    read_ecdsa_adaptor_signatures, read_option, read_option_cb, read_usize, read_vec, read_vec_cb,
+    write_ecdsa_adaptor_signatures, write_option, write_option_cb, write_usize, write_vec,
+    write_vec_cb,
 };
+use ddk_messages::{AcceptDlc, SignDlc};
 use ddk_trie::digit_trie::{DigitNodeData, DigitTrieDump};
 use ddk_trie::multi_oracle_trie::{MultiOracleTrie, MultiOracleTrieDump};
 use ddk_trie::multi_oracle_trie_with_diff::{MultiOracleTrieWithDiff, MultiOracleTrieWithDiffDump};
@@ -104,6 +106,17 @@ impl Writeable for OfferedContract {
         self.contract_flags.write(w)?;
         self.counter_party.write(w)?;
         self.keys_id.write(w)?;
+        // Only Taproot funded contracts carry nonces, so that contracts stored
+        // before Taproot funding existed read back unchanged.
+        if let Some(taproot) = &self.taproot {
+            taproot.write(w)?;
+            write_option(&self.taproot_nonces, w)?;
+        }
+        // Likewise, only batch funded contracts carry their group, flagged by
+        // `BATCH_FUNDING_FLAG`.
+        if let Some(batch_funding_group) = &self.batch_funding_group {
+            batch_funding_group.write(w)?;
+        }
         Ok(())
     }
 }
@@ -123,8 +136,8 @@ impl Readable for OfferedContract {
 
         // Backward compatibility: contract_flags (u8) was inserted between
         // refund_locktime and counter_party. Peek one byte: 0x02/0x03 means
-        // old format (compressed pubkey prefix), 0x00/0x01 means new format
-        // (contract_flags value).
+        // old format (compressed pubkey prefix), any other value means new
+        // format (contract_flags value, which never has bit 1 set).
         let mut peek = [0u8; 1];
         r.read_exact(&mut peek)?;
         let (contract_flags, counter_party) = if peek[0] == 0x02 || peek[0] == 0x03 {
@@ -142,6 +155,16 @@ impl Readable for OfferedContract {
         };
 
         let keys_id: KeysId = Readable::read(r)?;
+        let (taproot, taproot_nonces) = if contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0 {
+            (Some(Readable::read(r)?), read_option(r)?)
+        } else {
+            (None, None)
+        };
+        let batch_funding_group = if contract_flags & ddk_dlc::BATCH_FUNDING_FLAG != 0 {
+            Some(Readable::read(r)?)
+        } else {
+            None
+        };
 
         Ok(Self {
             id,
@@ -157,6 +180,9 @@ impl Readable for OfferedContract {
             contract_flags,
             counter_party,
             keys_id,
+            taproot,
+            taproot_nonces,
+            batch_funding_group,
         })
     }
 }
@@ -170,22 +196,87 @@ impl_dlc_writeable_external!(
     (funding_witness_script, writeable),
     (pending_close_txs, vec)}
 );
-impl_dlc_writeable!(AcceptedContract, {
-    (offered_contract, writeable),
-    (accept_params, { cb_writeable, ddk_messages::ser_impls::party_params::write, ddk_messages::ser_impls::party_params::read }),
-    (funding_inputs, vec),
-    (adaptor_infos, vec),
-    (adaptor_signatures, { cb_writeable, write_ecdsa_adaptor_signatures, read_ecdsa_adaptor_signatures }),
-    (accept_refund_signature, writeable),
-    (dlc_transactions, {cb_writeable, dlc_transactions::write, dlc_transactions::read })
-});
-impl_dlc_writeable!(SignedContract, {
-    (accepted_contract, writeable),
-    (adaptor_signatures, { cb_writeable, write_ecdsa_adaptor_signatures, read_ecdsa_adaptor_signatures }),
-    (offer_refund_signature, writeable),
-    (funding_signatures, writeable),
-    (channel_id, option)
-});
+impl Writeable for AcceptedContract {
+    fn write<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        self.offered_contract.write(w)?;
+        ddk_messages::ser_impls::party_params::write(&self.accept_params, w)?;
+        write_vec(&self.funding_inputs, w)?;
+        write_vec(&self.adaptor_infos, w)?;
+        write_ecdsa_adaptor_signatures(&self.adaptor_signatures, w)?;
+        self.accept_refund_signature.write(w)?;
+        dlc_transactions::write(&self.dlc_transactions, w)?;
+        if let Some(taproot) = &self.taproot {
+            taproot.write(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl Readable for AcceptedContract {
+    fn read<R: Read>(r: &mut R) -> Result<Self, DecodeError> {
+        let offered_contract: OfferedContract = Readable::read(r)?;
+        let accept_params = ddk_messages::ser_impls::party_params::read(r)?;
+        let funding_inputs = read_vec(r)?;
+        let adaptor_infos = read_vec(r)?;
+        let adaptor_signatures = read_ecdsa_adaptor_signatures(r)?;
+        let accept_refund_signature = Readable::read(r)?;
+        let dlc_transactions = dlc_transactions::read(r)?;
+        let taproot = if offered_contract.is_taproot() {
+            Some(Readable::read(r)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            offered_contract,
+            accept_params,
+            funding_inputs,
+            adaptor_infos,
+            adaptor_signatures,
+            accept_refund_signature,
+            dlc_transactions,
+            taproot,
+        })
+    }
+}
+
+impl Writeable for SignedContract {
+    fn write<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        self.accepted_contract.write(w)?;
+        write_ecdsa_adaptor_signatures(&self.adaptor_signatures, w)?;
+        self.offer_refund_signature.write(w)?;
+        self.funding_signatures.write(w)?;
+        ddk_messages::ser_impls::write_option(&self.channel_id, w)?;
+        if let Some(taproot) = &self.taproot {
+            taproot.write(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl Readable for SignedContract {
+    fn read<R: Read>(r: &mut R) -> Result<Self, DecodeError> {
+        let accepted_contract: AcceptedContract = Readable::read(r)?;
+        let adaptor_signatures = read_ecdsa_adaptor_signatures(r)?;
+        let offer_refund_signature = Readable::read(r)?;
+        let funding_signatures = Readable::read(r)?;
+        let channel_id = ddk_messages::ser_impls::read_option(r)?;
+        let taproot = if accepted_contract.offered_contract.is_taproot() {
+            Some(Readable::read(r)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            accepted_contract,
+            adaptor_signatures,
+            offer_refund_signature,
+            funding_signatures,
+            channel_id,
+            taproot,
+        })
+    }
+}
 impl_dlc_writeable!(PreClosedContract, {
     (signed_contract, writeable),
     (attestations, {option_cb, write_vec, read_vec}),
@@ -201,8 +292,11 @@ impl_dlc_writeable!(ClosedContract, {
     (pnl, SignedAmount),
     (signed_contract, writeable)
 });
-impl_dlc_writeable!(FailedAcceptContract, {(offered_contract, writeable), (accept_message, writeable), (error_message, string)});
-impl_dlc_writeable!(FailedSignContract, {(accepted_contract, writeable), (sign_message, writeable), (error_message, string)});
+// The messages are followed by other fields, so they are stored without
+// their trailing TLV records: the Taproot nonces and partial signatures of a
+// failed contract are not kept.
+impl_dlc_writeable!(FailedAcceptContract, {(offered_contract, writeable), (accept_message, {cb_writeable, AcceptDlc::write_fields, AcceptDlc::read_fields}), (error_message, string)});
+impl_dlc_writeable!(FailedSignContract, {(accepted_contract, writeable), (sign_message, {cb_writeable, SignDlc::write_fields, SignDlc::read_fields}), (error_message, string)});
 
 impl_dlc_writeable_external!(DigitTrieDump<Vec<RangeInfo> >, digit_trie_dump_vec_range, { (node_data, {vec_cb, write_digit_node_data_vec_range, read_digit_node_data_vec_range}), (root, {option_cb, write_usize, read_usize}), (base, usize)});
 impl_dlc_writeable_external!(DigitTrieDump<RangeInfo>, digit_trie_dump_range, { (node_data, {vec_cb, write_digit_node_data_range, read_digit_node_data_range}), (root, {option_cb, write_usize, read_usize}), (base, usize)});
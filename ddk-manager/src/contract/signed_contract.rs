@@ -6,6 +6,8 @@ use crate::ChannelId;
 
 use super::accepted_contract::AcceptedContract;
 use ddk_dlc::dlc_input::DlcInputInfo;
+use ddk_messages::taproot_msgs::TaprootSign;
+use ddk_messages::tlv_stream::TlvStream;
 use ddk_messages::CetAdaptorSignature;
 use ddk_messages::CetAdaptorSignatures;
 use ddk_messages::FundingSignatures;
@@ -26,6 +28,9 @@ pub struct SignedContract {
     pub funding_signatures: FundingSignatures,
     /// The [`ChannelId`] to which the contract was associated if any.
     pub channel_id: Option<ChannelId>,
+    /// The MuSig2 partial signatures of the offering party, set if and only
+    /// if the contract is Taproot funded.
+    pub taproot: Option<TaprootSign>,
 }
 
 impl SignedContract {
@@ -44,6 +49,8 @@ impl SignedContract {
             },
             refund_signature: self.offer_refund_signature,
             funding_signatures: self.funding_signatures.clone(),
+            taproot: self.taproot.clone(),
+            tlvs: TlvStream::default(),
         }
     }
 
@@ -0,0 +1,543 @@
+//! # Taproot funded contracts
+//! Creation and verification of the MuSig2 nonces and partial signatures
+//! exchanged in the offer, accept and sign messages of a contract funded
+//! through a Taproot output, and completion of its CETs and refund
+//! transaction.
+//!
+//! The offer party commits to its nonces in the offer message. They are drawn
+//! at random when the offer is created and kept as [`OfferNonces`] until the
+//! accept message arrives. Signing with the same nonces twice reveals the
+//! funding secret key, so [`OfferNonces`] hand their secret nonces out once
+//! and refuse to sign a second accept message. The accept party uses fresh
+//! random nonces, as it signs as soon as it has them.
+//!
+//! Both parties also sign the refund transaction for the refund leaf of the
+//! fund output with plain Schnorr signatures, carried next to the partial
+//! signatures. The leaf lets either party spend the refund without the
+//! MuSig2 key path: see [`sign_refund_leaf`].
+
+use bitcoin::{Amount, Transaction};
+use ddk_dlc::musig2::{BinaryEncoding, PartialSignature, PubNonce, SecNonce};
+use ddk_dlc::taproot::TaprootFunding;
+use ddk_dlc::DlcTransactions;
+use ddk_messages::taproot_msgs::{
+    MusigPartialSignature, MusigPubNonce, TaprootAccept, TaprootOffer, TaprootSign,
+};
+use secp256k1_zkp::schnorr::Signature as SchnorrSignature;
+use secp256k1_zkp::{ecdsa::Signature, PublicKey, Secp256k1, SecretKey, Signing, Verification};
+
+use super::contract_info::ContractInfo;
+use super::AdaptorInfo;
+use crate::error::Error;
+use ddk_messages::ser_impls::{read_option_cb, read_vec_cb, write_option_cb, write_vec_cb};
+use lightning::io::Read;
+use lightning::ln::msgs::DecodeError;
+use lightning::util::ser::{Readable, Writeable, Writer};
+
+/// The value of the ECDSA refund signature fields of the messages and
+/// contracts of a Taproot funded contract, which are not used.
+pub fn unused_refund_signature() -> Signature {
+    Signature::from_compact(&[0; 64]).expect("a zero signature to be parseable")
+}
+
+/// The CET index and adaptor point of each CET partial signature of a
+/// contract, in the order the signatures are exchanged.
+pub type AdaptorPoints = Vec<(usize, PublicKey)>;
+
+/// Returns the adaptor information of each contract info together with the
+/// CET index and adaptor point of each CET partial signature, in the order
+/// the signatures are exchanged. CET indexes refer to the CETs of all the
+/// contract infos, concatenated in order.
+pub fn get_adaptor_points<C: Verification>(
+    secp: &Secp256k1<C>,
+    contract_infos: &[ContractInfo],
+    total_collateral: Amount,
+) -> Result<(Vec<AdaptorInfo>, AdaptorPoints), Error> {
+    let mut adaptor_infos = Vec::with_capacity(contract_infos.len());
+    let mut adaptor_points = Vec::new();
+    let mut cet_offset = 0;
+
+    for contract_info in contract_infos {
+        let (adaptor_info, points) =
+            contract_info.get_adaptor_points(secp, total_collateral, adaptor_points.len())?;
+        adaptor_points.extend(
+            points
+                .into_iter()
+                .map(|(cet_index, point)| (cet_offset + cet_index, point)),
+        );
+        cet_offset += contract_info.get_payouts(total_collateral)?.len();
+        adaptor_infos.push(adaptor_info);
+    }
+
+    Ok((adaptor_infos, adaptor_points))
+}
+
+/// Returns the index of the first CET of the contract info at the given
+/// position among the CETs of all the contract infos.
+pub fn get_cet_offset(
+    contract_infos: &[ContractInfo],
+    contract_info_index: usize,
+    total_collateral: Amount,
+) -> Result<usize, Error> {
+    let mut offset = 0;
+    for contract_info in contract_infos.iter().take(contract_info_index) {
+        offset += contract_info.get_payouts(total_collateral)?.len();
+    }
+    Ok(offset)
+}
+
+/// Returns the fund output information of a contract.
+pub fn get_funding<C: Verification>(
+    secp: &Secp256k1<C>,
+    offer_fund_pubkey: &PublicKey,
+    accept_fund_pubkey: &PublicKey,
+    refund_locktime: u32,
+) -> Result<TaprootFunding, Error> {
+    Ok(TaprootFunding::new(
+        secp,
+        offer_fund_pubkey,
+        accept_fund_pubkey,
+        refund_locktime,
+    )?)
+}
+
+/// The secret MuSig2 nonces the offer party committed to in its offer.
+///
+/// They can sign a single accept message: the first call to
+/// [`OfferNonces::take`] hands them out and every later call fails, so that
+/// answering a retried or a second accept message cannot reuse them.
+#[derive(Clone, PartialEq)]
+pub struct OfferNonces {
+    nonces: Option<Vec<SecNonce>>,
+}
+
+impl OfferNonces {
+    /// Whether the nonces were already used to sign an accept message.
+    pub fn is_used(&self) -> bool {
+        self.nonces.is_none()
+    }
+
+    /// Hands out the CET nonces and the refund nonce, marking them as used.
+    pub fn take(&mut self) -> Result<(Vec<SecNonce>, SecNonce), Error> {
+        let nonces = self.nonces.take().ok_or_else(|| {
+            Error::InvalidState(
+                "The nonces of the offer were already used to sign an accept message".to_string(),
+            )
+        })?;
+        Ok(split_refund_nonce(nonces))
+    }
+}
+
+impl std::fmt::Debug for OfferNonces {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OfferNonces")
+            .field("used", &self.is_used())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Writeable for OfferNonces {
+    fn write<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        write_option_cb(&self.nonces, w, &|nonces, w| {
+            write_vec_cb(nonces, w, &|nonce, w| w.write_all(&nonce.to_bytes()))
+        })
+    }
+}
+
+impl Readable for OfferNonces {
+    fn read<R: Read>(r: &mut R) -> Result<Self, DecodeError> {
+        let nonces = read_option_cb(r, &|r| {
+            read_vec_cb(r, &|r| {
+                let mut bytes = [0u8; 97];
+                r.read_exact(&mut bytes)?;
+                SecNonce::from_bytes(&bytes).map_err(|_| DecodeError::InvalidValue)
+            })
+        })?;
+        Ok(Self { nonces })
+    }
+}
+
+/// Derives `count` CET nonces followed by the refund nonce.
+fn derive_nonces(fund_secret_key: &SecretKey, session_id: &[u8], count: usize) -> Vec<SecNonce> {
+    ddk_dlc::taproot::derive_secret_nonces(fund_secret_key, session_id, count + 1)
+}
+
+fn split_refund_nonce(mut nonces: Vec<SecNonce>) -> (Vec<SecNonce>, SecNonce) {
+    let refund_nonce = nonces.pop().expect("nonces to include the refund nonce");
+    (nonces, refund_nonce)
+}
+
+fn to_musig_pub_nonce(nonce: &SecNonce) -> MusigPubNonce {
+    MusigPubNonce(nonce.public_nonce().to_bytes())
+}
+
+fn to_pub_nonce(nonce: &MusigPubNonce) -> Result<PubNonce, Error> {
+    PubNonce::from_bytes(&nonce.0)
+        .map_err(|_| Error::InvalidParameters("Invalid MuSig2 public nonce".to_string()))
+}
+
+fn to_pub_nonces(nonces: &[MusigPubNonce]) -> Result<Vec<PubNonce>, Error> {
+    nonces.iter().map(to_pub_nonce).collect()
+}
+
+fn to_musig_partial_signature(signature: PartialSignature) -> MusigPartialSignature {
+    MusigPartialSignature(signature.serialize())
+}
+
+fn to_partial_signature(signature: &MusigPartialSignature) -> Result<PartialSignature, Error> {
+    PartialSignature::from_slice(&signature.0)
+        .map_err(|_| Error::InvalidParameters("Invalid MuSig2 partial signature".to_string()))
+}
+
+fn to_partial_signatures(
+    signatures: &[MusigPartialSignature],
+) -> Result<Vec<PartialSignature>, Error> {
+    signatures.iter().map(to_partial_signature).collect()
+}
+
+/// Creates the offer party's nonces for a contract with the given number of
+/// CET adaptor points: the public nonces to send in the offer, and the secret
+/// ones to keep until the accept message arrives.
+pub fn create_offer(
+    fund_secret_key: &SecretKey,
+    nb_adaptor_points: usize,
+) -> (TaprootOffer, OfferNonces) {
+    let session_id = crate::utils::get_new_temporary_id();
+    let nonces = derive_nonces(fund_secret_key, &session_id, nb_adaptor_points);
+    let (cet_nonces, refund_nonce) = nonces.split_at(nb_adaptor_points);
+
+    let offer = TaprootOffer {
+        cet_nonces: cet_nonces.iter().map(to_musig_pub_nonce).collect(),
+        refund_nonce: to_musig_pub_nonce(&refund_nonce[0]),
+    };
+    (
+        offer,
+        OfferNonces {
+            nonces: Some(nonces),
+        },
+    )
+}
+
+/// Creates the accept party's nonces and partial signatures for the CETs and
+/// refund transaction of `dlc_transactions`, which must hold the CETs of all
+/// the contract infos, and its refund leaf signature.
+pub fn create_accept<C: Signing>(
+    secp: &Secp256k1<C>,
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    adaptor_points: &[(usize, PublicKey)],
+    fund_secret_key: &SecretKey,
+    offer: &TaprootOffer,
+) -> Result<TaprootAccept, Error> {
+    let session_id = crate::utils::get_new_temporary_id();
+    let (cet_nonces, refund_nonce) = split_refund_nonce(derive_nonces(
+        fund_secret_key,
+        &session_id,
+        adaptor_points.len(),
+    ));
+    let public_cet_nonces = cet_nonces.iter().map(to_musig_pub_nonce).collect();
+    let public_refund_nonce = to_musig_pub_nonce(&refund_nonce);
+    let fund_output = dlc_transactions.get_fund_output();
+
+    let cet_partial_signatures = ddk_dlc::taproot::create_cet_partial_adaptor_signatures(
+        funding,
+        fund_output,
+        &dlc_transactions.cets,
+        adaptor_points,
+        fund_secret_key,
+        cet_nonces,
+        &to_pub_nonces(&offer.cet_nonces)?,
+    )?;
+    let refund_partial_signature = ddk_dlc::taproot::create_refund_partial_signature(
+        funding,
+        fund_output,
+        &dlc_transactions.refund,
+        fund_secret_key,
+        refund_nonce,
+        &to_pub_nonce(&offer.refund_nonce)?,
+    )?;
+    let refund_leaf_signature = ddk_dlc::taproot::create_refund_leaf_signature(
+        secp,
+        funding,
+        fund_output,
+        &dlc_transactions.refund,
+        fund_secret_key,
+    )?;
+
+    Ok(TaprootAccept {
+        cet_nonces: public_cet_nonces,
+        refund_nonce: public_refund_nonce,
+        cet_partial_signatures: cet_partial_signatures
+            .into_iter()
+            .map(to_musig_partial_signature)
+            .collect(),
+        refund_partial_signature: to_musig_partial_signature(refund_partial_signature),
+        refund_leaf_signature,
+    })
+}
+
+/// Verifies the accept party's partial signatures and refund leaf signature.
+pub fn verify_accept<C: Verification>(
+    secp: &Secp256k1<C>,
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    adaptor_points: &[(usize, PublicKey)],
+    accept_fund_pubkey: &PublicKey,
+    offer: &TaprootOffer,
+    accept: &TaprootAccept,
+) -> Result<(), Error> {
+    verify_partial_signatures(
+        funding,
+        dlc_transactions,
+        adaptor_points,
+        accept_fund_pubkey,
+        (&accept.cet_nonces, &accept.refund_nonce),
+        (&offer.cet_nonces, &offer.refund_nonce),
+        &accept.cet_partial_signatures,
+        &accept.refund_partial_signature,
+    )?;
+    ddk_dlc::taproot::verify_refund_leaf_signature(
+        secp,
+        funding,
+        dlc_transactions.get_fund_output(),
+        &dlc_transactions.refund,
+        accept_fund_pubkey,
+        &accept.refund_leaf_signature,
+    )?;
+    Ok(())
+}
+
+/// Creates the offer party's partial signatures with the nonces it committed
+/// to in its offer, using them up, and its refund leaf signature.
+#[allow(clippy::too_many_arguments)]
+pub fn create_sign<C: Signing>(
+    secp: &Secp256k1<C>,
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    adaptor_points: &[(usize, PublicKey)],
+    fund_secret_key: &SecretKey,
+    nonces: &mut OfferNonces,
+    offer: &TaprootOffer,
+    accept: &TaprootAccept,
+) -> Result<TaprootSign, Error> {
+    let (cet_nonces, refund_nonce) = nonces.take()?;
+    let committed_offer = TaprootOffer {
+        cet_nonces: cet_nonces.iter().map(to_musig_pub_nonce).collect(),
+        refund_nonce: to_musig_pub_nonce(&refund_nonce),
+    };
+    if &committed_offer != offer {
+        return Err(Error::InvalidState(
+            "Stored nonces do not match the offered ones".to_string(),
+        ));
+    }
+    let fund_output = dlc_transactions.get_fund_output();
+
+    let cet_partial_signatures = ddk_dlc::taproot::create_cet_partial_adaptor_signatures(
+        funding,
+        fund_output,
+        &dlc_transactions.cets,
+        adaptor_points,
+        fund_secret_key,
+        cet_nonces,
+        &to_pub_nonces(&accept.cet_nonces)?,
+    )?;
+    let refund_partial_signature = ddk_dlc::taproot::create_refund_partial_signature(
+        funding,
+        fund_output,
+        &dlc_transactions.refund,
+        fund_secret_key,
+        refund_nonce,
+        &to_pub_nonce(&accept.refund_nonce)?,
+    )?;
+    let refund_leaf_signature = ddk_dlc::taproot::create_refund_leaf_signature(
+        secp,
+        funding,
+        fund_output,
+        &dlc_transactions.refund,
+        fund_secret_key,
+    )?;
+
+    Ok(TaprootSign {
+        cet_partial_signatures: cet_partial_signatures
+            .into_iter()
+            .map(to_musig_partial_signature)
+            .collect(),
+        refund_partial_signature: to_musig_partial_signature(refund_partial_signature),
+        refund_leaf_signature,
+    })
+}
+
+/// Verifies the offer party's partial signatures and refund leaf signature.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_sign<C: Verification>(
+    secp: &Secp256k1<C>,
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    adaptor_points: &[(usize, PublicKey)],
+    offer_fund_pubkey: &PublicKey,
+    offer: &TaprootOffer,
+    accept: &TaprootAccept,
+    sign: &TaprootSign,
+) -> Result<(), Error> {
+    verify_partial_signatures(
+        funding,
+        dlc_transactions,
+        adaptor_points,
+        offer_fund_pubkey,
+        (&offer.cet_nonces, &offer.refund_nonce),
+        (&accept.cet_nonces, &accept.refund_nonce),
+        &sign.cet_partial_signatures,
+        &sign.refund_partial_signature,
+    )?;
+    ddk_dlc::taproot::verify_refund_leaf_signature(
+        secp,
+        funding,
+        dlc_transactions.get_fund_output(),
+        &dlc_transactions.refund,
+        offer_fund_pubkey,
+        &sign.refund_leaf_signature,
+    )?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn verify_partial_signatures(
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    adaptor_points: &[(usize, PublicKey)],
+    signer_pubkey: &PublicKey,
+    signer_nonces: (&[MusigPubNonce], &MusigPubNonce),
+    counterparty_nonces: (&[MusigPubNonce], &MusigPubNonce),
+    cet_partial_signatures: &[MusigPartialSignature],
+    refund_partial_signature: &MusigPartialSignature,
+) -> Result<(), Error> {
+    let fund_output = dlc_transactions.get_fund_output();
+
+    ddk_dlc::taproot::verify_cet_partial_adaptor_signatures(
+        funding,
+        fund_output,
+        &dlc_transactions.cets,
+        adaptor_points,
+        signer_pubkey,
+        &to_pub_nonces(signer_nonces.0)?,
+        &to_pub_nonces(counterparty_nonces.0)?,
+        &to_partial_signatures(cet_partial_signatures)?,
+    )?;
+    ddk_dlc::taproot::verify_refund_partial_signature(
+        funding,
+        fund_output,
+        &dlc_transactions.refund,
+        signer_pubkey,
+        &to_pub_nonce(signer_nonces.1)?,
+        &to_pub_nonce(counterparty_nonces.1)?,
+        to_partial_signature(refund_partial_signature)?,
+    )?;
+
+    Ok(())
+}
+
+/// Completes the CET signed by the partial signatures at `adaptor_index`
+/// with the oracle signatures.
+#[allow(clippy::too_many_arguments)]
+pub fn sign_cet(
+    cet: &mut Transaction,
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    adaptor_index: usize,
+    oracle_signatures: &[Vec<SchnorrSignature>],
+    offer: &TaprootOffer,
+    accept: &TaprootAccept,
+    sign: &TaprootSign,
+) -> Result<(), Error> {
+    let missing = || Error::InvalidState("Missing CET partial signature".to_string());
+    let nonces = [
+        to_pub_nonce(offer.cet_nonces.get(adaptor_index).ok_or_else(missing)?)?,
+        to_pub_nonce(accept.cet_nonces.get(adaptor_index).ok_or_else(missing)?)?,
+    ];
+    let partial_signatures = [
+        to_partial_signature(
+            sign.cet_partial_signatures
+                .get(adaptor_index)
+                .ok_or_else(missing)?,
+        )?,
+        to_partial_signature(
+            accept
+                .cet_partial_signatures
+                .get(adaptor_index)
+                .ok_or_else(missing)?,
+        )?,
+    ];
+
+    ddk_dlc::taproot::sign_cet(
+        cet,
+        funding,
+        dlc_transactions.get_fund_output(),
+        oracle_signatures,
+        &nonces,
+        &partial_signatures,
+    )?;
+
+    Ok(())
+}
+
+/// Returns the refund transaction of the contract, signed through the key
+/// path.
+pub fn sign_refund(
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    offer: &TaprootOffer,
+    accept: &TaprootAccept,
+    sign: &TaprootSign,
+) -> Result<Transaction, Error> {
+    let mut refund = dlc_transactions.refund.clone();
+    ddk_dlc::taproot::sign_refund(
+        &mut refund,
+        funding,
+        dlc_transactions.get_fund_output(),
+        &[
+            to_pub_nonce(&offer.refund_nonce)?,
+            to_pub_nonce(&accept.refund_nonce)?,
+        ],
+        &[
+            to_partial_signature(&sign.refund_partial_signature)?,
+            to_partial_signature(&accept.refund_partial_signature)?,
+        ],
+    )?;
+    Ok(refund)
+}
+
+/// Returns the refund transaction of the contract, signed through the refund
+/// leaf with both parties' refund leaf signatures, which are verified first.
+pub fn sign_refund_leaf(
+    funding: &TaprootFunding,
+    dlc_transactions: &DlcTransactions,
+    offer_fund_pubkey: &PublicKey,
+    accept_fund_pubkey: &PublicKey,
+    accept: &TaprootAccept,
+    sign: &TaprootSign,
+) -> Result<Transaction, Error> {
+    let secp = Secp256k1::verification_only();
+    for (pubkey, signature) in [
+        (offer_fund_pubkey, &sign.refund_leaf_signature),
+        (accept_fund_pubkey, &accept.refund_leaf_signature),
+    ] {
+        ddk_dlc::taproot::verify_refund_leaf_signature(
+            &secp,
+            funding,
+            dlc_transactions.get_fund_output(),
+            &dlc_transactions.refund,
+            pubkey,
+            signature,
+        )?;
+    }
+    let mut refund = dlc_transactions.refund.clone();
+    ddk_dlc::taproot::sign_refund_leaf(
+        &mut refund,
+        funding,
+        &[
+            (*offer_fund_pubkey, sign.refund_leaf_signature),
+            (*accept_fund_pubkey, accept.refund_leaf_signature),
+        ],
+    )?;
+    Ok(refund)
+}
@@ -6,17 +6,22 @@ use bitcoin::hex::DisplayHex;
 use bitcoin::psbt::Psbt;
 use bitcoin::Amount;
 use bitcoin::{consensus::Decodable, Script, Transaction, Witness};
+use ddk_dlc::batch::BatchContractParams;
 use ddk_dlc::dlc_input::DlcInputInfo;
 use ddk_dlc::{DlcTransactions, PartyParams};
+use ddk_messages::batch_msgs::BatchFundingGroup;
+use ddk_messages::taproot_msgs::{TaprootAccept, TaprootOffer, TaprootSign};
 use ddk_messages::{
     oracle_msgs::{OracleAnnouncement, OracleAttestation},
-    AcceptDlc, FundingSignature, FundingSignatures, OfferDlc, SignDlc, WitnessElement,
+    AcceptDlc, BumpFundingAccept, BumpFundingSign, FundingSignature, FundingSignatures, OfferDlc,
+    SignDlc, WitnessElement,
 };
 use ddk_messages::{CloseDlc, FundingInput};
 use lightning::util::logger::Logger;
 use lightning::{log_debug, log_info};
 use secp256k1_zkp::{
     ecdsa::Signature, All, EcdsaAdaptorSignature, PublicKey, Secp256k1, SecretKey, Signing,
+    Verification,
 };
 
 use crate::dlc_input::{get_dlc_inputs_from_funding_inputs, get_signature_for_dlc_input};
@@ -25,7 +30,7 @@ use crate::{
     contract::{
         accepted_contract::AcceptedContract, contract_info::ContractInfo,
         contract_input::ContractInput, offered_contract::OfferedContract,
-        signed_contract::SignedContract, AdaptorInfo,
+        signed_contract::SignedContract, taproot, AdaptorInfo, Contract,
     },
     conversion_utils::get_tx_input_infos,
     error::Error,
@@ -41,7 +46,7 @@ pub async fn offer_contract<
     T: Deref,
     X: ContractSigner,
     SP: Deref,
-    C: Signing,
+    C: Signing + Verification,
     L: Deref,
 >(
     secp: &Secp256k1<C>,
@@ -75,6 +80,7 @@ where
         total_collateral,
         dlc_inputs,
         contract_input.fee_rate,
+        contract_input.contract_flags,
         wallet,
         &signer,
         blockchain,
@@ -94,7 +100,7 @@ where
         party_params.collateral.to_sat(),
     );
 
-    let offered_contract = OfferedContract::new(
+    let mut offered_contract = OfferedContract::new(
         id,
         contract_input,
         oracle_announcements,
@@ -106,6 +112,29 @@ where
         keys_id,
     );
 
+    if offered_contract.is_taproot() {
+        if !party_params.dlc_inputs.is_empty() {
+            return Err(Error::InvalidParameters(
+                "Taproot funded contracts cannot have DLC inputs".to_string(),
+            ));
+        }
+        let (_, adaptor_points) = taproot::get_adaptor_points(
+            secp,
+            &offered_contract.contract_info,
+            offered_contract.total_collateral,
+        )?;
+        let (taproot_offer, nonces) =
+            taproot::create_offer(&signer.get_secret_key()?, adaptor_points.len());
+        offered_contract.taproot = Some(taproot_offer);
+        offered_contract.taproot_nonces = Some(nonces);
+        log_debug!(
+            logger,
+            "Created Taproot nonces for offer. temp_id={} num_cet_nonces={}",
+            id.to_lower_hex_string(),
+            adaptor_points.len(),
+        );
+    }
+
     let offer_msg: OfferDlc = (&offered_contract).into();
 
     Ok((offered_contract, offer_msg))
@@ -137,6 +166,7 @@ where
         // The accept party does not have any DLC inputs, so we pass an empty vector.
         vec![],
         offered_contract.fee_rate_per_vb,
+        offered_contract.contract_flags,
         wallet,
         &signer,
         blockchain,
@@ -156,6 +186,17 @@ where
         accept_params.collateral.to_sat(),
     );
 
+    if offered_contract.is_taproot() {
+        return accept_taproot_contract(
+            secp,
+            offered_contract,
+            &accept_params,
+            &funding_inputs,
+            &signer.get_secret_key()?,
+            logger,
+        );
+    }
+
     // Check BOTH parties for DLC inputs - either party having DLC inputs means we need splicing
     let has_dlc_inputs = !accept_params.dlc_inputs.is_empty()
         || !offered_contract.offer_params.dlc_inputs.is_empty();
@@ -279,6 +320,7 @@ pub(crate) fn accept_contract_internal(
             accept_params.payout_serial_id,
             &payouts,
             0,
+            offered_contract.contract_flags,
         );
 
         let (adaptor_info, adaptor_sig) = contract_info.get_adaptor_info(
@@ -322,11 +364,149 @@ pub(crate) fn accept_contract_internal(
         funding_inputs: funding_inputs.to_vec(),
         dlc_transactions,
         accept_refund_signature: refund_signature,
+        taproot: None,
     };
 
     Ok((accepted_contract, adaptor_sigs))
 }
 
+/// Creates the DLC transactions of a Taproot funded contract, with the CETs
+/// of all its contract infos, along with the adaptor information and the
+/// CET index and adaptor point of each CET partial signature.
+fn create_taproot_dlc_transactions(
+    secp: &Secp256k1<All>,
+    offered_contract: &OfferedContract,
+    accept_params: &PartyParams,
+) -> Result<(DlcTransactions, Vec<AdaptorInfo>, taproot::AdaptorPoints), Error> {
+    let total_collateral = offered_contract.total_collateral;
+
+    let mut dlc_transactions = ddk_dlc::taproot::create_taproot_dlc_transactions(
+        &offered_contract.offer_params,
+        accept_params,
+        &offered_contract.contract_info[0].get_payouts(total_collateral)?,
+        offered_contract.refund_locktime,
+        offered_contract.fee_rate_per_vb,
+        0,
+        offered_contract.cet_locktime,
+        offered_contract.fund_output_serial_id,
+        offered_contract.contract_flags,
+    )?;
+
+    let cet_input = dlc_transactions.cets[0].input[0].clone();
+    for contract_info in offered_contract.contract_info.iter().skip(1) {
+        let payouts = contract_info.get_payouts(total_collateral)?;
+        dlc_transactions.cets.extend(ddk_dlc::create_cets(
+            &cet_input,
+            &offered_contract.offer_params.payout_script_pubkey,
+            offered_contract.offer_params.payout_serial_id,
+            &accept_params.payout_script_pubkey,
+            accept_params.payout_serial_id,
+            &payouts,
+            0,
+            offered_contract.contract_flags,
+        ));
+    }
+
+    let (adaptor_infos, adaptor_points) =
+        taproot::get_adaptor_points(secp, &offered_contract.contract_info, total_collateral)?;
+
+    Ok((dlc_transactions, adaptor_infos, adaptor_points))
+}
+
+fn get_taproot_offer(offered_contract: &OfferedContract) -> Result<&TaprootOffer, Error> {
+    offered_contract
+        .taproot
+        .as_ref()
+        .ok_or_else(|| Error::InvalidState("Missing Taproot nonces of the offer".to_string()))
+}
+
+fn get_taproot_offer_and_accept(
+    accepted_contract: &AcceptedContract,
+) -> Result<(&TaprootOffer, &TaprootAccept), Error> {
+    let accept_taproot = accepted_contract.taproot.as_ref().ok_or_else(|| {
+        Error::InvalidState("Missing Taproot signatures of the accept party".to_string())
+    })?;
+    Ok((
+        get_taproot_offer(&accepted_contract.offered_contract)?,
+        accept_taproot,
+    ))
+}
+
+fn get_taproot_signatures(
+    contract: &SignedContract,
+) -> Result<(&TaprootOffer, &TaprootAccept, &TaprootSign), Error> {
+    let (offer_taproot, accept_taproot) =
+        get_taproot_offer_and_accept(&contract.accepted_contract)?;
+    let sign_taproot = contract.taproot.as_ref().ok_or_else(|| {
+        Error::InvalidState("Missing Taproot signatures of the offer party".to_string())
+    })?;
+    Ok((offer_taproot, accept_taproot, sign_taproot))
+}
+
+fn accept_taproot_contract<L: Deref>(
+    secp: &Secp256k1<All>,
+    offered_contract: &OfferedContract,
+    accept_params: &PartyParams,
+    funding_inputs: &[FundingInput],
+    fund_secret_key: &SecretKey,
+    logger: &L,
+) -> Result<(AcceptedContract, AcceptDlc), Error>
+where
+    L::Target: Logger,
+{
+    let offer_taproot = get_taproot_offer(offered_contract)?;
+    let (dlc_transactions, adaptor_infos, adaptor_points) =
+        create_taproot_dlc_transactions(secp, offered_contract, accept_params)?;
+
+    log_info!(
+        logger,
+        "Created Taproot DLC transactions. temp_id={} fund_txid={} fund_script_pubkey={} fund_output_value={} refund_txid={} num_cets={}",
+        offered_contract.id.to_lower_hex_string(),
+        dlc_transactions.fund.compute_txid().to_string(),
+        dlc_transactions.funding_script_pubkey().to_string(),
+        dlc_transactions.get_fund_output().value.to_sat(),
+        dlc_transactions.refund.compute_txid().to_string(),
+        dlc_transactions.cets.len()
+    );
+
+    let funding = taproot::get_funding(
+        secp,
+        &offered_contract.offer_params.fund_pubkey,
+        &accept_params.fund_pubkey,
+        offered_contract.refund_locktime,
+    )?;
+    let accept_taproot = taproot::create_accept(
+        secp,
+        &funding,
+        &dlc_transactions,
+        &adaptor_points,
+        fund_secret_key,
+        offer_taproot,
+    )?;
+
+    let accepted_contract = AcceptedContract {
+        offered_contract: offered_contract.clone(),
+        adaptor_infos,
+        adaptor_signatures: vec![],
+        accept_params: accept_params.clone(),
+        funding_inputs: funding_inputs.to_vec(),
+        dlc_transactions,
+        accept_refund_signature: taproot::unused_refund_signature(),
+        taproot: Some(accept_taproot),
+    };
+
+    log_info!(
+        logger,
+        "Created Taproot accept contract. temp_id={} contract_id={}",
+        offered_contract.id.to_lower_hex_string(),
+        accepted_contract.get_contract_id().to_lower_hex_string()
+    );
+
+    let accept_msg = accepted_contract.get_accept_contract_msg(&[]);
+
+    Ok((accepted_contract, accept_msg))
+}
+
 /// Verifies the information of the accepting party [`Accept` message](dlc_messages::AcceptDlc),
 /// creates a [`SignedContract`], and generates the offering party CET adaptor signatures.
 pub async fn verify_accepted_and_sign_contract<
@@ -350,20 +530,8 @@ where
     S::Target: Storage,
     L::Target: Logger,
 {
-    let (tx_input_infos, input_amount) = get_tx_input_infos(&accept_msg.funding_inputs)?;
-    let accept_dlc_inputs = get_dlc_inputs_from_funding_inputs(&accept_msg.funding_inputs);
-
-    let accept_params = PartyParams {
-        fund_pubkey: accept_msg.funding_pubkey,
-        change_script_pubkey: accept_msg.change_spk.clone(),
-        change_serial_id: accept_msg.change_serial_id,
-        payout_script_pubkey: accept_msg.payout_spk.clone(),
-        payout_serial_id: accept_msg.payout_serial_id,
-        inputs: tx_input_infos,
-        dlc_inputs: accept_dlc_inputs.clone(),
-        input_amount,
-        collateral: accept_msg.accept_collateral,
-    };
+    let accept_params = get_accept_party_params(accept_msg)?;
+    let accept_dlc_inputs = accept_params.dlc_inputs.clone();
 
     log_debug!(
         logger,
@@ -378,6 +546,27 @@ where
         accept_params.collateral.to_sat(),
     );
 
+    if offered_contract.is_taproot() != accept_msg.taproot.is_some() {
+        return Err(Error::InvalidParameters(
+            "Accept message Taproot signatures do not match the contract funding".to_string(),
+        ));
+    }
+
+    if let Some(accept_taproot) = &accept_msg.taproot {
+        return verify_taproot_accepted_and_sign_contract(
+            secp,
+            offered_contract,
+            &accept_params,
+            &accept_msg.funding_inputs,
+            accept_taproot,
+            wallet,
+            signer_provider,
+            storage,
+            logger,
+        )
+        .await;
+    }
+
     let cet_adaptor_signatures = accept_msg
         .cet_adaptor_signatures
         .ecdsa_adaptor_signatures
@@ -473,6 +662,145 @@ where
     Ok((signed_contract, signed_msg))
 }
 
+/// Returns the parameters of the accepting party from its [`AcceptDlc`] message.
+fn get_accept_party_params(accept_msg: &AcceptDlc) -> Result<PartyParams, Error> {
+    let (tx_input_infos, input_amount) = get_tx_input_infos(&accept_msg.funding_inputs)?;
+    let accept_dlc_inputs = get_dlc_inputs_from_funding_inputs(&accept_msg.funding_inputs);
+
+    Ok(PartyParams {
+        fund_pubkey: accept_msg.funding_pubkey,
+        change_script_pubkey: accept_msg.change_spk.clone(),
+        change_serial_id: accept_msg.change_serial_id,
+        payout_script_pubkey: accept_msg.payout_spk.clone(),
+        payout_serial_id: accept_msg.payout_serial_id,
+        inputs: tx_input_infos,
+        dlc_inputs: accept_dlc_inputs,
+        input_amount,
+        collateral: accept_msg.accept_collateral,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn verify_taproot_accepted_and_sign_contract<
+    W: Deref,
+    X: ContractSigner,
+    SP: Deref,
+    S: Deref,
+    L: Deref,
+>(
+    secp: &Secp256k1<All>,
+    offered_contract: &OfferedContract,
+    accept_params: &PartyParams,
+    funding_inputs_info: &[FundingInput],
+    accept_taproot: &TaprootAccept,
+    wallet: &W,
+    signer_provider: &SP,
+    storage: &S,
+    logger: &L,
+) -> Result<(SignedContract, SignDlc), Error>
+where
+    W::Target: Wallet,
+    SP::Target: ContractSignerProvider<Signer = X>,
+    S::Target: Storage,
+    L::Target: Logger,
+{
+    let offer_taproot = get_taproot_offer(offered_contract)?;
+    let (dlc_transactions, adaptor_infos, adaptor_points) =
+        create_taproot_dlc_transactions(secp, offered_contract, accept_params)?;
+    let funding = taproot::get_funding(
+        secp,
+        &offered_contract.offer_params.fund_pubkey,
+        &accept_params.fund_pubkey,
+        offered_contract.refund_locktime,
+    )?;
+
+    taproot::verify_accept(
+        secp,
+        &funding,
+        &dlc_transactions,
+        &adaptor_points,
+        &accept_params.fund_pubkey,
+        offer_taproot,
+        accept_taproot,
+    )?;
+
+    log_debug!(
+        logger,
+        "Verified Taproot partial signatures. temp_id={} num_partial_sigs={}",
+        offered_contract.id.to_lower_hex_string(),
+        accept_taproot.cet_partial_signatures.len(),
+    );
+
+    // The nonces are marked as used in storage before they sign anything, so
+    // that no later accept message for this offer can be signed with them,
+    // even after a crash.
+    let mut offered_contract = offered_contract.clone();
+    let stored_nonces = offered_contract
+        .taproot_nonces
+        .as_mut()
+        .ok_or_else(|| Error::InvalidState("Missing the secret nonces of the offer".to_string()))?;
+    let mut nonces = stored_nonces.clone();
+    stored_nonces.take()?;
+    storage
+        .update_contract(&Contract::Offered(offered_contract.clone()))
+        .await?;
+
+    let signer = signer_provider.derive_contract_signer(offered_contract.keys_id)?;
+    let sign_taproot = taproot::create_sign(
+        secp,
+        &funding,
+        &dlc_transactions,
+        &adaptor_points,
+        &signer.get_secret_key()?,
+        &mut nonces,
+        offer_taproot,
+        accept_taproot,
+    )?;
+
+    let funding_signatures = sign_offer_funding_inputs(
+        secp,
+        &offered_contract,
+        funding_inputs_info,
+        &dlc_transactions.fund,
+        wallet,
+        storage,
+        signer_provider,
+        logger,
+    )
+    .await?;
+
+    let accepted_contract = AcceptedContract {
+        offered_contract: offered_contract.clone(),
+        accept_params: accept_params.clone(),
+        funding_inputs: funding_inputs_info.to_vec(),
+        adaptor_infos,
+        adaptor_signatures: vec![],
+        accept_refund_signature: taproot::unused_refund_signature(),
+        dlc_transactions,
+        taproot: Some(accept_taproot.clone()),
+    };
+
+    let signed_contract = SignedContract {
+        accepted_contract,
+        adaptor_signatures: vec![],
+        offer_refund_signature: taproot::unused_refund_signature(),
+        funding_signatures,
+        channel_id: None,
+        taproot: Some(sign_taproot),
+    };
+
+    log_info!(
+        logger,
+        "Signed and verified Taproot accept message. tmp_id={} contract_id={}",
+        offered_contract.id.to_lower_hex_string(),
+        signed_contract.accepted_contract.get_contract_id_string(),
+    );
+
+    let signed_msg = signed_contract.get_sign_dlc(vec![]);
+
+    Ok((signed_contract, signed_msg))
+}
+
 fn populate_psbt(psbt: &mut Psbt, all_funding_inputs: &[&FundingInput]) -> Result<(), Error> {
     // add witness utxo to fund_psbt for all inputs
     for (input_index, x) in all_funding_inputs.iter().enumerate() {
@@ -532,8 +860,6 @@ where
         pending_close_txs: _,
     } = dlc_transactions;
 
-    let mut fund_psbt = Psbt::from_unsigned_tx(fund.clone())
-        .map_err(|_| Error::InvalidState("Tried to create PSBT from signed tx".to_string()))?;
     let mut cets = cets.clone();
 
     let input_script_pubkey = input_script_pubkey.unwrap_or_else(|| funding_witness_script);
@@ -585,6 +911,7 @@ where
             accept_params.payout_serial_id,
             &payouts,
             0,
+            offered_contract.contract_flags,
         );
 
         let (adaptor_info, tmp_adaptor_index) = contract_info.verify_and_get_adaptor_info(
@@ -623,46 +950,119 @@ where
         own_signatures.extend(sigs);
     }
 
-    // get all funding inputs
-    let mut all_funding_inputs = offered_contract
-        .funding_inputs
-        .iter()
-        .chain(funding_inputs_info.iter())
-        .collect::<Vec<_>>();
-    // sort by serial id
-    all_funding_inputs.sort_by_key(|x| x.input_serial_id);
-
-    log_info!(
+    let funding_signatures = sign_offer_funding_inputs(
+        secp,
+        offered_contract,
+        funding_inputs_info,
+        fund,
+        wallet,
+        storage,
+        signer_provider,
         logger,
-        "Populating PSBT for signing funding inputs. num_funding_inputs={}",
-        all_funding_inputs.len(),
-    );
-    populate_psbt(&mut fund_psbt, &all_funding_inputs)?;
+    )
+    .await?;
 
-    let mut witnesses: Vec<Witness> = Vec::new();
-    for funding_input in offered_contract.funding_inputs.iter() {
-        let input_index = all_funding_inputs
-            .iter()
-            .position(|y| y == &funding_input)
-            .ok_or_else(|| {
-                Error::InvalidState(format!(
-                    "Could not find input for serial id {}",
-                    funding_input.input_serial_id
-                ))
-            })?;
+    let offer_refund_signature = ddk_dlc::util::get_raw_sig_for_tx_input(
+        secp,
+        refund,
+        0,
+        input_script_pubkey,
+        input_value,
+        &signer.get_secret_key()?,
+    )?;
 
-        // If the funding input contains DLC information, we can sign the input internally using the
-        // corresponding contract id to get the keys id.
-        //
-        // The funding pubkeys for both the offer and accepting party are deterministically derived from
-        // the ContractSignerProvider. Becuase of this, we do not need to prompt the consumer for the signature.
-        if let Some(dlc_input) = &funding_input.dlc_input {
-            log_debug!(
-                logger,
-                "Signing DLC input. temp_id={} splice_contract_id={} local_fund_pubkey={} remote_fund_pubkey={} input_index={}",
-                offered_contract.id.to_lower_hex_string(),
-                dlc_input.contract_id.to_lower_hex_string(),
-                dlc_input.local_fund_pubkey.to_string(),
+    let dlc_transactions = DlcTransactions {
+        fund: fund.clone(),
+        cets,
+        refund: refund.clone(),
+        funding_witness_script: funding_witness_script.clone(),
+        pending_close_txs: vec![],
+    };
+
+    let accepted_contract = AcceptedContract {
+        offered_contract: offered_contract.clone(),
+        accept_params: accept_params.clone(),
+        funding_inputs: funding_inputs_info.to_vec(),
+        adaptor_infos,
+        adaptor_signatures: cet_adaptor_signatures.to_vec(),
+        accept_refund_signature: *refund_signature,
+        dlc_transactions,
+        taproot: None,
+    };
+
+    let signed_contract = SignedContract {
+        accepted_contract,
+        adaptor_signatures: own_signatures.clone(),
+        offer_refund_signature,
+        funding_signatures,
+        channel_id,
+        taproot: None,
+    };
+
+    Ok((signed_contract, own_signatures))
+}
+
+/// Signs the offer party's funding inputs of the fund transaction.
+#[allow(clippy::too_many_arguments)]
+async fn sign_offer_funding_inputs<W: Deref, X: ContractSigner, S: Deref, SP: Deref, L: Deref>(
+    secp: &Secp256k1<All>,
+    offered_contract: &OfferedContract,
+    funding_inputs_info: &[FundingInput],
+    fund: &Transaction,
+    wallet: &W,
+    storage: &S,
+    signer_provider: &SP,
+    logger: &L,
+) -> Result<FundingSignatures, Error>
+where
+    W::Target: Wallet,
+    S::Target: Storage,
+    SP::Target: ContractSignerProvider<Signer = X>,
+    L::Target: Logger,
+{
+    let mut fund_psbt = Psbt::from_unsigned_tx(fund.clone())
+        .map_err(|_| Error::InvalidState("Tried to create PSBT from signed tx".to_string()))?;
+
+    // get all funding inputs
+    let mut all_funding_inputs = offered_contract
+        .funding_inputs
+        .iter()
+        .chain(funding_inputs_info.iter())
+        .collect::<Vec<_>>();
+    // sort by serial id
+    all_funding_inputs.sort_by_key(|x| x.input_serial_id);
+
+    log_info!(
+        logger,
+        "Populating PSBT for signing funding inputs. num_funding_inputs={}",
+        all_funding_inputs.len(),
+    );
+    populate_psbt(&mut fund_psbt, &all_funding_inputs)?;
+
+    let mut witnesses: Vec<Witness> = Vec::new();
+    for funding_input in offered_contract.funding_inputs.iter() {
+        let input_index = all_funding_inputs
+            .iter()
+            .position(|y| y == &funding_input)
+            .ok_or_else(|| {
+                Error::InvalidState(format!(
+                    "Could not find input for serial id {}",
+                    funding_input.input_serial_id
+                ))
+            })?;
+
+        // If the funding input contains DLC information, we can sign the input internally using the
+        // corresponding contract id to get the keys id.
+        //
+        // The funding pubkeys for both the offer and accepting party are deterministically derived from
+        // the ContractSignerProvider. Becuase of this, we do not need to prompt the consumer for the signature.
+        if let Some(dlc_input) = &funding_input.dlc_input {
+            log_debug!(
+                logger,
+                "Signing DLC input. temp_id={} splice_contract_id={} local_fund_pubkey={} remote_fund_pubkey={} input_index={}",
+                offered_contract.id.to_lower_hex_string(),
+                dlc_input.contract_id.to_lower_hex_string(),
+                dlc_input.local_fund_pubkey.to_string(),
                 dlc_input.remote_fund_pubkey.to_string(),
                 input_index,
             );
@@ -715,42 +1115,7 @@ where
         })
         .collect::<Result<Vec<_>, Error>>()?;
 
-    let offer_refund_signature = ddk_dlc::util::get_raw_sig_for_tx_input(
-        secp,
-        refund,
-        0,
-        input_script_pubkey,
-        input_value,
-        &signer.get_secret_key()?,
-    )?;
-
-    let dlc_transactions = DlcTransactions {
-        fund: fund.clone(),
-        cets,
-        refund: refund.clone(),
-        funding_witness_script: funding_witness_script.clone(),
-        pending_close_txs: vec![],
-    };
-
-    let accepted_contract = AcceptedContract {
-        offered_contract: offered_contract.clone(),
-        accept_params: accept_params.clone(),
-        funding_inputs: funding_inputs_info.to_vec(),
-        adaptor_infos,
-        adaptor_signatures: cet_adaptor_signatures.to_vec(),
-        accept_refund_signature: *refund_signature,
-        dlc_transactions,
-    };
-
-    let signed_contract = SignedContract {
-        accepted_contract,
-        adaptor_signatures: own_signatures.clone(),
-        offer_refund_signature,
-        funding_signatures: FundingSignatures { funding_signatures },
-        channel_id,
-    };
-
-    Ok((signed_contract, own_signatures))
+    Ok(FundingSignatures { funding_signatures })
 }
 
 /// Verifies the information from the offer party [`Sign` message](dlc_messages::SignDlc),
@@ -771,6 +1136,66 @@ where
     SP::Target: ContractSignerProvider<Signer = X>,
     L::Target: Logger,
 {
+    let offered_contract = &accepted_contract.offered_contract;
+    if offered_contract.is_taproot() != sign_msg.taproot.is_some() {
+        return Err(Error::InvalidParameters(
+            "Sign message Taproot signatures do not match the contract funding".to_string(),
+        ));
+    }
+
+    if let Some(sign_taproot) = &sign_msg.taproot {
+        let (_, adaptor_points) = taproot::get_adaptor_points(
+            secp,
+            &offered_contract.contract_info,
+            offered_contract.total_collateral,
+        )?;
+        let funding = taproot::get_funding(
+            secp,
+            &offered_contract.offer_params.fund_pubkey,
+            &accepted_contract.accept_params.fund_pubkey,
+            offered_contract.refund_locktime,
+        )?;
+        let (offer_taproot, accept_taproot) = get_taproot_offer_and_accept(accepted_contract)?;
+        taproot::verify_sign(
+            secp,
+            &funding,
+            &accepted_contract.dlc_transactions,
+            &adaptor_points,
+            &offered_contract.offer_params.fund_pubkey,
+            offer_taproot,
+            accept_taproot,
+            sign_taproot,
+        )?;
+
+        log_debug!(
+            logger,
+            "Verified Taproot partial signatures. contract_id={}",
+            accepted_contract.get_contract_id_string(),
+        );
+
+        let transaction = complete_fund_transaction(
+            secp,
+            accepted_contract,
+            &sign_msg.funding_signatures,
+            wallet,
+            storage,
+            signer_provider,
+            logger,
+        )
+        .await?;
+
+        let signed_contract = SignedContract {
+            accepted_contract: accepted_contract.clone(),
+            adaptor_signatures: vec![],
+            offer_refund_signature: taproot::unused_refund_signature(),
+            funding_signatures: sign_msg.funding_signatures.clone(),
+            channel_id: None,
+            taproot: Some(sign_taproot.clone()),
+        };
+
+        return Ok((signed_contract, transaction));
+    }
+
     let cet_adaptor_signatures: Vec<_> = (&sign_msg.cet_adaptor_signatures).into();
     verify_signed_contract_internal(
         secp,
@@ -870,6 +1295,47 @@ where
         accepted_contract.adaptor_infos.len(),
     );
 
+    let transaction = complete_fund_transaction(
+        secp,
+        accepted_contract,
+        funding_signatures,
+        wallet,
+        storage,
+        signer_provider,
+        logger,
+    )
+    .await?;
+
+    let signed_contract = SignedContract {
+        accepted_contract: accepted_contract.clone(),
+        adaptor_signatures: cet_adaptor_signatures.to_vec(),
+        offer_refund_signature: *refund_signature,
+        funding_signatures: funding_signatures.clone(),
+        channel_id,
+        taproot: None,
+    };
+
+    Ok((signed_contract, transaction))
+}
+
+/// Adds the offer party's funding signatures to the fund transaction and
+/// signs the accept party's funding inputs.
+async fn complete_fund_transaction<W: Deref, SP: Deref, S: Deref, X: ContractSigner, L: Deref>(
+    secp: &Secp256k1<All>,
+    accepted_contract: &AcceptedContract,
+    funding_signatures: &FundingSignatures,
+    wallet: &W,
+    storage: &S,
+    signer_provider: &SP,
+    logger: &L,
+) -> Result<Transaction, Error>
+where
+    W::Target: Wallet,
+    S::Target: Storage,
+    SP::Target: ContractSignerProvider<Signer = X>,
+    L::Target: Logger,
+{
+    let offered_contract = &accepted_contract.offered_contract;
     let fund_tx = &accepted_contract.dlc_transactions.fund;
     let mut fund_psbt = Psbt::from_unsigned_tx(fund_tx.clone())
         .map_err(|_| Error::InvalidState("Tried to create PSBT from signed tx".to_string()))?;
@@ -1015,50 +1481,670 @@ where
         wallet.sign_psbt_input(&mut fund_psbt, input_index).await?;
     }
 
-    let signed_contract = SignedContract {
-        accepted_contract: accepted_contract.clone(),
-        adaptor_signatures: cet_adaptor_signatures.to_vec(),
-        offer_refund_signature: *refund_signature,
-        funding_signatures: funding_signatures.clone(),
-        channel_id,
-    };
-
-    let transaction = fund_psbt.extract_tx_unchecked_fee_rate();
-
-    Ok((signed_contract, transaction))
+    Ok(fund_psbt.extract_tx_unchecked_fee_rate())
 }
 
-/// Signs and return the CET that can be used to close the given contract.
-pub fn get_signed_cet<C: Signing, S: Deref, L: Deref>(
+/// Creates the [`OfferedContract`]s and [`OfferDlc`] messages of a batch of
+/// contracts, funded by a single fund transaction with one output per
+/// contract. Each offer carries the [`BatchFundingGroup`] of the batch and the
+/// same funding inputs and change, and has keys of its own.
+#[allow(clippy::too_many_arguments)]
+pub async fn offer_batch_contracts<
+    W: Deref,
+    B: Deref,
+    T: Deref,
+    X: ContractSigner,
+    SP: Deref,
+    C: Signing + Verification,
+    L: Deref,
+>(
     secp: &Secp256k1<C>,
-    contract: &SignedContract,
-    contract_info: &ContractInfo,
-    adaptor_info: &AdaptorInfo,
-    attestations: &[(usize, OracleAttestation)],
-    signer: S,
+    contract_inputs: &[ContractInput],
+    oracle_announcements: Vec<Vec<Vec<OracleAnnouncement>>>,
+    refund_delay: u32,
+    counter_party: &PublicKey,
+    wallet: &W,
+    blockchain: &B,
+    time: &T,
+    signer_provider: &SP,
     logger: &L,
-) -> Result<Transaction, Error>
+) -> Result<Vec<(OfferedContract, OfferDlc)>, Error>
 where
-    S::Target: ContractSigner,
+    W::Target: Wallet,
+    B::Target: Blockchain,
+    T::Target: Time,
+    SP::Target: ContractSignerProvider<Signer = X>,
     L::Target: Logger,
 {
-    let contract_id = contract.accepted_contract.get_contract_id_string();
-    log_info!(
-        logger,
-        "Getting the signed CET for the Oracle Attestation. contract_id={} outcomes={:?} event_id={}",
-        contract_id,
-        attestations
+    let Some(first) = contract_inputs.first() else {
+        return Err(Error::InvalidParameters("Empty batch".to_string()));
+    };
+    if contract_inputs.len() != oracle_announcements.len() {
+        return Err(Error::InvalidParameters(
+            "Expected oracle announcements for each contract of the batch".to_string(),
+        ));
+    }
+    for contract_input in contract_inputs {
+        contract_input.validate()?;
+        if contract_input.fee_rate != first.fee_rate {
+            return Err(Error::InvalidParameters(
+                "Contracts of a batch must have the same fee rate".to_string(),
+            ));
+        }
+        if contract_input.contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0 {
+            return Err(Error::InvalidParameters(
+                "Batch funded contracts cannot be Taproot funded".to_string(),
+            ));
+        }
+    }
+
+    let ids = contract_inputs
+        .iter()
+        .map(|_| crate::utils::get_new_temporary_id())
+        .collect::<Vec<_>>();
+    let keys_ids = ids
+        .iter()
+        .map(|id| signer_provider.derive_signer_key_id(true, *id))
+        .collect::<Vec<_>>();
+    let signers = keys_ids
+        .iter()
+        .map(|keys_id| signer_provider.derive_contract_signer(*keys_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (party_params, funding_inputs_info) = crate::utils::get_batch_party_params(
+        secp,
+        &contract_inputs
             .iter()
-            .map(|(_, a)| &a.outcomes)
+            .map(|c| c.offer_collateral)
             .collect::<Vec<_>>(),
-        attestations.first().unwrap().1.event_id,
-    );
-    let (range_info, sigs) =
-        crate::utils::get_range_info_and_oracle_sigs(contract_info, adaptor_info, attestations)?;
-    let mut cet = contract.accepted_contract.dlc_transactions.cets[range_info.cet_index].clone();
-    let offered_contract = &contract.accepted_contract.offered_contract;
+        &contract_inputs
+            .iter()
+            .map(|c| c.offer_collateral + c.accept_collateral)
+            .collect::<Vec<_>>(),
+        &contract_inputs
+            .iter()
+            .map(|c| c.contract_flags)
+            .collect::<Vec<_>>(),
+        first.fee_rate,
+        wallet,
+        &signers,
+        blockchain,
+    )
+    .await?;
 
-    let (adaptor_sigs, other_pubkey) = if offered_contract.is_offer_party {
+    log_debug!(
+        logger,
+        "Created batch offer party params. num_contracts={} counter_party={} change_spk={} input_amount={}",
+        ids.len(),
+        counter_party.to_string(),
+        party_params[0].change_script_pubkey.to_string(),
+        party_params[0].input_amount.to_sat(),
+    );
+
+    let now = time.unix_time_now() as u32;
+    let mut offers = contract_inputs
+        .iter()
+        .zip(oracle_announcements)
+        .zip(ids.into_iter().zip(keys_ids))
+        .zip(party_params)
+        .map(
+            |(((contract_input, announcements), (id, keys_id)), params)| {
+                let mut offered_contract = OfferedContract::new(
+                    id,
+                    contract_input,
+                    announcements,
+                    &params,
+                    &funding_inputs_info,
+                    counter_party,
+                    refund_delay,
+                    now,
+                    keys_id,
+                );
+                offered_contract.contract_flags |= ddk_dlc::BATCH_FUNDING_FLAG;
+                let offer_msg: OfferDlc = (&offered_contract).into();
+                (offered_contract, offer_msg)
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let batch_funding_group = BatchFundingGroup::new(
+        &offers
+            .iter()
+            .map(|(_, offer_msg)| offer_msg.clone())
+            .collect::<Vec<_>>(),
+    );
+    for (offered_contract, offer_msg) in &mut offers {
+        offered_contract.batch_funding_group = Some(batch_funding_group.clone());
+        offer_msg.tlvs.set(&batch_funding_group);
+    }
+
+    Ok(offers)
+}
+
+/// Accepts all the offered contracts of a batch, which must be given in the
+/// order of their [`BatchFundingGroup`]. Creates the shared fund transaction
+/// and returns each [`AcceptedContract`] with its [`AcceptDlc`] message.
+pub async fn accept_batch_contracts<W: Deref, X: ContractSigner, SP: Deref, B: Deref, L: Deref>(
+    secp: &Secp256k1<All>,
+    offered_contracts: &[OfferedContract],
+    wallet: &W,
+    signer_provider: &SP,
+    blockchain: &B,
+    logger: &L,
+) -> Result<Vec<(AcceptedContract, AcceptDlc)>, Error>
+where
+    W::Target: Wallet,
+    B::Target: Blockchain,
+    SP::Target: ContractSignerProvider<Signer = X>,
+    L::Target: Logger,
+{
+    check_batch_offers(offered_contracts)?;
+
+    let signers = offered_contracts
+        .iter()
+        .map(|c| signer_provider.derive_contract_signer(c.keys_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (accept_params, funding_inputs) = crate::utils::get_batch_party_params(
+        secp,
+        &offered_contracts
+            .iter()
+            .map(|c| c.total_collateral - c.offer_params.collateral)
+            .collect::<Vec<_>>(),
+        &offered_contracts
+            .iter()
+            .map(|c| c.total_collateral)
+            .collect::<Vec<_>>(),
+        &offered_contracts
+            .iter()
+            .map(|c| c.contract_flags)
+            .collect::<Vec<_>>(),
+        offered_contracts[0].fee_rate_per_vb,
+        wallet,
+        &signers,
+        blockchain,
+    )
+    .await?;
+
+    let dlc_transactions = create_batch_dlc_transactions(offered_contracts, &accept_params)?;
+
+    log_info!(
+        logger,
+        "Created batch DLC transactions. num_contracts={} fund_txid={}",
+        offered_contracts.len(),
+        dlc_transactions[0].fund.compute_txid().to_string(),
+    );
+
+    let mut accepted = Vec::with_capacity(offered_contracts.len());
+    for (((offered_contract, accept_params), signer), dlc_transactions) in offered_contracts
+        .iter()
+        .zip(&accept_params)
+        .zip(&signers)
+        .zip(&dlc_transactions)
+    {
+        let (accepted_contract, adaptor_sigs) = accept_contract_internal(
+            secp,
+            offered_contract,
+            accept_params,
+            &funding_inputs,
+            &signer.get_secret_key()?,
+            dlc_transactions.get_fund_output().value,
+            None,
+            dlc_transactions,
+        )?;
+        let accept_msg = accepted_contract.get_accept_contract_msg(&adaptor_sigs);
+        accepted.push((accepted_contract, accept_msg));
+    }
+
+    Ok(accepted)
+}
+
+/// Verifies the [`AcceptDlc`] messages of all the contracts of a batch, given
+/// in the same order as the offered contracts, and signs the contracts.
+/// Returns each [`SignedContract`] with its [`SignDlc`] message.
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_batch_accepted_and_sign_contracts<
+    W: Deref,
+    X: ContractSigner,
+    SP: Deref,
+    S: Deref,
+    L: Deref,
+>(
+    secp: &Secp256k1<All>,
+    offered_contracts: &[OfferedContract],
+    accept_msgs: &[AcceptDlc],
+    wallet: &W,
+    signer_provider: &SP,
+    storage: &S,
+    logger: &L,
+) -> Result<Vec<(SignedContract, SignDlc)>, Error>
+where
+    W::Target: Wallet,
+    SP::Target: ContractSignerProvider<Signer = X>,
+    S::Target: Storage,
+    L::Target: Logger,
+{
+    check_batch_offers(offered_contracts)?;
+    if offered_contracts.len() != accept_msgs.len() {
+        return Err(Error::InvalidParameters(
+            "Expected an accept message for each contract of the batch".to_string(),
+        ));
+    }
+
+    let mut accept_params = Vec::with_capacity(accept_msgs.len());
+    for (offered_contract, accept_msg) in offered_contracts.iter().zip(accept_msgs) {
+        if accept_msg.temporary_contract_id != offered_contract.id {
+            return Err(Error::InvalidParameters(
+                "Accept messages do not match the batch".to_string(),
+            ));
+        }
+        if accept_msg.taproot.is_some() {
+            return Err(Error::InvalidParameters(
+                "Batch funded contracts cannot be Taproot funded".to_string(),
+            ));
+        }
+        accept_params.push(get_accept_party_params(accept_msg)?);
+    }
+
+    let dlc_transactions = create_batch_dlc_transactions(offered_contracts, &accept_params)?;
+
+    log_info!(
+        logger,
+        "Created batch DLC transactions. num_contracts={} fund_txid={}",
+        offered_contracts.len(),
+        dlc_transactions[0].fund.compute_txid().to_string(),
+    );
+
+    let mut signed = Vec::with_capacity(offered_contracts.len());
+    for (((offered_contract, accept_msg), accept_params), dlc_transactions) in offered_contracts
+        .iter()
+        .zip(accept_msgs)
+        .zip(&accept_params)
+        .zip(&dlc_transactions)
+    {
+        let cet_adaptor_signatures: Vec<_> = (&accept_msg.cet_adaptor_signatures).into();
+        let signer = signer_provider.derive_contract_signer(offered_contract.keys_id)?;
+        let (signed_contract, adaptor_sigs) = verify_accepted_and_sign_contract_internal(
+            secp,
+            offered_contract,
+            accept_params,
+            &accept_msg.funding_inputs,
+            &accept_msg.refund_signature,
+            &cet_adaptor_signatures,
+            dlc_transactions.get_fund_output().value,
+            wallet,
+            &signer,
+            None,
+            None,
+            dlc_transactions,
+            None,
+            storage,
+            signer_provider,
+            logger,
+        )
+        .await?;
+        let sign_msg = signed_contract.get_sign_dlc(adaptor_sigs);
+        signed.push((signed_contract, sign_msg));
+    }
+
+    Ok(signed)
+}
+
+/// Checks that the offered contracts are all the contracts of one batch, in
+/// the order of its [`BatchFundingGroup`].
+fn check_batch_offers(offered_contracts: &[OfferedContract]) -> Result<(), Error> {
+    let Some(first) = offered_contracts.first() else {
+        return Err(Error::InvalidParameters("Empty batch".to_string()));
+    };
+    let Some(group) = &first.batch_funding_group else {
+        return Err(Error::InvalidParameters(
+            "Contract is not batch funded".to_string(),
+        ));
+    };
+    let ids = offered_contracts.iter().map(|c| c.id).collect::<Vec<_>>();
+    if ids != group.temporary_contract_ids {
+        return Err(Error::InvalidParameters(
+            "Contracts do not match their batch funding group".to_string(),
+        ));
+    }
+    for offered_contract in offered_contracts {
+        if offered_contract.batch_funding_group.as_ref() != Some(group)
+            || offered_contract.counter_party != first.counter_party
+            || offered_contract.fee_rate_per_vb != first.fee_rate_per_vb
+            || offered_contract.is_offer_party != first.is_offer_party
+        {
+            return Err(Error::InvalidParameters(
+                "Contracts of a batch must have the same group, counter party and fee rate"
+                    .to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Creates the transactions of a batch from the offered contracts and the
+/// parameters of the accepting party for each of them.
+fn create_batch_dlc_transactions(
+    offered_contracts: &[OfferedContract],
+    accept_params: &[PartyParams],
+) -> Result<Vec<DlcTransactions>, Error> {
+    let contracts = offered_contracts
+        .iter()
+        .zip(accept_params)
+        .map(|(offered_contract, accept_params)| {
+            Ok(BatchContractParams {
+                offer_params: offered_contract.offer_params.clone(),
+                accept_params: accept_params.clone(),
+                payouts: offered_contract.contract_info[0]
+                    .get_payouts(offered_contract.total_collateral)?,
+                refund_lock_time: offered_contract.refund_locktime,
+                cet_lock_time: offered_contract.cet_locktime,
+                fund_output_serial_id: offered_contract.fund_output_serial_id,
+                contract_flags: offered_contract.contract_flags,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(ddk_dlc::batch::create_batch_dlc_transactions(
+        &contracts,
+        offered_contracts[0].fee_rate_per_vb,
+        0,
+    )?)
+}
+
+/// Creates the contract and transactions replacing those of a signed contract,
+/// with a fund transaction spending the same inputs at a higher fee rate. The
+/// CETs and refund transaction pay the higher fee rate as well, and each party
+/// pays its share of the higher fees out of its change output. Only the transactions spending the fund output of the first
+/// contract info are returned, as for a newly accepted contract.
+///
+/// The funding inputs do not signal replaceability, the replacing fund
+/// transaction relies on full-RBF relay policy to replace the first one.
+pub(crate) fn get_bumped_funding_transactions(
+    signed_contract: &SignedContract,
+    fee_rate_per_vb: u64,
+) -> Result<(OfferedContract, DlcTransactions), Error> {
+    let accepted_contract = &signed_contract.accepted_contract;
+    let offered_contract = &accepted_contract.offered_contract;
+    if offered_contract.is_taproot() {
+        return Err(Error::InvalidParameters(
+            "Bumping the fee of a Taproot funded contract is not supported".to_string(),
+        ));
+    }
+    if signed_contract.channel_id.is_some() {
+        return Err(Error::InvalidParameters(
+            "Bumping the fee of a channel fund transaction is not supported".to_string(),
+        ));
+    }
+    if offered_contract.is_batch_funded() {
+        return Err(Error::InvalidParameters(
+            "Bumping the fee of a batch fund transaction is not supported".to_string(),
+        ));
+    }
+    if !offered_contract.offer_params.dlc_inputs.is_empty()
+        || !accepted_contract.accept_params.dlc_inputs.is_empty()
+    {
+        return Err(Error::InvalidParameters(
+            "Bumping the fee of a spliced contract is not supported".to_string(),
+        ));
+    }
+    if fee_rate_per_vb <= offered_contract.fee_rate_per_vb {
+        return Err(Error::InvalidParameters(format!(
+            "The fee rate must be higher than the current one. fee_rate={} current_fee_rate={}",
+            fee_rate_per_vb, offered_contract.fee_rate_per_vb
+        )));
+    }
+
+    let mut offered_contract = offered_contract.clone();
+    offered_contract.fee_rate_per_vb = fee_rate_per_vb;
+    let dlc_transactions = ddk_dlc::create_dlc_transactions(
+        &offered_contract.offer_params,
+        &accepted_contract.accept_params,
+        &offered_contract.contract_info[0].get_payouts(offered_contract.total_collateral)?,
+        offered_contract.refund_locktime,
+        fee_rate_per_vb,
+        0,
+        offered_contract.cet_locktime,
+        offered_contract.fund_output_serial_id,
+        offered_contract.contract_flags,
+    )?;
+
+    Ok((offered_contract, dlc_transactions))
+}
+
+/// Creates the [`AcceptedContract`] replacing a signed contract with a fund
+/// transaction paying `fee_rate_per_vb`, and the [`BumpFundingAccept`] message
+/// with the signatures of the accept party.
+pub fn accept_funding_fee_bump<X: ContractSigner, SP: Deref, L: Deref>(
+    secp: &Secp256k1<All>,
+    signed_contract: &SignedContract,
+    fee_rate_per_vb: u64,
+    signer_provider: &SP,
+    logger: &L,
+) -> Result<(AcceptedContract, BumpFundingAccept), Error>
+where
+    SP::Target: ContractSignerProvider<Signer = X>,
+    L::Target: Logger,
+{
+    let accepted_contract = &signed_contract.accepted_contract;
+    let (offered_contract, dlc_transactions) =
+        get_bumped_funding_transactions(signed_contract, fee_rate_per_vb)?;
+    let signer = signer_provider.derive_contract_signer(offered_contract.keys_id)?;
+
+    let (bumped_contract, adaptor_sigs) = accept_contract_internal(
+        secp,
+        &offered_contract,
+        &accepted_contract.accept_params,
+        &accepted_contract.funding_inputs,
+        &signer.get_secret_key()?,
+        dlc_transactions.get_fund_output().value,
+        None,
+        &dlc_transactions,
+    )?;
+
+    log_info!(
+        logger,
+        "Signed transactions of a fund transaction with a higher fee. contract_id={} bumped_contract_id={} fee_rate={}",
+        accepted_contract.get_contract_id_string(),
+        bumped_contract.get_contract_id_string(),
+        fee_rate_per_vb,
+    );
+
+    let msg = BumpFundingAccept {
+        protocol_version: crate::conversion_utils::PROTOCOL_VERSION,
+        contract_id: accepted_contract.get_contract_id(),
+        fee_rate_per_vb,
+        cet_adaptor_signatures: adaptor_sigs.as_slice().into(),
+        refund_signature: bumped_contract.accept_refund_signature,
+    };
+
+    Ok((bumped_contract, msg))
+}
+
+/// Verifies the signatures of the accept party for the transactions replacing
+/// those of a signed contract, and signs them along with the funding inputs of
+/// the offer party in the replacing fund transaction.
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_funding_fee_bump_accept_and_sign<
+    W: Deref,
+    X: ContractSigner,
+    S: Deref,
+    SP: Deref,
+    L: Deref,
+>(
+    secp: &Secp256k1<All>,
+    signed_contract: &SignedContract,
+    accept_msg: &BumpFundingAccept,
+    wallet: &W,
+    storage: &S,
+    signer_provider: &SP,
+    logger: &L,
+) -> Result<(SignedContract, BumpFundingSign), Error>
+where
+    W::Target: Wallet,
+    S::Target: Storage,
+    SP::Target: ContractSignerProvider<Signer = X>,
+    L::Target: Logger,
+{
+    let accepted_contract = &signed_contract.accepted_contract;
+    let (offered_contract, dlc_transactions) =
+        get_bumped_funding_transactions(signed_contract, accept_msg.fee_rate_per_vb)?;
+    let signer = signer_provider.derive_contract_signer(offered_contract.keys_id)?;
+    let cet_adaptor_signatures: Vec<_> = (&accept_msg.cet_adaptor_signatures).into();
+
+    let (bumped_contract, adaptor_sigs) = verify_accepted_and_sign_contract_internal(
+        secp,
+        &offered_contract,
+        &accepted_contract.accept_params,
+        &accepted_contract.funding_inputs,
+        &accept_msg.refund_signature,
+        &cet_adaptor_signatures,
+        dlc_transactions.get_fund_output().value,
+        wallet,
+        &signer,
+        None,
+        None,
+        &dlc_transactions,
+        None,
+        storage,
+        signer_provider,
+        logger,
+    )
+    .await?;
+
+    log_info!(
+        logger,
+        "Signed transactions of a fund transaction with a higher fee. contract_id={} bumped_contract_id={} fee_rate={}",
+        accepted_contract.get_contract_id_string(),
+        bumped_contract.accepted_contract.get_contract_id_string(),
+        accept_msg.fee_rate_per_vb,
+    );
+
+    let msg = BumpFundingSign {
+        protocol_version: crate::conversion_utils::PROTOCOL_VERSION,
+        contract_id: accepted_contract.get_contract_id(),
+        cet_adaptor_signatures: adaptor_sigs.as_slice().into(),
+        refund_signature: bumped_contract.offer_refund_signature,
+        funding_signatures: bumped_contract.funding_signatures.clone(),
+    };
+
+    Ok((bumped_contract, msg))
+}
+
+/// Verifies the signatures of the offer party for the transactions replacing
+/// those of a signed contract, and returns the signed contract along with the
+/// fully signed replacing fund transaction.
+pub async fn verify_funding_fee_bump_sign<
+    W: Deref,
+    S: Deref,
+    SP: Deref,
+    X: ContractSigner,
+    L: Deref,
+>(
+    secp: &Secp256k1<All>,
+    bumped_contract: &AcceptedContract,
+    sign_msg: &BumpFundingSign,
+    wallet: &W,
+    storage: &S,
+    signer_provider: &SP,
+    logger: &L,
+) -> Result<(SignedContract, Transaction), Error>
+where
+    W::Target: Wallet,
+    S::Target: Storage,
+    SP::Target: ContractSignerProvider<Signer = X>,
+    L::Target: Logger,
+{
+    let cet_adaptor_signatures: Vec<_> = (&sign_msg.cet_adaptor_signatures).into();
+    verify_signed_contract_internal(
+        secp,
+        bumped_contract,
+        &sign_msg.refund_signature,
+        &cet_adaptor_signatures,
+        &sign_msg.funding_signatures,
+        bumped_contract.dlc_transactions.get_fund_output().value,
+        None,
+        None,
+        wallet,
+        None,
+        storage,
+        signer_provider,
+        logger,
+    )
+    .await
+}
+
+/// Signs and return the CET that can be used to close the given contract.
+pub fn get_signed_cet<C: Signing + Verification, S: Deref, L: Deref>(
+    secp: &Secp256k1<C>,
+    contract: &SignedContract,
+    contract_info: &ContractInfo,
+    adaptor_info: &AdaptorInfo,
+    attestations: &[(usize, OracleAttestation)],
+    signer: S,
+    logger: &L,
+) -> Result<Transaction, Error>
+where
+    S::Target: ContractSigner,
+    L::Target: Logger,
+{
+    let contract_id = contract.accepted_contract.get_contract_id_string();
+    log_info!(
+        logger,
+        "Getting the signed CET for the Oracle Attestation. contract_id={} outcomes={:?} event_id={}",
+        contract_id,
+        attestations
+            .iter()
+            .map(|(_, a)| &a.outcomes)
+            .collect::<Vec<_>>(),
+        attestations.first().unwrap().1.event_id,
+    );
+    let (range_info, sigs) =
+        crate::utils::get_range_info_and_oracle_sigs(contract_info, adaptor_info, attestations)?;
+    let offered_contract = &contract.accepted_contract.offered_contract;
+
+    if offered_contract.is_taproot() {
+        // The CETs of all the contract infos are signed together, so the CET
+        // index of the range info is relative to the contract info's first CET.
+        let contract_info_index = offered_contract
+            .contract_info
+            .iter()
+            .position(|x| std::ptr::eq(x, contract_info))
+            .ok_or_else(|| {
+                Error::InvalidParameters("Contract info is not part of the contract".to_string())
+            })?;
+        let cet_index = taproot::get_cet_offset(
+            &offered_contract.contract_info,
+            contract_info_index,
+            offered_contract.total_collateral,
+        )? + range_info.cet_index;
+        let dlc_transactions = &contract.accepted_contract.dlc_transactions;
+        let mut cet = dlc_transactions
+            .cets
+            .get(cet_index)
+            .ok_or_else(|| Error::InvalidState("Missing CET".to_string()))?
+            .clone();
+        let (offer_taproot, accept_taproot, sign_taproot) = get_taproot_signatures(contract)?;
+        let funding = taproot::get_funding(
+            secp,
+            &offered_contract.offer_params.fund_pubkey,
+            &contract.accepted_contract.accept_params.fund_pubkey,
+            offered_contract.refund_locktime,
+        )?;
+        taproot::sign_cet(
+            &mut cet,
+            &funding,
+            dlc_transactions,
+            range_info.adaptor_index,
+            &sigs,
+            offer_taproot,
+            accept_taproot,
+            sign_taproot,
+        )?;
+        return Ok(cet);
+    }
+
+    let mut cet = contract.accepted_contract.dlc_transactions.cets[range_info.cet_index].clone();
+
+    let (adaptor_sigs, other_pubkey) = if offered_contract.is_offer_party {
         (
             contract.accepted_contract.adaptor_signatures.clone(),
             &contract.accepted_contract.accept_params.fund_pubkey,
@@ -1094,7 +2180,7 @@ where
 }
 
 /// Signs and return the refund transaction to refund the contract.
-pub fn get_signed_refund<C: Signing, S: Deref, L: Deref>(
+pub fn get_signed_refund<C: Signing + Verification, S: Deref, L: Deref>(
     secp: &Secp256k1<C>,
     contract: &SignedContract,
     signer: S,
@@ -1111,6 +2197,24 @@ where
     );
     let accepted_contract = &contract.accepted_contract;
     let offered_contract = &accepted_contract.offered_contract;
+
+    if offered_contract.is_taproot() {
+        let (offer_taproot, accept_taproot, sign_taproot) = get_taproot_signatures(contract)?;
+        let funding = taproot::get_funding(
+            secp,
+            &offered_contract.offer_params.fund_pubkey,
+            &accepted_contract.accept_params.fund_pubkey,
+            offered_contract.refund_locktime,
+        )?;
+        return taproot::sign_refund(
+            &funding,
+            &accepted_contract.dlc_transactions,
+            offer_taproot,
+            accept_taproot,
+            sign_taproot,
+        );
+    }
+
     let funding_witness_script = &accepted_contract.dlc_transactions.funding_witness_script;
     let fund_output_value = accepted_contract.dlc_transactions.get_fund_output().value;
     let (other_fund_pubkey, other_sig) = if offered_contract.is_offer_party {
@@ -1140,6 +2244,37 @@ where
     Ok(refund)
 }
 
+/// Returns the refund transaction of a Taproot funded contract, spending the
+/// refund leaf with the refund leaf signatures both parties exchanged, instead
+/// of the key path.
+pub fn get_signed_refund_leaf<C: Verification>(
+    secp: &Secp256k1<C>,
+    contract: &SignedContract,
+) -> Result<Transaction, Error> {
+    let accepted_contract = &contract.accepted_contract;
+    let offered_contract = &accepted_contract.offered_contract;
+    if !offered_contract.is_taproot() {
+        return Err(Error::InvalidParameters(
+            "Only Taproot funded contracts have a refund leaf".to_string(),
+        ));
+    }
+    let (_, accept_taproot, sign_taproot) = get_taproot_signatures(contract)?;
+    let funding = taproot::get_funding(
+        secp,
+        &offered_contract.offer_params.fund_pubkey,
+        &accepted_contract.accept_params.fund_pubkey,
+        offered_contract.refund_locktime,
+    )?;
+    taproot::sign_refund_leaf(
+        &funding,
+        &accepted_contract.dlc_transactions,
+        &offered_contract.offer_params.fund_pubkey,
+        &accepted_contract.accept_params.fund_pubkey,
+        accept_taproot,
+        sign_taproot,
+    )
+}
+
 /// Creates a cooperative close transaction and signs it with the local party's key.
 pub fn create_cooperative_close<C: Signing, SP: Deref, L: Deref>(
     secp: &Secp256k1<C>,
@@ -1156,6 +2291,12 @@ where
     let offered_contract = &accepted_contract.offered_contract;
     let total_collateral = offered_contract.total_collateral;
 
+    if offered_contract.is_taproot() {
+        return Err(Error::InvalidParameters(
+            "Cooperative close is not supported for Taproot funded contracts".to_string(),
+        ));
+    }
+
     if counter_payout > total_collateral {
         return Err(Error::InvalidParameters(
             "Counter payout is greater than total collateral".to_string(),
@@ -1228,6 +2369,13 @@ where
 {
     let accepted_contract = &signed_contract.accepted_contract;
     let offered_contract = &accepted_contract.offered_contract;
+
+    if offered_contract.is_taproot() {
+        return Err(Error::InvalidParameters(
+            "Cooperative close is not supported for Taproot funded contracts".to_string(),
+        ));
+    }
+
     let fund_output_value = accepted_contract.dlc_transactions.get_fund_output().value;
     let fund_outpoint = accepted_contract.dlc_transactions.get_fund_outpoint();
 
@@ -1279,3 +2427,49 @@ where
 
     Ok(close_tx)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::ser::Serializable;
+
+    fn signed_contract() -> SignedContract {
+        let stored = include_bytes!("../../testconfig/contract_binaries/Signed");
+        // The first byte is the prefix of the contract state.
+        SignedContract::deserialize(&mut lightning::io::Cursor::new(&stored[1..])).unwrap()
+    }
+
+    #[test]
+    fn bumped_funding_transactions_spend_the_same_inputs() {
+        let signed = signed_contract();
+        let accepted = &signed.accepted_contract;
+        let fee_rate = accepted.offered_contract.fee_rate_per_vb;
+        let fund = &accepted.dlc_transactions.fund;
+
+        let (offered, bumped) = get_bumped_funding_transactions(&signed, fee_rate + 10).unwrap();
+
+        assert_eq!(offered.fee_rate_per_vb, fee_rate + 10);
+        let outpoints = |tx: &Transaction| {
+            let mut outpoints: Vec<_> = tx.input.iter().map(|i| i.previous_output).collect();
+            outpoints.sort();
+            outpoints
+        };
+        assert_eq!(outpoints(&bumped.fund), outpoints(fund));
+        assert!(bumped.get_fund_output().value > accepted.dlc_transactions.get_fund_output().value);
+        let total = |tx: &Transaction| tx.output.iter().map(|o| o.value).sum::<Amount>();
+        assert!(total(&bumped.fund) < total(fund));
+        assert_ne!(bumped.fund.compute_txid(), fund.compute_txid());
+        assert_eq!(
+            bumped.cets[0].input[0].previous_output,
+            bumped.get_fund_outpoint()
+        );
+    }
+
+    #[test]
+    fn bumped_funding_transactions_need_a_higher_fee_rate() {
+        let signed = signed_contract();
+        let fee_rate = signed.accepted_contract.offered_contract.fee_rate_per_vb;
+
+        assert!(get_bumped_funding_transactions(&signed, fee_rate).is_err());
+    }
+}
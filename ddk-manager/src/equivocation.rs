@@ -0,0 +1,454 @@
+//! Detection of oracles attesting to conflicting outcomes.
+//!
+//! An oracle commits to one nonce per digit of an event in its announcement.
+//! Signing two different outcomes with the same nonce reveals the secret key
+//! of the oracle, and whoever holds it can sign any outcome of any of its
+//! events. The [`AttestationLedger`] keeps every valid attestation seen for an
+//! announcement, whatever its source, and turns a conflicting pair into a
+//! [`FraudProof`] that anyone can check with [`FraudProof::verify`]. The
+//! ledger is part of the [`ManagerState`](crate::manager_state::ManagerState),
+//! so oracles stay frozen across restarts.
+
+use std::collections::HashMap;
+
+use ddk_dlc::secp_utils::schnorrsig_extract_secret_key;
+use ddk_messages::oracle_msgs::{tagged_attestation_msg, OracleAnnouncement, OracleAttestation};
+use ddk_messages::ser_impls::{
+    read_schnorr_pubkey, read_string, read_vec, write_schnorr_pubkey, write_string, write_vec,
+};
+use lightning::io::Read;
+use lightning::ln::msgs::DecodeError;
+use lightning::util::ser::{Readable, Writeable, Writer};
+use secp256k1_zkp::{PublicKey, Secp256k1, SecretKey, Signing, Verification, XOnlyPublicKey};
+
+use crate::error::Error;
+
+/// Where an attestation was seen.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum AttestationSource {
+    /// Fetched from an oracle client, over HTTP, Nostr or any other transport.
+    Oracle,
+    /// Sent by a peer.
+    Peer(PublicKey),
+    /// Given to close a contract manually.
+    Manual,
+    /// Seen elsewhere by the application, described by the string.
+    Other(String),
+    /// Seen by an oracle client on top of the attestation it answered the
+    /// manager with, e.g. from another client of an oracle set or on a Nostr
+    /// relay. The string describes the transport.
+    Transport(String),
+}
+
+/// Proof that an oracle signed two different outcomes of an event with the
+/// same nonce.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct FraudProof {
+    /// The announcement committing the oracle to the nonces of the event.
+    pub announcement: OracleAnnouncement,
+    /// An attestation of the event.
+    pub first: OracleAttestation,
+    /// An attestation of the event conflicting with `first`.
+    pub second: OracleAttestation,
+    /// The index of the nonce used to sign two different outcomes.
+    pub nonce_index: usize,
+    /// The secret key of the oracle, extracted from the two signatures at
+    /// `nonce_index`.
+    pub oracle_secret_key: SecretKey,
+}
+
+impl FraudProof {
+    /// Builds the proof for two valid attestations of `announcement`, if they
+    /// attest to different outcomes.
+    pub fn from_attestations<C: Signing + Verification>(
+        secp: &Secp256k1<C>,
+        announcement: &OracleAnnouncement,
+        first: &OracleAttestation,
+        second: &OracleAttestation,
+    ) -> Result<Option<FraudProof>, Error> {
+        first.validate(secp, announcement)?;
+        second.validate(secp, announcement)?;
+        let Some(nonce_index) = first
+            .outcomes
+            .iter()
+            .zip(second.outcomes.iter())
+            .position(|(a, b)| a != b)
+        else {
+            return Ok(None);
+        };
+
+        // Both attestations are signed with the announced nonces, so the two
+        // signatures at the index share their nonce.
+        let oracle_secret_key = schnorrsig_extract_secret_key(
+            secp,
+            &announcement.oracle_public_key,
+            (
+                &first.signatures[nonce_index],
+                &tagged_attestation_msg(&first.outcomes[nonce_index]),
+            ),
+            (
+                &second.signatures[nonce_index],
+                &tagged_attestation_msg(&second.outcomes[nonce_index]),
+            ),
+        )?;
+
+        Ok(Some(FraudProof {
+            announcement: announcement.clone(),
+            first: first.clone(),
+            second: second.clone(),
+            nonce_index,
+            oracle_secret_key,
+        }))
+    }
+
+    /// Checks that the announcement is signed by the oracle, and that the
+    /// attestations conflict and reveal the included secret key.
+    pub fn verify<C: Signing + Verification>(&self, secp: &Secp256k1<C>) -> Result<(), Error> {
+        self.announcement.validate(secp)?;
+        match Self::from_attestations(secp, &self.announcement, &self.first, &self.second)? {
+            Some(proof) if proof == *self => Ok(()),
+            _ => Err(Error::InvalidParameters(
+                "The attestations do not prove an equivocation of the oracle.".to_string(),
+            )),
+        }
+    }
+
+    /// The public key of the oracle that equivocated.
+    pub fn oracle_public_key(&self) -> XOnlyPublicKey {
+        self.announcement.oracle_public_key
+    }
+}
+
+/// A valid attestation and the sources it was seen from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeenAttestation {
+    /// The attestation.
+    pub attestation: OracleAttestation,
+    /// Every source the attestation was seen from.
+    pub sources: Vec<AttestationSource>,
+}
+
+pub(crate) type EventKey = (XOnlyPublicKey, String);
+
+impl_dlc_writeable_enum!(AttestationSource,
+    (1, Peer);;
+    (3, Other, write_string, read_string), (4, Transport, write_string, read_string);
+    (0, Oracle), (2, Manual)
+);
+impl_dlc_writeable!(FraudProof, {
+    (announcement, writeable),
+    (first, writeable),
+    (second, writeable),
+    (nonce_index, usize),
+    (oracle_secret_key, writeable)
+});
+impl_dlc_writeable!(SeenAttestation, { (attestation, writeable), (sources, vec) });
+
+/// The attestations seen for each announcement, and the oracles caught
+/// attesting to conflicting outcomes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttestationLedger {
+    seen: HashMap<EventKey, Vec<SeenAttestation>>,
+    fraud_proofs: HashMap<XOnlyPublicKey, FraudProof>,
+}
+
+impl AttestationLedger {
+    /// Records an attestation of `announcement` seen from `source`.
+    ///
+    /// Returns the fraud proof when the attestation conflicts with one seen
+    /// before and the oracle was not already caught.
+    ///
+    /// # Errors
+    /// If the attestation is not valid for the announcement.
+    pub fn record<C: Signing + Verification>(
+        &mut self,
+        secp: &Secp256k1<C>,
+        announcement: &OracleAnnouncement,
+        attestation: OracleAttestation,
+        source: AttestationSource,
+    ) -> Result<Option<FraudProof>, Error> {
+        attestation.validate(secp, announcement)?;
+        let seen = self.seen.entry(event_key(announcement)).or_default();
+        if let Some(known) = seen.iter_mut().find(|s| s.attestation == attestation) {
+            if !known.sources.contains(&source) {
+                known.sources.push(source);
+            }
+            return Ok(None);
+        }
+
+        let mut fraud_proof = None;
+        for known in seen.iter() {
+            fraud_proof = FraudProof::from_attestations(
+                secp,
+                announcement,
+                &known.attestation,
+                &attestation,
+            )?;
+            if fraud_proof.is_some() {
+                break;
+            }
+        }
+        seen.push(SeenAttestation {
+            attestation,
+            sources: vec![source],
+        });
+
+        match fraud_proof {
+            Some(proof) if !self.fraud_proofs.contains_key(&proof.oracle_public_key()) => {
+                self.fraud_proofs
+                    .insert(proof.oracle_public_key(), proof.clone());
+                Ok(Some(proof))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The attestations seen for an announcement.
+    pub fn attestations(&self, announcement: &OracleAnnouncement) -> &[SeenAttestation] {
+        self.seen
+            .get(&event_key(announcement))
+            .map_or(&[], |seen| seen.as_slice())
+    }
+
+    /// The fraud proof of an oracle, if it was caught equivocating.
+    pub fn fraud_proof(&self, oracle: &XOnlyPublicKey) -> Option<&FraudProof> {
+        self.fraud_proofs.get(oracle)
+    }
+
+    /// The fraud proofs of every oracle caught equivocating.
+    pub fn fraud_proofs(&self) -> impl Iterator<Item = &FraudProof> {
+        self.fraud_proofs.values()
+    }
+
+    /// Removes the fraud proof of an oracle, once it was reviewed.
+    pub fn clear_fraud_proof(&mut self, oracle: &XOnlyPublicKey) -> Option<FraudProof> {
+        self.fraud_proofs.remove(oracle)
+    }
+
+    /// Whether no attestation and no fraud proof is kept.
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty() && self.fraud_proofs.is_empty()
+    }
+
+    /// Forgets the attestations of an announcement, once no contract needs
+    /// them anymore. Fraud proofs are kept.
+    pub fn forget(&mut self, announcement: &OracleAnnouncement) {
+        self.seen.remove(&event_key(announcement));
+    }
+}
+
+impl Writeable for AttestationLedger {
+    fn write<W: Writer>(&self, writer: &mut W) -> Result<(), lightning::io::Error> {
+        (self.seen.len() as u64).write(writer)?;
+        for ((oracle, event_id), seen) in &self.seen {
+            write_schnorr_pubkey(oracle, writer)?;
+            write_string(event_id, writer)?;
+            write_vec(seen, writer)?;
+        }
+        // Fraud proofs are keyed by the oracle they prove the key of.
+        write_vec(&self.fraud_proofs.values().cloned().collect(), writer)
+    }
+}
+
+impl Readable for AttestationLedger {
+    fn read<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let len: u64 = Readable::read(reader)?;
+        let mut seen = HashMap::new();
+        for _ in 0..len {
+            let oracle = read_schnorr_pubkey(reader)?;
+            let event_id = read_string(reader)?;
+            seen.insert((oracle, event_id), read_vec(reader)?);
+        }
+        let fraud_proofs = read_vec::<_, FraudProof>(reader)?
+            .into_iter()
+            .map(|proof| (proof.oracle_public_key(), proof))
+            .collect();
+        Ok(AttestationLedger { seen, fraud_proofs })
+    }
+}
+
+/// The oracle and id of the event of an announcement.
+pub(crate) fn event_key(announcement: &OracleAnnouncement) -> EventKey {
+    (
+        announcement.oracle_public_key,
+        announcement.oracle_event.event_id.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ddk_dlc::secp_utils::schnorrsig_sign_with_nonce;
+    use ddk_messages::oracle_msgs::{
+        tagged_announcement_msg, EnumEventDescriptor, EventDescriptor, OracleEvent,
+    };
+    use lightning::io::Cursor;
+    use secp256k1_zkp::rand::thread_rng;
+    use secp256k1_zkp::{Keypair, SECP256K1};
+
+    use super::*;
+    use crate::manager_state::ManagerState;
+
+    struct TestOracle {
+        keypair: Keypair,
+        nonce: SecretKey,
+        announcement: OracleAnnouncement,
+    }
+
+    impl TestOracle {
+        fn new() -> Self {
+            let keypair = Keypair::new(SECP256K1, &mut thread_rng());
+            let nonce = SecretKey::new(&mut thread_rng());
+            let oracle_event = OracleEvent {
+                oracle_nonces: vec![nonce.x_only_public_key(SECP256K1).0],
+                event_maturity_epoch: 10,
+                event_descriptor: EventDescriptor::EnumEvent(EnumEventDescriptor {
+                    outcomes: vec!["yes".to_string(), "no".to_string()],
+                }),
+                event_id: "event".to_string(),
+            };
+            let announcement = OracleAnnouncement {
+                announcement_signature: SECP256K1
+                    .sign_schnorr(&tagged_announcement_msg(&oracle_event), &keypair),
+                oracle_public_key: keypair.x_only_public_key().0,
+                oracle_event,
+            };
+            TestOracle {
+                keypair,
+                nonce,
+                announcement,
+            }
+        }
+
+        fn attest(&self, outcome: &str) -> OracleAttestation {
+            OracleAttestation {
+                event_id: self.announcement.oracle_event.event_id.clone(),
+                oracle_public_key: self.announcement.oracle_public_key,
+                signatures: vec![schnorrsig_sign_with_nonce(
+                    SECP256K1,
+                    &tagged_attestation_msg(outcome),
+                    &self.keypair,
+                    &self.nonce.secret_bytes(),
+                )],
+                outcomes: vec![outcome.to_string()],
+            }
+        }
+    }
+
+    #[test]
+    fn conflicting_attestations_reveal_the_oracle_key() {
+        let oracle = TestOracle::new();
+        let mut ledger = AttestationLedger::default();
+        let announcement = &oracle.announcement;
+
+        let first = ledger
+            .record(
+                SECP256K1,
+                announcement,
+                oracle.attest("yes"),
+                AttestationSource::Oracle,
+            )
+            .unwrap();
+        assert!(first.is_none());
+        // The same attestation from another source is not a conflict.
+        let again = ledger
+            .record(
+                SECP256K1,
+                announcement,
+                oracle.attest("yes"),
+                AttestationSource::Manual,
+            )
+            .unwrap();
+        assert!(again.is_none());
+        assert_eq!(
+            ledger.attestations(announcement)[0].sources,
+            vec![AttestationSource::Oracle, AttestationSource::Manual]
+        );
+
+        let proof = ledger
+            .record(
+                SECP256K1,
+                announcement,
+                oracle.attest("no"),
+                AttestationSource::Other("relay".to_string()),
+            )
+            .unwrap()
+            .expect("conflicting attestations");
+        assert_eq!(proof.nonce_index, 0);
+        assert_eq!(
+            proof.oracle_secret_key.x_only_public_key(SECP256K1).0,
+            oracle.keypair.x_only_public_key().0
+        );
+        proof.verify(SECP256K1).unwrap();
+        assert_eq!(ledger.attestations(announcement).len(), 2);
+        assert_eq!(
+            ledger.fraud_proof(&announcement.oracle_public_key),
+            Some(&proof)
+        );
+
+        let mut forged = proof.clone();
+        forged.oracle_secret_key = SecretKey::new(&mut thread_rng());
+        assert!(forged.verify(SECP256K1).is_err());
+    }
+
+    #[test]
+    fn invalid_attestations_are_rejected() {
+        let oracle = TestOracle::new();
+        let other = TestOracle::new();
+        let mut ledger = AttestationLedger::default();
+
+        // Signed by another oracle, with other nonces.
+        let mut attestation = other.attest("no");
+        attestation.oracle_public_key = oracle.announcement.oracle_public_key;
+        assert!(ledger
+            .record(
+                SECP256K1,
+                &oracle.announcement,
+                attestation,
+                AttestationSource::Oracle,
+            )
+            .is_err());
+        assert!(ledger.attestations(&oracle.announcement).is_empty());
+        assert!(ledger.fraud_proofs().next().is_none());
+    }
+
+    #[test]
+    fn frozen_oracles_survive_a_restart() {
+        let oracle = TestOracle::new();
+        let announcement = &oracle.announcement;
+        let mut state = ManagerState::default();
+        for (outcome, source) in [
+            (
+                "yes",
+                AttestationSource::Peer(SECP256K1.generate_keypair(&mut thread_rng()).1),
+            ),
+            ("no", AttestationSource::Other("relay".to_string())),
+        ] {
+            state
+                .attestation_ledger
+                .record(SECP256K1, announcement, oracle.attest(outcome), source)
+                .unwrap();
+        }
+
+        let restored: ManagerState = Readable::read(&mut Cursor::new(state.encode())).unwrap();
+        assert_eq!(restored.attestation_ledger, state.attestation_ledger);
+        let proof = restored
+            .attestation_ledger
+            .fraud_proof(&announcement.oracle_public_key)
+            .expect("the oracle stays frozen");
+        proof.verify(SECP256K1).unwrap();
+        assert_eq!(
+            restored.attestation_ledger.attestations(announcement).len(),
+            2
+        );
+    }
+}
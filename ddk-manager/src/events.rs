@@ -0,0 +1,242 @@
+//! Events emitted by the [`crate::manager::Manager`] as contracts and channels
+//! change state.
+//!
+//! Subscribe with [`crate::manager::Manager::subscribe`]. Events are delivered
+//! through a [`tokio::sync::broadcast`] channel: a subscriber that falls more
+//! than [`EVENT_CHANNEL_CAPACITY`] events behind misses the oldest ones and
+//! should read the current state from the store.
+
+use bitcoin::{SignedAmount, Txid};
+use secp256k1_zkp::PublicKey;
+
+use crate::channel::signed_channel::SignedChannelStateType;
+use crate::channel::Channel;
+use crate::contract::Contract;
+use crate::equivocation::FraudProof;
+use crate::{ChannelId, ContractId};
+
+/// Number of events kept for subscribers that have not read them yet.
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// A change of state of a contract, a channel or the wallet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A contract offer was received from a counter party.
+    OfferReceived {
+        /// The temporary id of the offered contract.
+        contract_id: ContractId,
+        /// The node that sent the offer.
+        counter_party: PublicKey,
+    },
+    /// A contract offer was accepted by the local party.
+    Accepted {
+        /// The id of the contract.
+        contract_id: ContractId,
+        /// The temporary id the contract had while offered.
+        temporary_contract_id: ContractId,
+        /// The counter party of the contract.
+        counter_party: PublicKey,
+    },
+    /// The contract was signed and its funding transaction can be broadcast.
+    Signed {
+        /// The id of the contract.
+        contract_id: ContractId,
+        /// The temporary id the contract had while offered.
+        temporary_contract_id: ContractId,
+        /// The counter party of the contract.
+        counter_party: PublicKey,
+        /// The funding transaction of the contract.
+        funding_txid: Txid,
+    },
+    /// The funding transaction of the contract reached the required number of
+    /// confirmations.
+    FundingConfirmed {
+        /// The id of the contract.
+        contract_id: ContractId,
+        /// The funding transaction of the contract.
+        funding_txid: Txid,
+    },
+    /// A transaction closing the contract was broadcast or seen on chain. It
+    /// is a CET, a cooperative close transaction or the funding transaction
+    /// of a splice.
+    CetBroadcast {
+        /// The id of the contract.
+        contract_id: ContractId,
+        /// The closing transaction.
+        closing_txid: Txid,
+    },
+    /// The closing transaction of the contract is confirmed.
+    Closed {
+        /// The id of the contract.
+        contract_id: ContractId,
+        /// The closing transaction, if any.
+        closing_txid: Option<Txid>,
+        /// The profit and loss of the local party.
+        pnl: SignedAmount,
+    },
+    /// The contract was refunded.
+    Refunded {
+        /// The id of the contract.
+        contract_id: ContractId,
+        /// The refund transaction.
+        refund_txid: Txid,
+    },
+    /// An offered or accepted contract expired before the counter party
+    /// answered. Its funding inputs are released.
+    OfferExpired {
+        /// The temporary id of the contract.
+        contract_id: ContractId,
+        /// The counter party of the contract.
+        counter_party: PublicKey,
+    },
+    /// A channel was stored in a new state.
+    ChannelStateChanged {
+        /// The id of the channel, temporary while the channel is offered.
+        channel_id: ChannelId,
+        /// The counter party of the channel.
+        counter_party: PublicKey,
+        /// The state of the channel, as returned by [`Channel::state_name`].
+        state: &'static str,
+        /// The state of a signed channel.
+        signed_state: Option<SignedChannelStateType>,
+    },
+    /// An oracle signed two different outcomes of an event with the same
+    /// nonce. Its contracts are not closed anymore until
+    /// [`crate::manager::Manager::unfreeze_oracle`].
+    OracleEquivocation {
+        /// The open contracts depending on the oracle.
+        contract_ids: Vec<ContractId>,
+        /// The proof of the equivocation, with the secret key of the oracle.
+        fraud_proof: Box<FraudProof>,
+    },
+    /// A transaction of the wallet was first seen, or was confirmed.
+    WalletTxReceived {
+        /// The transaction.
+        txid: Txid,
+        /// The height of the block that confirmed it, if any.
+        confirmation_height: Option<u32>,
+    },
+}
+
+impl Event {
+    /// The event for a contract moved to a new state, if the state is one
+    /// that subscribers are notified of.
+    pub fn from_contract(contract: &Contract) -> Option<Event> {
+        let event = match contract {
+            Contract::Accepted(a) => Event::Accepted {
+                contract_id: a.get_contract_id(),
+                temporary_contract_id: a.offered_contract.id,
+                counter_party: a.offered_contract.counter_party,
+            },
+            Contract::Signed(s) => Event::Signed {
+                contract_id: s.accepted_contract.get_contract_id(),
+                temporary_contract_id: s.accepted_contract.offered_contract.id,
+                counter_party: s.accepted_contract.offered_contract.counter_party,
+                funding_txid: s.accepted_contract.dlc_transactions.fund.compute_txid(),
+            },
+            Contract::Confirmed(c) => Event::FundingConfirmed {
+                contract_id: c.accepted_contract.get_contract_id(),
+                funding_txid: c.accepted_contract.dlc_transactions.fund.compute_txid(),
+            },
+            Contract::PreClosed(p) => Event::CetBroadcast {
+                contract_id: p.signed_contract.accepted_contract.get_contract_id(),
+                closing_txid: p.signed_cet.compute_txid(),
+            },
+            Contract::Closed(c) => Event::Closed {
+                contract_id: c.contract_id,
+                closing_txid: c.signed_cet.as_ref().map(|tx| tx.compute_txid()),
+                pnl: c.pnl,
+            },
+            Contract::Refunded(r) => Event::Refunded {
+                contract_id: r.accepted_contract.get_contract_id(),
+                refund_txid: r.accepted_contract.dlc_transactions.refund.compute_txid(),
+            },
+            Contract::Expired(o) => Event::OfferExpired {
+                contract_id: o.id,
+                counter_party: o.counter_party,
+            },
+            Contract::Offered(_)
+            | Contract::Rejected(_)
+            | Contract::FailedAccept(_)
+            | Contract::FailedSign(_) => return None,
+        };
+        Some(event)
+    }
+
+    /// The event for a contract stored in place of `previous`, if the
+    /// contract moved to a state subscribers are notified of. Saving a
+    /// contract again in the same state produces no event.
+    pub(crate) fn from_contract_update(
+        previous: Option<&Contract>,
+        contract: &Contract,
+    ) -> Option<Event> {
+        let event = Event::from_contract(contract)?;
+        (previous.and_then(Event::from_contract).as_ref() != Some(&event)).then_some(event)
+    }
+
+    /// The event for a channel stored in a new state.
+    pub fn from_channel(channel: &Channel) -> Event {
+        Event::ChannelStateChanged {
+            channel_id: channel.get_id(),
+            counter_party: channel.get_counter_party_id(),
+            state: channel.state_name(),
+            signed_state: match channel {
+                Channel::Signed(s) => Some(s.state.get_type()),
+                _ => None,
+            },
+        }
+    }
+
+    /// The event for a channel stored in place of `previous`, if the channel
+    /// changed state. Saving a channel again in the same state produces no
+    /// event.
+    pub(crate) fn from_channel_update(
+        previous: Option<&Channel>,
+        channel: &Channel,
+    ) -> Option<Event> {
+        let event = Event::from_channel(channel);
+        (previous.map(Event::from_channel).as_ref() != Some(&event)).then_some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::ser::Serializable;
+    use crate::contract::signed_contract::SignedContract;
+
+    fn signed_contract() -> SignedContract {
+        let stored = include_bytes!("../../testconfig/contract_binaries/Signed");
+        // The first byte is the prefix of the contract state.
+        SignedContract::deserialize(&mut lightning::io::Cursor::new(&stored[1..])).unwrap()
+    }
+
+    #[test]
+    fn saving_a_contract_again_emits_no_event() {
+        let signed = signed_contract();
+        let offered = Contract::Offered(signed.accepted_contract.offered_contract.clone());
+        let accepted = Contract::Accepted(signed.accepted_contract.clone());
+        let confirmed = Contract::Confirmed(signed.clone());
+        let signed = Contract::Signed(signed);
+
+        assert!(matches!(
+            Event::from_contract_update(Some(&offered), &accepted),
+            Some(Event::Accepted { .. })
+        ));
+        assert!(matches!(
+            Event::from_contract_update(Some(&accepted), &signed),
+            Some(Event::Signed { .. })
+        ));
+        assert!(matches!(
+            Event::from_contract_update(Some(&signed), &confirmed),
+            Some(Event::FundingConfirmed { .. })
+        ));
+        assert!(matches!(
+            Event::from_contract_update(None, &confirmed),
+            Some(Event::FundingConfirmed { .. })
+        ));
+        for contract in [&accepted, &signed, &confirmed] {
+            assert_eq!(Event::from_contract_update(Some(contract), contract), None);
+        }
+    }
+}
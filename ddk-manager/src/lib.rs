@@ -23,6 +23,8 @@
 #[macro_use]
 extern crate ddk_messages;
 
+#[cfg(feature = "manager")]
+pub mod attestation;
 pub mod chain_monitor;
 pub mod channel;
 #[cfg(feature = "manager")]
@@ -31,11 +33,16 @@ pub mod contract;
 pub mod contract_updater;
 mod conversion_utils;
 mod dlc_input;
+pub mod equivocation;
 pub mod error;
 #[cfg(feature = "manager")]
+pub mod events;
+#[cfg(feature = "manager")]
 pub mod manager;
+pub mod manager_state;
 pub mod payout_curve;
 mod utils;
+pub mod watchtower;
 
 use bitcoin::psbt::Psbt;
 use bitcoin::{Address, Amount, Block, OutPoint, ScriptBuf, Transaction, TxOut, Txid};
@@ -49,8 +56,7 @@ use ddk_messages::impl_dlc_writeable;
 use ddk_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
 use ddk_messages::ser_impls::{read_address, write_address};
 use error::Error;
-use lightning::ln::msgs::DecodeError;
-use lightning::util::ser::{Readable, Writeable, Writer};
+use manager_state::ManagerState;
 use secp256k1_zkp::{PublicKey, SecretKey, Signing};
 use secp256k1_zkp::{Secp256k1, XOnlyPublicKey};
 use std::collections::HashMap;
@@ -256,6 +262,8 @@ pub trait Storage {
     async fn delete_channel(&self, channel_id: &ChannelId) -> Result<(), Error>;
     /// Returns the channel with given [`ChannelId`] if any.
     async fn get_channel(&self, channel_id: &ChannelId) -> Result<Option<Channel>, Error>;
+    /// Returns all the channels in the store, whatever their state.
+    async fn get_channels(&self) -> Result<Vec<Channel>, Error>;
     /// Returns the set of [`SignedChannel`] in the store. Returns only the one
     /// with matching `channel_state` if set.
     async fn get_signed_channels(
@@ -268,6 +276,10 @@ pub trait Storage {
     async fn persist_chain_monitor(&self, monitor: &ChainMonitor) -> Result<(), Error>;
     /// Returns the latest [`ChainMonitor`] in the store if any.
     async fn get_chain_monitor(&self) -> Result<Option<ChainMonitor>, Error>;
+    /// Writes the [`ManagerState`] to the store.
+    async fn persist_manager_state(&self, state: &ManagerState) -> Result<(), Error>;
+    /// Returns the latest [`ManagerState`] in the store if any.
+    async fn get_manager_state(&self) -> Result<Option<ManagerState>, Error>;
 }
 
 #[async_trait::async_trait]
@@ -4,6 +4,7 @@ use super::{
     Blockchain, CachedContractSignerProvider, ConfirmationStatus, ContractSigner, Oracle, Storage,
     Time, Wallet,
 };
+use crate::attestation::{AttestationBackoff, AttestationScheduler, OracleHealth};
 use crate::chain_monitor::{ChainMonitor, ChannelInfo, RevokedTxType, TxType};
 use crate::channel::offered_channel::OfferedChannel;
 use crate::channel::signed_channel::{SignedChannel, SignedChannelState, SignedChannelStateType};
@@ -17,22 +18,31 @@ use crate::contract::{
     FailedSignContract, PreClosedContract,
 };
 use crate::contract_updater::{accept_contract, verify_accepted_and_sign_contract};
+use crate::equivocation::{event_key, AttestationSource, FraudProof};
 use crate::error::Error;
+use crate::events::{Event, EVENT_CHANNEL_CAPACITY};
+use crate::manager_state::ManagerState;
 use crate::utils::get_object_in_state;
+use crate::watchtower::{EncryptedJusticeBlob, JusticeData, PunishParams};
 use crate::{ChannelId, ContractId, ContractSignerProvider, CET_NSEQUENCE};
 use bitcoin::absolute::Height;
 use bitcoin::consensus::encode::serialize_hex;
 use bitcoin::consensus::Decodable;
 use bitcoin::hex::DisplayHex;
 use bitcoin::{Address, Amount, SignedAmount};
-use bitcoin::{OutPoint, Transaction};
+use bitcoin::{OutPoint, Psbt, Transaction, Txid};
+use ddk_dlc::DlcTransactions;
+use ddk_messages::batch_msgs::BATCH_FUNDING_GROUP_TYPE;
 use ddk_messages::channel::{
     AcceptChannel, CollaborativeCloseOffer, OfferChannel, Reject, RenewAccept, RenewConfirm,
     RenewFinalize, RenewOffer, RenewRevoke, SettleAccept, SettleConfirm, SettleFinalize,
     SettleOffer, SignChannel,
 };
-use ddk_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
-use ddk_messages::{AcceptDlc, CloseDlc, Message as DlcMessage, OfferDlc, SignDlc};
+use ddk_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation, ATTESTATION_TYPE};
+use ddk_messages::{
+    AcceptDlc, BumpFundingAccept, BumpFundingFee, BumpFundingSign, CloseDlc, Message as DlcMessage,
+    OfferDlc, SignDlc,
+};
 use futures::stream;
 use futures::stream::FuturesUnordered;
 use futures::{StreamExt, TryStreamExt};
@@ -44,14 +54,13 @@ use lightning::util::logger::Logger;
 use lightning::{log_debug, log_error, log_info, log_trace, log_warn};
 use once_cell::sync::Lazy;
 use secp256k1_zkp::XOnlyPublicKey;
-use secp256k1_zkp::{
-    ecdsa::Signature, All, EcdsaAdaptorSignature, PublicKey, Secp256k1, SecretKey,
-};
-use std::collections::HashMap;
+use secp256k1_zkp::{All, EcdsaAdaptorSignature, PublicKey, Secp256k1, SecretKey};
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
 use std::string::ToString;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Instant;
+use tokio::sync::{broadcast, Mutex};
 
 /// The number of confirmations required before moving the the confirmed state.
 pub const DEFAULT_NB_CONFIRMATIONS: u32 = 3;
@@ -74,6 +83,17 @@ pub const REFUND_DELAY: u32 = 86400 * 7;
 /// Timeout in seconds when waiting for a peer's reply, after which a DLC channel
 /// is forced closed.
 pub const PEER_TIMEOUT: u64 = 3600;
+/// Default time in seconds after which a contract offer that was not answered
+/// expires, see [`Manager::set_offer_ttl`].
+pub const OFFER_TTL: u64 = 86400;
+/// Default highest fee rate, in sats per vbyte, paid when the periodic check
+/// bumps the fee of a closing transaction, see
+/// [`Manager::set_max_closing_fee_rate`].
+pub const MAX_CLOSING_FEE_RATE: u64 = 100;
+/// How much higher, in percent, than our own estimate for urgent transactions
+/// the fee rate of a funding fee bump asked for by the counter party can be.
+/// Higher fee rates need to be approved with [`Manager::bump_funding_fee`].
+pub const FUNDING_FEE_BUMP_MARGIN: u64 = 50;
 
 type ClosableContractInfo<'a> = Option<(
     &'a ContractInfo,
@@ -110,11 +130,39 @@ pub struct Manager<
     store: S,
     secp: Secp256k1<All>,
     chain_monitor: Mutex<ChainMonitor>,
+    /// State belonging to no single contract or channel, persisted whenever
+    /// it changes.
+    state: Mutex<ManagerState>,
+    /// When to ask the oracles for attestations, and their health.
+    attestations: Mutex<AttestationScheduler>,
+    /// Highest fee rate the periodic check bumps closing transactions to, or
+    /// `None` to never bump them automatically.
+    max_closing_fee_rate: Mutex<Option<u64>>,
+    /// Accept messages of batch funded contracts we offered, keyed by
+    /// temporary contract id, until one was received for every contract of
+    /// the batch. Not persisted.
+    batch_accepts: Mutex<HashMap<ContractId, AcceptDlc>>,
+    /// Time in seconds after which an offered or accepted contract expires.
+    offer_ttl: Mutex<u64>,
+    /// Messages for counter parties beyond the single reply returned by
+    /// [`Manager::on_dlc_message`], see
+    /// [`Manager::get_and_clear_pending_messages`].
+    pending_messages: Mutex<Vec<(PublicKey, DlcMessage)>>,
+    events: broadcast::Sender<Event>,
     time: T,
     fee_estimator: F,
     logger: L,
 }
 
+/// The address of the fund output of a contract, to be watched by the wallet.
+fn fund_output_address(
+    dlc_transactions: &DlcTransactions,
+    network: bitcoin::Network,
+) -> Result<Address, Error> {
+    Address::from_script(&dlc_transactions.funding_script_pubkey(), network)
+        .map_err(|e| Error::InvalidState(format!("Invalid fund output script: {e}")))
+}
+
 macro_rules! get_contract_in_state {
     ($manager: ident, $contract_id: expr, $state: ident, $peer_id: expr) => {{
         get_object_in_state!(
@@ -215,6 +263,8 @@ where
                 .unwrap_or(ChainMonitor::new(init_height)),
         );
 
+        let state = Mutex::new(store.get_manager_state().await?.unwrap_or_default());
+
         let signer_provider = Arc::new(CachedContractSignerProvider::new(signer_provider));
 
         log_info!(logger, "Manager initialized");
@@ -229,6 +279,13 @@ where
             time,
             fee_estimator,
             chain_monitor,
+            state,
+            attestations: Mutex::new(AttestationScheduler::default()),
+            max_closing_fee_rate: Mutex::new(Some(MAX_CLOSING_FEE_RATE)),
+            batch_accepts: Mutex::new(HashMap::new()),
+            offer_ttl: Mutex::new(OFFER_TTL),
+            pending_messages: Mutex::new(Vec::new()),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
             logger,
         })
     }
@@ -238,12 +295,361 @@ where
         &self.store
     }
 
+    /// Sets the delays between attempts to fetch an attestation that is not
+    /// available yet.
+    pub async fn set_attestation_backoff(&self, backoff: AttestationBackoff) {
+        self.attestations.lock().await.set_backoff(backoff);
+    }
+
+    /// Sets the time in seconds after which a contract offer that was not
+    /// answered expires. Defaults to [`OFFER_TTL`].
+    pub async fn set_offer_ttl(&self, ttl: u64) {
+        *self.offer_ttl.lock().await = ttl;
+    }
+
+    /// Sets the highest fee rate, in sats per vbyte, the periodic check pays
+    /// when bumping the fee of a closing transaction below the current
+    /// estimate. `None` disables the automatic bumps, closing transactions can
+    /// still be bumped with [`Manager::bump_closing_fee`]. Defaults to
+    /// [`MAX_CLOSING_FEE_RATE`].
+    pub async fn set_max_closing_fee_rate(&self, max_fee_rate_per_vb: Option<u64>) {
+        *self.max_closing_fee_rate.lock().await = max_fee_rate_per_vb;
+    }
+
+    /// Returns the fee rate of the last child transaction broadcast to bump
+    /// the fee of a closing transaction, until the closing transaction
+    /// confirms.
+    pub async fn closing_fee_bump(&self, closing_txid: &Txid) -> Option<u64> {
+        self.state.lock().await.fee_bumps.get(closing_txid).copied()
+    }
+
+    /// Returns the request statistics of every oracle asked for an
+    /// attestation since the manager started.
+    pub async fn oracle_health(&self) -> HashMap<XOnlyPublicKey, OracleHealth> {
+        self.attestations.lock().await.health().clone()
+    }
+
+    /// Records an attestation seen outside of the oracle clients of the
+    /// manager, e.g. sent by a peer or read from a Nostr relay.
+    ///
+    /// The attestation is checked against the announcement of an offered,
+    /// signed or confirmed contract. Returns the fraud proof if it conflicts with an attestation
+    /// seen before, in which case [`Event::OracleEquivocation`] is published
+    /// and the contracts of the oracle are frozen.
+    pub async fn record_attestation(
+        &self,
+        attestation: OracleAttestation,
+        source: AttestationSource,
+    ) -> Result<Option<FraudProof>, Error> {
+        let announcement = self
+            .open_contract_infos()
+            .await?
+            .iter()
+            .flat_map(|(_, infos)| infos)
+            .flat_map(|info| &info.oracle_announcements)
+            .find(|announcement| {
+                announcement.oracle_public_key == attestation.oracle_public_key
+                    && announcement.oracle_event.event_id == attestation.event_id
+            })
+            .cloned()
+            .ok_or_else(|| {
+                Error::InvalidParameters(format!(
+                    "No contract depends on the attested event. event_id={}",
+                    attestation.event_id
+                ))
+            })?;
+        self.check_attestation(&announcement, attestation, source)
+            .await
+    }
+
+    /// Returns the fraud proofs of the oracles caught attesting to conflicting
+    /// outcomes and not unfrozen since.
+    pub async fn fraud_proofs(&self) -> Vec<FraudProof> {
+        self.state
+            .lock()
+            .await
+            .attestation_ledger
+            .fraud_proofs()
+            .cloned()
+            .collect()
+    }
+
+    /// Lets contracts of an oracle caught equivocating be closed again, once
+    /// the fraud proof was reviewed. Returns the removed fraud proof.
+    pub async fn unfreeze_oracle(
+        &self,
+        oracle: &XOnlyPublicKey,
+    ) -> Result<Option<FraudProof>, Error> {
+        self.update_state(|state| state.attestation_ledger.clear_fraud_proof(oracle))
+            .await
+    }
+
+    /// Subscribes to the [`Event`]s emitted as contracts and channels change
+    /// state.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+
+    /// Sends an event to the subscribers of the manager. Lets the application
+    /// layer publish events the manager does not observe, such as
+    /// [`Event::WalletTxReceived`].
+    pub fn publish_event(&self, event: Event) {
+        // Sending only fails when nobody is subscribed.
+        let _ = self.events.send(event);
+    }
+
+    /// Applies `update` to the manager state and persists the result.
+    async fn update_state<R>(
+        &self,
+        update: impl FnOnce(&mut ManagerState) -> R,
+    ) -> Result<R, Error> {
+        let mut state = self.state.lock().await;
+        let result = update(&mut state);
+        self.store.persist_manager_state(&state).await?;
+        Ok(result)
+    }
+
+    /// Stores a new offered contract, and when it was offered for it to
+    /// expire `offer_ttl` later, see [`Manager::set_offer_ttl`].
+    async fn create_offered_contract(
+        &self,
+        offered_contract: &OfferedContract,
+    ) -> Result<(), Error> {
+        self.store.create_contract(offered_contract).await?;
+        let now = self.time.unix_time_now();
+        self.update_state(|state| state.offers_seen_at.insert(offered_contract.id, now))
+            .await?;
+        Ok(())
+    }
+
+    /// The ids and contract infos of the offered, signed and confirmed
+    /// contracts, the ones that can still be closed with attestations.
+    /// Accepted contracts are left out as they are signed or dropped right
+    /// after, and only these states are queried so that closing a contract
+    /// does not load the whole store.
+    async fn open_contract_infos(&self) -> Result<Vec<(ContractId, Vec<ContractInfo>)>, Error> {
+        let mut infos = self
+            .store
+            .get_contract_offers()
+            .await?
+            .into_iter()
+            .map(|offered| (offered.id, offered.contract_info))
+            .collect::<Vec<_>>();
+        let signed = self.store.get_signed_contracts().await?;
+        let confirmed = self.store.get_confirmed_contracts().await?;
+        infos.extend(signed.into_iter().chain(confirmed).map(|signed| {
+            (
+                signed.accepted_contract.get_contract_id(),
+                signed.accepted_contract.offered_contract.contract_info,
+            )
+        }));
+        Ok(infos)
+    }
+
+    async fn update_contract(&self, contract: &Contract) -> Result<(), Error> {
+        // Contracts are saved again unchanged, e.g. by the periodic checks,
+        // and subscribers only hear of actual transitions.
+        let previous = self.store.get_contract(&contract.get_id()).await?;
+        self.store.update_contract(contract).await?;
+        // A closing contract no longer needs its attestations.
+        let settled = match contract {
+            Contract::PreClosed(c) => Some(&c.signed_contract),
+            Contract::Closed(c) => Some(&c.signed_contract),
+            Contract::Refunded(c) => Some(c),
+            _ => None,
+        };
+        if let Some(settled) = settled {
+            // Other open contracts can depend on the same events.
+            let needed = self
+                .open_contract_infos()
+                .await?
+                .iter()
+                .flat_map(|(_, infos)| infos)
+                .flat_map(|info| &info.oracle_announcements)
+                .map(event_key)
+                .collect::<HashSet<_>>();
+            let unneeded = settled
+                .accepted_contract
+                .offered_contract
+                .contract_info
+                .iter()
+                .flat_map(|info| &info.oracle_announcements)
+                .filter(|announcement| !needed.contains(&event_key(announcement)))
+                .collect::<Vec<_>>();
+            if !unneeded.is_empty() {
+                let mut attestations = self.attestations.lock().await;
+                for announcement in &unneeded {
+                    attestations.forget(announcement);
+                }
+                drop(attestations);
+                self.update_state(|state| {
+                    for announcement in &unneeded {
+                        state.attestation_ledger.forget(announcement);
+                    }
+                })
+                .await?;
+            }
+        }
+        // A confirmed closing transaction needs no more fee bumps.
+        let closing_txid = match contract {
+            Contract::Closed(ClosedContract {
+                signed_cet: Some(cet),
+                ..
+            }) => Some(cet.compute_txid()),
+            Contract::Refunded(c) => {
+                Some(c.accepted_contract.dlc_transactions.refund.compute_txid())
+            }
+            _ => None,
+        };
+        if let Some(txid) = closing_txid {
+            if self.closing_fee_bump(&txid).await.is_some() {
+                self.update_state(|state| state.fee_bumps.remove(&txid))
+                    .await?;
+            }
+        }
+        if let Some(event) = Event::from_contract_update(previous.as_ref(), contract) {
+            self.publish_event(event);
+        }
+        Ok(())
+    }
+
+    /// Records an attestation in the ledger and reports the oracle if it
+    /// attested to conflicting outcomes.
+    async fn check_attestation(
+        &self,
+        announcement: &OracleAnnouncement,
+        attestation: OracleAttestation,
+        source: AttestationSource,
+    ) -> Result<Option<FraudProof>, Error> {
+        let fraud_proof = self
+            .update_state(|state| {
+                state
+                    .attestation_ledger
+                    .record(&self.secp, announcement, attestation, source)
+            })
+            .await??;
+        if let Some(fraud_proof) = &fraud_proof {
+            let contract_ids = self
+                .open_contract_infos()
+                .await?
+                .into_iter()
+                .filter(|(_, infos)| {
+                    infos.iter().any(|info| {
+                        info.oracle_announcements.iter().any(|announcement| {
+                            announcement.oracle_public_key == fraud_proof.oracle_public_key()
+                        })
+                    })
+                })
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            log_error!(
+                self.logger,
+                "Oracle attested to conflicting outcomes. Freezing its contracts. pubkey={} event_id={} contracts={}",
+                fraud_proof.oracle_public_key(),
+                announcement.oracle_event.event_id,
+                contract_ids.len()
+            );
+            self.publish_event(Event::OracleEquivocation {
+                contract_ids,
+                fraud_proof: Box::new(fraud_proof.clone()),
+            });
+        }
+        Ok(fraud_proof)
+    }
+
+    /// The error for closing a contract with an oracle caught equivocating.
+    async fn check_not_frozen(&self, contract_info: &ContractInfo) -> Result<(), Error> {
+        let state = self.state.lock().await;
+        match contract_info.oracle_announcements.iter().find(|a| {
+            state
+                .attestation_ledger
+                .fraud_proof(&a.oracle_public_key)
+                .is_some()
+        }) {
+            Some(announcement) => Err(Error::OracleError(format!(
+                "Oracle attested to conflicting outcomes, its contracts are frozen. pubkey={}",
+                announcement.oracle_public_key
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    async fn upsert_channel(
+        &self,
+        channel: Channel,
+        contract: Option<Contract>,
+    ) -> Result<(), Error> {
+        // Channels are saved again unchanged, and subscribers only hear of
+        // actual transitions.
+        let previous = self.store.get_channel(&channel.get_id()).await?;
+        let event = Event::from_channel_update(previous.as_ref(), &channel);
+        self.store.upsert_channel(channel, contract).await?;
+        if let Some(event) = event {
+            self.publish_event(event);
+        }
+        Ok(())
+    }
+
     /// Function called to pass a DlcMessage to the Manager.
+    ///
+    /// An [`OracleAttestation`] record in the TLV stream of the message is
+    /// recorded with [`AttestationSource::Peer`], see
+    /// [`Manager::record_attestation`].
     #[tracing::instrument(skip_all)]
     pub async fn on_dlc_message(
         &self,
         msg: &DlcMessage,
         counter_party: PublicKey,
+    ) -> Result<Option<DlcMessage>, Error> {
+        let tlvs = match msg {
+            DlcMessage::Offer(o) => Some(&o.tlvs),
+            DlcMessage::Accept(a) => Some(&a.tlvs),
+            DlcMessage::Sign(s) => Some(&s.tlvs),
+            _ => None,
+        };
+        // Unknown records are carried rather than rejected, even those a
+        // reader is supposed to understand, so that they at least get noticed.
+        for tlv_type in tlvs
+            .iter()
+            .flat_map(|tlvs| tlvs.required_types())
+            .filter(|tlv_type| {
+                ![BATCH_FUNDING_GROUP_TYPE, ATTESTATION_TYPE]
+                    .iter()
+                    .any(|known| u64::from(*known) == *tlv_type)
+            })
+        {
+            log_warn!(
+                self.logger,
+                "Message from {} carries unknown required TLV record type {}",
+                counter_party,
+                tlv_type
+            );
+        }
+        let reply = self.handle_dlc_message(msg, counter_party).await?;
+        // A peer can attach an attestation it saw, e.g. of an event another
+        // contract between the parties depends on, for the oracle to be
+        // checked against the attestations seen before.
+        let attestation = tlvs.and_then(|tlvs| tlvs.get::<OracleAttestation>().ok().flatten());
+        if let Some(attestation) = attestation {
+            if let Err(e) = self
+                .record_attestation(attestation, AttestationSource::Peer(counter_party))
+                .await
+            {
+                log_debug!(
+                    self.logger,
+                    "Did not record attestation from peer. counter_party={} error={}",
+                    counter_party,
+                    e.to_string()
+                );
+            }
+        }
+        Ok(reply)
+    }
+
+    async fn handle_dlc_message(
+        &self,
+        msg: &DlcMessage,
+        counter_party: PublicKey,
     ) -> Result<Option<DlcMessage>, Error> {
         match msg {
             DlcMessage::Offer(o) => {
@@ -253,6 +659,15 @@ where
             }
             DlcMessage::Accept(a) => {
                 log_debug!(self.logger, "Received accept message");
+                if let Some(Contract::Offered(offered_contract)) =
+                    self.store.get_contract(&a.temporary_contract_id).await?
+                {
+                    if offered_contract.is_batch_funded() {
+                        return self
+                            .on_batch_accept_message(a, offered_contract, &counter_party)
+                            .await;
+                    }
+                }
                 Ok(Some(self.on_accept_message(a, &counter_party).await?))
             }
             DlcMessage::Sign(s) => {
@@ -265,6 +680,23 @@ where
                 self.on_close_message(c, &counter_party).await?;
                 Ok(None)
             }
+            DlcMessage::BumpFundingFee(b) => {
+                log_debug!(self.logger, "Received bump funding fee message");
+                Ok(Some(DlcMessage::BumpFundingAccept(
+                    self.on_bump_funding_fee(b, &counter_party).await?,
+                )))
+            }
+            DlcMessage::BumpFundingAccept(b) => {
+                log_debug!(self.logger, "Received bump funding accept message");
+                Ok(Some(DlcMessage::BumpFundingSign(
+                    self.on_bump_funding_accept(b, &counter_party).await?,
+                )))
+            }
+            DlcMessage::BumpFundingSign(b) => {
+                log_debug!(self.logger, "Received bump funding sign message");
+                self.on_bump_funding_sign(b, &counter_party).await?;
+                Ok(None)
+            }
             DlcMessage::OfferChannel(o) => {
                 log_debug!(self.logger, "Received offer channel message");
                 self.on_offer_channel(o, counter_party).await?;
@@ -347,6 +779,14 @@ where
         }
     }
 
+    /// Returns the messages to send to counter parties that were produced
+    /// while handling received messages, on top of the replies returned by
+    /// [`Manager::on_dlc_message`], and clears them. Transports should send
+    /// them after each call to [`Manager::on_dlc_message`].
+    pub async fn get_and_clear_pending_messages(&self) -> Vec<(PublicKey, DlcMessage)> {
+        std::mem::take(&mut *self.pending_messages.lock().await)
+    }
+
     /// Create a new spliced offer
     #[tracing::instrument(skip_all)]
     pub async fn send_splice_offer(
@@ -379,6 +819,16 @@ where
         let confirmed_contract =
             get_contract_in_state!(self, contract_id, Confirmed, Some(counter_party))?;
 
+        if confirmed_contract
+            .accepted_contract
+            .offered_contract
+            .is_taproot()
+        {
+            return Err(Error::InvalidParameters(
+                "Taproot funded contracts cannot be used as DLC inputs".to_string(),
+            ));
+        }
+
         let dlc_input = confirmed_contract.get_dlc_input();
 
         let (offered_contract, offer_msg) = crate::contract_updater::offer_contract(
@@ -398,7 +848,7 @@ where
 
         offered_contract.validate()?;
 
-        self.store.create_contract(&offered_contract).await?;
+        self.create_offered_contract(&offered_contract).await?;
 
         Ok(offer_msg)
     }
@@ -449,7 +899,7 @@ where
 
         offered_contract.validate()?;
 
-        self.store.create_contract(&offered_contract).await?;
+        self.create_offered_contract(&offered_contract).await?;
 
         Ok(offer_msg)
     }
@@ -463,6 +913,12 @@ where
         let offered_contract =
             get_contract_in_state!(self, contract_id, Offered, None as Option<PublicKey>)?;
 
+        if offered_contract.is_batch_funded() {
+            return Err(Error::InvalidParameters(
+                "Batch funded contracts are accepted with accept_batch_contract_offer".to_string(),
+            ));
+        }
+
         let counter_party = offered_contract.counter_party;
 
         let (accepted_contract, accept_msg) = accept_contract(
@@ -475,15 +931,14 @@ where
         )
         .await?;
 
-        self.wallet.import_address(&Address::p2wsh(
-            &accepted_contract.dlc_transactions.funding_witness_script,
+        self.wallet.import_address(&fund_output_address(
+            &accepted_contract.dlc_transactions,
             self.blockchain.get_network()?,
-        ))?;
+        )?)?;
 
         let contract_id = accepted_contract.get_contract_id();
 
-        self.store
-            .update_contract(&Contract::Accepted(accepted_contract))
+        self.update_contract(&Contract::Accepted(accepted_contract))
             .await?;
 
         log_info!(
@@ -496,6 +951,134 @@ where
         Ok((contract_id, counter_party, accept_msg))
     }
 
+    /// Function called to create a batch of DLCs funded by a single fund
+    /// transaction. The offered contracts will be stored and an OfferDlc
+    /// message returned for each of them, all to be sent to the counter party.
+    ///
+    /// The contracts must have the same fee rate, and cannot be Taproot
+    /// funded. This function will fetch the oracle announcements from the
+    /// oracle.
+    #[tracing::instrument(skip_all)]
+    pub async fn send_batch_offer(
+        &self,
+        contract_inputs: &[ContractInput],
+        counter_party: PublicKey,
+    ) -> Result<Vec<OfferDlc>, Error> {
+        let mut oracle_announcements = Vec::with_capacity(contract_inputs.len());
+        for contract_input in contract_inputs {
+            oracle_announcements.push(self.oracle_announcements(contract_input).await?);
+        }
+
+        self.send_batch_offer_with_announcements(
+            contract_inputs,
+            counter_party,
+            oracle_announcements,
+        )
+        .await
+    }
+
+    /// Same as [`Manager::send_batch_offer`], with the oracle announcements
+    /// of each contract passed directly instead of fetched from the oracle.
+    #[tracing::instrument(skip_all)]
+    pub async fn send_batch_offer_with_announcements(
+        &self,
+        contract_inputs: &[ContractInput],
+        counter_party: PublicKey,
+        oracle_announcements: Vec<Vec<Vec<OracleAnnouncement>>>,
+    ) -> Result<Vec<OfferDlc>, Error> {
+        let offers = crate::contract_updater::offer_batch_contracts(
+            &self.secp,
+            contract_inputs,
+            oracle_announcements,
+            REFUND_DELAY,
+            &counter_party,
+            &self.wallet,
+            &self.blockchain,
+            &self.time,
+            &self.signer_provider,
+            &self.logger,
+        )
+        .await?;
+
+        for (offered_contract, _) in &offers {
+            offered_contract.validate()?;
+        }
+
+        let mut offer_msgs = Vec::with_capacity(offers.len());
+        for (offered_contract, offer_msg) in offers {
+            self.create_offered_contract(&offered_contract).await?;
+            offer_msgs.push(offer_msg);
+        }
+
+        log_info!(
+            self.logger,
+            "Created and stored a batch of offered contracts. num_contracts={} counter_party={}",
+            offer_msgs.len(),
+            counter_party,
+        );
+
+        Ok(offer_msgs)
+    }
+
+    /// Function to call to accept a batch of DLCs funded by a single fund
+    /// transaction, given the temporary id of any of them. The offers of all
+    /// the contracts of the batch must have been received.
+    #[tracing::instrument(skip_all)]
+    pub async fn accept_batch_contract_offer(
+        &self,
+        contract_id: &ContractId,
+    ) -> Result<(Vec<ContractId>, PublicKey, Vec<AcceptDlc>), Error> {
+        let offered_contract =
+            get_contract_in_state!(self, contract_id, Offered, None as Option<PublicKey>)?;
+        let group = offered_contract
+            .batch_funding_group
+            .ok_or_else(|| Error::InvalidParameters("Contract is not batch funded".to_string()))?;
+        let counter_party = offered_contract.counter_party;
+
+        let mut offered_contracts = Vec::with_capacity(group.temporary_contract_ids.len());
+        for id in &group.temporary_contract_ids {
+            offered_contracts.push(get_contract_in_state!(
+                self,
+                id,
+                Offered,
+                Some(counter_party)
+            )?);
+        }
+
+        let accepted = crate::contract_updater::accept_batch_contracts(
+            &self.secp,
+            &offered_contracts,
+            &self.wallet,
+            &self.signer_provider,
+            &self.blockchain,
+            &self.logger,
+        )
+        .await?;
+
+        let network = self.blockchain.get_network()?;
+        let mut contract_ids = Vec::with_capacity(accepted.len());
+        let mut accept_msgs = Vec::with_capacity(accepted.len());
+        for (accepted_contract, accept_msg) in accepted {
+            self.wallet.import_address(&fund_output_address(
+                &accepted_contract.dlc_transactions,
+                network,
+            )?)?;
+            contract_ids.push(accepted_contract.get_contract_id());
+            self.update_contract(&Contract::Accepted(accepted_contract))
+                .await?;
+            accept_msgs.push(accept_msg);
+        }
+
+        log_info!(
+            self.logger,
+            "Accepted and stored a batch of contracts. num_contracts={} counter_party={}",
+            contract_ids.len(),
+            counter_party,
+        );
+
+        Ok((contract_ids, counter_party, accept_msgs))
+    }
+
     /// Function to update the state of the [`ChainMonitor`] with new
     /// blocks.
     ///
@@ -526,6 +1109,17 @@ where
         Ok(())
     }
 
+    /// Adds the transactions watched by `chain_monitor`, restored from a
+    /// backup, to the ones watched by the manager and persists the result.
+    ///
+    /// The blocks mined since the backup was written are processed again on
+    /// the next [`Manager::periodic_chain_monitor`].
+    pub async fn restore_chain_monitor(&self, chain_monitor: ChainMonitor) -> Result<(), Error> {
+        let mut current = self.chain_monitor.lock().await;
+        current.merge(chain_monitor);
+        self.store.persist_chain_monitor(&current).await
+    }
+
     /// Function to call to check the state of the currently executing DLCs and
     /// update them if possible.
     #[tracing::instrument(skip_all, level = "debug")]
@@ -534,6 +1128,8 @@ where
         self.check_signed_contracts(&signed_contracts).await?;
         self.check_confirmed_contracts().await?;
         self.check_preclosed_contracts().await?;
+        self.check_closing_fee_bumps().await?;
+        self.check_for_expired_offers().await?;
 
         if check_channels {
             self.channel_checks().await?;
@@ -563,12 +1159,16 @@ where
             ));
         }
 
-        self.store.create_contract(&contract).await?;
+        self.create_offered_contract(&contract).await?;
         log_info!(
             self.logger,
             "Created and stored the offered contract. temp_id={}",
             contract.id.to_lower_hex_string(),
         );
+        self.publish_event(Event::OfferReceived {
+            contract_id: contract.id,
+            counter_party,
+        });
         Ok(())
     }
 
@@ -599,7 +1199,12 @@ where
 
         // Message is valid - the application layer should call accept_cooperative_close()
         // if they want to accept the offered terms
-        Ok(())
+        self.update_state(|state| {
+            state
+                .close_offers
+                .insert(close_msg.contract_id, close_msg.clone());
+        })
+        .await
     }
 
     /// Function to call to accept a DLC for which an offer was received.
@@ -650,16 +1255,12 @@ where
 
         let contract_id = signed_contract.accepted_contract.get_contract_id_string();
 
-        self.wallet.import_address(&Address::p2wsh(
-            &signed_contract
-                .accepted_contract
-                .dlc_transactions
-                .funding_witness_script,
+        self.wallet.import_address(&fund_output_address(
+            &signed_contract.accepted_contract.dlc_transactions,
             self.blockchain.get_network()?,
-        ))?;
+        )?)?;
 
-        self.store
-            .update_contract(&Contract::Signed(signed_contract))
+        self.update_contract(&Contract::Signed(signed_contract))
             .await?;
 
         log_info!(
@@ -672,16 +1273,159 @@ where
         Ok(DlcMessage::Sign(signed_msg))
     }
 
-    /// Function to call to sign a DLC for which an accept was received.
-    #[tracing::instrument(skip_all)]
-    pub async fn on_sign_message(
+    /// Stores the accept message of a batch funded contract we offered. Once
+    /// one was received for every contract of the batch, verifies them all
+    /// and signs the contracts, replying with the sign message of this
+    /// contract and queuing those of the others.
+    async fn on_batch_accept_message(
         &self,
-        sign_message: &SignDlc,
-        peer_id: &PublicKey,
-    ) -> Result<(), Error> {
-        let accepted_contract =
-            get_contract_in_state!(self, &sign_message.contract_id, Accepted, Some(*peer_id))?;
-        let (signed_contract, fund_tx) = match crate::contract_updater::verify_signed_contract(
+        accept_msg: &AcceptDlc,
+        offered_contract: OfferedContract,
+        counter_party: &PublicKey,
+    ) -> Result<Option<DlcMessage>, Error> {
+        if offered_contract.counter_party != *counter_party {
+            return Err(Error::InvalidParameters(format!(
+                "Peer {counter_party} is not involved with the contract."
+            )));
+        }
+        let Some(group) = offered_contract.batch_funding_group else {
+            return Err(Error::InvalidState(
+                "Contract is not batch funded".to_string(),
+            ));
+        };
+
+        let accept_msgs = {
+            let mut batch_accepts = self.batch_accepts.lock().await;
+            batch_accepts.insert(accept_msg.temporary_contract_id, accept_msg.clone());
+            let received = group
+                .temporary_contract_ids
+                .iter()
+                .filter(|id| batch_accepts.contains_key(*id))
+                .count();
+            if received < group.temporary_contract_ids.len() {
+                log_debug!(
+                    self.logger,
+                    "Waiting for the rest of the batch accept messages. received={} num_contracts={}",
+                    received,
+                    group.temporary_contract_ids.len(),
+                );
+                return Ok(None);
+            }
+            group
+                .temporary_contract_ids
+                .iter()
+                .filter_map(|id| batch_accepts.remove(id))
+                .collect::<Vec<_>>()
+        };
+
+        let mut offered_contracts = Vec::with_capacity(accept_msgs.len());
+        for id in &group.temporary_contract_ids {
+            offered_contracts.push(get_contract_in_state!(
+                self,
+                id,
+                Offered,
+                Some(*counter_party)
+            )?);
+        }
+
+        let signed = match crate::contract_updater::verify_batch_accepted_and_sign_contracts(
+            &self.secp,
+            &offered_contracts,
+            &accept_msgs,
+            &self.wallet,
+            &self.signer_provider,
+            &self.store,
+            &self.logger,
+        )
+        .await
+        {
+            Ok(signed) => signed,
+            Err(e) => {
+                log_error!(
+                    self.logger,
+                    "Error in on_batch_accept_message marking contracts as failed accept. num_contracts={} error={}",
+                    offered_contracts.len(),
+                    e.to_string()
+                );
+                for (offered_contract, accept_message) in
+                    offered_contracts.into_iter().zip(accept_msgs)
+                {
+                    self.update_contract(&Contract::FailedAccept(FailedAcceptContract {
+                        offered_contract,
+                        accept_message,
+                        error_message: e.to_string(),
+                    }))
+                    .await?;
+                }
+                return Err(e);
+            }
+        };
+
+        let network = self.blockchain.get_network()?;
+        let mut reply = None;
+        let mut pending = Vec::with_capacity(signed.len());
+        for (signed_contract, sign_msg) in signed {
+            self.wallet.import_address(&fund_output_address(
+                &signed_contract.accepted_contract.dlc_transactions,
+                network,
+            )?)?;
+            let temporary_contract_id = signed_contract.accepted_contract.offered_contract.id;
+            self.update_contract(&Contract::Signed(signed_contract))
+                .await?;
+            if temporary_contract_id == accept_msg.temporary_contract_id {
+                reply = Some(DlcMessage::Sign(sign_msg));
+            } else {
+                pending.push((*counter_party, DlcMessage::Sign(sign_msg)));
+            }
+        }
+        self.pending_messages.lock().await.extend(pending);
+
+        log_info!(
+            self.logger,
+            "Accepted and signed a batch of contracts. num_contracts={} counter_party={}",
+            group.temporary_contract_ids.len(),
+            counter_party,
+        );
+
+        Ok(reply)
+    }
+
+    /// Whether every contract sharing the fund transaction of a signed
+    /// contract is signed, so that the fund transaction can be broadcast.
+    /// Always the case for a contract that is not batch funded.
+    async fn is_batch_signed(&self, contract: &SignedContract) -> Result<bool, Error> {
+        let Some(group) = &contract
+            .accepted_contract
+            .offered_contract
+            .batch_funding_group
+        else {
+            return Ok(true);
+        };
+        let fund_txid = contract
+            .accepted_contract
+            .dlc_transactions
+            .fund
+            .compute_txid();
+        let num_signed = self
+            .store
+            .get_signed_contracts()
+            .await?
+            .iter()
+            .filter(|c| c.accepted_contract.dlc_transactions.fund.compute_txid() == fund_txid)
+            .count();
+        Ok(num_signed == group.temporary_contract_ids.len())
+    }
+
+    /// Function to call to sign a DLC for which an accept was received.
+    #[tracing::instrument(skip_all)]
+    pub async fn on_sign_message(
+        &self,
+        sign_message: &SignDlc,
+        peer_id: &PublicKey,
+    ) -> Result<(), Error> {
+        let accepted_contract =
+            get_contract_in_state!(self, &sign_message.contract_id, Accepted, Some(*peer_id))?;
+        let (signed_contract, fund_tx) = match crate::contract_updater::verify_signed_contract(
             &self.secp,
             &accepted_contract,
             sign_message,
@@ -706,10 +1450,19 @@ where
             }
         };
 
-        self.store
-            .update_contract(&Contract::Signed(signed_contract.clone()))
+        self.update_contract(&Contract::Signed(signed_contract.clone()))
             .await?;
 
+        // A batch fund transaction is broadcast once all its contracts are signed.
+        if !self.is_batch_signed(&signed_contract).await? {
+            log_debug!(
+                self.logger,
+                "Waiting for the rest of the batch sign messages. contract_id={}",
+                signed_contract.accepted_contract.get_contract_id_string(),
+            );
+            return Ok(());
+        }
+
         self.blockchain.send_transaction(&fund_tx).await?;
 
         // Check if there are any DLC inputs in the funding inputs of the contract.
@@ -750,8 +1503,7 @@ where
                 preclosed_contract.signed_contract.accepted_contract.get_contract_id_string()
             );
 
-            self.store
-                .update_contract(&Contract::PreClosed(preclosed_contract.clone()))
+            self.update_contract(&Contract::PreClosed(preclosed_contract.clone()))
                 .await?;
         }
 
@@ -788,13 +1540,12 @@ where
             accepted_contract.get_contract_id_string(),
             e.to_string()
         );
-        self.store
-            .update_contract(&Contract::FailedSign(FailedSignContract {
-                accepted_contract,
-                sign_message,
-                error_message: e.to_string(),
-            }))
-            .await?;
+        self.update_contract(&Contract::FailedSign(FailedSignContract {
+            accepted_contract,
+            sign_message,
+            error_message: e.to_string(),
+        }))
+        .await?;
         Err(e)
     }
 
@@ -810,13 +1561,12 @@ where
             offered_contract.id.to_lower_hex_string(),
             e.to_string()
         );
-        self.store
-            .update_contract(&Contract::FailedAccept(FailedAcceptContract {
-                offered_contract,
-                accept_message,
-                error_message: e.to_string(),
-            }))
-            .await?;
+        self.update_contract(&Contract::FailedAccept(FailedAcceptContract {
+            offered_contract,
+            accept_message,
+            error_message: e.to_string(),
+        }))
+        .await?;
         Err(e)
     }
 
@@ -847,9 +1597,9 @@ where
                 confirmations,
                 contract.accepted_contract.get_contract_id_string(),
             );
-            self.store
-                .update_contract(&Contract::Confirmed(contract.clone()))
+            self.update_contract(&Contract::Confirmed(contract.clone()))
                 .await?;
+            self.delete_replaced_contracts(contract).await?;
         } else {
             log_debug!(self.logger,
                 "Not enough confirmations to mark contract as confirmed. confirmations={} required={} contract_id={}", 
@@ -955,8 +1705,7 @@ where
                 contract_id.to_lower_hex_string(),
             );
 
-            self.store
-                .update_contract(&Contract::PreClosed(preclosed_contract.clone()))
+            self.update_contract(&Contract::PreClosed(preclosed_contract.clone()))
                 .await?;
         }
         Ok(contracts)
@@ -995,6 +1744,15 @@ where
                 "Checking contract for oracle maturation. contract_id={}",
                 contract.accepted_contract.get_contract_id_string()
             );
+            if let Err(e) = self.check_not_frozen(contract_info).await {
+                log_warn!(
+                    self.logger,
+                    "Not closing contract. contract_id={} error={}",
+                    contract.accepted_contract.get_contract_id_string(),
+                    e.to_string()
+                );
+                return None;
+            }
             let matured: Vec<_> = contract_info
                 .oracle_announcements
                 .iter()
@@ -1011,6 +1769,18 @@ where
                             contract.accepted_contract.get_contract_id_string(),
                             announcement.oracle_event.event_id
                         );
+                        // Use the attestation if it was already found, and don't ask the
+                        // oracle again before the backoff after a failed request passed.
+                        let now = self.time.unix_time_now();
+                        {
+                            let scheduler = self.attestations.lock().await;
+                            if let Some(attestation) = scheduler.attestation(announcement) {
+                                return Some((*i, attestation.clone()));
+                            }
+                            if !scheduler.is_due(announcement, now) {
+                                return None;
+                            }
+                        }
                         // First try to get the oracle
                         let oracle = match self.oracles.get(&announcement.oracle_public_key) {
                             Some(oracle) => oracle,
@@ -1025,44 +1795,84 @@ where
                             }
                         };
                         // Then try to get the attestation
-                        let attestation = match oracle
+                        let started = Instant::now();
+                        let attestation = oracle
                             .get_attestation(&announcement.oracle_event.event_id)
                             .await
-                        {
-                            Ok(attestation) => attestation,
-                            Err(_) => {
-                                // log_error!(self.logger,
-                                //     "Attestation not found for event. pubkey={} event_id={} error={}",
-                                //     announcement.oracle_public_key,
-                                //     announcement.oracle_event.event_id,
-                                //     e.to_string()
-                                // );
+                            .and_then(|attestation| {
+                                // Validate the attestation
+                                attestation
+                                    .validate(&self.secp, announcement)
+                                    .map(|_| attestation)
+                                    .map_err(|e| {
+                                        log_error!(self.logger,
+                                            "Oracle attestation is not valid. pubkey={} event_id={}, error={}",
+                                            announcement.oracle_public_key,
+                                            announcement.oracle_event.event_id,
+                                            e.to_string()
+                                        );
+                                        Error::OracleError(format!("Invalid attestation: {e}"))
+                                    })
+                            });
+                        let latency = started.elapsed();
+                        let attestation = match attestation {
+                            Ok(attestation) => {
+                                self.attestations.lock().await.record_success(
+                                    announcement,
+                                    attestation.clone(),
+                                    latency,
+                                    now,
+                                );
+                                attestation
+                            }
+                            Err(e) => {
+                                log_debug!(self.logger,
+                                    "Attestation not found for event. pubkey={} event_id={} error={}",
+                                    announcement.oracle_public_key,
+                                    announcement.oracle_event.event_id,
+                                    e.to_string()
+                                );
+                                self.attestations.lock().await.record_failure(
+                                    announcement,
+                                    e.to_string(),
+                                    latency,
+                                    now,
+                                );
                                 return None;
                             }
                         };
-                        // Validate the attestation
-                        if let Err(e) = attestation.validate(&self.secp, announcement) {
-                            log_error!(self.logger,
-                                "Oracle attestation is not valid. pubkey={} event_id={}, error={}",
-                                announcement.oracle_public_key,
-                                announcement.oracle_event.event_id,
-                                e.to_string()
-                            );
-                            return None;
-                        }
                         log_info!(self.logger,
                             "Retrieved a valid attestation. pubkey={} event_id={} outcomes={:?}", 
                             announcement.oracle_public_key,
                             announcement.oracle_event.event_id,
                             attestation.outcomes
                         );
-                        Some((*i, attestation))
+                        match self
+                            .check_attestation(announcement, attestation.clone(), AttestationSource::Oracle)
+                            .await
+                        {
+                            Ok(None) => Some((*i, attestation)),
+                            Ok(Some(_)) => None,
+                            Err(e) => {
+                                log_error!(self.logger,
+                                    "Could not record attestation. pubkey={} event_id={} error={}",
+                                    announcement.oracle_public_key,
+                                    announcement.oracle_event.event_id,
+                                    e.to_string()
+                                );
+                                None
+                            }
+                        }
                     })
                     .collect::<FuturesUnordered<_>>()
                     .await
                     .filter_map(|result| async move { result }) // Filter out None values
                     .collect::<Vec<_>>()
                     .await;
+                // One of the oracles may have been caught equivocating meanwhile.
+                if self.check_not_frozen(contract_info).await.is_err() {
+                    return None;
+                }
                 if attestations.len() >= contract_info.threshold {
                     log_info!(self.logger,
                         "Found enough attestations to close contract. contract_id={} attestations={}", 
@@ -1127,7 +1937,7 @@ where
                             "Updated contract to closed. contract_id={}",
                             contract.accepted_contract.get_contract_id_string()
                         );
-                        self.store.update_contract(&closed_contract).await?;
+                        self.update_contract(&closed_contract).await?;
                         return Ok(());
                     }
                     Err(e) => {
@@ -1196,8 +2006,7 @@ where
                     signed_contract: contract.clone(),
                 };
 
-                self.store
-                    .update_contract(&Contract::Closed(closed_contract))
+                self.update_contract(&Contract::Closed(closed_contract))
                     .await?;
                 break; // Only one close can be confirmed
             } else if confirmations >= 1 {
@@ -1213,8 +2022,7 @@ where
                     signed_cet: pending_close_tx.clone(),
                 };
 
-                self.store
-                    .update_contract(&Contract::PreClosed(preclosed_contract))
+                self.update_contract(&Contract::PreClosed(preclosed_contract))
                     .await?;
                 break; // Only one close can be confirmed
             }
@@ -1258,6 +2066,17 @@ where
                 matches >= c.threshold
             })
         {
+            for (i, attestation) in &attestations {
+                let announcement = &contract_info.oracle_announcements[*i];
+                self.check_attestation(
+                    announcement,
+                    attestation.clone(),
+                    AttestationSource::Manual,
+                )
+                .await?;
+            }
+            self.check_not_frozen(contract_info).await?;
+
             let offer = &contract.accepted_contract.offered_contract;
             let signer = self.signer_provider.derive_contract_signer(offer.keys_id)?;
             log_debug!(
@@ -1303,7 +2122,7 @@ where
                         "Closed contract manually. contract_id={}",
                         contract.accepted_contract.get_contract_id_string()
                     );
-                    self.store.update_contract(&closed_contract).await?;
+                    self.update_contract(&closed_contract).await?;
                     Ok(closed_contract)
                 }
                 Err(e) => {
@@ -1339,6 +2158,148 @@ where
         Ok(())
     }
 
+    /// Expires the offered and accepted contracts that were not answered
+    /// within the offer TTL, releasing their funding inputs and queuing a
+    /// [`Reject`] for the counter party. Contracts of channels are left to the
+    /// channel timeouts.
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn check_for_expired_offers(&self) -> Result<(), Error> {
+        let mut channel_contract_ids = self
+            .store
+            .get_offered_channels()
+            .await?
+            .iter()
+            .map(|c| c.offered_contract_id)
+            .collect::<HashSet<_>>();
+        channel_contract_ids.extend(
+            self.store
+                .get_signed_channels(None)
+                .await?
+                .iter()
+                .filter_map(|c| c.get_contract_id()),
+        );
+
+        let pending = self
+            .store
+            .get_contracts()
+            .await?
+            .into_iter()
+            .filter(|c| matches!(c, Contract::Offered(_) | Contract::Accepted(_)))
+            .filter(|c| !channel_contract_ids.contains(&c.get_id()))
+            .collect::<Vec<_>>();
+
+        let now = self.time.unix_time_now();
+        let ttl = *self.offer_ttl.lock().await;
+        let pending_ids = pending
+            .iter()
+            .map(|c| c.get_temporary_id())
+            .collect::<HashSet<_>>();
+        let offers_seen_at = self.state.lock().await.offers_seen_at.clone();
+        let stale = offers_seen_at.len() != pending_ids.len()
+            || pending_ids
+                .iter()
+                .any(|id| !offers_seen_at.contains_key(id));
+        // Forget the answered offers, and start the clock of the offers
+        // stored before their time was kept.
+        let offers_seen_at = if !stale {
+            offers_seen_at
+        } else {
+            self.update_state(|state| {
+                state
+                    .offers_seen_at
+                    .retain(|id, _| pending_ids.contains(id));
+                for id in &pending_ids {
+                    state.offers_seen_at.entry(*id).or_insert(now);
+                }
+                state.offers_seen_at.clone()
+            })
+            .await?
+        };
+        let expired = pending
+            .into_iter()
+            .filter(|c| {
+                offers_seen_at
+                    .get(&c.get_temporary_id())
+                    .is_some_and(|seen_at| now >= seen_at.saturating_add(ttl))
+            })
+            .collect::<Vec<_>>();
+
+        for contract in expired {
+            let temporary_contract_id = contract.get_temporary_id();
+            let counter_party = contract.get_counter_party_id();
+            match self.release_offer(contract).await {
+                Ok(offered_contract) => {
+                    self.update_contract(&Contract::Expired(offered_contract))
+                        .await?;
+                    self.update_state(|state| state.offers_seen_at.remove(&temporary_contract_id))
+                        .await?;
+                    self.pending_messages.lock().await.push((
+                        counter_party,
+                        DlcMessage::Reject(Reject {
+                            channel_id: temporary_contract_id,
+                        }),
+                    ));
+                    log_info!(
+                        self.logger,
+                        "Contract offer expired. temp_id={} counter_party={}",
+                        temporary_contract_id.to_lower_hex_string(),
+                        counter_party,
+                    );
+                }
+                Err(e) => log_error!(
+                    self.logger,
+                    "Could not expire contract offer. temp_id={} error={}",
+                    temporary_contract_id.to_lower_hex_string(),
+                    e
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unreserves the funding inputs of an offered or accepted contract that
+    /// will not be signed, and removes an accepted contract stored under its
+    /// final id. Returns the offer of the contract.
+    async fn release_offer(&self, contract: Contract) -> Result<OfferedContract, Error> {
+        let (offered_contract, own_funding_inputs) = match contract {
+            Contract::Offered(o) if o.is_offer_party => {
+                let funding_inputs = o.funding_inputs.clone();
+                (o, funding_inputs)
+            }
+            Contract::Offered(o) => (o, Vec::new()),
+            Contract::Accepted(a) => {
+                self.store.delete_contract(&a.get_contract_id()).await?;
+                (a.offered_contract, a.funding_inputs)
+            }
+            c => {
+                return Err(Error::InvalidState(format!(
+                    "Only offered or accepted contracts can be released. state={c:?}"
+                )))
+            }
+        };
+
+        let outpoints = own_funding_inputs
+            .iter()
+            .map(|funding_input| {
+                let txid = Transaction::consensus_decode(&mut funding_input.prev_tx.as_slice())
+                    .map_err(|_| {
+                        Error::InvalidParameters("Invalid funding input transaction".to_string())
+                    })?
+                    .compute_txid();
+                Ok(OutPoint {
+                    txid,
+                    vout: funding_input.prev_tx_vout,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        if !outpoints.is_empty() {
+            self.wallet.unreserve_utxos(&outpoints)?;
+        }
+
+        Ok(offered_contract)
+    }
+
     #[tracing::instrument(skip_all, level = "debug")]
     async fn check_preclosed_contract(&self, contract: &PreClosedContract) -> Result<(), Error> {
         let broadcasted_txid = contract.signed_cet.compute_txid();
@@ -1379,6 +2340,22 @@ where
             }
             return Ok(());
         }
+        if status == ConfirmationStatus::InMempool {
+            if let Err(e) = self
+                .bump_closing_fee_if_needed(
+                    &contract.signed_cet,
+                    &contract.signed_contract.accepted_contract.dlc_transactions,
+                )
+                .await
+            {
+                log_warn!(self.logger,
+                    "Could not bump the fee of the closing transaction. close_txid={} contract_id={} error={}",
+                    broadcasted_txid.to_string(),
+                    contract.signed_contract.accepted_contract.get_contract_id_string(),
+                    e.to_string(),
+                );
+            }
+        }
         let confirmations = status.confirmations();
         log_debug!(
             self.logger,
@@ -1428,8 +2405,7 @@ where
                 pnl,
                 signed_contract: contract.signed_contract.clone(),
             };
-            self.store
-                .update_contract(&Contract::Closed(closed_contract))
+            self.update_contract(&Contract::Closed(closed_contract))
                 .await?;
         }
 
@@ -1553,7 +2529,7 @@ where
             }
 
             let refunded = Contract::Refunded(contract.clone());
-            self.store.update_contract(&refunded).await?;
+            self.update_contract(&refunded).await?;
             Ok(refunded)
         } else {
             return Err(Error::InvalidParameters(
@@ -1562,38 +2538,547 @@ where
         }
     }
 
-    #[tracing::instrument(skip_all, level = "debug")]
-    async fn check_refund(&self, contract: &SignedContract) -> Result<(), Error> {
-        if contract
+    #[tracing::instrument(skip_all, level = "debug")]
+    async fn check_refund(&self, contract: &SignedContract) -> Result<(), Error> {
+        if contract
+            .accepted_contract
+            .dlc_transactions
+            .refund
+            .lock_time
+            .to_consensus_u32() as u64
+            <= self.time.unix_time_now()
+        {
+            let refund_txid = contract
+                .accepted_contract
+                .dlc_transactions
+                .refund
+                .compute_txid();
+            let confirmations = self
+                .blockchain
+                .get_transaction_confirmations(&refund_txid)
+                .await?
+                .confirmations();
+
+            if confirmations > 0 {
+                // Counterparty (or we) already broadcast the refund tx. Update state.
+                self.update_contract(&Contract::Refunded(contract.clone()))
+                    .await?;
+            } else if *AUTOMATIC_REFUND {
+                self.check_and_broadcast_refund(&contract.accepted_contract.get_contract_id())
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the stuck closing transaction of a contract confirmed by
+    /// broadcasting a child transaction that spends its anchor output and
+    /// pays for both at `fee_rate_per_vb`. The closing transaction is the CET
+    /// of a pre-closed contract or the refund transaction of a refunded one,
+    /// and the contract must be set up with [`ddk_dlc::ANCHOR_OUTPUTS_FLAG`].
+    /// Bumping again with a higher fee rate replaces the previous child.
+    ///
+    /// Returns the broadcast child transaction.
+    #[tracing::instrument(skip_all, level = "debug")]
+    pub async fn bump_closing_fee(
+        &self,
+        contract_id: &ContractId,
+        fee_rate_per_vb: u64,
+    ) -> Result<Transaction, Error> {
+        let contract =
+            self.store
+                .get_contract(contract_id)
+                .await?
+                .ok_or(Error::InvalidParameters(format!(
+                    "Unknown contract id {}",
+                    contract_id.to_lower_hex_string()
+                )))?;
+        let (closing_tx, dlc_transactions) = match &contract {
+            Contract::PreClosed(c) => (
+                c.signed_cet.clone(),
+                &c.signed_contract.accepted_contract.dlc_transactions,
+            ),
+            Contract::Refunded(c) => {
+                let signer = self
+                    .signer_provider
+                    .derive_contract_signer(c.accepted_contract.offered_contract.keys_id)?;
+                let refund = crate::contract_updater::get_signed_refund(
+                    &self.secp,
+                    c,
+                    &signer,
+                    &self.logger,
+                )?;
+                (refund, &c.accepted_contract.dlc_transactions)
+            }
+            _ => return Err(Error::InvalidState(
+                "Only the closing transaction of a pre-closed or refunded contract can be bumped."
+                    .to_string(),
+            )),
+        };
+
+        let status = self
+            .blockchain
+            .get_transaction_confirmations(&closing_tx.compute_txid())
+            .await?;
+        if status != ConfirmationStatus::InMempool {
+            return Err(Error::InvalidState(
+                "Closing transaction is not waiting in the mempool.".to_string(),
+            ));
+        }
+
+        self.broadcast_cpfp(&closing_tx, dlc_transactions, fee_rate_per_vb)
+            .await
+    }
+
+    /// Funds, signs and broadcasts a child transaction spending the anchor
+    /// output of `parent`, a closing transaction spending the fund output.
+    async fn broadcast_cpfp(
+        &self,
+        parent: &Transaction,
+        dlc_transactions: &DlcTransactions,
+        fee_rate_per_vb: u64,
+    ) -> Result<Transaction, Error> {
+        let parent_txid = parent.compute_txid();
+        let (_, anchor_output) = ddk_dlc::anchor::get_anchor_output(parent).ok_or(
+            Error::InvalidState("Closing transaction has no anchor output.".to_string()),
+        )?;
+        let anchor_output = anchor_output.clone();
+        let parent_fee = dlc_transactions
+            .get_fund_output()
+            .value
+            .checked_sub(parent.output.iter().map(|o| o.value).sum())
+            .ok_or(Error::InvalidState(
+                "Closing transaction spends more than the fund output.".to_string(),
+            ))?;
+
+        let change_spk = self.wallet.get_new_change_address().await?.script_pubkey();
+        let funding_amount = ddk_dlc::anchor::get_cpfp_funding_amount(
+            parent,
+            parent_fee,
+            &change_spk,
+            fee_rate_per_vb,
+        )?;
+        let utxos = self
+            .wallet
+            .get_utxos_for_amount(funding_amount, fee_rate_per_vb, true)
+            .await?;
+        let outpoints = utxos.iter().map(|u| u.outpoint).collect::<Vec<_>>();
+
+        let child = async {
+            // Assumes P2WPKH inputs with low R signatures, like the funding
+            // inputs.
+            let inputs = utxos
+                .iter()
+                .map(|utxo| ddk_dlc::TxInputInfo {
+                    outpoint: utxo.outpoint,
+                    max_witness_len: 108,
+                    redeem_script: utxo.redeem_script.clone(),
+                    serial_id: 0,
+                })
+                .collect::<Vec<_>>();
+            let input_amount = utxos.iter().map(|u| u.tx_out.value).sum();
+            let unsigned = ddk_dlc::anchor::create_cpfp_transaction(
+                parent,
+                parent_fee,
+                &inputs,
+                input_amount,
+                &change_spk,
+                fee_rate_per_vb,
+            )?;
+
+            let mut psbt = Psbt::from_unsigned_tx(unsigned.clone()).map_err(|_| {
+                Error::InvalidState("Tried to create PSBT from signed tx".to_string())
+            })?;
+            psbt.inputs[0].witness_utxo = Some(anchor_output);
+            for (i, utxo) in utxos.iter().enumerate() {
+                psbt.inputs[i + 1].witness_utxo = Some(utxo.tx_out.clone());
+            }
+
+            let mut child = unsigned;
+            for input_index in 1..child.input.len() {
+                self.wallet.sign_psbt_input(&mut psbt, input_index).await?;
+                child.input[input_index].witness = psbt.inputs[input_index]
+                    .final_script_witness
+                    .clone()
+                    .ok_or(Error::InvalidParameters(
+                        "No witness from signing psbt input".to_string(),
+                    ))?;
+            }
+
+            self.blockchain.send_transaction(&child).await?;
+            Ok::<_, Error>(child)
+        }
+        .await;
+
+        let child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                self.wallet.unreserve_utxos(&outpoints)?;
+                return Err(e);
+            }
+        };
+
+        log_info!(
+            self.logger,
+            "Broadcast child transaction to bump the fee of a closing transaction. txid={} child_txid={} fee_rate={}",
+            parent_txid.to_string(),
+            child.compute_txid().to_string(),
+            fee_rate_per_vb
+        );
+        self.update_state(|state| state.fee_bumps.insert(parent_txid, fee_rate_per_vb))
+            .await?;
+
+        Ok(child)
+    }
+
+    /// Forgets the fee bumps of closing transactions that confirmed.
+    ///
+    /// A refund is only bumped once its contract is `Refunded`, a terminal
+    /// state that no periodic check examines again, so its bump is dropped
+    /// here rather than on a state change.
+    async fn check_closing_fee_bumps(&self) -> Result<(), Error> {
+        let txids = self
+            .state
+            .lock()
+            .await
+            .fee_bumps
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        let mut confirmed = Vec::new();
+        for txid in txids {
+            let confirmations = self
+                .blockchain
+                .get_transaction_confirmations(&txid)
+                .await?
+                .confirmations();
+            if confirmations > 0 {
+                confirmed.push(txid);
+            }
+        }
+        if !confirmed.is_empty() {
+            self.update_state(|state| {
+                for txid in &confirmed {
+                    state.fee_bumps.remove(txid);
+                }
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Bumps the fee of a closing transaction waiting in the mempool when its
+    /// fee rate, or the one of its last bump, is below the current estimate.
+    async fn bump_closing_fee_if_needed(
+        &self,
+        closing_tx: &Transaction,
+        dlc_transactions: &DlcTransactions,
+    ) -> Result<(), Error> {
+        if ddk_dlc::anchor::get_anchor_output(closing_tx).is_none() {
+            return Ok(());
+        }
+        let Some(max_fee_rate) = *self.max_closing_fee_rate.lock().await else {
+            return Ok(());
+        };
+        let estimate: u64 = (self.fee_estimator.get_est_sat_per_1000_weight(
+            lightning::chain::chaininterface::ConfirmationTarget::UrgentOnChainSweep,
+        ) / 250)
+            .into();
+        let target_fee_rate = estimate.min(max_fee_rate);
+        let txid = closing_tx.compute_txid();
+        let closing_fee = dlc_transactions
+            .get_fund_output()
+            .value
+            .checked_sub(closing_tx.output.iter().map(|o| o.value).sum())
+            .unwrap_or(Amount::ZERO);
+        let closing_fee_rate = closing_fee.to_sat() / closing_tx.vsize() as u64;
+        let current_fee_rate = self
+            .closing_fee_bump(&txid)
+            .await
+            .unwrap_or(closing_fee_rate);
+        if target_fee_rate <= current_fee_rate {
+            return Ok(());
+        }
+
+        self.broadcast_cpfp(closing_tx, dlc_transactions, target_fee_rate)
+            .await?;
+        Ok(())
+    }
+
+    /// Replaces the fund transaction of a signed contract, stuck in the
+    /// mempool, with one spending the same inputs at `fee_rate_per_vb`. Both
+    /// parties pay their share of the higher fee out of their change.
+    ///
+    /// The contract id and the CETs depend on the fund transaction, so the
+    /// parties sign the transactions of a new contract, in the same order as
+    /// when the contract was set up. Returns the message to send to the
+    /// counter party: a [`BumpFundingFee`] request from the offer party, or
+    /// the [`BumpFundingAccept`] signatures from the accept party. The
+    /// replaced contract stays signed until one of the fund transactions
+    /// confirms, and is then deleted.
+    ///
+    /// The counter party can ask for a bump too. Its fee rate is accepted up
+    /// to [`FUNDING_FEE_BUMP_MARGIN`] percent above our own estimate, a
+    /// higher one is approved by calling this function with it.
+    #[tracing::instrument(skip_all, level = "debug")]
+    pub async fn bump_funding_fee(
+        &self,
+        contract_id: &ContractId,
+        fee_rate_per_vb: u64,
+    ) -> Result<(DlcMessage, PublicKey), Error> {
+        let contract =
+            get_contract_in_state!(self, contract_id, Signed, None as Option<PublicKey>)?;
+        self.check_funding_unconfirmed(&contract).await?;
+        crate::contract_updater::get_bumped_funding_transactions(&contract, fee_rate_per_vb)?;
+
+        let offered_contract = &contract.accepted_contract.offered_contract;
+        let msg = if offered_contract.is_offer_party {
+            self.update_state(|state| {
+                state
+                    .funding_fee_requests
+                    .insert(*contract_id, fee_rate_per_vb)
+            })
+            .await?;
+            DlcMessage::BumpFundingFee(BumpFundingFee {
+                protocol_version: crate::conversion_utils::PROTOCOL_VERSION,
+                contract_id: *contract_id,
+                fee_rate_per_vb,
+            })
+        } else {
+            DlcMessage::BumpFundingAccept(
+                self.accept_funding_fee_bump(&contract, fee_rate_per_vb)
+                    .await?,
+            )
+        };
+
+        Ok((msg, offered_contract.counter_party))
+    }
+
+    async fn on_bump_funding_fee(
+        &self,
+        bump_msg: &BumpFundingFee,
+        peer_id: &PublicKey,
+    ) -> Result<BumpFundingAccept, Error> {
+        let contract = get_contract_in_state!(self, &bump_msg.contract_id, Signed, Some(*peer_id))?;
+        if contract.accepted_contract.offered_contract.is_offer_party {
+            return Err(Error::InvalidParameters(
+                "Only the offer party can ask for a funding fee bump".to_string(),
+            ));
+        }
+        self.check_funding_unconfirmed(&contract).await?;
+        self.check_peer_funding_fee_rate(&bump_msg.contract_id, bump_msg.fee_rate_per_vb)
+            .await?;
+        self.accept_funding_fee_bump(&contract, bump_msg.fee_rate_per_vb)
+            .await
+    }
+
+    /// Checks that a fee rate asked for by the counter party is one we asked
+    /// for, or is close enough to our own estimate.
+    async fn check_peer_funding_fee_rate(
+        &self,
+        contract_id: &ContractId,
+        fee_rate_per_vb: u64,
+    ) -> Result<(), Error> {
+        let requested = self
+            .state
+            .lock()
+            .await
+            .funding_fee_requests
+            .get(contract_id)
+            .copied();
+        if requested == Some(fee_rate_per_vb) {
+            return Ok(());
+        }
+        let estimate: u64 = (self.fee_estimator.get_est_sat_per_1000_weight(
+            lightning::chain::chaininterface::ConfirmationTarget::UrgentOnChainSweep,
+        ) / 250)
+            .into();
+        let max_fee_rate = estimate + estimate * FUNDING_FEE_BUMP_MARGIN / 100;
+        if fee_rate_per_vb > max_fee_rate {
+            return Err(Error::InvalidParameters(format!(
+                "Funding fee rate asked for by the counter party is too high, approve it with bump_funding_fee. fee_rate={} max_fee_rate={} contract_id={}",
+                fee_rate_per_vb,
+                max_fee_rate,
+                contract_id.to_lower_hex_string()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn accept_funding_fee_bump(
+        &self,
+        contract: &SignedContract,
+        fee_rate_per_vb: u64,
+    ) -> Result<BumpFundingAccept, Error> {
+        let (bumped_contract, accept_msg) = crate::contract_updater::accept_funding_fee_bump(
+            &self.secp,
+            contract,
+            fee_rate_per_vb,
+            &self.signer_provider,
+            &self.logger,
+        )?;
+        self.update_state(|state| {
+            state.funding_fee_bumps.insert(
+                contract.accepted_contract.get_contract_id(),
+                bumped_contract,
+            )
+        })
+        .await?;
+        Ok(accept_msg)
+    }
+
+    async fn on_bump_funding_accept(
+        &self,
+        accept_msg: &BumpFundingAccept,
+        peer_id: &PublicKey,
+    ) -> Result<BumpFundingSign, Error> {
+        let contract =
+            get_contract_in_state!(self, &accept_msg.contract_id, Signed, Some(*peer_id))?;
+        if !contract.accepted_contract.offered_contract.is_offer_party {
+            return Err(Error::InvalidParameters(
+                "Only the accept party can accept a funding fee bump".to_string(),
+            ));
+        }
+        self.check_funding_unconfirmed(&contract).await?;
+        self.check_peer_funding_fee_rate(&accept_msg.contract_id, accept_msg.fee_rate_per_vb)
+            .await?;
+
+        let (bumped_contract, sign_msg) =
+            crate::contract_updater::verify_funding_fee_bump_accept_and_sign(
+                &self.secp,
+                &contract,
+                accept_msg,
+                &self.wallet,
+                &self.store,
+                &self.signer_provider,
+                &self.logger,
+            )
+            .await?;
+
+        self.update_contract(&Contract::Signed(bumped_contract))
+            .await?;
+        self.update_state(|state| state.funding_fee_requests.remove(&accept_msg.contract_id))
+            .await?;
+
+        Ok(sign_msg)
+    }
+
+    async fn on_bump_funding_sign(
+        &self,
+        sign_msg: &BumpFundingSign,
+        peer_id: &PublicKey,
+    ) -> Result<(), Error> {
+        get_contract_in_state!(self, &sign_msg.contract_id, Signed, Some(*peer_id))?;
+        let bumped_contract = self
+            .state
+            .lock()
+            .await
+            .funding_fee_bumps
+            .get(&sign_msg.contract_id)
+            .cloned()
+            .ok_or_else(|| {
+                Error::InvalidState(format!(
+                    "No funding fee bump was accepted. contract_id={}",
+                    sign_msg.contract_id.to_lower_hex_string()
+                ))
+            })?;
+
+        let (signed_contract, fund_tx) = crate::contract_updater::verify_funding_fee_bump_sign(
+            &self.secp,
+            &bumped_contract,
+            sign_msg,
+            &self.wallet,
+            &self.store,
+            &self.signer_provider,
+            &self.logger,
+        )
+        .await?;
+
+        self.update_contract(&Contract::Signed(signed_contract))
+            .await?;
+        self.update_state(|state| state.funding_fee_bumps.remove(&sign_msg.contract_id))
+            .await?;
+        self.blockchain.send_transaction(&fund_tx).await?;
+
+        log_info!(
+            self.logger,
+            "Broadcast fund transaction with a higher fee. contract_id={} bumped_contract_id={} fund_txid={}",
+            sign_msg.contract_id.to_lower_hex_string(),
+            bumped_contract.get_contract_id_string(),
+            fund_tx.compute_txid().to_string(),
+        );
+
+        Ok(())
+    }
+
+    async fn check_funding_unconfirmed(&self, contract: &SignedContract) -> Result<(), Error> {
+        let fund_txid = contract
             .accepted_contract
             .dlc_transactions
-            .refund
-            .lock_time
-            .to_consensus_u32() as u64
-            <= self.time.unix_time_now()
-        {
-            let refund_txid = contract
+            .fund
+            .compute_txid();
+        let status = self
+            .blockchain
+            .get_transaction_confirmations(&fund_txid)
+            .await?;
+        if status.confirmations() > 0 {
+            return Err(Error::InvalidState(format!(
+                "Fund transaction is already confirmed. fund_txid={}",
+                fund_txid
+            )));
+        }
+        Ok(())
+    }
+
+    /// Deletes the signed contracts whose fund transaction spends an input of
+    /// the confirmed fund transaction of `contract`, replaced by each other
+    /// with [`Manager::bump_funding_fee`].
+    async fn delete_replaced_contracts(&self, contract: &SignedContract) -> Result<(), Error> {
+        let contract_id = contract.accepted_contract.get_contract_id();
+        let fund = &contract.accepted_contract.dlc_transactions.fund;
+        let mut replaced_ids = vec![contract_id];
+        for replaced in self.store.get_signed_contracts().await? {
+            let replaced_id = replaced.accepted_contract.get_contract_id();
+            let conflicts = replaced
                 .accepted_contract
                 .dlc_transactions
-                .refund
-                .compute_txid();
-            let confirmations = self
-                .blockchain
-                .get_transaction_confirmations(&refund_txid)
-                .await?
-                .confirmations();
-
-            if confirmations > 0 {
-                // Counterparty (or we) already broadcast the refund tx. Update state.
-                self.store
-                    .update_contract(&Contract::Refunded(contract.clone()))
-                    .await?;
-            } else if *AUTOMATIC_REFUND {
-                self.check_and_broadcast_refund(&contract.accepted_contract.get_contract_id())
-                    .await?;
+                .fund
+                .input
+                .iter()
+                .any(|a| {
+                    fund.input
+                        .iter()
+                        .any(|b| a.previous_output == b.previous_output)
+                });
+            if replaced_id == contract_id || !conflicts {
+                continue;
             }
+            log_info!(
+                self.logger,
+                "Deleting contract whose fund transaction was replaced. contract_id={} confirmed_contract_id={}",
+                replaced_id.to_lower_hex_string(),
+                contract_id.to_lower_hex_string(),
+            );
+            self.store.delete_contract(&replaced_id).await?;
+            replaced_ids.push(replaced_id);
+        }
+        let pending = {
+            let state = self.state.lock().await;
+            replaced_ids.iter().any(|id| {
+                state.funding_fee_bumps.contains_key(id)
+                    || state.funding_fee_requests.contains_key(id)
+            })
+        };
+        if pending {
+            self.update_state(|state| {
+                for id in &replaced_ids {
+                    state.funding_fee_bumps.remove(id);
+                    state.funding_fee_requests.remove(id);
+                }
+            })
+            .await?;
         }
-
         Ok(())
     }
 
@@ -1640,7 +3125,7 @@ where
                 contract.accepted_contract.get_contract_id_string()
             );
             let refunded = Contract::Refunded(contract.clone());
-            self.store.update_contract(&refunded).await?;
+            self.update_contract(&refunded).await?;
             return Ok(refunded);
         }
 
@@ -1678,7 +3163,7 @@ where
             })
         };
 
-        self.store.update_contract(&contract).await?;
+        self.update_contract(&contract).await?;
 
         Ok(contract)
     }
@@ -1774,11 +3259,41 @@ where
             signed_cet: close_tx,
         };
 
-        self.store
-            .update_contract(&Contract::PreClosed(preclosed_contract))
+        self.update_contract(&Contract::PreClosed(preclosed_contract))
             .await?;
+        self.update_state(|state| {
+            state.close_offers.remove(contract_id);
+        })
+        .await
+    }
 
-        Ok(())
+    /// Returns the cooperative close offer received for a contract, if any.
+    pub async fn get_received_close_offer(&self, contract_id: &ContractId) -> Option<CloseDlc> {
+        self.state
+            .lock()
+            .await
+            .close_offers
+            .get(contract_id)
+            .cloned()
+    }
+
+    /// Accepts the cooperative close offer received from the counter party
+    /// through [`Manager::on_dlc_message`] and broadcasts the close transaction.
+    pub async fn accept_received_cooperative_close(
+        &self,
+        contract_id: &ContractId,
+    ) -> Result<(), Error> {
+        let close_message = self
+            .get_received_close_offer(contract_id)
+            .await
+            .ok_or_else(|| {
+                Error::InvalidState(format!(
+                    "No cooperative close offer received. contract_id={}",
+                    contract_id.to_lower_hex_string()
+                ))
+            })?;
+        self.accept_cooperative_close(contract_id, &close_message)
+            .await
     }
 }
 
@@ -1810,6 +3325,17 @@ where
         contract_input: &ContractInput,
         counter_party: PublicKey,
     ) -> Result<OfferChannel, Error> {
+        if contract_input.contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0 {
+            return Err(Error::InvalidParameters(
+                "Taproot funding is not supported for channels".to_string(),
+            ));
+        }
+        if contract_input.contract_flags & ddk_dlc::ANCHOR_OUTPUTS_FLAG != 0 {
+            return Err(Error::InvalidParameters(
+                "Anchor outputs are not supported for channels".to_string(),
+            ));
+        }
+
         let oracle_announcements = self.oracle_announcements(contract_input).await?;
 
         let (offered_channel, offered_contract) = crate::channel_updater::offer_channel(
@@ -1829,12 +3355,11 @@ where
 
         let msg = offered_channel.get_offer_channel_msg(&offered_contract);
 
-        self.store
-            .upsert_channel(
-                Channel::Offered(offered_channel),
-                Some(Contract::Offered(offered_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Offered(offered_channel),
+            Some(Contract::Offered(offered_contract)),
+        )
+        .await?;
 
         Ok(msg)
     }
@@ -1862,12 +3387,11 @@ where
         )?;
 
         let counterparty = offered_channel.counter_party;
-        self.store
-            .upsert_channel(
-                Channel::Cancelled(offered_channel),
-                Some(Contract::Rejected(offered_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Cancelled(offered_channel),
+            Some(Contract::Rejected(offered_contract)),
+        )
+        .await?;
 
         let msg = Reject {
             channel_id: *channel_id,
@@ -1918,12 +3442,11 @@ where
         let contract_id = accepted_contract.get_contract_id();
         let counter_party = accepted_contract.offered_contract.counter_party;
 
-        self.store
-            .upsert_channel(
-                Channel::Accepted(accepted_channel),
-                Some(Contract::Accepted(accepted_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Accepted(accepted_channel),
+            Some(Contract::Accepted(accepted_contract)),
+        )
+        .await?;
 
         Ok((accept_channel, channel_id, contract_id, counter_party))
     }
@@ -1935,6 +3458,67 @@ where
         self.force_close_channel_internal(channel, true).await
     }
 
+    /// Returns the justice data of every revoked transaction of the signed
+    /// channels, sealed for a watchtower.
+    ///
+    /// A watchtower holding the blobs punishes the counter party for
+    /// publishing a revoked state while this node is offline. Every channel
+    /// update revokes a state, so the blobs should be exported again after
+    /// each of them. The punishments of one export pay to a single new address
+    /// of the wallet, at the fee rate estimated for urgent sweeps at the time
+    /// of the export.
+    pub async fn export_justice_blobs(&self) -> Result<Vec<EncryptedJusticeBlob>, Error> {
+        let revoked_txs = self.chain_monitor.lock().await.unconfirmed_revoked_txs();
+        if revoked_txs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let destination = self.wallet.get_new_address().await?;
+        let mut blobs = Vec::with_capacity(revoked_txs.len());
+        for (txid, channel_info) in revoked_txs {
+            let TxType::Revoked {
+                update_idx,
+                own_adaptor_signature,
+                is_offer,
+                revoked_tx_type,
+                revoked_value,
+            } = channel_info.tx_type
+            else {
+                continue;
+            };
+            let signed_channel = match get_channel_in_state!(
+                self,
+                &channel_info.channel_id,
+                Signed,
+                None as Option<PublicKey>
+            ) {
+                Ok(c) => c,
+                Err(e) => {
+                    log_error!(
+                        self.logger,
+                        "Could not retrieve channel {:?}: {}",
+                        channel_info.channel_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let justice_data = self.get_justice_data(
+                &signed_channel,
+                update_idx,
+                own_adaptor_signature,
+                is_offer,
+                revoked_tx_type,
+                txid,
+                revoked_value,
+                destination.clone(),
+            )?;
+            blobs.push(justice_data.encrypt(&txid));
+        }
+
+        Ok(blobs)
+    }
+
     /// Offer to settle the balance of a channel so that the counter party gets
     /// `counter_payout`. Returns the [`dlc_messages::channel::SettleChannelOffer`]
     /// message to be sent and the public key of the counter party node.
@@ -1957,8 +3541,7 @@ where
 
         let counter_party = signed_channel.counter_party;
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok((msg, counter_party))
@@ -1987,8 +3570,7 @@ where
 
         let counter_party = signed_channel.counter_party;
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok((msg, counter_party))
@@ -2023,12 +3605,11 @@ where
 
         let counter_party = offered_contract.counter_party;
 
-        self.store
-            .upsert_channel(
-                Channel::Signed(signed_channel),
-                Some(Contract::Offered(offered_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Signed(signed_channel),
+            Some(Contract::Offered(offered_contract)),
+        )
+        .await?;
 
         Ok((msg, counter_party))
     }
@@ -2065,12 +3646,11 @@ where
 
         let counter_party = signed_channel.counter_party;
 
-        self.store
-            .upsert_channel(
-                Channel::Signed(signed_channel),
-                Some(Contract::Accepted(accepted_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Signed(signed_channel),
+            Some(Contract::Accepted(accepted_contract)),
+        )
+        .await?;
 
         Ok((msg, counter_party))
     }
@@ -2101,12 +3681,11 @@ where
 
         let counter_party = signed_channel.counter_party;
 
-        self.store
-            .upsert_channel(
-                Channel::Signed(signed_channel),
-                Some(Contract::Rejected(offered_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Signed(signed_channel),
+            Some(Contract::Rejected(offered_contract)),
+        )
+        .await?;
 
         Ok((reject_msg, counter_party))
     }
@@ -2125,8 +3704,7 @@ where
 
         let counter_party = signed_channel.counter_party;
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok((msg, counter_party))
@@ -2162,8 +3740,7 @@ where
             },
         );
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
         self.store
             .persist_chain_monitor(&*self.chain_monitor.lock().await)
@@ -2204,11 +3781,10 @@ where
 
         self.blockchain.send_transaction(&close_tx).await?;
 
-        self.store.upsert_channel(closed_channel, None).await?;
+        self.upsert_channel(closed_channel, None).await?;
 
         if let Some(closed_contract) = closed_contract {
-            self.store
-                .update_contract(&Contract::Closed(closed_contract))
+            self.update_contract(&Contract::Closed(closed_contract))
                 .await?;
         }
 
@@ -2275,8 +3851,7 @@ where
                 .await
                 .cleanup_channel(signed_channel.channel_id);
 
-            self.store
-                .upsert_channel(closed_channel, Some(closed_contract))
+            self.upsert_channel(closed_channel, Some(closed_contract))
                 .await?;
         }
 
@@ -2296,6 +3871,17 @@ where
             CET_NSEQUENCE * 2,
         )?;
 
+        if offer_channel.contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0 {
+            return Err(Error::InvalidParameters(
+                "Taproot funding is not supported for channels".to_string(),
+            ));
+        }
+        if offer_channel.contract_flags & ddk_dlc::ANCHOR_OUTPUTS_FLAG != 0 {
+            return Err(Error::InvalidParameters(
+                "Anchor outputs are not supported for channels".to_string(),
+            ));
+        }
+
         let keys_id = self
             .signer_provider
             .derive_signer_key_id(false, offer_channel.temporary_contract_id);
@@ -2315,8 +3901,7 @@ where
             ));
         }
 
-        self.store
-            .upsert_channel(Channel::Offered(channel), Some(Contract::Offered(contract)))
+        self.upsert_channel(Channel::Offered(channel), Some(Contract::Offered(contract)))
             .await?;
 
         Ok(())
@@ -2365,8 +3950,7 @@ where
                         accept_message: accept_channel.clone(),
                         counter_party: *peer_id,
                     };
-                    self.store
-                        .upsert_channel(Channel::FailedAccept(channel), None)
+                    self.upsert_channel(Channel::FailedAccept(channel), None)
                         .await?;
                     return Err(e);
                 }
@@ -2396,12 +3980,11 @@ where
             unreachable!();
         }
 
-        self.store
-            .upsert_channel(
-                Channel::Signed(signed_channel),
-                Some(Contract::Signed(signed_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Signed(signed_channel),
+            Some(Contract::Signed(signed_contract)),
+        )
+        .await?;
 
         self.store
             .persist_chain_monitor(&*self.chain_monitor.lock().await)
@@ -2447,8 +4030,7 @@ where
                         sign_message: sign_channel.clone(),
                         counter_party: *peer_id,
                     };
-                    self.store
-                        .upsert_channel(Channel::FailedSign(channel), None)
+                    self.upsert_channel(Channel::FailedSign(channel), None)
                         .await?;
                     return Err(e);
                 }
@@ -2472,12 +4054,11 @@ where
 
         self.blockchain.send_transaction(&signed_fund_tx).await?;
 
-        self.store
-            .upsert_channel(
-                Channel::Signed(signed_channel),
-                Some(Contract::Signed(signed_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Signed(signed_channel),
+            Some(Contract::Signed(signed_contract)),
+        )
+        .await?;
         self.store
             .persist_chain_monitor(&*self.chain_monitor.lock().await)
             .await?;
@@ -2501,8 +4082,7 @@ where
 
         crate::channel_updater::on_settle_offer(&mut signed_channel, settle_offer)?;
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok(None)
@@ -2529,8 +4109,7 @@ where
         )
         .await?;
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok(msg)
@@ -2554,6 +4133,7 @@ where
         )?;
 
         let prev_buffer_txid = prev_buffer_tx.compute_txid();
+        let prev_buffer_value = prev_buffer_tx.output[0].value;
         let own_buffer_adaptor_signature = *own_buffer_adaptor_signature;
         let is_offer = *is_offer;
         let signed_contract_id = *signed_contract_id;
@@ -2574,6 +4154,7 @@ where
                     own_adaptor_signature: own_buffer_adaptor_signature,
                     is_offer,
                     revoked_tx_type: RevokedTxType::Buffer,
+                    revoked_value: prev_buffer_value,
                 },
             },
         );
@@ -2583,8 +4164,7 @@ where
                 .await?,
         );
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), Some(closed_contract))
+        self.upsert_channel(Channel::Signed(signed_channel), Some(closed_contract))
             .await?;
         self.store
             .persist_chain_monitor(&*self.chain_monitor.lock().await)
@@ -2613,6 +4193,7 @@ where
         let own_buffer_adaptor_signature = *own_buffer_adaptor_signature;
         let is_offer = *is_offer;
         let buffer_txid = buffer_tx.compute_txid();
+        let buffer_value = buffer_tx.output[0].value;
         let signed_contract_id = *signed_contract_id;
 
         crate::channel_updater::settle_channel_on_finalize(
@@ -2630,6 +4211,7 @@ where
                     own_adaptor_signature: own_buffer_adaptor_signature,
                     is_offer,
                     revoked_tx_type: RevokedTxType::Buffer,
+                    revoked_value: buffer_value,
                 },
             },
         );
@@ -2638,8 +4220,7 @@ where
             self.get_collaboratively_closed_contract(&signed_contract_id, own_payout, true)
                 .await?,
         );
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), Some(closed_contract))
+        self.upsert_channel(Channel::Signed(signed_channel), Some(closed_contract))
             .await?;
         self.store
             .persist_chain_monitor(&*self.chain_monitor.lock().await)
@@ -2673,8 +4254,7 @@ where
         )?;
 
         self.store.create_contract(&offered_contract).await?;
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok(None)
@@ -2712,12 +4292,11 @@ where
         .await?;
 
         // Directly confirmed as we're in a channel the fund tx is already confirmed.
-        self.store
-            .upsert_channel(
-                Channel::Signed(signed_channel),
-                Some(Contract::Confirmed(signed_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Signed(signed_channel),
+            Some(Contract::Confirmed(signed_contract)),
+        )
+        .await?;
 
         Ok(msg)
     }
@@ -2757,6 +4336,7 @@ where
                         own_adaptor_signature: *own_buffer_adaptor_signature,
                         is_offer: false,
                         revoked_tx_type: RevokedTxType::Buffer,
+                        revoked_value: buffer_transaction.output[0].value,
                     },
                     buffer_transaction.compute_txid(),
                     Some(closed_contract),
@@ -2772,6 +4352,7 @@ where
                     own_adaptor_signature: *own_settle_adaptor_signature,
                     is_offer: false,
                     revoked_tx_type: RevokedTxType::Settle,
+                    revoked_value: RevokedTxType::Settle.revoked_value(settle_tx, false),
                 },
                 settle_tx.compute_txid(),
                 None,
@@ -2809,19 +4390,18 @@ where
         );
 
         // Directly confirmed as we're in a channel the fund tx is already confirmed.
-        self.store
-            .upsert_channel(
-                Channel::Signed(signed_channel),
-                Some(Contract::Confirmed(signed_contract)),
-            )
-            .await?;
+        self.upsert_channel(
+            Channel::Signed(signed_channel),
+            Some(Contract::Confirmed(signed_contract)),
+        )
+        .await?;
 
         self.store
             .persist_chain_monitor(&*self.chain_monitor.lock().await)
             .await?;
 
         if let Some(closed_contract) = closed_contract {
-            self.store.update_contract(&closed_contract).await?;
+            self.update_contract(&closed_contract).await?;
         }
 
         Ok(msg)
@@ -2856,6 +4436,7 @@ where
                         own_adaptor_signature: *own_buffer_adaptor_signature,
                         is_offer: false,
                         revoked_tx_type: RevokedTxType::Buffer,
+                        revoked_value: buffer_transaction.output[0].value,
                     },
                     buffer_transaction.compute_txid(),
                     Some(Contract::Closed(closed_contract)),
@@ -2871,6 +4452,7 @@ where
                     own_adaptor_signature: *own_settle_adaptor_signature,
                     is_offer: false,
                     revoked_tx_type: RevokedTxType::Settle,
+                    revoked_value: RevokedTxType::Settle.revoked_value(settle_tx, false),
                 },
                 settle_tx.compute_txid(),
                 None,
@@ -2908,15 +4490,14 @@ where
             },
         );
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
         self.store
             .persist_chain_monitor(&*self.chain_monitor.lock().await)
             .await?;
 
         if let Some(closed_contract) = closed_contract {
-            self.store.update_contract(&closed_contract).await?;
+            self.update_contract(&closed_contract).await?;
         }
 
         Ok(msg)
@@ -2936,8 +4517,7 @@ where
             renew_revoke,
         )?;
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok(())
@@ -2958,8 +4538,7 @@ where
             &self.time,
         )?;
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         Ok(())
@@ -3002,12 +4581,11 @@ where
                     self.wallet.unreserve_utxos(&utxos)?;
 
                     // remove rejected channel, since nothing has been confirmed on chain yet.
-                    self.store
-                        .upsert_channel(
-                            Channel::Cancelled(offered_channel),
-                            Some(Contract::Rejected(offered_contract)),
-                        )
-                        .await?;
+                    self.upsert_channel(
+                        Channel::Cancelled(offered_channel),
+                        Some(Contract::Rejected(offered_contract)),
+                    )
+                    .await?;
                 }
                 Channel::Signed(mut signed_channel) => {
                     let contract = match signed_channel.state {
@@ -3028,8 +4606,7 @@ where
 
                     crate::channel_updater::on_reject(&mut signed_channel)?;
 
-                    self.store
-                        .upsert_channel(Channel::Signed(signed_channel), contract)
+                    self.upsert_channel(Channel::Signed(signed_channel), contract)
                         .await?;
                 }
                 channel => {
@@ -3038,6 +4615,27 @@ where
                     )))
                 }
             }
+        } else if let Some(contract) = self
+            .store
+            .get_contracts()
+            .await?
+            .into_iter()
+            .filter(|c| matches!(c, Contract::Offered(_) | Contract::Accepted(_)))
+            .find(|c| {
+                c.get_temporary_id() == reject.channel_id
+                    && c.get_counter_party_id() == *counter_party
+            })
+        {
+            // Contracts are rejected by temporary id, e.g. when the offer
+            // expired on the side of the counter party.
+            let offered_contract = self.release_offer(contract).await?;
+            self.update_contract(&Contract::Rejected(offered_contract))
+                .await?;
+            log_info!(
+                self.logger,
+                "Contract offer rejected by counter party. temp_id={}",
+                reject.channel_id.to_lower_hex_string()
+            );
         } else {
             log_warn!(
                 self.logger,
@@ -3128,8 +4726,7 @@ where
 
                     signed_channel.roll_back_state = Some(state);
 
-                    self.store
-                        .upsert_channel(Channel::Signed(signed_channel), None)
+                    self.upsert_channel(Channel::Signed(signed_channel), None)
                         .await?;
 
                     false
@@ -3139,126 +4736,20 @@ where
                     own_adaptor_signature,
                     is_offer,
                     revoked_tx_type,
+                    revoked_value,
                 } => {
-                    let secret = signed_channel
-                        .counter_party_commitment_secrets
-                        .get_secret(update_idx)
-                        .expect("to be able to retrieve the per update secret");
-                    let counter_per_update_secret = SecretKey::from_slice(&secret)
-                        .expect("to be able to parse the counter per update secret.");
-
-                    let per_update_seed_pk = signed_channel.own_per_update_seed;
-
-                    let per_update_seed_sk = self
-                        .signer_provider
-                        .get_secret_key_for_pubkey(&per_update_seed_pk)?;
-
-                    let per_update_secret = SecretKey::from_slice(&build_commitment_secret(
-                        per_update_seed_sk.as_ref(),
+                    let destination = self.wallet.get_new_address().await?;
+                    let justice_data = self.get_justice_data(
+                        &signed_channel,
                         update_idx,
-                    ))
-                    .expect("a valid secret key.");
-
-                    let per_update_point =
-                        PublicKey::from_secret_key(&self.secp, &per_update_secret);
-
-                    let own_revocation_params = signed_channel.own_points.get_revokable_params(
-                        &self.secp,
-                        &signed_channel.counter_points.revocation_basepoint,
-                        &per_update_point,
-                    );
-
-                    let counter_per_update_point =
-                        PublicKey::from_secret_key(&self.secp, &counter_per_update_secret);
-
-                    let base_own_sk = self
-                        .signer_provider
-                        .get_secret_key_for_pubkey(&signed_channel.own_points.own_basepoint)?;
-
-                    let own_sk = derive_private_key(&self.secp, &per_update_point, &base_own_sk);
-
-                    let counter_revocation_params =
-                        signed_channel.counter_points.get_revokable_params(
-                            &self.secp,
-                            &signed_channel.own_points.revocation_basepoint,
-                            &counter_per_update_point,
-                        );
-
-                    let witness = if signed_channel.own_params.fund_pubkey
-                        < signed_channel.counter_params.fund_pubkey
-                    {
-                        tx.input[0].witness.to_vec().remove(1)
-                    } else {
-                        tx.input[0].witness.to_vec().remove(2)
-                    };
-
-                    let sig_data = witness
-                        .iter()
-                        .take(witness.len() - 1)
-                        .cloned()
-                        .collect::<Vec<_>>();
-                    let own_sig = Signature::from_der(&sig_data)?;
-
-                    let counter_sk = own_adaptor_signature.recover(
-                        &self.secp,
-                        &own_sig,
-                        &counter_revocation_params.publish_pk.inner,
+                        own_adaptor_signature,
+                        is_offer,
+                        revoked_tx_type,
+                        tx.compute_txid(),
+                        revoked_value,
+                        destination,
                     )?;
-
-                    let own_revocation_base_secret =
-                        &self.signer_provider.get_secret_key_for_pubkey(
-                            &signed_channel.own_points.revocation_basepoint,
-                        )?;
-
-                    let counter_revocation_sk = derive_private_revocation_key(
-                        &self.secp,
-                        &counter_per_update_secret,
-                        own_revocation_base_secret,
-                    );
-
-                    let (offer_params, accept_params) = if is_offer {
-                        (&own_revocation_params, &counter_revocation_params)
-                    } else {
-                        (&counter_revocation_params, &own_revocation_params)
-                    };
-
-                    let fee_rate_per_vb: u64 = (self.fee_estimator.get_est_sat_per_1000_weight(
-                        lightning::chain::chaininterface::ConfirmationTarget::UrgentOnChainSweep,
-                    ) / 250)
-                        .into();
-
-                    let signed_tx = match revoked_tx_type {
-                        RevokedTxType::Buffer => {
-                            ddk_dlc::channel::create_and_sign_punish_buffer_transaction(
-                                &self.secp,
-                                offer_params,
-                                accept_params,
-                                &own_sk,
-                                &counter_sk,
-                                &counter_revocation_sk,
-                                &tx,
-                                &self.wallet.get_new_address().await?,
-                                0,
-                                fee_rate_per_vb,
-                            )?
-                        }
-                        RevokedTxType::Settle => {
-                            ddk_dlc::channel::create_and_sign_punish_settle_transaction(
-                                &self.secp,
-                                offer_params,
-                                accept_params,
-                                &own_sk,
-                                &counter_sk,
-                                &counter_revocation_sk,
-                                &tx,
-                                &self.wallet.get_new_address().await?,
-                                CET_NSEQUENCE,
-                                0,
-                                fee_rate_per_vb,
-                                is_offer,
-                            )?
-                        }
-                    };
+                    let signed_tx = justice_data.punish_transaction(&self.secp, &tx)?;
 
                     self.blockchain.send_transaction(&signed_tx).await?;
 
@@ -3275,7 +4766,7 @@ where
                         .lock()
                         .await
                         .cleanup_channel(signed_channel.channel_id);
-                    self.store.upsert_channel(closed_channel, None).await?;
+                    self.upsert_channel(closed_channel, None).await?;
                     true
                 }
                 TxType::CollaborativeClose => {
@@ -3295,8 +4786,7 @@ where
                                 false,
                             )
                             .await?;
-                        self.store
-                            .update_contract(&Contract::Closed(closed_contract))
+                        self.update_contract(&Contract::Closed(closed_contract))
                             .await?;
                     }
                     let closed_channel = Channel::CollaborativelyClosed(ClosedChannel {
@@ -3308,7 +4798,7 @@ where
                         .lock()
                         .await
                         .cleanup_channel(signed_channel.channel_id);
-                    self.store.upsert_channel(closed_channel, None).await?;
+                    self.upsert_channel(closed_channel, None).await?;
                     true
                 }
                 TxType::SettleTx => {
@@ -3321,7 +4811,7 @@ where
                         .lock()
                         .await
                         .cleanup_channel(signed_channel.channel_id);
-                    self.store.upsert_channel(closed_channel, None).await?;
+                    self.upsert_channel(closed_channel, None).await?;
                     true
                 }
                 TxType::Cet => {
@@ -3382,8 +4872,7 @@ where
                         .flatten()
                         .next();
 
-                    self.store
-                        .upsert_channel(closed_channel, pre_closed_contract)
+                    self.upsert_channel(closed_channel, pre_closed_contract)
                         .await?;
 
                     true
@@ -3503,8 +4992,7 @@ where
             .await
             .remove_tx(&buffer_transaction.compute_txid());
 
-        self.store
-            .upsert_channel(Channel::Signed(signed_channel), None)
+        self.upsert_channel(Channel::Signed(signed_channel), None)
             .await?;
 
         self.store
@@ -3542,11 +5030,110 @@ where
             .await
             .cleanup_channel(signed_channel.channel_id);
 
-        self.store.upsert_channel(closed_channel, None).await?;
+        self.upsert_channel(closed_channel, None).await?;
 
         Ok(())
     }
 
+    /// Gathers what is needed to punish the publication of the revoked
+    /// transaction `revoked_txid` of `signed_channel` at `update_idx`, and signs
+    /// our part of the punishment paying to `destination`.
+    #[allow(clippy::too_many_arguments)]
+    fn get_justice_data(
+        &self,
+        signed_channel: &SignedChannel,
+        update_idx: u64,
+        own_adaptor_signature: EcdsaAdaptorSignature,
+        is_offer: bool,
+        revoked_tx_type: RevokedTxType,
+        revoked_txid: Txid,
+        revoked_value: Amount,
+        destination: Address,
+    ) -> Result<JusticeData, Error> {
+        let secret = signed_channel
+            .counter_party_commitment_secrets
+            .get_secret(update_idx)
+            .expect("to be able to retrieve the per update secret");
+        let counter_per_update_secret = SecretKey::from_slice(&secret)
+            .expect("to be able to parse the counter per update secret.");
+
+        let per_update_seed_pk = signed_channel.own_per_update_seed;
+
+        let per_update_seed_sk = self
+            .signer_provider
+            .get_secret_key_for_pubkey(&per_update_seed_pk)?;
+
+        let per_update_secret = SecretKey::from_slice(&build_commitment_secret(
+            per_update_seed_sk.as_ref(),
+            update_idx,
+        ))
+        .expect("a valid secret key.");
+
+        let per_update_point = PublicKey::from_secret_key(&self.secp, &per_update_secret);
+
+        let own_revocation_params = signed_channel.own_points.get_revokable_params(
+            &self.secp,
+            &signed_channel.counter_points.revocation_basepoint,
+            &per_update_point,
+        );
+
+        let counter_per_update_point =
+            PublicKey::from_secret_key(&self.secp, &counter_per_update_secret);
+
+        let base_own_sk = self
+            .signer_provider
+            .get_secret_key_for_pubkey(&signed_channel.own_points.own_basepoint)?;
+
+        let own_sk = derive_private_key(&self.secp, &per_update_point, &base_own_sk);
+
+        let counter_revocation_params = signed_channel.counter_points.get_revokable_params(
+            &self.secp,
+            &signed_channel.own_points.revocation_basepoint,
+            &counter_per_update_point,
+        );
+
+        let own_revocation_base_secret = &self
+            .signer_provider
+            .get_secret_key_for_pubkey(&signed_channel.own_points.revocation_basepoint)?;
+
+        let counter_revocation_sk = derive_private_revocation_key(
+            &self.secp,
+            &counter_per_update_secret,
+            own_revocation_base_secret,
+        );
+
+        let (offer_revoke_params, accept_revoke_params) = if is_offer {
+            (own_revocation_params, counter_revocation_params)
+        } else {
+            (counter_revocation_params, own_revocation_params)
+        };
+
+        let fee_rate_per_vb: u64 = (self.fee_estimator.get_est_sat_per_1000_weight(
+            lightning::chain::chaininterface::ConfirmationTarget::UrgentOnChainSweep,
+        ) / 250)
+            .into();
+
+        let punish_params = PunishParams {
+            revoked_tx_type,
+            is_offer,
+            offer_revoke_params,
+            accept_revoke_params,
+            revoked_value,
+            destination,
+            fee_rate_per_vb,
+        };
+
+        JusticeData::new(
+            &self.secp,
+            punish_params,
+            revoked_txid,
+            &own_sk,
+            own_adaptor_signature,
+            signed_channel.own_params.fund_pubkey < signed_channel.counter_params.fund_pubkey,
+            counter_revocation_sk,
+        )
+    }
+
     async fn get_collaboratively_closed_contract(
         &self,
         contract_id: &ContractId,
@@ -0,0 +1,62 @@
+//! State the manager keeps between messages that belongs to no single contract
+//! or channel.
+
+use std::collections::HashMap;
+
+use bitcoin::Txid;
+use ddk_messages::ser_impls::{read_hash_map, write_hash_map};
+use ddk_messages::CloseDlc;
+
+use crate::contract::accepted_contract::AcceptedContract;
+use crate::equivocation::AttestationLedger;
+use crate::ContractId;
+
+/// The manager's own state, written with
+/// [`Storage::persist_manager_state`](crate::Storage::persist_manager_state)
+/// whenever it changes and read back when the manager starts.
+#[derive(Clone, Debug, Default)]
+pub struct ManagerState {
+    /// Validated cooperative close offers received from counter parties,
+    /// waiting for [`Manager::accept_received_cooperative_close`](crate::manager::Manager::accept_received_cooperative_close).
+    pub(crate) close_offers: HashMap<ContractId, CloseDlc>,
+    /// Every attestation seen for the events of open contracts, and the
+    /// oracles frozen for attesting to conflicting outcomes.
+    pub(crate) attestation_ledger: AttestationLedger,
+    /// Fee rate of the last child transaction broadcast for each closing
+    /// transaction with an anchor output, until it confirms.
+    pub(crate) fee_bumps: HashMap<Txid, u64>,
+    /// Fee rates we asked the counter party to bump fund transactions to, as
+    /// the offer party, keyed by the id of the contract to replace.
+    pub(crate) funding_fee_requests: HashMap<ContractId, u64>,
+    /// Contracts replacing a signed contract with a higher fee fund
+    /// transaction, signed by us as the accept party and waiting for the
+    /// signatures of the offer party, keyed by the id of the contract to
+    /// replace.
+    pub(crate) funding_fee_bumps: HashMap<ContractId, AcceptedContract>,
+    /// When each offered or accepted contract outside of a channel was
+    /// offered, keyed by temporary contract id, until it is answered or
+    /// expires.
+    pub(crate) offers_seen_at: HashMap<ContractId, u64>,
+}
+
+impl ManagerState {
+    /// Whether nothing is kept, e.g. once every close offer was answered and
+    /// every fee bump completed.
+    pub fn is_empty(&self) -> bool {
+        self.close_offers.is_empty()
+            && self.attestation_ledger.is_empty()
+            && self.fee_bumps.is_empty()
+            && self.funding_fee_requests.is_empty()
+            && self.funding_fee_bumps.is_empty()
+            && self.offers_seen_at.is_empty()
+    }
+}
+
+impl_dlc_writeable!(ManagerState, {
+    (close_offers, { cb_writeable, write_hash_map, read_hash_map }),
+    (attestation_ledger, writeable),
+    (fee_bumps, { cb_writeable, write_hash_map, read_hash_map }),
+    (funding_fee_requests, { cb_writeable, write_hash_map, read_hash_map }),
+    (funding_fee_bumps, { cb_writeable, write_hash_map, read_hash_map }),
+    (offers_seen_at, { cb_writeable, write_hash_map, read_hash_map })
+});
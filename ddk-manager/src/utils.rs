@@ -96,6 +96,7 @@ pub(crate) async fn get_party_params<W: Deref, B: Deref, X: ContractSigner, C: S
     total_collateral: Amount,
     dlc_inputs: Vec<DlcInputInfo>,
     fee_rate: u64,
+    contract_flags: u8,
     wallet: &W,
     signer: &X,
     blockchain: &B,
@@ -114,7 +115,13 @@ where
     let change_serial_id = get_new_serial_id();
 
     let appr_required_amount =
-        get_approximate_required_amount(&dlc_inputs, total_collateral, own_collateral, fee_rate)?;
+        get_approximate_required_amount(&dlc_inputs, total_collateral, own_collateral, fee_rate)?
+            + ddk_dlc::anchor::get_anchor_cost(
+                contract_flags,
+                own_collateral,
+                total_collateral,
+                fee_rate,
+            )?;
 
     // First get the amount in the DLC input. Subtract from own collateral to see if there is needed extra UTXO.
     let dlc_input_amount = dlc_inputs.iter().map(|d| d.fund_amount).sum::<Amount>();
@@ -122,34 +129,14 @@ where
     let potential_additional_collateral_needed = appr_required_amount
         .checked_sub(dlc_input_amount)
         .unwrap_or(Amount::ZERO);
-    let utxos = wallet
-        .get_utxos_for_amount(potential_additional_collateral_needed, fee_rate, true)
-        .await?;
-
-    let mut funding_inputs: Vec<FundingInput> = Vec::new();
-    let mut funding_tx_info: Vec<TxInputInfo> = Vec::new();
-    let mut total_input = dlc_input_amount;
-    for utxo in utxos {
-        let prev_tx = blockchain.get_transaction(&utxo.outpoint.txid).await?;
-        let mut writer = Vec::new();
-        prev_tx.consensus_encode(&mut writer)?;
-        let prev_tx_vout = utxo.outpoint.vout;
-        let sequence = 0xffffffff;
-        // TODO(tibo): this assumes P2WPKH with low R
-        let max_witness_len = 108;
-        let funding_input = FundingInput {
-            input_serial_id: get_new_serial_id(),
-            prev_tx: writer,
-            prev_tx_vout,
-            sequence,
-            max_witness_len,
-            redeem_script: utxo.redeem_script,
-            dlc_input: None,
-        };
-        total_input += prev_tx.output[prev_tx_vout as usize].value;
-        funding_tx_info.push((&funding_input).into());
-        funding_inputs.push(funding_input);
-    }
+    let (mut funding_inputs, mut funding_tx_info, utxo_amount) = get_funding_inputs(
+        potential_additional_collateral_needed,
+        fee_rate,
+        wallet,
+        blockchain,
+    )
+    .await?;
+    let total_input = dlc_input_amount + utxo_amount;
 
     dlc_inputs.iter().for_each(|d| {
         let funding_input = FundingInput {
@@ -184,6 +171,109 @@ where
     Ok((party_params, funding_inputs))
 }
 
+/// Returns the parameters of a party for each contract of a batch, funded
+/// by a single selection of UTXOs shared by all of them. Each contract gets
+/// the fund public key of its own signer and a payout address of its own.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn get_batch_party_params<W: Deref, B: Deref, X: ContractSigner, C: Signing>(
+    secp: &Secp256k1<C>,
+    own_collaterals: &[Amount],
+    total_collaterals: &[Amount],
+    contract_flags: &[u8],
+    fee_rate: u64,
+    wallet: &W,
+    signers: &[X],
+    blockchain: &B,
+) -> Result<(Vec<PartyParams>, Vec<FundingInput>), Error>
+where
+    W::Target: Wallet,
+    B::Target: Blockchain,
+{
+    let change_addr = wallet.get_new_change_address().await?;
+    let change_spk = change_addr.script_pubkey();
+    let change_serial_id = get_new_serial_id();
+
+    // Approximated as if each contract was funded on its own, which
+    // overestimates the fee of the shared fund transaction.
+    let mut appr_required_amount = Amount::ZERO;
+    for ((own_collateral, total_collateral), contract_flags) in own_collaterals
+        .iter()
+        .zip(total_collaterals)
+        .zip(contract_flags)
+    {
+        appr_required_amount +=
+            get_approximate_required_amount(&[], *total_collateral, *own_collateral, fee_rate)?
+                + ddk_dlc::anchor::get_anchor_cost(
+                    *contract_flags,
+                    *own_collateral,
+                    *total_collateral,
+                    fee_rate,
+                )?;
+    }
+    let (funding_inputs, funding_tx_info, input_amount) =
+        get_funding_inputs(appr_required_amount, fee_rate, wallet, blockchain).await?;
+
+    let mut party_params = Vec::with_capacity(signers.len());
+    for (signer, own_collateral) in signers.iter().zip(own_collaterals) {
+        let payout_addr = wallet.get_new_address().await?;
+        party_params.push(PartyParams {
+            fund_pubkey: signer.get_public_key(secp)?,
+            change_script_pubkey: change_spk.clone(),
+            change_serial_id,
+            payout_script_pubkey: payout_addr.script_pubkey(),
+            payout_serial_id: get_new_serial_id(),
+            inputs: funding_tx_info.clone(),
+            dlc_inputs: vec![],
+            collateral: *own_collateral,
+            input_amount,
+        });
+    }
+
+    Ok((party_params, funding_inputs))
+}
+
+/// Selects wallet UTXOs for `amount`, returning them as funding inputs along
+/// with their total value.
+async fn get_funding_inputs<W: Deref, B: Deref>(
+    amount: Amount,
+    fee_rate: u64,
+    wallet: &W,
+    blockchain: &B,
+) -> Result<(Vec<FundingInput>, Vec<TxInputInfo>, Amount), Error>
+where
+    W::Target: Wallet,
+    B::Target: Blockchain,
+{
+    let utxos = wallet.get_utxos_for_amount(amount, fee_rate, true).await?;
+
+    let mut funding_inputs: Vec<FundingInput> = Vec::new();
+    let mut funding_tx_info: Vec<TxInputInfo> = Vec::new();
+    let mut total_input = Amount::ZERO;
+    for utxo in utxos {
+        let prev_tx = blockchain.get_transaction(&utxo.outpoint.txid).await?;
+        let mut writer = Vec::new();
+        prev_tx.consensus_encode(&mut writer)?;
+        let prev_tx_vout = utxo.outpoint.vout;
+        let sequence = 0xffffffff;
+        // TODO(tibo): this assumes P2WPKH with low R
+        let max_witness_len = 108;
+        let funding_input = FundingInput {
+            input_serial_id: get_new_serial_id(),
+            prev_tx: writer,
+            prev_tx_vout,
+            sequence,
+            max_witness_len,
+            redeem_script: utxo.redeem_script,
+            dlc_input: None,
+        };
+        total_input += prev_tx.output[prev_tx_vout as usize].value;
+        funding_tx_info.push((&funding_input).into());
+        funding_inputs.push(funding_input);
+    }
+
+    Ok((funding_inputs, funding_tx_info, total_input))
+}
+
 // If own_collateral is zero, appr_required_amount is zero.
 // If own_collateral is equal to total_collateral, appr_required_amount is the common fee.
 // Otherwise, appr_required_amount is the half common fee.
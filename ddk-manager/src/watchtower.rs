@@ -0,0 +1,606 @@
+//! Punishment data handed to a watchtower.
+//!
+//! A counter party publishing a revoked channel state can be punished by
+//! claiming every output of the revoked transaction, but only if it is noticed
+//! before their own output can be spent. [`JusticeData`] holds what is needed
+//! to build and sign the punishment transaction for one revoked transaction, so
+//! that a watchtower can broadcast it while the node is offline.
+//!
+//! The data leaves the node sealed in an [`EncryptedJusticeBlob`]. A blob is
+//! filed under a hint made of the first bytes of the revoked transaction id and
+//! encrypted with a key derived from the whole id: the watchtower learns
+//! nothing about the channel until the revoked transaction is published.
+
+use bitcoin::ecdsa::Signature as EcdsaSignature;
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::{Address, Amount, Transaction, Txid};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ddk_dlc::channel::RevokeParams;
+use ddk_messages::ser_impls::{
+    read_address, read_ecdsa_adaptor_signature, write_address, write_ecdsa_adaptor_signature,
+};
+use lightning::io::Read;
+use lightning::ln::msgs::DecodeError;
+use lightning::util::ser::{Readable, Writeable, Writer};
+use secp256k1_zkp::ecdsa::Signature;
+use secp256k1_zkp::{All, EcdsaAdaptorSignature, PublicKey, Secp256k1, SecretKey};
+
+use crate::chain_monitor::RevokedTxType;
+use crate::error::Error;
+use crate::CET_NSEQUENCE;
+
+/// Number of bytes of the revoked transaction id used as the hint of a blob.
+pub const JUSTICE_HINT_LEN: usize = 16;
+
+const NONCE_LEN: usize = 12;
+
+/// What is needed to punish the publication of one revoked transaction.
+///
+/// The punishment transaction pays a destination chosen when the data is
+/// created, which our signature of it commits to: the data holds no secret
+/// key of ours, only the revocation secret of the counter party.
+#[derive(Clone, Debug)]
+pub struct JusticeData {
+    pub(crate) punish_params: PunishParams,
+    pub(crate) own_adaptor_signature: EcdsaAdaptorSignature,
+    /// Whether our signature comes first in the witness of the revoked
+    /// transaction, which is the case when our fund public key is the lowest.
+    pub(crate) own_signature_first: bool,
+    pub(crate) own_punish_signature: Signature,
+    pub(crate) counter_revocation_sk: SecretKey,
+}
+
+impl_dlc_writeable!(JusticeData, {
+    (punish_params, writeable),
+    (own_adaptor_signature, {cb_writeable, write_ecdsa_adaptor_signature, read_ecdsa_adaptor_signature}),
+    (own_signature_first, writeable),
+    (own_punish_signature, writeable),
+    (counter_revocation_sk, writeable)
+});
+
+/// What the punishment transaction of one revoked transaction is built from.
+#[derive(Clone, Debug)]
+pub(crate) struct PunishParams {
+    pub(crate) revoked_tx_type: RevokedTxType,
+    pub(crate) is_offer: bool,
+    pub(crate) offer_revoke_params: RevokeParams,
+    pub(crate) accept_revoke_params: RevokeParams,
+    /// Value of the output of the revoked transaction being claimed.
+    pub(crate) revoked_value: Amount,
+    pub(crate) destination: Address,
+    pub(crate) fee_rate_per_vb: u64,
+}
+
+impl_dlc_writeable!(PunishParams, {
+    (revoked_tx_type, writeable),
+    (is_offer, writeable),
+    (offer_revoke_params, {cb_writeable, write_revoke_params, read_revoke_params}),
+    (accept_revoke_params, {cb_writeable, write_revoke_params, read_revoke_params}),
+    (revoked_value, writeable),
+    (destination, {cb_writeable, write_address, read_address}),
+    (fee_rate_per_vb, writeable)
+});
+
+impl PunishParams {
+    /// Returns the unsigned transaction claiming the output of the revoked
+    /// transaction `revoked_txid`.
+    fn transaction(&self, revoked_txid: Txid) -> Result<Transaction, Error> {
+        let tx = match self.revoked_tx_type {
+            RevokedTxType::Buffer => ddk_dlc::channel::create_punish_buffer_transaction(
+                revoked_txid,
+                self.revoked_value,
+                &self.destination,
+                0,
+                self.fee_rate_per_vb,
+            )?,
+            RevokedTxType::Settle => ddk_dlc::channel::create_punish_settle_transaction(
+                revoked_txid,
+                self.revoked_value,
+                &self.destination,
+                0,
+                self.fee_rate_per_vb,
+                self.is_offer,
+            )?,
+        };
+        Ok(tx)
+    }
+
+    fn sign(
+        &self,
+        secp: &Secp256k1<All>,
+        punish_tx: &Transaction,
+        sk: &SecretKey,
+    ) -> Result<(bitcoin::PublicKey, EcdsaSignature), Error> {
+        let sig = match self.revoked_tx_type {
+            RevokedTxType::Buffer => ddk_dlc::channel::get_punish_buffer_signature(
+                secp,
+                &self.offer_revoke_params,
+                &self.accept_revoke_params,
+                punish_tx,
+                self.revoked_value,
+                sk,
+            )?,
+            RevokedTxType::Settle => ddk_dlc::channel::get_punish_settle_signature(
+                secp,
+                &self.offer_revoke_params,
+                &self.accept_revoke_params,
+                punish_tx,
+                self.revoked_value,
+                sk,
+                CET_NSEQUENCE,
+                self.is_offer,
+            )?,
+        };
+        Ok((bitcoin::PublicKey::new(sk.public_key(secp)), sig))
+    }
+
+    fn finalize(
+        &self,
+        punish_tx: &mut Transaction,
+        signatures: &[(bitcoin::PublicKey, EcdsaSignature)],
+    ) -> Result<(), Error> {
+        match self.revoked_tx_type {
+            RevokedTxType::Buffer => ddk_dlc::channel::sign_punish_buffer_transaction(
+                &self.offer_revoke_params,
+                &self.accept_revoke_params,
+                punish_tx,
+                signatures,
+            )?,
+            RevokedTxType::Settle => ddk_dlc::channel::sign_punish_settle_transaction(
+                &self.offer_revoke_params,
+                &self.accept_revoke_params,
+                punish_tx,
+                signatures,
+                CET_NSEQUENCE,
+                self.is_offer,
+            )?,
+        }
+        Ok(())
+    }
+
+    fn own_revoke_params(&self) -> &RevokeParams {
+        if self.is_offer {
+            &self.offer_revoke_params
+        } else {
+            &self.accept_revoke_params
+        }
+    }
+
+    fn counter_revoke_params(&self) -> &RevokeParams {
+        if self.is_offer {
+            &self.accept_revoke_params
+        } else {
+            &self.offer_revoke_params
+        }
+    }
+}
+
+impl JusticeData {
+    /// Signs the punishment of the revoked transaction `revoked_txid` with
+    /// `own_sk`, the key of our own branch of the revoked output, and returns
+    /// the data without the key.
+    pub(crate) fn new(
+        secp: &Secp256k1<All>,
+        punish_params: PunishParams,
+        revoked_txid: Txid,
+        own_sk: &SecretKey,
+        own_adaptor_signature: EcdsaAdaptorSignature,
+        own_signature_first: bool,
+        counter_revocation_sk: SecretKey,
+    ) -> Result<Self, Error> {
+        let punish_tx = punish_params.transaction(revoked_txid)?;
+        let (_, own_punish_signature) = punish_params.sign(secp, &punish_tx, own_sk)?;
+
+        Ok(JusticeData {
+            punish_params,
+            own_adaptor_signature,
+            own_signature_first,
+            own_punish_signature: own_punish_signature.signature,
+            counter_revocation_sk,
+        })
+    }
+
+    /// Builds and signs the transaction claiming the outputs of `revoked_tx`,
+    /// paying them to the destination chosen when the data was created.
+    ///
+    /// The secret key of the counter party is recovered from the signature
+    /// of the revoked transaction, so it must be the transaction the data was
+    /// created for.
+    pub fn punish_transaction(
+        &self,
+        secp: &Secp256k1<All>,
+        revoked_tx: &Transaction,
+    ) -> Result<Transaction, Error> {
+        let witness_index = if self.own_signature_first { 1 } else { 2 };
+        let witness = revoked_tx
+            .input
+            .first()
+            .and_then(|input| input.witness.nth(witness_index))
+            .filter(|witness| !witness.is_empty())
+            .ok_or_else(|| {
+                Error::InvalidParameters(
+                    "Revoked transaction does not carry our signature".to_string(),
+                )
+            })?;
+        // The last byte is the sighash type.
+        let own_sig = Signature::from_der(&witness[..witness.len() - 1])?;
+
+        let params = &self.punish_params;
+        let counter_sk = self.own_adaptor_signature.recover(
+            secp,
+            &own_sig,
+            &params.counter_revoke_params().publish_pk.inner,
+        )?;
+
+        let mut punish_tx = params.transaction(revoked_tx.compute_txid())?;
+        let signatures = [
+            (
+                params.own_revoke_params().own_pk,
+                EcdsaSignature::sighash_all(self.own_punish_signature),
+            ),
+            params.sign(secp, &punish_tx, &counter_sk)?,
+            params.sign(secp, &punish_tx, &self.counter_revocation_sk)?,
+        ];
+        params.finalize(&mut punish_tx, &signatures)?;
+
+        Ok(punish_tx)
+    }
+
+    /// Seals the data for a watchtower, to be opened with the id of the
+    /// revoked transaction.
+    pub fn encrypt(&self, revoked_txid: &Txid) -> EncryptedJusticeBlob {
+        let plaintext = self.encode();
+        let key = blob_key(revoked_txid);
+
+        // A key belongs to a single revoked transaction, but the data sealed
+        // for it changes between exports. Deriving the nonce from the data
+        // keeps a nonce from being used twice with the same key.
+        let mut engine = sha256::Hash::engine();
+        engine.input(&key);
+        engine.input(&plaintext);
+        let nonce = sha256::Hash::from_engine(engine).to_byte_array();
+        let nonce = Nonce::from_slice(&nonce[..NONCE_LEN]);
+
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+            .encrypt(nonce, plaintext.as_slice())
+            .expect("encrypting an in memory buffer to succeed");
+
+        let mut blob = nonce.to_vec();
+        blob.extend(ciphertext);
+        EncryptedJusticeBlob {
+            hint: justice_hint(revoked_txid),
+            blob,
+        }
+    }
+}
+
+/// [`JusticeData`] sealed for a watchtower.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct EncryptedJusticeBlob {
+    /// The first [`JUSTICE_HINT_LEN`] bytes of the id of the revoked
+    /// transaction.
+    pub hint: [u8; JUSTICE_HINT_LEN],
+    /// The nonce followed by the encrypted data.
+    pub blob: Vec<u8>,
+}
+
+impl_dlc_writeable!(EncryptedJusticeBlob, { (hint, writeable), (blob, vec) });
+
+impl EncryptedJusticeBlob {
+    /// Returns whether the blob is filed under the hint of `txid`.
+    pub fn matches(&self, txid: &Txid) -> bool {
+        self.hint == justice_hint(txid)
+    }
+
+    /// Opens the blob with the id of the revoked transaction it was sealed for.
+    pub fn decrypt(&self, revoked_txid: &Txid) -> Result<JusticeData, Error> {
+        if !self.matches(revoked_txid) || self.blob.len() < NONCE_LEN {
+            return Err(Error::InvalidParameters(
+                "Justice blob was not sealed for this transaction".to_string(),
+            ));
+        }
+
+        let (nonce, ciphertext) = self.blob.split_at(NONCE_LEN);
+        let plaintext = ChaCha20Poly1305::new(Key::from_slice(&blob_key(revoked_txid)))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                Error::InvalidParameters(
+                    "Justice blob was not sealed for this transaction".to_string(),
+                )
+            })?;
+
+        JusticeData::read(&mut lightning::io::Cursor::new(&plaintext))
+            .map_err(|e| Error::InvalidParameters(format!("Invalid justice data: {e}")))
+    }
+}
+
+/// Returns the hint under which the blobs of the revoked transaction `txid`
+/// are filed.
+pub fn justice_hint(txid: &Txid) -> [u8; JUSTICE_HINT_LEN] {
+    let mut hint = [0u8; JUSTICE_HINT_LEN];
+    hint.copy_from_slice(&txid.as_byte_array()[..JUSTICE_HINT_LEN]);
+    hint
+}
+
+fn blob_key(txid: &Txid) -> [u8; 32] {
+    sha256::Hash::hash(txid.as_byte_array()).to_byte_array()
+}
+
+fn write_revoke_params<W: Writer>(
+    params: &RevokeParams,
+    writer: &mut W,
+) -> Result<(), lightning::io::Error> {
+    params.own_pk.inner.write(writer)?;
+    params.publish_pk.inner.write(writer)?;
+    params.revoke_pk.inner.write(writer)
+}
+
+fn read_revoke_params<R: Read>(reader: &mut R) -> Result<RevokeParams, DecodeError> {
+    let own_pk: PublicKey = Readable::read(reader)?;
+    let publish_pk: PublicKey = Readable::read(reader)?;
+    let revoke_pk: PublicKey = Readable::read(reader)?;
+    Ok(RevokeParams {
+        own_pk: bitcoin::PublicKey::new(own_pk),
+        publish_pk: bitcoin::PublicKey::new(publish_pk),
+        revoke_pk: bitcoin::PublicKey::new(revoke_pk),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::sighash::Prevouts;
+    use bitcoin::{Network, OutPoint, TxIn, Witness};
+    use ddk_dlc::channel::{
+        buffer_descriptor, create_buffer_transaction, create_settle_transaction,
+    };
+    use secp256k1_zkp::rand::thread_rng;
+    use secp256k1_zkp::Message;
+
+    struct RevokeKeys {
+        own_sk: SecretKey,
+        publish_sk: SecretKey,
+        revoke_sk: SecretKey,
+    }
+
+    impl RevokeKeys {
+        fn new() -> Self {
+            RevokeKeys {
+                own_sk: SecretKey::new(&mut thread_rng()),
+                publish_sk: SecretKey::new(&mut thread_rng()),
+                revoke_sk: SecretKey::new(&mut thread_rng()),
+            }
+        }
+
+        fn params(&self, secp: &Secp256k1<All>) -> RevokeParams {
+            RevokeParams {
+                own_pk: bitcoin::PublicKey::new(self.own_sk.public_key(secp)),
+                publish_pk: bitcoin::PublicKey::new(self.publish_sk.public_key(secp)),
+                revoke_pk: bitcoin::PublicKey::new(self.revoke_sk.public_key(secp)),
+            }
+        }
+    }
+
+    fn destination(secp: &Secp256k1<All>) -> Address {
+        let (_, pk) = secp.generate_keypair(&mut thread_rng());
+        Address::p2wpkh(&bitcoin::CompressedPublicKey(pk), Network::Regtest)
+    }
+
+    fn justice_data() -> JusticeData {
+        let secp = Secp256k1::new();
+        let revoke_params = RevokeKeys::new().params(&secp);
+        let (adaptor_sk, adaptor_pk) = secp.generate_keypair(&mut thread_rng());
+        let own_adaptor_signature = EcdsaAdaptorSignature::encrypt(
+            &secp,
+            &Message::from_digest([1; 32]),
+            &adaptor_sk,
+            &adaptor_pk,
+        );
+        let punish_params = PunishParams {
+            revoked_tx_type: RevokedTxType::Settle,
+            is_offer: true,
+            offer_revoke_params: revoke_params.clone(),
+            accept_revoke_params: revoke_params,
+            revoked_value: Amount::from_sat(100_000),
+            destination: destination(&secp),
+            fee_rate_per_vb: 5,
+        };
+
+        JusticeData::new(
+            &secp,
+            punish_params,
+            Txid::from_byte_array([7; 32]),
+            &SecretKey::from_slice(&[2; 32]).unwrap(),
+            own_adaptor_signature,
+            false,
+            SecretKey::from_slice(&[3; 32]).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn blob_opens_only_with_the_revoked_txid() {
+        let data = justice_data();
+        let txid = Txid::from_byte_array([7; 32]);
+        let blob = data.encrypt(&txid);
+
+        assert!(blob.matches(&txid));
+        assert_eq!(data.encode(), blob.decrypt(&txid).unwrap().encode());
+
+        // Same hint, different transaction.
+        let mut other = [7; 32];
+        other[31] = 8;
+        let other = Txid::from_byte_array(other);
+        assert!(blob.matches(&other));
+        assert!(blob.decrypt(&other).is_err());
+        assert!(blob.decrypt(&Txid::from_byte_array([9; 32])).is_err());
+    }
+
+    /// The counter party publishes a revoked transaction. Whoever holds the
+    /// blob builds from it a punishment that spends the revoked output to our
+    /// destination, although the blob holds none of our secret keys.
+    fn revoked_broadcast_is_punished(revoked_tx_type: RevokedTxType) {
+        let secp = Secp256k1::new();
+        let own_fund_sk = SecretKey::new(&mut thread_rng());
+        let counter_fund_sk = SecretKey::new(&mut thread_rng());
+        let own_keys = RevokeKeys::new();
+        let counter_keys = RevokeKeys::new();
+        let offer_params = own_keys.params(&secp);
+        let accept_params = counter_keys.params(&secp);
+
+        let fund_value = Amount::from_sat(200_000);
+        let fund_script = ddk_dlc::make_funding_redeemscript(
+            &own_fund_sk.public_key(&secp),
+            &counter_fund_sk.public_key(&secp),
+        );
+        let fund_input = TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+            ..Default::default()
+        };
+        let mut revoked_tx = match revoked_tx_type {
+            RevokedTxType::Buffer => create_buffer_transaction(
+                &fund_input,
+                &buffer_descriptor(&offer_params, &accept_params),
+                fund_value - Amount::from_sat(1_000),
+                0,
+            ),
+            RevokedTxType::Settle => create_settle_transaction(
+                &fund_input,
+                &offer_params,
+                &accept_params,
+                Amount::from_sat(60_000),
+                Amount::from_sat(120_000),
+                CET_NSEQUENCE,
+                0,
+                fund_value,
+                2,
+            )
+            .unwrap(),
+        };
+        let revoked_txid = revoked_tx.compute_txid();
+
+        // We handed the counter party an adaptor signature of the revoked
+        // transaction, which only its publish key completes.
+        let own_adaptor_signature = ddk_dlc::channel::get_tx_adaptor_signature(
+            &secp,
+            &revoked_tx,
+            fund_value,
+            &fund_script,
+            &own_fund_sk,
+            &counter_keys.params(&secp).publish_pk.inner,
+        )
+        .unwrap();
+        let own_signature_first = own_fund_sk.public_key(&secp) < counter_fund_sk.public_key(&secp);
+
+        let punish_params = PunishParams {
+            revoked_tx_type,
+            is_offer: true,
+            offer_revoke_params: offer_params,
+            accept_revoke_params: accept_params,
+            revoked_value: revoked_tx_type.revoked_value(&revoked_tx, true),
+            destination: destination(&secp),
+            fee_rate_per_vb: 2,
+        };
+        let blob = JusticeData::new(
+            &secp,
+            punish_params.clone(),
+            revoked_txid,
+            &own_keys.own_sk,
+            own_adaptor_signature,
+            own_signature_first,
+            counter_keys.revoke_sk,
+        )
+        .unwrap()
+        .encrypt(&revoked_txid);
+        assert!(!blob
+            .blob
+            .windows(32)
+            .any(|w| w == own_keys.own_sk.secret_bytes()));
+
+        // The counter party publishes the revoked state.
+        let own_sig = own_adaptor_signature
+            .decrypt(&counter_keys.publish_sk)
+            .unwrap();
+        let counter_sig = ddk_dlc::util::get_raw_sig_for_tx_input(
+            &secp,
+            &revoked_tx,
+            0,
+            &fund_script,
+            fund_value,
+            &counter_fund_sk,
+        )
+        .unwrap();
+        let (first, second) = if own_signature_first {
+            (own_sig, counter_sig)
+        } else {
+            (counter_sig, own_sig)
+        };
+        revoked_tx.input[0].witness = Witness::from_slice(&[
+            Vec::new(),
+            EcdsaSignature::sighash_all(first).to_vec(),
+            EcdsaSignature::sighash_all(second).to_vec(),
+            fund_script.to_bytes(),
+        ]);
+
+        let punish_tx = blob
+            .decrypt(&revoked_tx.compute_txid())
+            .unwrap()
+            .punish_transaction(&secp, &revoked_tx)
+            .unwrap();
+
+        let vout = match revoked_tx_type {
+            RevokedTxType::Buffer => 0,
+            RevokedTxType::Settle => 1,
+        };
+        assert_eq!(
+            OutPoint::new(revoked_txid, vout),
+            punish_tx.input[0].previous_output
+        );
+        assert_eq!(
+            punish_params.destination.script_pubkey(),
+            punish_tx.output[0].script_pubkey
+        );
+
+        let spent = &revoked_tx.output[vout as usize];
+        assert!(satisfies(&secp, &punish_tx, spent));
+
+        // Our signature commits to the destination.
+        let mut redirected = punish_tx.clone();
+        redirected.output[0].script_pubkey = destination(&secp).script_pubkey();
+        assert!(!satisfies(&secp, &redirected, spent));
+    }
+
+    fn satisfies(secp: &Secp256k1<All>, tx: &Transaction, spent: &bitcoin::TxOut) -> bool {
+        let input = &tx.input[0];
+        let interpreter = miniscript::Interpreter::from_txdata(
+            &spent.script_pubkey,
+            &input.script_sig,
+            &input.witness,
+            input.sequence,
+            tx.lock_time,
+        )
+        .unwrap();
+        let prevouts = [spent.clone()];
+        let prevouts = Prevouts::All(&prevouts);
+        let satisfied = interpreter
+            .iter(secp, tx, 0, &prevouts)
+            .collect::<Result<Vec<_>, _>>();
+        matches!(satisfied, Ok(constraints) if !constraints.is_empty())
+    }
+
+    #[test]
+    fn revoked_buffer_broadcast_is_punished() {
+        revoked_broadcast_is_punished(RevokedTxType::Buffer);
+    }
+
+    #[test]
+    fn revoked_settle_broadcast_is_punished() {
+        revoked_broadcast_is_punished(RevokedTxType::Settle);
+    }
+}
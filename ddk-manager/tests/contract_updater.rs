@@ -29,8 +29,14 @@ async fn accept_contract_test() {
     let blockchain = test_utils::esplora_client(&env, logger.clone());
 
     let amount = Amount::from_btc(2.1).unwrap();
-    let stuff =
-        test_utils::create_and_fund_wallet(&env, logger.clone(), blockchain.clone(), amount).await;
+    let stuff = test_utils::create_and_fund_wallet(
+        &env,
+        logger.clone(),
+        blockchain.clone(),
+        test_utils::random_seed(),
+        amount,
+    )
+    .await;
     let wallet = Arc::new(stuff.0);
     wallet.sync().await.unwrap();
 
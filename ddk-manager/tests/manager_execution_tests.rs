@@ -2,8 +2,9 @@
 #[allow(dead_code)]
 mod test_utils;
 
-use bitcoin::Amount;
+use bitcoin::{Amount, Network};
 use bitcoincore_rpc::Client;
+use ddk::backup::StaticBackup;
 use ddk::chain::EsploraClient;
 use ddk::logger::Logger;
 use ddk::oracle::memory::MemoryOracle;
@@ -22,6 +23,7 @@ use ddk_manager::{
 use ddk_messages::oracle_msgs::OracleAttestation;
 use ddk_messages::{AcceptDlc, OfferDlc, SignDlc};
 use ddk_messages::{CetAdaptorSignatures, Message};
+use lightning::chain::chaininterface::{ConfirmationTarget, FeeEstimator};
 use lightning::ln::wire::Type;
 use lightning::util::ser::Writeable;
 use secp256k1_zkp::rand::{thread_rng, RngCore};
@@ -189,6 +191,21 @@ enum TestPath {
     Refund,
     ManualRefund,
     CooperativeClose,
+    /// Cooperatively close with the close offer received as a message and
+    /// accepted later.
+    ReceivedCooperativeClose,
+    /// Close an anchor output contract and bump the fee of its CET with a
+    /// child transaction.
+    BumpClosingFee,
+    /// Lose the store of the offer party once the contract confirmed, and
+    /// refund it from a static backup on a new node with the same seed.
+    BackupRefund,
+    /// Refund an anchor output contract and bump the fee of the refund
+    /// transaction with a child transaction.
+    BumpRefundFee,
+    /// Replace the unconfirmed fund transaction with a higher fee one, asked
+    /// for by the offer party.
+    BumpFundingFee,
     /// Splice the funded contract, then settle whatever the last round
     /// produced.
     Splice(SplicePath),
@@ -300,6 +317,8 @@ struct TestContext {
     alice: Arc<Mutex<TestManager>>,
     bob_wallet: Arc<DlcDevKitWallet>,
     alice_wallet: Arc<DlcDevKitWallet>,
+    /// The seed of Bob's wallet, to start a node from a backup of his.
+    bob_seed: [u8; 64],
     electrs: Arc<EsploraClient>,
     sink: Arc<Client>,
     /// Carries what Bob sends to Alice.
@@ -1203,6 +1222,55 @@ async fn cooperative_close_multi_oracle_test() {
     .await;
 }
 
+#[tokio::test]
+#[ignore]
+async fn received_cooperative_close_test() {
+    manager_execution_test(
+        get_enum_test_params(1, 1, None).await,
+        TestPath::ReceivedCooperativeClose,
+        false,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn bump_funding_fee_test() {
+    manager_execution_test(
+        get_enum_test_params(1, 1, None).await,
+        TestPath::BumpFundingFee,
+        false,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn bump_closing_fee_test() {
+    let mut test_params = get_enum_test_params(1, 1, None).await;
+    test_params.contract_input.contract_flags |= ddk_dlc::ANCHOR_OUTPUTS_FLAG;
+    manager_execution_test(test_params, TestPath::BumpClosingFee, false).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn backup_refund_test() {
+    manager_execution_test(
+        get_enum_test_params(1, 1, None).await,
+        TestPath::BackupRefund,
+        false,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn bump_refund_fee_test() {
+    let mut test_params = get_enum_test_params(1, 1, None).await;
+    test_params.contract_input.contract_flags |= ddk_dlc::ANCHOR_OUTPUTS_FLAG;
+    manager_execution_test(test_params, TestPath::BumpRefundFee, false).await;
+}
+
 #[tokio::test]
 #[ignore]
 async fn cooperative_close_numerical_test() {
@@ -1295,8 +1363,15 @@ async fn manager_execution_test_inner(test_params: TestParams, path: TestPath, m
     let alice_sync_send = sync_send.clone();
     let bob_sync_send = sync_send;
     let amount = Amount::from_btc(2.1).unwrap();
-    let (bob_wallet, bob_storage, alice_wallet, alice_storage, sink_rpc) =
-        init_clients(&env, logger.clone(), electrs.clone(), amount, amount).await;
+    let bob_seed = random_seed();
+    let (bob_wallet, bob_storage, alice_wallet, alice_storage, sink_rpc) = init_clients(
+        &env,
+        logger.clone(),
+        electrs.clone(),
+        (bob_seed, amount),
+        (random_seed(), amount),
+    )
+    .await;
     let alice_wallet = Arc::new(alice_wallet);
     let bob_wallet = Arc::new(bob_wallet);
     let sink = Arc::new(sink_rpc);
@@ -1417,6 +1492,7 @@ async fn manager_execution_test_inner(test_params: TestParams, path: TestPath, m
         alice: alice_manager,
         bob_wallet: Arc::clone(&bob_wallet),
         alice_wallet: Arc::clone(&alice_wallet),
+        bob_seed,
         electrs: Arc::clone(&electrs),
         sink: Arc::clone(&sink),
         bob_send,
@@ -1475,6 +1551,10 @@ async fn manager_execution_test_inner(test_params: TestParams, path: TestPath, m
             fund_contract(&mut ctx, contract_id, accept_msg).await;
             cooperative_close_path(&mut ctx, contract_id).await
         }
+        TestPath::ReceivedCooperativeClose => {
+            fund_contract(&mut ctx, contract_id, accept_msg).await;
+            received_cooperative_close_path(&mut ctx, contract_id).await
+        }
         TestPath::Close => {
             fund_contract(&mut ctx, contract_id, accept_msg).await;
             close_path(&mut ctx, &test_params, contract_id, manual_close).await
@@ -1483,6 +1563,19 @@ async fn manager_execution_test_inner(test_params: TestParams, path: TestPath, m
             fund_contract(&mut ctx, contract_id, accept_msg).await;
             refund_path(&mut ctx, contract_id, &path, manual_close).await
         }
+        TestPath::BumpFundingFee => bump_funding_fee_path(&mut ctx, contract_id, accept_msg).await,
+        TestPath::BumpClosingFee => {
+            fund_contract(&mut ctx, contract_id, accept_msg).await;
+            bump_closing_fee_path(&mut ctx, &test_params, contract_id).await
+        }
+        TestPath::BackupRefund => {
+            fund_contract(&mut ctx, contract_id, accept_msg).await;
+            backup_refund_path(&mut ctx, &test_params, contract_id).await
+        }
+        TestPath::BumpRefundFee => {
+            fund_contract(&mut ctx, contract_id, accept_msg).await;
+            bump_refund_fee_path(&mut ctx, contract_id).await
+        }
         TestPath::Splice(splice) => {
             fund_contract(&mut ctx, contract_id, accept_msg).await;
             splice_path(&mut ctx, &test_params, contract_id, splice, manual_close).await
@@ -1642,6 +1735,292 @@ async fn close_path(
     }
 }
 
+/// Signs a contract, then replaces its fund transaction before it confirms
+/// with the request, accept and sign exchange of a funding fee bump.
+async fn bump_funding_fee_path(
+    ctx: &mut TestContext,
+    contract_id: ContractId,
+    accept_msg: AcceptDlc,
+) {
+    ctx.send(Party::Alice, Message::Accept(accept_msg)).await;
+    ctx.sync().await;
+    ctx.sync().await;
+    assert_contract_state!(ctx.bob, contract_id, Signed);
+    assert_contract_state!(ctx.alice, contract_id, Signed);
+
+    let Contract::Signed(signed) = ctx.contract(Party::Alice, &contract_id).await else {
+        panic!("Contract should be signed");
+    };
+    let fund = signed.accepted_contract.dlc_transactions.fund;
+    let bob = ctx.bob.lock().await;
+    let alice = ctx.alice.lock().await;
+
+    // Fee rates far above Alice's own estimate need her approval.
+    let (request, _) = bob
+        .bump_funding_fee(&contract_id, 10_000)
+        .await
+        .expect("Error asking for a funding fee bump");
+    assert!(alice
+        .on_dlc_message(&request, counter_party())
+        .await
+        .is_err());
+
+    let fee_rate = (ctx
+        .electrs
+        .get_est_sat_per_1000_weight(ConfirmationTarget::UrgentOnChainSweep)
+        / 250) as u64;
+    let (request, _) = bob
+        .bump_funding_fee(&contract_id, fee_rate)
+        .await
+        .expect("Error asking for a funding fee bump");
+    let accept = alice
+        .on_dlc_message(&request, counter_party())
+        .await
+        .expect("Error accepting the funding fee bump")
+        .expect("The accept party answers with its signatures");
+    assert!(matches!(accept, Message::BumpFundingAccept(_)));
+    // The bump waiting for the signatures of Bob survives a restart.
+    let stored = alice.get_store().get_manager_state().await.unwrap();
+    assert!(stored.is_some_and(|state| !state.is_empty()));
+
+    let sign = bob
+        .on_dlc_message(&accept, counter_party())
+        .await
+        .expect("Error signing the funding fee bump")
+        .expect("The offer party answers with its signatures");
+    assert!(alice
+        .on_dlc_message(&sign, counter_party())
+        .await
+        .expect("Error broadcasting the bumped fund transaction")
+        .is_none());
+    let stored = alice.get_store().get_manager_state().await.unwrap();
+    assert!(stored.is_some_and(|state| state.is_empty()));
+
+    let bumped = alice
+        .get_store()
+        .get_signed_contracts()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|c| c.accepted_contract.get_contract_id() != contract_id)
+        .expect("Bumped contract");
+    let bumped_id = bumped.accepted_contract.get_contract_id();
+    // Same inputs, less left in the outputs.
+    let output_value =
+        |fund: &bitcoin::Transaction| -> Amount { fund.output.iter().map(|o| o.value).sum() };
+    let bumped_fund = &bumped.accepted_contract.dlc_transactions.fund;
+    assert!(output_value(bumped_fund) < output_value(&fund));
+    drop(alice);
+    drop(bob);
+
+    ctx.sync_wallets().await;
+    ctx.mine(10).await;
+    periodic_check!(ctx.alice, bumped_id, Confirmed);
+    periodic_check!(ctx.bob, bumped_id, Confirmed);
+    // The replaced contract is deleted once the bumped one confirms.
+    assert!(ctx
+        .bob
+        .lock()
+        .await
+        .get_store()
+        .get_contract(&contract_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Closes a confirmed anchor output contract, then gets its CET confirmed by
+/// spending the anchor output.
+async fn bump_closing_fee_path(
+    ctx: &mut TestContext,
+    test_params: &TestParams,
+    contract_id: ContractId,
+) {
+    let attestations = get_attestations(test_params).await;
+    let bob = ctx.bob.lock().await;
+    // Bumps are only broadcast on request.
+    bob.set_max_closing_fee_rate(None).await;
+    let contract = bob
+        .close_confirmed_contract(&contract_id, attestations)
+        .await
+        .expect("Error closing contract");
+    let Contract::PreClosed(contract) = contract else {
+        panic!("Invalid contract state {:?}", contract);
+    };
+    let cet = contract.signed_cet;
+    let cet_txid = cet.compute_txid();
+    let (anchor_vout, _) =
+        ddk_dlc::anchor::get_anchor_output(&cet).expect("CET has an anchor output");
+
+    bob.periodic_check(false)
+        .await
+        .expect("Error during periodic check");
+    assert_eq!(bob.closing_fee_bump(&cet_txid).await, None);
+
+    let fee_rate = 20;
+    let child = bob
+        .bump_closing_fee(&contract_id, fee_rate)
+        .await
+        .expect("Error bumping the closing fee");
+    assert_eq!(
+        child.input[0].previous_output,
+        bitcoin::OutPoint::new(cet_txid, anchor_vout as u32)
+    );
+    assert!(child.input.len() > 1, "The child is funded by the wallet");
+    assert_eq!(bob.closing_fee_bump(&cet_txid).await, Some(fee_rate));
+    let stored = bob.get_store().get_manager_state().await.unwrap();
+    assert!(stored.is_some_and(|state| !state.is_empty()));
+    assert_eq!(
+        ctx.electrs
+            .get_transaction_confirmations(&child.compute_txid())
+            .await
+            .unwrap(),
+        ddk_manager::ConfirmationStatus::InMempool
+    );
+
+    // Only a higher fee rate replaces the child.
+    assert!(bob.bump_closing_fee(&contract_id, fee_rate).await.is_err());
+    drop(bob);
+
+    ctx.mine(10).await;
+    ctx.sync_wallets().await;
+    periodic_check!(ctx.bob, contract_id, Closed);
+    assert_eq!(ctx.bob.lock().await.closing_fee_bump(&cet_txid).await, None);
+}
+
+/// Restores Bob's confirmed contract from a static backup into an empty store,
+/// on a new wallet with the same seed, and checks the periodic check of the
+/// restored node refunds it.
+async fn backup_refund_path(
+    ctx: &mut TestContext,
+    test_params: &TestParams,
+    contract_id: ContractId,
+) {
+    let key = [7u8; 32];
+    let backup = {
+        let bob = ctx.bob.lock().await;
+        StaticBackup::from_storage(&**bob.get_store(), Network::Regtest)
+            .await
+            .expect("Error reading the backup")
+            .encrypt(&key)
+            .expect("Error encrypting the backup")
+    };
+
+    let (wallet, storage) = wallet_from_seed(
+        &ctx.bob_seed,
+        ctx.electrs.clone(),
+        Arc::new(Logger::disabled("restored".to_string())),
+    )
+    .await;
+    StaticBackup::decrypt(&backup, &key)
+        .expect("Error decrypting the backup")
+        .restore_into(&*storage)
+        .await
+        .expect("Error restoring the backup");
+    let wallet = Arc::new(wallet);
+    wallet.sync().await.unwrap();
+    let oracles = test_params
+        .oracles
+        .iter()
+        .map(|oracle| (oracle.get_public_key(), Arc::new(oracle.clone())))
+        .collect::<HashMap<_, _>>();
+    let restored = Manager::new(
+        Arc::clone(&wallet),
+        Arc::clone(&wallet),
+        Arc::clone(&ctx.electrs),
+        Arc::clone(&storage),
+        oracles,
+        Arc::new(test_utils::MockTime {}),
+        Arc::clone(&ctx.electrs),
+        Arc::new(Logger::disabled("restored".to_string())),
+    )
+    .await
+    .unwrap();
+
+    test_utils::set_time(((EVENT_MATURITY + ddk_manager::manager::REFUND_DELAY) as u64) + 1);
+    ctx.mine(10).await;
+    wallet.sync().await.unwrap();
+
+    restored
+        .periodic_check(false)
+        .await
+        .expect("Error during periodic check");
+    let Some(Contract::Refunded(contract)) = storage.get_contract(&contract_id).await.unwrap()
+    else {
+        panic!("The restored contract should be refunded");
+    };
+    let refund_txid = contract
+        .accepted_contract
+        .dlc_transactions
+        .refund
+        .compute_txid();
+    assert_eq!(
+        ctx.electrs
+            .get_transaction_confirmations(&refund_txid)
+            .await
+            .unwrap(),
+        ddk_manager::ConfirmationStatus::InMempool
+    );
+
+    ctx.mine(1).await;
+    assert!(
+        ctx.electrs
+            .get_transaction_confirmations(&refund_txid)
+            .await
+            .unwrap()
+            .confirmations()
+            > 0
+    );
+}
+
+/// Refunds a confirmed anchor output contract, bumps the fee of the refund and
+/// checks the bump is forgotten once the refund confirms.
+async fn bump_refund_fee_path(ctx: &mut TestContext, contract_id: ContractId) {
+    test_utils::set_time(((EVENT_MATURITY + ddk_manager::manager::REFUND_DELAY) as u64) + 1);
+    ctx.mine(10).await;
+    ctx.sync_wallets().await;
+
+    let bob = ctx.bob.lock().await;
+    // Bumps are only broadcast on request.
+    bob.set_max_closing_fee_rate(None).await;
+    bob.check_and_broadcast_refund(&contract_id)
+        .await
+        .expect("Error broadcasting refund");
+    let Contract::Refunded(contract) = bob
+        .get_store()
+        .get_contract(&contract_id)
+        .await
+        .unwrap()
+        .unwrap()
+    else {
+        panic!("Contract should be refunded");
+    };
+    let refund_txid = contract
+        .accepted_contract
+        .dlc_transactions
+        .refund
+        .compute_txid();
+
+    let fee_rate = 20;
+    let child = bob
+        .bump_closing_fee(&contract_id, fee_rate)
+        .await
+        .expect("Error bumping the refund fee");
+    assert_eq!(child.input[0].previous_output.txid, refund_txid);
+    assert_eq!(bob.closing_fee_bump(&refund_txid).await, Some(fee_rate));
+    drop(bob);
+
+    ctx.mine(10).await;
+    ctx.sync_wallets().await;
+    let bob = ctx.bob.lock().await;
+    bob.periodic_check(false)
+        .await
+        .expect("Error during periodic check");
+    assert_eq!(bob.closing_fee_bump(&refund_txid).await, None);
+    let stored = bob.get_store().get_manager_state().await.unwrap();
+    assert!(stored.is_none_or(|state| state.is_empty()));
+}
+
 /// Runs a confirmed contract past its refund locktime, either letting the
 /// periodic check broadcast the refund or broadcasting it by hand.
 async fn refund_path(
@@ -1769,6 +2148,44 @@ async fn cooperative_close_path(ctx: &mut TestContext, contract_id: ContractId)
     );
 }
 
+/// Settles a confirmed contract by agreement, the close offer reaching the
+/// counter party as a message it stores until accepting it.
+async fn received_cooperative_close_path(ctx: &mut TestContext, contract_id: ContractId) {
+    let counter_payout = Amount::from_sat(ACCEPT_COLLATERAL / 2);
+    let (close_msg, _counter_party_pubkey) = ctx
+        .alice
+        .lock()
+        .await
+        .cooperative_close_contract(&contract_id, counter_payout)
+        .await
+        .expect("Error initiating cooperative close");
+
+    {
+        let bob = ctx.bob.lock().await;
+        bob.on_dlc_message(&Message::Close(close_msg.clone()), counter_party())
+            .await
+            .expect("Error receiving cooperative close");
+        assert_eq!(
+            bob.get_received_close_offer(&contract_id).await,
+            Some(close_msg)
+        );
+        // The offer is persisted, so it survives a restart.
+        let stored = bob.get_store().get_manager_state().await.unwrap();
+        assert!(stored.is_some_and(|state| !state.is_empty()));
+        bob.accept_received_cooperative_close(&contract_id)
+            .await
+            .expect("Error accepting cooperative close");
+        assert!(bob.get_received_close_offer(&contract_id).await.is_none());
+        let stored = bob.get_store().get_manager_state().await.unwrap();
+        assert!(stored.is_some_and(|state| state.is_empty()));
+    }
+
+    periodic_check!(ctx.bob, contract_id, PreClosed);
+    ctx.mine(8).await;
+    periodic_check!(ctx.bob, contract_id, Closed);
+    periodic_check!(ctx.alice, contract_id, Closed);
+}
+
 /// Replaces a confirmed contract with one holding more or less collateral, by
 /// spending its funding output into the funding transaction of a new contract.
 ///
@@ -2114,3 +2531,181 @@ fn random_party() -> Party {
         Party::Bob
     }
 }
+
+/// Two contracts funded by a single fund transaction, from the offers to the
+/// confirmation of the fund transaction.
+#[tokio::test]
+#[ignore]
+async fn batch_funding_test() {
+    test_utils::on_big_stack(batch_funding_test_inner())
+}
+
+async fn batch_funding_test_inner() {
+    let logger = Arc::new(Logger::disabled("test_batch_funding".to_string()));
+    let env = test_utils::test_env();
+    let electrs = test_utils::esplora_client(&env, logger.clone());
+    let amount = Amount::from_btc(2.1).unwrap();
+    let (bob_wallet, bob_storage, alice_wallet, alice_storage, sink) = init_clients(
+        &env,
+        logger.clone(),
+        electrs.clone(),
+        (random_seed(), amount),
+        (random_seed(), amount),
+    )
+    .await;
+    let alice_wallet = Arc::new(alice_wallet);
+    let bob_wallet = Arc::new(bob_wallet);
+    let sink = Arc::new(sink);
+
+    let test_params = get_enum_test_params(1, 1, None).await;
+    let oracles = test_params
+        .oracles
+        .iter()
+        .map(|oracle| (oracle.get_public_key(), Arc::new(oracle.clone())))
+        .collect::<HashMap<_, _>>();
+    let mock_time = Arc::new(test_utils::MockTime {});
+    test_utils::set_time((EVENT_MATURITY as u64) - 1);
+    test_utils::generate_blocks(6, electrs.clone(), sink.clone()).await;
+    refresh_wallet(&alice_wallet, 2 * ACCEPT_COLLATERAL).await;
+    refresh_wallet(&bob_wallet, 2 * OFFER_COLLATERAL).await;
+
+    let alice = Mutex::new(
+        Manager::new(
+            Arc::clone(&alice_wallet),
+            Arc::clone(&alice_wallet),
+            Arc::clone(&electrs),
+            alice_storage,
+            oracles.clone(),
+            Arc::clone(&mock_time),
+            Arc::clone(&electrs),
+            logger.clone(),
+        )
+        .await
+        .unwrap(),
+    );
+    let bob = Mutex::new(
+        Manager::new(
+            Arc::clone(&bob_wallet),
+            Arc::clone(&bob_wallet),
+            Arc::clone(&electrs),
+            bob_storage,
+            oracles,
+            Arc::clone(&mock_time),
+            Arc::clone(&electrs),
+            logger,
+        )
+        .await
+        .unwrap(),
+    );
+
+    let contract_inputs = vec![test_params.contract_input.clone(); 2];
+    let offers = bob
+        .lock()
+        .await
+        .send_batch_offer(&contract_inputs, counter_party())
+        .await
+        .expect("Error sending the batch offer");
+    assert_eq!(offers.len(), 2);
+    for offer in &offers {
+        assert!(alice
+            .lock()
+            .await
+            .on_dlc_message(&Message::Offer(offer.clone()), counter_party())
+            .await
+            .expect("Error receiving a batch offer")
+            .is_none());
+    }
+
+    let (contract_ids, _, accepts) = alice
+        .lock()
+        .await
+        .accept_batch_contract_offer(&offers[1].temporary_contract_id)
+        .await
+        .expect("Error accepting the batch");
+    assert_eq!(contract_ids.len(), 2);
+
+    // Nothing is signed until an accept was received for every contract.
+    assert!(bob
+        .lock()
+        .await
+        .on_dlc_message(&Message::Accept(accepts[0].clone()), counter_party())
+        .await
+        .expect("Error receiving the first accept")
+        .is_none());
+    assert_contract_state!(bob, offers[0].temporary_contract_id, Offered);
+    let Some(Message::Sign(second_sign)) = bob
+        .lock()
+        .await
+        .on_dlc_message(&Message::Accept(accepts[1].clone()), counter_party())
+        .await
+        .expect("Error receiving the last accept")
+    else {
+        panic!("Expected the sign message of the last accepted contract");
+    };
+    // The sign message of the other contract waits to be sent.
+    let pending = bob.lock().await.get_and_clear_pending_messages().await;
+    assert_eq!(pending.len(), 1);
+    let (peer, Message::Sign(first_sign)) = &pending[0] else {
+        panic!("Expected a pending sign message");
+    };
+    assert_eq!(*peer, counter_party());
+    assert!(bob
+        .lock()
+        .await
+        .get_and_clear_pending_messages()
+        .await
+        .is_empty());
+    for contract_id in &contract_ids {
+        assert_contract_state!(bob, *contract_id, Signed);
+    }
+
+    // The fund transaction is only broadcast once both contracts are signed.
+    let Contract::Accepted(accepted) = alice
+        .lock()
+        .await
+        .get_store()
+        .get_contract(&first_sign.contract_id)
+        .await
+        .unwrap()
+        .unwrap()
+    else {
+        panic!("Expected an accepted contract");
+    };
+    let fund_txid = accepted.dlc_transactions.fund.compute_txid();
+    alice
+        .lock()
+        .await
+        .on_dlc_message(&Message::Sign(first_sign.clone()), counter_party())
+        .await
+        .expect("Error receiving the first sign message");
+    assert_eq!(
+        electrs
+            .get_transaction_confirmations(&fund_txid)
+            .await
+            .unwrap(),
+        ddk_manager::ConfirmationStatus::NotFound
+    );
+    alice
+        .lock()
+        .await
+        .on_dlc_message(&Message::Sign(second_sign), counter_party())
+        .await
+        .expect("Error receiving the last sign message");
+    assert_eq!(
+        electrs
+            .get_transaction_confirmations(&fund_txid)
+            .await
+            .unwrap(),
+        ddk_manager::ConfirmationStatus::InMempool
+    );
+
+    test_utils::generate_blocks(10, electrs.clone(), sink).await;
+    alice_wallet.sync().await.unwrap();
+    bob_wallet.sync().await.unwrap();
+    alice.lock().await.periodic_check(false).await.unwrap();
+    bob.lock().await.periodic_check(false).await.unwrap();
+    for contract_id in &contract_ids {
+        assert_contract_state!(alice, *contract_id, Confirmed);
+        assert_contract_state!(bob, *contract_id, Confirmed);
+    }
+}
@@ -7,8 +7,12 @@ use ddk::oracle::memory::MemoryOracle;
 use ddk::storage::memory::MemoryStorage;
 use ddk::wallet::DlcDevKitWallet;
 use ddk::{chain::EsploraClient, logger::Logger};
-use ddk_manager::{manager::Manager, CachedContractSignerProvider, Oracle, SimpleSigner};
-use ddk_messages::Message;
+use ddk_manager::contract::Contract;
+use ddk_manager::events::Event;
+use ddk_manager::{manager::Manager, CachedContractSignerProvider, Oracle, SimpleSigner, Storage};
+use ddk_messages::batch_msgs::BatchFundingGroup;
+use ddk_messages::channel::OfferChannel;
+use ddk_messages::{Message, OfferDlc};
 use secp256k1_zkp::{rand::Fill, PublicKey, XOnlyPublicKey};
 use std::{collections::HashMap, sync::Arc};
 use test_utils::{set_time, MockTime};
@@ -26,6 +30,19 @@ type TestManager = Manager<
 >;
 
 async fn get_manager(logger: Arc<Logger>) -> TestManager {
+    let mut seed = [0u8; 64];
+    seed.try_fill(&mut bitcoin::key::rand::thread_rng())
+        .unwrap();
+    set_time(0);
+    get_manager_with_store(logger, Arc::new(MemoryStorage::new()), seed).await
+}
+
+/// A manager with the wallet of `seed` on `store`, e.g. to restart one.
+async fn get_manager_with_store(
+    logger: Arc<Logger>,
+    store: Arc<MemoryStorage>,
+    seed: [u8; 64],
+) -> TestManager {
     // These tests reject offers before anything reaches the chain and never
     // mine, so the backends shared across this binary are enough.
     let blockchain = Arc::new(
@@ -36,10 +53,6 @@ async fn get_manager(logger: Arc<Logger>) -> TestManager {
         )
         .unwrap(),
     );
-    let store = Arc::new(MemoryStorage::new());
-    let mut seed = [0u8; 64];
-    seed.try_fill(&mut bitcoin::key::rand::thread_rng())
-        .unwrap();
     let wallet = Arc::new(
         DlcDevKitWallet::new(
             &seed,
@@ -60,8 +73,6 @@ async fn get_manager(logger: Arc<Logger>) -> TestManager {
         .collect();
     let time = Arc::new(MockTime {});
 
-    set_time(0);
-
     Manager::new(
         wallet.clone(),
         wallet.clone(),
@@ -121,3 +132,163 @@ async fn reject_channel_offer_with_existing_channel_id() {
         .await
         .expect_err("To reject the second offer message");
 }
+
+#[tokio::test]
+async fn received_offers_are_published_to_subscribers() {
+    let logger = Arc::new(Logger::disabled("test_manager".to_string()));
+    let offer: OfferDlc =
+        serde_json::from_str(include_str!("../test_inputs/offer_contract.json")).unwrap();
+    let offer_channel: OfferChannel =
+        serde_json::from_str(include_str!("../test_inputs/offer_channel.json")).unwrap();
+
+    let manager = get_manager(logger).await;
+    let mut events = manager.subscribe();
+
+    manager
+        .on_dlc_message(&Message::Offer(offer.clone()), pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        events.try_recv().unwrap(),
+        Event::OfferReceived {
+            contract_id: offer.temporary_contract_id,
+            counter_party: pubkey(),
+        }
+    );
+
+    manager
+        .on_dlc_message(&Message::OfferChannel(offer_channel.clone()), pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        events.try_recv().unwrap(),
+        Event::ChannelStateChanged {
+            channel_id: offer_channel.temporary_channel_id,
+            counter_party: pubkey(),
+            state: "offered",
+            signed_state: None,
+        }
+    );
+    assert!(events.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn batch_offers_are_accepted_together() {
+    let logger = Arc::new(Logger::disabled("test_manager".to_string()));
+    let mut offer: OfferDlc =
+        serde_json::from_str(include_str!("../test_inputs/offer_contract.json")).unwrap();
+    let mut second = offer.clone();
+    second.temporary_contract_id = [7; 32];
+    offer
+        .tlvs
+        .set(&BatchFundingGroup::new(&[offer.clone(), second]));
+
+    let manager = get_manager(logger).await;
+    manager
+        .on_dlc_message(&Message::Offer(offer.clone()), pubkey())
+        .await
+        .unwrap();
+
+    manager
+        .accept_contract_offer(&offer.temporary_contract_id)
+        .await
+        .expect_err("To reject accepting a batch funded contract on its own");
+    manager
+        .accept_batch_contract_offer(&offer.temporary_contract_id)
+        .await
+        .expect_err("To wait for every offer of the batch");
+}
+
+#[tokio::test]
+async fn unanswered_offers_expire_and_are_rejected() {
+    let logger = Arc::new(Logger::disabled("test_manager".to_string()));
+    let offer: OfferDlc =
+        serde_json::from_str(include_str!("../test_inputs/offer_contract.json")).unwrap();
+    let offer_channel: OfferChannel =
+        serde_json::from_str(include_str!("../test_inputs/offer_channel.json")).unwrap();
+
+    let manager = get_manager(logger).await;
+    manager.set_offer_ttl(10).await;
+    manager
+        .on_dlc_message(&Message::Offer(offer.clone()), pubkey())
+        .await
+        .unwrap();
+    manager
+        .on_dlc_message(&Message::OfferChannel(offer_channel.clone()), pubkey())
+        .await
+        .unwrap();
+    let mut events = manager.subscribe();
+
+    manager.periodic_check(false).await.unwrap();
+    set_time(9);
+    manager.periodic_check(false).await.unwrap();
+    assert!(manager.get_and_clear_pending_messages().await.is_empty());
+
+    set_time(10);
+    manager.periodic_check(false).await.unwrap();
+    let store = manager.get_store();
+    assert!(matches!(
+        store.get_contract(&offer.temporary_contract_id).await,
+        Ok(Some(Contract::Expired(_)))
+    ));
+    // The contract of the channel offer is left to the channel timeouts.
+    assert!(matches!(
+        store
+            .get_contract(&offer_channel.temporary_contract_id)
+            .await,
+        Ok(Some(Contract::Offered(_)))
+    ));
+    assert_eq!(
+        events.try_recv().unwrap(),
+        Event::OfferExpired {
+            contract_id: offer.temporary_contract_id,
+            counter_party: pubkey(),
+        }
+    );
+
+    let pending = manager.get_and_clear_pending_messages().await;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].0, pubkey());
+    assert!(matches!(
+        &pending[0].1,
+        Message::Reject(reject) if reject.channel_id == offer.temporary_contract_id
+    ));
+}
+
+#[tokio::test]
+async fn offers_expire_across_restarts() {
+    let logger = Arc::new(Logger::disabled("test_manager".to_string()));
+    let offer: OfferDlc =
+        serde_json::from_str(include_str!("../test_inputs/offer_contract.json")).unwrap();
+
+    let store = Arc::new(MemoryStorage::new());
+    let seed = [7; 64];
+    set_time(0);
+    let manager = get_manager_with_store(logger.clone(), store.clone(), seed).await;
+    manager
+        .on_dlc_message(&Message::Offer(offer.clone()), pubkey())
+        .await
+        .unwrap();
+    drop(manager);
+
+    // Each restart starts no new clock for the offers already received.
+    for now in [5, 9] {
+        set_time(now);
+        let manager = get_manager_with_store(logger.clone(), store.clone(), seed).await;
+        manager.set_offer_ttl(10).await;
+        manager.periodic_check(false).await.unwrap();
+        assert!(matches!(
+            store.get_contract(&offer.temporary_contract_id).await,
+            Ok(Some(Contract::Offered(_)))
+        ));
+    }
+
+    set_time(10);
+    let manager = get_manager_with_store(logger, store.clone(), seed).await;
+    manager.set_offer_ttl(10).await;
+    manager.periodic_check(false).await.unwrap();
+    assert!(matches!(
+        store.get_contract(&offer.temporary_contract_id).await,
+        Ok(Some(Contract::Expired(_)))
+    ));
+}
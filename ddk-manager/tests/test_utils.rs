@@ -744,8 +744,8 @@ pub async fn init_clients(
     env: &TestEnv,
     logger: Arc<Logger>,
     esplora: Arc<EsploraClient>,
-    offer_amount: Amount,
-    accept_amount: Amount,
+    (offer_seed, offer_amount): ([u8; 64], Amount),
+    (accept_seed, accept_amount): ([u8; 64], Amount),
 ) -> (
     DlcDevKitWallet,
     Arc<MemoryStorage>,
@@ -756,10 +756,22 @@ pub async fn init_clients(
     let sink_rpc = env.rpc();
 
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-    let offer_rpc =
-        create_and_fund_wallet(env, logger.clone(), esplora.clone(), offer_amount).await;
-    let accept_rpc =
-        create_and_fund_wallet(env, logger.clone(), esplora.clone(), accept_amount).await;
+    let offer_rpc = create_and_fund_wallet(
+        env,
+        logger.clone(),
+        esplora.clone(),
+        offer_seed,
+        offer_amount,
+    )
+    .await;
+    let accept_rpc = create_and_fund_wallet(
+        env,
+        logger.clone(),
+        esplora.clone(),
+        accept_seed,
+        accept_amount,
+    )
+    .await;
 
     let sink_address = sink_rpc
         .get_new_address(None, Some(AddressType::Bech32))
@@ -777,31 +789,46 @@ pub async fn init_clients(
     )
 }
 
-pub async fn create_and_fund_wallet(
-    env: &TestEnv,
-    logger: Arc<Logger>,
-    esplora: Arc<EsploraClient>,
-    amount: Amount,
-) -> (DlcDevKitWallet, Arc<MemoryStorage>) {
-    let sink_rpc = env.rpc();
-    let sink_address = sink_rpc
-        .get_new_address(None, None)
-        .unwrap()
-        .assume_checked();
+pub fn random_seed() -> [u8; 64] {
     let mut seed = [0u8; 64];
     seed.try_fill(&mut bitcoin::key::rand::thread_rng())
         .unwrap();
+    seed
+}
+
+/// Creates a wallet from `seed` with an empty store.
+pub async fn wallet_from_seed(
+    seed: &[u8; 64],
+    esplora: Arc<EsploraClient>,
+    logger: Arc<Logger>,
+) -> (DlcDevKitWallet, Arc<MemoryStorage>) {
     let memory_storage = Arc::new(MemoryStorage::new());
     let wallet = DlcDevKitWallet::new(
-        &seed,
+        seed,
         esplora,
         Network::Regtest,
         memory_storage.clone(),
         None,
-        logger.clone(),
+        logger,
     )
     .await
     .unwrap();
+    (wallet, memory_storage)
+}
+
+pub async fn create_and_fund_wallet(
+    env: &TestEnv,
+    logger: Arc<Logger>,
+    esplora: Arc<EsploraClient>,
+    seed: [u8; 64],
+    amount: Amount,
+) -> (DlcDevKitWallet, Arc<MemoryStorage>) {
+    let sink_rpc = env.rpc();
+    let sink_address = sink_rpc
+        .get_new_address(None, None)
+        .unwrap()
+        .assume_checked();
+    let (wallet, memory_storage) = wallet_from_seed(&seed, esplora, logger).await;
 
     let address = wallet.new_external_address().await.unwrap().address;
     if amount > Amount::ZERO {
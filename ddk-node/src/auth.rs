@@ -0,0 +1,449 @@
+//! Bearer token authentication for the gRPC server.
+//!
+//! Tokens are macaroon-style: an identifier followed by caveats restricting what the token is
+//! allowed to do, sealed with an HMAC chained over each caveat from a root key that never leaves
+//! the node. A token holder can attenuate a token by appending another caveat, but cannot widen
+//! it without the root key.
+//!
+//! On startup `ddk-node` writes an `admin.token` without caveats, and a `readonly.token`,
+//! `contracts.token`, `wallet.token` and `oracle.token` restricted to their permission, to the
+//! `auth` directory in its storage path.
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+use bitcoin::key::rand;
+use rand::Fill;
+use std::collections::BTreeSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+/// Identifier of the tokens minted by this version of `ddk-node`.
+const TOKEN_VERSION: &str = "v1";
+/// Caveat prefix restricting a token to a set of permissions.
+const ALLOW_CAVEAT: &str = "allow=";
+const AUTHORIZATION_HEADER: &str = "authorization";
+const GRPC_SERVICE_PREFIX: &str = "/ddkrpc.DdkRpc/";
+
+/// Client for `ddk-node` attaching a bearer token to every request.
+pub type AuthChannel = InterceptedService<Channel, BearerToken>;
+
+/// Scope of the gRPC methods a token is allowed to call. Every permission also grants
+/// [`Permission::ReadOnly`] access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Permission {
+    /// Query the node, wallet, contracts and channels, and subscribe to events.
+    ReadOnly,
+    /// Offer, accept and close contracts and channels, and connect to peers.
+    Contracts,
+    /// Generate addresses, sync and send funds from the wallet.
+    Wallet,
+    /// Create and sign oracle events.
+    Oracle,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 4] = [
+        Permission::ReadOnly,
+        Permission::Contracts,
+        Permission::Wallet,
+        Permission::Oracle,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::ReadOnly => "readonly",
+            Permission::Contracts => "contracts",
+            Permission::Wallet => "wallet",
+            Permission::Oracle => "oracle",
+        }
+    }
+
+    /// The permission required to call a gRPC method given its request path. Returns `None` for
+    /// methods the node does not know about.
+    pub fn required_for(path: &str) -> Option<Permission> {
+        let method = path.strip_prefix(GRPC_SERVICE_PREFIX)?;
+        let permission = match method {
+            "Info"
+            | "ListOffers"
+            | "WalletBalance"
+            | "GetWalletTransactions"
+            | "ListUtxos"
+            | "ListPeers"
+            | "ListOracles"
+            | "OracleHealth"
+            | "ListContracts"
+            | "GetContract"
+            | "OracleAnnouncements"
+            | "ListChannels"
+            | "SubscribeEvents" => Permission::ReadOnly,
+            "NewAddress" | "WalletSync" | "Sync" | "Send" => Permission::Wallet,
+            "CreateEnum" | "CreateNumeric" | "SignAnnouncement" => Permission::Oracle,
+            "SendOffer"
+            | "AcceptOffer"
+            | "ConnectPeer"
+            | "CooperativeClose"
+            | "AcceptCooperativeClose"
+            | "RefundContract"
+            | "BumpClosingFee"
+            | "BumpFundingFee"
+            | "CloseContract"
+            | "OfferChannel"
+            | "AcceptChannel"
+            | "SettleOffer"
+            | "AcceptSettleOffer"
+            | "RejectSettleOffer"
+            | "RenewOffer"
+            | "AcceptRenewOffer"
+            | "RejectRenewOffer"
+            | "CollaborativeClose"
+            | "AcceptCollaborativeClose"
+            | "ForceClose" => Permission::Contracts,
+            _ => return None,
+        };
+        Some(permission)
+    }
+}
+
+impl FromStr for Permission {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .into_iter()
+            .find(|p| p.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown permission {}", s))
+    }
+}
+
+/// Mints and verifies bearer tokens with the node's root key.
+pub struct Credentials {
+    root_key: [u8; 32],
+}
+
+impl Credentials {
+    pub fn new(root_key: [u8; 32]) -> Self {
+        Self { root_key }
+    }
+
+    /// Reads the root key from `auth/root.key` in the storage path, creating it if it does not
+    /// exist, and writes the default tokens next to it.
+    pub fn from_path(storage_path: &Path) -> anyhow::Result<Self> {
+        let auth_path = storage_path.join("auth");
+        std::fs::create_dir_all(&auth_path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&auth_path, std::fs::Permissions::from_mode(0o700))?;
+        }
+        let key_path = auth_path.join("root.key");
+        let root_key = if key_path.exists() {
+            let bytes = std::fs::read(&key_path)?;
+            bytes
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Invalid root key at {}", key_path.display()))?
+        } else {
+            let mut root_key = [0u8; 32];
+            root_key.try_fill(&mut rand::thread_rng())?;
+            write_secret(&key_path, root_key)?;
+            root_key
+        };
+        let credentials = Self::new(root_key);
+
+        write_secret(
+            &auth_path.join("admin.token"),
+            credentials.mint(&Permission::ALL),
+        )?;
+        for permission in Permission::ALL {
+            write_secret(
+                &auth_path.join(format!("{}.token", permission.as_str())),
+                credentials.mint(&[permission]),
+            )?;
+        }
+
+        Ok(credentials)
+    }
+
+    /// Mints a token allowed to use the given permissions. A token with every permission is
+    /// minted without caveats.
+    pub fn mint(&self, permissions: &[Permission]) -> String {
+        let permissions = permissions.iter().copied().collect::<BTreeSet<_>>();
+        let mut parts = vec![TOKEN_VERSION.to_string()];
+        if permissions.len() < Permission::ALL.len() {
+            let allowed = permissions
+                .iter()
+                .map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(",");
+            parts.push(format!("{}{}", ALLOW_CAVEAT, allowed));
+        }
+        let signature = self.signature(&parts);
+        parts.push(hex::encode(signature));
+        parts.join(".")
+    }
+
+    /// Verifies the signature of a token and returns the permissions its caveats allow. Returns
+    /// `None` if the token was not minted with this root key or has a caveat the node does not
+    /// understand.
+    pub fn verify(&self, token: &str) -> Option<BTreeSet<Permission>> {
+        let mut parts = token.split('.').map(str::to_string).collect::<Vec<_>>();
+        let signature = hex::decode(parts.pop()?).ok()?;
+        if parts.first().map(String::as_str) != Some(TOKEN_VERSION) {
+            return None;
+        }
+        if !constant_time_eq(&self.signature(&parts), &signature) {
+            return None;
+        }
+
+        let mut allowed = Permission::ALL.into_iter().collect::<BTreeSet<_>>();
+        for caveat in &parts[1..] {
+            let permissions = caveat
+                .strip_prefix(ALLOW_CAVEAT)?
+                .split(',')
+                .map(Permission::from_str)
+                .collect::<Result<BTreeSet<_>, _>>()
+                .ok()?;
+            allowed = allowed.intersection(&permissions).copied().collect();
+        }
+        Some(allowed)
+    }
+
+    /// Checks that the `authorization` header of a request to the given gRPC method carries a
+    /// token allowed to call it.
+    pub fn authorize(&self, path: &str, authorization: Option<&str>) -> Result<(), Status> {
+        let required = Permission::required_for(path)
+            .ok_or_else(|| Status::permission_denied(format!("Unknown method {}", path)))?;
+        let token = authorization
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("Missing bearer token."))?;
+        let allowed = self
+            .verify(token.trim())
+            .ok_or_else(|| Status::unauthenticated("Invalid bearer token."))?;
+
+        let permitted = allowed.contains(&required)
+            || (required == Permission::ReadOnly && !allowed.is_empty());
+        if !permitted {
+            return Err(Status::permission_denied(format!(
+                "Token does not grant the {} permission required for {}",
+                required.as_str(),
+                path
+            )));
+        }
+        Ok(())
+    }
+
+    fn signature(&self, parts: &[String]) -> [u8; 32] {
+        parts.iter().fold(self.root_key, |key, part| {
+            let mut engine = hmac::HmacEngine::<sha256::Hash>::new(&key);
+            engine.input(part.as_bytes());
+            hmac::Hmac::<sha256::Hash>::from_engine(engine).to_byte_array()
+        })
+    }
+}
+
+/// Writes a file only the owner can read, such as a key or a token.
+pub(crate) fn write_secret(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    // The mode only applies to new files. Also restrict the ones written by earlier versions.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents.as_ref())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Server interceptor rejecting requests without a token allowed to call the requested method.
+/// Every request is let through if no credentials are given.
+///
+/// Tonic interceptors do not see the request path, so this is applied as a tower filter on the
+/// server.
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    credentials: Option<Arc<Credentials>>,
+}
+
+impl AuthInterceptor {
+    pub fn new(credentials: Option<Arc<Credentials>>) -> Self {
+        Self { credentials }
+    }
+}
+
+impl<B> tower::filter::Predicate<http::Request<B>> for AuthInterceptor {
+    type Request = http::Request<B>;
+
+    fn check(&mut self, request: http::Request<B>) -> Result<Self::Request, tower::BoxError> {
+        let Some(credentials) = &self.credentials else {
+            return Ok(request);
+        };
+        let authorization = request
+            .headers()
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|header| header.to_str().ok());
+        credentials
+            .authorize(request.uri().path(), authorization)
+            .map_err(|status| {
+                tracing::warn!(path = request.uri().path(), %status, "Rejected gRPC request.");
+                Box::new(status) as tower::BoxError
+            })?;
+        Ok(request)
+    }
+}
+
+/// Client interceptor attaching a bearer token to every request.
+#[derive(Clone)]
+pub struct BearerToken(Option<MetadataValue<Ascii>>);
+
+impl BearerToken {
+    pub fn new(token: Option<&str>) -> anyhow::Result<Self> {
+        let header = token
+            .map(|token| format!("Bearer {}", token.trim()).parse())
+            .transpose()?;
+        Ok(Self(header))
+    }
+}
+
+impl Interceptor for BearerToken {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(header) = &self.0 {
+            request
+                .metadata_mut()
+                .insert(AUTHORIZATION_HEADER, header.clone());
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials() -> Credentials {
+        Credentials::new([7u8; 32])
+    }
+
+    fn bearer(token: &str) -> String {
+        format!("Bearer {}", token)
+    }
+
+    #[test]
+    fn scoped_tokens_only_reach_their_methods() {
+        let credentials = credentials();
+        let wallet = bearer(&credentials.mint(&[Permission::Wallet]));
+        let readonly = bearer(&credentials.mint(&[Permission::ReadOnly]));
+        let admin = bearer(&credentials.mint(&Permission::ALL));
+
+        assert!(credentials
+            .authorize("/ddkrpc.DdkRpc/Send", Some(&wallet))
+            .is_ok());
+        assert!(credentials
+            .authorize("/ddkrpc.DdkRpc/Info", Some(&wallet))
+            .is_ok());
+        let denied = credentials
+            .authorize("/ddkrpc.DdkRpc/SignAnnouncement", Some(&wallet))
+            .unwrap_err();
+        assert_eq!(denied.code(), tonic::Code::PermissionDenied);
+        assert!(credentials
+            .authorize("/ddkrpc.DdkRpc/Send", Some(&readonly))
+            .is_err());
+        assert!(credentials
+            .authorize("/ddkrpc.DdkRpc/SubscribeEvents", Some(&readonly))
+            .is_ok());
+        for method in ["Send", "SendOffer", "SignAnnouncement", "ListContracts"] {
+            let path = format!("{}{}", GRPC_SERVICE_PREFIX, method);
+            assert!(credentials.authorize(&path, Some(&admin)).is_ok());
+        }
+        assert!(credentials
+            .authorize("/ddkrpc.DdkRpc/Unknown", Some(&admin))
+            .is_err());
+    }
+
+    #[test]
+    fn forged_and_widened_tokens_are_rejected() {
+        let credentials = credentials();
+        let missing = credentials
+            .authorize("/ddkrpc.DdkRpc/Info", None)
+            .unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::Unauthenticated);
+
+        let forged = Credentials::new([8u8; 32]).mint(&Permission::ALL);
+        assert!(credentials.verify(&forged).is_none());
+
+        // Dropping or rewriting the caveat invalidates the signature.
+        let readonly = credentials.mint(&[Permission::ReadOnly]);
+        let widened = readonly.replace("allow=readonly", "allow=readonly,wallet");
+        assert!(credentials.verify(&widened).is_none());
+        let signature = readonly.rsplit('.').next().unwrap();
+        assert!(credentials
+            .verify(&format!("{}.{}", TOKEN_VERSION, signature))
+            .is_none());
+    }
+
+    #[test]
+    fn appending_a_caveat_attenuates_a_token() {
+        let credentials = credentials();
+        let admin = credentials.mint(&Permission::ALL);
+        let (unsealed, signature) = admin.rsplit_once('.').unwrap();
+
+        let caveat = "allow=oracle".to_string();
+        let mut engine = hmac::HmacEngine::<sha256::Hash>::new(&hex::decode(signature).unwrap());
+        engine.input(caveat.as_bytes());
+        let attenuated = format!(
+            "{}.{}.{}",
+            unsealed,
+            caveat,
+            hex::encode(hmac::Hmac::<sha256::Hash>::from_engine(engine).to_byte_array())
+        );
+
+        let allowed = credentials.verify(&attenuated).unwrap();
+        assert_eq!(allowed, BTreeSet::from([Permission::Oracle]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn key_and_tokens_are_only_readable_by_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut suffix = [0u8; 8];
+        suffix.try_fill(&mut rand::thread_rng()).unwrap();
+        let storage_path =
+            std::env::temp_dir().join(format!("ddk_node_auth_{}", hex::encode(suffix)));
+        let auth_path = storage_path.join("auth");
+        // A token left world readable by an earlier version is restricted too.
+        std::fs::create_dir_all(&auth_path).unwrap();
+        std::fs::write(auth_path.join("admin.token"), "stale").unwrap();
+        std::fs::set_permissions(
+            auth_path.join("admin.token"),
+            std::fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+
+        Credentials::from_path(&storage_path).unwrap();
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&auth_path), 0o700);
+        assert_eq!(mode(&auth_path.join("root.key")), 0o600);
+        assert_eq!(mode(&auth_path.join("admin.token")), 0o600);
+        for permission in Permission::ALL {
+            let token = auth_path.join(format!("{}.token", permission.as_str()));
+            assert_eq!(mode(&token), 0o600);
+        }
+        std::fs::remove_dir_all(&storage_path).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+//! Storage and transport backends `ddk-node` can be configured with.
+//!
+//! [`DlcDevKit`](ddk::DlcDevKit) is generic over its storage and transport. The node picks them
+//! at startup from its configuration, so these enums dispatch to the selected backend.
+use async_trait::async_trait;
+use bdk_wallet::ChangeSet;
+use bitcoin::secp256k1::PublicKey;
+use ddk::error::{TransportError, WalletError};
+use ddk::storage::memory::MemoryStorage;
+use ddk::storage::postgres::PostgresStore;
+use ddk::storage::sled::SledStorage;
+use ddk::transport::lightning::LightningTransport;
+use ddk::transport::nostr::NostrDlc;
+use ddk::wallet::ChannelKeyDerivation;
+use ddk::{DlcDevKitDlcManager, Oracle, Storage, Transport};
+use ddk_manager::chain_monitor::ChainMonitor;
+use ddk_manager::channel::offered_channel::OfferedChannel;
+use ddk_manager::channel::signed_channel::{SignedChannel, SignedChannelStateType};
+use ddk_manager::channel::Channel;
+use ddk_manager::contract::offered_contract::OfferedContract;
+use ddk_manager::contract::signed_contract::SignedContract;
+use ddk_manager::contract::{Contract, PreClosedContract};
+use ddk_manager::error::Error;
+use ddk_manager::manager_state::ManagerState;
+use ddk_manager::{ChannelId, ContractId};
+use ddk_messages::Message;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Storage selected by the `[storage]` section of the config.
+#[derive(Debug)]
+pub enum NodeStorage {
+    Sled(SledStorage),
+    Postgres(PostgresStore),
+    Memory(Box<MemoryStorage>),
+}
+
+macro_rules! storage {
+    ($self:ident, $storage:ident => $call:expr) => {
+        match $self {
+            NodeStorage::Sled($storage) => $call,
+            NodeStorage::Postgres($storage) => $call,
+            NodeStorage::Memory($storage) => $call,
+        }
+    };
+}
+
+#[async_trait]
+impl Storage for NodeStorage {
+    async fn initialize_bdk(&self) -> Result<ChangeSet, WalletError> {
+        storage!(self, s => s.initialize_bdk().await)
+    }
+
+    async fn persist_bdk(&self, changeset: &ChangeSet) -> Result<(), WalletError> {
+        storage!(self, s => s.persist_bdk(changeset).await)
+    }
+
+    async fn persist_channel_key(
+        &self,
+        pubkey: &PublicKey,
+        derivation: &ChannelKeyDerivation,
+    ) -> Result<(), WalletError> {
+        storage!(self, s => s.persist_channel_key(pubkey, derivation).await)
+    }
+
+    async fn get_channel_keys(
+        &self,
+    ) -> Result<Vec<(PublicKey, ChannelKeyDerivation)>, WalletError> {
+        storage!(self, s => s.get_channel_keys().await)
+    }
+}
+
+#[async_trait]
+impl ddk_manager::Storage for NodeStorage {
+    async fn get_contract(&self, id: &ContractId) -> Result<Option<Contract>, Error> {
+        storage!(self, s => s.get_contract(id).await)
+    }
+
+    async fn get_contracts(&self) -> Result<Vec<Contract>, Error> {
+        storage!(self, s => s.get_contracts().await)
+    }
+
+    async fn create_contract(&self, contract: &OfferedContract) -> Result<(), Error> {
+        storage!(self, s => s.create_contract(contract).await)
+    }
+
+    async fn delete_contract(&self, id: &ContractId) -> Result<(), Error> {
+        storage!(self, s => s.delete_contract(id).await)
+    }
+
+    async fn update_contract(&self, contract: &Contract) -> Result<(), Error> {
+        storage!(self, s => s.update_contract(contract).await)
+    }
+
+    async fn get_contract_offers(&self) -> Result<Vec<OfferedContract>, Error> {
+        storage!(self, s => s.get_contract_offers().await)
+    }
+
+    async fn get_signed_contracts(&self) -> Result<Vec<SignedContract>, Error> {
+        storage!(self, s => s.get_signed_contracts().await)
+    }
+
+    async fn get_confirmed_contracts(&self) -> Result<Vec<SignedContract>, Error> {
+        storage!(self, s => s.get_confirmed_contracts().await)
+    }
+
+    async fn get_preclosed_contracts(&self) -> Result<Vec<PreClosedContract>, Error> {
+        storage!(self, s => s.get_preclosed_contracts().await)
+    }
+
+    async fn upsert_channel(
+        &self,
+        channel: Channel,
+        contract: Option<Contract>,
+    ) -> Result<(), Error> {
+        storage!(self, s => s.upsert_channel(channel, contract).await)
+    }
+
+    async fn delete_channel(&self, channel_id: &ChannelId) -> Result<(), Error> {
+        storage!(self, s => s.delete_channel(channel_id).await)
+    }
+
+    async fn get_channel(&self, channel_id: &ChannelId) -> Result<Option<Channel>, Error> {
+        storage!(self, s => s.get_channel(channel_id).await)
+    }
+
+    async fn get_channels(&self) -> Result<Vec<Channel>, Error> {
+        storage!(self, s => s.get_channels().await)
+    }
+
+    async fn get_signed_channels(
+        &self,
+        channel_state: Option<SignedChannelStateType>,
+    ) -> Result<Vec<SignedChannel>, Error> {
+        storage!(self, s => s.get_signed_channels(channel_state).await)
+    }
+
+    async fn get_offered_channels(&self) -> Result<Vec<OfferedChannel>, Error> {
+        storage!(self, s => s.get_offered_channels().await)
+    }
+
+    async fn persist_chain_monitor(&self, monitor: &ChainMonitor) -> Result<(), Error> {
+        storage!(self, s => s.persist_chain_monitor(monitor).await)
+    }
+
+    async fn get_chain_monitor(&self) -> Result<Option<ChainMonitor>, Error> {
+        storage!(self, s => s.get_chain_monitor().await)
+    }
+
+    async fn persist_manager_state(&self, state: &ManagerState) -> Result<(), Error> {
+        storage!(self, s => s.persist_manager_state(state).await)
+    }
+
+    async fn get_manager_state(&self) -> Result<Option<ManagerState>, Error> {
+        storage!(self, s => s.get_manager_state().await)
+    }
+}
+
+/// Transport selected by the `[transport]` section of the config.
+///
+/// With both transports the node listens on both, and identifies itself with its Nostr public
+/// key. Messages go over lightning to peers connected to the lightning transport, and over Nostr
+/// otherwise.
+pub enum NodeTransport {
+    Lightning(LightningTransport),
+    Nostr(NostrDlc),
+    Both {
+        lightning: LightningTransport,
+        nostr: NostrDlc,
+    },
+}
+
+#[async_trait]
+impl Transport for NodeTransport {
+    fn name(&self) -> String {
+        match self {
+            NodeTransport::Lightning(lightning) => lightning.name(),
+            NodeTransport::Nostr(nostr) => nostr.name(),
+            NodeTransport::Both { lightning, nostr } => {
+                format!("{}+{}", lightning.name(), nostr.name())
+            }
+        }
+    }
+
+    fn public_key(&self) -> PublicKey {
+        match self {
+            NodeTransport::Lightning(lightning) => lightning.public_key(),
+            NodeTransport::Nostr(nostr) | NodeTransport::Both { nostr, .. } => nostr.public_key(),
+        }
+    }
+
+    async fn start<S: Storage, O: Oracle>(
+        &self,
+        stop_signal: watch::Receiver<bool>,
+        manager: Arc<DlcDevKitDlcManager<S, O>>,
+    ) -> Result<(), TransportError> {
+        match self {
+            NodeTransport::Lightning(lightning) => {
+                Transport::start(lightning, stop_signal, manager).await
+            }
+            NodeTransport::Nostr(nostr) => Transport::start(nostr, stop_signal, manager).await,
+            NodeTransport::Both { lightning, nostr } => {
+                tokio::try_join!(
+                    Transport::start(lightning, stop_signal.clone(), manager.clone()),
+                    Transport::start(nostr, stop_signal, manager)
+                )?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn send_message(&self, counterparty: PublicKey, message: Message) {
+        match self {
+            NodeTransport::Lightning(lightning) => {
+                lightning.send_message(counterparty, message).await
+            }
+            NodeTransport::Nostr(nostr) => nostr.send_message(counterparty, message).await,
+            NodeTransport::Both { lightning, nostr } => {
+                if lightning
+                    .peer_manager
+                    .peer_by_node_id(&counterparty)
+                    .is_some()
+                {
+                    lightning.send_message(counterparty, message).await
+                } else {
+                    nostr.send_message(counterparty, message).await
+                }
+            }
+        }
+    }
+
+    async fn connect_outbound(&self, pubkey: PublicKey, host: &str) {
+        match self {
+            NodeTransport::Lightning(lightning) | NodeTransport::Both { lightning, .. } => {
+                lightning.connect_outbound(pubkey, host).await
+            }
+            NodeTransport::Nostr(nostr) => nostr.connect_outbound(pubkey, host).await,
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use clap::Parser;
+use ddk_node::auth::BearerToken;
 use ddk_node::cli_opts::CliCommand;
 use ddk_node::ddkrpc::ddk_rpc_client::DdkRpcClient;
+use std::path::PathBuf;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig};
 
 #[derive(Debug, Clone, Parser)]
 #[clap(name = "ddk-cli")]
@@ -12,8 +15,18 @@ use ddk_node::ddkrpc::ddk_rpc_client::DdkRpcClient;
 struct DdkCliArgs {
     #[arg(short, long)]
     #[arg(help = "ddk-node gRPC server to connect to.")]
-    #[arg(default_value = "http://127.0.0.1:3030")]
+    #[arg(default_value = "https://127.0.0.1:3030")]
     pub server: String,
+    #[arg(long)]
+    #[arg(
+        help = "TLS certificate of the ddk-node gRPC server. Defaults to the certificate in ~/.ddk/default-ddk."
+    )]
+    pub tls_cert: Option<PathBuf>,
+    #[arg(long)]
+    #[arg(
+        help = "Bearer token file to authenticate with. Defaults to the admin token in ~/.ddk/default-ddk."
+    )]
+    pub token: Option<PathBuf>,
     #[clap(subcommand)]
     pub command: CliCommand,
 }
@@ -22,7 +35,37 @@ struct DdkCliArgs {
 async fn main() -> anyhow::Result<()> {
     let opts = DdkCliArgs::parse();
 
-    let mut client = DdkRpcClient::connect(opts.server).await?;
+    let default_storage = homedir::my_home()?.map(|home| home.join(".ddk").join("default-ddk"));
+
+    let mut endpoint = Channel::from_shared(opts.server)?;
+    if endpoint.uri().scheme_str() == Some("https") {
+        let cert_path = opts
+            .tls_cert
+            .or_else(|| default_storage.as_ref().map(|s| s.join("tls.cert")))
+            .ok_or_else(|| anyhow::anyhow!("Provide the TLS certificate of ddk-node."))?;
+        let cert = std::fs::read(&cert_path).map_err(|e| {
+            anyhow::anyhow!(
+                "Could not read TLS certificate {}: {}",
+                cert_path.display(),
+                e
+            )
+        })?;
+        endpoint = endpoint
+            .tls_config(ClientTlsConfig::new().ca_certificate(Certificate::from_pem(cert)))?;
+    }
+    let channel = endpoint.connect().await?;
+
+    // Connect without a token if none was given and there is no admin token in the default
+    // storage directory, for nodes running with `--no-auth`.
+    let token = match opts.token {
+        Some(path) => Some(std::fs::read_to_string(path)?),
+        None => default_storage
+            .map(|s| s.join("auth").join("admin.token"))
+            .filter(|path| path.exists())
+            .map(std::fs::read_to_string)
+            .transpose()?,
+    };
+    let mut client = DdkRpcClient::with_interceptor(channel, BearerToken::new(token.as_deref())?);
 
     ddk_node::command::cli_command(opts.command, &mut client).await?;
 
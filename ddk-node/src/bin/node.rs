@@ -1,4 +1,5 @@
 use clap::Parser;
+use ddk_node::config::NodeConfig;
 use ddk_node::opts::NodeOpts;
 use ddk_node::DdkNode;
 use std::str::FromStr;
@@ -8,7 +9,8 @@ use tracing_subscriber::EnvFilter;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let opts = NodeOpts::parse();
-    let base_level = LevelFilter::from_str(&opts.log).unwrap_or(LevelFilter::INFO);
+    let config = NodeConfig::load(&opts)?;
+    let base_level = LevelFilter::from_str(&config.log).unwrap_or(LevelFilter::INFO);
 
     // Build the filter string based on the base level
     let filter_string = match base_level {
@@ -34,7 +36,7 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::subscriber::set_global_default(subscriber).unwrap();
 
-    DdkNode::serve(opts).await?;
+    DdkNode::serve(config).await?;
 
     Ok(())
 }
@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum CliCommand {
@@ -12,6 +13,49 @@ pub enum CliCommand {
     AcceptOffer(Accept),
     /// List contracts.
     Contracts,
+    /// Get the details of a contract.
+    Contract {
+        #[arg(help = "The contract id.")]
+        contract_id: String,
+    },
+    /// Offer the counterparty to close a confirmed contract before maturity.
+    CloseContract {
+        #[arg(help = "The contract id.")]
+        contract_id: String,
+        #[arg(help = "The payout to the counterparty in sats.")]
+        counter_payout: u64,
+    },
+    /// Accept a cooperative close offer from the counterparty.
+    AcceptClose {
+        #[arg(help = "The contract id.")]
+        contract_id: String,
+    },
+    /// Refund a contract once its refund locktime has passed.
+    RefundContract {
+        #[arg(help = "The contract id.")]
+        contract_id: String,
+    },
+    /// Bump the fee of a contract's CET or refund transaction stuck in the mempool.
+    BumpClosingFee {
+        #[arg(help = "The contract id.")]
+        contract_id: String,
+        #[arg(help = "The fee rate of the closing and child transactions in sats/vbyte.")]
+        fee_rate: u64,
+    },
+    /// Ask the counterparty to re-sign a contract with a higher fee fund transaction.
+    BumpFundingFee {
+        #[arg(help = "The contract id.")]
+        contract_id: String,
+        #[arg(help = "The fee rate of the replacing fund transaction in sats/vbyte.")]
+        fee_rate: u64,
+    },
+    /// Close a confirmed contract with oracle attestations.
+    SettleContract {
+        #[arg(help = "The contract id.")]
+        contract_id: String,
+        #[arg(required = true, help = "Paths to JSON oracle attestations.")]
+        attestations: Vec<PathBuf>,
+    },
     #[command(about = "Get the wallet balance.")]
     Balance,
     /// Wallet commands
@@ -20,6 +64,9 @@ pub enum CliCommand {
     /// Interface with the oracle
     #[clap(subcommand)]
     Oracle(OracleCommand),
+    /// DLC channel commands
+    #[clap(subcommand)]
+    Channel(ChannelCommand),
     /// Get the peers connected to the node.
     Peers,
     /// Connect to another DDK node.
@@ -30,6 +77,8 @@ pub enum CliCommand {
     /// Sync the wallet and contracts.
     #[command(about = "Sync the wallet and contracts.")]
     Sync,
+    /// Stream contract, channel and wallet events as they happen.
+    Events,
 }
 
 #[derive(Parser, Clone, Debug)]
@@ -102,6 +151,85 @@ pub enum OracleCommand {
         #[arg(long, help = "The event id to sign.")]
         event_id: String,
     },
+    #[command(about = "Get the attestation request statistics of each oracle.")]
+    Health,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ChannelCommand {
+    #[command(about = "Offer a DLC channel to a counterparty.")]
+    Offer {
+        #[arg(help = "The channel counterparty to send to.")]
+        counter_party: String,
+        #[arg(
+            long,
+            help = "Path to a JSON contract input. Prompts for one if not set."
+        )]
+        contract_input: Option<PathBuf>,
+    },
+    #[command(about = "Accept a channel offer with the temporary channel id.")]
+    Accept {
+        #[arg(help = "The temporary channel id of the offer.")]
+        channel_id: String,
+    },
+    #[command(about = "List the offered and signed channels.")]
+    List,
+    #[command(about = "Offer to settle a channel.")]
+    Settle {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+        #[arg(help = "The payout in sats the counterparty gets.")]
+        counter_payout: u64,
+    },
+    #[command(about = "Accept a settle offer.")]
+    AcceptSettle {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+    },
+    #[command(about = "Reject a settle offer.")]
+    RejectSettle {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+    },
+    #[command(about = "Offer a new contract in a channel.")]
+    Renew {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+        #[arg(help = "The payout in sats the counterparty gets from the current contract.")]
+        counter_payout: u64,
+        #[arg(
+            long,
+            help = "Path to a JSON contract input. Prompts for one if not set."
+        )]
+        contract_input: Option<PathBuf>,
+    },
+    #[command(about = "Accept a renew offer.")]
+    AcceptRenew {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+    },
+    #[command(about = "Reject a renew offer.")]
+    RejectRenew {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+    },
+    #[command(about = "Offer to collaboratively close a channel.")]
+    Close {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+        #[arg(help = "The payout in sats the counterparty gets.")]
+        counter_payout: u64,
+    },
+    #[command(about = "Accept a collaborative close offer.")]
+    AcceptClose {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+    },
+    #[command(about = "Force close a channel on chain.")]
+    ForceClose {
+        #[arg(help = "The channel id.")]
+        channel_id: String,
+    },
 }
 
 #[derive(Parser, Clone, Debug)]
@@ -223,4 +351,79 @@ mod tests {
             _ => panic!("Wrong variant"),
         }
     }
+
+    #[test]
+    fn test_channel_commands_parse() {
+        #[derive(Parser)]
+        struct Cli {
+            #[clap(subcommand)]
+            command: CliCommand,
+        }
+
+        let cli = Cli::parse_from([
+            "ddk-cli",
+            "channel",
+            "renew",
+            "abcd",
+            "5000",
+            "--contract-input",
+            "input.json",
+        ]);
+        match cli.command {
+            CliCommand::Channel(ChannelCommand::Renew {
+                channel_id,
+                counter_payout,
+                contract_input,
+            }) => {
+                assert_eq!(channel_id, "abcd");
+                assert_eq!(counter_payout, 5000);
+                assert_eq!(contract_input, Some(PathBuf::from("input.json")));
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let cli = Cli::parse_from(["ddk-cli", "channel", "force-close", "abcd"]);
+        assert!(matches!(
+            cli.command,
+            CliCommand::Channel(ChannelCommand::ForceClose { .. })
+        ));
+    }
+
+    #[test]
+    fn test_contract_close_commands_parse() {
+        #[derive(Parser)]
+        struct Cli {
+            #[clap(subcommand)]
+            command: CliCommand,
+        }
+
+        let cli = Cli::parse_from(["ddk-cli", "close-contract", "abcd", "5000"]);
+        match cli.command {
+            CliCommand::CloseContract {
+                contract_id,
+                counter_payout,
+            } => {
+                assert_eq!(contract_id, "abcd");
+                assert_eq!(counter_payout, 5000);
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let cli = Cli::parse_from(["ddk-cli", "settle-contract", "abcd", "a.json", "b.json"]);
+        match cli.command {
+            CliCommand::SettleContract {
+                contract_id,
+                attestations,
+            } => {
+                assert_eq!(contract_id, "abcd");
+                assert_eq!(
+                    attestations,
+                    vec![PathBuf::from("a.json"), PathBuf::from("b.json")]
+                );
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        assert!(Cli::try_parse_from(["ddk-cli", "settle-contract", "abcd"]).is_err());
+    }
 }
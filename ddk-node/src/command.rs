@@ -1,13 +1,19 @@
 use std::sync::Arc;
 
-use crate::cli_opts::{CliCommand, OracleCommand, WalletCommand};
+use crate::auth::AuthChannel;
+use crate::cli_opts::{ChannelCommand, CliCommand, OracleCommand, WalletCommand};
 use crate::ddkrpc::ddk_rpc_client::DdkRpcClient;
 use crate::ddkrpc::{
-    sign_request, AcceptOfferRequest, ConnectRequest, CreateEnumRequest, CreateNumericRequest,
-    GetWalletTransactionsRequest, InfoRequest, ListContractsRequest, ListOffersRequest,
-    ListPeersRequest, ListUtxosRequest, NewAddressRequest, OracleAnnouncementsRequest,
-    SendOfferRequest, SendRequest, SignRequest, SyncRequest, WalletBalanceRequest,
-    WalletSyncRequest,
+    sign_request, AcceptChannelRequest, AcceptCollaborativeCloseRequest,
+    AcceptCooperativeCloseRequest, AcceptOfferRequest, AcceptRenewOfferRequest,
+    AcceptSettleOfferRequest, BumpClosingFeeRequest, BumpFundingFeeRequest, CloseContractRequest,
+    CollaborativeCloseRequest, ConnectRequest, CooperativeCloseRequest, CreateEnumRequest,
+    CreateNumericRequest, ForceCloseRequest, GetContractRequest, GetWalletTransactionsRequest,
+    InfoRequest, ListChannelsRequest, ListContractsRequest, ListOffersRequest, ListPeersRequest,
+    ListUtxosRequest, NewAddressRequest, OfferChannelRequest, OracleAnnouncementsRequest,
+    OracleHealthRequest, RefundContractRequest, RejectRenewOfferRequest, RejectSettleOfferRequest,
+    RenewOfferRequest, SendOfferRequest, SendRequest, SettleOfferRequest, SignRequest,
+    SubscribeEventsRequest, SyncRequest, WalletBalanceRequest, WalletSyncRequest,
 };
 use anyhow::anyhow;
 use bitcoin::{Amount, Transaction};
@@ -23,15 +29,19 @@ use ddk_manager::contract::enum_descriptor::EnumDescriptor;
 use ddk_manager::contract::offered_contract::OfferedContract;
 use ddk_manager::contract::{Contract, ContractDescriptor};
 use ddk_manager::Oracle;
-use ddk_messages::oracle_msgs::{EventDescriptor, OracleAnnouncement};
-use ddk_messages::{AcceptDlc, OfferDlc};
+use ddk_messages::channel::{
+    AcceptChannel, CollaborativeCloseOffer, OfferChannel, RenewAccept, RenewOffer, SettleAccept,
+    SettleOffer,
+};
+use ddk_messages::oracle_msgs::{EventDescriptor, OracleAnnouncement, OracleAttestation};
+use ddk_messages::{AcceptDlc, CloseDlc, OfferDlc};
 use inquire::{Select, Text};
 use serde_json::Value;
-use tonic::transport::Channel;
+use std::path::PathBuf;
 
 pub async fn cli_command(
     arg: CliCommand,
-    client: &mut DdkRpcClient<Channel>,
+    client: &mut DdkRpcClient<AuthChannel>,
 ) -> anyhow::Result<()> {
     match arg {
         CliCommand::Info => {
@@ -95,6 +105,123 @@ pub async fn cli_command(
                 .collect::<Vec<Value>>();
             print!("{}", serde_json::to_string_pretty(&contract_values)?)
         }
+        CliCommand::Contract { contract_id } => {
+            let contract = client
+                .get_contract(GetContractRequest { contract_id })
+                .await?
+                .into_inner();
+            let announcements = contract
+                .oracle_announcements
+                .iter()
+                .map(|a| serde_json::from_slice(a))
+                .collect::<Result<Vec<OracleAnnouncement>, _>>()?;
+            let attestations = contract
+                .attestations
+                .iter()
+                .map(|a| serde_json::from_slice(a))
+                .collect::<Result<Vec<OracleAttestation>, _>>()?;
+            let value = serde_json::json!({
+                "contract_id": contract.contract_id,
+                "state": contract.state,
+                "counter_party": contract.counter_party,
+                "is_offer_party": contract.is_offer_party,
+                "offer_collateral": contract.offer_collateral,
+                "accept_collateral": contract.accept_collateral,
+                "fee_rate_per_vb": contract.fee_rate_per_vb,
+                "cet_locktime": contract.cet_locktime,
+                "refund_locktime": contract.refund_locktime,
+                "funding_txid": contract.funding_txid,
+                "refund_txid": contract.refund_txid,
+                "cet_txid": contract.cet_txid,
+                "pnl": contract.pnl,
+                "oracle_announcements": announcements,
+                "attestations": attestations,
+            });
+            print!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        CliCommand::CloseContract {
+            contract_id,
+            counter_payout,
+        } => {
+            let close = client
+                .cooperative_close(CooperativeCloseRequest {
+                    contract_id,
+                    counter_payout,
+                })
+                .await?
+                .into_inner();
+            let close_dlc: CloseDlc = serde_json::from_slice(&close.close_dlc)?;
+            print!("{}", serde_json::to_string_pretty(&close_dlc)?);
+        }
+        CliCommand::AcceptClose { contract_id } => {
+            client
+                .accept_cooperative_close(AcceptCooperativeCloseRequest {
+                    contract_id: contract_id.clone(),
+                })
+                .await?;
+            print!("Accepted cooperative close of contract {contract_id}.");
+        }
+        CliCommand::RefundContract { contract_id } => {
+            let refund = client
+                .refund_contract(RefundContractRequest { contract_id })
+                .await?
+                .into_inner();
+            let contract = util::ser::deserialize_contract(&refund.contract)?;
+            print!(
+                "{}",
+                serde_json::to_string_pretty(&contract_to_value(&contract))?
+            );
+        }
+        CliCommand::BumpClosingFee {
+            contract_id,
+            fee_rate,
+        } => {
+            let bump = client
+                .bump_closing_fee(BumpClosingFeeRequest {
+                    contract_id,
+                    fee_rate,
+                })
+                .await?
+                .into_inner();
+            print!("{}", bump.txid);
+        }
+        CliCommand::BumpFundingFee {
+            contract_id,
+            fee_rate,
+        } => {
+            client
+                .bump_funding_fee(BumpFundingFeeRequest {
+                    contract_id,
+                    fee_rate,
+                })
+                .await?;
+            print!("Asked the counterparty to sign a fund transaction with a higher fee.");
+        }
+        CliCommand::SettleContract {
+            contract_id,
+            attestations,
+        } => {
+            let attestations = attestations
+                .iter()
+                .map(|path| {
+                    let attestation = std::fs::read(path)?;
+                    serde_json::from_slice::<OracleAttestation>(&attestation)?;
+                    Ok(attestation)
+                })
+                .collect::<anyhow::Result<Vec<Vec<u8>>>>()?;
+            let closed = client
+                .close_contract(CloseContractRequest {
+                    contract_id,
+                    attestations,
+                })
+                .await?
+                .into_inner();
+            let contract = util::ser::deserialize_contract(&closed.contract)?;
+            print!(
+                "{}",
+                serde_json::to_string_pretty(&contract_to_value(&contract))?
+            );
+        }
         CliCommand::Balance => {
             let balance = client
                 .wallet_balance(WalletBalanceRequest::default())
@@ -227,7 +354,15 @@ pub async fn cli_command(
                 let response = client.sign_announcement(request).await?.into_inner();
                 print!("{}", serde_json::to_string_pretty(&response.signature)?);
             }
+            OracleCommand::Health => {
+                let response = client
+                    .oracle_health(OracleHealthRequest::default())
+                    .await?
+                    .into_inner();
+                print!("{}", serde_json::to_string_pretty(&response.oracles)?);
+            }
         },
+        CliCommand::Channel(command) => channel_command(command, client).await?,
         CliCommand::Peers => {
             let peers_response = client
                 .list_peers(ListPeersRequest::default())
@@ -250,10 +385,163 @@ pub async fn cli_command(
             let _ = client.sync(SyncRequest {}).await?.into_inner();
             println!("Synced.")
         }
+        CliCommand::Events => {
+            let mut events = client
+                .subscribe_events(SubscribeEventsRequest {})
+                .await?
+                .into_inner();
+            while let Some(event) = events.message().await? {
+                println!("{}", serde_json::to_string(&event)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn channel_command(
+    command: ChannelCommand,
+    client: &mut DdkRpcClient<AuthChannel>,
+) -> anyhow::Result<()> {
+    match command {
+        ChannelCommand::Offer {
+            counter_party,
+            contract_input,
+        } => {
+            let contract_input = channel_contract_input(contract_input, client).await?;
+            let offer = client
+                .offer_channel(OfferChannelRequest {
+                    contract_input: serde_json::to_vec(&contract_input)?,
+                    counter_party,
+                })
+                .await?
+                .into_inner();
+            let offer_channel: OfferChannel = serde_json::from_slice(&offer.offer_channel)?;
+            print!("{}", serde_json::to_string_pretty(&offer_channel)?);
+        }
+        ChannelCommand::Accept { channel_id } => {
+            let accept = client
+                .accept_channel(AcceptChannelRequest { channel_id })
+                .await?
+                .into_inner();
+            let accept_channel: AcceptChannel = serde_json::from_slice(&accept.accept_channel)?;
+            let value = serde_json::json!({
+                "channel_id": accept.channel_id,
+                "contract_id": accept.contract_id,
+                "counter_party": accept.counter_party,
+                "accept_channel": accept_channel,
+            });
+            print!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        ChannelCommand::List => {
+            let channels = client
+                .list_channels(ListChannelsRequest {})
+                .await?
+                .into_inner()
+                .channels;
+            let channel_values = channels
+                .iter()
+                .map(|c| Ok(channel_to_value(&util::ser::deserialize_channel(c)?)))
+                .collect::<anyhow::Result<Vec<Value>>>()?;
+            print!("{}", serde_json::to_string_pretty(&channel_values)?)
+        }
+        ChannelCommand::Settle {
+            channel_id,
+            counter_payout,
+        } => {
+            let offer = client
+                .settle_offer(SettleOfferRequest {
+                    channel_id,
+                    counter_payout,
+                })
+                .await?
+                .into_inner();
+            let settle_offer: SettleOffer = serde_json::from_slice(&offer.settle_offer)?;
+            print!("{}", serde_json::to_string_pretty(&settle_offer)?);
+        }
+        ChannelCommand::AcceptSettle { channel_id } => {
+            let accept = client
+                .accept_settle_offer(AcceptSettleOfferRequest { channel_id })
+                .await?
+                .into_inner();
+            let settle_accept: SettleAccept = serde_json::from_slice(&accept.settle_accept)?;
+            print!("{}", serde_json::to_string_pretty(&settle_accept)?);
+        }
+        ChannelCommand::RejectSettle { channel_id } => {
+            client
+                .reject_settle_offer(RejectSettleOfferRequest { channel_id })
+                .await?;
+            println!("Settle offer rejected.")
+        }
+        ChannelCommand::Renew {
+            channel_id,
+            counter_payout,
+            contract_input,
+        } => {
+            let contract_input = channel_contract_input(contract_input, client).await?;
+            let offer = client
+                .renew_offer(RenewOfferRequest {
+                    channel_id,
+                    counter_payout,
+                    contract_input: serde_json::to_vec(&contract_input)?,
+                })
+                .await?
+                .into_inner();
+            let renew_offer: RenewOffer = serde_json::from_slice(&offer.renew_offer)?;
+            print!("{}", serde_json::to_string_pretty(&renew_offer)?);
+        }
+        ChannelCommand::AcceptRenew { channel_id } => {
+            let accept = client
+                .accept_renew_offer(AcceptRenewOfferRequest { channel_id })
+                .await?
+                .into_inner();
+            let renew_accept: RenewAccept = serde_json::from_slice(&accept.renew_accept)?;
+            print!("{}", serde_json::to_string_pretty(&renew_accept)?);
+        }
+        ChannelCommand::RejectRenew { channel_id } => {
+            client
+                .reject_renew_offer(RejectRenewOfferRequest { channel_id })
+                .await?;
+            println!("Renew offer rejected.")
+        }
+        ChannelCommand::Close {
+            channel_id,
+            counter_payout,
+        } => {
+            let offer = client
+                .collaborative_close(CollaborativeCloseRequest {
+                    channel_id,
+                    counter_payout,
+                })
+                .await?
+                .into_inner();
+            let close_offer: CollaborativeCloseOffer = serde_json::from_slice(&offer.close_offer)?;
+            print!("{}", serde_json::to_string_pretty(&close_offer)?);
+        }
+        ChannelCommand::AcceptClose { channel_id } => {
+            client
+                .accept_collaborative_close(AcceptCollaborativeCloseRequest { channel_id })
+                .await?;
+            println!("Collaborative close accepted.")
+        }
+        ChannelCommand::ForceClose { channel_id } => {
+            client.force_close(ForceCloseRequest { channel_id }).await?;
+            println!("Channel force closed.")
+        }
     }
     Ok(())
 }
 
+/// Reads the contract input of a channel from a JSON file, or prompts for it.
+async fn channel_contract_input(
+    path: Option<PathBuf>,
+    client: &mut DdkRpcClient<AuthChannel>,
+) -> anyhow::Result<ContractInput> {
+    match path {
+        Some(path) => Ok(serde_json::from_slice(&std::fs::read(path)?)?),
+        None => interactive_contract_input(client).await,
+    }
+}
+
 async fn generate_contract_input() -> anyhow::Result<ContractInput> {
     let contract_descriptor = ContractDescriptor::Enum(EnumDescriptor {
         outcome_payouts: vec![
@@ -308,7 +596,7 @@ async fn generate_contract_input() -> anyhow::Result<ContractInput> {
 }
 
 async fn interactive_contract_input(
-    client: &mut DdkRpcClient<Channel>,
+    client: &mut DdkRpcClient<AuthChannel>,
 ) -> anyhow::Result<ContractInput> {
     let contract_type =
         Select::new("Select type of contract.", vec!["enum", "numerical"]).prompt()?;
@@ -330,7 +618,9 @@ async fn interactive_contract_input(
             let accept_collateral: u64 = Text::new("Collateral from counterparty (sats):")
                 .prompt()?
                 .parse()?;
-            let fee_rate: u64 = Text::new("Fee rate (sats/vbyte):").prompt()?.parse()?;
+            let fee_rate: u64 = Text::new("Fee rate (sats/vbyte, 0 to estimate):")
+                .prompt()?
+                .parse()?;
             let min_price: u64 = Text::new("Minimum Bitcoin price:").prompt()?.parse()?;
             let max_price: u64 = Text::new("Maximum Bitcoin price:").prompt()?.parse()?;
             let num_steps: u64 = Text::new("Number of rounding steps:").prompt()?.parse()?;
@@ -372,7 +662,9 @@ async fn interactive_contract_input(
                 };
                 outcome_payouts.push(outcome_payout);
             }
-            let fee_rate: u64 = Text::new("Fee rate (sats/vbyte):").prompt()?.parse()?;
+            let fee_rate: u64 = Text::new("Fee rate (sats/vbyte, 0 to estimate):")
+                .prompt()?
+                .parse()?;
             // TODO: list possible events.
             ddk_payouts::enumeration::create_contract_input(
                 outcome_payouts,
@@ -0,0 +1,562 @@
+//! Configuration of `ddk-node`.
+//!
+//! Settings are layered, each layer overriding the one before it:
+//!
+//! 1. the defaults of [`NodeConfig`],
+//! 2. the TOML config file,
+//! 3. environment variables,
+//! 4. command line flags.
+//!
+//! ```toml
+//! network = "regtest"
+//! name = "node-one"
+//!
+//! [grpc]
+//! host = "127.0.0.1:3030"
+//!
+//! [storage]
+//! backend = "sled"
+//!
+//! [transport]
+//! kind = "both"
+//! listening_port = 1776
+//! nostr_relay = "wss://nostr.dlcdevkit.com"
+//!
+//! [chain]
+//! source = "zmq"
+//! esplora_host = "http://localhost:30000"
+//! zmq_blockhash_endpoint = "tcp://127.0.0.1:28332"
+//!
+//! [[oracles]]
+//! kind = "kormir"
+//! url = "https://kormir.dlcdevkit.com"
+//!
+//! [[oracles]]
+//! kind = "p2pd"
+//! url = "https://oracle.p2pderivatives.io"
+//! ```
+use crate::opts::NodeOpts;
+use bitcoin::Network;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the config file looked up in the storage directory.
+pub const CONFIG_FILE_NAME: &str = "ddk-node.toml";
+
+/// Log levels accepted by `--log` and the `log` setting.
+pub const LOG_LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeConfig {
+    /// Bitcoin network: regtest, signet or mainnet.
+    pub network: String,
+    pub log: String,
+    /// Name of the wallet.
+    pub name: String,
+    /// Where the node stores its seed, credentials and sled database. Defaults to
+    /// `~/.ddk/default-ddk`.
+    pub storage_dir: Option<PathBuf>,
+    pub grpc: GrpcConfig,
+    pub storage: StorageConfig,
+    pub transport: TransportConfig,
+    pub chain: ChainConfig,
+    pub oracles: Vec<OracleConfig>,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            network: "signet".to_string(),
+            log: "info".to_string(),
+            name: "ddk-node".to_string(),
+            storage_dir: None,
+            grpc: GrpcConfig::default(),
+            storage: StorageConfig::default(),
+            transport: TransportConfig::default(),
+            chain: ChainConfig::default(),
+            oracles: vec![OracleConfig::Kormir {
+                url: "https://kormir.dlcdevkit.com".to_string(),
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GrpcConfig {
+    pub host: String,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    pub no_tls: bool,
+    pub no_auth: bool,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0:3030".to_string(),
+            tls_cert: None,
+            tls_key: None,
+            no_tls: false,
+            no_auth: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    Sled,
+    Postgres,
+    Memory,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// Required by the postgres backend.
+    pub postgres_url: Option<String>,
+    /// Path of the sled database. Defaults to `sled` in the storage directory.
+    pub sled_path: Option<PathBuf>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Postgres,
+            postgres_url: None,
+            sled_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    Lightning,
+    Nostr,
+    Both,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportConfig {
+    pub kind: TransportKind,
+    /// Listening port of the lightning transport.
+    pub listening_port: u16,
+    /// Relay of the nostr transport.
+    pub nostr_relay: String,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            kind: TransportKind::Nostr,
+            listening_port: 1776,
+            nostr_relay: "wss://nostr.dlcdevkit.com".to_string(),
+        }
+    }
+}
+
+/// Where the node learns about new blocks. Both sources read chain data from esplora; `zmq`
+/// gets notified of new blocks by bitcoind instead of polling esplora for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainSource {
+    Esplora,
+    Zmq,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainConfig {
+    pub source: ChainSource,
+    pub esplora_host: String,
+    /// Required by the zmq source.
+    pub zmq_blockhash_endpoint: Option<String>,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            source: ChainSource::Esplora,
+            esplora_host: "https://mutinynet.com/api".to_string(),
+            zmq_blockhash_endpoint: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)]
+pub enum OracleConfig {
+    Kormir { url: String },
+    P2pd { url: String },
+}
+
+impl OracleConfig {
+    pub fn url(&self) -> &str {
+        match self {
+            OracleConfig::Kormir { url } | OracleConfig::P2pd { url } => url,
+        }
+    }
+}
+
+impl NodeConfig {
+    /// Layers the config file and the command line flags and environment variables in `opts`
+    /// over the defaults, and validates the result.
+    ///
+    /// The config file is read from `--config`, or from [`CONFIG_FILE_NAME`] in the storage
+    /// directory if it exists.
+    pub fn load(opts: &NodeOpts) -> anyhow::Result<Self> {
+        let config_path = match &opts.config {
+            Some(path) => Some(path.clone()),
+            None => opts
+                .storage_dir
+                .clone()
+                .or_else(default_storage_dir)
+                .map(|dir| dir.join(CONFIG_FILE_NAME))
+                .filter(|path| path.exists()),
+        };
+        let mut config = match config_path {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+        config.apply(opts);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read config {}: {}", path.display(), e))?;
+        toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid config {}: {}", path.display(), e))
+    }
+
+    /// Overrides the config with the flags and environment variables that are set.
+    pub fn apply(&mut self, opts: &NodeOpts) {
+        if let Some(network) = &opts.network {
+            self.network = network.clone();
+        }
+        if let Some(log) = &opts.log {
+            self.log = log.clone();
+        }
+        if let Some(name) = &opts.name {
+            self.name = name.clone();
+        }
+        if let Some(storage_dir) = &opts.storage_dir {
+            self.storage_dir = Some(storage_dir.clone());
+        }
+
+        if let Some(host) = &opts.grpc_host {
+            self.grpc.host = host.clone();
+        }
+        if let Some(tls_cert) = &opts.tls_cert {
+            self.grpc.tls_cert = Some(tls_cert.clone());
+        }
+        if let Some(tls_key) = &opts.tls_key {
+            self.grpc.tls_key = Some(tls_key.clone());
+        }
+        if let Some(no_tls) = opts.no_tls {
+            self.grpc.no_tls = no_tls;
+        }
+        if let Some(no_auth) = opts.no_auth {
+            self.grpc.no_auth = no_auth;
+        }
+
+        if let Some(backend) = opts.storage {
+            self.storage.backend = backend;
+        }
+        if let Some(url) = &opts.postgres_url {
+            self.storage.postgres_url = Some(url.clone());
+        }
+
+        if let Some(kind) = opts.transport {
+            self.transport.kind = kind;
+        }
+        if let Some(port) = opts.listening_port {
+            self.transport.listening_port = port;
+        }
+        if let Some(relay) = &opts.nostr_relay {
+            self.transport.nostr_relay = relay.clone();
+        }
+
+        if let Some(host) = &opts.esplora_host {
+            self.chain.esplora_host = host.clone();
+        }
+        // An empty endpoint leaves the chain source as is, so scripts can pass an unset variable.
+        if let Some(endpoint) = opts
+            .zmq_blockhash_endpoint
+            .as_ref()
+            .filter(|e| !e.is_empty())
+        {
+            self.chain.zmq_blockhash_endpoint = Some(endpoint.clone());
+            self.chain.source = ChainSource::Zmq;
+        }
+        if let Some(source) = opts.chain {
+            self.chain.source = source;
+        }
+
+        if !opts.oracle_hosts.is_empty() || !opts.p2pd_oracle_hosts.is_empty() {
+            let kormir = opts
+                .oracle_hosts
+                .iter()
+                .map(|url| OracleConfig::Kormir { url: url.clone() });
+            let p2pd = opts
+                .p2pd_oracle_hosts
+                .iter()
+                .map(|url| OracleConfig::P2pd { url: url.clone() });
+            self.oracles = kormir.chain(p2pd).collect();
+        }
+    }
+
+    /// Checks that the selected backends have the settings they need. Returns every problem
+    /// found, not just the first.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut errors = Vec::new();
+
+        if let Err(e) = self.network() {
+            errors.push(e.to_string());
+        }
+        if !LOG_LEVELS.contains(&self.log.as_str()) {
+            errors.push(format!("Unknown log level {}.", self.log));
+        }
+        if self.name.is_empty() {
+            errors.push("name must not be empty.".to_string());
+        }
+
+        if self.grpc.host.parse::<SocketAddr>().is_err() {
+            errors.push(format!(
+                "grpc.host {} is not a host and port.",
+                self.grpc.host
+            ));
+        }
+        if self.grpc.tls_cert.is_some() != self.grpc.tls_key.is_some() {
+            errors.push("grpc.tls_cert and grpc.tls_key must be set together.".to_string());
+        }
+        if self.grpc.no_tls && self.grpc.tls_cert.is_some() {
+            errors.push("grpc.tls_cert is set but TLS is disabled.".to_string());
+        }
+
+        if self.storage.backend == StorageBackend::Postgres
+            && self
+                .storage
+                .postgres_url
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+        {
+            errors.push("The postgres storage requires storage.postgres_url.".to_string());
+        }
+
+        if self.transport.kind != TransportKind::Nostr && self.transport.listening_port == 0 {
+            errors.push("The lightning transport requires transport.listening_port.".to_string());
+        }
+        if self.transport.kind != TransportKind::Lightning
+            && !has_scheme(&self.transport.nostr_relay, &["ws", "wss"])
+        {
+            errors.push(format!(
+                "transport.nostr_relay {} is not a ws:// or wss:// url.",
+                self.transport.nostr_relay
+            ));
+        }
+
+        if !has_scheme(&self.chain.esplora_host, &["http", "https"]) {
+            errors.push(format!(
+                "chain.esplora_host {} is not a http:// or https:// url.",
+                self.chain.esplora_host
+            ));
+        }
+        match (self.chain.source, &self.chain.zmq_blockhash_endpoint) {
+            (ChainSource::Zmq, None) => errors
+                .push("The zmq chain source requires chain.zmq_blockhash_endpoint.".to_string()),
+            (ChainSource::Zmq, Some(endpoint)) if !has_scheme(endpoint, &["tcp", "ipc"]) => errors
+                .push(format!(
+                    "chain.zmq_blockhash_endpoint {} is not a tcp:// or ipc:// endpoint.",
+                    endpoint
+                )),
+            (ChainSource::Esplora, Some(_)) => errors.push(
+                "chain.zmq_blockhash_endpoint is only used with the zmq chain source.".to_string(),
+            ),
+            _ => {}
+        }
+
+        if self.oracles.is_empty() {
+            errors.push("At least one oracle is required.".to_string());
+        }
+        let mut seen = HashSet::new();
+        for oracle in &self.oracles {
+            if !has_scheme(oracle.url(), &["http", "https"]) {
+                errors.push(format!(
+                    "Oracle {} is not a http:// or https:// url.",
+                    oracle.url()
+                ));
+            }
+            if !seen.insert(oracle) {
+                errors.push(format!("Oracle {} is configured twice.", oracle.url()));
+            }
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!("Invalid ddk-node config:\n  - {}", errors.join("\n  - "));
+        }
+        Ok(())
+    }
+
+    /// The configured network. Accepts `mainnet` for [`Network::Bitcoin`].
+    pub fn network(&self) -> anyhow::Result<Network> {
+        match self.network.as_str() {
+            "mainnet" => Ok(Network::Bitcoin),
+            network => Network::from_str(network)
+                .map_err(|_| anyhow::anyhow!("Unknown network {}.", network)),
+        }
+    }
+
+    /// The storage directory, `~/.ddk/default-ddk` if not configured.
+    pub fn storage_path(&self) -> anyhow::Result<PathBuf> {
+        self.storage_dir
+            .clone()
+            .or_else(default_storage_dir)
+            .ok_or_else(|| anyhow::anyhow!("Provide a directory for ddk."))
+    }
+}
+
+fn default_storage_dir() -> Option<PathBuf> {
+    homedir::my_home()
+        .ok()
+        .flatten()
+        .map(|home| home.join(".ddk").join("default-ddk"))
+}
+
+fn has_scheme(url: &str, schemes: &[&str]) -> bool {
+    url.split_once("://")
+        .is_some_and(|(scheme, rest)| schemes.contains(&scheme) && !rest.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn opts(args: &[&str]) -> NodeOpts {
+        NodeOpts::parse_from(std::iter::once("ddk-node").chain(args.iter().copied()))
+    }
+
+    const CONFIG: &str = r#"
+        network = "regtest"
+        name = "node-one"
+
+        [storage]
+        backend = "sled"
+
+        [transport]
+        kind = "both"
+        listening_port = 1777
+
+        [chain]
+        source = "zmq"
+        esplora_host = "http://localhost:30000"
+        zmq_blockhash_endpoint = "tcp://127.0.0.1:28332"
+
+        [[oracles]]
+        kind = "kormir"
+        url = "https://kormir.dlcdevkit.com"
+
+        [[oracles]]
+        kind = "p2pd"
+        url = "https://oracle.p2pderivatives.io"
+    "#;
+
+    #[test]
+    fn config_file_selects_backends() {
+        let config: NodeConfig = toml::from_str(CONFIG).unwrap();
+        assert_eq!(config.network().unwrap(), Network::Regtest);
+        assert_eq!(config.storage.backend, StorageBackend::Sled);
+        assert_eq!(config.transport.kind, TransportKind::Both);
+        assert_eq!(config.transport.listening_port, 1777);
+        assert_eq!(config.chain.source, ChainSource::Zmq);
+        assert_eq!(config.oracles.len(), 2);
+        // Unset settings keep their defaults.
+        assert_eq!(config.grpc, GrpcConfig::default());
+        assert_eq!(config.transport.nostr_relay, "wss://nostr.dlcdevkit.com");
+        config.validate().unwrap();
+
+        let unknown = toml::from_str::<NodeConfig>("[storage]\nbackend = \"rocksdb\"");
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn flags_override_config_file() {
+        let mut config: NodeConfig = toml::from_str(CONFIG).unwrap();
+        config.apply(&opts(&[
+            "--storage",
+            "postgres",
+            "--postgres-url",
+            "postgres://localhost/ddk",
+            "--transport",
+            "nostr",
+            "--oracle",
+            "https://one.example,https://two.example",
+        ]));
+
+        assert_eq!(config.storage.backend, StorageBackend::Postgres);
+        assert_eq!(config.transport.kind, TransportKind::Nostr);
+        assert_eq!(
+            config.oracles,
+            vec![
+                OracleConfig::Kormir {
+                    url: "https://one.example".to_string()
+                },
+                OracleConfig::Kormir {
+                    url: "https://two.example".to_string()
+                },
+            ]
+        );
+        // Settings without a flag are kept from the file.
+        assert_eq!(config.name, "node-one");
+        assert_eq!(config.chain.source, ChainSource::Zmq);
+        config.validate().unwrap();
+
+        // Boolean flags override the file both ways.
+        let mut config: NodeConfig =
+            toml::from_str("[grpc]\nno_tls = true\nno_auth = true").unwrap();
+        config.apply(&opts(&["--no-tls=false"]));
+        assert!(!config.grpc.no_tls);
+        assert!(config.grpc.no_auth);
+        config.apply(&opts(&["--no-tls", "--no-auth=false"]));
+        assert!(config.grpc.no_tls);
+        assert!(!config.grpc.no_auth);
+    }
+
+    #[test]
+    fn every_log_level_is_accepted_by_the_flag() {
+        for level in LOG_LEVELS {
+            let mut config = NodeConfig::default();
+            config.apply(&opts(&["--log", level]));
+            assert_eq!(config.log, level);
+        }
+    }
+
+    #[test]
+    fn validation_reports_every_problem() {
+        let mut config = NodeConfig::default();
+        config.chain.source = ChainSource::Zmq;
+        config.transport.nostr_relay = "https://nostr.dlcdevkit.com".to_string();
+        config.oracles.push(config.oracles[0].clone());
+
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("storage.postgres_url"));
+        assert!(error.contains("transport.nostr_relay"));
+        assert!(error.contains("chain.zmq_blockhash_endpoint"));
+        assert!(error.contains("configured twice"));
+
+        config.oracles.clear();
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("At least one oracle"));
+    }
+}
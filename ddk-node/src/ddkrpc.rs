@@ -134,6 +134,36 @@ pub struct ListOraclesResponse {
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct OracleHealthRequest {}
+/// Attestation request statistics of an oracle since the node started.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct OracleHealth {
+    #[prost(string, tag = "1")]
+    pub pubkey: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub requests: u64,
+    #[prost(uint64, tag = "3")]
+    pub failures: u64,
+    #[prost(uint32, tag = "4")]
+    pub consecutive_failures: u32,
+    #[prost(uint64, optional, tag = "5")]
+    pub last_latency_ms: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "6")]
+    pub average_latency_ms: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "7")]
+    pub last_success: ::core::option::Option<u64>,
+    #[prost(string, optional, tag = "8")]
+    pub last_error: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OracleHealthResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub oracles: ::prost::alloc::vec::Vec<OracleHealth>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ListContractsRequest {}
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
@@ -143,6 +173,122 @@ pub struct ListContractsResponse {
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct GetContractRequest {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct GetContractResponse {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub state: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub counter_party: ::prost::alloc::string::String,
+    #[prost(bool, tag = "4")]
+    pub is_offer_party: bool,
+    #[prost(uint64, tag = "5")]
+    pub offer_collateral: u64,
+    #[prost(uint64, tag = "6")]
+    pub accept_collateral: u64,
+    #[prost(uint64, tag = "7")]
+    pub fee_rate_per_vb: u64,
+    #[prost(uint32, tag = "8")]
+    pub cet_locktime: u32,
+    #[prost(uint32, tag = "9")]
+    pub refund_locktime: u32,
+    #[prost(string, optional, tag = "10")]
+    pub funding_txid: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "11")]
+    pub refund_txid: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "12")]
+    pub cet_txid: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, optional, tag = "13")]
+    pub pnl: ::core::option::Option<i64>,
+    #[prost(bytes = "vec", repeated, tag = "14")]
+    pub oracle_announcements: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", repeated, tag = "15")]
+    pub attestations: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "16")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CooperativeCloseRequest {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub counter_payout: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CooperativeCloseResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub close_dlc: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptCooperativeCloseRequest {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptCooperativeCloseResponse {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RefundContractRequest {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RefundContractResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BumpClosingFeeRequest {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub fee_rate: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BumpClosingFeeResponse {
+    #[prost(string, tag = "1")]
+    pub txid: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BumpFundingFeeRequest {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub fee_rate: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BumpFundingFeeResponse {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CloseContractRequest {
+    #[prost(string, tag = "1")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub attestations: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CloseContractResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct SendRequest {
     #[prost(string, tag = "1")]
     pub address: ::prost::alloc::string::String,
@@ -234,6 +380,187 @@ pub struct SignResponse {
     #[prost(bytes = "vec", tag = "1")]
     pub signature: ::prost::alloc::vec::Vec<u8>,
 }
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct OfferChannelRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract_input: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "2")]
+    pub counter_party: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct OfferChannelResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub offer_channel: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptChannelRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptChannelResponse {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub contract_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub counter_party: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "4")]
+    pub accept_channel: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ListChannelsRequest {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ListChannelsResponse {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub channels: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SettleOfferRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub counter_payout: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SettleOfferResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub settle_offer: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptSettleOfferRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptSettleOfferResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub settle_accept: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RejectSettleOfferRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RejectSettleOfferResponse {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RenewOfferRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub counter_payout: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub contract_input: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RenewOfferResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub renew_offer: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptRenewOfferRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptRenewOfferResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub renew_accept: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RejectRenewOfferRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RejectRenewOfferResponse {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CollaborativeCloseRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub counter_payout: u64,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct CollaborativeCloseResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub close_offer: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptCollaborativeCloseRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AcceptCollaborativeCloseResponse {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ForceCloseRequest {
+    #[prost(string, tag = "1")]
+    pub channel_id: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ForceCloseResponse {}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SubscribeEventsRequest {}
+/// A contract, channel or wallet event. `kind` names the event; the fields set
+/// depend on it. `txid` is the funding, closing, refund or wallet transaction.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SubscribeEventsResponse {
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub contract_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "3")]
+    pub temporary_contract_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub channel_id: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "5")]
+    pub counter_party: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "6")]
+    pub txid: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(int64, optional, tag = "7")]
+    pub pnl: ::core::option::Option<i64>,
+    #[prost(string, optional, tag = "8")]
+    pub state: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "9")]
+    pub signed_state: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "10")]
+    pub confirmation_height: ::core::option::Option<u32>,
+    #[prost(string, repeated, tag = "11")]
+    pub contract_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "12")]
+    pub oracle_pubkey: ::core::option::Option<::prost::alloc::string::String>,
+    /// JSON fraud proof of an oracle that attested to conflicting outcomes.
+    #[prost(string, optional, tag = "13")]
+    pub fraud_proof: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// Generated client implementations.
 pub mod ddk_rpc_client {
     #![allow(
@@ -604,6 +931,30 @@ pub mod ddk_rpc_client {
             req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "ListOracles"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn oracle_health(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OracleHealthRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OracleHealthResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/OracleHealth",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "OracleHealth"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn list_contracts(
             &mut self,
             request: impl tonic::IntoRequest<super::ListContractsRequest>,
@@ -628,10 +979,13 @@ pub mod ddk_rpc_client {
                 .insert(GrpcMethod::new("ddkrpc.DdkRpc", "ListContracts"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn send(
+        pub async fn get_contract(
             &mut self,
-            request: impl tonic::IntoRequest<super::SendRequest>,
-        ) -> std::result::Result<tonic::Response<super::SendResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::GetContractRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContractResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -641,16 +995,18 @@ pub mod ddk_rpc_client {
                     )
                 })?;
             let codec = tonic_prost::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/ddkrpc.DdkRpc/Send");
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/GetContract",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "Send"));
+            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "GetContract"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn oracle_announcements(
+        pub async fn cooperative_close(
             &mut self,
-            request: impl tonic::IntoRequest<super::OracleAnnouncementsRequest>,
+            request: impl tonic::IntoRequest<super::CooperativeCloseRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::OracleAnnouncementsResponse>,
+            tonic::Response<super::CooperativeCloseResponse>,
             tonic::Status,
         > {
             self.inner
@@ -663,18 +1019,18 @@ pub mod ddk_rpc_client {
                 })?;
             let codec = tonic_prost::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/ddkrpc.DdkRpc/OracleAnnouncements",
+                "/ddkrpc.DdkRpc/CooperativeClose",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "OracleAnnouncements"));
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "CooperativeClose"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_enum(
+        pub async fn accept_cooperative_close(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateEnumRequest>,
+            request: impl tonic::IntoRequest<super::AcceptCooperativeCloseRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateEnumResponse>,
+            tonic::Response<super::AcceptCooperativeCloseResponse>,
             tonic::Status,
         > {
             self.inner
@@ -686,16 +1042,19 @@ pub mod ddk_rpc_client {
                     )
                 })?;
             let codec = tonic_prost::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/ddkrpc.DdkRpc/CreateEnum");
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/AcceptCooperativeClose",
+            );
             let mut req = request.into_request();
-            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "CreateEnum"));
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "AcceptCooperativeClose"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_numeric(
+        pub async fn refund_contract(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateNumericRequest>,
+            request: impl tonic::IntoRequest<super::RefundContractRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateNumericResponse>,
+            tonic::Response<super::RefundContractResponse>,
             tonic::Status,
         > {
             self.inner
@@ -708,17 +1067,20 @@ pub mod ddk_rpc_client {
                 })?;
             let codec = tonic_prost::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/ddkrpc.DdkRpc/CreateNumeric",
+                "/ddkrpc.DdkRpc/RefundContract",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "CreateNumeric"));
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "RefundContract"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn sign_announcement(
+        pub async fn bump_closing_fee(
             &mut self,
-            request: impl tonic::IntoRequest<super::SignRequest>,
-        ) -> std::result::Result<tonic::Response<super::SignResponse>, tonic::Status> {
+            request: impl tonic::IntoRequest<super::BumpClosingFeeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BumpClosingFeeResponse>,
+            tonic::Status,
+        > {
             self.inner
                 .ready()
                 .await
@@ -729,167 +1091,781 @@ pub mod ddk_rpc_client {
                 })?;
             let codec = tonic_prost::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/ddkrpc.DdkRpc/SignAnnouncement",
+                "/ddkrpc.DdkRpc/BumpClosingFee",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "SignAnnouncement"));
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "BumpClosingFee"));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod ddk_rpc_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value,
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with DdkRpcServer.
-    #[async_trait]
-    pub trait DdkRpc: std::marker::Send + std::marker::Sync + 'static {
-        async fn info(
-            &self,
-            request: tonic::Request<super::InfoRequest>,
-        ) -> std::result::Result<tonic::Response<super::InfoResponse>, tonic::Status>;
-        async fn send_offer(
-            &self,
-            request: tonic::Request<super::SendOfferRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::SendOfferResponse>,
-            tonic::Status,
-        >;
-        async fn accept_offer(
-            &self,
-            request: tonic::Request<super::AcceptOfferRequest>,
+        pub async fn bump_funding_fee(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BumpFundingFeeRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::AcceptOfferResponse>,
+            tonic::Response<super::BumpFundingFeeResponse>,
             tonic::Status,
-        >;
-        async fn list_offers(
-            &self,
-            request: tonic::Request<super::ListOffersRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/BumpFundingFee",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "BumpFundingFee"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn close_contract(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CloseContractRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListOffersResponse>,
+            tonic::Response<super::CloseContractResponse>,
             tonic::Status,
-        >;
-        async fn new_address(
-            &self,
-            request: tonic::Request<super::NewAddressRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/CloseContract",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "CloseContract"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn send(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SendRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/ddkrpc.DdkRpc/Send");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "Send"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn oracle_announcements(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OracleAnnouncementsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::NewAddressResponse>,
+            tonic::Response<super::OracleAnnouncementsResponse>,
             tonic::Status,
-        >;
-        async fn wallet_balance(
-            &self,
-            request: tonic::Request<super::WalletBalanceRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/OracleAnnouncements",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "OracleAnnouncements"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_enum(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateEnumRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::WalletBalanceResponse>,
+            tonic::Response<super::CreateEnumResponse>,
             tonic::Status,
-        >;
-        async fn wallet_sync(
-            &self,
-            request: tonic::Request<super::WalletSyncRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/ddkrpc.DdkRpc/CreateEnum");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "CreateEnum"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_numeric(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateNumericRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::WalletSyncResponse>,
+            tonic::Response<super::CreateNumericResponse>,
             tonic::Status,
-        >;
-        async fn sync(
-            &self,
-            request: tonic::Request<super::SyncRequest>,
-        ) -> std::result::Result<tonic::Response<super::SyncResponse>, tonic::Status>;
-        async fn get_wallet_transactions(
-            &self,
-            request: tonic::Request<super::GetWalletTransactionsRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/CreateNumeric",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "CreateNumeric"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn sign_announcement(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SignRequest>,
+        ) -> std::result::Result<tonic::Response<super::SignResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/SignAnnouncement",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "SignAnnouncement"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn offer_channel(
+            &mut self,
+            request: impl tonic::IntoRequest<super::OfferChannelRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetWalletTransactionsResponse>,
+            tonic::Response<super::OfferChannelResponse>,
             tonic::Status,
-        >;
-        async fn list_utxos(
-            &self,
-            request: tonic::Request<super::ListUtxosRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/OfferChannel",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "OfferChannel"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn accept_channel(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AcceptChannelRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListUtxosResponse>,
+            tonic::Response<super::AcceptChannelResponse>,
             tonic::Status,
-        >;
-        async fn list_peers(
-            &self,
-            request: tonic::Request<super::ListPeersRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/AcceptChannel",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "AcceptChannel"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_channels(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListChannelsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListPeersResponse>,
+            tonic::Response<super::ListChannelsResponse>,
             tonic::Status,
-        >;
-        async fn connect_peer(
-            &self,
-            request: tonic::Request<super::ConnectRequest>,
-        ) -> std::result::Result<tonic::Response<super::ConnectResponse>, tonic::Status>;
-        async fn list_oracles(
-            &self,
-            request: tonic::Request<super::ListOraclesRequest>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/ListChannels",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "ListChannels"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn settle_offer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SettleOfferRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListOraclesResponse>,
+            tonic::Response<super::SettleOfferResponse>,
             tonic::Status,
-        >;
-        async fn list_contracts(
-            &self,
-            request: tonic::Request<super::ListContractsRequest>,
-        ) -> std::result::Result<
-            tonic::Response<super::ListContractsResponse>,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/SettleOffer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "SettleOffer"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn accept_settle_offer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AcceptSettleOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptSettleOfferResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/AcceptSettleOffer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "AcceptSettleOffer"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn reject_settle_offer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RejectSettleOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RejectSettleOfferResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/RejectSettleOffer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "RejectSettleOffer"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn renew_offer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RenewOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RenewOfferResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/ddkrpc.DdkRpc/RenewOffer");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "RenewOffer"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn accept_renew_offer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AcceptRenewOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptRenewOfferResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/AcceptRenewOffer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "AcceptRenewOffer"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn reject_renew_offer(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RejectRenewOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RejectRenewOfferResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/RejectRenewOffer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "RejectRenewOffer"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn collaborative_close(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CollaborativeCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CollaborativeCloseResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/CollaborativeClose",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "CollaborativeClose"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn accept_collaborative_close(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AcceptCollaborativeCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptCollaborativeCloseResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/AcceptCollaborativeClose",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "AcceptCollaborativeClose"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn force_close(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ForceCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ForceCloseResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/ddkrpc.DdkRpc/ForceClose");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("ddkrpc.DdkRpc", "ForceClose"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn subscribe_events(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SubscribeEventsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::SubscribeEventsResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/ddkrpc.DdkRpc/SubscribeEvents",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("ddkrpc.DdkRpc", "SubscribeEvents"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod ddk_rpc_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with DdkRpcServer.
+    #[async_trait]
+    pub trait DdkRpc: std::marker::Send + std::marker::Sync + 'static {
+        async fn info(
+            &self,
+            request: tonic::Request<super::InfoRequest>,
+        ) -> std::result::Result<tonic::Response<super::InfoResponse>, tonic::Status>;
+        async fn send_offer(
+            &self,
+            request: tonic::Request<super::SendOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SendOfferResponse>,
             tonic::Status,
         >;
-        async fn send(
+        async fn accept_offer(
             &self,
-            request: tonic::Request<super::SendRequest>,
-        ) -> std::result::Result<tonic::Response<super::SendResponse>, tonic::Status>;
-        async fn oracle_announcements(
+            request: tonic::Request<super::AcceptOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptOfferResponse>,
+            tonic::Status,
+        >;
+        async fn list_offers(
             &self,
-            request: tonic::Request<super::OracleAnnouncementsRequest>,
+            request: tonic::Request<super::ListOffersRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::OracleAnnouncementsResponse>,
+            tonic::Response<super::ListOffersResponse>,
             tonic::Status,
         >;
-        async fn create_enum(
+        async fn new_address(
             &self,
-            request: tonic::Request<super::CreateEnumRequest>,
+            request: tonic::Request<super::NewAddressRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateEnumResponse>,
+            tonic::Response<super::NewAddressResponse>,
             tonic::Status,
         >;
-        async fn create_numeric(
+        async fn wallet_balance(
             &self,
-            request: tonic::Request<super::CreateNumericRequest>,
+            request: tonic::Request<super::WalletBalanceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateNumericResponse>,
+            tonic::Response<super::WalletBalanceResponse>,
             tonic::Status,
         >;
-        async fn sign_announcement(
+        async fn wallet_sync(
             &self,
-            request: tonic::Request<super::SignRequest>,
-        ) -> std::result::Result<tonic::Response<super::SignResponse>, tonic::Status>;
-    }
-    #[derive(Debug)]
-    pub struct DdkRpcServer<T> {
-        inner: Arc<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-        max_decoding_message_size: Option<usize>,
-        max_encoding_message_size: Option<usize>,
-    }
-    impl<T> DdkRpcServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-                max_decoding_message_size: None,
-                max_encoding_message_size: None,
-            }
+            request: tonic::Request<super::WalletSyncRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::WalletSyncResponse>,
+            tonic::Status,
+        >;
+        async fn sync(
+            &self,
+            request: tonic::Request<super::SyncRequest>,
+        ) -> std::result::Result<tonic::Response<super::SyncResponse>, tonic::Status>;
+        async fn get_wallet_transactions(
+            &self,
+            request: tonic::Request<super::GetWalletTransactionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetWalletTransactionsResponse>,
+            tonic::Status,
+        >;
+        async fn list_utxos(
+            &self,
+            request: tonic::Request<super::ListUtxosRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListUtxosResponse>,
+            tonic::Status,
+        >;
+        async fn list_peers(
+            &self,
+            request: tonic::Request<super::ListPeersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListPeersResponse>,
+            tonic::Status,
+        >;
+        async fn connect_peer(
+            &self,
+            request: tonic::Request<super::ConnectRequest>,
+        ) -> std::result::Result<tonic::Response<super::ConnectResponse>, tonic::Status>;
+        async fn list_oracles(
+            &self,
+            request: tonic::Request<super::ListOraclesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListOraclesResponse>,
+            tonic::Status,
+        >;
+        async fn oracle_health(
+            &self,
+            request: tonic::Request<super::OracleHealthRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OracleHealthResponse>,
+            tonic::Status,
+        >;
+        async fn list_contracts(
+            &self,
+            request: tonic::Request<super::ListContractsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListContractsResponse>,
+            tonic::Status,
+        >;
+        async fn get_contract(
+            &self,
+            request: tonic::Request<super::GetContractRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContractResponse>,
+            tonic::Status,
+        >;
+        async fn cooperative_close(
+            &self,
+            request: tonic::Request<super::CooperativeCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CooperativeCloseResponse>,
+            tonic::Status,
+        >;
+        async fn accept_cooperative_close(
+            &self,
+            request: tonic::Request<super::AcceptCooperativeCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptCooperativeCloseResponse>,
+            tonic::Status,
+        >;
+        async fn refund_contract(
+            &self,
+            request: tonic::Request<super::RefundContractRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RefundContractResponse>,
+            tonic::Status,
+        >;
+        async fn bump_closing_fee(
+            &self,
+            request: tonic::Request<super::BumpClosingFeeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BumpClosingFeeResponse>,
+            tonic::Status,
+        >;
+        async fn bump_funding_fee(
+            &self,
+            request: tonic::Request<super::BumpFundingFeeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BumpFundingFeeResponse>,
+            tonic::Status,
+        >;
+        async fn close_contract(
+            &self,
+            request: tonic::Request<super::CloseContractRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CloseContractResponse>,
+            tonic::Status,
+        >;
+        async fn send(
+            &self,
+            request: tonic::Request<super::SendRequest>,
+        ) -> std::result::Result<tonic::Response<super::SendResponse>, tonic::Status>;
+        async fn oracle_announcements(
+            &self,
+            request: tonic::Request<super::OracleAnnouncementsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OracleAnnouncementsResponse>,
+            tonic::Status,
+        >;
+        async fn create_enum(
+            &self,
+            request: tonic::Request<super::CreateEnumRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateEnumResponse>,
+            tonic::Status,
+        >;
+        async fn create_numeric(
+            &self,
+            request: tonic::Request<super::CreateNumericRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateNumericResponse>,
+            tonic::Status,
+        >;
+        async fn sign_announcement(
+            &self,
+            request: tonic::Request<super::SignRequest>,
+        ) -> std::result::Result<tonic::Response<super::SignResponse>, tonic::Status>;
+        async fn offer_channel(
+            &self,
+            request: tonic::Request<super::OfferChannelRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::OfferChannelResponse>,
+            tonic::Status,
+        >;
+        async fn accept_channel(
+            &self,
+            request: tonic::Request<super::AcceptChannelRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptChannelResponse>,
+            tonic::Status,
+        >;
+        async fn list_channels(
+            &self,
+            request: tonic::Request<super::ListChannelsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListChannelsResponse>,
+            tonic::Status,
+        >;
+        async fn settle_offer(
+            &self,
+            request: tonic::Request<super::SettleOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SettleOfferResponse>,
+            tonic::Status,
+        >;
+        async fn accept_settle_offer(
+            &self,
+            request: tonic::Request<super::AcceptSettleOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptSettleOfferResponse>,
+            tonic::Status,
+        >;
+        async fn reject_settle_offer(
+            &self,
+            request: tonic::Request<super::RejectSettleOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RejectSettleOfferResponse>,
+            tonic::Status,
+        >;
+        async fn renew_offer(
+            &self,
+            request: tonic::Request<super::RenewOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RenewOfferResponse>,
+            tonic::Status,
+        >;
+        async fn accept_renew_offer(
+            &self,
+            request: tonic::Request<super::AcceptRenewOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptRenewOfferResponse>,
+            tonic::Status,
+        >;
+        async fn reject_renew_offer(
+            &self,
+            request: tonic::Request<super::RejectRenewOfferRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RejectRenewOfferResponse>,
+            tonic::Status,
+        >;
+        async fn collaborative_close(
+            &self,
+            request: tonic::Request<super::CollaborativeCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CollaborativeCloseResponse>,
+            tonic::Status,
+        >;
+        async fn accept_collaborative_close(
+            &self,
+            request: tonic::Request<super::AcceptCollaborativeCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AcceptCollaborativeCloseResponse>,
+            tonic::Status,
+        >;
+        async fn force_close(
+            &self,
+            request: tonic::Request<super::ForceCloseRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ForceCloseResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the SubscribeEvents method.
+        type SubscribeEventsStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::SubscribeEventsResponse, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        async fn subscribe_events(
+            &self,
+            request: tonic::Request<super::SubscribeEventsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubscribeEventsStream>,
+            tonic::Status,
+        >;
+    }
+    #[derive(Debug)]
+    pub struct DdkRpcServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> DdkRpcServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
         }
         pub fn with_interceptor<F>(
             inner: T,
@@ -948,21 +1924,907 @@ pub mod ddk_rpc_server {
             match req.uri().path() {
                 "/ddkrpc.DdkRpc/Info" => {
                     #[allow(non_camel_case_types)]
-                    struct InfoSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::InfoRequest>
-                    for InfoSvc<T> {
-                        type Response = super::InfoResponse;
+                    struct InfoSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::InfoRequest>
+                    for InfoSvc<T> {
+                        type Response = super::InfoResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::InfoRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::info(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = InfoSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/SendOffer" => {
+                    #[allow(non_camel_case_types)]
+                    struct SendOfferSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::SendOfferRequest>
+                    for SendOfferSvc<T> {
+                        type Response = super::SendOfferResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SendOfferRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::send_offer(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SendOfferSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/AcceptOffer" => {
+                    #[allow(non_camel_case_types)]
+                    struct AcceptOfferSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::AcceptOfferRequest>
+                    for AcceptOfferSvc<T> {
+                        type Response = super::AcceptOfferResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AcceptOfferRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::accept_offer(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = AcceptOfferSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/ListOffers" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListOffersSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::ListOffersRequest>
+                    for ListOffersSvc<T> {
+                        type Response = super::ListOffersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListOffersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::list_offers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListOffersSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/NewAddress" => {
+                    #[allow(non_camel_case_types)]
+                    struct NewAddressSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::NewAddressRequest>
+                    for NewAddressSvc<T> {
+                        type Response = super::NewAddressResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::NewAddressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::new_address(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = NewAddressSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/WalletBalance" => {
+                    #[allow(non_camel_case_types)]
+                    struct WalletBalanceSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::WalletBalanceRequest>
+                    for WalletBalanceSvc<T> {
+                        type Response = super::WalletBalanceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WalletBalanceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::wallet_balance(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WalletBalanceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/WalletSync" => {
+                    #[allow(non_camel_case_types)]
+                    struct WalletSyncSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::WalletSyncRequest>
+                    for WalletSyncSvc<T> {
+                        type Response = super::WalletSyncResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WalletSyncRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::wallet_sync(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WalletSyncSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/Sync" => {
+                    #[allow(non_camel_case_types)]
+                    struct SyncSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::SyncRequest>
+                    for SyncSvc<T> {
+                        type Response = super::SyncResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SyncRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::sync(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SyncSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/GetWalletTransactions" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetWalletTransactionsSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::GetWalletTransactionsRequest>
+                    for GetWalletTransactionsSvc<T> {
+                        type Response = super::GetWalletTransactionsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetWalletTransactionsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::get_wallet_transactions(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetWalletTransactionsSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/ListUtxos" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListUtxosSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::ListUtxosRequest>
+                    for ListUtxosSvc<T> {
+                        type Response = super::ListUtxosResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListUtxosRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::list_utxos(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListUtxosSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/ListPeers" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListPeersSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::ListPeersRequest>
+                    for ListPeersSvc<T> {
+                        type Response = super::ListPeersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListPeersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::list_peers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListPeersSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/ConnectPeer" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConnectPeerSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::ConnectRequest>
+                    for ConnectPeerSvc<T> {
+                        type Response = super::ConnectResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConnectRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::connect_peer(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ConnectPeerSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/ListOracles" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListOraclesSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::ListOraclesRequest>
+                    for ListOraclesSvc<T> {
+                        type Response = super::ListOraclesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListOraclesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::list_oracles(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListOraclesSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/OracleHealth" => {
+                    #[allow(non_camel_case_types)]
+                    struct OracleHealthSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::OracleHealthRequest>
+                    for OracleHealthSvc<T> {
+                        type Response = super::OracleHealthResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::OracleHealthRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::oracle_health(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = OracleHealthSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/ListContracts" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListContractsSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::ListContractsRequest>
+                    for ListContractsSvc<T> {
+                        type Response = super::ListContractsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListContractsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::list_contracts(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListContractsSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/GetContract" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetContractSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::GetContractRequest>
+                    for GetContractSvc<T> {
+                        type Response = super::GetContractResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetContractRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::get_contract(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetContractSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/CooperativeClose" => {
+                    #[allow(non_camel_case_types)]
+                    struct CooperativeCloseSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::CooperativeCloseRequest>
+                    for CooperativeCloseSvc<T> {
+                        type Response = super::CooperativeCloseResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CooperativeCloseRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::cooperative_close(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = CooperativeCloseSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/AcceptCooperativeClose" => {
+                    #[allow(non_camel_case_types)]
+                    struct AcceptCooperativeCloseSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::AcceptCooperativeCloseRequest>
+                    for AcceptCooperativeCloseSvc<T> {
+                        type Response = super::AcceptCooperativeCloseResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AcceptCooperativeCloseRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::accept_cooperative_close(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = AcceptCooperativeCloseSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/RefundContract" => {
+                    #[allow(non_camel_case_types)]
+                    struct RefundContractSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::RefundContractRequest>
+                    for RefundContractSvc<T> {
+                        type Response = super::RefundContractResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RefundContractRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::refund_contract(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RefundContractSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/BumpClosingFee" => {
+                    #[allow(non_camel_case_types)]
+                    struct BumpClosingFeeSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::BumpClosingFeeRequest>
+                    for BumpClosingFeeSvc<T> {
+                        type Response = super::BumpClosingFeeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::BumpClosingFeeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::bump_closing_fee(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = BumpClosingFeeSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/ddkrpc.DdkRpc/BumpFundingFee" => {
+                    #[allow(non_camel_case_types)]
+                    struct BumpFundingFeeSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::BumpFundingFeeRequest>
+                    for BumpFundingFeeSvc<T> {
+                        type Response = super::BumpFundingFeeResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::InfoRequest>,
+                            request: tonic::Request<super::BumpFundingFeeRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::info(&inner, request).await
+                                <T as DdkRpc>::bump_funding_fee(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -973,7 +2835,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = InfoSvc(inner);
+                        let method = BumpFundingFeeSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -989,23 +2851,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/SendOffer" => {
+                "/ddkrpc.DdkRpc/CloseContract" => {
                     #[allow(non_camel_case_types)]
-                    struct SendOfferSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::SendOfferRequest>
-                    for SendOfferSvc<T> {
-                        type Response = super::SendOfferResponse;
+                    struct CloseContractSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::CloseContractRequest>
+                    for CloseContractSvc<T> {
+                        type Response = super::CloseContractResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SendOfferRequest>,
+                            request: tonic::Request<super::CloseContractRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::send_offer(&inner, request).await
+                                <T as DdkRpc>::close_contract(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1016,7 +2880,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = SendOfferSvc(inner);
+                        let method = CloseContractSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1032,25 +2896,23 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/AcceptOffer" => {
+                "/ddkrpc.DdkRpc/Send" => {
                     #[allow(non_camel_case_types)]
-                    struct AcceptOfferSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<
-                        T: DdkRpc,
-                    > tonic::server::UnaryService<super::AcceptOfferRequest>
-                    for AcceptOfferSvc<T> {
-                        type Response = super::AcceptOfferResponse;
+                    struct SendSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::SendRequest>
+                    for SendSvc<T> {
+                        type Response = super::SendResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::AcceptOfferRequest>,
+                            request: tonic::Request<super::SendRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::accept_offer(&inner, request).await
+                                <T as DdkRpc>::send(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1061,7 +2923,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = AcceptOfferSvc(inner);
+                        let method = SendSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1077,23 +2939,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/ListOffers" => {
+                "/ddkrpc.DdkRpc/OracleAnnouncements" => {
                     #[allow(non_camel_case_types)]
-                    struct ListOffersSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::ListOffersRequest>
-                    for ListOffersSvc<T> {
-                        type Response = super::ListOffersResponse;
+                    struct OracleAnnouncementsSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::OracleAnnouncementsRequest>
+                    for OracleAnnouncementsSvc<T> {
+                        type Response = super::OracleAnnouncementsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListOffersRequest>,
+                            request: tonic::Request<super::OracleAnnouncementsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::list_offers(&inner, request).await
+                                <T as DdkRpc>::oracle_announcements(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1104,7 +2968,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListOffersSvc(inner);
+                        let method = OracleAnnouncementsSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1120,23 +2984,23 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/NewAddress" => {
+                "/ddkrpc.DdkRpc/CreateEnum" => {
                     #[allow(non_camel_case_types)]
-                    struct NewAddressSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::NewAddressRequest>
-                    for NewAddressSvc<T> {
-                        type Response = super::NewAddressResponse;
+                    struct CreateEnumSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::CreateEnumRequest>
+                    for CreateEnumSvc<T> {
+                        type Response = super::CreateEnumResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::NewAddressRequest>,
+                            request: tonic::Request<super::CreateEnumRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::new_address(&inner, request).await
+                                <T as DdkRpc>::create_enum(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1147,7 +3011,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = NewAddressSvc(inner);
+                        let method = CreateEnumSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1163,25 +3027,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/WalletBalance" => {
+                "/ddkrpc.DdkRpc/CreateNumeric" => {
                     #[allow(non_camel_case_types)]
-                    struct WalletBalanceSvc<T: DdkRpc>(pub Arc<T>);
+                    struct CreateNumericSvc<T: DdkRpc>(pub Arc<T>);
                     impl<
                         T: DdkRpc,
-                    > tonic::server::UnaryService<super::WalletBalanceRequest>
-                    for WalletBalanceSvc<T> {
-                        type Response = super::WalletBalanceResponse;
+                    > tonic::server::UnaryService<super::CreateNumericRequest>
+                    for CreateNumericSvc<T> {
+                        type Response = super::CreateNumericResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::WalletBalanceRequest>,
+                            request: tonic::Request<super::CreateNumericRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::wallet_balance(&inner, request).await
+                                <T as DdkRpc>::create_numeric(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1192,7 +3056,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = WalletBalanceSvc(inner);
+                        let method = CreateNumericSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1208,23 +3072,23 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/WalletSync" => {
+                "/ddkrpc.DdkRpc/SignAnnouncement" => {
                     #[allow(non_camel_case_types)]
-                    struct WalletSyncSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::WalletSyncRequest>
-                    for WalletSyncSvc<T> {
-                        type Response = super::WalletSyncResponse;
+                    struct SignAnnouncementSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::SignRequest>
+                    for SignAnnouncementSvc<T> {
+                        type Response = super::SignResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::WalletSyncRequest>,
+                            request: tonic::Request<super::SignRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::wallet_sync(&inner, request).await
+                                <T as DdkRpc>::sign_announcement(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1235,7 +3099,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = WalletSyncSvc(inner);
+                        let method = SignAnnouncementSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1251,23 +3115,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/Sync" => {
+                "/ddkrpc.DdkRpc/OfferChannel" => {
                     #[allow(non_camel_case_types)]
-                    struct SyncSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::SyncRequest>
-                    for SyncSvc<T> {
-                        type Response = super::SyncResponse;
+                    struct OfferChannelSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::OfferChannelRequest>
+                    for OfferChannelSvc<T> {
+                        type Response = super::OfferChannelResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SyncRequest>,
+                            request: tonic::Request<super::OfferChannelRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::sync(&inner, request).await
+                                <T as DdkRpc>::offer_channel(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1278,7 +3144,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = SyncSvc(inner);
+                        let method = OfferChannelSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1294,26 +3160,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/GetWalletTransactions" => {
+                "/ddkrpc.DdkRpc/AcceptChannel" => {
                     #[allow(non_camel_case_types)]
-                    struct GetWalletTransactionsSvc<T: DdkRpc>(pub Arc<T>);
+                    struct AcceptChannelSvc<T: DdkRpc>(pub Arc<T>);
                     impl<
                         T: DdkRpc,
-                    > tonic::server::UnaryService<super::GetWalletTransactionsRequest>
-                    for GetWalletTransactionsSvc<T> {
-                        type Response = super::GetWalletTransactionsResponse;
+                    > tonic::server::UnaryService<super::AcceptChannelRequest>
+                    for AcceptChannelSvc<T> {
+                        type Response = super::AcceptChannelResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetWalletTransactionsRequest>,
+                            request: tonic::Request<super::AcceptChannelRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::get_wallet_transactions(&inner, request)
-                                    .await
+                                <T as DdkRpc>::accept_channel(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1324,7 +3189,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetWalletTransactionsSvc(inner);
+                        let method = AcceptChannelSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1340,23 +3205,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/ListUtxos" => {
+                "/ddkrpc.DdkRpc/ListChannels" => {
                     #[allow(non_camel_case_types)]
-                    struct ListUtxosSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::ListUtxosRequest>
-                    for ListUtxosSvc<T> {
-                        type Response = super::ListUtxosResponse;
+                    struct ListChannelsSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::ListChannelsRequest>
+                    for ListChannelsSvc<T> {
+                        type Response = super::ListChannelsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListUtxosRequest>,
+                            request: tonic::Request<super::ListChannelsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::list_utxos(&inner, request).await
+                                <T as DdkRpc>::list_channels(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1367,7 +3234,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListUtxosSvc(inner);
+                        let method = ListChannelsSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1383,23 +3250,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/ListPeers" => {
+                "/ddkrpc.DdkRpc/SettleOffer" => {
                     #[allow(non_camel_case_types)]
-                    struct ListPeersSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::ListPeersRequest>
-                    for ListPeersSvc<T> {
-                        type Response = super::ListPeersResponse;
+                    struct SettleOfferSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::SettleOfferRequest>
+                    for SettleOfferSvc<T> {
+                        type Response = super::SettleOfferResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListPeersRequest>,
+                            request: tonic::Request<super::SettleOfferRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::list_peers(&inner, request).await
+                                <T as DdkRpc>::settle_offer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1410,7 +3279,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListPeersSvc(inner);
+                        let method = SettleOfferSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1426,23 +3295,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/ConnectPeer" => {
+                "/ddkrpc.DdkRpc/AcceptSettleOffer" => {
                     #[allow(non_camel_case_types)]
-                    struct ConnectPeerSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::ConnectRequest>
-                    for ConnectPeerSvc<T> {
-                        type Response = super::ConnectResponse;
+                    struct AcceptSettleOfferSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::AcceptSettleOfferRequest>
+                    for AcceptSettleOfferSvc<T> {
+                        type Response = super::AcceptSettleOfferResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ConnectRequest>,
+                            request: tonic::Request<super::AcceptSettleOfferRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::connect_peer(&inner, request).await
+                                <T as DdkRpc>::accept_settle_offer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1453,7 +3324,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ConnectPeerSvc(inner);
+                        let method = AcceptSettleOfferSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1469,25 +3340,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/ListOracles" => {
+                "/ddkrpc.DdkRpc/RejectSettleOffer" => {
                     #[allow(non_camel_case_types)]
-                    struct ListOraclesSvc<T: DdkRpc>(pub Arc<T>);
+                    struct RejectSettleOfferSvc<T: DdkRpc>(pub Arc<T>);
                     impl<
                         T: DdkRpc,
-                    > tonic::server::UnaryService<super::ListOraclesRequest>
-                    for ListOraclesSvc<T> {
-                        type Response = super::ListOraclesResponse;
+                    > tonic::server::UnaryService<super::RejectSettleOfferRequest>
+                    for RejectSettleOfferSvc<T> {
+                        type Response = super::RejectSettleOfferResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListOraclesRequest>,
+                            request: tonic::Request<super::RejectSettleOfferRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::list_oracles(&inner, request).await
+                                <T as DdkRpc>::reject_settle_offer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1498,7 +3369,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListOraclesSvc(inner);
+                        let method = RejectSettleOfferSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1514,25 +3385,23 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/ListContracts" => {
+                "/ddkrpc.DdkRpc/RenewOffer" => {
                     #[allow(non_camel_case_types)]
-                    struct ListContractsSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<
-                        T: DdkRpc,
-                    > tonic::server::UnaryService<super::ListContractsRequest>
-                    for ListContractsSvc<T> {
-                        type Response = super::ListContractsResponse;
+                    struct RenewOfferSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::RenewOfferRequest>
+                    for RenewOfferSvc<T> {
+                        type Response = super::RenewOfferResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListContractsRequest>,
+                            request: tonic::Request<super::RenewOfferRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::list_contracts(&inner, request).await
+                                <T as DdkRpc>::renew_offer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1543,7 +3412,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListContractsSvc(inner);
+                        let method = RenewOfferSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1559,23 +3428,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/Send" => {
+                "/ddkrpc.DdkRpc/AcceptRenewOffer" => {
                     #[allow(non_camel_case_types)]
-                    struct SendSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::SendRequest>
-                    for SendSvc<T> {
-                        type Response = super::SendResponse;
+                    struct AcceptRenewOfferSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::AcceptRenewOfferRequest>
+                    for AcceptRenewOfferSvc<T> {
+                        type Response = super::AcceptRenewOfferResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SendRequest>,
+                            request: tonic::Request<super::AcceptRenewOfferRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::send(&inner, request).await
+                                <T as DdkRpc>::accept_renew_offer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1586,7 +3457,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = SendSvc(inner);
+                        let method = AcceptRenewOfferSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1602,25 +3473,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/OracleAnnouncements" => {
+                "/ddkrpc.DdkRpc/RejectRenewOffer" => {
                     #[allow(non_camel_case_types)]
-                    struct OracleAnnouncementsSvc<T: DdkRpc>(pub Arc<T>);
+                    struct RejectRenewOfferSvc<T: DdkRpc>(pub Arc<T>);
                     impl<
                         T: DdkRpc,
-                    > tonic::server::UnaryService<super::OracleAnnouncementsRequest>
-                    for OracleAnnouncementsSvc<T> {
-                        type Response = super::OracleAnnouncementsResponse;
+                    > tonic::server::UnaryService<super::RejectRenewOfferRequest>
+                    for RejectRenewOfferSvc<T> {
+                        type Response = super::RejectRenewOfferResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::OracleAnnouncementsRequest>,
+                            request: tonic::Request<super::RejectRenewOfferRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::oracle_announcements(&inner, request).await
+                                <T as DdkRpc>::reject_renew_offer(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1631,7 +3502,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = OracleAnnouncementsSvc(inner);
+                        let method = RejectRenewOfferSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1647,23 +3518,25 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/CreateEnum" => {
+                "/ddkrpc.DdkRpc/CollaborativeClose" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateEnumSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::CreateEnumRequest>
-                    for CreateEnumSvc<T> {
-                        type Response = super::CreateEnumResponse;
+                    struct CollaborativeCloseSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::UnaryService<super::CollaborativeCloseRequest>
+                    for CollaborativeCloseSvc<T> {
+                        type Response = super::CollaborativeCloseResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateEnumRequest>,
+                            request: tonic::Request<super::CollaborativeCloseRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::create_enum(&inner, request).await
+                                <T as DdkRpc>::collaborative_close(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1674,7 +3547,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CreateEnumSvc(inner);
+                        let method = CollaborativeCloseSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1690,25 +3563,28 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/CreateNumeric" => {
+                "/ddkrpc.DdkRpc/AcceptCollaborativeClose" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateNumericSvc<T: DdkRpc>(pub Arc<T>);
+                    struct AcceptCollaborativeCloseSvc<T: DdkRpc>(pub Arc<T>);
                     impl<
                         T: DdkRpc,
-                    > tonic::server::UnaryService<super::CreateNumericRequest>
-                    for CreateNumericSvc<T> {
-                        type Response = super::CreateNumericResponse;
+                    > tonic::server::UnaryService<super::AcceptCollaborativeCloseRequest>
+                    for AcceptCollaborativeCloseSvc<T> {
+                        type Response = super::AcceptCollaborativeCloseResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateNumericRequest>,
+                            request: tonic::Request<
+                                super::AcceptCollaborativeCloseRequest,
+                            >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::create_numeric(&inner, request).await
+                                <T as DdkRpc>::accept_collaborative_close(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -1719,7 +3595,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = CreateNumericSvc(inner);
+                        let method = AcceptCollaborativeCloseSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1735,23 +3611,23 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
-                "/ddkrpc.DdkRpc/SignAnnouncement" => {
+                "/ddkrpc.DdkRpc/ForceClose" => {
                     #[allow(non_camel_case_types)]
-                    struct SignAnnouncementSvc<T: DdkRpc>(pub Arc<T>);
-                    impl<T: DdkRpc> tonic::server::UnaryService<super::SignRequest>
-                    for SignAnnouncementSvc<T> {
-                        type Response = super::SignResponse;
+                    struct ForceCloseSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<T: DdkRpc> tonic::server::UnaryService<super::ForceCloseRequest>
+                    for ForceCloseSvc<T> {
+                        type Response = super::ForceCloseResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SignRequest>,
+                            request: tonic::Request<super::ForceCloseRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as DdkRpc>::sign_announcement(&inner, request).await
+                                <T as DdkRpc>::force_close(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -1762,7 +3638,7 @@ pub mod ddk_rpc_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = SignAnnouncementSvc(inner);
+                        let method = ForceCloseSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1778,6 +3654,53 @@ pub mod ddk_rpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/ddkrpc.DdkRpc/SubscribeEvents" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeEventsSvc<T: DdkRpc>(pub Arc<T>);
+                    impl<
+                        T: DdkRpc,
+                    > tonic::server::ServerStreamingService<
+                        super::SubscribeEventsRequest,
+                    > for SubscribeEventsSvc<T> {
+                        type Response = super::SubscribeEventsResponse;
+                        type ResponseStream = T::SubscribeEventsStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SubscribeEventsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DdkRpc>::subscribe_events(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubscribeEventsSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(
@@ -1,114 +1,222 @@
+pub mod auth;
+pub mod backend;
 pub mod cli_opts;
 pub mod command;
+pub mod config;
 pub mod ddkrpc;
 pub mod opts;
 mod seed;
+mod tls;
 
+use auth::{AuthInterceptor, Credentials};
+use backend::{NodeStorage, NodeTransport};
+use bitcoin::hashes::{sha256, Hash};
 use bitcoin::secp256k1::PublicKey;
-use bitcoin::{Address, Amount, FeeRate, Network};
+use bitcoin::{Address, Amount, FeeRate};
+use config::{ChainSource, NodeConfig, OracleConfig, StorageBackend, TransportKind};
 use ddk::builder::{Builder, SeedConfig};
 use ddk::logger::{LogLevel, Logger};
 use ddk::oracle::kormir::KormirOracleClient;
+use ddk::oracle::p2p_derivatives::P2PDOracleClient;
+use ddk::oracle::set::OracleSet;
+use ddk::storage::memory::MemoryStorage;
 use ddk::storage::postgres::PostgresStore;
+use ddk::storage::sled::SledStorage;
+use ddk::transport::lightning::LightningTransport;
 use ddk::transport::nostr::NostrDlc;
-use ddk::util::ser::serialize_contract;
+use ddk::util::ser::{serialize_channel, serialize_contract};
 use ddk::DlcDevKit;
 use ddk::{Oracle, Transport};
 use ddk_manager::contract::contract_input::ContractInput;
+use ddk_manager::contract::Contract;
+use ddk_manager::events::Event;
 use ddk_manager::Oracle as DlcOracle;
 use ddk_manager::Storage as DlcStorage;
+use ddk_messages::oracle_msgs::OracleAttestation;
 use ddkrpc::ddk_rpc_server::{DdkRpc, DdkRpcServer};
+use ddkrpc::{
+    AcceptChannelRequest, AcceptChannelResponse, AcceptCollaborativeCloseRequest,
+    AcceptCollaborativeCloseResponse, AcceptRenewOfferRequest, AcceptRenewOfferResponse,
+    AcceptSettleOfferRequest, AcceptSettleOfferResponse, CollaborativeCloseRequest,
+    CollaborativeCloseResponse, ForceCloseRequest, ForceCloseResponse, ListChannelsRequest,
+    ListChannelsResponse, OfferChannelRequest, OfferChannelResponse, RejectRenewOfferRequest,
+    RejectRenewOfferResponse, RejectSettleOfferRequest, RejectSettleOfferResponse,
+    RenewOfferRequest, RenewOfferResponse, SettleOfferRequest, SettleOfferResponse,
+};
+use ddkrpc::{
+    AcceptCooperativeCloseRequest, AcceptCooperativeCloseResponse, BumpClosingFeeRequest,
+    BumpClosingFeeResponse, BumpFundingFeeRequest, BumpFundingFeeResponse, CloseContractRequest,
+    CloseContractResponse, CooperativeCloseRequest, CooperativeCloseResponse, GetContractRequest,
+    GetContractResponse, RefundContractRequest, RefundContractResponse,
+};
 use ddkrpc::{
     AcceptOfferRequest, AcceptOfferResponse, ConnectRequest, ConnectResponse, CreateEnumRequest,
     CreateEnumResponse, CreateNumericRequest, CreateNumericResponse, GetWalletTransactionsRequest,
     GetWalletTransactionsResponse, ListContractsRequest, ListContractsResponse, ListOffersRequest,
     ListOffersResponse, ListOraclesRequest, ListOraclesResponse, ListPeersRequest,
     ListPeersResponse, ListUtxosRequest, ListUtxosResponse, NewAddressRequest, NewAddressResponse,
-    OracleAnnouncementsRequest, OracleAnnouncementsResponse, SendOfferRequest, SendOfferResponse,
-    SendRequest, SendResponse, SignRequest, SignResponse, SyncRequest, SyncResponse,
-    WalletBalanceRequest, WalletBalanceResponse, WalletSyncRequest, WalletSyncResponse,
+    OracleAnnouncementsRequest, OracleAnnouncementsResponse, OracleHealth, OracleHealthRequest,
+    OracleHealthResponse, SendOfferRequest, SendOfferResponse, SendRequest, SendResponse,
+    SignRequest, SignResponse, SyncRequest, SyncResponse, WalletBalanceRequest,
+    WalletBalanceResponse, WalletSyncRequest, WalletSyncResponse,
 };
-use ddkrpc::{InfoRequest, InfoResponse};
-use opts::NodeOpts;
+use ddkrpc::{InfoRequest, InfoResponse, SubscribeEventsRequest, SubscribeEventsResponse};
+use futures::stream::{self, Stream};
+use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::transport::Server;
 use tonic::Request;
 use tonic::Response;
 use tonic::Status;
 use tonic::{async_trait, Code};
+use tower::filter::FilterLayer;
 
-type Ddk = DlcDevKit<NostrDlc, PostgresStore, KormirOracleClient>;
+type Ddk = DlcDevKit<NodeTransport, NodeStorage, OracleSet>;
 
 #[derive(Clone)]
 pub struct DdkNode {
     pub node: Arc<Ddk>,
+    /// Kormir oracle the node creates and signs oracle events with, the first configured one.
+    pub kormir: Option<Arc<KormirOracleClient>>,
 }
 
 impl DdkNode {
-    pub fn new(ddk: Ddk) -> Self {
+    pub fn new(ddk: Ddk, kormir: Option<Arc<KormirOracleClient>>) -> Self {
         Self {
             node: Arc::new(ddk),
+            kormir,
         }
     }
 
-    pub async fn serve(opts: NodeOpts) -> anyhow::Result<()> {
+    pub async fn serve(config: NodeConfig) -> anyhow::Result<()> {
         let logger = Arc::new(Logger::console(
             "console_logger".to_string(),
-            LogLevel::from(opts.log),
+            LogLevel::from(config.log.clone()),
         ));
-        let storage_path = match opts.storage_dir {
-            Some(storage) => storage,
-            None => homedir::my_home()
-                .expect("Provide a directory for ddk.")
-                .unwrap()
-                .join(".ddk")
-                .join("default-ddk"),
-        };
-        let network = Network::from_str(&opts.network)?;
+        let storage_path = config.storage_path()?;
+        let network = config.network()?;
         std::fs::create_dir_all(storage_path.clone())?;
 
         let seed_bytes = crate::seed::xprv_from_path(storage_path.clone())?;
 
-        let transport = Arc::new(
-            NostrDlc::new(
+        let transport = match config.transport.kind {
+            TransportKind::Lightning => NodeTransport::Lightning(lightning_transport(
                 &seed_bytes,
-                "wss://nostr.dlcdevkit.com",
-                network,
+                config.transport.listening_port,
                 logger.clone(),
-            )
-            .await?,
-        );
+            )?),
+            TransportKind::Nostr => NodeTransport::Nostr(
+                NostrDlc::new(
+                    &seed_bytes,
+                    &config.transport.nostr_relay,
+                    network,
+                    logger.clone(),
+                )
+                .await?,
+            ),
+            TransportKind::Both => NodeTransport::Both {
+                lightning: lightning_transport(
+                    &seed_bytes,
+                    config.transport.listening_port,
+                    logger.clone(),
+                )?,
+                nostr: NostrDlc::new(
+                    &seed_bytes,
+                    &config.transport.nostr_relay,
+                    network,
+                    logger.clone(),
+                )
+                .await?,
+            },
+        };
+        let transport = Arc::new(transport);
 
-        let storage = Arc::new(
-            PostgresStore::new(&opts.postgres_url, true, logger.clone(), opts.name).await?,
-        );
+        let storage = match config.storage.backend {
+            StorageBackend::Sled => {
+                let path = config
+                    .storage
+                    .sled_path
+                    .clone()
+                    .unwrap_or_else(|| storage_path.join("sled"));
+                NodeStorage::Sled(SledStorage::new(&path.to_string_lossy(), logger.clone())?)
+            }
+            StorageBackend::Postgres => NodeStorage::Postgres(
+                PostgresStore::new(
+                    config.storage.postgres_url.as_deref().unwrap_or_default(),
+                    true,
+                    logger.clone(),
+                    config.name.clone(),
+                )
+                .await?,
+            ),
+            StorageBackend::Memory => {
+                tracing::warn!("Using memory storage. Contracts are lost on shutdown.");
+                NodeStorage::Memory(Box::new(MemoryStorage::new()))
+            }
+        };
+        let storage = Arc::new(storage);
 
-        // let oracle = Arc::new(P2PDOracleClient::new(&oracle_host).await?);
-        let oracle =
-            Arc::new(KormirOracleClient::new(&opts.oracle_host, None, logger.clone()).await?);
+        let mut kormir = None;
+        let mut oracles: Vec<Arc<dyn Oracle>> = Vec::with_capacity(config.oracles.len());
+        for oracle in &config.oracles {
+            match oracle {
+                OracleConfig::Kormir { url } => {
+                    let client =
+                        Arc::new(KormirOracleClient::new(url, None, logger.clone()).await?);
+                    kormir.get_or_insert_with(|| client.clone());
+                    oracles.push(client);
+                }
+                OracleConfig::P2pd { url } => {
+                    oracles.push(Arc::new(P2PDOracleClient::new(url).await?));
+                }
+            }
+        }
+        let oracle = Arc::new(OracleSet::new(oracles)?);
 
         let mut builder = Builder::new();
         builder.set_seed_bytes(SeedConfig::Bytes(seed_bytes))?;
-        builder.set_esplora_host(opts.esplora_host);
+        builder.set_esplora_host(config.chain.esplora_host.clone());
         builder.set_network(network);
         builder.set_transport(transport.clone());
         builder.set_storage(storage.clone());
         builder.set_oracle(oracle.clone());
         builder.set_logger(logger.clone());
 
-        if let Some(endpoint) = opts.zmq_blockhash_endpoint.filter(|e| !e.is_empty()) {
-            builder.set_zmq_blockhash_endpoint(endpoint);
+        if config.chain.source == ChainSource::Zmq {
+            if let Some(endpoint) = &config.chain.zmq_blockhash_endpoint {
+                builder.set_zmq_blockhash_endpoint(endpoint);
+            }
         }
 
         let ddk: Ddk = builder.finish().await?;
 
         ddk.start()?;
-        let node = DdkNode::new(ddk);
+        let node = DdkNode::new(ddk, kormir);
         let node_stop = node.node.clone();
-        let server = Server::builder()
+
+        let mut server = Server::builder();
+        if config.grpc.no_tls {
+            tracing::warn!("Serving the gRPC server without TLS.");
+        } else {
+            server = server.tls_config(crate::tls::server_tls_config(
+                &storage_path,
+                config.grpc.tls_cert,
+                config.grpc.tls_key,
+            )?)?;
+        }
+        let credentials = if config.grpc.no_auth {
+            tracing::warn!("Serving the gRPC server without authentication.");
+            None
+        } else {
+            Some(Arc::new(Credentials::from_path(&storage_path)?))
+        };
+
+        let server = server
+            .layer(FilterLayer::new(AuthInterceptor::new(credentials)))
             .add_service(DdkRpcServer::new(node))
-            .serve_with_shutdown(opts.grpc_host.parse()?, async {
+            .serve_with_shutdown(config.grpc.host.parse()?, async {
                 tokio::signal::ctrl_c()
                     .await
                     .expect("Failed to install Ctrl+C signal handler");
@@ -119,10 +227,242 @@ impl DdkNode {
 
         Ok(())
     }
+
+    /// The kormir oracle to create and sign oracle events with.
+    fn kormir(&self) -> Result<&KormirOracleClient, Status> {
+        self.kormir
+            .as_deref()
+            .ok_or_else(|| Status::failed_precondition("No kormir oracle is configured."))
+    }
+}
+
+/// Creates the lightning transport. Its node key is derived from the seed rather than using the
+/// seed itself.
+fn lightning_transport(
+    seed_bytes: &[u8; 64],
+    listening_port: u16,
+    logger: Arc<Logger>,
+) -> anyhow::Result<LightningTransport> {
+    let lightning_seed = sha256::Hash::hash(seed_bytes).to_byte_array();
+    Ok(LightningTransport::new(
+        &lightning_seed,
+        listening_port,
+        logger,
+    )?)
+}
+
+/// Parses a hex encoded contract or channel id from a request.
+fn parse_id(id: &str) -> Result<[u8; 32], Status> {
+    let bytes = hex::decode(id).map_err(|_| Status::invalid_argument("Id is not valid hex."))?;
+    <[u8; 32]>::try_from(bytes.as_slice())
+        .map_err(|_| Status::invalid_argument("Id must be 32 bytes."))
+}
+
+fn parse_contract_input(contract_input: &[u8]) -> Result<ContractInput, Status> {
+    serde_json::from_slice(contract_input)
+        .map_err(|e| Status::invalid_argument(format!("Invalid contract input. error={e}")))
+}
+
+fn operation_error(action: &str, e: ddk::error::Error) -> Status {
+    Status::new(Code::Cancelled, format!("{action} failed. error={e}"))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, Status> {
+    serde_json::to_vec(value).map_err(|e| Status::internal(e.to_string()))
+}
+
+/// The state specific details of a contract.
+fn contract_details(contract: &Contract) -> Result<GetContractResponse, Status> {
+    let (state, offered, accepted) = match contract {
+        Contract::Offered(o) => ("offered", o, None),
+        Contract::Rejected(o) => ("rejected", o, None),
+        Contract::Expired(o) => ("expired", o, None),
+        Contract::FailedAccept(f) => ("failed_accept", &f.offered_contract, None),
+        Contract::Accepted(a) => ("accepted", &a.offered_contract, Some(a)),
+        Contract::FailedSign(f) => (
+            "failed_sign",
+            &f.accepted_contract.offered_contract,
+            Some(&f.accepted_contract),
+        ),
+        Contract::Signed(s) | Contract::Confirmed(s) | Contract::Refunded(s) => {
+            let state = match contract {
+                Contract::Signed(_) => "signed",
+                Contract::Confirmed(_) => "confirmed",
+                _ => "refunded",
+            };
+            (
+                state,
+                &s.accepted_contract.offered_contract,
+                Some(&s.accepted_contract),
+            )
+        }
+        Contract::PreClosed(p) => (
+            "preclosed",
+            &p.signed_contract.accepted_contract.offered_contract,
+            Some(&p.signed_contract.accepted_contract),
+        ),
+        Contract::Closed(c) => (
+            "closed",
+            &c.signed_contract.accepted_contract.offered_contract,
+            Some(&c.signed_contract.accepted_contract),
+        ),
+    };
+    let (cet_txid, pnl, attestations) = match contract {
+        Contract::PreClosed(p) => (
+            Some(p.signed_cet.compute_txid().to_string()),
+            None,
+            p.attestations.clone().unwrap_or_default(),
+        ),
+        Contract::Closed(c) => (
+            c.signed_cet
+                .as_ref()
+                .map(|tx| tx.compute_txid().to_string()),
+            Some(c.pnl.to_sat()),
+            c.attestations.clone().unwrap_or_default(),
+        ),
+        _ => (None, None, Vec::new()),
+    };
+    let offer_collateral = offered.offer_params.collateral;
+
+    Ok(GetContractResponse {
+        contract_id: hex::encode(contract.get_id()),
+        state: state.to_string(),
+        counter_party: offered.counter_party.to_string(),
+        is_offer_party: offered.is_offer_party,
+        offer_collateral: offer_collateral.to_sat(),
+        accept_collateral: (offered.total_collateral - offer_collateral).to_sat(),
+        fee_rate_per_vb: offered.fee_rate_per_vb,
+        cet_locktime: offered.cet_locktime,
+        refund_locktime: offered.refund_locktime,
+        funding_txid: accepted.map(|a| a.dlc_transactions.fund.compute_txid().to_string()),
+        refund_txid: accepted.map(|a| a.dlc_transactions.refund.compute_txid().to_string()),
+        cet_txid,
+        pnl,
+        oracle_announcements: offered
+            .contract_info
+            .iter()
+            .flat_map(|info| &info.oracle_announcements)
+            .map(to_json)
+            .collect::<Result<_, _>>()?,
+        attestations: attestations.iter().map(to_json).collect::<Result<_, _>>()?,
+        contract: serialize_contract(contract).map_err(|e| Status::internal(e.to_string()))?,
+    })
+}
+
+/// The RPC representation of an event of the node.
+fn event_response(event: Event) -> SubscribeEventsResponse {
+    let mut response = SubscribeEventsResponse::default();
+    match event {
+        Event::OfferReceived {
+            contract_id,
+            counter_party,
+        } => {
+            response.kind = "offer_received".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.counter_party = Some(counter_party.to_string());
+        }
+        Event::Accepted {
+            contract_id,
+            temporary_contract_id,
+            counter_party,
+        } => {
+            response.kind = "accepted".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.temporary_contract_id = Some(hex::encode(temporary_contract_id));
+            response.counter_party = Some(counter_party.to_string());
+        }
+        Event::Signed {
+            contract_id,
+            temporary_contract_id,
+            counter_party,
+            funding_txid,
+        } => {
+            response.kind = "signed".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.temporary_contract_id = Some(hex::encode(temporary_contract_id));
+            response.counter_party = Some(counter_party.to_string());
+            response.txid = Some(funding_txid.to_string());
+        }
+        Event::FundingConfirmed {
+            contract_id,
+            funding_txid,
+        } => {
+            response.kind = "funding_confirmed".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.txid = Some(funding_txid.to_string());
+        }
+        Event::CetBroadcast {
+            contract_id,
+            closing_txid,
+        } => {
+            response.kind = "cet_broadcast".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.txid = Some(closing_txid.to_string());
+        }
+        Event::Closed {
+            contract_id,
+            closing_txid,
+            pnl,
+        } => {
+            response.kind = "closed".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.txid = closing_txid.map(|txid| txid.to_string());
+            response.pnl = Some(pnl.to_sat());
+        }
+        Event::Refunded {
+            contract_id,
+            refund_txid,
+        } => {
+            response.kind = "refunded".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.txid = Some(refund_txid.to_string());
+        }
+        Event::OfferExpired {
+            contract_id,
+            counter_party,
+        } => {
+            response.kind = "offer_expired".to_string();
+            response.contract_id = Some(hex::encode(contract_id));
+            response.counter_party = Some(counter_party.to_string());
+        }
+        Event::ChannelStateChanged {
+            channel_id,
+            counter_party,
+            state,
+            signed_state,
+        } => {
+            response.kind = "channel_state_changed".to_string();
+            response.channel_id = Some(hex::encode(channel_id));
+            response.counter_party = Some(counter_party.to_string());
+            response.state = Some(state.to_string());
+            response.signed_state = signed_state.map(|state| format!("{state:?}"));
+        }
+        Event::WalletTxReceived {
+            txid,
+            confirmation_height,
+        } => {
+            response.kind = "wallet_tx_received".to_string();
+            response.txid = Some(txid.to_string());
+            response.confirmation_height = confirmation_height;
+        }
+        Event::OracleEquivocation {
+            contract_ids,
+            fraud_proof,
+        } => {
+            response.kind = "oracle_equivocation".to_string();
+            response.contract_ids = contract_ids.iter().map(hex::encode).collect();
+            response.oracle_pubkey = Some(fraud_proof.oracle_public_key().to_string());
+            response.fraud_proof = serde_json::to_string(&fraud_proof).ok();
+        }
+    }
+    response
 }
 
 #[async_trait]
 impl DdkRpc for DdkNode {
+    type SubscribeEventsStream =
+        Pin<Box<dyn Stream<Item = Result<SubscribeEventsResponse, Status>> + Send>>;
+
     #[tracing::instrument(skip(self, _request), name = "grpc_server")]
     async fn info(&self, _request: Request<InfoRequest>) -> Result<Response<InfoResponse>, Status> {
         tracing::info!("Request for node info.");
@@ -229,6 +569,8 @@ impl DdkRpc for DdkNode {
     ) -> Result<Response<ListOffersResponse>, Status> {
         tracing::info!("Request for offers to the node.");
         let offers = self.node.storage.get_contract_offers().await.unwrap();
+        // For display only: the JSON leaves out the secret nonces of the
+        // Taproot funded contracts we offered.
         let offers: Vec<Vec<u8>> = offers
             .iter()
             .map(|offer| serde_json::to_vec(offer).unwrap())
@@ -315,6 +657,29 @@ impl DdkRpc for DdkNode {
         Ok(Response::new(ListOraclesResponse { name, pubkey }))
     }
 
+    async fn oracle_health(
+        &self,
+        _request: Request<OracleHealthRequest>,
+    ) -> Result<Response<OracleHealthResponse>, Status> {
+        let oracles = self
+            .node
+            .oracle_health()
+            .await
+            .into_iter()
+            .map(|(pubkey, health)| OracleHealth {
+                pubkey: pubkey.to_string(),
+                requests: health.requests,
+                failures: health.failures,
+                consecutive_failures: health.consecutive_failures,
+                last_latency_ms: health.last_latency.map(|l| l.as_millis() as u64),
+                average_latency_ms: health.average_latency.map(|l| l.as_millis() as u64),
+                last_success: health.last_success,
+                last_error: health.last_error,
+            })
+            .collect();
+        Ok(Response::new(OracleHealthResponse { oracles }))
+    }
+
     async fn list_contracts(
         &self,
         _request: Request<ListContractsRequest>,
@@ -376,8 +741,7 @@ impl DdkRpc for DdkNode {
     ) -> Result<Response<CreateEnumResponse>, Status> {
         let CreateEnumRequest { maturity, outcomes } = request.into_inner();
         let announcement = self
-            .node
-            .oracle
+            .kormir()?
             .create_enum_event(outcomes, maturity)
             .await
             .unwrap();
@@ -420,8 +784,7 @@ impl DdkRpc for DdkNode {
             nb_digits,
         } = request.into_inner();
         let announcement = self
-            .node
-            .oracle
+            .kormir()?
             .create_numeric_event(
                 Some(nb_digits as u16), // Number of digits
                 None,                   // Default is_signed to false
@@ -447,10 +810,10 @@ impl DdkRpc for DdkNode {
         let SignRequest { event_id, outcome } = request.into_inner();
         let attestation = match outcome {
             Some(ddkrpc::sign_request::Outcome::EnumOutcome(outcome)) => {
-                self.node.oracle.sign_enum_event(event_id, outcome).await
+                self.kormir()?.sign_enum_event(event_id, outcome).await
             }
             Some(ddkrpc::sign_request::Outcome::NumericOutcome(outcome)) => {
-                self.node.oracle.sign_numeric_event(event_id, outcome).await
+                self.kormir()?.sign_numeric_event(event_id, outcome).await
             }
             None => return Err(Status::invalid_argument("Outcome must be specified")),
         }
@@ -461,4 +824,560 @@ impl DdkRpc for DdkNode {
 
         Ok(Response::new(SignResponse { signature }))
     }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn offer_channel(
+        &self,
+        request: Request<OfferChannelRequest>,
+    ) -> Result<Response<OfferChannelResponse>, Status> {
+        tracing::info!("Request to offer channel.");
+        let OfferChannelRequest {
+            contract_input,
+            counter_party,
+        } = request.into_inner();
+        let contract_input = parse_contract_input(&contract_input)?;
+        let counter_party = PublicKey::from_str(&counter_party)
+            .map_err(|_| Status::invalid_argument("Invalid counterparty public key."))?;
+        let offer = self
+            .node
+            .offer_channel(&contract_input, counter_party)
+            .await
+            .map_err(|e| operation_error("Channel offer", e))?;
+        let offer_channel =
+            serde_json::to_vec(&offer).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(OfferChannelResponse { offer_channel }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn accept_channel(
+        &self,
+        request: Request<AcceptChannelRequest>,
+    ) -> Result<Response<AcceptChannelResponse>, Status> {
+        tracing::info!("Request to accept channel.");
+        let channel_id = parse_id(&request.into_inner().channel_id)?;
+        let (accept, channel_id, contract_id, counter_party) = self
+            .node
+            .accept_channel(&channel_id)
+            .await
+            .map_err(|e| operation_error("Channel accept", e))?;
+        let accept_channel =
+            serde_json::to_vec(&accept).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(AcceptChannelResponse {
+            channel_id: hex::encode(channel_id),
+            contract_id: hex::encode(contract_id),
+            counter_party: counter_party.to_string(),
+            accept_channel,
+        }))
+    }
+
+    #[tracing::instrument(skip(self, _request), name = "grpc_server")]
+    async fn list_channels(
+        &self,
+        _request: Request<ListChannelsRequest>,
+    ) -> Result<Response<ListChannelsResponse>, Status> {
+        tracing::info!("Request to list channels.");
+        let storage_error = |e: ddk_manager::error::Error| Status::internal(e.to_string());
+        let channels = self
+            .node
+            .storage
+            .get_channels()
+            .await
+            .map_err(storage_error)?;
+        let channels = channels
+            .iter()
+            .map(serialize_channel)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(storage_error)?;
+        Ok(Response::new(ListChannelsResponse { channels }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn settle_offer(
+        &self,
+        request: Request<SettleOfferRequest>,
+    ) -> Result<Response<SettleOfferResponse>, Status> {
+        tracing::info!("Request to offer channel settlement.");
+        let SettleOfferRequest {
+            channel_id,
+            counter_payout,
+        } = request.into_inner();
+        let channel_id = parse_id(&channel_id)?;
+        let offer = self
+            .node
+            .settle_offer(&channel_id, Amount::from_sat(counter_payout))
+            .await
+            .map_err(|e| operation_error("Settle offer", e))?;
+        let settle_offer =
+            serde_json::to_vec(&offer).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(SettleOfferResponse { settle_offer }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn accept_settle_offer(
+        &self,
+        request: Request<AcceptSettleOfferRequest>,
+    ) -> Result<Response<AcceptSettleOfferResponse>, Status> {
+        tracing::info!("Request to accept channel settlement.");
+        let channel_id = parse_id(&request.into_inner().channel_id)?;
+        let accept = self
+            .node
+            .accept_settle_offer(&channel_id)
+            .await
+            .map_err(|e| operation_error("Settle accept", e))?;
+        let settle_accept =
+            serde_json::to_vec(&accept).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(AcceptSettleOfferResponse { settle_accept }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn reject_settle_offer(
+        &self,
+        request: Request<RejectSettleOfferRequest>,
+    ) -> Result<Response<RejectSettleOfferResponse>, Status> {
+        tracing::info!("Request to reject channel settlement.");
+        let channel_id = parse_id(&request.into_inner().channel_id)?;
+        self.node
+            .reject_settle_offer(&channel_id)
+            .await
+            .map_err(|e| operation_error("Settle reject", e))?;
+        Ok(Response::new(RejectSettleOfferResponse {}))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn renew_offer(
+        &self,
+        request: Request<RenewOfferRequest>,
+    ) -> Result<Response<RenewOfferResponse>, Status> {
+        tracing::info!("Request to offer channel renewal.");
+        let RenewOfferRequest {
+            channel_id,
+            counter_payout,
+            contract_input,
+        } = request.into_inner();
+        let channel_id = parse_id(&channel_id)?;
+        let contract_input = parse_contract_input(&contract_input)?;
+        let offer = self
+            .node
+            .renew_offer(
+                &channel_id,
+                Amount::from_sat(counter_payout),
+                &contract_input,
+            )
+            .await
+            .map_err(|e| operation_error("Renew offer", e))?;
+        let renew_offer =
+            serde_json::to_vec(&offer).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(RenewOfferResponse { renew_offer }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn accept_renew_offer(
+        &self,
+        request: Request<AcceptRenewOfferRequest>,
+    ) -> Result<Response<AcceptRenewOfferResponse>, Status> {
+        tracing::info!("Request to accept channel renewal.");
+        let channel_id = parse_id(&request.into_inner().channel_id)?;
+        let accept = self
+            .node
+            .accept_renew_offer(&channel_id)
+            .await
+            .map_err(|e| operation_error("Renew accept", e))?;
+        let renew_accept =
+            serde_json::to_vec(&accept).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(AcceptRenewOfferResponse { renew_accept }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn reject_renew_offer(
+        &self,
+        request: Request<RejectRenewOfferRequest>,
+    ) -> Result<Response<RejectRenewOfferResponse>, Status> {
+        tracing::info!("Request to reject channel renewal.");
+        let channel_id = parse_id(&request.into_inner().channel_id)?;
+        self.node
+            .reject_renew_offer(&channel_id)
+            .await
+            .map_err(|e| operation_error("Renew reject", e))?;
+        Ok(Response::new(RejectRenewOfferResponse {}))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn collaborative_close(
+        &self,
+        request: Request<CollaborativeCloseRequest>,
+    ) -> Result<Response<CollaborativeCloseResponse>, Status> {
+        tracing::info!("Request to collaboratively close channel.");
+        let CollaborativeCloseRequest {
+            channel_id,
+            counter_payout,
+        } = request.into_inner();
+        let channel_id = parse_id(&channel_id)?;
+        let offer = self
+            .node
+            .offer_collaborative_close(&channel_id, Amount::from_sat(counter_payout))
+            .await
+            .map_err(|e| operation_error("Collaborative close", e))?;
+        let close_offer =
+            serde_json::to_vec(&offer).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(CollaborativeCloseResponse { close_offer }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn accept_collaborative_close(
+        &self,
+        request: Request<AcceptCollaborativeCloseRequest>,
+    ) -> Result<Response<AcceptCollaborativeCloseResponse>, Status> {
+        tracing::info!("Request to accept collaborative channel close.");
+        let channel_id = parse_id(&request.into_inner().channel_id)?;
+        self.node
+            .accept_collaborative_close(&channel_id)
+            .await
+            .map_err(|e| operation_error("Collaborative close accept", e))?;
+        Ok(Response::new(AcceptCollaborativeCloseResponse {}))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn force_close(
+        &self,
+        request: Request<ForceCloseRequest>,
+    ) -> Result<Response<ForceCloseResponse>, Status> {
+        tracing::info!("Request to force close channel.");
+        let channel_id = parse_id(&request.into_inner().channel_id)?;
+        self.node
+            .force_close_channel(&channel_id)
+            .await
+            .map_err(|e| operation_error("Force close", e))?;
+        Ok(Response::new(ForceCloseResponse {}))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn get_contract(
+        &self,
+        request: Request<GetContractRequest>,
+    ) -> Result<Response<GetContractResponse>, Status> {
+        tracing::info!("Request for contract.");
+        let contract_id = parse_id(&request.into_inner().contract_id)?;
+        let contract = self
+            .node
+            .storage
+            .get_contract(&contract_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found("Contract not found."))?;
+        Ok(Response::new(contract_details(&contract)?))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn cooperative_close(
+        &self,
+        request: Request<CooperativeCloseRequest>,
+    ) -> Result<Response<CooperativeCloseResponse>, Status> {
+        tracing::info!("Request to cooperatively close contract.");
+        let CooperativeCloseRequest {
+            contract_id,
+            counter_payout,
+        } = request.into_inner();
+        let contract_id = parse_id(&contract_id)?;
+        let close = self
+            .node
+            .cooperative_close_contract(&contract_id, Amount::from_sat(counter_payout))
+            .await
+            .map_err(|e| operation_error("Cooperative close", e))?;
+        Ok(Response::new(CooperativeCloseResponse {
+            close_dlc: to_json(&close)?,
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn accept_cooperative_close(
+        &self,
+        request: Request<AcceptCooperativeCloseRequest>,
+    ) -> Result<Response<AcceptCooperativeCloseResponse>, Status> {
+        tracing::info!("Request to accept cooperative contract close.");
+        let contract_id = parse_id(&request.into_inner().contract_id)?;
+        self.node
+            .accept_cooperative_close(&contract_id)
+            .await
+            .map_err(|e| operation_error("Cooperative close accept", e))?;
+        Ok(Response::new(AcceptCooperativeCloseResponse {}))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn refund_contract(
+        &self,
+        request: Request<RefundContractRequest>,
+    ) -> Result<Response<RefundContractResponse>, Status> {
+        tracing::info!("Request to refund contract.");
+        let contract_id = parse_id(&request.into_inner().contract_id)?;
+        let contract = self
+            .node
+            .refund_dlc(&contract_id)
+            .await
+            .map_err(|e| operation_error("Refund", e))?;
+        let contract =
+            serialize_contract(&contract).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(RefundContractResponse { contract }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn bump_closing_fee(
+        &self,
+        request: Request<BumpClosingFeeRequest>,
+    ) -> Result<Response<BumpClosingFeeResponse>, Status> {
+        tracing::info!("Request to bump the fee of a closing transaction.");
+        let BumpClosingFeeRequest {
+            contract_id,
+            fee_rate,
+        } = request.into_inner();
+        let contract_id = parse_id(&contract_id)?;
+        let fee_rate = match FeeRate::from_sat_per_vb(fee_rate) {
+            Some(f) => f,
+            None => return Err(Status::new(Code::InvalidArgument, "Invalid fee rate.")),
+        };
+        let child = self
+            .node
+            .bump_closing_fee(&contract_id, fee_rate)
+            .await
+            .map_err(|e| operation_error("Fee bump", e))?;
+        Ok(Response::new(BumpClosingFeeResponse {
+            txid: child.compute_txid().to_string(),
+        }))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn bump_funding_fee(
+        &self,
+        request: Request<BumpFundingFeeRequest>,
+    ) -> Result<Response<BumpFundingFeeResponse>, Status> {
+        tracing::info!("Request to bump the fee of a fund transaction.");
+        let BumpFundingFeeRequest {
+            contract_id,
+            fee_rate,
+        } = request.into_inner();
+        let contract_id = parse_id(&contract_id)?;
+        let fee_rate = match FeeRate::from_sat_per_vb(fee_rate) {
+            Some(f) => f,
+            None => return Err(Status::new(Code::InvalidArgument, "Invalid fee rate.")),
+        };
+        self.node
+            .bump_funding_fee(&contract_id, fee_rate)
+            .await
+            .map_err(|e| operation_error("Fee bump", e))?;
+        Ok(Response::new(BumpFundingFeeResponse {}))
+    }
+
+    #[tracing::instrument(skip(self, request), name = "grpc_server")]
+    async fn close_contract(
+        &self,
+        request: Request<CloseContractRequest>,
+    ) -> Result<Response<CloseContractResponse>, Status> {
+        tracing::info!("Request to close contract with attestations.");
+        let CloseContractRequest {
+            contract_id,
+            attestations,
+        } = request.into_inner();
+        let contract_id = parse_id(&contract_id)?;
+        let attestations = attestations
+            .iter()
+            .map(|attestation| serde_json::from_slice(attestation))
+            .collect::<Result<Vec<OracleAttestation>, _>>()
+            .map_err(|e| Status::invalid_argument(format!("Invalid attestation. error={e}")))?;
+        let contract = self
+            .node
+            .close_with_attestations(&contract_id, attestations)
+            .await
+            .map_err(|e| operation_error("Contract close", e))?;
+        let contract =
+            serialize_contract(&contract).map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(CloseContractResponse { contract }))
+    }
+
+    #[tracing::instrument(skip(self, _request), name = "grpc_server")]
+    async fn subscribe_events(
+        &self,
+        _request: Request<SubscribeEventsRequest>,
+    ) -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        tracing::info!("Request to subscribe to events.");
+        let events = stream::unfold(self.node.subscribe(), |mut events| async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => return Some((Ok(event_response(event)), events)),
+                    Err(RecvError::Lagged(missed)) => {
+                        tracing::warn!(missed, "Event subscriber fell behind.");
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(events)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddk::oracle::memory::MemoryOracle;
+    use ddk::util::ser::deserialize_channel;
+    use ddk_manager::channel::offered_channel::OfferedChannel;
+    use ddk_manager::channel::Channel;
+    use ddk_messages::channel::OfferChannel;
+
+    /// Serves the chain tip height at every path, which is all the node asks the esplora server
+    /// for while it starts up.
+    async fn esplora_stub() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 3\r\n\r\n100")
+                    .await;
+            }
+        });
+        format!("http://{address}")
+    }
+
+    async fn node() -> DdkNode {
+        let logger = Arc::new(Logger::disabled("ddk-node-test".to_string()));
+        let seed_bytes = [3u8; 64];
+        let transport =
+            NodeTransport::Lightning(lightning_transport(&seed_bytes, 0, logger.clone()).unwrap());
+        let oracle = OracleSet::new(vec![Arc::new(MemoryOracle::default())]).unwrap();
+
+        let mut builder = Builder::new();
+        builder
+            .set_seed_bytes(SeedConfig::Bytes(seed_bytes))
+            .unwrap();
+        builder.set_esplora_host(esplora_stub().await);
+        builder.set_network(bitcoin::Network::Regtest);
+        builder.set_transport(Arc::new(transport));
+        builder.set_storage(Arc::new(NodeStorage::Memory(
+            Box::new(MemoryStorage::new()),
+        )));
+        builder.set_oracle(Arc::new(oracle));
+        builder.set_logger(logger);
+        DdkNode::new(builder.finish().await.unwrap(), None)
+    }
+
+    /// A channel offered to the node, with the contract it was offered with.
+    fn offered_channel() -> (OfferedChannel, Contract) {
+        let offer: OfferChannel = serde_json::from_str(include_str!(
+            "../../ddk-manager/test_inputs/offer_channel.json"
+        ))
+        .unwrap();
+        let (channel, contract) =
+            OfferedChannel::from_offer_channel(&offer, offer.funding_pubkey, [0u8; 32]).unwrap();
+        (channel, Contract::Offered(contract))
+    }
+
+    async fn list_channels(node: &DdkNode) -> Vec<Channel> {
+        node.list_channels(Request::new(ListChannelsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .channels
+            .iter()
+            .map(|c| deserialize_channel(c).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn list_channels_returns_every_channel_state() {
+        let node = node().await;
+        let (offered, contract) = offered_channel();
+        let mut cancelled = offered.clone();
+        cancelled.temporary_channel_id = [9u8; 32];
+        node.node
+            .storage
+            .upsert_channel(Channel::Offered(offered), Some(contract))
+            .await
+            .unwrap();
+        node.node
+            .storage
+            .upsert_channel(Channel::Cancelled(cancelled), None)
+            .await
+            .unwrap();
+
+        let mut states = list_channels(&node)
+            .await
+            .iter()
+            .map(|c| c.state_name())
+            .collect::<Vec<_>>();
+        states.sort();
+        assert_eq!(states, vec!["cancelled", "offered"]);
+    }
+
+    #[tokio::test]
+    async fn channel_rpcs_reject_bad_and_unknown_ids() {
+        let node = node().await;
+
+        let status = node
+            .accept_channel(Request::new(AcceptChannelRequest {
+                channel_id: "not hex".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let status = node
+            .force_close(Request::new(ForceCloseRequest {
+                channel_id: hex::encode([1u8; 16]),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        let unknown = hex::encode([1u8; 32]);
+        let status = node
+            .accept_channel(Request::new(AcceptChannelRequest {
+                channel_id: unknown.clone(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Cancelled);
+        let status = node
+            .settle_offer(Request::new(SettleOfferRequest {
+                channel_id: unknown.clone(),
+                counter_payout: 1_000,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Cancelled);
+        let status = node
+            .force_close(Request::new(ForceCloseRequest {
+                channel_id: unknown,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn accepting_a_channel_without_funds_fails_and_keeps_the_offer() {
+        let node = node().await;
+        let (offered, contract) = offered_channel();
+        let channel_id = offered.temporary_channel_id;
+        node.node
+            .storage
+            .upsert_channel(Channel::Offered(offered), Some(contract))
+            .await
+            .unwrap();
+
+        let status = node
+            .accept_channel(Request::new(AcceptChannelRequest {
+                channel_id: hex::encode(channel_id),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Cancelled);
+
+        let channels = list_channels(&node).await;
+        assert_eq!(channels.len(), 1);
+        assert!(
+            matches!(&channels[0], Channel::Offered(c) if c.temporary_channel_id == channel_id)
+        );
+    }
 }
@@ -1,6 +1,10 @@
+use crate::config::{ChainSource, StorageBackend, TransportKind, LOG_LEVELS};
 use clap::Parser;
 use std::path::PathBuf;
 
+/// Command line flags of `ddk-node`. Every flag can also be set with its environment variable,
+/// and overrides the config file. Unset flags fall back to the config file, then the defaults of
+/// [`crate::config::NodeConfig`].
 #[derive(Parser, Clone, Debug)]
 #[clap(name = "ddk-node")]
 #[clap(
@@ -9,51 +13,84 @@ use std::path::PathBuf;
 )]
 #[clap(version = option_env ! ("CARGO_PKG_VERSION").unwrap_or("unknown"))]
 pub struct NodeOpts {
-    #[arg(long)]
-    #[arg(help = "Set the log level.")]
-    #[arg(default_value = "info")]
-    #[arg(value_parser = ["info", "debug"])]
-    pub log: String,
-    #[arg(short, long)]
-    #[arg(help = "Set the Bitcoin network for DDK")]
-    #[arg(default_value = "signet")]
+    #[arg(short, long, env = "DDK_CONFIG")]
+    #[arg(
+        help = "Path to the TOML config file. Defaults to ddk-node.toml in the storage directory if it exists."
+    )]
+    pub config: Option<PathBuf>,
+    #[arg(long, env = "DDK_LOG")]
+    #[arg(help = "Set the log level. [default: info]")]
+    #[arg(value_parser = LOG_LEVELS)]
+    pub log: Option<String>,
+    #[arg(short, long, env = "DDK_NETWORK")]
+    #[arg(help = "Set the Bitcoin network for DDK [default: signet]")]
     #[arg(value_parser = ["regtest", "mainnet", "signet"])]
-    pub network: String,
-    #[arg(short, long)]
+    pub network: Option<String>,
+    #[arg(short, long, env = "DDK_STORAGE_DIR")]
     #[arg(
         help = "The path where ddk-node stores data. ddk-node will try to store in the $HOME directory by default."
     )]
     pub storage_dir: Option<PathBuf>,
     #[arg(short = 'p')]
-    #[arg(long = "port")]
-    #[arg(default_value = "1776")]
-    #[arg(help = "Listening port for the lightning network transport.")]
-    pub listening_port: u16,
-    #[arg(long = "grpc")]
-    #[arg(default_value = "0.0.0.0:3030")]
-    #[arg(help = "Host and port the gRPC server will run on.")]
-    pub grpc_host: String,
-    #[arg(long = "esplora")]
-    #[arg(default_value = "https://mutinynet.com/api")]
-    #[arg(help = "Esplora server to connect to.")]
-    pub esplora_host: String,
-    #[arg(long = "oracle")]
-    #[arg(default_value = "https://kormir.dlcdevkit.com")]
-    #[arg(help = "Kormir oracle to connect to.")]
-    pub oracle_host: String,
+    #[arg(long = "port", env = "DDK_PORT")]
+    #[arg(help = "Listening port for the lightning network transport. [default: 1776]")]
+    pub listening_port: Option<u16>,
+    #[arg(long = "grpc", env = "DDK_GRPC")]
+    #[arg(help = "Host and port the gRPC server will run on. [default: 0.0.0.0:3030]")]
+    pub grpc_host: Option<String>,
+    #[arg(long, env = "DDK_STORAGE")]
+    #[arg(help = "Storage backend. [default: postgres]")]
+    pub storage: Option<StorageBackend>,
+    #[arg(long, env = "DDK_TRANSPORT")]
+    #[arg(help = "Transport to send DLC messages over. [default: nostr]")]
+    pub transport: Option<TransportKind>,
+    #[arg(long, env = "DDK_NOSTR_RELAY")]
+    #[arg(help = "Nostr relay for the nostr transport. [default: wss://nostr.dlcdevkit.com]")]
+    pub nostr_relay: Option<String>,
+    #[arg(long, env = "DDK_CHAIN")]
+    #[arg(help = "Chain source. zmq requires --zmq-blockhash-endpoint. [default: esplora]")]
+    pub chain: Option<ChainSource>,
+    #[arg(long = "esplora", env = "DDK_ESPLORA")]
+    #[arg(help = "Esplora server to connect to. [default: https://mutinynet.com/api]")]
+    pub esplora_host: Option<String>,
+    #[arg(long = "oracle", env = "DDK_ORACLE", value_delimiter = ',')]
+    #[arg(
+        help = "Kormir oracle to connect to. Can be repeated. [default: https://kormir.dlcdevkit.com]"
+    )]
+    pub oracle_hosts: Vec<String>,
+    #[arg(long = "p2pd-oracle", env = "DDK_P2PD_ORACLE", value_delimiter = ',')]
+    #[arg(help = "P2PDerivatives oracle to connect to. Can be repeated.")]
+    pub p2pd_oracle_hosts: Vec<String>,
     #[arg(long)]
     #[arg(help = "Seed config strategy.")]
     #[arg(default_value = "file")]
     #[arg(value_parser = ["file", "bytes"])]
     pub seed: String,
-    #[arg(long)]
-    #[arg(help = "Name for the wallet.")]
-    #[arg(default_value = "ddk-node")]
-    pub name: String,
-    #[arg(long)]
+    #[arg(long, env = "DDK_NAME")]
+    #[arg(help = "Name for the wallet. [default: ddk-node]")]
+    pub name: Option<String>,
+    #[arg(long, env = "DDK_POSTGRES_URL")]
     #[arg(help = "Url for the postgres database connection.")]
-    pub postgres_url: String,
-    #[arg(long)]
+    pub postgres_url: Option<String>,
+    #[arg(long, env = "DDK_ZMQ_BLOCKHASH_ENDPOINT")]
     #[arg(help = "Endpoint for bitcoind ZeroMQ blockhash notifications")]
     pub zmq_blockhash_endpoint: Option<String>,
+    #[arg(long, env = "DDK_TLS_CERT")]
+    #[arg(
+        help = "TLS certificate for the gRPC server. A self-signed certificate is generated in the storage directory if not set."
+    )]
+    pub tls_cert: Option<PathBuf>,
+    #[arg(long, env = "DDK_TLS_KEY")]
+    #[arg(help = "Private key of the TLS certificate for the gRPC server.")]
+    pub tls_key: Option<PathBuf>,
+    #[arg(long, env = "DDK_NO_TLS", num_args = 0..=1, require_equals = true)]
+    #[arg(default_missing_value = "true")]
+    #[arg(help = "Serve the gRPC server without TLS. --no-tls=false re-enables it.")]
+    pub no_tls: Option<bool>,
+    #[arg(long, env = "DDK_NO_AUTH", num_args = 0..=1, require_equals = true)]
+    #[arg(default_missing_value = "true")]
+    #[arg(
+        help = "Serve the gRPC server without bearer token authentication. --no-auth=false re-enables it."
+    )]
+    pub no_auth: Option<bool>,
 }
@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+use tonic::transport::{Identity, ServerTlsConfig};
+
+/// Names the self-signed certificate is valid for.
+const SELF_SIGNED_NAMES: [&str; 3] = ["localhost", "127.0.0.1", "::1"];
+
+/// Builds the TLS config for the gRPC server from the certificate and key supplied by the
+/// operator. If neither is supplied, a self-signed certificate is read from `tls.cert` and
+/// `tls.key` in the storage path, and generated on first start.
+pub fn server_tls_config(
+    storage_path: &Path,
+    cert: Option<PathBuf>,
+    key: Option<PathBuf>,
+) -> anyhow::Result<ServerTlsConfig> {
+    let (cert_path, key_path) = match (cert, key) {
+        (Some(cert), Some(key)) => (cert, key),
+        (None, None) => {
+            let cert_path = storage_path.join("tls.cert");
+            let key_path = storage_path.join("tls.key");
+            if !cert_path.exists() || !key_path.exists() {
+                let names = SELF_SIGNED_NAMES.map(String::from).to_vec();
+                let certified = rcgen::generate_simple_self_signed(names)?;
+                std::fs::write(&cert_path, certified.cert.pem())?;
+                crate::auth::write_secret(&key_path, certified.signing_key.serialize_pem())?;
+                tracing::info!(
+                    cert = %cert_path.display(),
+                    "Generated a self-signed TLS certificate for the gRPC server."
+                );
+            }
+            (cert_path, key_path)
+        }
+        _ => anyhow::bail!("Both a TLS certificate and key are required."),
+    };
+
+    // Other dependencies enable both rustls crypto providers, so the server cannot pick one on
+    // its own. An error means a provider is installed already.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let cert = std::fs::read(&cert_path)?;
+    let key = std::fs::read(&key_path)?;
+    Ok(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn generated_key_is_only_readable_by_the_owner() {
+        use bitcoin::key::rand::{self, Fill};
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut suffix = [0u8; 8];
+        suffix.try_fill(&mut rand::thread_rng()).unwrap();
+        let storage_path =
+            std::env::temp_dir().join(format!("ddk_node_tls_{}", hex::encode(suffix)));
+        std::fs::create_dir_all(&storage_path).unwrap();
+
+        server_tls_config(&storage_path, None, None).unwrap();
+
+        let mode = std::fs::metadata(storage_path.join("tls.key"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_dir_all(&storage_path).unwrap();
+    }
+}
@@ -0,0 +1,506 @@
+//! Static backups of the contracts and channels of a node.
+//!
+//! The funds of a contract or a channel sit in a 2-of-2 output. The seed
+//! derives the keys again, but not which contracts exist, what the counter
+//! party signed or which channel states it revoked: without the store, the
+//! funds can only be recovered with the help of the counter party. A
+//! [`StaticBackup`] holds what is needed to rebuild the store:
+//!
+//! - every open contract, with the data of its offer, accept and sign
+//!   messages, its keys id and its funding transaction,
+//! - every open channel, with its latest state and the revocation secrets of
+//!   the counter party,
+//! - how the channel keys are derived from the seed,
+//! - the transactions watched on chain.
+//!
+//! A backup is encrypted with a key derived from the seed, so it can be kept
+//! anywhere. With a [`BackupSink`] set through
+//! [`crate::builder::Builder::set_backup_sink`], a new backup is written
+//! whenever a contract or a channel changes state. Restore it on a node
+//! started from the same seed with [`crate::DlcDevKit::restore_from_backup`].
+//!
+//! Closing a channel with a state revoked after the backup was written gets
+//! the node punished, which is why backups should not be kept from an older
+//! state of a channel than the sink received.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::key::rand::{thread_rng, Fill};
+use bitcoin::p2p::Magic;
+use bitcoin::secp256k1::PublicKey;
+use bitcoin::Network;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ddk_manager::chain_monitor::ChainMonitor;
+use ddk_manager::channel::Channel;
+use ddk_manager::contract::Contract;
+use lightning::io::{Cursor, Read};
+use lightning::util::ser::{Readable, Writeable};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::error::Error;
+use crate::logger::{log_debug, log_error, Logger, WriteLog};
+use crate::util::ser::{
+    deserialize_channel, deserialize_contract, serialize_channel, serialize_contract,
+};
+use crate::wallet::ChannelKeyDerivation;
+use crate::Storage;
+
+/// Version of the backup format written by this release.
+pub const BACKUP_VERSION: u16 = 1;
+
+const BACKUP_MAGIC: &[u8; 4] = b"DDKB";
+const HEADER_LEN: usize = 6;
+const NONCE_LEN: usize = 12;
+
+/// The contracts and channels of a node that can still hold funds.
+#[derive(Debug)]
+pub struct StaticBackup {
+    /// The network of the node.
+    pub network: Network,
+    /// The contracts that are not closed, refunded or failed.
+    pub contracts: Vec<Contract>,
+    /// The channels that are offered, accepted or signed.
+    pub channels: Vec<Channel>,
+    /// How the channel keys are derived from the seed.
+    pub channel_keys: Vec<(PublicKey, ChannelKeyDerivation)>,
+    /// The transactions watched on chain, if the store keeps them.
+    pub chain_monitor: Option<ChainMonitor>,
+}
+
+impl StaticBackup {
+    /// Reads the open contracts and channels of `storage`.
+    pub async fn from_storage<S: Storage>(storage: &S, network: Network) -> Result<Self, Error> {
+        let contracts = storage
+            .get_contracts()
+            .await?
+            .into_iter()
+            .filter(|contract| {
+                matches!(
+                    contract,
+                    Contract::Offered(_)
+                        | Contract::Accepted(_)
+                        | Contract::Signed(_)
+                        | Contract::Confirmed(_)
+                        | Contract::PreClosed(_)
+                )
+            })
+            .collect();
+
+        // An accepted channel's fund transaction can still confirm once the
+        // offer party signs and broadcasts it.
+        let channels = storage
+            .get_channels()
+            .await?
+            .into_iter()
+            .filter(|channel| {
+                matches!(
+                    channel,
+                    Channel::Offered(_) | Channel::Accepted(_) | Channel::Signed(_)
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            network,
+            contracts,
+            channels,
+            channel_keys: storage.get_channel_keys().await?,
+            chain_monitor: storage.get_chain_monitor().await?,
+        })
+    }
+
+    /// Writes the contracts and channels of the backup to `storage`.
+    pub async fn restore_into<S: Storage>(&self, storage: &S) -> Result<(), Error> {
+        for contract in &self.contracts {
+            match contract {
+                Contract::Offered(offered) => storage.create_contract(offered).await?,
+                contract => storage.update_contract(contract).await?,
+            }
+        }
+        for channel in &self.channels {
+            storage.upsert_channel(channel.clone(), None).await?;
+        }
+        for (pubkey, derivation) in &self.channel_keys {
+            storage.persist_channel_key(pubkey, derivation).await?;
+        }
+        if let Some(chain_monitor) = &self.chain_monitor {
+            storage.persist_chain_monitor(chain_monitor).await?;
+        }
+        Ok(())
+    }
+
+    /// Serializes and encrypts the backup with `key`.
+    pub fn encrypt(&self, key: &[u8; 32]) -> Result<Vec<u8>, Error> {
+        let payload = self.serialize()?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce
+            .try_fill(&mut thread_rng())
+            .map_err(|e| Error::Backup(e.to_string()))?;
+
+        let header = header(BACKUP_VERSION);
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key))
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &payload,
+                    aad: &header,
+                },
+            )
+            .map_err(|_| Error::Backup("Could not encrypt the backup.".to_string()))?;
+
+        let mut backup = Vec::with_capacity(HEADER_LEN + NONCE_LEN + ciphertext.len());
+        backup.extend_from_slice(&header);
+        backup.extend_from_slice(&nonce);
+        backup.extend(ciphertext);
+        Ok(backup)
+    }
+
+    /// Decrypts a backup written by [`StaticBackup::encrypt`] with `key`.
+    pub fn decrypt(backup: &[u8], key: &[u8; 32]) -> Result<Self, Error> {
+        if backup.len() < HEADER_LEN + NONCE_LEN || &backup[..4] != BACKUP_MAGIC {
+            return Err(Error::Backup("Not a DDK backup.".to_string()));
+        }
+        let version = u16::from_be_bytes([backup[4], backup[5]]);
+        if version != BACKUP_VERSION {
+            return Err(Error::Backup(format!(
+                "Unsupported backup version. version={version}"
+            )));
+        }
+
+        let (header, rest) = backup.split_at(HEADER_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let payload = ChaCha20Poly1305::new(Key::from_slice(key))
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| Error::Backup("Could not decrypt the backup, wrong seed?".to_string()))?;
+
+        Self::deserialize(&payload)
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&self.network.magic().to_bytes());
+
+        write_len(&mut buf, self.contracts.len());
+        for contract in &self.contracts {
+            write_record(&mut buf, &serialize_contract(contract)?);
+        }
+
+        write_len(&mut buf, self.channels.len());
+        for channel in &self.channels {
+            write_record(&mut buf, &serialize_channel(channel)?);
+        }
+
+        write_len(&mut buf, self.channel_keys.len());
+        for (pubkey, derivation) in &self.channel_keys {
+            buf.extend_from_slice(&pubkey.serialize());
+            match derivation {
+                ChannelKeyDerivation::Channel(index) => {
+                    buf.push(0);
+                    buf.extend_from_slice(&index.to_be_bytes());
+                }
+                ChannelKeyDerivation::Funding(keys_id) => {
+                    buf.push(1);
+                    buf.extend_from_slice(keys_id);
+                }
+            }
+        }
+
+        match &self.chain_monitor {
+            Some(chain_monitor) => {
+                buf.push(1);
+                write_record(&mut buf, &chain_monitor.encode());
+            }
+            None => buf.push(0),
+        }
+
+        Ok(buf)
+    }
+
+    fn deserialize(payload: &[u8]) -> Result<Self, Error> {
+        let mut cursor = Cursor::new(payload);
+
+        let magic: [u8; 4] = read_array(&mut cursor)?;
+        let network = Network::from_magic(Magic::from_bytes(magic))
+            .ok_or_else(|| Error::Backup("Unknown network in backup.".to_string()))?;
+
+        let contracts = (0..read_len(&mut cursor)?)
+            .map(|_| deserialize_contract(&read_record(&mut cursor)?).map_err(Error::from))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let channels = (0..read_len(&mut cursor)?)
+            .map(|_| deserialize_channel(&read_record(&mut cursor)?).map_err(Error::from))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let channel_keys = (0..read_len(&mut cursor)?)
+            .map(|_| {
+                let pubkey = PublicKey::from_slice(&read_array::<33>(&mut cursor)?)
+                    .map_err(|e| Error::Backup(e.to_string()))?;
+                let derivation = match read_array::<1>(&mut cursor)? {
+                    [0] => {
+                        ChannelKeyDerivation::Channel(u32::from_be_bytes(read_array(&mut cursor)?))
+                    }
+                    [1] => ChannelKeyDerivation::Funding(read_array(&mut cursor)?),
+                    _ => return Err(Error::Backup("Unknown channel key derivation.".to_string())),
+                };
+                Ok((pubkey, derivation))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let chain_monitor = match read_array::<1>(&mut cursor)? {
+            [0] => None,
+            [1] => {
+                let record = read_record(&mut cursor)?;
+                Some(
+                    ChainMonitor::read(&mut Cursor::new(&record))
+                        .map_err(|e| Error::Backup(format!("Invalid chain monitor: {e}")))?,
+                )
+            }
+            _ => return Err(Error::Backup("Invalid chain monitor flag.".to_string())),
+        };
+
+        Ok(Self {
+            network,
+            contracts,
+            channels,
+            channel_keys,
+            chain_monitor,
+        })
+    }
+}
+
+/// Where the encrypted backups are written.
+#[async_trait::async_trait]
+pub trait BackupSink: Send + Sync + 'static {
+    /// Stores `backup`, the encrypted bytes of a [`StaticBackup`], replacing
+    /// the previous one.
+    async fn write_backup(&self, backup: &[u8]) -> Result<(), Error>;
+}
+
+/// Writes the backups to a file, replacing it atomically. The new backup is
+/// synced to disk before it replaces the previous one, and the replacement is
+/// synced before the write returns.
+#[derive(Debug, Clone)]
+pub struct FileBackupSink {
+    path: PathBuf,
+}
+
+impl FileBackupSink {
+    /// Writes the backups to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl BackupSink for FileBackupSink {
+    async fn write_backup(&self, backup: &[u8]) -> Result<(), Error> {
+        let tmp_path = self.path.with_extension("tmp");
+        let write = async {
+            let mut file = tokio::fs::File::create(&tmp_path).await?;
+            file.write_all(backup).await?;
+            file.sync_all().await?;
+            drop(file);
+            tokio::fs::rename(&tmp_path, &self.path).await?;
+            // The rename is only durable once the directory is synced.
+            #[cfg(unix)]
+            {
+                let dir = match self.path.parent() {
+                    Some(dir) if !dir.as_os_str().is_empty() => dir,
+                    _ => std::path::Path::new("."),
+                };
+                tokio::fs::File::open(dir).await?.sync_all().await?;
+            }
+            Ok::<_, std::io::Error>(())
+        };
+        write.await.map_err(|e| Error::Backup(e.to_string()))
+    }
+}
+
+/// Writes a backup to the sink when the contracts or channels in the store
+/// changed since the last one.
+pub(crate) struct BackupWriter<S: Storage> {
+    storage: Arc<S>,
+    sink: Arc<dyn BackupSink>,
+    key: [u8; 32],
+    network: Network,
+    last_written: Mutex<Option<sha256::Hash>>,
+    logger: Arc<Logger>,
+}
+
+impl<S: Storage> BackupWriter<S> {
+    pub(crate) fn new(
+        storage: Arc<S>,
+        sink: Arc<dyn BackupSink>,
+        key: [u8; 32],
+        network: Network,
+        logger: Arc<Logger>,
+    ) -> Self {
+        Self {
+            storage,
+            sink,
+            key,
+            network,
+            last_written: Mutex::new(None),
+            logger,
+        }
+    }
+
+    pub(crate) async fn write(&self) {
+        if let Err(e) = self.try_write().await {
+            log_error!(self.logger, "Could not write the backup. error={}", e);
+        }
+    }
+
+    async fn try_write(&self) -> Result<(), Error> {
+        let backup = StaticBackup::from_storage(&*self.storage, self.network).await?;
+        let digest = sha256::Hash::hash(&backup.serialize()?);
+
+        let mut last_written = self.last_written.lock().await;
+        if *last_written == Some(digest) {
+            return Ok(());
+        }
+        self.sink.write_backup(&backup.encrypt(&self.key)?).await?;
+        *last_written = Some(digest);
+        log_debug!(
+            self.logger,
+            "Wrote backup. contracts={} channels={}",
+            backup.contracts.len(),
+            backup.channels.len()
+        );
+        Ok(())
+    }
+}
+
+fn header(version: u16) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..4].copy_from_slice(BACKUP_MAGIC);
+    header[4..].copy_from_slice(&version.to_be_bytes());
+    header
+}
+
+fn write_len(buf: &mut Vec<u8>, len: usize) {
+    buf.extend_from_slice(&(len as u64).to_be_bytes());
+}
+
+fn write_record(buf: &mut Vec<u8>, record: &[u8]) {
+    write_len(buf, record.len());
+    buf.extend_from_slice(record);
+}
+
+fn read_array<const N: usize>(cursor: &mut Cursor<&[u8]>) -> Result<[u8; N], Error> {
+    let mut array = [0u8; N];
+    cursor
+        .read_exact(&mut array)
+        .map_err(|_| Error::Backup("Truncated backup.".to_string()))?;
+    Ok(array)
+}
+
+fn read_len(cursor: &mut Cursor<&[u8]>) -> Result<u64, Error> {
+    Ok(u64::from_be_bytes(read_array(cursor)?))
+}
+
+fn read_record(cursor: &mut Cursor<&[u8]>) -> Result<Vec<u8>, Error> {
+    let len = read_len(cursor)?;
+    let remaining = cursor.get_ref().len() as u64 - cursor.position();
+    if len > remaining {
+        return Err(Error::Backup("Truncated backup.".to_string()));
+    }
+    let mut record = vec![0u8; len as usize];
+    cursor
+        .read_exact(&mut record)
+        .map_err(|_| Error::Backup("Truncated backup.".to_string()))?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::memory::MemoryStorage;
+    use ddk_manager::Storage as _;
+
+    fn contract(name: &str) -> Contract {
+        let bytes: &[u8] = match name {
+            "Offered" => include_bytes!("../../testconfig/contract_binaries/Offered"),
+            "Closed" => include_bytes!("../../testconfig/contract_binaries/Closed"),
+            _ => unreachable!(),
+        };
+        deserialize_contract(&bytes.to_vec()).unwrap()
+    }
+
+    async fn storage() -> MemoryStorage {
+        let storage = MemoryStorage::new();
+        for name in ["Offered", "Closed"] {
+            storage.update_contract(&contract(name)).await.unwrap();
+        }
+        let pubkey = PublicKey::from_slice(&[2; 33]).unwrap();
+        storage
+            .persist_channel_key(&pubkey, &ChannelKeyDerivation::Channel(3))
+            .await
+            .unwrap();
+        storage
+    }
+
+    #[tokio::test]
+    async fn backup_round_trips_open_contracts() {
+        let storage = storage().await;
+        let backup = StaticBackup::from_storage(&storage, Network::Regtest)
+            .await
+            .unwrap();
+        // The closed contract holds no funds anymore.
+        assert_eq!(backup.contracts.len(), 1);
+
+        let key = [7u8; 32];
+        let decrypted = StaticBackup::decrypt(&backup.encrypt(&key).unwrap(), &key).unwrap();
+        assert_eq!(decrypted.network, Network::Regtest);
+        assert_eq!(decrypted.channel_keys, backup.channel_keys);
+        assert_eq!(decrypted.serialize().unwrap(), backup.serialize().unwrap());
+
+        let restored = MemoryStorage::new();
+        decrypted.restore_into(&restored).await.unwrap();
+        let restored_contracts = restored.get_contracts().await.unwrap();
+        assert_eq!(restored_contracts.len(), 1);
+        assert_eq!(restored_contracts[0].get_id(), contract("Offered").get_id());
+        assert_eq!(
+            restored.get_channel_keys().await.unwrap(),
+            backup.channel_keys
+        );
+    }
+
+    #[tokio::test]
+    async fn backup_is_rejected_with_another_key_or_version() {
+        let backup = StaticBackup::from_storage(&storage().await, Network::Regtest)
+            .await
+            .unwrap();
+        let mut encrypted = backup.encrypt(&[7u8; 32]).unwrap();
+        assert!(StaticBackup::decrypt(&encrypted, &[8u8; 32]).is_err());
+
+        encrypted[5] = 2;
+        let err = StaticBackup::decrypt(&encrypted, &[7u8; 32]).unwrap_err();
+        assert!(err.to_string().contains("Unsupported backup version"));
+    }
+
+    #[tokio::test]
+    async fn file_sink_replaces_the_previous_backup() {
+        let dir = std::env::temp_dir().join(format!("ddk_backup_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ddk.backup");
+        let sink = FileBackupSink::new(&path);
+
+        sink.write_backup(b"first").await.unwrap();
+        sink.write_backup(b"second").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert!(!path.with_extension("tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,12 +1,17 @@
-use crate::logger::{log_error, log_info, WriteLog};
+use crate::logger::{log_debug, log_error, log_info, log_warn, WriteLog};
+use bdk_wallet::WalletEvent;
 use bip39::{Language, Mnemonic};
 use bitcoin::key::rand::Fill;
 use bitcoin::Network;
+use ddk_manager::events::Event;
 use ddk_manager::manager::Manager;
 use ddk_manager::SystemTimeProvider;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
 
+use crate::backup::{BackupSink, BackupWriter};
 use crate::chain::{EsploraClient, ZeromqClient};
 use crate::ddk::{DlcDevKit, DlcManagerMessage};
 use crate::error::{BuilderError, Error};
@@ -40,9 +45,11 @@ pub struct Builder<T, S, O> {
     contract_address_generator: Option<Arc<dyn AddressGenerator + Send + Sync + 'static>>,
     esplora_host: String,
     zmq_blockhash_endpoint: Option<String>,
+    fee_cache_ttl: Option<Duration>,
     network: Network,
     seed_bytes: [u8; 64],
     logger: Option<Arc<Logger>>,
+    backup_sink: Option<Arc<dyn BackupSink>>,
 }
 
 /// Defaults when creating a DDK application
@@ -60,9 +67,11 @@ impl<T: Transport, S: Storage, O: Oracle> Default for Builder<T, S, O> {
             contract_address_generator: None,
             esplora_host: DEFAULT_ESPLORA_HOST.to_string(),
             zmq_blockhash_endpoint: None,
+            fee_cache_ttl: None,
             network: DEFAULT_NETWORK,
             seed_bytes: [0u8; 64],
             logger: None,
+            backup_sink: None,
         }
     }
 }
@@ -98,7 +107,8 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
     }
 
     /// Oracle implementation for the [ddk_manager::manager::Manager] to retrieve oracle attestations and announcements.
-    /// MUST implement [`crate::Oracle`].
+    /// MUST implement [`crate::Oracle`]. Use an [`OracleSet`](crate::oracle::set::OracleSet) for contracts with
+    /// several oracles.
     pub fn set_oracle(&mut self, oracle: Arc<O>) -> &mut Self {
         self.oracle = Some(oracle);
         self
@@ -126,6 +136,13 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
         self
     }
 
+    /// Set how long fee estimates from esplora are cached before they are fetched again.
+    /// Defaults to [`crate::chain::fee::DEFAULT_FEE_CACHE_TTL`].
+    pub fn set_fee_cache_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.fee_cache_ttl = Some(ttl);
+        self
+    }
+
     /// Set the network DDK connects to.
     pub fn set_network(&mut self, network: Network) -> &mut Self {
         self.network = network;
@@ -156,6 +173,13 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
         self
     }
 
+    /// Write an encrypted [`crate::backup::StaticBackup`] to `sink` at startup
+    /// and whenever a contract or a channel changes state.
+    pub fn set_backup_sink(&mut self, sink: Arc<dyn BackupSink>) -> &mut Self {
+        self.backup_sink = Some(sink);
+        self
+    }
+
     /// Setup the logger based on the provided logger or use default console logging
     fn setup_logger(&self, name: &str) -> Result<Arc<Logger>, Error> {
         match &self.logger {
@@ -195,11 +219,25 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
 
         let logger = self.setup_logger(&name)?;
 
-        let esplora_client = Arc::new(EsploraClient::new(
-            &self.esplora_host,
-            self.network,
-            logger.clone(),
-        )?);
+        let mut esplora_client =
+            EsploraClient::new(&self.esplora_host, self.network, logger.clone())?;
+        if let Some(ttl) = self.fee_cache_ttl {
+            esplora_client = esplora_client.with_fee_cache_ttl(ttl);
+        }
+        let esplora_client = Arc::new(esplora_client);
+        // Fetched in the background so that an unreachable Esplora does not hold up
+        // the start, the fallback fee rates are used in the meantime.
+        let fee_client = esplora_client.clone();
+        let fee_logger = logger.clone();
+        tokio::spawn(async move {
+            if let Err(e) = fee_client.update_fee_estimates().await {
+                log_warn!(
+                    fee_logger,
+                    "Could not fetch the initial fee estimates. error={}",
+                    e
+                );
+            }
+        });
 
         let wallet = match &self.contract_address_generator {
             Some(w) => {
@@ -227,8 +265,12 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
             ),
         };
 
-        let mut oracles = HashMap::new();
-        oracles.insert(oracle.get_public_key(), oracle.clone());
+        // An oracle set serves several oracles, the manager looks each up by public key.
+        let oracles = oracle
+            .clone()
+            .routes()
+            .into_iter()
+            .collect::<HashMap<_, _>>();
 
         let (sender, mut receiver) = tokio::sync::mpsc::channel(100);
         let (stop_signal_sender, stop_signal) = tokio::sync::watch::channel(false);
@@ -249,6 +291,7 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
 
         let manager_clone = manager.clone();
         let logger_clone = logger.clone();
+        let transport_clone = transport.clone();
         tokio::spawn(async move {
             while let Some(msg) = receiver.recv().await {
                 match msg {
@@ -282,11 +325,76 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
                     }
                     DlcManagerMessage::PeriodicCheck => {
                         let _ = manager_clone.periodic_check(false).await;
+                        // Rejects for the contract offers that expired.
+                        for (counter_party, message) in
+                            manager_clone.get_and_clear_pending_messages().await
+                        {
+                            transport_clone.send_message(counter_party, message).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        // Publish the transactions found by wallet syncs on the event bus of
+        // the manager.
+        let mut wallet_events = wallet.subscribe();
+        let manager_clone = manager.clone();
+        tokio::spawn(async move {
+            loop {
+                match wallet_events.recv().await {
+                    Ok(event) => {
+                        if let Some(event) = wallet_tx_event(event) {
+                            manager_clone.publish_event(event);
+                        }
                     }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        // Check the oracles against the attestations their clients see on top
+        // of the ones the manager asks for.
+        let (attestation_sender, mut attestations) = tokio::sync::mpsc::unbounded_channel();
+        oracle.forward_attestations(attestation_sender);
+        let manager_clone = manager.clone();
+        let logger_clone = logger.clone();
+        tokio::spawn(async move {
+            while let Some((attestation, source)) = attestations.recv().await {
+                // Relays carry the attestations of events no contract depends on.
+                if let Err(e) = manager_clone.record_attestation(attestation, source).await {
+                    log_debug!(
+                        logger_clone,
+                        "Did not record forwarded attestation. error={}",
+                        e.to_string()
+                    );
                 }
             }
         });
 
+        if let Some(sink) = &self.backup_sink {
+            let writer = BackupWriter::new(
+                storage.clone(),
+                sink.clone(),
+                wallet.backup_key()?,
+                self.network,
+                logger.clone(),
+            );
+            // Subscribe before the first backup, so no state change is missed.
+            let mut events = manager.subscribe();
+            writer.write().await;
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(Event::WalletTxReceived { .. }) => continue,
+                        Ok(_) | Err(RecvError::Lagged(_)) => writer.write().await,
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+
         let zmq_client = if let Some(endpoint) = &self.zmq_blockhash_endpoint {
             Some(Arc::new(
                 ZeromqClient::new(endpoint, logger.clone(), stop_signal.clone()).await?,
@@ -297,18 +405,20 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
 
         log_info!(
             logger.clone(),
-            "DDK runtime created. name={}, esplora={}, network={}, transport={}, oracle={}, zmq_enabled={}",
+            "DDK runtime created. name={}, esplora={}, network={}, transport={}, oracle={}, zmq_enabled={}, backup_enabled={}",
             name,
             self.esplora_host,
             self.network,
             transport.name(),
             oracle.get_public_key(),
-            zmq_client.is_some()
+            zmq_client.is_some(),
+            self.backup_sink.is_some()
         );
 
         Ok(DlcDevKit {
             runtime: Arc::new(RwLock::new(None)),
             wallet,
+            blockchain: esplora_client,
             manager,
             sender,
             transport,
@@ -322,3 +432,25 @@ impl<T: Transport, S: Storage, O: Oracle> Builder<T, S, O> {
         })
     }
 }
+
+/// The [`Event::WalletTxReceived`] for a transaction first seen or confirmed
+/// by a wallet sync.
+fn wallet_tx_event(event: WalletEvent) -> Option<Event> {
+    match event {
+        WalletEvent::TxUnconfirmed {
+            txid,
+            old_block_time: None,
+            ..
+        } => Some(Event::WalletTxReceived {
+            txid,
+            confirmation_height: None,
+        }),
+        WalletEvent::TxConfirmed {
+            txid, block_time, ..
+        } => Some(Event::WalletTxReceived {
+            txid,
+            confirmation_height: Some(block_time.block_id.height),
+        }),
+        _ => None,
+    }
+}
@@ -1,12 +1,13 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::chain::fee::{FeeEstimateCache, FeeEstimates, FeeTarget};
 use crate::error::{esplora_err_to_manager_err, Error};
 use crate::logger::Logger;
 use crate::logger::{log_debug, log_error, log_info, log_warn, WriteLog};
 use bdk_esplora::esplora_client::Error as EsploraError;
 use bdk_esplora::esplora_client::{AsyncClient, Builder};
-use bitcoin::Network;
-use bitcoin::{consensus::encode, Transaction, Txid};
+use bitcoin::{consensus::encode, FeeRate, Network, Transaction, Txid};
 use ddk_manager::error::Error as ManagerError;
 use ddk_manager::ConfirmationStatus;
 use lightning::chain::chaininterface::{ConfirmationTarget, FeeEstimator};
@@ -35,10 +36,12 @@ fn esplora_timeout_secs() -> u64 {
 ///
 /// Used by rust-dlc for getting transactions related to DLC contracts.
 /// Used by bdk to sync the wallet and track transaction.
+/// Used for fee estimation through the `/fee-estimates` endpoint.
 #[derive(Debug)]
 pub struct EsploraClient {
     pub async_client: AsyncClient,
     network: Network,
+    fee_cache: FeeEstimateCache,
     logger: Arc<Logger>,
 }
 
@@ -53,9 +56,61 @@ impl EsploraClient {
         Ok(EsploraClient {
             async_client,
             network,
+            fee_cache: FeeEstimateCache::default(),
             logger,
         })
     }
+
+    /// Set how long fee estimates are cached before they are fetched again.
+    pub fn with_fee_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.fee_cache = FeeEstimateCache::new(ttl);
+        self
+    }
+
+    /// Fetches the fee estimates from Esplora and replaces the cached ones.
+    ///
+    /// On failure the previously cached estimates are kept.
+    pub async fn update_fee_estimates(&self) -> Result<(), Error> {
+        match self.async_client.get_fee_estimates().await {
+            Ok(estimates) => {
+                log_debug!(
+                    self.logger,
+                    "Updated fee estimates. num_targets={}",
+                    estimates.len()
+                );
+                self.fee_cache.update(estimates);
+                Ok(())
+            }
+            Err(e) => {
+                log_warn!(
+                    self.logger,
+                    "Could not fetch fee estimates. Using cached or fallback fee rates. error={}",
+                    e.to_string()
+                );
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Returns the estimated fee rate for a target, fetching new estimates if
+    /// the cached ones are stale. Falls back to the cached or default rates
+    /// when Esplora is unreachable.
+    pub async fn estimate_fee_rate(&self, target: FeeTarget) -> FeeRate {
+        if self.fee_cache.is_stale() {
+            let _ = self.update_fee_estimates().await;
+        }
+        self.fee_cache.fee_rate(target)
+    }
+
+    /// The currently cached fee estimates, without fetching new ones.
+    pub fn fee_estimates(&self) -> Option<FeeEstimates> {
+        self.fee_cache.estimates()
+    }
+
+    /// Whether the cached fee estimates are missing or older than the TTL.
+    pub fn fee_estimates_stale(&self) -> bool {
+        self.fee_cache.is_stale()
+    }
 }
 
 /// Implements the `ddk_manager::Blockchain` interface. Grabs chain related information
@@ -212,8 +267,11 @@ impl ddk_manager::Blockchain for EsploraClient {
     }
 }
 
+/// Serves fee rates from the cached Esplora estimates. The cache is refreshed
+/// by [`EsploraClient::update_fee_estimates`], this never blocks on the network.
 impl FeeEstimator for EsploraClient {
-    fn get_est_sat_per_1000_weight(&self, _confirmation_target: ConfirmationTarget) -> u32 {
-        1
+    fn get_est_sat_per_1000_weight(&self, confirmation_target: ConfirmationTarget) -> u32 {
+        self.fee_cache
+            .sat_per_1000_weight(FeeTarget::from(confirmation_target))
     }
 }
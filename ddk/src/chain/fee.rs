@@ -0,0 +1,333 @@
+//! Fee estimation backed by Esplora's `/fee-estimates` endpoint.
+//!
+//! Esplora returns a map of confirmation targets (in blocks) to fee rates in
+//! sat/vB. The estimates are cached for a configurable TTL so the synchronous
+//! [`lightning::chain::chaininterface::FeeEstimator`] interface can be served
+//! without a network round trip. When the endpoint is unreachable, or does not
+//! know a rate (regtest usually returns an empty map), a conservative fallback
+//! table is used instead.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use bitcoin::FeeRate;
+use lightning::chain::chaininterface::ConfirmationTarget;
+
+/// Default time fee estimates are considered fresh before they are fetched again.
+pub const DEFAULT_FEE_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The minimum fee rate LDK accepts, in sat per 1000 weight units.
+pub const MIN_FEERATE_SAT_PER_KW: u32 = 253;
+
+/// Transactions a fee rate can be estimated for.
+///
+/// The DLC specific targets cover the transactions of a contract. The
+/// [`FeeTarget::Lightning`] variant wraps the targets the `ddk_manager` asks for
+/// through the LDK `FeeEstimator` interface (e.g. channel punishment transactions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeeTarget {
+    /// The funding transaction of a contract. Used as the default fee rate of an offer.
+    Funding,
+    /// A contract execution transaction. CETs race the refund locktime, so they
+    /// target a quicker confirmation than the funding transaction.
+    Cet,
+    /// The refund transaction of a contract.
+    Refund,
+    /// A target requested by LDK components.
+    Lightning(ConfirmationTarget),
+}
+
+impl FeeTarget {
+    /// The number of blocks the transaction should confirm within.
+    pub fn block_target(&self) -> u16 {
+        match self {
+            FeeTarget::Funding => 6,
+            FeeTarget::Cet => 3,
+            FeeTarget::Refund => 12,
+            FeeTarget::Lightning(target) => match target {
+                ConfirmationTarget::MaximumFeeEstimate => 1,
+                ConfirmationTarget::UrgentOnChainSweep => 6,
+                ConfirmationTarget::OutputSpendingFee => 12,
+                ConfirmationTarget::NonAnchorChannelFee => 12,
+                ConfirmationTarget::ChannelCloseMinimum => 144,
+                ConfirmationTarget::MinAllowedNonAnchorChannelRemoteFee => 144,
+                ConfirmationTarget::AnchorChannelFee => 1008,
+                ConfirmationTarget::MinAllowedAnchorChannelRemoteFee => 1008,
+            },
+        }
+    }
+
+    /// Fee rate in sat/vB used when no estimate is available for the target.
+    pub fn fallback_sat_per_vb(&self) -> f64 {
+        match self.block_target() {
+            0..=2 => 20.0,
+            3..=6 => 10.0,
+            7..=24 => 5.0,
+            25..=144 => 2.0,
+            _ => 1.0,
+        }
+    }
+}
+
+impl From<ConfirmationTarget> for FeeTarget {
+    fn from(target: ConfirmationTarget) -> Self {
+        FeeTarget::Lightning(target)
+    }
+}
+
+/// A snapshot of the fee estimates returned by Esplora.
+#[derive(Debug, Clone)]
+pub struct FeeEstimates {
+    /// Block target to fee rate in sat/vB.
+    pub estimates: HashMap<u16, f64>,
+    /// When the estimates were fetched.
+    pub updated_at: Instant,
+}
+
+impl FeeEstimates {
+    /// Creates a snapshot fetched now.
+    pub fn new(estimates: HashMap<u16, f64>) -> Self {
+        Self {
+            estimates,
+            updated_at: Instant::now(),
+        }
+    }
+
+    /// The fee rate in sat/vB for a block target.
+    ///
+    /// Esplora only reports a subset of targets. The estimate of the largest
+    /// reported target that is not above the requested one is used, so a
+    /// request never gets a slower rate than asked for. If every reported
+    /// target is above the requested one, the fastest reported rate is used.
+    pub fn sat_per_vb(&self, block_target: u16) -> Option<f64> {
+        self.estimates
+            .iter()
+            .filter(|(target, _)| **target <= block_target)
+            .max_by_key(|(target, _)| **target)
+            .or_else(|| self.estimates.iter().min_by_key(|(target, _)| **target))
+            .map(|(_, rate)| *rate)
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+    }
+}
+
+/// Cache of Esplora fee estimates with a time-to-live.
+#[derive(Debug)]
+pub struct FeeEstimateCache {
+    ttl: Duration,
+    estimates: RwLock<Option<FeeEstimates>>,
+}
+
+impl FeeEstimateCache {
+    /// Creates an empty cache.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            estimates: RwLock::new(None),
+        }
+    }
+
+    /// How long estimates are considered fresh.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Whether the cache is empty or older than the TTL.
+    pub fn is_stale(&self) -> bool {
+        match self.estimates.read().unwrap().as_ref() {
+            Some(estimates) => estimates.updated_at.elapsed() >= self.ttl,
+            None => true,
+        }
+    }
+
+    /// Replaces the cached estimates.
+    pub fn update(&self, estimates: HashMap<u16, f64>) {
+        *self.estimates.write().unwrap() = Some(FeeEstimates::new(estimates));
+    }
+
+    /// The cached estimates, if any were ever fetched.
+    pub fn estimates(&self) -> Option<FeeEstimates> {
+        self.estimates.read().unwrap().clone()
+    }
+
+    /// The fee rate in sat/vB for a target.
+    ///
+    /// Stale estimates are still preferred over the fallback table: an old
+    /// market rate is a better guess than a hardcoded one.
+    pub fn sat_per_vb(&self, target: FeeTarget) -> f64 {
+        self.estimates
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|estimates| estimates.sat_per_vb(target.block_target()))
+            .unwrap_or_else(|| target.fallback_sat_per_vb())
+            .max(1.0)
+    }
+
+    /// The fee rate for a target.
+    pub fn fee_rate(&self, target: FeeTarget) -> FeeRate {
+        FeeRate::from_sat_per_kwu(self.sat_per_1000_weight(target) as u64)
+    }
+
+    /// The fee rate for a target in sat per 1000 weight units, floored at
+    /// [`MIN_FEERATE_SAT_PER_KW`].
+    pub fn sat_per_1000_weight(&self, target: FeeTarget) -> u32 {
+        ((self.sat_per_vb(target) * 250.0).round() as u32).max(MIN_FEERATE_SAT_PER_KW)
+    }
+}
+
+impl Default for FeeEstimateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEE_CACHE_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use bitcoin::Network;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::chain::EsploraClient;
+    use crate::logger::Logger;
+    use lightning::chain::chaininterface::FeeEstimator;
+
+    const FEE_ESTIMATES: &str =
+        r#"{"1":40.5,"2":30.0,"3":25.1,"6":12.0,"12":8.0,"144":2.5,"1008":1.0}"#;
+
+    /// Serves `FEE_ESTIMATES` on `/fee-estimates` and counts the requests.
+    async fn mock_esplora() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_clone = hits.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let response = if request.starts_with("GET /fee-estimates ") {
+                    hits_clone.fetch_add(1, Ordering::SeqCst);
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        FEE_ESTIMATES.len(),
+                        FEE_ESTIMATES
+                    )
+                } else {
+                    "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        .to_string()
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        (host, hits)
+    }
+
+    fn logger() -> Arc<Logger> {
+        Arc::new(Logger::disabled("fee-test".to_string()))
+    }
+
+    #[test]
+    fn estimates_use_closest_faster_target() {
+        let estimates = FeeEstimates::new(serde_json::from_str(FEE_ESTIMATES).unwrap());
+        assert_eq!(estimates.sat_per_vb(1), Some(40.5));
+        assert_eq!(estimates.sat_per_vb(6), Some(12.0));
+        // 10 blocks is not reported, the 6 block estimate is used.
+        assert_eq!(estimates.sat_per_vb(10), Some(12.0));
+        assert_eq!(estimates.sat_per_vb(2016), Some(1.0));
+
+        let sparse = FeeEstimates::new(HashMap::from([(6, 9.0)]));
+        assert_eq!(sparse.sat_per_vb(1), Some(9.0));
+        assert_eq!(FeeEstimates::new(HashMap::new()).sat_per_vb(6), None);
+    }
+
+    #[test]
+    fn empty_cache_uses_fallback() {
+        let cache = FeeEstimateCache::default();
+        assert!(cache.is_stale());
+        assert_eq!(
+            cache.sat_per_vb(FeeTarget::Funding),
+            FeeTarget::Funding.fallback_sat_per_vb()
+        );
+        assert_eq!(
+            cache.sat_per_1000_weight(ConfirmationTarget::AnchorChannelFee.into()),
+            MIN_FEERATE_SAT_PER_KW
+        );
+    }
+
+    #[test]
+    fn cache_expires_after_ttl() {
+        let cache = FeeEstimateCache::new(Duration::ZERO);
+        cache.update(HashMap::from([(6, 9.0)]));
+        assert!(cache.is_stale());
+        // Stale estimates are still served.
+        assert_eq!(cache.sat_per_vb(FeeTarget::Funding), 9.0);
+
+        let cache = FeeEstimateCache::new(Duration::from_secs(60));
+        cache.update(HashMap::from([(6, 9.0)]));
+        assert!(!cache.is_stale());
+    }
+
+    #[tokio::test]
+    async fn esplora_fee_estimates_are_cached() {
+        let (host, hits) = mock_esplora().await;
+        let esplora = EsploraClient::new(&host, Network::Regtest, logger()).unwrap();
+
+        assert_eq!(
+            esplora.estimate_fee_rate(FeeTarget::Funding).await,
+            FeeRate::from_sat_per_vb_u32(12)
+        );
+        assert_eq!(
+            esplora.estimate_fee_rate(FeeTarget::Cet).await,
+            FeeRate::from_sat_per_kwu(25 * 250 + 25)
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        assert_eq!(
+            esplora.get_est_sat_per_1000_weight(ConfirmationTarget::MaximumFeeEstimate),
+            10125
+        );
+        assert_eq!(
+            esplora.get_est_sat_per_1000_weight(ConfirmationTarget::ChannelCloseMinimum),
+            625
+        );
+    }
+
+    #[tokio::test]
+    async fn esplora_fee_estimates_refresh_when_stale() {
+        let (host, hits) = mock_esplora().await;
+        let esplora = EsploraClient::new(&host, Network::Regtest, logger())
+            .unwrap()
+            .with_fee_cache_ttl(Duration::ZERO);
+
+        esplora.estimate_fee_rate(FeeTarget::Funding).await;
+        esplora.estimate_fee_rate(FeeTarget::Funding).await;
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn unreachable_esplora_falls_back() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let esplora = EsploraClient::new(&host, Network::Regtest, logger()).unwrap();
+        assert!(esplora.update_fee_estimates().await.is_err());
+        assert_eq!(
+            esplora.estimate_fee_rate(FeeTarget::Refund).await,
+            FeeRate::from_sat_per_vb_u32(5)
+        );
+        assert_eq!(
+            esplora.get_est_sat_per_1000_weight(ConfirmationTarget::UrgentOnChainSweep),
+            2500
+        );
+    }
+}
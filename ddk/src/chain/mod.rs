@@ -1,5 +1,7 @@
 mod esplora;
+pub mod fee;
 mod zmq;
 
 pub use esplora::EsploraClient;
+pub use fee::FeeTarget;
 pub use zmq::{ZeromqClient, ZeromqMessage};
@@ -2,6 +2,8 @@
 
 use ddk_dlc::secp256k1_zkp::{PublicKey, Secp256k1, SecretKey};
 use ddk_dlc::DlcTransactions;
+use ddk_manager::contract::taproot;
+use ddk_messages::tlv_stream::TlvStream;
 use ddk_messages::{AcceptDlc, CetAdaptorSignatures, OfferDlc};
 
 use super::context::{
@@ -22,7 +24,9 @@ use super::types::{random_serial_id, AcceptOfferParams, AcceptResult};
 ///
 /// `funding_secret_key` is the accepting party's DLC funding key, used here to
 /// produce CET adaptor signatures and the refund signature. It must match
-/// `params.party.funding_pubkey`.
+/// `params.party.funding_pubkey`. For a Taproot funded offer it produces MuSig2
+/// partial signatures instead, carried in the accept message's `taproot`
+/// record.
 pub fn accept_offer(
     offer: &OfferDlc,
     params: AcceptOfferParams,
@@ -62,13 +66,36 @@ pub fn accept_offer(
         &party.funding_inputs,
     )?;
     let context = build_context(offer, &accept_params)?;
-    let adaptor_signatures = create_adaptor_signatures(
-        &secp,
-        &context,
-        funding_secret_key,
-        offer.get_total_collateral(),
-    )?;
-    let refund_signature = create_refund_signature(&secp, &context, funding_secret_key)?;
+    let (adaptor_signatures, refund_signature, taproot_accept) =
+        if let Some(taproot_context) = &context.taproot {
+            let offer_record = offer.taproot.as_ref().ok_or_else(|| {
+                ContractError::InvalidOffer("offer is missing its Taproot nonces".to_string())
+            })?;
+            let accept_record = taproot::create_accept(
+                &secp,
+                &taproot_context.funding,
+                &context.transactions,
+                &taproot_context.adaptor_points,
+                funding_secret_key,
+                offer_record,
+            )?;
+            (
+                Vec::new(),
+                taproot::unused_refund_signature(),
+                Some(accept_record),
+            )
+        } else {
+            (
+                create_adaptor_signatures(
+                    &secp,
+                    &context,
+                    funding_secret_key,
+                    offer.get_total_collateral(),
+                )?,
+                create_refund_signature(&secp, &context, funding_secret_key)?,
+                None,
+            )
+        };
 
     let accept = AcceptDlc {
         protocol_version: offer.protocol_version,
@@ -83,6 +110,8 @@ pub fn accept_offer(
         cet_adaptor_signatures: CetAdaptorSignatures::from(adaptor_signatures.as_slice()),
         refund_signature,
         negotiation_fields: None,
+        taproot: taproot_accept,
+        tlvs: TlvStream::default(),
     };
     ensure_unique_input_serial_ids(offer, &accept)?;
 
@@ -0,0 +1,389 @@
+//! Batch funding: several contracts funded by a single funding transaction.
+//!
+//! The offers of a batch share the offering party's funding inputs and change
+//! output, and each carries a [`BatchFundingGroup`] record listing the
+//! temporary ids of all the offers of the batch. The accepting party answers
+//! every offer with the same funding inputs and change output of its own. The
+//! funding transaction then has one 2-of-2 output per contract, spent by that
+//! contract's CETs and refund transaction.
+//!
+//! Since the funding transaction depends on every contract of the batch, each
+//! operation takes the messages of all of them, in the order of their batch
+//! funding group.
+
+use bitcoin::psbt::Psbt;
+use bitcoin::Transaction;
+use ddk_dlc::secp256k1_zkp::{PublicKey, Secp256k1, SecretKey};
+use ddk_dlc::DlcTransactions;
+use ddk_messages::batch_msgs::BatchFundingGroup;
+use ddk_messages::oracle_msgs::OracleAttestation;
+use ddk_messages::tlv_stream::TlvStream;
+use ddk_messages::{AcceptDlc, CetAdaptorSignatures, OfferDlc, SignDlc};
+
+use super::context::{
+    batch_contexts_from_messages, build_batch_contexts, create_adaptor_signatures,
+    create_refund_signature, dlc_party_params, ensure_batch_offers, ensure_no_dlc_inputs,
+    ensure_unique_input_serial_ids, ContractContext,
+};
+use super::create::{build_offer, validate_offer};
+use super::error::ContractError;
+use super::finalize::finalize_with_context;
+use super::psbt::{
+    build_funding_psbt, ensure_psbt_matches_funding_transaction, extract_funding_signatures,
+};
+use super::settle::{sign_cet_with_context, sign_refund_with_context};
+use super::sign::sign_with_context;
+use super::types::{
+    random_serial_id, AcceptOfferParams, BatchAcceptResult, BatchSignResult, CreateOfferParams,
+    Party,
+};
+
+/// Creates the offer messages of a batch of contracts funded by a single
+/// funding transaction, one per element of `params`.
+///
+/// Every element must carry the same `party.funding_inputs` and
+/// `party.change_spk`, which must cover the collateral of all the contracts,
+/// and the same `fee_rate_per_vb`. The change serial id of the first element
+/// is used for all of them, and randomly generated when `None`. Batch funded
+/// offers cannot be Taproot funded or contain DLC (splice) inputs.
+pub fn create_batch_offer(params: Vec<CreateOfferParams>) -> Result<Vec<OfferDlc>, ContractError> {
+    let Some(first) = params.first() else {
+        return Err(ContractError::InvalidOffer("empty batch".to_string()));
+    };
+    ensure_no_dlc_inputs(&first.party.funding_inputs)?;
+    let funding_inputs = first.party.funding_inputs.clone();
+    let change_spk = first.party.change_spk.clone();
+    let change_serial_id = first
+        .party
+        .change_serial_id
+        .unwrap_or_else(random_serial_id);
+
+    let mut offers = Vec::with_capacity(params.len());
+    for mut params in params {
+        if params.contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0 {
+            return Err(ContractError::InvalidOffer(
+                "batch funded offers cannot be Taproot funded".to_string(),
+            ));
+        }
+        if params.party.funding_inputs != funding_inputs
+            || params.party.change_spk != change_spk
+            || params
+                .party
+                .change_serial_id
+                .is_some_and(|id| id != change_serial_id)
+        {
+            return Err(ContractError::InvalidOffer(
+                "offers of a batch must share their funding inputs and change output".to_string(),
+            ));
+        }
+        params.party.change_serial_id = Some(change_serial_id);
+        offers.push(build_offer(params)?);
+    }
+
+    let group = BatchFundingGroup::new(&offers);
+    for offer in &mut offers {
+        offer.tlvs.set(&group);
+    }
+    ensure_batch_offers(&offers)?;
+    Ok(offers)
+}
+
+/// Validates the offers of a batch and creates the accepting party's wire
+/// messages.
+///
+/// `params` and `funding_secret_keys` hold the accepting party's data for each
+/// offer, in the order of the offers. Every element of `params` must carry the
+/// same `party.funding_inputs` and `party.change_spk`, which must cover this
+/// party's collateral in all the contracts. The change serial id of the first
+/// element is used for all of them, and randomly generated when `None`.
+pub fn accept_batch_offer(
+    offers: &[OfferDlc],
+    params: Vec<AcceptOfferParams>,
+    funding_secret_keys: &[SecretKey],
+) -> Result<BatchAcceptResult, ContractError> {
+    if params.len() != offers.len() || funding_secret_keys.len() != offers.len() {
+        return Err(ContractError::InvalidAccept(
+            "expected accept parameters and a funding secret key for each offer of the batch"
+                .to_string(),
+        ));
+    }
+    ensure_batch_offers(offers)?;
+
+    let secp = Secp256k1::new();
+    let change_serial_id = params[0]
+        .party
+        .change_serial_id
+        .unwrap_or_else(random_serial_id);
+    let mut parties = Vec::with_capacity(params.len());
+    let mut accept_params = Vec::with_capacity(params.len());
+    for ((offer, params), funding_secret_key) in offers.iter().zip(params).zip(funding_secret_keys)
+    {
+        let AcceptOfferParams {
+            party,
+            min_timeout_interval,
+            max_timeout_interval,
+        } = params;
+        validate_offer(offer, min_timeout_interval, max_timeout_interval)?;
+        ensure_no_dlc_inputs(&party.funding_inputs)?;
+        if PublicKey::from_secret_key(&secp, funding_secret_key) != party.funding_pubkey {
+            return Err(ContractError::InvalidAccept(
+                "funding secret key does not match the accept party funding public key".to_string(),
+            ));
+        }
+        if party
+            .change_serial_id
+            .is_some_and(|id| id != change_serial_id)
+        {
+            return Err(ContractError::InvalidAccept(
+                "accepts of a batch must share their change output".to_string(),
+            ));
+        }
+        let accept_collateral = offer
+            .get_total_collateral()
+            .checked_sub(offer.offer_collateral)
+            .ok_or_else(|| {
+                ContractError::InvalidOffer("offer collateral exceeds total collateral".to_string())
+            })?;
+        let payout_serial_id = party.payout_serial_id.unwrap_or_else(random_serial_id);
+        accept_params.push(dlc_party_params(
+            party.funding_pubkey,
+            party.payout_spk.clone(),
+            payout_serial_id,
+            party.change_spk.clone(),
+            change_serial_id,
+            accept_collateral,
+            &party.funding_inputs,
+        )?);
+        parties.push((party, payout_serial_id, accept_collateral));
+    }
+
+    let contexts = build_batch_contexts(offers, &accept_params)?;
+    let mut accepts = Vec::with_capacity(offers.len());
+    for (((offer, (party, payout_serial_id, accept_collateral)), funding_secret_key), context) in
+        offers
+            .iter()
+            .zip(parties)
+            .zip(funding_secret_keys)
+            .zip(&contexts)
+    {
+        let adaptor_signatures = create_adaptor_signatures(
+            &secp,
+            context,
+            funding_secret_key,
+            offer.get_total_collateral(),
+        )?;
+        let accept = AcceptDlc {
+            protocol_version: offer.protocol_version,
+            temporary_contract_id: offer.temporary_contract_id,
+            accept_collateral,
+            funding_pubkey: party.funding_pubkey,
+            payout_spk: party.payout_spk,
+            payout_serial_id,
+            funding_inputs: party.funding_inputs,
+            change_spk: party.change_spk,
+            change_serial_id,
+            cet_adaptor_signatures: CetAdaptorSignatures::from(adaptor_signatures.as_slice()),
+            refund_signature: create_refund_signature(&secp, context, funding_secret_key)?,
+            negotiation_fields: None,
+            taproot: None,
+            tlvs: TlvStream::default(),
+        };
+        ensure_unique_input_serial_ids(offer, &accept)?;
+        accepts.push(accept);
+    }
+
+    let funding_psbt = build_funding_psbt(
+        &offers[0],
+        &accepts[0],
+        contexts[0].transactions.fund.clone(),
+    )?;
+    Ok(BatchAcceptResult {
+        accepts,
+        transactions: contexts
+            .into_iter()
+            .map(|context| context.transactions)
+            .collect(),
+        funding_psbt,
+    })
+}
+
+/// Builds the funding PSBT of a batch from its offer and accept messages.
+///
+/// See [`create_funding_psbt`](super::create_funding_psbt).
+pub fn create_batch_funding_psbt(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+) -> Result<Psbt, ContractError> {
+    let contexts = batch_contexts_from_messages(offers, accepts)?;
+    build_funding_psbt(
+        &offers[0],
+        &accepts[0],
+        contexts[0].transactions.fund.clone(),
+    )
+}
+
+/// Rebuilds the unsigned funding, CET, and refund transactions of each
+/// contract of a batch from its wire messages.
+pub fn create_batch_dlc_transactions(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+) -> Result<Vec<DlcTransactions>, ContractError> {
+    Ok(batch_contexts_from_messages(offers, accepts)?
+        .into_iter()
+        .map(|context| context.transactions)
+        .collect())
+}
+
+/// Verifies the accept messages of a batch and creates the offering party's
+/// sign messages.
+///
+/// `funding_secret_keys` holds the offering party's DLC funding key of each
+/// contract, in the order of the offers. `signed_funding_psbt` must contain
+/// finalized witnesses for every offer-side funding input, which are carried
+/// by each of the sign messages.
+pub fn sign_batch_accept(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+    funding_secret_keys: &[SecretKey],
+    signed_funding_psbt: &Psbt,
+) -> Result<BatchSignResult, ContractError> {
+    if funding_secret_keys.len() != offers.len() {
+        return Err(ContractError::InvalidOffer(
+            "expected a funding secret key for each offer of the batch".to_string(),
+        ));
+    }
+    let contexts = batch_contexts_from_messages(offers, accepts)?;
+    ensure_psbt_matches_funding_transaction(signed_funding_psbt, &contexts[0].transactions.fund)?;
+    let funding_signatures =
+        extract_funding_signatures(&offers[0], &accepts[0], Party::Offer, signed_funding_psbt)?;
+
+    let mut signs = Vec::with_capacity(offers.len());
+    let mut transactions = Vec::with_capacity(offers.len());
+    for (((offer, accept), funding_secret_key), context) in offers
+        .iter()
+        .zip(accepts)
+        .zip(funding_secret_keys)
+        .zip(contexts)
+    {
+        let result = sign_with_context(
+            offer,
+            accept,
+            funding_secret_key,
+            None,
+            funding_signatures.clone(),
+            context,
+        )?;
+        signs.push(result.sign);
+        transactions.push(result.transactions);
+    }
+    Ok(BatchSignResult {
+        signs,
+        transactions,
+    })
+}
+
+/// Verifies the sign messages of a batch and completes the shared funding
+/// transaction.
+///
+/// `signed_funding_psbt` must contain finalized witnesses for every
+/// accept-side funding input. See [`finalize_sign`](super::finalize_sign).
+pub fn finalize_batch_sign(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+    signs: &[SignDlc],
+    signed_funding_psbt: &Psbt,
+) -> Result<Transaction, ContractError> {
+    if signs.len() != offers.len() {
+        return Err(ContractError::InvalidSign(
+            "expected a sign message for each offer of the batch".to_string(),
+        ));
+    }
+    let contexts = batch_contexts_from_messages(offers, accepts)?;
+    ensure_psbt_matches_funding_transaction(signed_funding_psbt, &contexts[0].transactions.fund)?;
+    let funding_signatures =
+        extract_funding_signatures(&offers[0], &accepts[0], Party::Accept, signed_funding_psbt)?;
+
+    let mut funding_transaction: Option<Transaction> = None;
+    for (((offer, accept), sign), context) in offers.iter().zip(accepts).zip(signs).zip(contexts) {
+        let transaction = finalize_with_context(
+            offer,
+            accept,
+            sign,
+            funding_signatures.clone(),
+            context,
+            &[],
+        )?;
+        match &funding_transaction {
+            Some(previous) if *previous != transaction => {
+                return Err(ContractError::InvalidSign(
+                    "sign messages of the batch carry different funding signatures".to_string(),
+                ));
+            }
+            Some(_) => {}
+            None => funding_transaction = Some(transaction),
+        }
+    }
+    funding_transaction.ok_or_else(|| ContractError::InvalidOffer("empty batch".to_string()))
+}
+
+/// Signs the CET of the contract at `index` in the batch matching a set of
+/// oracle attestations. See [`sign_cet`](super::sign_cet).
+pub fn sign_batch_cet(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+    signs: &[SignDlc],
+    index: usize,
+    funding_secret_key: &SecretKey,
+    attestations: &[(usize, OracleAttestation)],
+) -> Result<Transaction, ContractError> {
+    let (context, sign) = batch_contract_context(offers, accepts, signs, index)?;
+    sign_cet_with_context(
+        &offers[index],
+        &accepts[index],
+        sign,
+        &context,
+        funding_secret_key,
+        attestations,
+    )
+}
+
+/// Signs the refund transaction of the contract at `index` in the batch. See
+/// [`sign_refund`](super::sign_refund).
+pub fn sign_batch_refund(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+    signs: &[SignDlc],
+    index: usize,
+    funding_secret_key: &SecretKey,
+) -> Result<Transaction, ContractError> {
+    let (context, sign) = batch_contract_context(offers, accepts, signs, index)?;
+    sign_refund_with_context(
+        &offers[index],
+        &accepts[index],
+        sign,
+        &context,
+        funding_secret_key,
+    )
+}
+
+/// Rebuilds the contract at `index` in the batch and returns it with its sign
+/// message.
+fn batch_contract_context<'a>(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+    signs: &'a [SignDlc],
+    index: usize,
+) -> Result<(ContractContext, &'a SignDlc), ContractError> {
+    if signs.len() != offers.len() {
+        return Err(ContractError::InvalidSign(
+            "expected a sign message for each offer of the batch".to_string(),
+        ));
+    }
+    let context = batch_contexts_from_messages(offers, accepts)?
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| {
+            ContractError::InvalidOffer(format!("the batch has no contract at index {index}"))
+        })?;
+    Ok((context, &signs[index]))
+}
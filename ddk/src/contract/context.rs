@@ -6,10 +6,17 @@
 
 use bitcoin::consensus::Decodable;
 use bitcoin::{Amount, ScriptBuf, Transaction, Witness};
+use ddk_dlc::batch::BatchContractParams;
 use ddk_dlc::secp256k1_zkp::{All, EcdsaAdaptorSignature, PublicKey, Secp256k1, SecretKey};
+use ddk_dlc::taproot::TaprootFunding;
 use ddk_dlc::{DlcTransactions, PartyParams as DlcPartyParams, TxInputInfo};
 use ddk_manager::contract::contract_info::ContractInfo as ExecutionContractInfo;
-use ddk_messages::{AcceptDlc, CetAdaptorSignatures, FundingInput, FundingSignatures, OfferDlc};
+use ddk_manager::contract::AdaptorInfo;
+use ddk_messages::batch_msgs::BatchFundingGroup;
+use ddk_messages::taproot_msgs::{TaprootAccept, TaprootOffer, TaprootSign};
+use ddk_messages::{
+    AcceptDlc, CetAdaptorSignatures, FundingInput, FundingSignatures, OfferDlc, SignDlc,
+};
 
 use super::error::ContractError;
 use super::types::Party;
@@ -20,6 +27,20 @@ pub(crate) struct ContractContext {
     pub execution_infos: Vec<ExecutionContractInfo>,
     pub cet_ranges: Vec<std::ops::Range<usize>>,
     pub transactions: DlcTransactions,
+    /// Present when the offer sets [`ddk_dlc::TAPROOT_FUNDING_FLAG`].
+    pub taproot: Option<TaprootContext>,
+}
+
+/// MuSig2 data of a Taproot funded contract, rebuilt from the funding keys and
+/// the contract infos.
+pub(crate) struct TaprootContext {
+    pub funding: TaprootFunding,
+    pub adaptor_infos: Vec<AdaptorInfo>,
+    /// The index of the first adaptor point of each execution info.
+    pub adaptor_starts: Vec<usize>,
+    /// The CET index and adaptor point of each CET partial signature. CET
+    /// indexes refer to the CETs of all the execution infos.
+    pub adaptor_points: Vec<(usize, PublicKey)>,
 }
 
 /// Validates an offer/accept pair and rebuilds the contract transactions.
@@ -27,6 +48,34 @@ pub(crate) fn context_from_messages(
     offer: &OfferDlc,
     accept: &AcceptDlc,
 ) -> Result<ContractContext, ContractError> {
+    let accept_params = accept_params_from_messages(offer, accept)?;
+    build_context(offer, &accept_params)
+}
+
+/// Validates the offer/accept pairs of a batch, given in the order of their
+/// batch funding group, and rebuilds the transactions of each contract.
+pub(crate) fn batch_contexts_from_messages(
+    offers: &[OfferDlc],
+    accepts: &[AcceptDlc],
+) -> Result<Vec<ContractContext>, ContractError> {
+    if offers.len() != accepts.len() {
+        return Err(ContractError::InvalidAccept(
+            "expected an accept message for each offer of the batch".to_string(),
+        ));
+    }
+    let accept_params = offers
+        .iter()
+        .zip(accepts)
+        .map(|(offer, accept)| accept_params_from_messages(offer, accept))
+        .collect::<Result<Vec<_>, _>>()?;
+    build_batch_contexts(offers, &accept_params)
+}
+
+/// Validates an offer/accept pair and returns the accepting party's parameters.
+fn accept_params_from_messages(
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+) -> Result<DlcPartyParams, ContractError> {
     ensure_protocol_version(offer.protocol_version, ContractError::InvalidOffer)?;
     ensure_protocol_version(accept.protocol_version, ContractError::InvalidAccept)?;
     if offer.protocol_version != accept.protocol_version {
@@ -44,11 +93,17 @@ pub(crate) fn context_from_messages(
             "negotiation fields are not supported by the stateless API".to_string(),
         ));
     }
+    if offer.is_taproot() != accept.taproot.is_some() {
+        return Err(ContractError::InvalidAccept(
+            "Taproot signatures must be present exactly when the offer is Taproot funded"
+                .to_string(),
+        ));
+    }
     validate_offer_funding_inputs(&offer.funding_inputs)?;
     ensure_no_dlc_inputs(&accept.funding_inputs)?;
     ensure_unique_input_serial_ids(offer, accept)?;
 
-    let accept_params = dlc_party_params(
+    dlc_party_params(
         accept.funding_pubkey,
         accept.payout_spk.clone(),
         accept.payout_serial_id,
@@ -56,8 +111,7 @@ pub(crate) fn context_from_messages(
         accept.change_serial_id,
         accept.accept_collateral,
         &accept.funding_inputs,
-    )?;
-    build_context(offer, &accept_params)
+    )
 }
 
 /// Rebuilds the contract transactions from an offer and the accepting party's
@@ -67,38 +121,37 @@ pub(crate) fn build_context(
     offer: &OfferDlc,
     accept_params: &DlcPartyParams,
 ) -> Result<ContractContext, ContractError> {
-    let total_collateral = offer.get_total_collateral();
-    if offer.offer_collateral + accept_params.collateral != total_collateral {
-        return Err(ContractError::InvalidAccept(
-            "offer and accept collateral do not equal total collateral".to_string(),
-        ));
-    }
-    let offer_params = dlc_party_params(
-        offer.funding_pubkey,
-        offer.payout_spk.clone(),
-        offer.payout_serial_id,
-        offer.change_spk.clone(),
-        offer.change_serial_id,
-        offer.offer_collateral,
-        &offer.funding_inputs,
-    )?;
-    let execution_infos = ddk_manager::contract::execution_contract_infos(&offer.contract_info)?;
-    if execution_infos.is_empty() {
+    if batch_funding_group(offer)?.is_some() {
         return Err(ContractError::InvalidOffer(
-            "contract does not contain execution information".to_string(),
+            "batch funded offers must be handled with the batch functions".to_string(),
         ));
     }
-    for info in &execution_infos {
-        info.validate()?;
-    }
+    let (offer_params, execution_infos) = offer_params_and_execution_infos(offer, accept_params)?;
 
-    let payouts = execution_infos[0].get_payouts(total_collateral)?;
+    let payouts = execution_infos[0].get_payouts(offer.get_total_collateral())?;
     // A splice input carries a `dlc_input`; when present the funding transaction
     // spends the previous contract's 2-of-2 output and must be built through the
     // spliced constructor. Only the offer side may contribute DLC inputs.
     let has_dlc_inputs =
         !offer_params.dlc_inputs.is_empty() || !accept_params.dlc_inputs.is_empty();
-    let mut transactions = if has_dlc_inputs {
+    let transactions = if offer.is_taproot() {
+        if has_dlc_inputs {
+            return Err(ContractError::InvalidFundingInput(
+                "DLC inputs (splicing) are not supported with Taproot funding".to_string(),
+            ));
+        }
+        ddk_dlc::taproot::create_taproot_dlc_transactions(
+            &offer_params,
+            accept_params,
+            &payouts,
+            offer.refund_locktime,
+            offer.fee_rate_per_vb,
+            0,
+            offer.cet_locktime,
+            offer.fund_output_serial_id,
+            offer.contract_flags,
+        )?
+    } else if has_dlc_inputs {
         ddk_dlc::create_spliced_dlc_transactions(
             &offer_params,
             accept_params,
@@ -123,6 +176,167 @@ pub(crate) fn build_context(
             offer.contract_flags,
         )?
     };
+    complete_context(
+        offer,
+        &offer_params,
+        accept_params,
+        execution_infos,
+        transactions,
+    )
+}
+
+/// Rebuilds the transactions of the contracts of a batch, sharing one funding
+/// transaction, from their offers and the accepting party's parameters for
+/// each of them.
+pub(crate) fn build_batch_contexts(
+    offers: &[OfferDlc],
+    accept_params: &[DlcPartyParams],
+) -> Result<Vec<ContractContext>, ContractError> {
+    ensure_batch_offers(offers)?;
+    if offers.len() != accept_params.len() {
+        return Err(ContractError::InvalidAccept(
+            "expected accept parameters for each offer of the batch".to_string(),
+        ));
+    }
+    let mut contracts = Vec::with_capacity(offers.len());
+    let mut parts = Vec::with_capacity(offers.len());
+    for (offer, accept_params) in offers.iter().zip(accept_params) {
+        let (offer_params, execution_infos) =
+            offer_params_and_execution_infos(offer, accept_params)?;
+        contracts.push(BatchContractParams {
+            offer_params: offer_params.clone(),
+            accept_params: accept_params.clone(),
+            payouts: execution_infos[0].get_payouts(offer.get_total_collateral())?,
+            refund_lock_time: offer.refund_locktime,
+            cet_lock_time: offer.cet_locktime,
+            fund_output_serial_id: offer.fund_output_serial_id,
+            contract_flags: offer.contract_flags,
+        });
+        parts.push((offer_params, execution_infos));
+    }
+    let transactions =
+        ddk_dlc::batch::create_batch_dlc_transactions(&contracts, offers[0].fee_rate_per_vb, 0)?;
+
+    offers
+        .iter()
+        .zip(accept_params)
+        .zip(parts)
+        .zip(transactions)
+        .map(
+            |(((offer, accept_params), (offer_params, execution_infos)), transactions)| {
+                complete_context(
+                    offer,
+                    &offer_params,
+                    accept_params,
+                    execution_infos,
+                    transactions,
+                )
+            },
+        )
+        .collect()
+}
+
+/// The batch funding group of an offer, if it is batch funded.
+pub(crate) fn batch_funding_group(
+    offer: &OfferDlc,
+) -> Result<Option<BatchFundingGroup>, ContractError> {
+    offer
+        .tlvs
+        .get::<BatchFundingGroup>()
+        .map_err(|e| ContractError::InvalidOffer(format!("invalid batch funding group: {e}")))
+}
+
+/// Checks that offers are all the offers of one batch, carrying the same batch
+/// funding group and in its order, and that they can share a funding
+/// transaction.
+pub(crate) fn ensure_batch_offers(offers: &[OfferDlc]) -> Result<(), ContractError> {
+    let Some(first) = offers.first() else {
+        return Err(ContractError::InvalidOffer("empty batch".to_string()));
+    };
+    let ids = offers
+        .iter()
+        .map(|offer| offer.temporary_contract_id)
+        .collect::<Vec<_>>();
+    let mut sorted_ids = ids.clone();
+    sorted_ids.sort_unstable();
+    if sorted_ids.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(ContractError::InvalidOffer(
+            "temporary contract ids of the batch are not unique".to_string(),
+        ));
+    }
+    let group = batch_funding_group(first)?;
+    if group.as_ref().map(|group| &group.temporary_contract_ids) != Some(&ids) {
+        return Err(ContractError::InvalidOffer(
+            "offers do not match their batch funding group".to_string(),
+        ));
+    }
+    for offer in offers {
+        if batch_funding_group(offer)? != group {
+            return Err(ContractError::InvalidOffer(
+                "offers do not match their batch funding group".to_string(),
+            ));
+        }
+        if offer.is_taproot() {
+            return Err(ContractError::InvalidOffer(
+                "batch funded offers cannot be Taproot funded".to_string(),
+            ));
+        }
+        if offer.chain_hash != first.chain_hash
+            || offer.protocol_version != first.protocol_version
+            || offer.fee_rate_per_vb != first.fee_rate_per_vb
+        {
+            return Err(ContractError::InvalidOffer(
+                "offers of a batch must have the same chain, protocol version and fee rate"
+                    .to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the collateral and execution information of an offer and returns
+/// the offering party's parameters with the execution infos.
+fn offer_params_and_execution_infos(
+    offer: &OfferDlc,
+    accept_params: &DlcPartyParams,
+) -> Result<(DlcPartyParams, Vec<ExecutionContractInfo>), ContractError> {
+    let total_collateral = offer.get_total_collateral();
+    if offer.offer_collateral + accept_params.collateral != total_collateral {
+        return Err(ContractError::InvalidAccept(
+            "offer and accept collateral do not equal total collateral".to_string(),
+        ));
+    }
+    let offer_params = dlc_party_params(
+        offer.funding_pubkey,
+        offer.payout_spk.clone(),
+        offer.payout_serial_id,
+        offer.change_spk.clone(),
+        offer.change_serial_id,
+        offer.offer_collateral,
+        &offer.funding_inputs,
+    )?;
+    let execution_infos = ddk_manager::contract::execution_contract_infos(&offer.contract_info)?;
+    if execution_infos.is_empty() {
+        return Err(ContractError::InvalidOffer(
+            "contract does not contain execution information".to_string(),
+        ));
+    }
+    for info in &execution_infos {
+        info.validate()?;
+    }
+    Ok((offer_params, execution_infos))
+}
+
+/// Adds the CETs of the execution infos after the first one to the
+/// transactions built for the first, and rebuilds the Taproot data.
+fn complete_context(
+    offer: &OfferDlc,
+    offer_params: &DlcPartyParams,
+    accept_params: &DlcPartyParams,
+    execution_infos: Vec<ExecutionContractInfo>,
+    mut transactions: DlcTransactions,
+) -> Result<ContractContext, ContractError> {
+    let total_collateral = offer.get_total_collateral();
     let mut cet_ranges = Vec::with_capacity(execution_infos.len());
     cet_ranges.push(0..transactions.cets.len());
     let cet_input = transactions
@@ -142,14 +356,63 @@ pub(crate) fn build_context(
             accept_params.payout_serial_id,
             &info.get_payouts(total_collateral)?,
             0,
+            offer.contract_flags,
         ));
         cet_ranges.push(start..transactions.cets.len());
     }
 
+    let taproot = if offer.is_taproot() {
+        Some(taproot_context(
+            offer,
+            &accept_params.fund_pubkey,
+            &execution_infos,
+            &cet_ranges,
+        )?)
+    } else {
+        None
+    };
+
     Ok(ContractContext {
         execution_infos,
         cet_ranges,
         transactions,
+        taproot,
+    })
+}
+
+fn taproot_context(
+    offer: &OfferDlc,
+    accept_funding_pubkey: &PublicKey,
+    execution_infos: &[ExecutionContractInfo],
+    cet_ranges: &[std::ops::Range<usize>],
+) -> Result<TaprootContext, ContractError> {
+    let secp = Secp256k1::verification_only();
+    let total_collateral = offer.get_total_collateral();
+    let funding = ddk_manager::contract::taproot::get_funding(
+        &secp,
+        &offer.funding_pubkey,
+        accept_funding_pubkey,
+        offer.refund_locktime,
+    )?;
+    let mut adaptor_infos = Vec::with_capacity(execution_infos.len());
+    let mut adaptor_starts = Vec::with_capacity(execution_infos.len());
+    let mut adaptor_points = Vec::new();
+    for (info, range) in execution_infos.iter().zip(cet_ranges) {
+        adaptor_starts.push(adaptor_points.len());
+        let (adaptor_info, points) =
+            info.get_adaptor_points(&secp, total_collateral, adaptor_points.len())?;
+        adaptor_points.extend(
+            points
+                .into_iter()
+                .map(|(cet_index, point)| (range.start + cet_index, point)),
+        );
+        adaptor_infos.push(adaptor_info);
+    }
+    Ok(TaprootContext {
+        funding,
+        adaptor_infos,
+        adaptor_starts,
+        adaptor_points,
     })
 }
 
@@ -249,6 +512,27 @@ pub(crate) fn create_refund_signature(
     )?)
 }
 
+/// The Taproot records of an offer/accept pair.
+pub(crate) fn taproot_records<'a>(
+    offer: &'a OfferDlc,
+    accept: &'a AcceptDlc,
+) -> Result<(&'a TaprootOffer, &'a TaprootAccept), ContractError> {
+    let offer_record = offer.taproot.as_ref().ok_or_else(|| {
+        ContractError::InvalidOffer("offer is missing its Taproot nonces".to_string())
+    })?;
+    let accept_record = accept.taproot.as_ref().ok_or_else(|| {
+        ContractError::InvalidAccept("accept is missing its Taproot signatures".to_string())
+    })?;
+    Ok((offer_record, accept_record))
+}
+
+/// The Taproot record of a sign message.
+pub(crate) fn taproot_sign_record(sign: &SignDlc) -> Result<&TaprootSign, ContractError> {
+    sign.taproot.as_ref().ok_or_else(|| {
+        ContractError::InvalidSign("sign is missing its Taproot signatures".to_string())
+    })
+}
+
 /// Verifies the counterparty's refund and CET adaptor signatures.
 ///
 /// `error` attributes failures to the message that carried the signatures
@@ -401,7 +685,7 @@ pub(crate) fn ensure_protocol_version(
 /// and accept messages.
 pub(crate) fn ensure_sign_message(
     offer: &OfferDlc,
-    sign: &ddk_messages::SignDlc,
+    sign: &SignDlc,
     context: &ContractContext,
 ) -> Result<(), ContractError> {
     ensure_protocol_version(sign.protocol_version, ContractError::InvalidSign)?;
@@ -410,6 +694,12 @@ pub(crate) fn ensure_sign_message(
             "offer and sign protocol versions differ".to_string(),
         ));
     }
+    if offer.is_taproot() != sign.taproot.is_some() {
+        return Err(ContractError::InvalidSign(
+            "Taproot signatures must be present exactly when the offer is Taproot funded"
+                .to_string(),
+        ));
+    }
     let expected_contract_id =
         contract_id_from_transactions(&context.transactions, &offer.temporary_contract_id);
     if sign.contract_id != expected_contract_id {
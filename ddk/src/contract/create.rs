@@ -1,9 +1,11 @@
 //! Offer creation and validation.
 
-use ddk_dlc::secp256k1_zkp::Secp256k1;
+use ddk_dlc::secp256k1_zkp::{Secp256k1, SecretKey};
+use ddk_manager::contract::taproot::{self, OfferNonces};
+use ddk_messages::tlv_stream::TlvStream;
 use ddk_messages::OfferDlc;
 
-use super::context::{ensure_protocol_version, validate_offer_funding_inputs};
+use super::context::{ensure_funding_key, ensure_protocol_version, validate_offer_funding_inputs};
 use super::error::ContractError;
 use super::types::{random_serial_id, random_temporary_contract_id, CreateOfferParams};
 use super::PROTOCOL_VERSION;
@@ -14,7 +16,64 @@ use super::PROTOCOL_VERSION;
 /// funding *public* key, and funding inputs are signed later through the PSBT
 /// signing layer. Serial ids and the temporary contract id are randomly
 /// generated when omitted from `params`.
+///
+/// Taproot funded offers need the offering party's funding secret key to
+/// create their nonces and are created with [`create_taproot_offer`] instead.
 pub fn create_offer(params: CreateOfferParams) -> Result<OfferDlc, ContractError> {
+    if params.contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0 {
+        return Err(ContractError::InvalidOffer(
+            "Taproot funded offers must be created with create_taproot_offer".to_string(),
+        ));
+    }
+    build_offer(params)
+}
+
+/// Creates the offer message of a Taproot funded contract.
+///
+/// Behaves like [`create_offer`], but sets [`ddk_dlc::TAPROOT_FUNDING_FLAG`] so
+/// that the collateral is locked in a Taproot output keyed by the MuSig2
+/// aggregate of both funding keys, and attaches the offering party's public
+/// MuSig2 nonces. The nonces are drawn at random; their secret halves are
+/// returned as [`OfferNonces`], which the offering party keeps (they can be
+/// persisted with their `Writeable` encoding) and passes to
+/// [`sign_taproot_accept`](super::sign_taproot_accept). They sign a single
+/// accept message: answering a second one with the same nonces would leak the
+/// funding secret key, so it is refused.
+///
+/// Taproot funded contracts cannot spend or be spent by a splice input.
+pub fn create_taproot_offer(
+    mut params: CreateOfferParams,
+    funding_secret_key: &SecretKey,
+) -> Result<(OfferDlc, OfferNonces), ContractError> {
+    let secp = Secp256k1::new();
+    ensure_funding_key(
+        &secp,
+        funding_secret_key,
+        &params.party.funding_pubkey,
+        ContractError::InvalidOffer,
+    )?;
+    if params
+        .party
+        .funding_inputs
+        .iter()
+        .any(|input| input.dlc_input.is_some())
+    {
+        return Err(ContractError::InvalidFundingInput(
+            "DLC inputs (splicing) are not supported with Taproot funding".to_string(),
+        ));
+    }
+    params.contract_flags |= ddk_dlc::TAPROOT_FUNDING_FLAG;
+
+    let mut offer = build_offer(params)?;
+    let execution_infos = ddk_manager::contract::execution_contract_infos(&offer.contract_info)?;
+    let (_, adaptor_points) =
+        taproot::get_adaptor_points(&secp, &execution_infos, offer.get_total_collateral())?;
+    let (taproot_offer, nonces) = taproot::create_offer(funding_secret_key, adaptor_points.len());
+    offer.taproot = Some(taproot_offer);
+    Ok((offer, nonces))
+}
+
+pub(crate) fn build_offer(params: CreateOfferParams) -> Result<OfferDlc, ContractError> {
     let CreateOfferParams {
         chain_hash,
         temporary_contract_id,
@@ -65,6 +124,8 @@ pub fn create_offer(params: CreateOfferParams) -> Result<OfferDlc, ContractError
         fee_rate_per_vb,
         cet_locktime,
         refund_locktime,
+        taproot: None,
+        tlvs: TlvStream::default(),
     };
 
     if offer.offer_collateral > offer.get_total_collateral() {
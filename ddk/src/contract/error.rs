@@ -55,6 +55,10 @@ pub enum ContractError {
     /// A DLC transaction or signature operation failed.
     #[error("DLC error: {0}")]
     Dlc(String),
+    /// The offering party's Taproot nonces already signed an accept message.
+    /// Signing a second one with them would reveal the funding secret key.
+    #[error("the nonces of the Taproot offer were already used")]
+    NoncesAlreadyUsed,
     /// Contract funding-key derivation failed (bad mnemonic, missing private
     /// key, or an invalid derived key).
     #[error("contract key error: {0}")]
@@ -4,11 +4,12 @@ use bitcoin::psbt::Psbt;
 use bitcoin::Transaction;
 use ddk_dlc::dlc_input::DlcInputInfo;
 use ddk_dlc::secp256k1_zkp::{PublicKey, Secp256k1};
+use ddk_manager::contract::taproot;
 use ddk_messages::{AcceptDlc, FundingSignatures, OfferDlc, SignDlc};
 
 use super::context::{
     apply_funding_signatures, context_from_messages, ensure_sign_message, funding_input_index,
-    verify_counterparty_signatures, ContractContext,
+    taproot_records, taproot_sign_record, verify_counterparty_signatures, ContractContext,
 };
 use super::error::ContractError;
 use super::psbt::{ensure_psbt_matches_funding_transaction, extract_funding_signatures};
@@ -80,7 +81,7 @@ pub(crate) fn finalize_sign_internal(
     )
 }
 
-fn finalize_with_context(
+pub(crate) fn finalize_with_context(
     offer: &OfferDlc,
     accept: &AcceptDlc,
     sign: &SignDlc,
@@ -104,16 +105,32 @@ fn finalize_with_context(
         )));
     }
 
-    let secp = Secp256k1::new();
-    verify_counterparty_signatures(
-        &secp,
-        &context,
-        offer.get_total_collateral(),
-        offer.funding_pubkey,
-        &sign.refund_signature,
-        &sign.cet_adaptor_signatures,
-        ContractError::InvalidSign,
-    )?;
+    if let Some(taproot_context) = &context.taproot {
+        let (offer_record, accept_record) = taproot_records(offer, accept)?;
+        taproot::verify_sign(
+            &Secp256k1::new(),
+            &taproot_context.funding,
+            &context.transactions,
+            &taproot_context.adaptor_points,
+            &offer.funding_pubkey,
+            offer_record,
+            accept_record,
+            taproot_sign_record(sign)?,
+        )
+        .map_err(|e| {
+            ContractError::InvalidSign(format!("invalid Taproot partial signatures: {e}"))
+        })?;
+    } else {
+        verify_counterparty_signatures(
+            &Secp256k1::new(),
+            &context,
+            offer.get_total_collateral(),
+            offer.funding_pubkey,
+            &sign.refund_signature,
+            &sign.cet_adaptor_signatures,
+            ContractError::InvalidSign,
+        )?;
+    }
 
     // Splice inputs' 2-of-2 signatures are computed over the unsigned funding
     // transaction (the SegWit sighash does not commit to other inputs' witnesses),
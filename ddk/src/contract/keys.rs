@@ -16,6 +16,12 @@
 //! interchangeable between the stateful manager path and the stateless API.
 //! It implements [`ddk_manager::ContractSignerProvider`], so it can also drive a
 //! manager directly.
+//!
+//! DLC channels additionally need a stream of fresh keys (basepoints and per
+//! update seeds). [`ContractKeyProvider::channel_secret_key`] derives those from
+//! a counter on a separate path; keeping track of the counter and of which index
+//! backs which public key is left to the caller (the wallet does it through
+//! [`crate::Storage`] when the `manager` feature is enabled).
 
 use std::str::FromStr;
 
@@ -37,6 +43,12 @@ const CHILD_NUMBER_RANGE: u32 = 3_400;
 /// Base derivation path for contract keys.
 const DLC_BASE_PATH: &str = "m/420'/0'/0'";
 
+/// Base derivation path for channel keys. Each key is a hardened child of it.
+const CHANNEL_BASE_PATH: &str = "m/420'/0'/1'";
+
+/// Derivation path of the key encrypting static backups.
+const BACKUP_KEY_PATH: &str = "m/420'/0'/2'";
+
 /// Domain-separation tag for the keys-id hash. Must stay in lockstep with
 /// [`crate::wallet::DlcDevKitWallet`] or keys derived by one will not match the
 /// other.
@@ -57,6 +69,7 @@ pub struct ContractKeyProvider {
     fingerprint: Fingerprint,
     secp: Secp256k1<All>,
     dlc_path: DerivationPath,
+    channel_path: DerivationPath,
 }
 
 impl ContractKeyProvider {
@@ -65,11 +78,14 @@ impl ContractKeyProvider {
         let secp = Secp256k1::new();
         let fingerprint = xprv.fingerprint(&secp);
         let dlc_path = DerivationPath::from_str(DLC_BASE_PATH).expect("valid base path");
+        let channel_path =
+            DerivationPath::from_str(CHANNEL_BASE_PATH).expect("valid channel base path");
         Self {
             xprv,
             fingerprint,
             secp,
             dlc_path,
+            channel_path,
         }
     }
 
@@ -174,6 +190,34 @@ impl ContractKeyProvider {
         })
     }
 
+    /// The channel secret key at `index` (a basepoint or per update seed).
+    ///
+    /// Channel keys are hardened children of their own base path, so they never
+    /// collide with contract funding keys. Indexes must be below `2^31`.
+    pub fn channel_secret_key(&self, index: u32) -> Result<SecretKey, ContractError> {
+        let child = ChildNumber::from_hardened_idx(index)
+            .map_err(|e| ContractError::Key(format!("invalid channel key index: {e}")))?;
+        Ok(self
+            .xprv
+            .derive_priv(&self.secp, &self.channel_path.child(child))
+            .map_err(|e| ContractError::Bip32(e.to_string()))?
+            .private_key)
+    }
+
+    /// The symmetric key encrypting the static backups of the node.
+    ///
+    /// It is derived from its own hardened path, so a backup can be
+    /// decrypted with the seed alone and leaks no signing key.
+    pub fn backup_key(&self) -> Result<[u8; 32], ContractError> {
+        let path = DerivationPath::from_str(BACKUP_KEY_PATH).expect("valid backup key path");
+        let secret_key = self
+            .xprv
+            .derive_priv(&self.secp, &path)
+            .map_err(|e| ContractError::Bip32(e.to_string()))?
+            .private_key;
+        Ok(sha256::Hash::hash(&secret_key.secret_bytes()).to_byte_array())
+    }
+
     fn hierarchical_derivation_path(
         &self,
         level_1: u32,
@@ -237,15 +281,22 @@ impl ddk_manager::ContractSignerProvider for ContractKeyProvider {
         Ok(SimpleSigner::new(secret_key))
     }
 
+    /// Channel keys need an index from public key to derivation, which this
+    /// stateless provider does not keep. Use [`crate::wallet::DlcDevKitWallet`]
+    /// for channels.
     fn get_secret_key_for_pubkey(
         &self,
         _pubkey: &PublicKey,
     ) -> Result<SecretKey, ddk_manager::error::Error> {
-        unreachable!("get_secret_key_for_pubkey is only used for channels")
+        Err(ddk_manager::error::Error::InvalidState(
+            "ContractKeyProvider does not track channel keys".to_string(),
+        ))
     }
 
     fn get_new_secret_key(&self) -> Result<SecretKey, ddk_manager::error::Error> {
-        unreachable!("get_new_secret_key is only used for channels")
+        Err(ddk_manager::error::Error::InvalidState(
+            "ContractKeyProvider does not track channel keys".to_string(),
+        ))
     }
 }
 
@@ -385,6 +436,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn channel_keys_are_deterministic_and_separate_from_funding_keys() {
+        let keys = provider();
+        let first = keys.channel_secret_key(0).unwrap();
+        assert_eq!(first, provider().channel_secret_key(0).unwrap());
+        assert_ne!(first, keys.channel_secret_key(1).unwrap());
+        assert_ne!(first, keys.funding_secret_key(TEMP_A).unwrap());
+        assert!(keys.channel_secret_key(1 << 31).is_err());
+    }
+
     #[test]
     fn from_descriptor_requires_a_private_key() {
         // Watch-only descriptor (xpub) has no private key to derive from.
@@ -96,6 +96,46 @@
 //! [`finalize_sign_spliced`](crate::contract::finalize_sign_spliced) (accepting
 //! party) as [`DlcInputSigningKey`](crate::contract::DlcInputSigningKey) values.
 //!
+//! # Taproot funding
+//!
+//! An offer created with
+//! [`create_taproot_offer`](crate::contract::create_taproot_offer) sets
+//! [`ddk_dlc::TAPROOT_FUNDING_FLAG`] and locks the collateral in a Taproot
+//! output keyed by the MuSig2 aggregate of both funding keys, with a refund
+//! leaf in its script tree. The lifecycle is unchanged, but the CET adaptor
+//! signatures and refund signatures are replaced by MuSig2 nonces and partial
+//! signatures carried in a `taproot` record of each message. The offering
+//! party keeps the secret half of its random nonces as
+//! [`OfferNonces`](crate::contract::OfferNonces) and signs the accept message
+//! with [`sign_taproot_accept`](crate::contract::sign_taproot_accept), which
+//! uses them up: a second accept message for the same offer is refused.
+//! The accept and sign messages also carry each party's signature for the
+//! refund leaf, which
+//! [`sign_refund_leaf`](crate::contract::sign_refund_leaf) places on a refund
+//! spending the leaf instead of the key path.
+//! Taproot funded contracts do not support splicing.
+//!
+//! # Batch funding
+//!
+//! Several contracts between the same two parties can be funded by a single
+//! funding transaction with one 2-of-2 output per contract.
+//! [`create_batch_offer`](crate::contract::create_batch_offer) creates one
+//! offer per contract, all spending the same funding inputs and tied together
+//! by a `BatchFundingGroup` record. The record is DDK's own: other
+//! implementations are not known to fund a batch from it. The batch
+//! counterparts of the lifecycle functions
+//! ([`accept_batch_offer`](crate::contract::accept_batch_offer),
+//! [`sign_batch_accept`](crate::contract::sign_batch_accept),
+//! [`finalize_batch_sign`](crate::contract::finalize_batch_sign),
+//! [`sign_batch_cet`](crate::contract::sign_batch_cet) and
+//! [`sign_batch_refund`](crate::contract::sign_batch_refund)) take the
+//! messages of every contract of the batch, since the funding transaction
+//! depends on all of them. The funding PSBT built by
+//! [`create_batch_funding_psbt`](crate::contract::create_batch_funding_psbt)
+//! is signed through [`signing`](crate::contract::signing) with the messages of
+//! any contract of the batch. The other single-contract functions reject batch
+//! funded offers.
+//!
 //! # Settlement
 //!
 //! A funded contract ends in one of two transactions, both of which spend the
@@ -139,6 +179,7 @@ pub mod advanced;
 pub mod signing;
 
 mod accept;
+mod batch;
 mod context;
 mod create;
 mod error;
@@ -154,17 +195,23 @@ mod types;
 mod tests;
 
 pub use accept::{accept_offer, create_dlc_transactions};
-pub use create::{create_offer, validate_offer};
+pub use batch::{
+    accept_batch_offer, create_batch_dlc_transactions, create_batch_funding_psbt,
+    create_batch_offer, finalize_batch_sign, sign_batch_accept, sign_batch_cet, sign_batch_refund,
+};
+pub use create::{create_offer, create_taproot_offer, validate_offer};
+pub use ddk_manager::contract::taproot::OfferNonces;
 pub use error::ContractError;
 pub use finalize::{finalize_sign, finalize_sign_spliced};
 pub use keys::ContractKeyProvider;
 pub use psbt::create_funding_psbt;
-pub use settle::{sign_cet, sign_refund};
-pub use sign::{sign_accept, sign_accept_spliced};
+pub use settle::{sign_cet, sign_refund, sign_refund_leaf};
+pub use sign::{sign_accept, sign_accept_spliced, sign_taproot_accept};
 pub use splice::{create_dlc_splice_input, DLC_INPUT_MAX_WITNESS_LEN};
 pub use types::{
-    chain_hash_from_network, funding_input, AcceptOfferParams, AcceptResult, CreateOfferParams,
-    DescriptorInput, DlcInputSigningKey, InputDerivation, Party, PartyParams, SignResult,
+    chain_hash_from_network, funding_input, AcceptOfferParams, AcceptResult, BatchAcceptResult,
+    BatchSignResult, CreateOfferParams, DescriptorInput, DlcInputSigningKey, InputDerivation,
+    Party, PartyParams, SignResult,
 };
 
 /// The current DLC protocol version used by DDK.
@@ -8,11 +8,12 @@
 use bitcoin::psbt::Psbt;
 use bitcoin::script::PushBytesBuf;
 use bitcoin::sighash::EcdsaSighashType;
-use bitcoin::{ScriptBuf, Transaction, Witness};
+use bitcoin::{OutPoint, ScriptBuf, Transaction, Witness};
 use ddk_messages::{AcceptDlc, FundingSignature, FundingSignatures, OfferDlc, WitnessElement};
 
 use super::context::{
-    context_from_messages, decode_previous_transaction, funding_input_index, party_funding_inputs,
+    batch_funding_group, context_from_messages, decode_previous_transaction, funding_input_index,
+    party_funding_inputs,
 };
 use super::error::ContractError;
 use super::types::Party;
@@ -133,15 +134,59 @@ pub(crate) fn ensure_psbt_matches_funding_transaction(
 
 /// Rebuilds the funding transaction from the messages and verifies the PSBT
 /// against it.
+///
+/// The funding transaction of a batch depends on the messages of all its
+/// contracts, so for a batch funded offer the PSBT is only checked to spend the
+/// funding inputs of the messages. The batch lifecycle functions verify it
+/// fully.
 pub(crate) fn ensure_matching_psbt(
     offer: &OfferDlc,
     accept: &AcceptDlc,
     psbt: &Psbt,
 ) -> Result<(), ContractError> {
+    if batch_funding_group(offer)?.is_some() {
+        return ensure_psbt_spends_funding_inputs(offer, accept, psbt);
+    }
     let transactions = context_from_messages(offer, accept)?.transactions;
     ensure_psbt_matches_funding_transaction(psbt, &transactions.fund)
 }
 
+/// Verifies that a PSBT spends exactly the funding inputs of the messages, in
+/// ascending serial id order.
+fn ensure_psbt_spends_funding_inputs(
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+    psbt: &Psbt,
+) -> Result<(), ContractError> {
+    let mut funding_inputs = offer
+        .funding_inputs
+        .iter()
+        .chain(&accept.funding_inputs)
+        .collect::<Vec<_>>();
+    funding_inputs.sort_by_key(|input| input.input_serial_id);
+    let outpoints = funding_inputs
+        .into_iter()
+        .map(|input| {
+            Ok(OutPoint {
+                txid: decode_previous_transaction(input)?.compute_txid(),
+                vout: input.prev_tx_vout,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    let spent = psbt
+        .unsigned_tx
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<_>>();
+    if spent != outpoints {
+        return Err(ContractError::PsbtMismatch(
+            "PSBT does not spend the funding inputs of the offer and accept messages".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// Extracts one party's finalized funding witnesses from a PSBT.
 ///
 /// The PSBT must already be verified against the rebuilt funding transaction.
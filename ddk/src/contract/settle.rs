@@ -10,15 +10,26 @@
 //! Like the rest of the module, nothing is stored: the CET set, the refund
 //! transaction, and the adaptor information are all rebuilt from the offer and
 //! accept messages on demand.
+//!
+//! Taproot funded contracts spend their funding output through the key path
+//! instead: the MuSig2 partial signatures of both parties, exchanged in the
+//! accept and sign messages, are aggregated into a single Schnorr signature.
+//! Their refund can also spend the refund leaf of the funding output
+//! ([`sign_refund_leaf`]).
 
 use bitcoin::Transaction;
 use ddk_dlc::secp256k1_zkp::{
-    ecdsa::Signature, All, EcdsaAdaptorSignature, PublicKey, Secp256k1, SecretKey,
+    ecdsa::Signature, schnorr::Signature as SchnorrSignature, All, EcdsaAdaptorSignature,
+    PublicKey, Secp256k1, SecretKey,
 };
+use ddk_manager::contract::taproot;
 use ddk_messages::oracle_msgs::OracleAttestation;
 use ddk_messages::{AcceptDlc, OfferDlc, SignDlc};
 
-use super::context::{context_from_messages, ensure_sign_message};
+use super::context::{
+    context_from_messages, ensure_sign_message, taproot_records, taproot_sign_record,
+    ContractContext, TaprootContext,
+};
 use super::error::ContractError;
 use super::types::Party;
 
@@ -51,20 +62,52 @@ pub fn sign_cet(
     funding_secret_key: &SecretKey,
     attestations: &[(usize, OracleAttestation)],
 ) -> Result<Transaction, ContractError> {
-    let secp = Secp256k1::new();
     let context = context_from_messages(offer, accept)?;
-    ensure_sign_message(offer, sign, &context)?;
+    sign_cet_with_context(
+        offer,
+        accept,
+        sign,
+        &context,
+        funding_secret_key,
+        attestations,
+    )
+}
+
+/// Signs the CET matching a set of oracle attestations from the contract
+/// rebuilt from the messages.
+pub(crate) fn sign_cet_with_context(
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+    sign: &SignDlc,
+    context: &ContractContext,
+    funding_secret_key: &SecretKey,
+    attestations: &[(usize, OracleAttestation)],
+) -> Result<Transaction, ContractError> {
+    let secp = Secp256k1::new();
+    ensure_sign_message(offer, sign, context)?;
     let party = settling_party(&secp, offer, accept, funding_secret_key)?;
+    let outcomes: Vec<(usize, &Vec<String>)> = attestations
+        .iter()
+        .map(|(index, attestation)| (*index, &attestation.outcomes))
+        .collect();
+    if let Some(taproot_context) = &context.taproot {
+        return sign_taproot_cet(
+            &secp,
+            offer,
+            accept,
+            sign,
+            context,
+            taproot_context,
+            attestations,
+            &outcomes,
+        );
+    }
+
     let (counterparty_pubkey, adaptor_signatures) =
         counterparty_adaptor_signatures(offer, accept, sign, party);
-
     let total_collateral = offer.get_total_collateral();
     let funding_witness_script = &context.transactions.funding_witness_script;
     let fund_value = context.transactions.get_fund_output().value;
-    let outcomes: Vec<(usize, &Vec<String>)> = attestations
-        .iter()
-        .map(|(index, attestation)| (*index, &attestation.outcomes))
-        .collect();
 
     let mut signature_index = 0;
     for (info, cet_range) in context.execution_infos.iter().zip(&context.cet_ranges) {
@@ -97,26 +140,11 @@ pub fn sign_cet(
         // `cet_index` is relative to the CETs of this contract info; the
         // adaptor index already carries the running offset.
         let mut cet = context.transactions.cets[cet_range.start + range_info.cet_index].clone();
-        let oracle_signatures: Vec<Vec<_>> = attestations
-            .iter()
-            .filter_map(|(index, attestation)| {
-                let signature_info = signature_infos.iter().find(|info| info.0 == *index)?;
-                Some(
-                    attestation
-                        .signatures
-                        .iter()
-                        .take(signature_info.1)
-                        .cloned()
-                        .collect(),
-                )
-            })
-            .collect();
-
         ddk_dlc::sign_cet(
             &secp,
             &mut cet,
             &adaptor_signatures[range_info.adaptor_index],
-            &oracle_signatures,
+            &oracle_signatures(attestations, &signature_infos),
             funding_secret_key,
             &counterparty_pubkey,
             funding_witness_script,
@@ -128,6 +156,77 @@ pub fn sign_cet(
     Err(ContractError::NoMatchingOutcome)
 }
 
+/// Completes the CET of a Taproot funded contract matching a set of oracle
+/// attestations from the partial signatures of both parties.
+#[allow(clippy::too_many_arguments)]
+fn sign_taproot_cet(
+    secp: &Secp256k1<All>,
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+    sign: &SignDlc,
+    context: &ContractContext,
+    taproot_context: &TaprootContext,
+    attestations: &[(usize, OracleAttestation)],
+    outcomes: &[(usize, &Vec<String>)],
+) -> Result<Transaction, ContractError> {
+    let (offer_record, accept_record) = taproot_records(offer, accept)?;
+    let sign_record = taproot_sign_record(sign)?;
+
+    for (info_index, (info, cet_range)) in context
+        .execution_infos
+        .iter()
+        .zip(&context.cet_ranges)
+        .enumerate()
+    {
+        let Some((signature_infos, range_info)) = info.get_range_info_for_outcome(
+            &taproot_context.adaptor_infos[info_index],
+            outcomes,
+            taproot_context.adaptor_starts[info_index],
+        ) else {
+            continue;
+        };
+
+        validate_attestations(secp, &info.oracle_announcements, attestations)?;
+
+        let mut cet = context.transactions.cets[cet_range.start + range_info.cet_index].clone();
+        taproot::sign_cet(
+            &mut cet,
+            &taproot_context.funding,
+            &context.transactions,
+            range_info.adaptor_index,
+            &oracle_signatures(attestations, &signature_infos),
+            offer_record,
+            accept_record,
+            sign_record,
+        )?;
+        return Ok(cet);
+    }
+
+    Err(ContractError::NoMatchingOutcome)
+}
+
+/// Selects, for each oracle used by the matched CET, the attestation
+/// signatures its adaptor point was built from.
+fn oracle_signatures(
+    attestations: &[(usize, OracleAttestation)],
+    signature_infos: &[(usize, usize)],
+) -> Vec<Vec<SchnorrSignature>> {
+    attestations
+        .iter()
+        .filter_map(|(index, attestation)| {
+            let signature_info = signature_infos.iter().find(|info| info.0 == *index)?;
+            Some(
+                attestation
+                    .signatures
+                    .iter()
+                    .take(signature_info.1)
+                    .cloned()
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
 /// Signs the refund transaction.
 ///
 /// The refund returns each party its own collateral and can only be broadcast
@@ -137,17 +236,68 @@ pub fn sign_cet(
 /// Both parties signed the refund during the offer/accept exchange, so this
 /// only adds `funding_secret_key`'s half. As in [`sign_cet`], the key
 /// identifies the settling party. The counterparty's stored signature is
-/// verified before the two are combined.
+/// verified before the two are combined. A Taproot funded contract's refund is
+/// completed from both parties' partial signatures instead.
 pub fn sign_refund(
     offer: &OfferDlc,
     accept: &AcceptDlc,
     sign: &SignDlc,
     funding_secret_key: &SecretKey,
 ) -> Result<Transaction, ContractError> {
-    let secp = Secp256k1::new();
+    let context = context_from_messages(offer, accept)?;
+    sign_refund_with_context(offer, accept, sign, &context, funding_secret_key)
+}
+
+/// Returns the refund transaction of a Taproot funded contract spending the
+/// refund leaf of its funding output instead of the key path.
+///
+/// Both parties signed the leaf spend in the accept and sign messages, so no
+/// funding key is needed. The signatures are verified before they are placed
+/// on the witness, next to the leaf script and its control block. Like
+/// [`sign_refund`], the transaction is only valid once `refund_locktime` has
+/// passed.
+pub fn sign_refund_leaf(
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+    sign: &SignDlc,
+) -> Result<Transaction, ContractError> {
     let context = context_from_messages(offer, accept)?;
     ensure_sign_message(offer, sign, &context)?;
+    let taproot_context = context.taproot.as_ref().ok_or_else(|| {
+        ContractError::InvalidOffer("only Taproot funded contracts have a refund leaf".to_string())
+    })?;
+    let (_, accept_record) = taproot_records(offer, accept)?;
+    Ok(taproot::sign_refund_leaf(
+        &taproot_context.funding,
+        &context.transactions,
+        &offer.funding_pubkey,
+        &accept.funding_pubkey,
+        accept_record,
+        taproot_sign_record(sign)?,
+    )?)
+}
+
+/// Signs the refund transaction of the contract rebuilt from the messages.
+pub(crate) fn sign_refund_with_context(
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+    sign: &SignDlc,
+    context: &ContractContext,
+    funding_secret_key: &SecretKey,
+) -> Result<Transaction, ContractError> {
+    let secp = Secp256k1::new();
+    ensure_sign_message(offer, sign, context)?;
     let party = settling_party(&secp, offer, accept, funding_secret_key)?;
+    if let Some(taproot_context) = &context.taproot {
+        let (offer_record, accept_record) = taproot_records(offer, accept)?;
+        return Ok(taproot::sign_refund(
+            &taproot_context.funding,
+            &context.transactions,
+            offer_record,
+            accept_record,
+            taproot_sign_record(sign)?,
+        )?);
+    }
     let (counterparty_pubkey, counterparty_signature): (PublicKey, Signature) = match party {
         Party::Offer => (accept.funding_pubkey, accept.refund_signature),
         Party::Accept => (offer.funding_pubkey, sign.refund_signature),
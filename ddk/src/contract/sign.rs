@@ -4,11 +4,13 @@ use bitcoin::psbt::Psbt;
 use bitcoin::{Transaction, Witness};
 use ddk_dlc::dlc_input::DlcInputInfo;
 use ddk_dlc::secp256k1_zkp::{All, PublicKey, Secp256k1, SecretKey};
+use ddk_manager::contract::taproot::{self, OfferNonces};
+use ddk_messages::tlv_stream::TlvStream;
 use ddk_messages::{AcceptDlc, CetAdaptorSignatures, FundingSignatures, OfferDlc, SignDlc};
 
 use super::context::{
     context_from_messages, contract_id_from_transactions, create_adaptor_signatures,
-    create_refund_signature, ensure_funding_key, funding_input_index,
+    create_refund_signature, ensure_funding_key, funding_input_index, taproot_records,
     verify_counterparty_signatures, ContractContext,
 };
 use super::error::ContractError;
@@ -24,6 +26,8 @@ use super::types::{DlcInputSigningKey, SignResult};
 ///
 /// `funding_secret_key` is the offering party's DLC funding key, used to
 /// produce CET adaptor signatures and the refund signature.
+///
+/// Taproot funded offers are signed with [`sign_taproot_accept`] instead.
 pub fn sign_accept(
     offer: &OfferDlc,
     accept: &AcceptDlc,
@@ -33,6 +37,48 @@ pub fn sign_accept(
     sign_accept_spliced(offer, accept, funding_secret_key, signed_funding_psbt, &[])
 }
 
+/// Verifies the accept message of a Taproot funded offer and creates the
+/// offering party's sign message.
+///
+/// Behaves like [`sign_accept`], but produces MuSig2 partial signatures with
+/// the `nonces` returned by
+/// [`create_taproot_offer`](super::create_taproot_offer). The nonces are used
+/// up once the accept message is verified: signing a second accept message
+/// for the same offer fails with [`ContractError::NoncesAlreadyUsed`], as
+/// reusing them would reveal the funding secret key. Persist `nonces` after
+/// this call so that the offer stays answered across restarts.
+pub fn sign_taproot_accept(
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+    funding_secret_key: &SecretKey,
+    nonces: &mut OfferNonces,
+    signed_funding_psbt: &Psbt,
+) -> Result<SignResult, ContractError> {
+    if !offer.is_taproot() {
+        return Err(ContractError::InvalidOffer(
+            "offer is not Taproot funded".to_string(),
+        ));
+    }
+    let context = context_from_messages(offer, accept)?;
+    ensure_psbt_matches_funding_transaction(signed_funding_psbt, &context.transactions.fund)?;
+    let funding_signatures = build_offer_funding_signatures(
+        offer,
+        accept,
+        &context.transactions.fund,
+        signed_funding_psbt,
+        &[],
+        &Secp256k1::new(),
+    )?;
+    sign_with_context(
+        offer,
+        accept,
+        funding_secret_key,
+        Some(nonces),
+        funding_signatures,
+        context,
+    )
+}
+
 /// Verifies the accept message and creates the offering party's sign message,
 /// including any splice (DLC) funding inputs.
 ///
@@ -64,6 +110,7 @@ pub fn sign_accept_spliced(
         offer,
         accept,
         funding_secret_key,
+        None,
         funding_signatures,
         context,
     )
@@ -138,15 +185,19 @@ pub(crate) fn sign_accept_internal(
         offer,
         accept,
         funding_secret_key,
+        None,
         funding_signatures,
         context,
     )
 }
 
-fn sign_with_context(
+/// Creates the sign message. `nonces` are the offering party's secret Taproot
+/// nonces, required if and only if the offer is Taproot funded.
+pub(crate) fn sign_with_context(
     offer: &OfferDlc,
     accept: &AcceptDlc,
     funding_secret_key: &SecretKey,
+    nonces: Option<&mut OfferNonces>,
     funding_signatures: FundingSignatures,
     context: ContractContext,
 ) -> Result<SignResult, ContractError> {
@@ -164,22 +215,65 @@ fn sign_with_context(
         &offer.funding_pubkey,
         ContractError::InvalidOffer,
     )?;
-    verify_counterparty_signatures(
-        &secp,
-        &context,
-        offer.get_total_collateral(),
-        accept.funding_pubkey,
-        &accept.refund_signature,
-        &accept.cet_adaptor_signatures,
-        ContractError::InvalidAccept,
-    )?;
-    let adaptor_signatures = create_adaptor_signatures(
-        &secp,
-        &context,
-        funding_secret_key,
-        offer.get_total_collateral(),
-    )?;
-    let refund_signature = create_refund_signature(&secp, &context, funding_secret_key)?;
+    let (adaptor_signatures, refund_signature, taproot_sign) =
+        if let Some(taproot_context) = &context.taproot {
+            let nonces = nonces.ok_or_else(|| {
+                ContractError::InvalidOffer(
+                    "Taproot funded offers must be signed with sign_taproot_accept".to_string(),
+                )
+            })?;
+            if nonces.is_used() {
+                return Err(ContractError::NoncesAlreadyUsed);
+            }
+            let (offer_record, accept_record) = taproot_records(offer, accept)?;
+            taproot::verify_accept(
+                &secp,
+                &taproot_context.funding,
+                &context.transactions,
+                &taproot_context.adaptor_points,
+                &accept.funding_pubkey,
+                offer_record,
+                accept_record,
+            )
+            .map_err(|e| {
+                ContractError::InvalidAccept(format!("invalid Taproot partial signatures: {e}"))
+            })?;
+            let sign_record = taproot::create_sign(
+                &secp,
+                &taproot_context.funding,
+                &context.transactions,
+                &taproot_context.adaptor_points,
+                funding_secret_key,
+                nonces,
+                offer_record,
+                accept_record,
+            )?;
+            (
+                Vec::new(),
+                taproot::unused_refund_signature(),
+                Some(sign_record),
+            )
+        } else {
+            verify_counterparty_signatures(
+                &secp,
+                &context,
+                offer.get_total_collateral(),
+                accept.funding_pubkey,
+                &accept.refund_signature,
+                &accept.cet_adaptor_signatures,
+                ContractError::InvalidAccept,
+            )?;
+            (
+                create_adaptor_signatures(
+                    &secp,
+                    &context,
+                    funding_secret_key,
+                    offer.get_total_collateral(),
+                )?,
+                create_refund_signature(&secp, &context, funding_secret_key)?,
+                None,
+            )
+        };
 
     let sign = SignDlc {
         protocol_version: offer.protocol_version,
@@ -190,6 +284,8 @@ fn sign_with_context(
         cet_adaptor_signatures: CetAdaptorSignatures::from(adaptor_signatures.as_slice()),
         refund_signature,
         funding_signatures,
+        taproot: taproot_sign,
+        tlvs: TlvStream::default(),
     };
     Ok(SignResult {
         sign,
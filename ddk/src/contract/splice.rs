@@ -39,6 +39,9 @@ pub const DLC_INPUT_MAX_WITNESS_LEN: u16 = 220;
 /// prior contract's funding secret key (see
 /// [`sign_accept_spliced`](super::sign_accept_spliced) and
 /// [`finalize_sign_spliced`](super::finalize_sign_spliced)).
+///
+/// The funding output of a Taproot funded contract is not a 2-of-2 P2WSH
+/// output and cannot be spliced.
 pub fn create_dlc_splice_input(
     prev_offer: &OfferDlc,
     prev_accept: &AcceptDlc,
@@ -51,6 +54,11 @@ pub fn create_dlc_splice_input(
             "DLC input max witness length must be greater than 108".to_string(),
         ));
     }
+    if prev_offer.is_taproot() {
+        return Err(ContractError::InvalidFundingInput(
+            "Taproot funded contracts cannot be spliced".to_string(),
+        ));
+    }
     let transactions = create_dlc_transactions(prev_offer, prev_accept)?;
     let fund_vout = transactions.get_fund_output_index() as u32;
     let contract_id =
@@ -15,6 +15,7 @@ use ddk_messages::oracle_msgs::{
     tagged_announcement_msg, EnumEventDescriptor, EventDescriptor, OracleAnnouncement, OracleEvent,
     OracleInfo, SingleOracleInfo,
 };
+use ddk_messages::tlv_stream::TlvStream;
 use ddk_messages::{AcceptDlc, CetAdaptorSignatures, DlcInput, FundingInput, OfferDlc};
 
 use super::context::{ensure_no_dlc_inputs, funding_input_index, validate_offer_funding_inputs};
@@ -120,6 +121,8 @@ fn messages_with_serial_ids(offer_ids: &[u64], accept_ids: &[u64]) -> (OfferDlc,
         fee_rate_per_vb: 2,
         cet_locktime: 500,
         refund_locktime: 1_000,
+        taproot: None,
+        tlvs: TlvStream::default(),
     };
     let accept = AcceptDlc {
         protocol_version: PROTOCOL_VERSION,
@@ -137,6 +140,8 @@ fn messages_with_serial_ids(offer_ids: &[u64], accept_ids: &[u64]) -> (OfferDlc,
         cet_adaptor_signatures: CetAdaptorSignatures::from(&[][..]),
         refund_signature: signature,
         negotiation_fields: None,
+        taproot: None,
+        tlvs: TlvStream::default(),
     };
     (offer, accept)
 }
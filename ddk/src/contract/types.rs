@@ -63,7 +63,9 @@ pub struct CreateOfferParams {
     pub cet_locktime: u32,
     /// The time after which the refund transaction can be broadcast.
     pub refund_locktime: u32,
-    /// Contract feature flags. Use `0` unless a protocol extension requires otherwise.
+    /// Contract feature flags. Use `0` unless a protocol extension requires otherwise;
+    /// Taproot funded offers are created with
+    /// [`create_taproot_offer`](crate::contract::create_taproot_offer).
     pub contract_flags: u8,
 }
 
@@ -105,6 +107,27 @@ pub struct SignResult {
     pub transactions: DlcTransactions,
 }
 
+/// The result of [`accept_batch_offer`](super::accept_batch_offer).
+pub struct BatchAcceptResult {
+    /// The accept messages to send to the offering party, in the order of the
+    /// offers.
+    pub accepts: Vec<AcceptDlc>,
+    /// The unsigned funding, CET, and refund transactions of each contract.
+    /// The funding transaction is the same for all of them.
+    pub transactions: Vec<DlcTransactions>,
+    /// The funding PSBT ready to be signed by either party's funding source.
+    pub funding_psbt: Psbt,
+}
+
+/// The result of [`sign_batch_accept`](super::sign_batch_accept).
+pub struct BatchSignResult {
+    /// The sign messages to send to the accepting party, in the order of the
+    /// offers.
+    pub signs: Vec<SignDlc>,
+    /// The unsigned funding, CET, and refund transactions of each contract.
+    pub transactions: Vec<DlcTransactions>,
+}
+
 /// Identifies a funding input and the BIP32 path that derives its key.
 ///
 /// Inputs are identified by their funding input serial id, not by transaction
@@ -25,7 +25,8 @@
 //! - Bitcoin wallet operations
 //! - DLC contract management
 
-use crate::chain::{EsploraClient, ZeromqClient, ZeromqMessage};
+use crate::backup::StaticBackup;
+use crate::chain::{EsploraClient, FeeTarget, ZeromqClient, ZeromqMessage};
 use crate::error::Error;
 use crate::logger::Logger;
 use crate::logger::{log_debug, log_error, log_info, log_warn, WriteLog};
@@ -33,19 +34,30 @@ use crate::wallet::DlcDevKitWallet;
 use crate::{Oracle, Storage, Transport};
 use bitcoin::hex::DisplayHex;
 use bitcoin::secp256k1::PublicKey;
-use bitcoin::{Amount, Network, SignedAmount};
+use bitcoin::XOnlyPublicKey;
+use bitcoin::{Amount, FeeRate, Network, SignedAmount};
+use ddk_manager::attestation::OracleHealth;
 use ddk_manager::contract::Contract;
+use ddk_manager::equivocation::{AttestationSource, FraudProof};
 use ddk_manager::error::Error as ManagerError;
+use ddk_manager::events::Event;
+use ddk_manager::watchtower::EncryptedJusticeBlob;
 use ddk_manager::{
-    contract::contract_input::ContractInput, CachedContractSignerProvider, ContractId,
+    contract::contract_input::ContractInput, CachedContractSignerProvider, ChannelId, ContractId,
     SimpleSigner, SystemTimeProvider,
 };
-use ddk_messages::oracle_msgs::OracleAnnouncement;
-use ddk_messages::{AcceptDlc, Message, OfferDlc};
+use ddk_messages::channel::{
+    AcceptChannel, CollaborativeCloseOffer, OfferChannel, Reject, RenewAccept, RenewOffer,
+    SettleAccept, SettleOffer,
+};
+use ddk_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
+use ddk_messages::{AcceptDlc, CloseDlc, Message, OfferDlc};
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use tokio::runtime::Runtime;
 use tokio::select;
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 use tokio::sync::watch;
@@ -132,6 +144,8 @@ pub struct DlcDevKit<T: Transport, S: Storage, O: Oracle> {
     pub runtime: Arc<RwLock<Option<Runtime>>>,
     /// Bitcoin wallet instance
     pub wallet: Arc<DlcDevKitWallet>,
+    /// Esplora client for chain data and fee estimates
+    pub blockchain: Arc<EsploraClient>,
     /// DLC manager instance
     pub manager: Arc<DlcDevKitDlcManager<S, O>>,
     /// Channel for sending messages to the DLC manager
@@ -189,6 +203,7 @@ where
     /// - Updates UTXO set
     /// - Syncs with blockchain
     /// - Maintains wallet state
+    /// - Refreshes stale fee estimates
     ///
     /// 3. Contract Monitor Thread:
     /// - Runs every 30 seconds
@@ -226,6 +241,7 @@ where
 
         // Spawn wallet sync thread (60-second interval)
         let wallet_clone = self.wallet.clone();
+        let blockchain = self.blockchain.clone();
         let logger = self.logger.clone();
         let zmq = self.zmq_client.clone();
         runtime.spawn(async move {
@@ -239,6 +255,10 @@ where
                 if let Err(e) = wallet_clone.sync().await {
                     log_warn!(logger, "Did not sync wallet. error={}", e.to_string());
                 };
+
+                if blockchain.fee_estimates_stale() {
+                    let _ = blockchain.update_fee_estimates().await;
+                }
             }
         });
 
@@ -295,12 +315,69 @@ where
         self.network
     }
 
+    /// Subscribes to contract, channel and wallet events.
+    ///
+    /// Contract and channel events are emitted by the manager as their state
+    /// is stored. Wallet transactions are published after each wallet sync.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.manager.subscribe()
+    }
+
+    /// Returns the health of the oracles asked for attestations.
+    ///
+    /// Attestations are requested once an event matures, and failed requests
+    /// are retried with exponential backoff. Statistics start empty on every
+    /// start of the node.
+    pub async fn oracle_health(&self) -> HashMap<XOnlyPublicKey, OracleHealth> {
+        self.manager.oracle_health().await
+    }
+
+    /// Records an attestation seen outside of the oracle client, to check the
+    /// oracle did not attest to another outcome.
+    ///
+    /// The attestations forwarded by the oracle client (see
+    /// [`Oracle::forward_attestations`]) and attached to messages by peers are
+    /// recorded already.
+    ///
+    /// Returns the fraud proof if the oracle signed conflicting outcomes. The
+    /// proof is also published as [`Event::OracleEquivocation`] and the
+    /// contracts of the oracle are frozen.
+    pub async fn record_attestation(
+        &self,
+        attestation: OracleAttestation,
+        source: AttestationSource,
+    ) -> Result<Option<FraudProof>> {
+        Ok(self.manager.record_attestation(attestation, source).await?)
+    }
+
+    /// Returns the fraud proofs of the oracles caught attesting to conflicting
+    /// outcomes and not unfrozen since.
+    pub async fn fraud_proofs(&self) -> Vec<FraudProof> {
+        self.manager.fraud_proofs().await
+    }
+
+    /// Lets contracts of an oracle caught equivocating be closed again.
+    pub async fn unfreeze_oracle(&self, oracle: &XOnlyPublicKey) -> Result<Option<FraudProof>> {
+        Ok(self.manager.unfreeze_oracle(oracle).await?)
+    }
+
+    /// Returns the estimated fee rate for a target.
+    ///
+    /// Estimates come from Esplora's `/fee-estimates` endpoint and are cached.
+    /// If Esplora is unreachable, the last known or a default fee rate is used.
+    pub async fn fee_rate(&self, target: FeeTarget) -> FeeRate {
+        self.blockchain.estimate_fee_rate(target).await
+    }
+
     /// Creates and sends a new DLC offer to a counterparty.
     ///
     /// This method:
     /// 1. Creates a DLC offer message
     /// 2. Sends it through the transport layer
     /// 3. Returns the created offer for further processing
+    ///
+    /// If the `fee_rate` of the contract input is `0`, the estimated
+    /// [`FeeTarget::Funding`] fee rate is used.
     #[tracing::instrument(skip(self, contract_input))]
     pub async fn send_dlc_offer(
         &self,
@@ -308,6 +385,16 @@ where
         counter_party: PublicKey,
         oracle_announcements: Vec<OracleAnnouncement>,
     ) -> Result<OfferDlc> {
+        let mut contract_input = contract_input.to_owned();
+        if contract_input.fee_rate == 0 {
+            contract_input.fee_rate = self.fee_rate(FeeTarget::Funding).await.to_sat_per_vb_ceil();
+            log_debug!(
+                self.logger,
+                "Using estimated fee rate for offer. fee_rate_per_vb={}",
+                contract_input.fee_rate
+            );
+        }
+
         let (responder, receiver) = oneshot::channel();
         let event_ids = &oracle_announcements
             .iter()
@@ -317,7 +404,7 @@ where
 
         self.sender
             .send(DlcManagerMessage::OfferDlc {
-                contract_input: contract_input.to_owned(),
+                contract_input,
                 counter_party,
                 oracle_announcements,
                 responder,
@@ -388,6 +475,82 @@ where
         Ok((contract_id, counter_party, accept_dlc))
     }
 
+    /// Offers several DLC contracts funded by a single fund transaction, one
+    /// offer per contract input, and sends the offers to the counterparty.
+    ///
+    /// The contracts must share a fee rate, a fee rate of zero using the
+    /// estimated funding fee rate. `oracle_announcements` holds the
+    /// announcements of each contract.
+    #[tracing::instrument(skip(self, oracle_announcements))]
+    pub async fn send_batch_dlc_offer(
+        &self,
+        contract_inputs: &[ContractInput],
+        counter_party: PublicKey,
+        oracle_announcements: Vec<Vec<OracleAnnouncement>>,
+    ) -> Result<Vec<OfferDlc>> {
+        let mut contract_inputs = contract_inputs.to_vec();
+        let fee_rate = self.fee_rate(FeeTarget::Funding).await.to_sat_per_vb_ceil();
+        for contract_input in contract_inputs.iter_mut() {
+            if contract_input.fee_rate == 0 {
+                contract_input.fee_rate = fee_rate;
+            }
+        }
+
+        let offers = self
+            .manager
+            .send_batch_offer_with_announcements(
+                &contract_inputs,
+                counter_party,
+                oracle_announcements
+                    .into_iter()
+                    .map(|announcements| vec![announcements])
+                    .collect(),
+            )
+            .await?;
+
+        for offer in &offers {
+            self.transport
+                .send_message(counter_party, Message::Offer(offer.clone()))
+                .await;
+        }
+
+        log_info!(
+            self.logger,
+            "Sent batch DLC offer to counterparty. counterparty={} num_contracts={}",
+            counter_party.to_string(),
+            offers.len(),
+        );
+
+        Ok(offers)
+    }
+
+    /// Accepts the offers of a batch of DLC contracts funded by a single fund
+    /// transaction, given the temporary id of any of them, and sends the
+    /// accept messages to the counterparty.
+    #[tracing::instrument(skip(self))]
+    pub async fn accept_batch_dlc_offer(
+        &self,
+        contract: [u8; 32],
+    ) -> Result<(Vec<ContractId>, PublicKey, Vec<AcceptDlc>)> {
+        let (contract_ids, counter_party, accepts) =
+            self.manager.accept_batch_contract_offer(&contract).await?;
+
+        for accept in &accepts {
+            self.transport
+                .send_message(counter_party, Message::Accept(accept.clone()))
+                .await;
+        }
+
+        log_info!(
+            self.logger,
+            "Accepted and sent batch DLC accept. counter_party={} num_contracts={}",
+            counter_party.to_string(),
+            contract_ids.len(),
+        );
+
+        Ok((contract_ids, counter_party, accepts))
+    }
+
     /// Refunds a DLC contract.
     ///
     /// This method checks if the refund locktime has passed and broadcasts the refund transaction if it has.
@@ -400,6 +563,447 @@ where
         Ok(self.manager.check_and_broadcast_refund(contract_id).await?)
     }
 
+    /// Bumps the fee of the CET or refund transaction of a contract waiting in
+    /// the mempool with a child transaction funded by the wallet.
+    ///
+    /// The contract must be set up with [`ddk_dlc::ANCHOR_OUTPUTS_FLAG`].
+    /// Stuck closing transactions are also bumped on each periodic check, at
+    /// the urgent fee rate estimate.
+    #[tracing::instrument(skip(self))]
+    pub async fn bump_closing_fee(
+        &self,
+        contract_id: &ContractId,
+        fee_rate: FeeRate,
+    ) -> Result<bitcoin::Transaction> {
+        let child = self
+            .manager
+            .bump_closing_fee(contract_id, fee_rate.to_sat_per_vb_ceil())
+            .await?;
+        log_info!(
+            self.logger,
+            "Bumped the fee of a closing transaction. contract_id={} child_txid={} fee_rate={}",
+            contract_id.to_lower_hex_string(),
+            child.compute_txid(),
+            fee_rate.to_sat_per_vb_ceil()
+        );
+        Ok(child)
+    }
+
+    /// Asks the counterparty to replace the fund transaction of a signed
+    /// contract, stuck in the mempool, with one paying `fee_rate`.
+    ///
+    /// Both parties sign the transactions of a new contract spending the
+    /// replacing fund transaction, which the accept party broadcasts. The
+    /// replaced contract is deleted once one of the fund transactions confirms.
+    #[tracing::instrument(skip(self))]
+    pub async fn bump_funding_fee(
+        &self,
+        contract_id: &ContractId,
+        fee_rate: FeeRate,
+    ) -> Result<()> {
+        let (msg, counter_party) = self
+            .manager
+            .bump_funding_fee(contract_id, fee_rate.to_sat_per_vb_ceil())
+            .await?;
+        self.transport.send_message(counter_party, msg).await;
+        log_info!(
+            self.logger,
+            "Sent funding fee bump. contract_id={} fee_rate={}",
+            contract_id.to_lower_hex_string(),
+            fee_rate.to_sat_per_vb_ceil()
+        );
+        Ok(())
+    }
+
+    /// Offers to cooperatively close a confirmed contract, with the counterparty
+    /// getting `counter_payout`, and sends them the signed close message.
+    ///
+    /// The contract stays confirmed until the counterparty broadcasts the close
+    /// transaction.
+    #[tracing::instrument(skip(self))]
+    pub async fn cooperative_close_contract(
+        &self,
+        contract_id: &ContractId,
+        counter_payout: Amount,
+    ) -> Result<CloseDlc> {
+        let (close, counter_party) = self
+            .manager
+            .cooperative_close_contract(contract_id, counter_payout)
+            .await?;
+        self.transport
+            .send_message(counter_party, Message::Close(close.clone()))
+            .await;
+        log_info!(
+            self.logger,
+            "Sent cooperative close offer. contract_id={} counter_payout={}",
+            contract_id.to_lower_hex_string(),
+            counter_payout.to_sat()
+        );
+        Ok(close)
+    }
+
+    /// Accepts the cooperative close offer received for a contract and
+    /// broadcasts the close transaction.
+    #[tracing::instrument(skip(self))]
+    pub async fn accept_cooperative_close(&self, contract_id: &ContractId) -> Result<()> {
+        self.manager
+            .accept_received_cooperative_close(contract_id)
+            .await?;
+        log_info!(
+            self.logger,
+            "Accepted cooperative close. contract_id={}",
+            contract_id.to_lower_hex_string()
+        );
+        Ok(())
+    }
+
+    /// Closes a confirmed contract with attestations obtained out of band,
+    /// broadcasting the matching CET.
+    ///
+    /// Each attestation is matched to the contract's oracles by public key and
+    /// event id.
+    #[tracing::instrument(skip(self, attestations))]
+    pub async fn close_with_attestations(
+        &self,
+        contract_id: &ContractId,
+        attestations: Vec<OracleAttestation>,
+    ) -> Result<Contract> {
+        let Some(Contract::Confirmed(contract)) = self.storage.get_contract(contract_id).await?
+        else {
+            return Err(ManagerError::InvalidState(
+                "Only confirmed contracts can be closed with attestations.".to_string(),
+            )
+            .into());
+        };
+        let contract_infos = &contract.accepted_contract.offered_contract.contract_info;
+        let attestations = attestations
+            .into_iter()
+            .map(|attestation| {
+                contract_infos
+                    .iter()
+                    .find_map(|info| {
+                        info.oracle_announcements.iter().position(|announcement| {
+                            announcement.oracle_public_key == attestation.oracle_public_key
+                                && announcement.oracle_event.event_id == attestation.event_id
+                        })
+                    })
+                    .map(|index| (index, attestation))
+                    .ok_or_else(|| {
+                        ManagerError::InvalidParameters(
+                            "Attestation does not match an oracle of the contract.".to_string(),
+                        )
+                    })
+            })
+            .collect::<StdResult<Vec<_>, _>>()?;
+
+        let contract = self
+            .manager
+            .close_confirmed_contract(contract_id, attestations)
+            .await?;
+        log_info!(
+            self.logger,
+            "Closed contract with supplied attestations. contract_id={}",
+            contract_id.to_lower_hex_string()
+        );
+        Ok(contract)
+    }
+
+    /// Offers a DLC channel to a counterparty and sends them the offer.
+    ///
+    /// The oracle announcements of the contract are fetched by the manager. If
+    /// the `fee_rate` of the contract input is `0`, the estimated
+    /// [`FeeTarget::Funding`] fee rate is used.
+    #[tracing::instrument(skip(self, contract_input))]
+    pub async fn offer_channel(
+        &self,
+        contract_input: &ContractInput,
+        counter_party: PublicKey,
+    ) -> Result<OfferChannel> {
+        let mut contract_input = contract_input.to_owned();
+        if contract_input.fee_rate == 0 {
+            contract_input.fee_rate = self.fee_rate(FeeTarget::Funding).await.to_sat_per_vb_ceil();
+        }
+
+        let offer = self
+            .manager
+            .offer_channel(&contract_input, counter_party)
+            .await?;
+        self.transport
+            .send_message(counter_party, Message::OfferChannel(offer.clone()))
+            .await;
+
+        log_info!(
+            self.logger,
+            "Sent channel offer to counterparty. counterparty={} temp_channel_id={}",
+            counter_party.to_string(),
+            offer.temporary_channel_id.to_lower_hex_string()
+        );
+        Ok(offer)
+    }
+
+    /// Accepts a channel offer and sends the acceptance to the offering party.
+    ///
+    /// Returns the accept message along with the channel id and contract id
+    /// that replace the temporary ones of the offer.
+    #[tracing::instrument(skip(self))]
+    pub async fn accept_channel(
+        &self,
+        temporary_channel_id: &ChannelId,
+    ) -> Result<(AcceptChannel, ChannelId, ContractId, PublicKey)> {
+        let (accept, channel_id, contract_id, counter_party) =
+            self.manager.accept_channel(temporary_channel_id).await?;
+        self.transport
+            .send_message(counter_party, Message::AcceptChannel(accept.clone()))
+            .await;
+
+        log_info!(
+            self.logger,
+            "Accepted channel offer. counterparty={} channel_id={}",
+            counter_party.to_string(),
+            channel_id.to_lower_hex_string()
+        );
+        Ok((accept, channel_id, contract_id, counter_party))
+    }
+
+    /// Offers to settle a channel so that the counterparty gets `counter_payout`.
+    #[tracing::instrument(skip(self))]
+    pub async fn settle_offer(
+        &self,
+        channel_id: &ChannelId,
+        counter_payout: Amount,
+    ) -> Result<SettleOffer> {
+        let (offer, counter_party) = self
+            .manager
+            .settle_offer(channel_id, counter_payout)
+            .await?;
+        self.transport
+            .send_message(counter_party, Message::SettleOffer(offer.clone()))
+            .await;
+        log_info!(
+            self.logger,
+            "Sent settle offer. channel_id={} counter_payout={}",
+            channel_id.to_lower_hex_string(),
+            counter_payout.to_sat()
+        );
+        Ok(offer)
+    }
+
+    /// Accepts the settle offer received for a channel.
+    #[tracing::instrument(skip(self))]
+    pub async fn accept_settle_offer(&self, channel_id: &ChannelId) -> Result<SettleAccept> {
+        let (accept, counter_party) = self.manager.accept_settle_offer(channel_id).await?;
+        self.transport
+            .send_message(counter_party, Message::SettleAccept(accept.clone()))
+            .await;
+        log_info!(
+            self.logger,
+            "Accepted settle offer. channel_id={}",
+            channel_id.to_lower_hex_string()
+        );
+        Ok(accept)
+    }
+
+    /// Rejects the settle offer received for a channel.
+    #[tracing::instrument(skip(self))]
+    pub async fn reject_settle_offer(&self, channel_id: &ChannelId) -> Result<Reject> {
+        let (reject, counter_party) = self.manager.reject_settle_offer(channel_id).await?;
+        self.transport
+            .send_message(counter_party, Message::Reject(reject.clone()))
+            .await;
+        log_info!(
+            self.logger,
+            "Rejected settle offer. channel_id={}",
+            channel_id.to_lower_hex_string()
+        );
+        Ok(reject)
+    }
+
+    /// Offers a new contract in a channel, with the counterparty getting
+    /// `counter_payout` from the current one.
+    ///
+    /// If the `fee_rate` of the contract input is `0`, the estimated
+    /// [`FeeTarget::Funding`] fee rate is used.
+    #[tracing::instrument(skip(self, contract_input))]
+    pub async fn renew_offer(
+        &self,
+        channel_id: &ChannelId,
+        counter_payout: Amount,
+        contract_input: &ContractInput,
+    ) -> Result<RenewOffer> {
+        let mut contract_input = contract_input.to_owned();
+        if contract_input.fee_rate == 0 {
+            contract_input.fee_rate = self.fee_rate(FeeTarget::Funding).await.to_sat_per_vb_ceil();
+        }
+
+        let (offer, counter_party) = self
+            .manager
+            .renew_offer(channel_id, counter_payout, &contract_input)
+            .await?;
+        self.transport
+            .send_message(counter_party, Message::RenewOffer(offer.clone()))
+            .await;
+        log_info!(
+            self.logger,
+            "Sent renew offer. channel_id={} counter_payout={}",
+            channel_id.to_lower_hex_string(),
+            counter_payout.to_sat()
+        );
+        Ok(offer)
+    }
+
+    /// Accepts the renew offer received for a channel.
+    #[tracing::instrument(skip(self))]
+    pub async fn accept_renew_offer(&self, channel_id: &ChannelId) -> Result<RenewAccept> {
+        let (accept, counter_party) = self.manager.accept_renew_offer(channel_id).await?;
+        self.transport
+            .send_message(counter_party, Message::RenewAccept(accept.clone()))
+            .await;
+        log_info!(
+            self.logger,
+            "Accepted renew offer. channel_id={}",
+            channel_id.to_lower_hex_string()
+        );
+        Ok(accept)
+    }
+
+    /// Rejects the renew offer received for a channel.
+    #[tracing::instrument(skip(self))]
+    pub async fn reject_renew_offer(&self, channel_id: &ChannelId) -> Result<Reject> {
+        let (reject, counter_party) = self.manager.reject_renew_offer(channel_id).await?;
+        self.transport
+            .send_message(counter_party, Message::Reject(reject.clone()))
+            .await;
+        log_info!(
+            self.logger,
+            "Rejected renew offer. channel_id={}",
+            channel_id.to_lower_hex_string()
+        );
+        Ok(reject)
+    }
+
+    /// Offers to collaboratively close a channel, with the counterparty getting
+    /// `counter_payout`.
+    ///
+    /// The channel is force closed if the counterparty does not broadcast the
+    /// close transaction in time.
+    #[tracing::instrument(skip(self))]
+    pub async fn offer_collaborative_close(
+        &self,
+        channel_id: &ChannelId,
+        counter_payout: Amount,
+    ) -> Result<CollaborativeCloseOffer> {
+        let counter_party = self
+            .storage
+            .get_channel(channel_id)
+            .await?
+            .ok_or_else(|| ManagerError::InvalidParameters("Unknown channel.".to_string()))?
+            .get_counter_party_id();
+        let offer = self
+            .manager
+            .offer_collaborative_close(channel_id, counter_payout, Vec::new())
+            .await?;
+        self.transport
+            .send_message(
+                counter_party,
+                Message::CollaborativeCloseOffer(offer.clone()),
+            )
+            .await;
+        log_info!(
+            self.logger,
+            "Sent collaborative close offer. channel_id={} counter_payout={}",
+            channel_id.to_lower_hex_string(),
+            counter_payout.to_sat()
+        );
+        Ok(offer)
+    }
+
+    /// Accepts the collaborative close offer received for a channel and
+    /// broadcasts the close transaction.
+    #[tracing::instrument(skip(self))]
+    pub async fn accept_collaborative_close(&self, channel_id: &ChannelId) -> Result<()> {
+        self.manager.accept_collaborative_close(channel_id).await?;
+        log_info!(
+            self.logger,
+            "Accepted collaborative close. channel_id={}",
+            channel_id.to_lower_hex_string()
+        );
+        Ok(())
+    }
+
+    /// Unilaterally closes a channel by broadcasting its buffer transaction.
+    #[tracing::instrument(skip(self))]
+    pub async fn force_close_channel(&self, channel_id: &ChannelId) -> Result<()> {
+        self.manager.force_close_channel(channel_id).await?;
+        log_warn!(
+            self.logger,
+            "Force closing channel. channel_id={}",
+            channel_id.to_lower_hex_string()
+        );
+        Ok(())
+    }
+
+    /// Exports the justice data of the revoked states of every channel, sealed
+    /// for a watchtower. Export again after each channel update.
+    #[tracing::instrument(skip(self))]
+    pub async fn export_justice_blobs(&self) -> Result<Vec<EncryptedJusticeBlob>> {
+        let blobs = self.manager.export_justice_blobs().await?;
+        log_info!(
+            self.logger,
+            "Exported justice blobs for a watchtower. num_blobs={}",
+            blobs.len()
+        );
+        Ok(blobs)
+    }
+
+    /// Returns an encrypted [`StaticBackup`] of the open contracts and
+    /// channels. Set a [`crate::backup::BackupSink`] on the builder to write
+    /// one on every state change instead.
+    #[tracing::instrument(skip(self))]
+    pub async fn export_backup(&self) -> Result<Vec<u8>> {
+        let backup = StaticBackup::from_storage(&*self.storage, self.network).await?;
+        backup.encrypt(&self.wallet.backup_key()?)
+    }
+
+    /// Rebuilds the store from an encrypted backup written by a node with the
+    /// same seed, and resumes monitoring its contracts and channels.
+    ///
+    /// The blocks mined since the backup was written are scanned again, so
+    /// contracts that closed meanwhile are picked up, and contracts past
+    /// their refund locktime are refunded by the periodic check. The watched
+    /// transactions are handed to the manager and left out of the returned
+    /// backup.
+    #[tracing::instrument(skip(self, backup))]
+    pub async fn restore_from_backup(&self, backup: &[u8]) -> Result<StaticBackup> {
+        let mut backup = StaticBackup::decrypt(backup, &self.wallet.backup_key()?)?;
+        if backup.network != self.network {
+            return Err(Error::Backup(format!(
+                "Backup is for another network. backup_network={} network={}",
+                backup.network, self.network
+            )));
+        }
+
+        backup.restore_into(&*self.storage).await?;
+        self.wallet.restore_channel_keys(&backup.channel_keys)?;
+        if let Some(chain_monitor) = backup.chain_monitor.take() {
+            self.manager.restore_chain_monitor(chain_monitor).await?;
+        }
+        log_info!(
+            self.logger,
+            "Restored from backup. contracts={} channels={}",
+            backup.contracts.len(),
+            backup.channels.len()
+        );
+
+        if let Err(e) = self.manager.periodic_check(true).await {
+            log_warn!(
+                self.logger,
+                "Periodic check after restoring failed. error={}",
+                e
+            );
+        }
+        Ok(backup)
+    }
+
     /// Retrieves the current balance state, including:
     /// - Confirmed balance
     /// - Unconfirmed changes
@@ -14,6 +14,7 @@ use thiserror::Error;
 /// - DLC manager operations
 /// - Builder process
 /// - External services (Esplora)
+/// - Static backups
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("DDK runtime has already been initialized.")]
@@ -40,6 +41,8 @@ pub enum Error {
     Logger(#[from] LoggerError),
     #[error("EsploraError: {0}")]
     Esplora(#[from] bdk_esplora::esplora_client::Error),
+    #[error("BackupError: {0}")]
+    Backup(String),
     #[error("Generic error: {0}")]
     Generic(String),
     #[cfg(feature = "nostr")]
@@ -122,6 +125,8 @@ pub enum TransportError {
 pub enum NostrError {
     #[error("Nostr nip4: {0}")]
     Nip04(#[from] nostr_rs::nips::nip04::Error),
+    #[error("Nostr nip59: {0}")]
+    Nip59(#[from] nostr_rs::nips::nip59::Error),
     #[error("Message parsing error: {0}")]
     MessageParsing(String),
     #[error("Signing nostr event error: {0}")]
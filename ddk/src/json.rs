@@ -1,4 +1,4 @@
-//! JSON conversion utilities for DLC contracts
+//! JSON conversion utilities for DLC contracts and channels
 //!
 //! This module provides functionality to convert Rust-DLC contract types into JSON
 //! representations, primarily for console output and API responses. The conversion
@@ -17,6 +17,9 @@
 //! - Support custom serialization formats
 //! - Add schema validation for JSON output
 
+use ddk_manager::channel::{
+    offered_channel::OfferedChannel, signed_channel::SignedChannel, Channel,
+};
 use ddk_manager::contract::{
     accepted_contract::AcceptedContract, offered_contract::OfferedContract,
     signed_contract::SignedContract, ClosedContract, Contract, FailedAcceptContract,
@@ -162,8 +165,92 @@ pub fn contract_to_value(contract: &Contract) -> Value {
         Contract::Refunded(r) => signed_contract_to_value(r, "refunded"),
         Contract::Confirmed(c) => signed_contract_to_value(c, "confirmed"),
         Contract::Rejected(o) => offered_contract_to_value(o, "rejected"),
+        Contract::Expired(o) => offered_contract_to_value(o, "expired"),
         Contract::PreClosed(p) => preclosed_contract_to_value(p),
         Contract::FailedSign(f) => failed_sign_contract_to_value(f),
         Contract::FailedAccept(f) => failed_accept_contract_to_value(f),
     }
 }
+
+/// Converts an offered channel to a JSON value with the offered contract it
+/// would set up.
+fn offered_channel_to_value(offered: &OfferedChannel, state: &str) -> Value {
+    json!({
+        "state": state,
+        "channel_id": hex::encode(offered.temporary_channel_id),
+        "contract_id": hex::encode(offered.offered_contract_id),
+        "is_offer_party": offered.is_offer_party,
+        "counter_party": offered.counter_party.to_string(),
+    })
+}
+
+/// Converts a signed channel to a JSON value, including the state of the
+/// channel update in progress and the contract set up in the channel.
+fn signed_channel_to_value(signed: &SignedChannel) -> Value {
+    json!({
+        "state": "signed",
+        "signed_state": signed.state.to_string(),
+        "channel_id": hex::encode(signed.channel_id),
+        "temporary_channel_id": hex::encode(signed.temporary_channel_id),
+        "contract_id": signed.get_contract_id().map(hex::encode),
+        "counter_party": signed.counter_party.to_string(),
+        "update_idx": signed.update_idx,
+        "funding_txid": signed.fund_tx.compute_txid(),
+        "fee_rate_per_vb": signed.fee_rate_per_vb,
+    })
+}
+
+/// Main conversion function that handles all channel states.
+/// Routes to the appropriate conversion function based on the channel's state.
+pub fn channel_to_value(channel: &Channel) -> Value {
+    match channel {
+        Channel::Offered(o) => offered_channel_to_value(o, "offered"),
+        Channel::Cancelled(o) => offered_channel_to_value(o, "cancelled"),
+        Channel::Signed(s) => signed_channel_to_value(s),
+        Channel::Accepted(a) => json!({
+            "state": "accepted",
+            "channel_id": hex::encode(a.channel_id),
+            "temporary_channel_id": hex::encode(a.temporary_channel_id),
+            "contract_id": hex::encode(a.accepted_contract_id),
+            "counter_party": a.counter_party.to_string(),
+        }),
+        Channel::FailedAccept(f) => json!({
+            "state": "failed_accept",
+            "channel_id": hex::encode(f.temporary_channel_id),
+            "counter_party": f.counter_party.to_string(),
+            "error_message": f.error_message,
+        }),
+        Channel::FailedSign(f) => json!({
+            "state": "failed_sign",
+            "channel_id": hex::encode(f.channel_id),
+            "counter_party": f.counter_party.to_string(),
+            "error_message": f.error_message,
+        }),
+        Channel::Closing(c) => json!({
+            "state": "closing",
+            "channel_id": hex::encode(c.channel_id),
+            "contract_id": hex::encode(c.contract_id),
+            "counter_party": c.counter_party.to_string(),
+            "buffer_txid": c.buffer_transaction.compute_txid(),
+            "is_closer": c.is_closer,
+        }),
+        Channel::Closed(c) | Channel::CounterClosed(c) | Channel::CollaborativelyClosed(c) => {
+            let state = match channel {
+                Channel::Closed(_) => "closed",
+                Channel::CounterClosed(_) => "counter_closed",
+                _ => "collaboratively_closed",
+            };
+            json!({
+                "state": state,
+                "channel_id": hex::encode(c.channel_id),
+                "counter_party": c.counter_party.to_string(),
+            })
+        }
+        Channel::ClosedPunished(c) => json!({
+            "state": "closed_punished",
+            "channel_id": hex::encode(c.channel_id),
+            "counter_party": c.counter_party.to_string(),
+            "punish_txid": c.punish_txid,
+        }),
+    }
+}
@@ -8,6 +8,9 @@
 /// on nothing heavier than `ddk-manager` and is what FFI/mobile consumers bind.
 pub mod contract;
 
+/// Encrypted static backups of contracts and channels.
+#[cfg(feature = "manager")]
+pub mod backup;
 /// Build a DDK application.
 #[cfg(feature = "manager")]
 pub mod builder;
@@ -45,10 +48,15 @@ pub mod util;
 pub mod wallet;
 
 pub use ddk_manager;
+pub use ddk_messages;
+pub use lightning;
 
 /// DDK object with all services
 #[cfg(feature = "manager")]
 pub use ddk::DlcDevKit;
+/// DLC manager driven by [`DlcDevKit`], handed to [`Transport::start`].
+#[cfg(feature = "manager")]
+pub use ddk::DlcDevKitDlcManager;
 #[cfg(feature = "manager")]
 pub use ddk::DlcManagerMessage;
 
@@ -61,7 +69,7 @@ use bitcoin::secp256k1::{PublicKey, SecretKey};
 #[cfg(feature = "manager")]
 use bitcoin::Amount;
 #[cfg(feature = "manager")]
-use ddk::DlcDevKitDlcManager;
+use bitcoin::XOnlyPublicKey;
 #[cfg(feature = "manager")]
 use ddk_messages::Message;
 #[cfg(feature = "manager")]
@@ -170,6 +178,21 @@ pub trait Storage: ddk_manager::Storage + Send + Sync + std::fmt::Debug + 'stati
     /// - Updating UTXO set
     /// - Maintaining wallet metadata
     async fn persist_bdk(&self, changeset: &ChangeSet) -> Result<(), WalletError>;
+
+    /// Records how the secret key behind a channel public key is derived.
+    ///
+    /// The wallet hands out channel keys (basepoints, per update seeds, and
+    /// funding keys) and needs to find their secret keys again after a restart.
+    async fn persist_channel_key(
+        &self,
+        pubkey: &PublicKey,
+        derivation: &wallet::ChannelKeyDerivation,
+    ) -> Result<(), WalletError>;
+
+    /// Returns every recorded channel key derivation.
+    async fn get_channel_keys(
+        &self,
+    ) -> Result<Vec<(PublicKey, wallet::ChannelKeyDerivation)>, WalletError>;
 }
 
 /// Interface for secure key material storage and retrieval.
@@ -207,6 +230,28 @@ pub trait KeyStorage {
 pub trait Oracle: ddk_manager::Oracle + Send + Sync + 'static {
     /// Returns the name of this oracle implementation.
     fn name(&self) -> String;
+
+    /// Returns the oracles served by this client, each with the client the
+    /// manager should use for it.
+    ///
+    /// A client for a single oracle only serves itself. Clients aggregating
+    /// several oracles, like [`oracle::set::OracleSet`], return one client
+    /// routed to each oracle.
+    fn routes(self: Arc<Self>) -> Vec<(XOnlyPublicKey, Arc<Self>)>
+    where
+        Self: Sized,
+    {
+        vec![(self.get_public_key(), self)]
+    }
+
+    /// Sets where the client sends the attestations it sees on top of the one
+    /// it answers the manager with, e.g. from other transports or pushed by a
+    /// Nostr relay. [`DlcDevKit`] records them with
+    /// [`Manager::record_attestation`](ddk_manager::manager::Manager::record_attestation),
+    /// so that an oracle attesting to conflicting outcomes is caught.
+    ///
+    /// Clients that only answer the manager need not implement it.
+    fn forward_attestations(&self, _attestations: oracle::AttestationSender) {}
 }
 
 /// Represents the complete balance state of a DLC wallet.
@@ -0,0 +1,476 @@
+//! # Chunked DLC messages over Nostr
+//!
+//! Numeric offer and sign messages carry thousands of adaptor signatures and are larger than most
+//! relays accept in a single event. Like [`ddk_messages::segmentation`] does for Lightning, large
+//! messages are split into chunks that are sent as separate kind 8888 events and put back together
+//! by the recipient.
+//!
+//! Relays do not guarantee ordering or exactly-once delivery, so every chunk carries a `chunk` tag:
+//!
+//! ```text
+//! ["chunk", <message id>, <index>, <total>]
+//! ```
+//!
+//! The message id is the hex encoded SHA-256 of the complete serialized message. It groups the
+//! chunks of a message and is checked against the reassembled bytes, so a corrupted or mixed up
+//! chunk fails the message instead of producing garbage. Duplicate chunks are ignored, and
+//! messages that are still missing chunks after [`DEFAULT_CHUNK_TIMEOUT`] are dropped.
+//!
+//! Anyone can send chunks, so the chunks waiting for the rest of their message are bounded per
+//! sender and in total, see [`MAX_PENDING_MESSAGES_PER_SENDER`], [`MAX_PENDING_BYTES_PER_SENDER`],
+//! [`MAX_PENDING_MESSAGES`] and [`MAX_PENDING_BYTES`]. A chunk that would go over a limit is
+//! refused.
+//!
+//! For gift wrapped messages the tag lives in the encrypted rumor, so relays cannot link the
+//! chunks of a message together.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use bitcoin::hashes::{sha256, Hash};
+use nostr_rs::{PublicKey, Tag, TagKind, Tags};
+
+use crate::error::NostrError;
+
+/// Name of the tag carrying the chunk metadata.
+pub const CHUNK_TAG: &str = "chunk";
+
+/// Maximum number of serialized message bytes sent in one event.
+///
+/// NIP-44 limits plaintexts to 65535 bytes. After base64 encoding, sealing and gift wrapping, a
+/// 16 KiB chunk stays below that and below the event size limit of common relays.
+pub const MAX_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Maximum number of chunks a message can be split into.
+pub const MAX_CHUNKS: u16 = 128;
+
+/// How long to wait for the missing chunks of a message.
+pub const DEFAULT_CHUNK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Maximum number of incomplete messages kept for one sender.
+pub const MAX_PENDING_MESSAGES_PER_SENDER: usize = 4;
+
+/// Maximum number of bytes kept for the incomplete messages of one sender, enough for one message
+/// of [`MAX_CHUNKS`] chunks.
+pub const MAX_PENDING_BYTES_PER_SENDER: usize = MAX_CHUNK_SIZE * MAX_CHUNKS as usize;
+
+/// Maximum number of incomplete messages kept across all senders.
+pub const MAX_PENDING_MESSAGES: usize = 256;
+
+/// Maximum number of bytes kept for incomplete messages across all senders.
+pub const MAX_PENDING_BYTES: usize = 16 * MAX_PENDING_BYTES_PER_SENDER;
+
+/// A piece of a serialized DLC message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// SHA-256 of the complete serialized message.
+    pub message_id: sha256::Hash,
+    /// Position of the chunk, starting at zero.
+    pub index: u16,
+    /// Number of chunks the message was split into.
+    pub total: u16,
+    /// The bytes of this chunk.
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    /// The `chunk` tag describing this chunk.
+    pub fn tag(&self) -> Tag {
+        Tag::custom(
+            TagKind::custom(CHUNK_TAG),
+            [
+                self.message_id.to_string(),
+                self.index.to_string(),
+                self.total.to_string(),
+            ],
+        )
+    }
+
+    /// Reads the chunk metadata from the tags of an event.
+    ///
+    /// # Returns
+    /// * `Ok(None)` - If the event is not a chunk
+    /// * `Ok(Some(Chunk))` - The chunk, holding a copy of `data`
+    /// * `Err(NostrError)` - If the `chunk` tag is malformed
+    pub fn from_tags(tags: &Tags, data: &[u8]) -> Result<Option<Chunk>, NostrError> {
+        let Some(tag) = tags.find(TagKind::custom(CHUNK_TAG)) else {
+            return Ok(None);
+        };
+        let malformed = || NostrError::MessageParsing("Malformed chunk tag.".to_string());
+        let [_, message_id, index, total] = tag.as_slice() else {
+            return Err(malformed());
+        };
+        let chunk = Chunk {
+            message_id: message_id.parse().map_err(|_| malformed())?,
+            index: index.parse().map_err(|_| malformed())?,
+            total: total.parse().map_err(|_| malformed())?,
+            data: data.to_vec(),
+        };
+        if chunk.total == 0 || chunk.total > MAX_CHUNKS || chunk.index >= chunk.total {
+            return Err(malformed());
+        }
+        if chunk.data.len() > MAX_CHUNK_SIZE {
+            return Err(NostrError::MessageParsing(format!(
+                "Chunk is larger than {MAX_CHUNK_SIZE} bytes."
+            )));
+        }
+        Ok(Some(chunk))
+    }
+}
+
+/// Splits a serialized message into chunks of at most [`MAX_CHUNK_SIZE`] bytes.
+///
+/// # Errors
+/// * `NostrError::Generic` - If the message needs more than [`MAX_CHUNKS`] chunks
+pub fn split(bytes: &[u8]) -> Result<Vec<Chunk>, NostrError> {
+    let message_id = sha256::Hash::hash(bytes);
+    let total = bytes.len().div_ceil(MAX_CHUNK_SIZE);
+    let total = u16::try_from(total)
+        .ok()
+        .filter(|total| *total <= MAX_CHUNKS)
+        .ok_or_else(|| {
+            NostrError::Generic(format!(
+                "Message of {} bytes is too large to send over nostr.",
+                bytes.len()
+            ))
+        })?;
+
+    Ok(bytes
+        .chunks(MAX_CHUNK_SIZE)
+        .enumerate()
+        .map(|(index, data)| Chunk {
+            message_id,
+            index: index as u16,
+            total,
+            data: data.to_vec(),
+        })
+        .collect())
+}
+
+/// A message that is still missing chunks.
+#[derive(Debug)]
+struct PartialMessage {
+    chunks: Vec<Option<Vec<u8>>>,
+    received: u16,
+    bytes: usize,
+    first_seen: Instant,
+}
+
+/// A message that expired before all of its chunks arrived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiredMessage {
+    /// The sender of the message.
+    pub sender: PublicKey,
+    /// SHA-256 of the complete serialized message.
+    pub message_id: sha256::Hash,
+    /// Indexes of the chunks that never arrived.
+    pub missing: Vec<u16>,
+}
+
+/// Collects chunks until a message is complete.
+#[derive(Debug)]
+pub struct ChunkReassembler {
+    timeout: Duration,
+    partial: HashMap<(PublicKey, sha256::Hash), PartialMessage>,
+    /// Recently completed messages, so late duplicates don't start a new message.
+    completed: HashMap<(PublicKey, sha256::Hash), Instant>,
+}
+
+impl Default for ChunkReassembler {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHUNK_TIMEOUT)
+    }
+}
+
+impl ChunkReassembler {
+    /// Creates a reassembler that drops incomplete messages after `timeout`.
+    pub fn new(timeout: Duration) -> Self {
+        ChunkReassembler {
+            timeout,
+            partial: HashMap::new(),
+            completed: HashMap::new(),
+        }
+    }
+
+    /// Adds a chunk received from `sender`.
+    ///
+    /// Messages that expired are dropped first when the chunk would go over a limit on the
+    /// incomplete messages kept. A chunk that still goes over a limit is refused, and if it belongs
+    /// to a message that already started, the message is dropped as it can no longer complete.
+    ///
+    /// # Returns
+    /// * `Ok(Some(bytes))` - The serialized message, once its last chunk arrived
+    /// * `Ok(None)` - If chunks are still missing, or the chunk was a duplicate
+    /// * `Err(NostrError)` - If the chunk doesn't fit the message, goes over a limit on the
+    ///   incomplete messages kept, or the reassembled message doesn't match its id
+    pub fn add(
+        &mut self,
+        sender: PublicKey,
+        chunk: Chunk,
+        now: Instant,
+    ) -> Result<Option<Vec<u8>>, NostrError> {
+        if chunk.index >= chunk.total {
+            return Err(NostrError::MessageParsing(format!(
+                "Chunk {} of message {} is out of range.",
+                chunk.index, chunk.message_id
+            )));
+        }
+        let key = (sender, chunk.message_id);
+        if self.completed.contains_key(&key) {
+            return Ok(None);
+        }
+
+        if let Some(partial) = self.partial.get(&key) {
+            if partial.chunks.len() != chunk.total as usize {
+                return Err(NostrError::MessageParsing(format!(
+                    "Chunk {} of message {} has an inconsistent chunk count.",
+                    chunk.index, chunk.message_id
+                )));
+            }
+            if partial.chunks[chunk.index as usize].is_some() {
+                return Ok(None);
+            }
+        }
+
+        if self.check_limits(&key, chunk.data.len()).is_err() {
+            self.expire(now);
+            if let Err(e) = self.check_limits(&key, chunk.data.len()) {
+                self.partial.remove(&key);
+                return Err(e);
+            }
+        }
+
+        let partial = self.partial.entry(key).or_insert_with(|| PartialMessage {
+            chunks: vec![None; chunk.total as usize],
+            received: 0,
+            bytes: 0,
+            first_seen: now,
+        });
+        partial.bytes += chunk.data.len();
+        partial.chunks[chunk.index as usize] = Some(chunk.data);
+        partial.received += 1;
+        if (partial.received as usize) < partial.chunks.len() {
+            return Ok(None);
+        }
+
+        let partial = self.partial.remove(&key).expect("partial message exists");
+        self.completed.insert(key, now);
+        let bytes = partial
+            .chunks
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect::<Vec<_>>();
+        if sha256::Hash::hash(&bytes) != chunk.message_id {
+            return Err(NostrError::MessageParsing(format!(
+                "Reassembled message does not match its id {}.",
+                chunk.message_id
+            )));
+        }
+        Ok(Some(bytes))
+    }
+
+    /// Fails if adding `len` bytes to the message `key` goes over a limit on the incomplete
+    /// messages kept.
+    fn check_limits(&self, key: &(PublicKey, sha256::Hash), len: usize) -> Result<(), NostrError> {
+        let sender = key.0;
+        let new_message = !self.partial.contains_key(key);
+        let (mut sender_messages, mut sender_bytes, mut total_bytes) = (0, len, len);
+        for ((from, _), partial) in &self.partial {
+            total_bytes += partial.bytes;
+            if *from == sender {
+                sender_messages += 1;
+                sender_bytes += partial.bytes;
+            }
+        }
+
+        let limit = if new_message && sender_messages >= MAX_PENDING_MESSAGES_PER_SENDER {
+            "incomplete messages from the sender"
+        } else if new_message && self.partial.len() >= MAX_PENDING_MESSAGES {
+            "incomplete messages"
+        } else if sender_bytes > MAX_PENDING_BYTES_PER_SENDER {
+            "bytes of incomplete messages from the sender"
+        } else if total_bytes > MAX_PENDING_BYTES {
+            "bytes of incomplete messages"
+        } else {
+            return Ok(());
+        };
+        Err(NostrError::MessageParsing(format!(
+            "Refusing a chunk of message {} from {}: too many {limit}.",
+            key.1, sender
+        )))
+    }
+
+    /// Drops the messages that are still missing chunks after the timeout.
+    pub fn expire(&mut self, now: Instant) -> Vec<ExpiredMessage> {
+        let timeout = self.timeout;
+        self.completed
+            .retain(|_, completed_at| now.duration_since(*completed_at) < timeout);
+
+        let expired = self
+            .partial
+            .iter()
+            .filter(|(_, partial)| now.duration_since(partial.first_seen) >= timeout)
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+
+        expired
+            .into_iter()
+            .map(|key| {
+                let partial = self.partial.remove(&key).expect("partial message exists");
+                ExpiredMessage {
+                    sender: key.0,
+                    message_id: key.1,
+                    missing: partial
+                        .chunks
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, chunk)| chunk.is_none())
+                        .map(|(index, _)| index as u16)
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_rs::Keys;
+
+    fn message(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    #[test]
+    fn split_and_reassemble_out_of_order_with_duplicates() {
+        let bytes = message(MAX_CHUNK_SIZE * 2 + 100);
+        let chunks = split(&bytes).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].data.len(), 100);
+
+        let sender = Keys::generate().public_key();
+        let mut reassembler = ChunkReassembler::default();
+        let now = Instant::now();
+        for index in [2, 0, 2] {
+            let chunk = chunks[index].clone();
+            assert_eq!(reassembler.add(sender, chunk, now).unwrap(), None);
+        }
+        let reassembled = reassembler.add(sender, chunks[1].clone(), now).unwrap();
+        assert_eq!(reassembled, Some(bytes));
+
+        // A late duplicate does not start the message over.
+        assert_eq!(
+            reassembler.add(sender, chunks[0].clone(), now).unwrap(),
+            None
+        );
+        assert!(reassembler.expire(now + DEFAULT_CHUNK_TIMEOUT).is_empty());
+    }
+
+    #[test]
+    fn tag_round_trip() {
+        let chunk = split(&message(10)).unwrap().remove(0);
+        let tags = Tags::from_list(vec![chunk.tag()]);
+        let parsed = Chunk::from_tags(&tags, &chunk.data).unwrap();
+        assert_eq!(parsed, Some(chunk));
+        assert_eq!(Chunk::from_tags(&Tags::new(), &[]).unwrap(), None);
+    }
+
+    #[test]
+    fn corrupted_chunk_fails_integrity_check() {
+        let mut chunks = split(&message(MAX_CHUNK_SIZE + 1)).unwrap();
+        chunks[1].data[0] ^= 1;
+
+        let sender = Keys::generate().public_key();
+        let mut reassembler = ChunkReassembler::default();
+        let now = Instant::now();
+        assert_eq!(
+            reassembler.add(sender, chunks[0].clone(), now).unwrap(),
+            None
+        );
+        assert!(reassembler.add(sender, chunks[1].clone(), now).is_err());
+    }
+
+    #[test]
+    fn incomplete_message_expires() {
+        let chunks = split(&message(MAX_CHUNK_SIZE * 3)).unwrap();
+        let sender = Keys::generate().public_key();
+        let mut reassembler = ChunkReassembler::new(Duration::from_secs(10));
+        let now = Instant::now();
+        reassembler.add(sender, chunks[1].clone(), now).unwrap();
+
+        assert!(reassembler.expire(now + Duration::from_secs(5)).is_empty());
+        let expired = reassembler.expire(now + Duration::from_secs(10));
+        assert_eq!(
+            expired,
+            vec![ExpiredMessage {
+                sender,
+                message_id: chunks[0].message_id,
+                missing: vec![0, 2],
+            }]
+        );
+    }
+
+    #[test]
+    fn pending_messages_are_bounded_per_sender() {
+        let sender = Keys::generate().public_key();
+        let other = Keys::generate().public_key();
+        let mut reassembler = ChunkReassembler::default();
+        let now = Instant::now();
+        let first_chunks = |len| split(&message(len)).unwrap().remove(0);
+
+        for len in 0..MAX_PENDING_MESSAGES_PER_SENDER {
+            let chunk = first_chunks(MAX_CHUNK_SIZE + len + 1);
+            assert_eq!(reassembler.add(sender, chunk, now).unwrap(), None);
+        }
+        let chunk = first_chunks(MAX_CHUNK_SIZE * 2);
+        assert!(reassembler.add(sender, chunk.clone(), now).is_err());
+        // Other senders are not affected.
+        assert_eq!(reassembler.add(other, chunk.clone(), now).unwrap(), None);
+
+        // Expired messages make room for new ones.
+        let later = now + DEFAULT_CHUNK_TIMEOUT;
+        assert_eq!(reassembler.add(sender, chunk, later).unwrap(), None);
+    }
+
+    #[test]
+    fn pending_bytes_are_bounded() {
+        let chunks = split(&message(MAX_CHUNK_SIZE * MAX_CHUNKS as usize)).unwrap();
+        let (last, chunks) = chunks.split_last().unwrap();
+        let sender = Keys::generate().public_key();
+        let mut reassembler = ChunkReassembler::default();
+        let now = Instant::now();
+        for chunk in chunks {
+            assert_eq!(reassembler.add(sender, chunk.clone(), now).unwrap(), None);
+        }
+        // A second message takes the room the first one needs to complete, so it is dropped.
+        let other_message = split(&message(MAX_CHUNK_SIZE + 1)).unwrap().remove(0);
+        assert_eq!(reassembler.add(sender, other_message, now).unwrap(), None);
+        assert!(reassembler.add(sender, last.clone(), now).is_err());
+        let expired = reassembler.expire(now + DEFAULT_CHUNK_TIMEOUT);
+        assert_eq!(expired.len(), 1);
+        assert_ne!(expired[0].message_id, last.message_id);
+
+        // The bytes kept across senders are bounded too.
+        let mut reassembler = ChunkReassembler::default();
+        for _ in 0..MAX_PENDING_BYTES / MAX_PENDING_BYTES_PER_SENDER {
+            let sender = Keys::generate().public_key();
+            for chunk in chunks {
+                reassembler.add(sender, chunk.clone(), now).unwrap();
+            }
+        }
+        let sender = Keys::generate().public_key();
+        let room = MAX_PENDING_BYTES / MAX_CHUNK_SIZE - reassembler.partial.len() * chunks.len();
+        for chunk in &chunks[..room] {
+            assert_eq!(reassembler.add(sender, chunk.clone(), now).unwrap(), None);
+        }
+        assert!(reassembler.add(sender, chunks[room].clone(), now).is_err());
+    }
+
+    #[test]
+    fn rejects_messages_with_too_many_chunks() {
+        assert!(split(&vec![0; MAX_CHUNK_SIZE * MAX_CHUNKS as usize]).is_ok());
+        assert!(split(&vec![0; MAX_CHUNK_SIZE * MAX_CHUNKS as usize + 1]).is_err());
+    }
+}
@@ -3,14 +3,26 @@
 //! This module implements Discreet Log Contract (DLC) message handling over the Nostr protocol,
 //! following the draft NIP-88 specification for DLCs over Nostr.
 //!
-//! The implementation uses NIP-04 encryption for secure message transmission and handles
-//! DLC protocol messages (kind 8888), oracle announcements (kind 88), and oracle attestations (kind 89).
+//! The implementation handles DLC protocol messages (kind 8888), oracle announcements (kind 88),
+//! and oracle attestations (kind 89). DLC messages are sent in one of two [`MessageEncryption`]
+//! modes:
+//!
+//! - **NIP-04**: a kind 8888 event encrypted with NIP-04. Relays see the sender, the recipient
+//!   and the time of every message.
+//! - **Gift wrap**: a kind 8888 rumor sealed with NIP-44 v2 and gift wrapped as described in
+//!   NIP-59. Relays only see a kind 1059 event from a one-time key, the recipient and a
+//!   randomized timestamp.
+//!
+//! Both modes are always accepted when receiving.
+//!
+//! Messages too large for a single event are split into several events, see [`super::chunking`].
 //!
 //! **NIP-88 Reference**: https://github.com/nostr-protocol/nips/pull/919
 //!
 //! Note: NIP-88 is still a draft specification and subject to change.
 
 use crate::error::NostrError;
+use crate::nostr::chunking::{self, Chunk, ChunkReassembler, MAX_CHUNK_SIZE};
 use crate::nostr::nostr_to_bitcoin_pubkey;
 use crate::nostr::{DLC_MESSAGE_KIND, ORACLE_ANNOUNCMENT_KIND, ORACLE_ATTESTATION_KIND};
 use base64::engine::general_purpose::STANDARD as BASE64;
@@ -20,10 +32,41 @@ use ddk_messages::message_handler::read_dlc_message;
 use ddk_messages::{Message, WireMessage};
 use lightning::ln::wire::Type;
 use lightning::util::ser::{Readable, Writeable};
-use nostr_rs::nips::nip04;
+use nostr_rs::nips::{nip04, nip59};
 use nostr_rs::{
-    Event, EventBuilder, EventId, Filter, Keys, Kind, PublicKey, SecretKey, Tag, Timestamp,
+    Event, EventBuilder, EventId, Filter, Keys, Kind, PublicKey, SecretKey, Tag, TagKind, Tags,
+    Timestamp, UnsignedEvent,
 };
+use std::time::Instant;
+
+/// How DLC messages sent to a counterparty are encrypted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageEncryption {
+    /// A kind 8888 event encrypted with NIP-04. Understood by every DDK
+    /// version, but leaks the sender, the recipient and the time to relays.
+    #[default]
+    Nip04,
+    /// A kind 8888 rumor sealed with NIP-44 v2 and gift wrapped (NIP-59).
+    GiftWrap,
+}
+
+/// A DLC message received over Nostr, in either [`MessageEncryption`] mode.
+#[derive(Debug, Clone)]
+pub struct ReceivedDlcMessage {
+    /// The secp256k1 public key of the sender.
+    pub counter_party: SecpPublicKey,
+    /// The Nostr public key of the sender.
+    pub sender: PublicKey,
+    /// The DLC message.
+    pub message: Message,
+    /// The id of the kind 8888 event that carried the message. For a gift
+    /// wrapped message, this is the id of the rumor.
+    pub event_id: Option<EventId>,
+    /// When the sender created the message.
+    pub created_at: Timestamp,
+    /// How the message was encrypted.
+    pub encryption: MessageEncryption,
+}
 
 /// Creates a Nostr filter to listen for DLC protocol messages.
 ///
@@ -44,6 +87,22 @@ pub fn create_dlc_message_filter(since: Timestamp, public_key: PublicKey) -> Fil
         .pubkey(public_key)
 }
 
+/// Creates a Nostr filter to listen for gift wrapped DLC messages.
+///
+/// Gift wraps carry a timestamp randomized up to two days in the past, so the
+/// filter reaches back that far. Callers should drop unwrapped messages whose
+/// rumor was created before `since`.
+///
+/// # Arguments
+/// * `since` - Timestamp to receive messages from
+/// * `public_key` - The recipient's public key to filter for
+pub fn create_gift_wrap_filter(since: Timestamp, public_key: PublicKey) -> Filter {
+    Filter::new()
+        .kind(Kind::GiftWrap)
+        .since(since - nip59::RANGE_RANDOM_TIMESTAMP_TWEAK.end)
+        .pubkey(public_key)
+}
+
 /// Creates a Nostr filter to listen for oracle announcements and attestations.
 ///
 /// This filter targets events of kind 88 (oracle announcements) and kind 89 (oracle attestations)
@@ -81,10 +140,34 @@ pub fn create_oracle_message_filter(since: Timestamp) -> Filter {
 pub fn parse_dlc_msg_event(event: &Event, secret_key: &SecretKey) -> Result<Message, NostrError> {
     let decrypt = nip04::decrypt(secret_key, &event.pubkey, &event.content)?;
 
-    let bytes = BASE64
-        .decode(decrypt)
-        .map_err(|e| NostrError::MessageParsing(e.to_string()))?;
+    decode_dlc_msg(&decrypt)
+}
 
+/// Serializes a DLC message, prefixed with its type.
+fn serialize_dlc_msg(msg: &Message) -> Vec<u8> {
+    let mut bytes = msg.type_id().encode();
+    bytes.extend(msg.encode());
+    bytes
+}
+
+/// Serializes a DLC message and encodes it as base64.
+fn encode_dlc_msg(msg: &Message) -> String {
+    BASE64.encode(serialize_dlc_msg(msg))
+}
+
+fn decode_base64(content: &str) -> Result<Vec<u8>, NostrError> {
+    BASE64
+        .decode(content)
+        .map_err(|e| NostrError::MessageParsing(e.to_string()))
+}
+
+/// Decodes a base64 encoded, serialized DLC message.
+fn decode_dlc_msg(content: &str) -> Result<Message, NostrError> {
+    deserialize_dlc_msg(decode_base64(content)?)
+}
+
+/// Deserializes a DLC message prefixed with its type.
+fn deserialize_dlc_msg(bytes: Vec<u8>) -> Result<Message, NostrError> {
     let mut cursor = lightning::io::Cursor::new(bytes);
 
     let msg_type: u16 =
@@ -100,11 +183,10 @@ pub fn parse_dlc_msg_event(event: &Event, secret_key: &SecretKey) -> Result<Mess
 
     let message = match wire {
         WireMessage::Message(msg) => Ok(msg),
-        // We could stll do segment chunks. Nostr relays can handle the large sizes,
-        // but I'm running a custom relay so generic relays won't be able to handle.
+        // Large messages are split into several events over nostr, see `chunking`.
         WireMessage::SegmentStart(_) | WireMessage::SegmentChunk(_) => {
             Err(NostrError::MessageParsing(
-                "DLC message is not a valid message. Nostr should not be chunking messages."
+                "DLC message is not a valid message. Nostr should not be segmenting messages."
                     .to_string(),
             ))
         }
@@ -149,6 +231,136 @@ pub fn handle_dlc_msg_event(
     Ok((pubkey, message, event.clone()))
 }
 
+/// Unwraps a gift wrapped DLC message.
+///
+/// The seal is verified and the author of the rumor must be the author of the
+/// seal, so the returned sender cannot be forged by whoever wrapped the gift.
+///
+/// # Returns
+/// * `Ok((SecpPublicKey, Message, UnsignedEvent))` - The sender, the DLC message and the rumor
+/// * `Err(NostrError)` - If the event cannot be unwrapped, or the rumor is not a DLC message
+pub async fn handle_gift_wrapped_dlc_msg_event(
+    event: &Event,
+    keys: &Keys,
+) -> Result<(SecpPublicKey, Message, UnsignedEvent), NostrError> {
+    let unwrapped = nip59::extract_rumor(keys, event).await?;
+    if unwrapped.rumor.kind != DLC_MESSAGE_KIND {
+        return Err(NostrError::MessageParsing(
+            "Gift wrapped rumor was not a DLC Message event (kind 8_888).".to_string(),
+        ));
+    }
+
+    let message = decode_dlc_msg(&unwrapped.rumor.content)?;
+
+    let pubkey = nostr_to_bitcoin_pubkey(&unwrapped.sender);
+
+    Ok((pubkey, message, unwrapped.rumor))
+}
+
+/// A decrypted kind 8888 event whose content has not been decoded yet.
+struct OpenedDlcEvent {
+    sender: PublicKey,
+    event_id: Option<EventId>,
+    created_at: Timestamp,
+    encryption: MessageEncryption,
+    tags: Tags,
+    bytes: Vec<u8>,
+}
+
+impl OpenedDlcEvent {
+    fn into_received(self, message: Message) -> ReceivedDlcMessage {
+        ReceivedDlcMessage {
+            counter_party: nostr_to_bitcoin_pubkey(&self.sender),
+            sender: self.sender,
+            message,
+            event_id: self.event_id,
+            created_at: self.created_at,
+            encryption: self.encryption,
+        }
+    }
+}
+
+async fn open_dlc_event(event: &Event, keys: &Keys) -> Result<OpenedDlcEvent, NostrError> {
+    if event.kind == Kind::GiftWrap {
+        let unwrapped = nip59::extract_rumor(keys, event).await?;
+        if unwrapped.rumor.kind != DLC_MESSAGE_KIND {
+            return Err(NostrError::MessageParsing(
+                "Gift wrapped rumor was not a DLC Message event (kind 8_888).".to_string(),
+            ));
+        }
+        Ok(OpenedDlcEvent {
+            sender: unwrapped.sender,
+            event_id: unwrapped.rumor.id,
+            created_at: unwrapped.rumor.created_at,
+            encryption: MessageEncryption::GiftWrap,
+            bytes: decode_base64(&unwrapped.rumor.content)?,
+            tags: unwrapped.rumor.tags,
+        })
+    } else {
+        if event.kind != DLC_MESSAGE_KIND {
+            return Err(NostrError::MessageParsing(
+                "Event reveived was not DLC Message event (kind 8_888).".to_string(),
+            ));
+        }
+        let decrypt = nip04::decrypt(keys.secret_key(), &event.pubkey, &event.content)?;
+        Ok(OpenedDlcEvent {
+            sender: event.pubkey,
+            event_id: Some(event.id),
+            created_at: event.created_at,
+            encryption: MessageEncryption::Nip04,
+            bytes: decode_base64(&decrypt)?,
+            tags: event.tags.clone(),
+        })
+    }
+}
+
+/// Handles a DLC message event in either [`MessageEncryption`] mode.
+///
+/// Chunks of a large message are rejected, use [`receive_dlc_message`] to reassemble them.
+pub async fn handle_dlc_message(
+    event: &Event,
+    keys: &Keys,
+) -> Result<ReceivedDlcMessage, NostrError> {
+    let mut opened = open_dlc_event(event, keys).await?;
+    if opened
+        .tags
+        .find(TagKind::custom(chunking::CHUNK_TAG))
+        .is_some()
+    {
+        return Err(NostrError::MessageParsing(
+            "Event is a chunk of a larger DLC message.".to_string(),
+        ));
+    }
+    let message = deserialize_dlc_msg(std::mem::take(&mut opened.bytes))?;
+    Ok(opened.into_received(message))
+}
+
+/// Receives a DLC message event in either [`MessageEncryption`] mode, reassembling chunked
+/// messages.
+///
+/// # Returns
+/// * `Ok(Some(ReceivedDlcMessage))` - A complete message. For a chunked message, the event id and
+///   timestamp are those of the chunk that completed it.
+/// * `Ok(None)` - If the event is a chunk and the message is still incomplete
+/// * `Err(NostrError)` - If the event cannot be decrypted or decoded, or the reassembled
+///   message fails its integrity check
+pub async fn receive_dlc_message(
+    event: &Event,
+    keys: &Keys,
+    reassembler: &mut ChunkReassembler,
+) -> Result<Option<ReceivedDlcMessage>, NostrError> {
+    let mut opened = open_dlc_event(event, keys).await?;
+    let bytes = match Chunk::from_tags(&opened.tags, &opened.bytes)? {
+        Some(chunk) => match reassembler.add(opened.sender, chunk, Instant::now())? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        },
+        None => std::mem::take(&mut opened.bytes),
+    };
+    let message = deserialize_dlc_msg(bytes)?;
+    Ok(Some(opened.into_received(message)))
+}
+
 /// Creates and signs a Nostr event containing an encrypted DLC message.
 ///
 /// This function serializes a DLC protocol message, encodes it as base64, encrypts it
@@ -177,19 +389,105 @@ pub fn create_dlc_msg_event(
     msg: Message,
     keys: &Keys,
 ) -> Result<Event, NostrError> {
-    let mut bytes = msg.type_id().encode();
-    bytes.extend(msg.encode());
+    nip04_dlc_event(to, encode_dlc_msg(&msg), dlc_msg_tags(to, event_id), keys)
+}
+
+/// Creates a gift wrapped DLC message.
+///
+/// The rumor is an unsigned kind 8888 event with the base64-encoded DLC
+/// message as content and the same tags as [`create_dlc_msg_event`]. It is
+/// sealed for the recipient with NIP-44 v2, and the seal is gift wrapped with
+/// a one-time key.
+///
+/// # Arguments
+/// * `to` - The recipient's Nostr public key
+/// * `event_id` - Optional event ID to reply to (creates an 'e' tag in the rumor)
+/// * `msg` - The DLC message to send
+/// * `keys` - The sender's Nostr keys for the seal
+pub async fn create_gift_wrapped_dlc_msg_event(
+    to: PublicKey,
+    event_id: Option<EventId>,
+    msg: Message,
+    keys: &Keys,
+) -> Result<Event, NostrError> {
+    gift_wrapped_dlc_event(to, encode_dlc_msg(&msg), dlc_msg_tags(to, event_id), keys).await
+}
 
-    let content = nip04::encrypt(&keys.secret_key().clone(), &to, BASE64.encode(&bytes))?;
+/// Creates a DLC message event in the given [`MessageEncryption`] mode.
+pub async fn create_dlc_message(
+    to: PublicKey,
+    event_id: Option<EventId>,
+    msg: Message,
+    keys: &Keys,
+    encryption: MessageEncryption,
+) -> Result<Event, NostrError> {
+    match encryption {
+        MessageEncryption::Nip04 => create_dlc_msg_event(to, event_id, msg, keys),
+        MessageEncryption::GiftWrap => {
+            create_gift_wrapped_dlc_msg_event(to, event_id, msg, keys).await
+        }
+    }
+}
 
-    let p_tags = Tag::public_key(to);
+/// Creates the events carrying a DLC message in the given [`MessageEncryption`] mode.
+///
+/// Messages of up to [`MAX_CHUNK_SIZE`] serialized bytes are sent in a single event, exactly like
+/// [`create_dlc_message`]. Larger messages are split into chunks, each sent in its own event with
+/// a `chunk` tag, see [`chunking`]. All the events must be published for the recipient to
+/// reassemble the message.
+///
+/// # Errors
+/// * `NostrError::Generic` - If the message is too large to be chunked
+pub async fn create_dlc_message_events(
+    to: PublicKey,
+    event_id: Option<EventId>,
+    msg: Message,
+    keys: &Keys,
+    encryption: MessageEncryption,
+) -> Result<Vec<Event>, NostrError> {
+    let bytes = serialize_dlc_msg(&msg);
+    if bytes.len() <= MAX_CHUNK_SIZE {
+        let event = encrypt_dlc_event(
+            to,
+            BASE64.encode(&bytes),
+            dlc_msg_tags(to, event_id),
+            keys,
+            encryption,
+        )
+        .await?;
+        return Ok(vec![event]);
+    }
 
-    let e_tags = event_id.map(Tag::event);
+    let mut events = Vec::new();
+    for chunk in chunking::split(&bytes)? {
+        let mut tags = dlc_msg_tags(to, event_id);
+        tags.push(chunk.tag());
+        let content = BASE64.encode(&chunk.data);
+        events.push(encrypt_dlc_event(to, content, tags, keys, encryption).await?);
+    }
+    Ok(events)
+}
 
-    let tags = [Some(p_tags), e_tags]
-        .into_iter()
-        .flatten()
-        .collect::<Vec<_>>();
+async fn encrypt_dlc_event(
+    to: PublicKey,
+    content: String,
+    tags: Vec<Tag>,
+    keys: &Keys,
+    encryption: MessageEncryption,
+) -> Result<Event, NostrError> {
+    match encryption {
+        MessageEncryption::Nip04 => nip04_dlc_event(to, content, tags, keys),
+        MessageEncryption::GiftWrap => gift_wrapped_dlc_event(to, content, tags, keys).await,
+    }
+}
+
+fn nip04_dlc_event(
+    to: PublicKey,
+    content: String,
+    tags: Vec<Tag>,
+    keys: &Keys,
+) -> Result<Event, NostrError> {
+    let content = nip04::encrypt(keys.secret_key(), &to, content)?;
 
     let event = EventBuilder::new(DLC_MESSAGE_KIND, content)
         .tags(tags)
@@ -197,3 +495,180 @@ pub fn create_dlc_msg_event(
 
     Ok(event)
 }
+
+async fn gift_wrapped_dlc_event(
+    to: PublicKey,
+    content: String,
+    tags: Vec<Tag>,
+    keys: &Keys,
+) -> Result<Event, NostrError> {
+    let rumor = EventBuilder::new(DLC_MESSAGE_KIND, content)
+        .tags(tags)
+        .build(keys.public_key());
+
+    Ok(EventBuilder::gift_wrap(keys, &to, rumor, []).await?)
+}
+
+fn dlc_msg_tags(to: PublicKey, event_id: Option<EventId>) -> Vec<Tag> {
+    [Some(Tag::public_key(to)), event_id.map(Tag::event)]
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddk_messages::channel::Reject;
+    use ddk_messages::AcceptDlc;
+    use std::time::Duration;
+
+    fn reject() -> Message {
+        Message::Reject(Reject {
+            channel_id: [7u8; 32],
+        })
+    }
+
+    #[tokio::test]
+    async fn gift_wrap_round_trip_hides_sender() {
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let reply_to = EventId::all_zeros();
+
+        let event =
+            create_gift_wrapped_dlc_msg_event(bob.public_key(), Some(reply_to), reject(), &alice)
+                .await
+                .unwrap();
+        assert_eq!(event.kind, Kind::GiftWrap);
+        assert_ne!(event.pubkey, alice.public_key());
+        assert!(!event.content.contains(&encode_dlc_msg(&reject())));
+
+        let received = handle_dlc_message(&event, &bob).await.unwrap();
+        assert_eq!(received.encryption, MessageEncryption::GiftWrap);
+        assert_eq!(received.sender, alice.public_key());
+        assert_eq!(
+            received.counter_party,
+            nostr_to_bitcoin_pubkey(&alice.public_key())
+        );
+        assert_eq!(received.message.encode(), reject().encode());
+
+        // Only the recipient can unwrap it.
+        assert!(handle_dlc_message(&event, &Keys::generate()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn receives_gift_wrapped_and_nip04_messages_from_relay() {
+        // Held for the duration of the test: the relay shuts down when dropped.
+        let relay = ddk_testenv::nostr::TestRelay::start().await;
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let since = Timestamp::now();
+
+        let alice_client = nostr_sdk::Client::new(alice.clone());
+        alice_client.add_relay(relay.url()).await.unwrap();
+        alice_client.connect().await;
+        for encryption in [MessageEncryption::GiftWrap, MessageEncryption::Nip04] {
+            let event = create_dlc_message(bob.public_key(), None, reject(), &alice, encryption)
+                .await
+                .unwrap();
+            alice_client.send_event(&event).await.unwrap();
+        }
+
+        let bob_client = nostr_sdk::Client::new(bob.clone());
+        bob_client.add_relay(relay.url()).await.unwrap();
+        bob_client.connect().await;
+        let mut received = Vec::new();
+        for filter in [
+            create_gift_wrap_filter(since, bob.public_key()),
+            create_dlc_message_filter(since, bob.public_key()),
+        ] {
+            let events = bob_client
+                .fetch_events(filter, Duration::from_secs(5))
+                .await
+                .unwrap();
+            for event in events {
+                received.push(handle_dlc_message(&event, &bob).await.unwrap());
+            }
+        }
+
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].encryption, MessageEncryption::GiftWrap);
+        assert_eq!(received[1].encryption, MessageEncryption::Nip04);
+        for msg in received {
+            assert_eq!(msg.sender, alice.public_key());
+            assert!(msg.created_at >= since);
+            assert_eq!(msg.message.encode(), reject().encode());
+        }
+    }
+
+    fn accept() -> Message {
+        let input = include_str!("../../../dlc-messages/src/test_inputs/accept_msg.json");
+        let accept: AcceptDlc = serde_json::from_str(input).unwrap();
+        Message::Accept(accept)
+    }
+
+    #[tokio::test]
+    async fn large_messages_are_chunked_over_relay() {
+        // Held for the duration of the test: the relay shuts down when dropped.
+        let relay = ddk_testenv::nostr::TestRelay::start().await;
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let since = Timestamp::now();
+        let message = accept();
+        assert!(serialize_dlc_msg(&message).len() > MAX_CHUNK_SIZE);
+
+        let alice_client = nostr_sdk::Client::new(alice.clone());
+        alice_client.add_relay(relay.url()).await.unwrap();
+        alice_client.connect().await;
+        for encryption in [MessageEncryption::GiftWrap, MessageEncryption::Nip04] {
+            let events = create_dlc_message_events(
+                bob.public_key(),
+                None,
+                message.clone(),
+                &alice,
+                encryption,
+            )
+            .await
+            .unwrap();
+            assert!(events.len() > 1);
+            // Relays don't guarantee ordering, and may deliver an event twice.
+            for event in events.iter().rev().chain(events.first()) {
+                let output = alice_client.send_event(event).await.unwrap();
+                assert!(!output.success.is_empty());
+            }
+        }
+
+        let bob_client = nostr_sdk::Client::new(bob.clone());
+        bob_client.add_relay(relay.url()).await.unwrap();
+        bob_client.connect().await;
+        let mut received = Vec::new();
+        for filter in [
+            create_gift_wrap_filter(since, bob.public_key()),
+            create_dlc_message_filter(since, bob.public_key()),
+        ] {
+            // The same message sent twice is deduplicated, so use a reassembler per mode.
+            let mut reassembler = ChunkReassembler::default();
+            let events = bob_client
+                .fetch_events(filter, Duration::from_secs(5))
+                .await
+                .unwrap();
+            for event in events {
+                assert!(handle_dlc_message(&event, &bob).await.is_err());
+                if let Some(msg) = receive_dlc_message(&event, &bob, &mut reassembler)
+                    .await
+                    .unwrap()
+                {
+                    received.push(msg);
+                }
+            }
+        }
+
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].encryption, MessageEncryption::GiftWrap);
+        assert_eq!(received[1].encryption, MessageEncryption::Nip04);
+        for msg in received {
+            assert_eq!(msg.sender, alice.public_key());
+            assert_eq!(msg.message.encode(), message.encode());
+        }
+    }
+}
@@ -11,6 +11,7 @@
 //! The conversion process involves handling these format differences while preserving the
 //! cryptographic properties of the keys.
 
+pub mod chunking;
 pub mod messages;
 
 use bitcoin::key::Parity;
@@ -363,72 +363,191 @@ impl crate::Oracle for KormirOracleClient {
 
 #[cfg(test)]
 mod tests {
+    use bitcoin::bip32::Xpriv;
+    use bitcoin::Network;
     use chrono::{Local, TimeDelta};
+    use ddk_manager::Oracle as _;
+    use kormir::storage::sled::SledStorage;
+    use kormir_server::storage::ServerStorage;
 
     use super::*;
 
-    async fn create_kormir() -> KormirOracleClient {
-        KormirOracleClient::new(
-            "https://kormir.dlcdevkit.com",
-            None,
-            Arc::new(Logger::disabled("kormir".to_string())),
-        )
-        .await
-        .unwrap()
+    /// A kormir server on a sled database, listening on an ephemeral port.
+    struct TestKormir {
+        host: String,
+        pubkey: XOnlyPublicKey,
+        path: String,
     }
 
-    #[tokio::test]
-    #[ignore = "Kormir is not available"]
-    async fn kormir_enum_events() {
-        let kormir = create_kormir().await;
+    impl TestKormir {
+        async fn start(name: &str, hmac_secret: Option<Vec<u8>>) -> Self {
+            let path = format!("{}/ddk_kormir_{name}", std::env::temp_dir().display());
+            let _ = std::fs::remove_dir_all(&path);
+            let storage = ServerStorage::Sled(SledStorage::new(&path).unwrap());
+            let xpriv = Xpriv::new_master(Network::Regtest, &[3; 32]).unwrap();
+            let oracle = kormir::Oracle::from_xpriv(storage, xpriv).unwrap();
+            let pubkey = oracle.public_key();
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let host = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(kormir_server::serve(listener, oracle, hmac_secret));
+            TestKormir { host, pubkey, path }
+        }
+
+        async fn client(&self, hmac_secret: Option<Vec<u8>>) -> KormirOracleClient {
+            KormirOracleClient::new(
+                &self.host,
+                hmac_secret,
+                Arc::new(Logger::disabled("kormir".to_string())),
+            )
+            .await
+            .unwrap()
+        }
+    }
 
+    impl Drop for TestKormir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    fn maturity() -> u32 {
         let expiry = TimeDelta::seconds(30);
-        let timestamp: u32 = Local::now()
+        Local::now()
             .checked_add_signed(expiry)
             .unwrap()
             .timestamp()
             .try_into()
-            .unwrap();
+            .unwrap()
+    }
 
-        let announcement = kormir
-            .create_enum_event(vec!["rust".to_string(), "go".to_string()], timestamp)
-            .await;
+    #[tokio::test]
+    async fn kormir_enum_events() {
+        let server = TestKormir::start("enum", None).await;
+        let kormir = server.client(None).await;
+        assert_eq!(kormir.get_public_key(), server.pubkey);
 
-        assert!(announcement.is_ok());
+        let announcement = kormir
+            .create_enum_event(vec!["rust".to_string(), "go".to_string()], maturity())
+            .await
+            .unwrap();
+        let event_id = announcement.oracle_event.event_id.clone();
+        assert_eq!(
+            kormir.get_announcement(&event_id).await.unwrap(),
+            announcement
+        );
+        assert!(kormir.get_attestation(&event_id).await.is_err());
 
-        let sign_enum = kormir
-            .sign_enum_event(
-                announcement.unwrap().oracle_event.event_id,
-                "rust".to_string(),
-            )
-            .await;
+        let attestation = kormir
+            .sign_enum_event(event_id.clone(), "rust".to_string())
+            .await
+            .unwrap();
+        assert_eq!(attestation.outcomes, vec!["rust".to_string()]);
+        assert_eq!(
+            kormir.get_attestation(&event_id).await.unwrap(),
+            attestation
+        );
+        assert!(kormir
+            .sign_enum_event(event_id.clone(), "go".to_string())
+            .await
+            .is_err());
 
-        assert!(sign_enum.is_ok())
+        let events = kormir.list_events().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].attestation(), Some(attestation));
     }
 
     #[tokio::test]
-    #[ignore = "Kormir is not available"]
     async fn kormir_numeric_events() {
-        let kormir = create_kormir().await;
+        let server = TestKormir::start("numeric", None).await;
+        let kormir = server.client(None).await;
 
-        let expiry = TimeDelta::seconds(30);
-        let timestamp: u32 = Local::now()
-            .checked_add_signed(expiry)
-            .unwrap()
-            .timestamp()
-            .try_into()
+        let announcement = kormir
+            .create_numeric_event(Some(14), Some(true), Some(0), "m/s".to_string(), maturity())
+            .await
             .unwrap();
+        assert_eq!(announcement.oracle_event.oracle_nonces.len(), 15);
+
+        let sign_numeric = kormir
+            .sign_numeric_event(announcement.oracle_event.event_id.clone(), -12345)
+            .await
+            .unwrap();
+        assert_eq!(
+            kormir
+                .get_attestation(&announcement.oracle_event.event_id)
+                .await
+                .unwrap(),
+            sign_numeric
+        );
 
+        let defaults = kormir
+            .create_numeric_event(None, None, None, "usd".to_string(), maturity())
+            .await
+            .unwrap();
+        assert_eq!(
+            defaults.oracle_event.oracle_nonces.len(),
+            kormir_server::DEFAULT_NUM_DIGITS as usize
+        );
+        assert_eq!(kormir.list_events().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn kormir_requires_the_hmac_secret() {
+        let secret = b"kormir secret".to_vec();
+        let server = TestKormir::start("hmac", Some(secret.clone())).await;
+
+        let kormir = server.client(Some(secret)).await;
         let announcement = kormir
-            .create_numeric_event(Some(14), Some(true), Some(0), "m/s".to_string(), timestamp)
-            .await;
+            .create_enum_event(vec!["a".to_string(), "b".to_string()], maturity())
+            .await
+            .unwrap();
+        kormir
+            .sign_enum_event(announcement.oracle_event.event_id, "a".to_string())
+            .await
+            .unwrap();
 
-        assert!(announcement.is_ok());
+        for secret in [None, Some(b"wrong".to_vec())] {
+            let kormir = server.client(secret).await;
+            assert!(kormir
+                .create_enum_event(vec!["a".to_string(), "b".to_string()], maturity())
+                .await
+                .is_err());
+        }
+    }
 
-        let sign_numeric = kormir
-            .sign_numeric_event(announcement.unwrap().oracle_event.event_id, -12345)
-            .await;
+    #[tokio::test]
+    async fn kormir_rejects_unsigned_sign_requests() {
+        let secret = b"kormir secret".to_vec();
+        let server = TestKormir::start("unsigned_sign", Some(secret.clone())).await;
+        let announcement = server
+            .client(Some(secret))
+            .await
+            .create_enum_event(vec!["a".to_string(), "b".to_string()], maturity())
+            .await
+            .unwrap();
+        let event_id = announcement.oracle_event.event_id;
+
+        let unsigned = server.client(None).await;
+        assert!(unsigned
+            .sign_enum_event(event_id.clone(), "a".to_string())
+            .await
+            .is_err());
+        assert!(unsigned.get_attestation(&event_id).await.is_err());
+    }
 
-        assert!(sign_numeric.is_ok());
+    #[tokio::test]
+    async fn kormir_needs_a_secret_off_loopback() {
+        let path = format!("{}/ddk_kormir_no_secret", std::env::temp_dir().display());
+        let _ = std::fs::remove_dir_all(&path);
+        let storage = ServerStorage::Sled(SledStorage::new(&path).unwrap());
+        let xpriv = Xpriv::new_master(Network::Regtest, &[3; 32]).unwrap();
+        let oracle = kormir::Oracle::from_xpriv(storage, xpriv).unwrap();
+
+        let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await.unwrap();
+        let error = kormir_server::serve(listener, oracle, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        let _ = std::fs::remove_dir_all(&path);
     }
 }
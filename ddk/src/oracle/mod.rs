@@ -5,3 +5,36 @@ pub mod memory;
 pub mod nostr;
 #[cfg(feature = "p2pderivatives")]
 pub mod p2p_derivatives;
+pub mod set;
+
+use std::sync::{Arc, RwLock};
+
+use ddk_manager::equivocation::AttestationSource;
+use ddk_messages::oracle_msgs::OracleAttestation;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Receives the attestations an oracle client sees, each with where it was seen.
+pub type AttestationSender = UnboundedSender<(OracleAttestation, AttestationSource)>;
+
+/// Where an oracle client sends the attestations it sees, once
+/// [`Oracle::forward_attestations`](crate::Oracle::forward_attestations) was called.
+///
+/// Clones share the sender, so a client can hand one to the tasks it spawns before it
+/// is told where to forward.
+#[derive(Clone, Debug, Default)]
+pub struct AttestationForwarder(Arc<RwLock<Option<AttestationSender>>>);
+
+impl AttestationForwarder {
+    /// Sets where to send attestations, replacing the previous sender.
+    pub fn set(&self, sender: AttestationSender) {
+        *self.0.write().unwrap() = Some(sender);
+    }
+
+    /// Sends an attestation, if a sender is set.
+    pub fn forward(&self, attestation: OracleAttestation, source: AttestationSource) {
+        if let Some(sender) = self.0.read().unwrap().as_ref() {
+            // Sending only fails once the receiver is dropped.
+            let _ = sender.send((attestation, source));
+        }
+    }
+}
@@ -5,7 +5,9 @@ use std::time::Duration;
 use crate::error::OracleError;
 use crate::logger::Logger;
 use crate::logger::{log_debug, log_error, log_info, log_warn, WriteLog};
+use crate::oracle::{AttestationForwarder, AttestationSender};
 use bitcoin::XOnlyPublicKey;
+use ddk_manager::equivocation::AttestationSource;
 use ddk_manager::error::Error as ManagerError;
 use ddk_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
 use ddk_messages::TlvRecord;
@@ -32,6 +34,7 @@ use tokio::task::JoinHandle;
 /// * `db` - In-memory database for caching oracle events
 /// * `xonly_oracle_pubkey` - Bitcoin-compatible x-only public key for DLC operations
 /// * `nostr_oracle_pubkey` - Nostr-format public key for message verification
+/// * `forwarder` - Where attestations received from relays are forwarded
 #[derive(Debug)]
 pub struct NostrOracle {
     /// Nostr client for connecting to relays and handling message subscriptions
@@ -44,6 +47,8 @@ pub struct NostrOracle {
     nostr_oracle_pubkey: NostrPublicKey,
     /// [`ddk::logger::Logger`] instance for logging
     logger: Arc<Logger>,
+    /// Where attestations received from relays are forwarded
+    forwarder: AttestationForwarder,
 }
 
 impl NostrOracle {
@@ -105,6 +110,7 @@ impl NostrOracle {
             xonly_oracle_pubkey,
             nostr_oracle_pubkey,
             logger,
+            forwarder: AttestationForwarder::default(),
         })
     }
 
@@ -114,7 +120,8 @@ impl NostrOracle {
     /// 1. Listens for incoming Nostr events (announcements and attestations)
     /// 2. Decodes and validates the events
     /// 3. Stores valid events in the in-memory database
-    /// 4. Can be gracefully shut down using the stop signal
+    /// 4. Forwards attestations, see [`crate::Oracle::forward_attestations`]
+    /// 5. Can be gracefully shut down using the stop signal
     ///
     /// # Arguments
     /// * `stop_signal` - Watch channel receiver for graceful shutdown
@@ -142,6 +149,7 @@ impl NostrOracle {
         let nostr_client = self.client.clone();
         let db = self.db.clone();
         let logger = self.logger.clone();
+        let forwarder = self.forwarder.clone();
         tokio::spawn(async move {
             log_info!(logger, "Listening for Oracle messages.");
             let mut notifications = nostr_client.notifications();
@@ -158,7 +166,7 @@ impl NostrOracle {
                         log_info!(logger, "Received notification {:?}", notification);
                         match notification {
                             RelayPoolNotification::Event {
-                                relay_url: _,
+                                relay_url,
                                 subscription_id: _,
                                 event,
                             } => {
@@ -174,6 +182,10 @@ impl NostrOracle {
                                         if let Ok(attestation) = decode_base64::<OracleAttestation>(&event.content) {
                                             log_info!(logger, "Received attestation event: {}", attestation.event_id);
                                             let _ =db.save_event(&event).await;
+                                            forwarder.forward(
+                                                attestation,
+                                                AttestationSource::Transport(format!("nostr {relay_url}")),
+                                            );
                                         }
                                     }
                                     _ => ()
@@ -193,6 +205,10 @@ impl crate::Oracle for NostrOracle {
     fn name(&self) -> String {
         "nostr".to_string()
     }
+
+    fn forward_attestations(&self, attestations: AttestationSender) {
+        self.forwarder.set(attestations);
+    }
 }
 
 #[async_trait::async_trait]
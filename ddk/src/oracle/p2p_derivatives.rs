@@ -35,10 +35,10 @@ where
 {
     reqwest::get(path)
         .await
-        .map_err(|x| OracleError::Reqwest(x))?
+        .map_err(OracleError::Reqwest)?
         .json::<T>()
         .await
-        .map_err(|e| OracleError::Reqwest(e))
+        .map_err(OracleError::Reqwest)
 }
 
 fn pubkey_path(host: &str) -> String {
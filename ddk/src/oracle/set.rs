@@ -0,0 +1,313 @@
+//! # Oracle set
+//!
+//! A DLC can depend on several oracles, and each oracle may be reachable over a different
+//! transport (kormir over HTTP, P2PDerivatives, or Nostr). [`OracleSet`] holds any number of
+//! oracle clients and implements [`Oracle`], so a single [`DlcDevKit`](crate::DlcDevKit) can sign
+//! and settle multi-oracle contracts.
+//!
+//! The manager looks oracles up by public key. [`Oracle::routes`] gives it one view of the set per
+//! oracle public key, and each view only asks the clients of that oracle. An unrouted set asks all
+//! of its clients. Requests go to the clients concurrently, each bounded by a timeout, and the
+//! first successful response wins. Several clients for the same oracle act as fallbacks for each
+//! other.
+//!
+//! The other requests are not cut short: every attestation a client returns is forwarded with
+//! [`AttestationSource::Transport`] naming the client, see [`Oracle::forward_attestations`], so
+//! that an oracle attesting to different outcomes over different transports is caught.
+
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bitcoin::XOnlyPublicKey;
+use ddk_manager::equivocation::AttestationSource;
+use ddk_manager::error::Error as ManagerError;
+use ddk_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
+use tokio::task::JoinSet;
+
+use crate::error::OracleError;
+use crate::oracle::{AttestationForwarder, AttestationSender};
+use crate::Oracle;
+
+/// How long to wait for a single oracle client before giving up on it.
+pub const DEFAULT_ORACLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Several oracle clients behind one [`Oracle`].
+#[derive(Clone)]
+pub struct OracleSet {
+    oracles: Arc<Vec<Arc<dyn Oracle>>>,
+    routed: Option<XOnlyPublicKey>,
+    timeout: Duration,
+    forwarder: AttestationForwarder,
+}
+
+impl std::fmt::Debug for OracleSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OracleSet")
+            .field("oracles", &self.public_keys())
+            .field("routed", &self.routed)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl OracleSet {
+    /// Creates a set from oracle clients.
+    ///
+    /// # Errors
+    /// * `OracleError::Init` - If no oracle client is given
+    pub fn new(oracles: Vec<Arc<dyn Oracle>>) -> Result<Self, OracleError> {
+        if oracles.is_empty() {
+            return Err(OracleError::Init(
+                "An oracle set needs at least one oracle.".to_string(),
+            ));
+        }
+        Ok(Self {
+            oracles: Arc::new(oracles),
+            routed: None,
+            timeout: DEFAULT_ORACLE_TIMEOUT,
+            forwarder: AttestationForwarder::default(),
+        })
+    }
+
+    /// Sets how long to wait for each oracle client.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The public keys of the oracles in the set.
+    pub fn public_keys(&self) -> Vec<XOnlyPublicKey> {
+        self.oracles
+            .iter()
+            .map(|oracle| oracle.get_public_key())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// A view of the set that only asks the clients of the oracle with `pubkey`.
+    pub fn route(&self, pubkey: XOnlyPublicKey) -> Option<Self> {
+        self.oracles
+            .iter()
+            .any(|oracle| oracle.get_public_key() == pubkey)
+            .then(|| Self {
+                oracles: self.oracles.clone(),
+                routed: Some(pubkey),
+                timeout: self.timeout,
+                forwarder: self.forwarder.clone(),
+            })
+    }
+
+    /// Asks every client of the routed oracle (or of all oracles) concurrently and returns the
+    /// first response that `accept` agrees with.
+    async fn first_success<T, F, Fut>(
+        &self,
+        request: F,
+        accept: impl Fn(&T) -> bool,
+    ) -> Result<T, ManagerError>
+    where
+        T: Send + 'static,
+        F: Fn(Arc<dyn Oracle>) -> Fut,
+        Fut: Future<Output = Result<T, ManagerError>> + Send + 'static,
+    {
+        let mut requests = JoinSet::new();
+        for oracle in self.oracles.iter().filter(|oracle| {
+            self.routed
+                .is_none_or(|pubkey| oracle.get_public_key() == pubkey)
+        }) {
+            let response = tokio::time::timeout(self.timeout, request(oracle.clone()));
+            let oracle = oracle.clone();
+            requests.spawn(async move { (oracle, response.await) });
+        }
+
+        let mut errors = Vec::new();
+        while let Some(joined) = requests.join_next().await {
+            let Ok((oracle, response)) = joined else {
+                continue;
+            };
+            let error = match response {
+                Ok(Ok(response)) if accept(&response) => {
+                    // Let the other requests finish, their responses may still be forwarded.
+                    requests.detach_all();
+                    return Ok(response);
+                }
+                Ok(Ok(_)) => "response is for another oracle".to_string(),
+                Ok(Err(e)) => e.to_string(),
+                Err(_) => format!("timed out after {:?}", self.timeout),
+            };
+            errors.push(format!(
+                "{} ({}): {}",
+                oracle.name(),
+                oracle.get_public_key(),
+                error
+            ));
+        }
+        Err(ManagerError::OracleError(errors.join(", ")))
+    }
+}
+
+#[async_trait::async_trait]
+impl ddk_manager::Oracle for OracleSet {
+    /// The routed oracle, or the first oracle of an unrouted set.
+    fn get_public_key(&self) -> XOnlyPublicKey {
+        self.routed
+            .unwrap_or_else(|| self.oracles[0].get_public_key())
+    }
+
+    async fn get_announcement(&self, event_id: &str) -> Result<OracleAnnouncement, ManagerError> {
+        self.first_success(
+            |oracle| {
+                let event_id = event_id.to_string();
+                async move { oracle.get_announcement(&event_id).await }
+            },
+            |announcement| {
+                self.routed
+                    .is_none_or(|pubkey| announcement.oracle_public_key == pubkey)
+            },
+        )
+        .await
+    }
+
+    async fn get_attestation(&self, event_id: &str) -> Result<OracleAttestation, ManagerError> {
+        self.first_success(
+            |oracle| {
+                let event_id = event_id.to_string();
+                let forwarder = self.forwarder.clone();
+                async move {
+                    let attestation = oracle.get_attestation(&event_id).await?;
+                    forwarder.forward(
+                        attestation.clone(),
+                        AttestationSource::Transport(oracle.name()),
+                    );
+                    Ok::<_, ManagerError>(attestation)
+                }
+            },
+            |attestation| {
+                self.routed
+                    .is_none_or(|pubkey| attestation.oracle_public_key == pubkey)
+            },
+        )
+        .await
+    }
+}
+
+impl Oracle for OracleSet {
+    fn name(&self) -> String {
+        "oracle-set".into()
+    }
+
+    fn routes(self: Arc<Self>) -> Vec<(XOnlyPublicKey, Arc<Self>)> {
+        self.public_keys()
+            .into_iter()
+            .filter_map(|pubkey| Some((pubkey, Arc::new(self.route(pubkey)?))))
+            .collect()
+    }
+
+    /// Forwards the attestation every client returns, and whatever the
+    /// clients forward themselves.
+    fn forward_attestations(&self, attestations: AttestationSender) {
+        for oracle in self.oracles.iter() {
+            oracle.forward_attestations(attestations.clone());
+        }
+        self.forwarder.set(attestations);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::memory::MemoryOracle;
+    use ddk_manager::Oracle as _;
+
+    /// An oracle client that never answers.
+    struct Unresponsive(XOnlyPublicKey);
+
+    #[async_trait::async_trait]
+    impl ddk_manager::Oracle for Unresponsive {
+        fn get_public_key(&self) -> XOnlyPublicKey {
+            self.0
+        }
+
+        async fn get_announcement(&self, _: &str) -> Result<OracleAnnouncement, ManagerError> {
+            std::future::pending().await
+        }
+
+        async fn get_attestation(&self, _: &str) -> Result<OracleAttestation, ManagerError> {
+            std::future::pending().await
+        }
+    }
+
+    impl Oracle for Unresponsive {
+        fn name(&self) -> String {
+            "unresponsive".into()
+        }
+    }
+
+    async fn create_event(oracle: &MemoryOracle, event_id: &str) -> OracleAnnouncement {
+        let maturity = chrono::Utc::now().timestamp() as u32 + 60;
+        oracle
+            .oracle
+            .create_enum_event(event_id.into(), vec!["a".into(), "b".into()], maturity)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn routes_requests_by_oracle_public_key() {
+        let alice = MemoryOracle::default();
+        let bob = MemoryOracle::default();
+        let alice_announcement = create_event(&alice, "event").await;
+        let bob_announcement = create_event(&bob, "event").await;
+        let set =
+            Arc::new(OracleSet::new(vec![Arc::new(alice.clone()), Arc::new(bob.clone())]).unwrap());
+
+        let routes = set.clone().routes();
+        assert_eq!(routes.len(), 2);
+        for (pubkey, routed) in routes {
+            let expected = if pubkey == alice.get_public_key() {
+                &alice_announcement
+            } else {
+                &bob_announcement
+            };
+            assert_eq!(routed.get_public_key(), pubkey);
+            assert_eq!(&routed.get_announcement("event").await.unwrap(), expected);
+        }
+
+        let signed = bob
+            .oracle
+            .sign_enum_event("event".into(), "b".into())
+            .await
+            .unwrap();
+        let routed = set.route(bob.get_public_key()).unwrap();
+        assert_eq!(routed.get_attestation("event").await.unwrap(), signed);
+    }
+
+    #[tokio::test]
+    async fn unresponsive_clients_time_out() {
+        let oracle = MemoryOracle::default();
+        let announcement = create_event(&oracle, "event").await;
+        let pubkey = oracle.get_public_key();
+
+        // Another client for the same oracle answers in time.
+        let set = OracleSet::new(vec![Arc::new(Unresponsive(pubkey)), Arc::new(oracle)])
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+        let routed = set.route(pubkey).unwrap();
+        assert_eq!(
+            routed.get_announcement("event").await.unwrap(),
+            announcement
+        );
+
+        let set = OracleSet::new(vec![Arc::new(Unresponsive(pubkey))])
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+        let error = set.get_announcement("event").await.unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{error}");
+
+        let other = MemoryOracle::default().get_public_key();
+        assert!(set.route(other).is_none());
+        assert!(OracleSet::new(vec![]).is_err());
+    }
+}
@@ -1,5 +1,8 @@
+use crate::wallet::ChannelKeyDerivation;
 use crate::Storage;
 use bdk_chain::Merge;
+use bitcoin::secp256k1::PublicKey;
+use ddk_manager::manager_state::ManagerState;
 use ddk_manager::{channel::Channel, contract::Contract, ChannelId, ContractId};
 use std::collections::HashMap;
 use std::sync::RwLock;
@@ -9,6 +12,8 @@ pub struct MemoryStorage {
     bdk_data: RwLock<Option<bdk_wallet::ChangeSet>>,
     contracts: RwLock<HashMap<ContractId, Contract>>,
     channels: RwLock<HashMap<ChannelId, Channel>>,
+    channel_keys: RwLock<HashMap<PublicKey, ChannelKeyDerivation>>,
+    manager_state: RwLock<Option<ManagerState>>,
 }
 
 impl MemoryStorage {
@@ -17,6 +22,8 @@ impl MemoryStorage {
             bdk_data: RwLock::new(None),
             contracts: RwLock::new(HashMap::new()),
             channels: RwLock::new(HashMap::new()),
+            channel_keys: RwLock::new(HashMap::new()),
+            manager_state: RwLock::new(None),
         }
     }
 }
@@ -36,6 +43,30 @@ impl Storage for MemoryStorage {
     async fn initialize_bdk(&self) -> Result<bdk_wallet::ChangeSet, crate::error::WalletError> {
         Ok(self.bdk_data.read().unwrap().clone().unwrap_or_default())
     }
+
+    async fn persist_channel_key(
+        &self,
+        pubkey: &PublicKey,
+        derivation: &ChannelKeyDerivation,
+    ) -> Result<(), crate::error::WalletError> {
+        self.channel_keys
+            .write()
+            .unwrap()
+            .insert(*pubkey, *derivation);
+        Ok(())
+    }
+
+    async fn get_channel_keys(
+        &self,
+    ) -> Result<Vec<(PublicKey, ChannelKeyDerivation)>, crate::error::WalletError> {
+        Ok(self
+            .channel_keys
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(pubkey, derivation)| (*pubkey, *derivation))
+            .collect())
+    }
 }
 
 #[async_trait::async_trait]
@@ -60,6 +91,12 @@ impl ddk_manager::Storage for MemoryStorage {
         Ok(self.contracts.read().unwrap().values().cloned().collect())
     }
 
+    async fn get_channels(
+        &self,
+    ) -> Result<Vec<ddk_manager::channel::Channel>, ddk_manager::error::Error> {
+        Ok(self.channels.read().unwrap().values().cloned().collect())
+    }
+
     async fn upsert_channel(
         &self,
         channel: ddk_manager::channel::Channel,
@@ -192,6 +229,18 @@ impl ddk_manager::Storage for MemoryStorage {
         Ok(())
     }
 
+    async fn persist_manager_state(
+        &self,
+        state: &ManagerState,
+    ) -> Result<(), ddk_manager::error::Error> {
+        *self.manager_state.write().unwrap() = Some(state.clone());
+        Ok(())
+    }
+
+    async fn get_manager_state(&self) -> Result<Option<ManagerState>, ddk_manager::error::Error> {
+        Ok(self.manager_state.read().unwrap().clone())
+    }
+
     async fn get_confirmed_contracts(
         &self,
     ) -> Result<
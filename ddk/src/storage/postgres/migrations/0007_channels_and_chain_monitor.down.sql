@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS chain_monitor;
+DROP TABLE IF EXISTS channel_data;
@@ -0,0 +1,18 @@
+-- DLC channels, stored like contract_data: the serialized channel plus the
+-- columns the Storage trait filters on.
+CREATE TABLE channel_data (
+    id TEXT PRIMARY KEY,
+    state SMALLINT NOT NULL CHECK (state >= 0),
+    signed_state SMALLINT CHECK (signed_state >= 0),
+    counter_party TEXT NOT NULL,
+    channel_data BYTEA NOT NULL
+);
+
+CREATE INDEX idx_channel_data_state ON channel_data(state, signed_state);
+CREATE INDEX idx_channel_data_counter_party ON channel_data(counter_party);
+
+-- The manager keeps a single ChainMonitor, so the table holds at most one row.
+CREATE TABLE chain_monitor (
+    id SMALLINT PRIMARY KEY CHECK (id = 1),
+    monitor_data BYTEA NOT NULL
+);
@@ -0,0 +1,10 @@
+-- How the wallet derives the secret key behind each channel public key: either
+-- an index on the channel key path or the keys id of a funding key.
+CREATE TABLE channel_keys (
+    wallet_name TEXT NOT NULL,
+    pubkey TEXT NOT NULL,
+    channel_index INTEGER CHECK (channel_index >= 0),
+    keys_id TEXT,
+    PRIMARY KEY (wallet_name, pubkey),
+    CHECK ((channel_index IS NULL) <> (keys_id IS NULL))
+);
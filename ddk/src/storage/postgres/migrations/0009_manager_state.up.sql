@@ -0,0 +1,5 @@
+-- The manager keeps a single ManagerState, so the table holds at most one row.
+CREATE TABLE manager_state (
+    id SMALLINT PRIMARY KEY CHECK (id = 1),
+    state_data BYTEA NOT NULL
+);
@@ -1,11 +1,15 @@
-use super::sqlx::{ContractData, ContractMetadata, SqlxError};
+use super::sqlx::{ChannelData, ChannelKey, ContractData, ContractMetadata, SqlxError};
 use crate::error::{StorageError, WalletError};
 use crate::logger::Logger;
 use crate::logger::{log_info, WriteLog};
+use crate::wallet::ChannelKeyDerivation;
 use crate::Storage;
 use crate::{
     error::to_storage_error,
-    util::ser::{deserialize_contract, serialize_contract, ContractPrefix},
+    util::ser::{
+        deserialize_channel, deserialize_contract, serialize_channel, serialize_contract,
+        ChannelPrefix, ContractPrefix, SignedChannelPrefix,
+    },
 };
 use bdk_chain::{
     local_chain, tx_graph, Anchor, ConfirmationBlockTime, DescriptorExt, DescriptorId, Merge,
@@ -23,10 +27,17 @@ use bdk_wallet::ChangeSet;
 use bdk_wallet::KeychainKind;
 use bdk_wallet::KeychainKind::{External, Internal};
 use ddk_manager::{
+    chain_monitor::ChainMonitor,
+    channel::{
+        offered_channel::OfferedChannel,
+        signed_channel::{SignedChannel, SignedChannelStateType},
+        Channel,
+    },
     contract::{
         offered_contract::OfferedContract, ser::Serializable, signed_contract::SignedContract,
         Contract, PreClosedContract,
     },
+    manager_state::ManagerState,
     Storage as ManagerStorage,
 };
 use serde_json::json;
@@ -328,6 +339,65 @@ impl Storage for PostgresStore {
             .await
             .map_err(|_| WalletError::StorageError("Did not persist bdk storage".to_string()))
     }
+
+    async fn persist_channel_key(
+        &self,
+        pubkey: &bitcoin::secp256k1::PublicKey,
+        derivation: &ChannelKeyDerivation,
+    ) -> Result<(), WalletError> {
+        let (channel_index, keys_id) = match derivation {
+            ChannelKeyDerivation::Channel(index) => (Some(*index as i32), None),
+            ChannelKeyDerivation::Funding(keys_id) => (None, Some(hex::encode(keys_id))),
+        };
+        sqlx::query(
+            "INSERT INTO channel_keys (wallet_name, pubkey, channel_index, keys_id)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (wallet_name, pubkey) DO UPDATE SET
+                channel_index = EXCLUDED.channel_index,
+                keys_id = EXCLUDED.keys_id",
+        )
+        .bind(&self.wallet_name)
+        .bind(pubkey.to_string())
+        .bind(channel_index)
+        .bind(keys_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WalletError::StorageError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_channel_keys(
+        &self,
+    ) -> Result<Vec<(bitcoin::secp256k1::PublicKey, ChannelKeyDerivation)>, WalletError> {
+        let rows = sqlx::query_as::<Postgres, ChannelKey>(
+            "SELECT pubkey, channel_index, keys_id FROM channel_keys WHERE wallet_name = $1",
+        )
+        .bind(&self.wallet_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WalletError::StorageError(e.to_string()))?;
+
+        let invalid = |e: String| WalletError::StorageError(format!("Invalid channel key: {e}"));
+        rows.into_iter()
+            .map(|row| {
+                let pubkey = bitcoin::secp256k1::PublicKey::from_str(&row.pubkey)
+                    .map_err(|e| invalid(e.to_string()))?;
+                let derivation = match (row.channel_index, row.keys_id) {
+                    (Some(index), None) => ChannelKeyDerivation::Channel(index as u32),
+                    (None, Some(keys_id)) => {
+                        let keys_id = hex::decode(keys_id).map_err(|e| invalid(e.to_string()))?;
+                        ChannelKeyDerivation::Funding(
+                            keys_id
+                                .try_into()
+                                .map_err(|_| invalid("keys id is not 32 bytes".to_string()))?,
+                        )
+                    }
+                    _ => return Err(invalid(row.pubkey)),
+                };
+                Ok((pubkey, derivation))
+            })
+            .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -458,100 +528,8 @@ impl ManagerStorage for PostgresStore {
             "Updating contract. id={}",
             hex::encode(contract.get_id())
         );
-        let prefix = ContractPrefix::get_prefix(contract);
-        let contract_id = hex::encode(contract.get_id());
-        let (offer_collateral, accept_collateral, total_collateral) = contract.get_collateral();
-
-        // Start a transaction
         let mut tx = self.pool.begin().await.map_err(to_storage_error)?;
-
-        // Step 1: Remove by temp_id if Accepted or Signed
-        match contract {
-            a @ Contract::Accepted(_) | a @ Contract::Signed(_) => {
-                log_info!(
-                    self.logger,
-                    "Deleting contract by temp_id. tmp_id={}",
-                    hex::encode(a.get_temporary_id())
-                );
-                let temp_id = hex::encode(a.get_temporary_id());
-                sqlx::query("DELETE FROM contract_data WHERE id = $1")
-                    .bind(temp_id.clone())
-                    .execute(&mut *tx)
-                    .await
-                    .map_err(to_storage_error)?;
-                sqlx::query("DELETE FROM contract_metadata WHERE id = $1")
-                    .bind(temp_id)
-                    .execute(&mut *tx)
-                    .await
-                    .map_err(to_storage_error)?;
-            }
-            _ => {}
-        }
-
-        let funding_txid = contract.get_funding_txid().map(|txid| txid.to_string());
-        let cet_txid = contract.get_cet_txid().map(|txid| txid.to_string());
-        let oracle_pubkey = contract
-            .get_oracle_announcement()
-            .map(|ann| ann.oracle_public_key.to_string());
-        let announcement_id = contract
-            .get_oracle_announcement()
-            .map(|ann| ann.oracle_event.event_id.clone());
-
-        // A single atomic upsert: the read-modify-write it replaces raced under
-        // concurrent updates, and its insert arm hardcoded is_offer_party and
-        // fee_rate_per_vb. The update arm deliberately leaves the columns set
-        // at insert time untouched and only advances the mutable ones.
-        sqlx::query(
-            r#"
-            INSERT INTO contract_metadata (
-                id, state, is_offer_party, counter_party,
-                offer_collateral, accept_collateral, total_collateral, fee_rate_per_vb,
-                cet_locktime, refund_locktime, pnl, funding_txid, cet_txid, announcement_id, oracle_pubkey
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-            ON CONFLICT (id) DO UPDATE SET
-                state = EXCLUDED.state,
-                pnl = EXCLUDED.pnl,
-                funding_txid = COALESCE(EXCLUDED.funding_txid, contract_metadata.funding_txid),
-                cet_txid = COALESCE(EXCLUDED.cet_txid, contract_metadata.cet_txid)
-            "#,
-        )
-        .bind(&contract_id)
-        .bind(prefix as i16)
-        .bind(contract.is_offer_party())
-        .bind(hex::encode(contract.get_counter_party_id().serialize()))
-        .bind(offer_collateral.to_sat() as i64)
-        .bind(accept_collateral.to_sat() as i64)
-        .bind(total_collateral.to_sat() as i64)
-        .bind(contract.get_fee_rate_per_vb() as i64)
-        .bind(contract.get_cet_locktime() as i32)
-        .bind(contract.get_refund_locktime() as i32)
-        .bind(Some(contract.get_pnl().to_sat()))
-        .bind(&funding_txid)
-        .bind(&cet_txid)
-        .bind(announcement_id.unwrap_or_else(|| "legacy_data".to_string()))
-        .bind(oracle_pubkey.unwrap_or_else(|| "legacy_data".to_string()))
-        .execute(&mut *tx)
-        .await
-        .map_err(to_storage_error)?;
-
-        let serialized_contract = serialize_contract(contract)?;
-
-        sqlx::query(
-            "INSERT INTO contract_data (id, state, contract_data, is_compressed)
-             VALUES ($1, $2, $3, $4)
-             ON CONFLICT (id) DO UPDATE SET
-                 state = EXCLUDED.state,
-                 contract_data = EXCLUDED.contract_data",
-        )
-        .bind(&contract_id)
-        .bind(prefix as i16)
-        .bind(&serialized_contract)
-        .bind(false)
-        .execute(&mut *tx)
-        .await
-        .map_err(to_storage_error)?;
-
+        upsert_contract(&mut tx, contract, &self.logger).await?;
         tx.commit().await.map_err(to_storage_error)?;
 
         Ok(())
@@ -645,62 +623,316 @@ impl ManagerStorage for PostgresStore {
         Ok(preclosed)
     }
 
-    #[tracing::instrument(skip(self))]
+    #[tracing::instrument(skip(self, channel, contract))]
     async fn upsert_channel(
         &self,
-        _channel: ddk_manager::channel::Channel,
-        _contract: Option<Contract>,
+        channel: Channel,
+        contract: Option<Contract>,
     ) -> Result<(), ddk_manager::error::Error> {
-        unimplemented!("Channels not supported.")
+        let channel_id = hex::encode(channel.get_id());
+        log_info!(
+            self.logger,
+            "Upserting channel. id={} state={}",
+            channel_id,
+            channel.state_name()
+        );
+        let signed_state = match &channel {
+            Channel::Signed(s) => Some(SignedChannelPrefix::get_prefix(&s.state.get_type()) as i16),
+            _ => None,
+        };
+        let serialized_channel = serialize_channel(&channel)?;
+
+        // The channel and its contract are written in one transaction, so a
+        // crash never leaves a channel pointing at a stale contract state.
+        let mut tx = self.pool.begin().await.map_err(to_storage_error)?;
+
+        if let a @ Channel::Accepted(_) | a @ Channel::Signed(_) = &channel {
+            sqlx::query("DELETE FROM channel_data WHERE id = $1")
+                .bind(hex::encode(a.get_temporary_id()))
+                .execute(&mut *tx)
+                .await
+                .map_err(to_storage_error)?;
+        }
+
+        sqlx::query(
+            "INSERT INTO channel_data (id, state, signed_state, counter_party, channel_data)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (id) DO UPDATE SET
+                 state = EXCLUDED.state,
+                 signed_state = EXCLUDED.signed_state,
+                 channel_data = EXCLUDED.channel_data",
+        )
+        .bind(&channel_id)
+        .bind(ChannelPrefix::get_prefix(&channel) as i16)
+        .bind(signed_state)
+        .bind(hex::encode(channel.get_counter_party_id().serialize()))
+        .bind(&serialized_channel)
+        .execute(&mut *tx)
+        .await
+        .map_err(to_storage_error)?;
+
+        if let Some(contract) = &contract {
+            upsert_contract(&mut tx, contract, &self.logger).await?;
+        }
+
+        tx.commit().await.map_err(to_storage_error)?;
+
+        Ok(())
     }
 
     #[tracing::instrument(skip(self))]
     async fn delete_channel(
         &self,
-        _channel_id: &ddk_manager::ChannelId,
+        channel_id: &ddk_manager::ChannelId,
     ) -> Result<(), ddk_manager::error::Error> {
-        unimplemented!("Channels not supported.")
+        sqlx::query("DELETE FROM channel_data WHERE id = $1")
+            .bind(hex::encode(channel_id))
+            .execute(&self.pool)
+            .await
+            .map_err(to_storage_error)?;
+
+        Ok(())
     }
 
-    #[tracing::instrument(skip(self, _channel_state))]
+    #[tracing::instrument(skip(self, channel_state))]
     async fn get_signed_channels(
         &self,
-        _channel_state: Option<ddk_manager::channel::signed_channel::SignedChannelStateType>,
-    ) -> Result<Vec<ddk_manager::channel::signed_channel::SignedChannel>, ddk_manager::error::Error>
-    {
-        unimplemented!("Channels not supported.")
+        channel_state: Option<SignedChannelStateType>,
+    ) -> Result<Vec<SignedChannel>, ddk_manager::error::Error> {
+        let channels = sqlx::query_as::<Postgres, ChannelData>(
+            "SELECT * FROM channel_data
+             WHERE state = $1 AND ($2::SMALLINT IS NULL OR signed_state = $2)",
+        )
+        .bind(u8::from(ChannelPrefix::Signed) as i16)
+        .bind(channel_state.map(|state| SignedChannelPrefix::get_prefix(&state) as i16))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(to_storage_error)?;
+
+        channels
+            .into_iter()
+            .map(|c| match deserialize_channel(&c.channel_data)? {
+                Channel::Signed(s) => Ok(s),
+                _ => Err(to_storage_error(format!("Channel {} is not signed.", c.id))),
+            })
+            .collect()
     }
 
     #[tracing::instrument(skip(self))]
     async fn get_channel(
         &self,
-        _channel_id: &ddk_manager::ChannelId,
-    ) -> Result<Option<ddk_manager::channel::Channel>, ddk_manager::error::Error> {
-        unimplemented!("Channels not supported.")
+        channel_id: &ddk_manager::ChannelId,
+    ) -> Result<Option<Channel>, ddk_manager::error::Error> {
+        let channel =
+            sqlx::query_as::<Postgres, ChannelData>("SELECT * FROM channel_data WHERE id = $1")
+                .bind(hex::encode(channel_id))
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(to_storage_error)?;
+
+        channel
+            .map(|c| deserialize_channel(&c.channel_data))
+            .transpose()
     }
 
     #[tracing::instrument(skip(self))]
-    async fn get_offered_channels(
-        &self,
-    ) -> Result<Vec<ddk_manager::channel::offered_channel::OfferedChannel>, ddk_manager::error::Error>
-    {
-        unimplemented!("Channels not supported.")
+    async fn get_channels(&self) -> Result<Vec<Channel>, ddk_manager::error::Error> {
+        let channels = sqlx::query_as::<Postgres, ChannelData>("SELECT * FROM channel_data")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(to_storage_error)?;
+
+        channels
+            .into_iter()
+            .map(|c| deserialize_channel(&c.channel_data))
+            .collect()
     }
 
     #[tracing::instrument(skip(self))]
+    async fn get_offered_channels(&self) -> Result<Vec<OfferedChannel>, ddk_manager::error::Error> {
+        let channels =
+            sqlx::query_as::<Postgres, ChannelData>("SELECT * FROM channel_data WHERE state = $1")
+                .bind(u8::from(ChannelPrefix::Offered) as i16)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(to_storage_error)?;
+
+        channels
+            .into_iter()
+            .map(|c| match deserialize_channel(&c.channel_data)? {
+                Channel::Offered(o) => Ok(o),
+                _ => Err(to_storage_error(format!(
+                    "Channel {} is not offered.",
+                    c.id
+                ))),
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(skip(self, monitor))]
     async fn persist_chain_monitor(
         &self,
-        _monitor: &ddk_manager::chain_monitor::ChainMonitor,
+        monitor: &ChainMonitor,
     ) -> Result<(), ddk_manager::error::Error> {
-        unimplemented!("Chain monitor not supported.")
+        sqlx::query(
+            "INSERT INTO chain_monitor (id, monitor_data) VALUES (1, $1)
+             ON CONFLICT (id) DO UPDATE SET monitor_data = EXCLUDED.monitor_data",
+        )
+        .bind(monitor.serialize()?)
+        .execute(&self.pool)
+        .await
+        .map_err(to_storage_error)?;
+
+        Ok(())
     }
 
     #[tracing::instrument(skip(self))]
-    async fn get_chain_monitor(
+    async fn get_chain_monitor(&self) -> Result<Option<ChainMonitor>, ddk_manager::error::Error> {
+        let row = sqlx::query("SELECT monitor_data FROM chain_monitor WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_storage_error)?;
+
+        row.map(|row| {
+            let data: Vec<u8> = row.get("monitor_data");
+            ChainMonitor::deserialize(&mut lightning::io::Cursor::new(data))
+                .map_err(to_storage_error)
+        })
+        .transpose()
+    }
+
+    #[tracing::instrument(skip(self, state))]
+    async fn persist_manager_state(
         &self,
-    ) -> Result<Option<ddk_manager::chain_monitor::ChainMonitor>, ddk_manager::error::Error> {
-        Ok(None)
+        state: &ManagerState,
+    ) -> Result<(), ddk_manager::error::Error> {
+        sqlx::query(
+            "INSERT INTO manager_state (id, state_data) VALUES (1, $1)
+             ON CONFLICT (id) DO UPDATE SET state_data = EXCLUDED.state_data",
+        )
+        .bind(state.serialize()?)
+        .execute(&self.pool)
+        .await
+        .map_err(to_storage_error)?;
+
+        Ok(())
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_manager_state(&self) -> Result<Option<ManagerState>, ddk_manager::error::Error> {
+        let row = sqlx::query("SELECT state_data FROM manager_state WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(to_storage_error)?;
+
+        row.map(|row| {
+            let data: Vec<u8> = row.get("state_data");
+            ManagerState::deserialize(&mut lightning::io::Cursor::new(data))
+                .map_err(to_storage_error)
+        })
+        .transpose()
+    }
+}
+
+/// Upserts a contract's metadata and data rows, replacing the rows stored under
+/// its temporary id once it is accepted or signed.
+async fn upsert_contract(
+    tx: &mut Transaction<'_, Postgres>,
+    contract: &Contract,
+    logger: &Arc<Logger>,
+) -> Result<(), ddk_manager::error::Error> {
+    let prefix = ContractPrefix::get_prefix(contract);
+    let contract_id = hex::encode(contract.get_id());
+    let (offer_collateral, accept_collateral, total_collateral) = contract.get_collateral();
+
+    // Step 1: Remove by temp_id if Accepted or Signed
+    match contract {
+        a @ Contract::Accepted(_) | a @ Contract::Signed(_) => {
+            log_info!(
+                logger,
+                "Deleting contract by temp_id. tmp_id={}",
+                hex::encode(a.get_temporary_id())
+            );
+            let temp_id = hex::encode(a.get_temporary_id());
+            sqlx::query("DELETE FROM contract_data WHERE id = $1")
+                .bind(temp_id.clone())
+                .execute(&mut **tx)
+                .await
+                .map_err(to_storage_error)?;
+            sqlx::query("DELETE FROM contract_metadata WHERE id = $1")
+                .bind(temp_id)
+                .execute(&mut **tx)
+                .await
+                .map_err(to_storage_error)?;
+        }
+        _ => {}
+    }
+
+    let funding_txid = contract.get_funding_txid().map(|txid| txid.to_string());
+    let cet_txid = contract.get_cet_txid().map(|txid| txid.to_string());
+    let oracle_pubkey = contract
+        .get_oracle_announcement()
+        .map(|ann| ann.oracle_public_key.to_string());
+    let announcement_id = contract
+        .get_oracle_announcement()
+        .map(|ann| ann.oracle_event.event_id.clone());
+
+    // A single atomic upsert: the read-modify-write it replaces raced under
+    // concurrent updates, and its insert arm hardcoded is_offer_party and
+    // fee_rate_per_vb. The update arm deliberately leaves the columns set
+    // at insert time untouched and only advances the mutable ones.
+    sqlx::query(
+        r#"
+        INSERT INTO contract_metadata (
+            id, state, is_offer_party, counter_party,
+            offer_collateral, accept_collateral, total_collateral, fee_rate_per_vb,
+            cet_locktime, refund_locktime, pnl, funding_txid, cet_txid, announcement_id, oracle_pubkey
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        ON CONFLICT (id) DO UPDATE SET
+            state = EXCLUDED.state,
+            pnl = EXCLUDED.pnl,
+            funding_txid = COALESCE(EXCLUDED.funding_txid, contract_metadata.funding_txid),
+            cet_txid = COALESCE(EXCLUDED.cet_txid, contract_metadata.cet_txid)
+        "#,
+    )
+    .bind(&contract_id)
+    .bind(prefix as i16)
+    .bind(contract.is_offer_party())
+    .bind(hex::encode(contract.get_counter_party_id().serialize()))
+    .bind(offer_collateral.to_sat() as i64)
+    .bind(accept_collateral.to_sat() as i64)
+    .bind(total_collateral.to_sat() as i64)
+    .bind(contract.get_fee_rate_per_vb() as i64)
+    .bind(contract.get_cet_locktime() as i32)
+    .bind(contract.get_refund_locktime() as i32)
+    .bind(Some(contract.get_pnl().to_sat()))
+    .bind(&funding_txid)
+    .bind(&cet_txid)
+    .bind(announcement_id.unwrap_or_else(|| "legacy_data".to_string()))
+    .bind(oracle_pubkey.unwrap_or_else(|| "legacy_data".to_string()))
+    .execute(&mut **tx)
+    .await
+    .map_err(to_storage_error)?;
+
+    let serialized_contract = serialize_contract(contract)?;
+
+    sqlx::query(
+        "INSERT INTO contract_data (id, state, contract_data, is_compressed)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (id) DO UPDATE SET
+             state = EXCLUDED.state,
+             contract_data = EXCLUDED.contract_data",
+    )
+    .bind(&contract_id)
+    .bind(prefix as i16)
+    .bind(&serialized_contract)
+    .bind(false)
+    .execute(&mut **tx)
+    .await
+    .map_err(to_storage_error)?;
+
+    Ok(())
 }
 
 /// Insert keychain descriptors.
@@ -1101,6 +1333,7 @@ struct KeychainEntry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Storage as _;
     use crate::{logger::LogLevel, util::ser::deserialize_contract};
     use ddk_manager::Storage;
     use ddk_testenv::postgres::TestPostgres;
@@ -1407,4 +1640,114 @@ mod tests {
         assert!(db.get_contract(&id).await.unwrap().is_none());
         assert!(db.get_contract_metadata(None).await.unwrap().is_empty());
     }
+
+    fn offered_channel() -> (OfferedChannel, OfferedContract) {
+        let offer: ddk_messages::channel::OfferChannel = serde_json::from_str(include_str!(
+            "../../../../ddk-manager/test_inputs/offer_channel.json"
+        ))
+        .unwrap();
+        OfferedChannel::from_offer_channel(&offer, offer.funding_pubkey, [0u8; 32]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn upsert_channel_stores_channel_and_contract() {
+        let (_server, db) = seed_db().await;
+        let (channel, contract) = offered_channel();
+        let channel_id = channel.temporary_channel_id;
+
+        db.upsert_channel(
+            Channel::Offered(channel.clone()),
+            Some(Contract::Offered(contract.clone())),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(
+            db.get_channel(&channel_id).await.unwrap(),
+            Some(Channel::Offered(c)) if c.offered_contract_id == contract.id
+        ));
+        assert_eq!(db.get_offered_channels().await.unwrap().len(), 1);
+        assert!(db.get_signed_channels(None).await.unwrap().is_empty());
+        assert_eq!(db.get_channels().await.unwrap().len(), 1);
+        assert!(matches!(
+            db.get_contract(&contract.id).await.unwrap(),
+            Some(Contract::Offered(_))
+        ));
+        let metadata = db
+            .get_contract_metadata_by_id(&hex::encode(contract.id))
+            .await
+            .unwrap();
+        assert_eq!(metadata.state, ContractPrefix::Offered as i16);
+
+        // Moving the channel on replaces its row instead of adding one.
+        db.upsert_channel(Channel::Cancelled(channel), None)
+            .await
+            .unwrap();
+        assert!(db.get_offered_channels().await.unwrap().is_empty());
+        assert!(matches!(
+            db.get_channel(&channel_id).await.unwrap(),
+            Some(Channel::Cancelled(_))
+        ));
+        assert!(matches!(
+            db.get_channels().await.unwrap().as_slice(),
+            [Channel::Cancelled(_)]
+        ));
+
+        db.delete_channel(&channel_id).await.unwrap();
+        assert!(db.get_channel(&channel_id).await.unwrap().is_none());
+        assert!(db.get_contract(&contract.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn manager_state_roundtrip() {
+        let (_server, db) = seed_db().await;
+        assert!(db.get_manager_state().await.unwrap().is_none());
+
+        let state = ManagerState::default();
+        db.persist_manager_state(&state).await.unwrap();
+        db.persist_manager_state(&state).await.unwrap();
+        assert!(db.get_manager_state().await.unwrap().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn chain_monitor_roundtrip() {
+        let (_server, db) = seed_db().await;
+        assert!(db.get_chain_monitor().await.unwrap().is_none());
+
+        for height in [100, 101] {
+            let monitor = ChainMonitor::new(height);
+            db.persist_chain_monitor(&monitor).await.unwrap();
+            let stored = db.get_chain_monitor().await.unwrap().unwrap();
+            assert_eq!(stored.serialize().unwrap(), monitor.serialize().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn channel_keys_roundtrip() {
+        let (_server, db) = seed_db().await;
+        assert!(db.get_channel_keys().await.unwrap().is_empty());
+
+        let secp = bitcoin::secp256k1::Secp256k1::new();
+        let pubkey = |byte: u8| {
+            bitcoin::secp256k1::SecretKey::from_slice(&[byte; 32])
+                .unwrap()
+                .public_key(&secp)
+        };
+        let mut keys = vec![
+            (pubkey(1), ChannelKeyDerivation::Channel(0)),
+            (pubkey(2), ChannelKeyDerivation::Funding([9u8; 32])),
+        ];
+        for (pubkey, derivation) in &keys {
+            db.persist_channel_key(pubkey, derivation).await.unwrap();
+        }
+        // Writing an entry again keeps a single row.
+        db.persist_channel_key(&keys[0].0, &keys[0].1)
+            .await
+            .unwrap();
+
+        let mut stored = db.get_channel_keys().await.unwrap();
+        stored.sort_by_key(|(pubkey, _)| *pubkey);
+        keys.sort_by_key(|(pubkey, _)| *pubkey);
+        assert_eq!(stored, keys);
+    }
 }
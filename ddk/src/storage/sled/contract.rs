@@ -1,24 +1,21 @@
-use super::{SledStorage, CHAIN_MONITOR_KEY, CHAIN_MONITOR_TREE};
+use super::{SledStorage, CHAIN_MONITOR_KEY, CHAIN_MONITOR_TREE, MANAGER_STATE_KEY};
 use crate::logger::{log_error, log_info, WriteLog};
 use crate::util::ser::{
-    deserialize_contract, serialize_contract, ChannelPrefix, ContractPrefix, SignedChannelPrefix,
+    deserialize_channel, deserialize_contract, serialize_channel, serialize_contract,
+    ChannelPrefix, ContractPrefix, SignedChannelPrefix,
 };
-use bitcoin::consensus::ReadExt;
 use ddk_manager::chain_monitor::ChainMonitor;
-use ddk_manager::channel::accepted_channel::AcceptedChannel;
 use ddk_manager::channel::offered_channel::OfferedChannel;
 use ddk_manager::channel::signed_channel::{SignedChannel, SignedChannelStateType};
-use ddk_manager::channel::{
-    Channel, ClosedChannel, ClosedPunishedChannel, ClosingChannel, FailedAccept, FailedSign,
-};
+use ddk_manager::channel::Channel;
 use ddk_manager::contract::offered_contract::OfferedContract;
 use ddk_manager::contract::ser::Serializable;
 use ddk_manager::contract::signed_contract::SignedContract;
 use ddk_manager::contract::{Contract, PreClosedContract};
+use ddk_manager::manager_state::ManagerState;
 use ddk_manager::{error::Error, ContractId, Storage};
 use sled::transaction::{ConflictableTransactionResult, UnabortableTransactionError};
 use sled::Transactional;
-use std::convert::TryInto;
 
 fn to_storage_error<T>(e: T) -> Error
 where
@@ -178,6 +175,14 @@ impl Storage for SledStorage {
         }
     }
 
+    async fn get_channels(&self) -> Result<Vec<Channel>, Error> {
+        self.channel_tree()?
+            .iter()
+            .values()
+            .map(|x| deserialize_channel(&x.map_err(to_storage_error)?))
+            .collect::<Result<Vec<Channel>, Error>>()
+    }
+
     async fn get_signed_channels(
         &self,
         channel_state: Option<SignedChannelStateType>,
@@ -225,6 +230,26 @@ impl Storage for SledStorage {
         };
         Ok(deserialized)
     }
+
+    async fn persist_manager_state(&self, state: &ManagerState) -> Result<(), Error> {
+        self.open_tree(&[CHAIN_MONITOR_TREE])?
+            .insert([MANAGER_STATE_KEY], state.serialize()?)
+            .map_err(|e| Error::StorageError(format!("Error writing manager state: {}", e)))?;
+        Ok(())
+    }
+
+    async fn get_manager_state(&self) -> Result<Option<ManagerState>, Error> {
+        let serialized = self
+            .open_tree(&[CHAIN_MONITOR_TREE])?
+            .get([MANAGER_STATE_KEY])
+            .map_err(|e| Error::StorageError(format!("Error reading manager state: {}", e)))?;
+        serialized
+            .map(|s| {
+                ManagerState::deserialize(&mut ::lightning::io::Cursor::new(s))
+                    .map_err(to_storage_error)
+            })
+            .transpose()
+    }
 }
 
 fn insert_contract(
@@ -242,75 +267,6 @@ fn insert_contract(
     db.insert(&contract.get_id(), serialized)
 }
 
-fn serialize_channel(channel: &Channel) -> Result<Vec<u8>, ::lightning::io::Error> {
-    let serialized = match channel {
-        Channel::Offered(o) => o.serialize(),
-        Channel::Accepted(a) => a.serialize(),
-        Channel::Signed(s) => s.serialize(),
-        Channel::FailedAccept(f) => f.serialize(),
-        Channel::FailedSign(f) => f.serialize(),
-        Channel::Cancelled(o) => o.serialize(),
-        Channel::Closing(c) => c.serialize(),
-        Channel::Closed(c) => c.serialize(),
-        Channel::CollaborativelyClosed(c) => c.serialize(),
-        Channel::CounterClosed(c) => c.serialize(),
-        Channel::ClosedPunished(c) => c.serialize(),
-    };
-    let mut serialized = serialized?;
-    let mut res = Vec::with_capacity(serialized.len() + 1);
-    res.push(ChannelPrefix::get_prefix(channel));
-    if let Channel::Signed(s) = channel {
-        res.push(SignedChannelPrefix::get_prefix(&s.state.get_type()))
-    }
-    res.append(&mut serialized);
-    Ok(res)
-}
-
-fn deserialize_channel(buff: &sled::IVec) -> Result<Channel, Error> {
-    let mut cursor = lightning::io::Cursor::new(buff);
-    let mut prefix = [0u8; 1];
-    cursor.read_slice(&mut prefix)?;
-    let channel_prefix: ChannelPrefix = prefix[0].try_into()?;
-    let channel = match channel_prefix {
-        ChannelPrefix::Offered => {
-            Channel::Offered(OfferedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::Accepted => {
-            Channel::Accepted(AcceptedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::Signed => {
-            // Skip the channel state prefix.
-            cursor.set_position(cursor.position() + 1);
-            Channel::Signed(SignedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::FailedAccept => {
-            Channel::FailedAccept(FailedAccept::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::FailedSign => {
-            Channel::FailedSign(FailedSign::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::Cancelled => {
-            Channel::Cancelled(OfferedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::Closed => {
-            Channel::Closed(ClosedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::Closing => {
-            Channel::Closing(ClosingChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
-        }
-        ChannelPrefix::CounterClosed => Channel::CounterClosed(
-            ClosedChannel::deserialize(&mut cursor).map_err(to_storage_error)?,
-        ),
-        ChannelPrefix::ClosedPunished => Channel::ClosedPunished(
-            ClosedPunishedChannel::deserialize(&mut cursor).map_err(to_storage_error)?,
-        ),
-        ChannelPrefix::CollaborativelyClosed => Channel::CollaborativelyClosed(
-            ClosedChannel::deserialize(&mut cursor).map_err(to_storage_error)?,
-        ),
-    };
-    Ok(channel)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -551,6 +507,27 @@ mod tests {
         }
     );
 
+    sled_test!(
+        persist_manager_state_test,
+        |storage: SledStorage| async move {
+            assert!(storage.get_manager_state().await.unwrap().is_none());
+            let state = ManagerState::default();
+
+            storage
+                .persist_manager_state(&state)
+                .await
+                .expect("to be able to persist the manager state.");
+
+            let retrieved = storage
+                .get_manager_state()
+                .await
+                .expect("to be able to retrieve the manager state.")
+                .expect("to have a persisted manager state.");
+
+            assert!(retrieved.is_empty());
+        }
+    );
+
     #[test]
     fn old_format_offered_contract_deserializes() {
         let serialized = include_bytes!("../../../../testconfig/contract_binaries/old/Offered");
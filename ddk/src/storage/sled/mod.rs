@@ -8,10 +8,12 @@ mod wallet;
 use std::sync::Arc;
 
 use crate::logger::{log_info, WriteLog};
+use crate::wallet::ChannelKeyDerivation;
 use crate::Storage;
 use crate::{error::WalletError, logger::Logger};
 use bdk_chain::Merge;
 use bdk_wallet::ChangeSet;
+use bitcoin::secp256k1::PublicKey;
 use ddk_manager::contract::ser::Serializable;
 use ddk_manager::error::Error;
 use lightning::io::{Cursor, Read};
@@ -21,9 +23,11 @@ const CONTRACT_TREE: u8 = 1;
 const CHANNEL_TREE: u8 = 2;
 pub const CHAIN_MONITOR_TREE: u8 = 3;
 pub const CHAIN_MONITOR_KEY: u8 = 4;
+pub const MANAGER_STATE_KEY: u8 = 5;
 const SIGNER_TREE: u8 = 6;
 const WALLET_TREE: u8 = 7;
 const MARKETPLACE_TREE: u8 = 8;
+const CHANNEL_KEY_TREE: u8 = 9;
 const CHANGESET_KEY: &str = "changeset";
 
 /// Implementation of Storage interface using the sled DB backend.
@@ -92,6 +96,10 @@ impl SledStorage {
     pub fn marketplace_tree(&self) -> Result<Tree, sled::Error> {
         self.db.open_tree([MARKETPLACE_TREE])
     }
+
+    fn channel_key_tree(&self) -> Result<Tree, sled::Error> {
+        self.db.open_tree([CHANNEL_KEY_TREE])
+    }
 }
 
 #[async_trait::async_trait]
@@ -129,6 +137,33 @@ impl Storage for SledStorage {
         };
         Ok(changeset)
     }
+
+    async fn persist_channel_key(
+        &self,
+        pubkey: &PublicKey,
+        derivation: &ChannelKeyDerivation,
+    ) -> Result<(), WalletError> {
+        self.channel_key_tree()
+            .map_err(sled_to_wallet_error)?
+            .insert(pubkey.serialize(), serde_json::to_vec(derivation)?)
+            .map_err(sled_to_wallet_error)?;
+        Ok(())
+    }
+
+    async fn get_channel_keys(
+        &self,
+    ) -> Result<Vec<(PublicKey, ChannelKeyDerivation)>, WalletError> {
+        self.channel_key_tree()
+            .map_err(sled_to_wallet_error)?
+            .iter()
+            .map(|entry| {
+                let (pubkey, derivation) = entry.map_err(sled_to_wallet_error)?;
+                let pubkey = PublicKey::from_slice(&pubkey)
+                    .map_err(|e| WalletError::StorageError(e.to_string()))?;
+                Ok((pubkey, serde_json::from_slice(&derivation)?))
+            })
+            .collect()
+    }
 }
 
 fn sled_to_wallet_error(error: sled::Error) -> WalletError {
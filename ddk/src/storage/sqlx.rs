@@ -54,3 +54,19 @@ pub struct ContractData {
     pub contract_data: Vec<u8>,
     pub is_compressed: bool,
 }
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ChannelData {
+    pub id: String,
+    pub state: i16,
+    pub signed_state: Option<i16>,
+    pub counter_party: String,
+    pub channel_data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ChannelKey {
+    pub pubkey: String,
+    pub channel_index: Option<i32>,
+    pub keys_id: Option<String>,
+}
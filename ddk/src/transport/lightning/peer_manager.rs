@@ -192,6 +192,17 @@ impl LightningTransport {
                                 }
                             }
                         }
+                        for (counter_party, message) in message_manager.get_and_clear_pending_messages().await {
+                            if peer_manager.peer_by_node_id(&counter_party).is_some() {
+                                log_info!(logger_clone, "Sending pending message to counter_party={}", counter_party.to_string());
+                                message_handler.send_message(counter_party, message);
+                                peer_manager.process_events();
+                            } else {
+                                log_warn!(logger_clone,
+                                    "Not connected to counterparty. Pending message not sent. counter_party={}", counter_party.to_string()
+                                )
+                            }
+                        }
                     }
                 }
             }
@@ -98,6 +98,9 @@ impl Transport for MemoryTransport {
                                 e.to_string()
                             ),
                         }
+                        for (counter_party, pending) in manager.get_and_clear_pending_messages().await {
+                            self.send_message(counter_party, pending).await;
+                        }
                     }
                 }
             }
@@ -45,21 +45,8 @@ impl Transport for NostrDlc {
             counterparty.to_string(),
             nostr_counterparty.to_string()
         );
-        let event =
-            nostr::messages::create_dlc_msg_event(nostr_counterparty, None, message, &self.keys)
-                .unwrap();
-        match self.client.send_event(&event).await {
-            Ok(e) => log_info!(
-                self.logger,
-                "Sent DLC message event. event_id={}",
-                e.val.to_string()
-            ),
-            Err(e) => log_error!(
-                self.logger,
-                "Failed to send nostr event. error={}",
-                e.to_string()
-            ),
-        }
+        self.send_dlc_message(nostr_counterparty, None, message)
+            .await;
     }
     /// Connect to a relay.
     async fn connect_outbound(&self, _pubkey: BitcoinPublicKey, host: &str) {
@@ -1,24 +1,58 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 use crate::error::TransportError;
 use crate::logger::Logger;
 use crate::logger::{log_error, log_info, log_warn, WriteLog};
-use crate::nostr::messages::{create_dlc_msg_event, handle_dlc_msg_event};
+use crate::nostr::chunking::{ChunkReassembler, DEFAULT_CHUNK_TIMEOUT};
+use crate::nostr::messages::{create_dlc_message_events, receive_dlc_message, MessageEncryption};
 use crate::DlcDevKitDlcManager;
 use crate::{nostr, Transport};
 use crate::{Oracle, Storage};
 use bitcoin::bip32::Xpriv;
 use bitcoin::Network;
-use nostr_rs::{secp256k1::Secp256k1, Keys, Timestamp, Url};
+use ddk_dlc::secp256k1_zkp::PublicKey as BitcoinPublicKey;
+use ddk_messages::Message;
+use nostr_rs::{secp256k1::Secp256k1, EventId, Keys, PublicKey, Timestamp, Url};
 use nostr_sdk::{Client, RelayPoolNotification};
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 
+/// The [`MessageEncryption`] mode used for each counterparty.
+///
+/// A mode set with [`NostrDlc::set_encryption`] always wins. Otherwise a
+/// counterparty that has sent us a gift wrapped message is answered with gift
+/// wraps, and everyone else gets the default mode.
+#[derive(Debug, Default)]
+struct EncryptionPolicy {
+    default: MessageEncryption,
+    configured: HashMap<PublicKey, MessageEncryption>,
+    negotiated: HashMap<PublicKey, MessageEncryption>,
+}
+
+impl EncryptionPolicy {
+    fn get(&self, counterparty: &PublicKey) -> MessageEncryption {
+        self.configured
+            .get(counterparty)
+            .or_else(|| self.negotiated.get(counterparty))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    fn received(&mut self, counterparty: PublicKey, encryption: MessageEncryption) {
+        if encryption == MessageEncryption::GiftWrap {
+            self.negotiated.insert(counterparty, encryption);
+        }
+    }
+}
+
 pub struct NostrDlc {
     pub keys: Keys,
     pub relay_url: Url,
     pub client: Client,
     pub logger: Arc<Logger>,
+    encryption: Arc<RwLock<EncryptionPolicy>>,
 }
 
 impl NostrDlc {
@@ -49,9 +83,51 @@ impl NostrDlc {
             relay_url,
             client,
             logger,
+            encryption: Arc::new(RwLock::new(EncryptionPolicy::default())),
         })
     }
 
+    /// Sets the [`MessageEncryption`] mode used for counterparties without a
+    /// configured or negotiated mode. Defaults to [`MessageEncryption::Nip04`].
+    pub fn set_default_encryption(&self, encryption: MessageEncryption) {
+        self.encryption.write().unwrap().default = encryption;
+    }
+
+    /// Sets the [`MessageEncryption`] mode used for messages sent to a counterparty.
+    pub fn set_encryption(&self, counterparty: &BitcoinPublicKey, encryption: MessageEncryption) {
+        let counterparty = nostr::bitcoin_to_nostr_pubkey(counterparty);
+        self.encryption
+            .write()
+            .unwrap()
+            .configured
+            .insert(counterparty, encryption);
+    }
+
+    /// The [`MessageEncryption`] mode used for messages sent to a counterparty.
+    pub fn encryption(&self, counterparty: &BitcoinPublicKey) -> MessageEncryption {
+        let counterparty = nostr::bitcoin_to_nostr_pubkey(counterparty);
+        self.encryption.read().unwrap().get(&counterparty)
+    }
+
+    pub(crate) async fn send_dlc_message(
+        &self,
+        to: PublicKey,
+        reply_to: Option<EventId>,
+        message: Message,
+    ) {
+        let encryption = self.encryption.read().unwrap().get(&to);
+        send_dlc_message(
+            &self.client,
+            &self.keys,
+            &self.logger,
+            encryption,
+            to,
+            reply_to,
+            message,
+        )
+        .await
+    }
+
     pub fn start<S: Storage, O: Oracle>(
         &self,
         mut stop_signal: watch::Receiver<bool>,
@@ -66,20 +142,27 @@ impl NostrDlc {
         let nostr_client = self.client.clone();
         let keys = self.keys.clone();
         let logger = self.logger.clone();
+        let encryption = self.encryption.clone();
         tokio::spawn(async move {
             let since = Timestamp::now();
             let msg_subscription =
                 nostr::messages::create_dlc_message_filter(since, keys.public_key());
-            nostr_client
-                .subscribe(msg_subscription, None)
-                .await
-                .map_err(|e| TransportError::Listen(e.to_string()))?;
+            let gift_wrap_subscription =
+                nostr::messages::create_gift_wrap_filter(since, keys.public_key());
+            for filter in [msg_subscription, gift_wrap_subscription] {
+                nostr_client
+                    .subscribe(filter, None)
+                    .await
+                    .map_err(|e| TransportError::Listen(e.to_string()))?;
+            }
             log_info!(
                 logger,
                 "Listening for messages over nostr. pubkey={}",
                 keys.public_key().to_string()
             );
             let mut notifications = nostr_client.notifications();
+            let mut reassembler = ChunkReassembler::new(DEFAULT_CHUNK_TIMEOUT);
+            let mut expire_chunks = tokio::time::interval(DEFAULT_CHUNK_TIMEOUT / 4);
             loop {
                 let logger_clone = logger.clone();
                 tokio::select! {
@@ -90,19 +173,28 @@ impl NostrDlc {
                             break;
                         }
                     },
+                    _ = expire_chunks.tick() => {
+                        for expired in reassembler.expire(Instant::now()) {
+                            log_warn!(
+                                logger_clone,
+                                "Dropping incomplete chunked DLC message. pubkey={} message_id={} missing_chunks={:?}",
+                                expired.sender.to_string(),
+                                expired.message_id,
+                                expired.missing
+                            );
+                        }
+                    },
                     Ok(notification) = notifications.recv() => {
                         if let RelayPoolNotification::Event {
                             relay_url: _,
                             subscription_id: _,
                             event,
                         } = notification {
-                            let (pubkey, message, event) = match handle_dlc_msg_event(
-                                &event,
-                                keys.secret_key(),
-                            ) {
-                                Ok(msg) => {
-                                    log_info!(logger_clone, "Received DLC nostr message. pubkey={}", msg.0.to_string());
-                                    (msg.0, msg.1, msg.2)
+                            let received = match receive_dlc_message(&event, &keys, &mut reassembler).await {
+                                Ok(None) => continue,
+                                Ok(Some(msg)) => {
+                                    log_info!(logger_clone, "Received DLC nostr message. pubkey={} encryption={:?}", msg.counter_party.to_string(), msg.encryption);
+                                    msg
                                 },
                                 Err(e) => {
                                     log_error!(logger_clone, "Could not parse event {}. error={}", event.id, e.to_string());
@@ -110,25 +202,50 @@ impl NostrDlc {
                                 }
                             };
 
-                            match manager.on_dlc_message(&message, pubkey).await {
+                            // Gift wraps are backdated, so the filter reaches back before `since`.
+                            if received.created_at < since {
+                                continue;
+                            }
+
+                            let reply_encryption = {
+                                let mut policy = encryption.write().unwrap();
+                                policy.received(received.sender, received.encryption);
+                                policy.get(&received.sender)
+                            };
+
+                            match manager.on_dlc_message(&received.message, received.counter_party).await {
                                 Ok(Some(msg)) => {
-                                    let event = create_dlc_msg_event(
-                                        event.pubkey,
-                                        Some(event.id),
-                                        msg,
+                                    send_dlc_message(
+                                        &nostr_client,
                                         &keys,
+                                        &logger_clone,
+                                        reply_encryption,
+                                        received.sender,
+                                        received.event_id,
+                                        msg,
                                     )
-                                    .expect("no message");
-                                    nostr_client
-                                        .send_event(&event)
-                                        .await
-                                        .expect("Break out into functions.");
+                                    .await;
                                 }
                                 Ok(None) => (),
                                 Err(_) => {
                                     // handle the error case and send
                                 }
                             }
+
+                            for (counter_party, msg) in manager.get_and_clear_pending_messages().await {
+                                let to = nostr::bitcoin_to_nostr_pubkey(&counter_party);
+                                let pending_encryption = encryption.read().unwrap().get(&to);
+                                send_dlc_message(
+                                    &nostr_client,
+                                    &keys,
+                                    &logger_clone,
+                                    pending_encryption,
+                                    to,
+                                    None,
+                                    msg,
+                                )
+                                .await;
+                            }
                         }
                     }
                 }
@@ -137,3 +254,52 @@ impl NostrDlc {
         })
     }
 }
+
+async fn send_dlc_message(
+    client: &Client,
+    keys: &Keys,
+    logger: &Arc<Logger>,
+    encryption: MessageEncryption,
+    to: PublicKey,
+    reply_to: Option<EventId>,
+    message: Message,
+) {
+    let events = match create_dlc_message_events(to, reply_to, message, keys, encryption).await {
+        Ok(events) => events,
+        Err(e) => {
+            log_error!(
+                logger,
+                "Failed to create nostr event. encryption={:?} error={}",
+                encryption,
+                e.to_string()
+            );
+            return;
+        }
+    };
+    let chunks = events.len();
+    for event in events {
+        match client.send_event(&event).await {
+            // Relays answer with `OK false` when they reject an event, e.g. because it is too
+            // large. That is not an error for the client, so check that some relay stored it.
+            Ok(output) if output.success.is_empty() => log_error!(
+                logger,
+                "No relay accepted DLC message event. event_id={} chunks={} errors={:?}",
+                output.val.to_string(),
+                chunks,
+                output.failed
+            ),
+            Ok(output) => log_info!(
+                logger,
+                "Sent DLC message event. event_id={} encryption={:?} chunks={}",
+                output.val.to_string(),
+                encryption,
+                chunks
+            ),
+            Err(e) => log_error!(
+                logger,
+                "Failed to send nostr event. error={}",
+                e.to_string()
+            ),
+        }
+    }
+}
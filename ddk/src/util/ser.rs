@@ -1,5 +1,9 @@
-use ddk_manager::channel::signed_channel::SignedChannelStateType;
-use ddk_manager::channel::Channel;
+use ddk_manager::channel::accepted_channel::AcceptedChannel;
+use ddk_manager::channel::offered_channel::OfferedChannel;
+use ddk_manager::channel::signed_channel::{SignedChannel, SignedChannelStateType};
+use ddk_manager::channel::{
+    Channel, ClosedChannel, ClosedPunishedChannel, ClosingChannel, FailedAccept, FailedSign,
+};
 use ddk_manager::contract::accepted_contract::AcceptedContract;
 use ddk_manager::contract::offered_contract::OfferedContract;
 use ddk_manager::contract::ser::Serializable;
@@ -75,7 +79,9 @@ convertible_enum!(
         // 9
         Refunded,
         // 10
-        Rejected,;
+        Rejected,
+        // 11
+        Expired,;
     },
     Contract
 );
@@ -93,6 +99,7 @@ impl From<String> for ContractPrefix {
             "failed-sign" => ContractPrefix::FailedSign,
             "refunded" => ContractPrefix::Refunded,
             "rejected" => ContractPrefix::Rejected,
+            "expired" => ContractPrefix::Expired,
             _ => ContractPrefix::Offered,
         }
     }
@@ -135,7 +142,7 @@ convertible_enum!(
 
 pub fn serialize_contract(contract: &Contract) -> Result<Vec<u8>, Error> {
     let serialized = match contract {
-        Contract::Offered(o) | Contract::Rejected(o) => o.serialize(),
+        Contract::Offered(o) | Contract::Rejected(o) | Contract::Expired(o) => o.serialize(),
         Contract::Accepted(o) => o.serialize(),
         Contract::Signed(o) | Contract::Confirmed(o) | Contract::Refunded(o) => o.serialize(),
         Contract::FailedAccept(c) => c.serialize(),
@@ -186,10 +193,82 @@ pub fn deserialize_contract(buff: &Vec<u8>) -> Result<Contract, Error> {
         ContractPrefix::Rejected => {
             Contract::Rejected(OfferedContract::deserialize(&mut cursor).map_err(to_storage_error)?)
         }
+        ContractPrefix::Expired => {
+            Contract::Expired(OfferedContract::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
     };
     Ok(contract)
 }
 
+pub fn serialize_channel(channel: &Channel) -> Result<Vec<u8>, Error> {
+    let serialized = match channel {
+        Channel::Offered(o) => o.serialize(),
+        Channel::Accepted(a) => a.serialize(),
+        Channel::Signed(s) => s.serialize(),
+        Channel::FailedAccept(f) => f.serialize(),
+        Channel::FailedSign(f) => f.serialize(),
+        Channel::Cancelled(o) => o.serialize(),
+        Channel::Closing(c) => c.serialize(),
+        Channel::Closed(c) => c.serialize(),
+        Channel::CollaborativelyClosed(c) => c.serialize(),
+        Channel::CounterClosed(c) => c.serialize(),
+        Channel::ClosedPunished(c) => c.serialize(),
+    };
+    let mut serialized = serialized.map_err(to_storage_error)?;
+    let mut res = Vec::with_capacity(serialized.len() + 2);
+    res.push(ChannelPrefix::get_prefix(channel));
+    if let Channel::Signed(s) = channel {
+        res.push(SignedChannelPrefix::get_prefix(&s.state.get_type()))
+    }
+    res.append(&mut serialized);
+    Ok(res)
+}
+
+pub fn deserialize_channel(buff: &[u8]) -> Result<Channel, Error> {
+    let mut cursor = ::lightning::io::Cursor::new(buff);
+    let mut prefix = [0u8; 1];
+    cursor.read_exact(&mut prefix)?;
+    let channel_prefix: ChannelPrefix = prefix[0].try_into()?;
+    let channel = match channel_prefix {
+        ChannelPrefix::Offered => {
+            Channel::Offered(OfferedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::Accepted => {
+            Channel::Accepted(AcceptedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::Signed => {
+            // Skip the channel state prefix.
+            cursor.set_position(cursor.position() + 1);
+            Channel::Signed(SignedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::FailedAccept => {
+            Channel::FailedAccept(FailedAccept::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::FailedSign => {
+            Channel::FailedSign(FailedSign::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::Cancelled => {
+            Channel::Cancelled(OfferedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::Closed => {
+            Channel::Closed(ClosedChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::Closing => {
+            Channel::Closing(ClosingChannel::deserialize(&mut cursor).map_err(to_storage_error)?)
+        }
+        ChannelPrefix::CounterClosed => Channel::CounterClosed(
+            ClosedChannel::deserialize(&mut cursor).map_err(to_storage_error)?,
+        ),
+        ChannelPrefix::ClosedPunished => Channel::ClosedPunished(
+            ClosedPunishedChannel::deserialize(&mut cursor).map_err(to_storage_error)?,
+        ),
+        ChannelPrefix::CollaborativelyClosed => Channel::CollaborativelyClosed(
+            ClosedChannel::deserialize(&mut cursor).map_err(to_storage_error)?,
+        ),
+    };
+    Ok(channel)
+}
+
 pub fn message_variant_name(message: &Message) -> String {
     let str = match message {
         Message::Accept(_) => "Accept",
@@ -0,0 +1,389 @@
+//! # Channel keys
+//!
+//! A DLC channel asks its signer for fresh secret keys (the own, publish and revocation
+//! basepoints and the per update seed) and later looks the secret up again from the public key
+//! it published. The same lookup is used for the channel's funding key.
+//!
+//! Every key is derived from the wallet seed by [`ContractKeyProvider`]: channel keys from an
+//! increasing index, funding keys from their keys id. The only state is the index from public
+//! key to [`ChannelKeyDerivation`], which is loaded from [`Storage`] when the wallet starts and
+//! written back in the background as keys are handed out.
+//!
+//! Handing out a channel key index twice would reuse its key in two channels, so indexes are
+//! reserved in storage ahead of use, [`CHANNEL_KEY_RESERVATION`] at a time, by writing the entry
+//! of the last reserved index. A restart continues past the reserved indexes even if the entries
+//! of the keys handed out were not written yet, and no key is handed out past the reserved ones.
+//!
+//! If the index is lost, channel keys are recovered from the seed by searching the next
+//! [`CHANNEL_KEY_LOOKAHEAD`] indexes past the last known one.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bitcoin::secp256k1::{All, PublicKey, Secp256k1, SecretKey};
+use ddk_manager::error::Error as ManagerError;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::contract::ContractKeyProvider;
+use crate::error::WalletError;
+use crate::logger::{log_error, Logger, WriteLog};
+use crate::Storage;
+
+/// Number of unused channel key indexes searched past the last known index when a public key is
+/// not in the index.
+pub const CHANNEL_KEY_LOOKAHEAD: u32 = 1_000;
+
+/// Number of channel key indexes reserved in storage ahead of use. More are reserved once half of
+/// them were handed out.
+pub const CHANNEL_KEY_RESERVATION: u32 = 100;
+
+/// How the secret key behind a channel public key is derived from the wallet seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChannelKeyDerivation {
+    /// A basepoint or per update seed, at this index of the channel key path.
+    Channel(u32),
+    /// A funding key, derived from the keys id of its contract.
+    Funding([u8; 32]),
+}
+
+#[derive(Debug, Default)]
+struct ChannelKeyIndex {
+    derivations: HashMap<PublicKey, ChannelKeyDerivation>,
+    next_index: u32,
+    /// Indexes below this one were asked to be reserved.
+    requested: u32,
+}
+
+impl ChannelKeyIndex {
+    fn insert(&mut self, pubkey: PublicKey, derivation: ChannelKeyDerivation) {
+        if let ChannelKeyDerivation::Channel(index) = derivation {
+            self.next_index = self.next_index.max(index.saturating_add(1));
+        }
+        self.derivations.insert(pubkey, derivation);
+    }
+}
+
+/// Hands out and looks up the channel keys of a [`DlcDevKitWallet`](super::DlcDevKitWallet).
+pub(crate) struct ChannelKeyManager {
+    keys: ContractKeyProvider,
+    secp: Secp256k1<All>,
+    index: Mutex<ChannelKeyIndex>,
+    /// Indexes below this one are reserved in storage and can be handed out.
+    reserved: Arc<AtomicU32>,
+    persist: mpsc::UnboundedSender<(PublicKey, ChannelKeyDerivation)>,
+}
+
+impl ChannelKeyManager {
+    /// Loads the key index from storage and starts persisting new entries.
+    pub(crate) async fn load(
+        keys: ContractKeyProvider,
+        storage: Arc<dyn Storage>,
+        logger: Arc<Logger>,
+    ) -> Result<Self, WalletError> {
+        let secp = Secp256k1::new();
+        let mut index = ChannelKeyIndex::default();
+        for (pubkey, derivation) in storage.get_channel_keys().await? {
+            index.insert(pubkey, derivation);
+        }
+
+        // The first indexes are reserved before any key is handed out.
+        let reserved = index.next_index.saturating_add(CHANNEL_KEY_RESERVATION);
+        let last_reserved = keys
+            .channel_secret_key(reserved - 1)
+            .map_err(|e| WalletError::SignerError(e.to_string()))?
+            .public_key(&secp);
+        storage
+            .persist_channel_key(&last_reserved, &ChannelKeyDerivation::Channel(reserved - 1))
+            .await?;
+        index.requested = reserved;
+        let reserved = Arc::new(AtomicU32::new(reserved));
+
+        // Entries are written in the order the keys were handed out.
+        let (persist, mut receiver) = mpsc::unbounded_channel();
+        let reserved_clone = reserved.clone();
+        tokio::spawn(async move {
+            while let Some((pubkey, derivation)) = receiver.recv().await {
+                match storage.persist_channel_key(&pubkey, &derivation).await {
+                    Ok(()) => {
+                        if let ChannelKeyDerivation::Channel(i) = derivation {
+                            reserved_clone.fetch_max(i.saturating_add(1), Ordering::SeqCst);
+                        }
+                    }
+                    Err(e) => log_error!(
+                        logger,
+                        "Could not persist channel key. pubkey={} error={}",
+                        pubkey,
+                        e
+                    ),
+                }
+            }
+        });
+
+        Ok(Self {
+            keys,
+            secp,
+            index: Mutex::new(index),
+            reserved,
+            persist,
+        })
+    }
+
+    /// Derives the next unused channel key. Fails if its index is not reserved in storage yet.
+    pub(crate) fn new_secret_key(&self) -> Result<SecretKey, ManagerError> {
+        let mut index = self.index.lock().unwrap();
+        let reserved = self.reserved.load(Ordering::SeqCst);
+        if index.next_index >= reserved {
+            // Asks again, in case the last reservation could not be written.
+            index.requested = 0;
+            self.reserve_ahead(&mut index)?;
+            return Err(ManagerError::StorageError(format!(
+                "Channel key index {} is not reserved in storage yet.",
+                index.next_index
+            )));
+        }
+        let secret_key = self.channel_secret_key(index.next_index)?;
+        let derivation = ChannelKeyDerivation::Channel(index.next_index);
+        self.record(&mut index, secret_key.public_key(&self.secp), derivation);
+        self.reserve_ahead(&mut index)?;
+        Ok(secret_key)
+    }
+
+    /// Derives the funding key for `keys_id` and records it, so a channel can look it up by its
+    /// public key later.
+    pub(crate) fn funding_secret_key(&self, keys_id: [u8; 32]) -> Result<SecretKey, ManagerError> {
+        let secret_key = self
+            .keys
+            .funding_secret_key_for_keys_id(keys_id)
+            .map_err(|e| ManagerError::InvalidParameters(e.to_string()))?;
+        let pubkey = secret_key.public_key(&self.secp);
+        let mut index = self.index.lock().unwrap();
+        if !index.derivations.contains_key(&pubkey) {
+            self.record(&mut index, pubkey, ChannelKeyDerivation::Funding(keys_id));
+        }
+        Ok(secret_key)
+    }
+
+    /// Looks up the secret key for a channel or funding public key.
+    pub(crate) fn secret_key_for_pubkey(
+        &self,
+        pubkey: &PublicKey,
+    ) -> Result<SecretKey, ManagerError> {
+        let derivation = self.index.lock().unwrap().derivations.get(pubkey).copied();
+        match derivation {
+            Some(ChannelKeyDerivation::Channel(i)) => self.channel_secret_key(i),
+            Some(ChannelKeyDerivation::Funding(keys_id)) => self
+                .keys
+                .funding_secret_key_for_keys_id(keys_id)
+                .map_err(|e| ManagerError::InvalidParameters(e.to_string())),
+            None => self.recover(pubkey),
+        }
+    }
+
+    /// Adds derivations restored from a backup to the index. They are already
+    /// in storage, so they are not persisted again.
+    pub(crate) fn restore(
+        &self,
+        derivations: &[(PublicKey, ChannelKeyDerivation)],
+    ) -> Result<(), ManagerError> {
+        let mut index = self.index.lock().unwrap();
+        for (pubkey, derivation) in derivations {
+            index.insert(*pubkey, *derivation);
+        }
+        self.reserved.fetch_max(index.next_index, Ordering::SeqCst);
+        self.reserve_ahead(&mut index)
+    }
+
+    /// Searches the channel key path for a key missing from the index. The index is not locked
+    /// during the search, so keys can still be handed out.
+    fn recover(&self, pubkey: &PublicKey) -> Result<SecretKey, ManagerError> {
+        let end = self
+            .index
+            .lock()
+            .unwrap()
+            .next_index
+            .saturating_add(CHANNEL_KEY_LOOKAHEAD);
+        for i in 0..end {
+            let derivation = ChannelKeyDerivation::Channel(i);
+            let secret_key = self.channel_secret_key(i)?;
+            if secret_key.public_key(&self.secp) == *pubkey {
+                self.record(&mut self.index.lock().unwrap(), *pubkey, derivation);
+                return Ok(secret_key);
+            }
+        }
+        Err(ManagerError::InvalidParameters(format!(
+            "No channel key found for pubkey {pubkey}."
+        )))
+    }
+
+    fn record(
+        &self,
+        index: &mut ChannelKeyIndex,
+        pubkey: PublicKey,
+        derivation: ChannelKeyDerivation,
+    ) {
+        index.insert(pubkey, derivation);
+        // The receiver only goes away with the runtime, when nothing is left to persist.
+        let _ = self.persist.send((pubkey, derivation));
+    }
+
+    /// Asks for more indexes to be reserved once fewer than half of [`CHANNEL_KEY_RESERVATION`]
+    /// are left.
+    fn reserve_ahead(&self, index: &mut ChannelKeyIndex) -> Result<(), ManagerError> {
+        if index.requested > index.next_index.saturating_add(CHANNEL_KEY_RESERVATION / 2) {
+            return Ok(());
+        }
+        let reserved = index.next_index.saturating_add(CHANNEL_KEY_RESERVATION);
+        let last_reserved = self.channel_secret_key(reserved - 1)?;
+        index.requested = reserved;
+        let _ = self.persist.send((
+            last_reserved.public_key(&self.secp),
+            ChannelKeyDerivation::Channel(reserved - 1),
+        ));
+        Ok(())
+    }
+
+    fn channel_secret_key(&self, index: u32) -> Result<SecretKey, ManagerError> {
+        self.keys
+            .channel_secret_key(index)
+            .map_err(|e| ManagerError::InvalidParameters(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bitcoin::Network;
+
+    use super::*;
+    use crate::logger::LogLevel;
+    use crate::storage::memory::MemoryStorage;
+
+    const MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn keys() -> ContractKeyProvider {
+        ContractKeyProvider::from_mnemonic(MNEMONIC, None, Network::Regtest).unwrap()
+    }
+
+    fn logger() -> Arc<Logger> {
+        Arc::new(Logger::console("channel_keys".to_string(), LogLevel::Info))
+    }
+
+    async fn wait_for_entries(storage: &MemoryStorage, count: usize) {
+        for _ in 0..100 {
+            if storage.get_channel_keys().await.unwrap().len() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("channel keys were not persisted");
+    }
+
+    #[tokio::test]
+    async fn keys_survive_a_restart() {
+        let secp = Secp256k1::new();
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = ChannelKeyManager::load(keys(), storage.clone(), logger())
+            .await
+            .unwrap();
+        let first = manager.new_secret_key().unwrap();
+        let second = manager.new_secret_key().unwrap();
+        let funding = manager.funding_secret_key([7u8; 32]).unwrap();
+        assert_ne!(first, second);
+        // The reservation, both channel keys and the funding key.
+        wait_for_entries(&storage, 4).await;
+
+        let restarted = ChannelKeyManager::load(keys(), storage.clone(), logger())
+            .await
+            .unwrap();
+        for key in [first, second, funding] {
+            let found = restarted
+                .secret_key_for_pubkey(&key.public_key(&secp))
+                .unwrap();
+            assert_eq!(found, key);
+        }
+        // New keys continue after the persisted ones instead of reusing them.
+        let third = restarted.new_secret_key().unwrap();
+        assert!(![first, second].contains(&third));
+    }
+
+    #[tokio::test]
+    async fn channel_keys_are_recovered_from_the_seed() {
+        let secp = Secp256k1::new();
+        let manager = ChannelKeyManager::load(keys(), Arc::new(MemoryStorage::new()), logger())
+            .await
+            .unwrap();
+        let secret_keys = (0..3)
+            .map(|_| manager.new_secret_key().unwrap())
+            .collect::<Vec<_>>();
+
+        let storage = Arc::new(MemoryStorage::new());
+        let recovered = ChannelKeyManager::load(keys(), storage.clone(), logger())
+            .await
+            .unwrap();
+        let found = recovered
+            .secret_key_for_pubkey(&secret_keys[2].public_key(&secp))
+            .unwrap();
+        assert_eq!(found, secret_keys[2]);
+        // The recovered key is written back to the index, next to the reservation.
+        wait_for_entries(&storage, 2).await;
+
+        let unknown = SecretKey::from_slice(&[1u8; 32]).unwrap();
+        assert!(recovered
+            .secret_key_for_pubkey(&unknown.public_key(&secp))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn unwritten_keys_are_not_handed_out_again_after_a_restart() {
+        let storage = Arc::new(MemoryStorage::new());
+        let manager = ChannelKeyManager::load(keys(), storage.clone(), logger())
+            .await
+            .unwrap();
+        // What storage holds if the process stops before any entry is written in the background.
+        let written = storage.get_channel_keys().await.unwrap();
+        let handed_out = (0..3)
+            .map(|_| manager.new_secret_key().unwrap())
+            .collect::<Vec<_>>();
+
+        let storage = Arc::new(MemoryStorage::new());
+        for (pubkey, derivation) in written {
+            storage
+                .persist_channel_key(&pubkey, &derivation)
+                .await
+                .unwrap();
+        }
+        let restarted = ChannelKeyManager::load(keys(), storage, logger())
+            .await
+            .unwrap();
+        let next = restarted.new_secret_key().unwrap();
+        assert!(!handed_out.contains(&next));
+    }
+
+    #[tokio::test]
+    async fn keys_are_only_handed_out_once_reserved() {
+        let manager = ChannelKeyManager::load(keys(), Arc::new(MemoryStorage::new()), logger())
+            .await
+            .unwrap();
+        for _ in 0..CHANNEL_KEY_RESERVATION {
+            manager.new_secret_key().unwrap();
+        }
+        // The next reservation was not written yet.
+        assert!(manager.new_secret_key().is_err());
+
+        tokio::task::yield_now().await;
+        let mut secret_keys = vec![manager.new_secret_key().unwrap()];
+        for _ in 0..2 * CHANNEL_KEY_RESERVATION {
+            secret_keys.push(manager.new_secret_key().unwrap());
+            tokio::task::yield_now().await;
+        }
+        let distinct = secret_keys
+            .iter()
+            .map(|key| key.secret_bytes())
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(distinct.len(), 2 * CHANNEL_KEY_RESERVATION as usize + 1);
+    }
+}
@@ -4,19 +4,20 @@ use crate::logger::{log_debug, WriteLog};
 use crate::{chain::EsploraClient, logger::Logger};
 use bdk_chain::spk_client::FullScanRequest;
 use bdk_esplora::EsploraAsyncExt;
-use bdk_wallet::{KeychainKind, PersistedWallet, Update};
+use bdk_wallet::{KeychainKind, PersistedWallet, Update, WalletEvent};
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
 type Result<T> = std::result::Result<T, WalletError>;
 
+/// Syncs the wallet and returns the changes the update made to it.
 #[tracing::instrument(skip_all)]
 pub async fn sync(
     wallet: &mut PersistedWallet<WalletStorage>,
     blockchain: &EsploraClient,
     storage: &mut WalletStorage,
     logger: Arc<Logger>,
-) -> Result<()> {
+) -> Result<Vec<WalletEvent>> {
     let block_height = blockchain
         .async_client
         .get_height()
@@ -25,7 +26,7 @@ pub async fn sync(
     let prev_tip = wallet.latest_checkpoint();
 
     if prev_tip.height() == block_height {
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     log_debug!(
@@ -76,10 +77,10 @@ pub async fn sync(
             chain: sync.chain_update,
         }
     };
-    wallet.apply_update(sync_result)?;
+    let events = wallet.apply_update_events(sync_result)?;
     wallet
         .persist_async(storage)
         .await
         .map_err(|e| WalletError::WalletPersistanceError(e.to_string()))?;
-    Ok(())
+    Ok(events)
 }
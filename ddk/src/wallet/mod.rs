@@ -22,13 +22,17 @@
 //! - UTXO management
 
 pub mod address;
+mod channel_keys;
 mod command;
 
+pub use channel_keys::{ChannelKeyDerivation, CHANNEL_KEY_LOOKAHEAD};
+
 use crate::contract::ContractKeyProvider;
 use crate::error::{wallet_err_to_manager_err, WalletError};
 use crate::logger::Logger;
 use crate::logger::{log_error, log_info, WriteLog};
 use crate::wallet::address::AddressGenerator;
+use crate::wallet::channel_keys::ChannelKeyManager;
 use crate::{chain::EsploraClient, Storage};
 use bdk_chain::Balance;
 use bdk_wallet::coin_selection::{
@@ -44,22 +48,22 @@ use bdk_wallet::{
         Address, Network, Txid,
     },
     template::Bip84,
-    AddressInfo, KeychainKind, SignOptions, Wallet,
+    AddressInfo, KeychainKind, SignOptions, Wallet, WalletEvent,
 };
 use bdk_wallet::{Utxo, WeightedUtxo};
 use bitcoin::bip32::Fingerprint;
 use bitcoin::key::rand::thread_rng;
 use bitcoin::Psbt;
 use bitcoin::{secp256k1::SecretKey, Amount, FeeRate, ScriptBuf, Transaction};
+use ddk_manager::events::EVENT_CHANNEL_CAPACITY;
 use ddk_manager::{error::Error as ManagerError, SimpleSigner};
 use lightning::chain::chaininterface::{ConfirmationTarget, FeeEstimator};
-use std::collections::HashMap;
 use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::atomic::AtomicU32;
-use std::sync::{atomic::Ordering, Arc};
+use std::sync::Arc;
 use tokio::sync::{
+    broadcast,
     mpsc::{channel, Sender},
     oneshot,
 };
@@ -189,14 +193,18 @@ pub struct DlcDevKitWallet {
     fingerprint: Fingerprint,
     /// Deterministic derivation of contract funding keys.
     contract_keys: ContractKeyProvider,
+    /// Channel keys and the persisted index to look them up by public key.
+    channel_keys: ChannelKeyManager,
+    /// Esplora client, also the source of fee estimates
+    blockchain: Arc<EsploraClient>,
     /// Function to generate external addresses
     address_generator: Option<Arc<dyn AddressGenerator + Send + Sync>>,
+    /// Changes to the wallet transactions found by a sync
+    events: broadcast::Sender<WalletEvent>,
     /// Logger
     logger: Arc<Logger>,
 }
 
-const MIN_FEERATE: u32 = 253;
-
 impl DlcDevKitWallet {
     /// Creates a new DlcDevKitWallet instance.
     ///
@@ -262,10 +270,16 @@ impl DlcDevKitWallet {
         };
 
         let contract_keys = ContractKeyProvider::from_xprv(xprv);
+        let channel_keys =
+            ChannelKeyManager::load(contract_keys.clone(), storage.0.clone(), logger.clone())
+                .await?;
 
         let (sender, mut receiver) = channel(100);
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
 
+        let wallet_blockchain = blockchain.clone();
         let logger_clone = logger.clone();
+        let events_clone = events.clone();
         tokio::spawn(async move {
             while let Some(command) = receiver.recv().await {
                 match command {
@@ -276,7 +290,12 @@ impl DlcDevKitWallet {
                             &mut storage,
                             logger_clone.clone(),
                         )
-                        .await;
+                        .await
+                        .map(|events| {
+                            for event in events {
+                                let _ = events_clone.send(event);
+                            }
+                        });
                         let _ = sender.send(sync).map_err(|e| {
                             log_error!(logger_clone, "Error sending sync command. error={:?}", e);
                         });
@@ -522,11 +541,37 @@ impl DlcDevKitWallet {
             secp,
             fingerprint,
             contract_keys,
+            channel_keys,
+            blockchain: wallet_blockchain,
             address_generator,
+            events,
             logger,
         })
     }
 
+    /// Subscribes to the changes to the wallet transactions found when the
+    /// wallet syncs.
+    pub fn subscribe(&self) -> broadcast::Receiver<WalletEvent> {
+        self.events.subscribe()
+    }
+
+    /// The key encrypting the static backups of the node, derived from the
+    /// seed.
+    pub(crate) fn backup_key(&self) -> Result<[u8; 32]> {
+        self.contract_keys
+            .backup_key()
+            .map_err(|e| WalletError::SignerError(e.to_string()))
+    }
+
+    /// Adds channel key derivations restored from a backup to the channel key
+    /// index.
+    pub(crate) fn restore_channel_keys(
+        &self,
+        channel_keys: &[(PublicKey, ChannelKeyDerivation)],
+    ) -> std::result::Result<(), ManagerError> {
+        self.channel_keys.restore(channel_keys)
+    }
+
     /// Synchronizes the wallet with the blockchain.
     /// This updates the wallet's UTXO set and transaction history.
     #[tracing::instrument(skip(self))]
@@ -667,13 +712,11 @@ impl DlcDevKitWallet {
 /// Provides fee estimation for DLC operations based on confirmation targets.
 impl FeeEstimator for DlcDevKitWallet {
     /// Returns the estimated fee rate in satoshis per 1000 weight units.
-    /// Used by the DLC manager to estimate fees for funding transactions.
+    /// Served from the Esplora client's cached fee estimates.
     #[tracing::instrument(skip(self))]
     fn get_est_sat_per_1000_weight(&self, confirmation_target: ConfirmationTarget) -> u32 {
-        let fees = fee_estimator();
-        fees.get(&confirmation_target)
-            .unwrap()
-            .load(Ordering::Acquire)
+        self.blockchain
+            .get_est_sat_per_1000_weight(confirmation_target)
     }
 }
 
@@ -700,29 +743,32 @@ impl ddk_manager::ContractSignerProvider for DlcDevKitWallet {
             .derive_signer_key_id(is_offer_party, temp_id)
     }
 
-    /// Creates a contract signer from a key ID by delegating to the
-    /// [`ContractKeyProvider`].
+    /// Creates a contract signer from a key ID with the funding key derived by
+    /// the [`ContractKeyProvider`]. The funding key is recorded in the channel
+    /// key index, since channels look it up by public key.
     #[tracing::instrument(skip(self, key_id))]
     fn derive_contract_signer(
         &self,
         key_id: [u8; 32],
     ) -> std::result::Result<Self::Signer, ManagerError> {
-        self.contract_keys.derive_contract_signer(key_id)
+        Ok(SimpleSigner::new(
+            self.channel_keys.funding_secret_key(key_id)?,
+        ))
     }
 
-    /// Gets a secret key for a given public key.
-    /// Currently unimplemented as it's only used for channel operations.
+    /// Gets the secret key behind a channel basepoint, per update seed, or
+    /// funding public key.
     fn get_secret_key_for_pubkey(
         &self,
-        _pubkey: &PublicKey,
+        pubkey: &PublicKey,
     ) -> std::result::Result<SecretKey, ManagerError> {
-        unreachable!("get_secret_key_for_pubkey is only used in channels.")
+        self.channel_keys.secret_key_for_pubkey(pubkey)
     }
 
-    /// Generates a new secret key.
-    /// Currently unimplemented as it's only used for channel operations.
+    /// Derives the next channel key from the seed and records it in the
+    /// channel key index.
     fn get_new_secret_key(&self) -> std::result::Result<SecretKey, ManagerError> {
-        unreachable!("get_new_secret_key is only used for channels")
+        self.channel_keys.new_secret_key()
     }
 }
 
@@ -868,44 +914,6 @@ impl ddk_manager::Wallet for DlcDevKitWallet {
     }
 }
 
-/// Creates a fee estimator with predefined fee rates for different confirmation targets.
-///
-/// This function sets up fee estimation for different urgency levels:
-/// - High Priority: For immediate confirmation
-/// - Normal: For confirmation within a few blocks  
-/// - Background: For non-urgent transactions
-///
-/// Returns a HashMap mapping confirmation targets to atomic fee rates.
-fn fee_estimator() -> HashMap<ConfirmationTarget, AtomicU32> {
-    let mut fees: HashMap<ConfirmationTarget, AtomicU32> = HashMap::new();
-    fees.insert(ConfirmationTarget::UrgentOnChainSweep, AtomicU32::new(5000));
-    fees.insert(
-        ConfirmationTarget::MinAllowedAnchorChannelRemoteFee,
-        AtomicU32::new(25 * 250),
-    );
-    fees.insert(
-        ConfirmationTarget::MinAllowedAnchorChannelRemoteFee,
-        AtomicU32::new(MIN_FEERATE),
-    );
-    fees.insert(
-        ConfirmationTarget::MinAllowedNonAnchorChannelRemoteFee,
-        AtomicU32::new(MIN_FEERATE),
-    );
-    fees.insert(
-        ConfirmationTarget::AnchorChannelFee,
-        AtomicU32::new(MIN_FEERATE),
-    );
-    fees.insert(
-        ConfirmationTarget::NonAnchorChannelFee,
-        AtomicU32::new(2000),
-    );
-    fees.insert(
-        ConfirmationTarget::ChannelCloseMinimum,
-        AtomicU32::new(MIN_FEERATE),
-    );
-    fees
-}
-
 impl Debug for DlcDevKitWallet {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "DlcDevKitWallet")?;
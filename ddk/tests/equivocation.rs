@@ -0,0 +1,204 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bitcoin::key::rand::{thread_rng, Fill};
+use bitcoin::secp256k1::{Keypair, Secp256k1, SecretKey};
+use bitcoin::{Network, XOnlyPublicKey};
+use ddk::builder::{Builder, SeedConfig};
+use ddk::logger::{LogLevel, Logger};
+use ddk::oracle::set::OracleSet;
+use ddk::storage::memory::MemoryStorage;
+use ddk::transport::memory::MemoryTransport;
+use ddk::util::ser::deserialize_contract;
+use ddk::DlcDevKit;
+use ddk_dlc::secp_utils::schnorrsig_sign_with_nonce;
+use ddk_manager::contract::Contract;
+use ddk_manager::error::Error as ManagerError;
+use ddk_manager::events::Event;
+use ddk_manager::{Oracle as _, Storage as _};
+use ddk_messages::oracle_msgs::{
+    tagged_announcement_msg, tagged_attestation_msg, OracleAnnouncement, OracleAttestation,
+    OracleEvent,
+};
+
+/// One transport to an oracle, answering with a fixed attestation.
+struct Transport {
+    name: &'static str,
+    announcement: OracleAnnouncement,
+    attestation: OracleAttestation,
+}
+
+#[async_trait::async_trait]
+impl ddk_manager::Oracle for Transport {
+    fn get_public_key(&self) -> XOnlyPublicKey {
+        self.announcement.oracle_public_key
+    }
+
+    async fn get_announcement(&self, _: &str) -> Result<OracleAnnouncement, ManagerError> {
+        Ok(self.announcement.clone())
+    }
+
+    async fn get_attestation(&self, _: &str) -> Result<OracleAttestation, ManagerError> {
+        Ok(self.attestation.clone())
+    }
+}
+
+impl ddk::Oracle for Transport {
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+/// An oracle that signs a different outcome for each transport with the
+/// announced nonces.
+struct EquivocatingOracle {
+    keypair: Keypair,
+    nonces: Vec<SecretKey>,
+    announcement: OracleAnnouncement,
+}
+
+impl EquivocatingOracle {
+    /// Announces `event` again, under the key and nonces of the oracle.
+    fn new(mut event: OracleEvent) -> Self {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::new(&secp, &mut thread_rng());
+        let nonces = event
+            .oracle_nonces
+            .iter()
+            .map(|_| SecretKey::new(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        event.oracle_nonces = nonces
+            .iter()
+            .map(|nonce| nonce.x_only_public_key(&secp).0)
+            .collect();
+        let announcement = OracleAnnouncement {
+            announcement_signature: secp.sign_schnorr(&tagged_announcement_msg(&event), &keypair),
+            oracle_public_key: keypair.x_only_public_key().0,
+            oracle_event: event,
+        };
+        EquivocatingOracle {
+            keypair,
+            nonces,
+            announcement,
+        }
+    }
+
+    /// Attests to `first_digit` followed by zeros.
+    fn attest(&self, first_digit: &str) -> OracleAttestation {
+        let secp = Secp256k1::new();
+        let outcomes = (0..self.nonces.len())
+            .map(|i| if i == 0 { first_digit } else { "0" }.to_string())
+            .collect::<Vec<_>>();
+        OracleAttestation {
+            event_id: self.announcement.oracle_event.event_id.clone(),
+            oracle_public_key: self.announcement.oracle_public_key,
+            signatures: outcomes
+                .iter()
+                .zip(self.nonces.iter())
+                .map(|(outcome, nonce)| {
+                    schnorrsig_sign_with_nonce(
+                        &secp,
+                        &tagged_attestation_msg(outcome),
+                        &self.keypair,
+                        &nonce.secret_bytes(),
+                    )
+                })
+                .collect(),
+            outcomes,
+        }
+    }
+
+    fn transport(&self, name: &'static str, first_digit: &str) -> Arc<dyn ddk::Oracle> {
+        Arc::new(Transport {
+            name,
+            announcement: self.announcement.clone(),
+            attestation: self.attest(first_digit),
+        })
+    }
+}
+
+#[tokio::test]
+async fn conflicting_attestations_over_two_transports_freeze_the_oracle() {
+    let Contract::Offered(mut offered) = deserialize_contract(
+        &include_bytes!("../../testconfig/contract_binaries/Offered").to_vec(),
+    )
+    .unwrap() else {
+        panic!("Contract is not an OfferedContract");
+    };
+    let oracle = EquivocatingOracle::new(
+        offered.contract_info[0].oracle_announcements[0]
+            .oracle_event
+            .clone(),
+    );
+    offered.contract_info[0].oracle_announcements[0] = oracle.announcement.clone();
+    let pubkey = oracle.announcement.oracle_public_key;
+    let event_id = oracle.announcement.oracle_event.event_id.clone();
+
+    let oracles = Arc::new(
+        OracleSet::new(vec![
+            oracle.transport("http", "0"),
+            oracle.transport("nostr", "1"),
+        ])
+        .unwrap(),
+    );
+    let logger = Arc::new(Logger::console(
+        "equivocation-test".to_string(),
+        LogLevel::Info,
+    ));
+    let mut seed = [0u8; 64];
+    seed.try_fill(&mut thread_rng()).unwrap();
+    let ddk: DlcDevKit<MemoryTransport, MemoryStorage, OracleSet> = Builder::new()
+        .set_network(Network::Regtest)
+        .set_seed_bytes(SeedConfig::Bytes(seed))
+        .unwrap()
+        .set_esplora_host(ddk_testenv::env().esplora_host().to_string())
+        .set_name("equivocation")
+        .set_oracle(oracles.clone())
+        .set_transport(Arc::new(MemoryTransport::new(
+            &Secp256k1::new(),
+            logger.clone(),
+        )))
+        .set_storage(Arc::new(MemoryStorage::new()))
+        .set_logger(logger)
+        .finish()
+        .await
+        .unwrap();
+    ddk.storage.create_contract(&offered).await.unwrap();
+    let mut events = ddk.subscribe();
+
+    // What the manager does once the event matures: the first response is
+    // used, and both are forwarded.
+    let attestation = oracles
+        .route(pubkey)
+        .unwrap()
+        .get_attestation(&event_id)
+        .await
+        .unwrap();
+    assert!([oracle.attest("0"), oracle.attest("1")].contains(&attestation));
+
+    let (contract_ids, fraud_proof) = tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let Event::OracleEquivocation {
+                contract_ids,
+                fraud_proof,
+            } = events.recv().await.unwrap()
+            {
+                return (contract_ids, fraud_proof);
+            }
+        }
+    })
+    .await
+    .expect("the equivocation is reported");
+    assert_eq!(contract_ids, vec![offered.id]);
+    assert_eq!(fraud_proof.oracle_public_key(), pubkey);
+    assert_eq!(fraud_proof.nonce_index, 0);
+    assert_eq!(
+        fraud_proof
+            .oracle_secret_key
+            .x_only_public_key(&Secp256k1::new())
+            .0,
+        pubkey
+    );
+    fraud_proof.verify(&Secp256k1::new()).unwrap();
+    assert_eq!(ddk.fraud_proofs().await, vec![*fraud_proof]);
+}
@@ -10,12 +10,17 @@ use bitcoin::psbt::Psbt;
 use bitcoin::transaction::Version;
 use bitcoin::{Amount, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
 use ddk::contract::{
-    accept_offer, chain_hash_from_network, create_dlc_splice_input, create_dlc_transactions,
-    create_funding_psbt, create_offer, finalize_sign, finalize_sign_spliced, funding_input,
-    sign_accept, sign_accept_spliced, sign_cet, sign_refund, signing, AcceptOfferParams,
-    ContractError, CreateOfferParams, DescriptorInput, DlcInputSigningKey, InputDerivation, Party,
-    PartyParams, DLC_INPUT_MAX_WITNESS_LEN,
+    accept_batch_offer, accept_offer, chain_hash_from_network, create_batch_dlc_transactions,
+    create_batch_funding_psbt, create_batch_offer, create_dlc_splice_input,
+    create_dlc_transactions, create_funding_psbt, create_offer, create_taproot_offer,
+    finalize_batch_sign, finalize_sign, finalize_sign_spliced, funding_input, sign_accept,
+    sign_accept_spliced, sign_batch_accept, sign_batch_cet, sign_batch_refund, sign_cet,
+    sign_refund, sign_refund_leaf, sign_taproot_accept, signing, AcceptOfferParams, ContractError,
+    CreateOfferParams, DescriptorInput, DlcInputSigningKey, InputDerivation, OfferNonces, Party,
+    PartyParams, SignResult, DLC_INPUT_MAX_WITNESS_LEN,
 };
+use ddk::ddk_messages::{impl_dlc_tlv_record, impl_dlc_writeable};
+use ddk::lightning::util::ser::{Readable, Writeable};
 use ddk_dlc::secp256k1_zkp::{All, Keypair, PublicKey, Secp256k1, SecretKey, XOnlyPublicKey};
 use ddk_messages::contract_msgs::{
     ContractDescriptor, ContractInfo, ContractInfoInner, ContractOutcome,
@@ -312,6 +317,34 @@ fn fund_with_xpriv(
     accepter: &PartySetup,
     offer: &OfferDlc,
     accept: &AcceptDlc,
+) -> (SignDlc, Transaction) {
+    fund_with_signer(offerer, accepter, offer, accept, |psbt| {
+        sign_accept(offer, accept, &offerer.funding_secret_key, psbt)
+    })
+}
+
+/// Like [`fund_with_xpriv`], for a Taproot funded offer signed with its
+/// nonces.
+fn fund_taproot_with_xpriv(
+    offerer: &PartySetup,
+    accepter: &PartySetup,
+    offer: &OfferDlc,
+    nonces: &mut OfferNonces,
+    accept: &AcceptDlc,
+) -> (SignDlc, Transaction) {
+    fund_with_signer(offerer, accepter, offer, accept, |psbt| {
+        sign_taproot_accept(offer, accept, &offerer.funding_secret_key, nonces, psbt)
+    })
+}
+
+/// Signs both sides of the funding PSBT with xpriv keys, creating the sign
+/// message with `sign_accept`.
+fn fund_with_signer(
+    offerer: &PartySetup,
+    accepter: &PartySetup,
+    offer: &OfferDlc,
+    accept: &AcceptDlc,
+    sign_accept: impl FnOnce(&Psbt) -> Result<SignResult, ContractError>,
 ) -> (SignDlc, Transaction) {
     let mut offer_psbt = create_funding_psbt(offer, accept).unwrap();
     signing::sign_funding_psbt_with_xpriv(
@@ -322,7 +355,7 @@ fn fund_with_xpriv(
         &offerer.derivations(),
     )
     .unwrap();
-    let sign_result = sign_accept(offer, accept, &offerer.funding_secret_key, &offer_psbt).unwrap();
+    let sign_result = sign_accept(&offer_psbt).unwrap();
 
     let mut accept_psbt = create_funding_psbt(offer, accept).unwrap();
     signing::sign_funding_psbt_with_xpriv(
@@ -389,6 +422,70 @@ fn enum_lifecycle_with_xpriv_signing() {
     complete_with_xpriv(&secp, &offerer, &accepter, &offer, &accept);
 }
 
+/// An application record, defined with nothing but what `ddk` re-exports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LoanRef {
+    loan_id: [u8; 16],
+    lender: String,
+}
+
+const LOAN_REF_TYPE: u16 = 65003;
+
+impl_dlc_writeable!(LoanRef, { (loan_id, writeable), (lender, string) });
+impl_dlc_tlv_record!(LoanRef, LOAN_REF_TYPE);
+
+/// Sends a message over the wire and back.
+fn over_the_wire<T: Writeable + Readable>(message: &T) -> T {
+    T::read(&mut &message.encode()[..]).unwrap()
+}
+
+#[test]
+fn application_records_travel_with_the_messages() {
+    let secp = Secp256k1::new();
+    let offerer = PartySetup::new(&secp, 1, NETWORK, Amount::from_sat(150_000), 1);
+    let accepter = PartySetup::new(&secp, 2, NETWORK, Amount::from_sat(150_000), 2);
+    let loan_ref = LoanRef {
+        loan_id: [7; 16],
+        lender: "bank".to_string(),
+    };
+
+    let mut offer = create_offer(offer_params(
+        &secp,
+        &offerer,
+        enum_contract_info(TOTAL_COLLATERAL),
+        Amount::from_sat(50_000),
+        NETWORK,
+        vec![offerer.funding_input.clone()],
+    ))
+    .unwrap();
+    offer.tlvs.set(&loan_ref);
+    let offer = over_the_wire(&offer);
+    assert_eq!(offer.tlvs.get::<LoanRef>().unwrap(), Some(loan_ref.clone()));
+
+    let mut accept = accept_offer(
+        &offer,
+        AcceptOfferParams {
+            party: accepter.party_params(&secp, vec![accepter.funding_input.clone()]),
+            min_timeout_interval: MIN_TIMEOUT,
+            max_timeout_interval: MAX_TIMEOUT,
+        },
+        &accepter.funding_secret_key,
+    )
+    .unwrap()
+    .accept;
+    accept.tlvs.set(&loan_ref);
+    let accept = over_the_wire(&accept);
+
+    let (mut sign, _) = fund_with_xpriv(&secp, &offerer, &accepter, &offer, &accept);
+    sign.tlvs.set(&loan_ref);
+    let sign = over_the_wire(&sign);
+    assert_eq!(
+        accept.tlvs.get::<LoanRef>().unwrap(),
+        Some(loan_ref.clone())
+    );
+    assert_eq!(sign.tlvs.get::<LoanRef>().unwrap(), Some(loan_ref));
+}
+
 #[test]
 fn numerical_lifecycle_with_xpriv_signing() {
     let secp = Secp256k1::new();
@@ -1705,3 +1802,509 @@ fn finalize_sign_spliced_rejects_a_tampered_offer_half() {
         Err(ContractError::InvalidSign(_))
     ));
 }
+
+/// Accepts a Taproot funded offer with the accepting party's single funding
+/// input.
+fn accept_taproot_offer(
+    secp: &Secp256k1<All>,
+    accepter: &PartySetup,
+    offer: &OfferDlc,
+) -> AcceptDlc {
+    accept_offer(
+        offer,
+        AcceptOfferParams {
+            party: accepter.party_params(secp, vec![accepter.funding_input.clone()]),
+            min_timeout_interval: MIN_TIMEOUT,
+            max_timeout_interval: MAX_TIMEOUT,
+        },
+        &accepter.funding_secret_key,
+    )
+    .unwrap()
+    .accept
+}
+
+/// Builds a Taproot funded enum contract offer/accept pair with one funding
+/// input per party, along with the offering party's secret nonces.
+fn taproot_enum_contract(
+    secp: &Secp256k1<All>,
+) -> (PartySetup, PartySetup, OfferDlc, OfferNonces, AcceptDlc) {
+    let offerer = PartySetup::new(secp, 21, NETWORK, Amount::from_sat(150_000), 1);
+    let accepter = PartySetup::new(secp, 22, NETWORK, Amount::from_sat(150_000), 2);
+    let (offer, nonces) = create_taproot_offer(
+        offer_params(
+            secp,
+            &offerer,
+            enum_contract_info(TOTAL_COLLATERAL),
+            Amount::from_sat(50_000),
+            NETWORK,
+            vec![offerer.funding_input.clone()],
+        ),
+        &offerer.funding_secret_key,
+    )
+    .unwrap();
+    let accept = accept_taproot_offer(secp, &accepter, &offer);
+    (offerer, accepter, offer, nonces, accept)
+}
+
+/// Checks that a settlement transaction spends the Taproot funding output
+/// through the key path.
+fn assert_key_path_spend(settlement: &Transaction, funding_transaction: &Transaction, vout: usize) {
+    assert!(funding_transaction.output[vout].script_pubkey.is_p2tr());
+    assert_eq!(
+        settlement.input[0].previous_output,
+        OutPoint {
+            txid: funding_transaction.compute_txid(),
+            vout: vout as u32,
+        }
+    );
+    let witness = &settlement.input[0].witness;
+    assert_eq!(witness.len(), 1, "expected a key path witness");
+    assert_eq!(witness[0].len(), 64, "expected a default sighash signature");
+}
+
+#[test]
+fn taproot_contract_completes_and_settles() {
+    let secp = Secp256k1::new();
+    let (offerer, accepter, offer, mut nonces, accept) = taproot_enum_contract(&secp);
+    assert!(offer.is_taproot());
+    assert!(accept.taproot.is_some());
+    assert!(accept
+        .cet_adaptor_signatures
+        .ecdsa_adaptor_signatures
+        .is_empty());
+
+    let (sign, funding_transaction) =
+        fund_taproot_with_xpriv(&offerer, &accepter, &offer, &mut nonces, &accept);
+    assert!(sign.taproot.is_some());
+    let vout = create_dlc_transactions(&offer, &accept)
+        .unwrap()
+        .get_fund_output_index();
+
+    let attestations = vec![(0, oracle_attestation(vec!["down".to_string()]))];
+    let cet = sign_cet(
+        &offer,
+        &accept,
+        &sign,
+        &offerer.funding_secret_key,
+        &attestations,
+    )
+    .unwrap();
+    assert_key_path_spend(&cet, &funding_transaction, vout);
+    assert_eq!(cet.output.len(), 1);
+    assert_eq!(cet.output[0].script_pubkey, accept.payout_spk);
+    let counterpart = sign_cet(
+        &offer,
+        &accept,
+        &sign,
+        &accepter.funding_secret_key,
+        &attestations,
+    )
+    .unwrap();
+    assert_eq!(cet, counterpart);
+
+    let refund = sign_refund(&offer, &accept, &sign, &accepter.funding_secret_key).unwrap();
+    assert_key_path_spend(&refund, &funding_transaction, vout);
+    assert_eq!(refund.output.len(), 2);
+}
+
+#[test]
+fn taproot_refund_spends_the_refund_leaf() {
+    use bitcoin::hashes::Hash;
+    use bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
+    use bitcoin::taproot::{ControlBlock, LeafVersion, TapLeafHash};
+
+    let secp = Secp256k1::new();
+    let (offerer, accepter, offer, mut nonces, accept) = taproot_enum_contract(&secp);
+    let (sign, funding_transaction) =
+        fund_taproot_with_xpriv(&offerer, &accepter, &offer, &mut nonces, &accept);
+    // Each party only keeps the messages it received.
+    let (offer, accept, sign) = (
+        over_the_wire(&offer),
+        over_the_wire(&accept),
+        over_the_wire(&sign),
+    );
+    let transactions = create_dlc_transactions(&offer, &accept).unwrap();
+    let fund_output = transactions.get_fund_output();
+
+    let refund = sign_refund_leaf(&offer, &accept, &sign).unwrap();
+    assert_eq!(refund.lock_time.to_consensus_u32(), offer.refund_locktime);
+    assert_eq!(
+        refund.input[0].previous_output,
+        OutPoint {
+            txid: funding_transaction.compute_txid(),
+            vout: transactions.get_fund_output_index() as u32,
+        }
+    );
+    let witness = &refund.input[0].witness;
+    assert_eq!(witness.len(), 4, "expected a script path witness");
+    let leaf = ScriptBuf::from_bytes(witness[2].to_vec());
+    let control_block = ControlBlock::decode(&witness[3]).unwrap();
+    let output_key =
+        XOnlyPublicKey::from_slice(&fund_output.script_pubkey.as_bytes()[2..]).unwrap();
+    assert!(control_block.verify_taproot_commitment(&secp, output_key, &leaf));
+
+    let sighash = SighashCache::new(&refund)
+        .taproot_script_spend_signature_hash(
+            0,
+            &Prevouts::All(&[fund_output]),
+            TapLeafHash::from_script(&leaf, LeafVersion::TapScript),
+            TapSighashType::Default,
+        )
+        .unwrap();
+    let message = ddk_dlc::secp256k1_zkp::Message::from_digest(sighash.to_byte_array());
+    // The leaf checks the lower funding key first, so its signature is on top
+    // of the stack.
+    let mut keys = [offer.funding_pubkey, accept.funding_pubkey];
+    keys.sort_by_key(|key| key.serialize());
+    for (element, key) in [(&witness[1], keys[0]), (&witness[0], keys[1])] {
+        let signature = ddk_dlc::secp256k1_zkp::schnorr::Signature::from_slice(element).unwrap();
+        secp.verify_schnorr(&signature, &message, &key.x_only_public_key().0)
+            .unwrap();
+    }
+
+    // A leaf signature of the wrong party is refused.
+    let mut tampered = accept.clone();
+    tampered.taproot.as_mut().unwrap().refund_leaf_signature =
+        sign.taproot.as_ref().unwrap().refund_leaf_signature;
+    assert!(sign_refund_leaf(&offer, &tampered, &sign).is_err());
+}
+
+#[test]
+fn taproot_offers_require_their_nonces() {
+    let secp = Secp256k1::new();
+    let offerer = PartySetup::new(&secp, 23, NETWORK, Amount::from_sat(150_000), 1);
+    let mut params = offer_params(
+        &secp,
+        &offerer,
+        enum_contract_info(TOTAL_COLLATERAL),
+        Amount::from_sat(50_000),
+        NETWORK,
+        vec![offerer.funding_input.clone()],
+    );
+    params.contract_flags = ddk_dlc::TAPROOT_FUNDING_FLAG;
+    assert!(matches!(
+        create_offer(params),
+        Err(ContractError::InvalidOffer(_))
+    ));
+
+    let (_, accepter, mut offer, _, _) = taproot_enum_contract(&secp);
+    offer.taproot = None;
+    assert!(matches!(
+        accept_offer(
+            &offer,
+            AcceptOfferParams {
+                party: accepter.party_params(&secp, vec![accepter.funding_input.clone()]),
+                min_timeout_interval: MIN_TIMEOUT,
+                max_timeout_interval: MAX_TIMEOUT,
+            },
+            &accepter.funding_secret_key,
+        ),
+        Err(ContractError::InvalidOffer(_))
+    ));
+}
+
+#[test]
+fn taproot_offers_sign_a_single_accept() {
+    let secp = Secp256k1::new();
+    let (offerer, accepter, offer, mut nonces, accept) = taproot_enum_contract(&secp);
+    let mut offer_psbt = create_funding_psbt(&offer, &accept).unwrap();
+    signing::sign_funding_psbt_with_xpriv(
+        &offer,
+        &accept,
+        &mut offer_psbt,
+        &offerer.xpriv,
+        &offerer.derivations(),
+    )
+    .unwrap();
+    assert!(matches!(
+        sign_accept(&offer, &accept, &offerer.funding_secret_key, &offer_psbt),
+        Err(ContractError::InvalidOffer(_))
+    ));
+
+    sign_taproot_accept(
+        &offer,
+        &accept,
+        &offerer.funding_secret_key,
+        &mut nonces,
+        &offer_psbt,
+    )
+    .unwrap();
+    assert!(nonces.is_used());
+
+    // A retry of the same accept message is refused.
+    assert!(matches!(
+        sign_taproot_accept(
+            &offer,
+            &accept,
+            &offerer.funding_secret_key,
+            &mut nonces,
+            &offer_psbt,
+        ),
+        Err(ContractError::NoncesAlreadyUsed)
+    ));
+
+    // So is a different accept message, whose nonces change the challenges.
+    let second_accept = accept_taproot_offer(&secp, &accepter, &offer);
+    assert_ne!(second_accept.taproot, accept.taproot);
+    let mut second_psbt = create_funding_psbt(&offer, &second_accept).unwrap();
+    signing::sign_funding_psbt_with_xpriv(
+        &offer,
+        &second_accept,
+        &mut second_psbt,
+        &offerer.xpriv,
+        &offerer.derivations(),
+    )
+    .unwrap();
+    assert!(matches!(
+        sign_taproot_accept(
+            &offer,
+            &second_accept,
+            &offerer.funding_secret_key,
+            &mut nonces,
+            &second_psbt,
+        ),
+        Err(ContractError::NoncesAlreadyUsed)
+    ));
+
+    // The used state survives serialization.
+    let restored = OfferNonces::read(&mut nonces.encode().as_slice()).unwrap();
+    assert!(restored.is_used());
+}
+
+#[test]
+fn invalid_taproot_partial_signatures_are_rejected() {
+    let secp = Secp256k1::new();
+    let (offerer, accepter, offer, mut nonces, accept) = taproot_enum_contract(&secp);
+    let (sign, _) = fund_taproot_with_xpriv(&offerer, &accepter, &offer, &mut nonces, &accept);
+
+    let mut tampered_sign = sign.clone();
+    tampered_sign
+        .taproot
+        .as_mut()
+        .unwrap()
+        .cet_partial_signatures
+        .swap(0, 1);
+    let mut accept_psbt = create_funding_psbt(&offer, &accept).unwrap();
+    signing::sign_funding_psbt_with_xpriv(
+        &offer,
+        &accept,
+        &mut accept_psbt,
+        &accepter.xpriv,
+        &accepter.derivations(),
+    )
+    .unwrap();
+    assert!(matches!(
+        finalize_sign(&offer, &accept, &tampered_sign, &accept_psbt),
+        Err(ContractError::InvalidSign(_))
+    ));
+
+    // Invalid accept messages are refused before the nonces are used.
+    let (offerer, _, offer, mut nonces, mut accept) = taproot_enum_contract(&secp);
+    let mut offer_psbt = create_funding_psbt(&offer, &accept).unwrap();
+    signing::sign_funding_psbt_with_xpriv(
+        &offer,
+        &accept,
+        &mut offer_psbt,
+        &offerer.xpriv,
+        &offerer.derivations(),
+    )
+    .unwrap();
+    let record = accept.taproot.as_mut().unwrap();
+    record.refund_partial_signature = record.cet_partial_signatures[0];
+    assert!(matches!(
+        sign_taproot_accept(
+            &offer,
+            &accept,
+            &offerer.funding_secret_key,
+            &mut nonces,
+            &offer_psbt,
+        ),
+        Err(ContractError::InvalidAccept(_))
+    ));
+    assert!(!nonces.is_used());
+}
+
+/// Builds a batch of two enum contracts funded by one funding input per party,
+/// each contract with its own DLC funding keys.
+fn enum_batch(
+    secp: &Secp256k1<All>,
+) -> (
+    PartySetup,
+    PartySetup,
+    Vec<SecretKey>,
+    Vec<SecretKey>,
+    Vec<OfferDlc>,
+    Vec<AcceptDlc>,
+) {
+    let offerer = PartySetup::new(secp, 1, NETWORK, Amount::from_sat(300_000), 1);
+    let accepter = PartySetup::new(secp, 2, NETWORK, Amount::from_sat(300_000), 2);
+    let offer_keys = vec![
+        offerer.funding_secret_key,
+        SecretKey::from_slice(&[11; 32]).unwrap(),
+    ];
+    let accept_keys = vec![
+        accepter.funding_secret_key,
+        SecretKey::from_slice(&[12; 32]).unwrap(),
+    ];
+
+    let offers = create_batch_offer(
+        offer_keys
+            .iter()
+            .map(|key| {
+                let mut params = offer_params(
+                    secp,
+                    &offerer,
+                    enum_contract_info(TOTAL_COLLATERAL),
+                    Amount::from_sat(50_000),
+                    NETWORK,
+                    vec![offerer.funding_input.clone()],
+                );
+                params.party.funding_pubkey = key.public_key(secp);
+                params
+            })
+            .collect(),
+    )
+    .unwrap();
+    let offers = offers.iter().map(over_the_wire).collect::<Vec<_>>();
+
+    let accept_result = accept_batch_offer(
+        &offers,
+        accept_keys
+            .iter()
+            .map(|key| {
+                let mut party = accepter.party_params(secp, vec![accepter.funding_input.clone()]);
+                party.funding_pubkey = key.public_key(secp);
+                AcceptOfferParams {
+                    party,
+                    min_timeout_interval: MIN_TIMEOUT,
+                    max_timeout_interval: MAX_TIMEOUT,
+                }
+            })
+            .collect(),
+        &accept_keys,
+    )
+    .unwrap();
+    let accepts = accept_result
+        .accepts
+        .iter()
+        .map(over_the_wire)
+        .collect::<Vec<_>>();
+    (offerer, accepter, offer_keys, accept_keys, offers, accepts)
+}
+
+#[test]
+fn batch_contracts_share_one_funding_transaction() {
+    let secp = Secp256k1::new();
+    let (offerer, accepter, offer_keys, accept_keys, offers, accepts) = enum_batch(&secp);
+
+    let mut offer_psbt = create_batch_funding_psbt(&offers, &accepts).unwrap();
+    signing::sign_funding_psbt_with_xpriv(
+        &offers[0],
+        &accepts[0],
+        &mut offer_psbt,
+        &offerer.xpriv,
+        &offerer.derivations(),
+    )
+    .unwrap();
+    let sign_result = sign_batch_accept(&offers, &accepts, &offer_keys, &offer_psbt).unwrap();
+    let signs = sign_result
+        .signs
+        .iter()
+        .map(over_the_wire)
+        .collect::<Vec<_>>();
+
+    let mut accept_psbt = create_batch_funding_psbt(&offers, &accepts).unwrap();
+    signing::sign_funding_psbt_with_xpriv(
+        &offers[1],
+        &accepts[1],
+        &mut accept_psbt,
+        &accepter.xpriv,
+        &accepter.derivations(),
+    )
+    .unwrap();
+    let funding_transaction = finalize_batch_sign(&offers, &accepts, &signs, &accept_psbt).unwrap();
+
+    // Two funding outputs and two change outputs.
+    assert_eq!(funding_transaction.input.len(), 2);
+    assert_eq!(funding_transaction.output.len(), 4);
+    let transactions = create_batch_dlc_transactions(&offers, &accepts).unwrap();
+    assert_ne!(
+        transactions[0].get_fund_output_index(),
+        transactions[1].get_fund_output_index()
+    );
+
+    let attestations = vec![(0, oracle_attestation(vec!["up".to_string()]))];
+    for (index, transactions) in transactions.iter().enumerate() {
+        assert_eq!(
+            transactions.fund.compute_txid(),
+            funding_transaction.compute_txid()
+        );
+        let fund_outpoint = OutPoint {
+            txid: funding_transaction.compute_txid(),
+            vout: transactions.get_fund_output_index() as u32,
+        };
+        let cet = sign_batch_cet(
+            &offers,
+            &accepts,
+            &signs,
+            index,
+            &offer_keys[index],
+            &attestations,
+        )
+        .unwrap();
+        assert_eq!(cet.input[0].previous_output, fund_outpoint);
+        assert_eq!(cet.output[0].script_pubkey, offers[index].payout_spk);
+        let refund =
+            sign_batch_refund(&offers, &accepts, &signs, index, &accept_keys[index]).unwrap();
+        assert_eq!(refund.input[0].previous_output, fund_outpoint);
+    }
+}
+
+#[test]
+fn batch_offers_are_rejected_by_the_single_contract_functions() {
+    let secp = Secp256k1::new();
+    let (_, accepter, _, _, offers, accepts) = enum_batch(&secp);
+
+    assert!(matches!(
+        create_funding_psbt(&offers[0], &accepts[0]),
+        Err(ContractError::InvalidOffer(_))
+    ));
+    assert!(matches!(
+        accept_offer(
+            &offers[0],
+            AcceptOfferParams {
+                party: accepter.party_params(&secp, vec![accepter.funding_input.clone()]),
+                min_timeout_interval: MIN_TIMEOUT,
+                max_timeout_interval: MAX_TIMEOUT,
+            },
+            &accepter.funding_secret_key,
+        ),
+        Err(ContractError::InvalidOffer(_))
+    ));
+    // Every contract of the batch is needed to rebuild the funding transaction.
+    assert!(create_batch_dlc_transactions(&offers[..1], &accepts[..1]).is_err());
+}
+
+#[test]
+fn batch_offers_must_share_their_funding_inputs() {
+    let secp = Secp256k1::new();
+    let offerer = PartySetup::new(&secp, 1, NETWORK, Amount::from_sat(300_000), 1);
+    let other = PartySetup::new(&secp, 3, NETWORK, Amount::from_sat(300_000), 3);
+    let params = [&offerer, &other]
+        .into_iter()
+        .map(|party| {
+            offer_params(
+                &secp,
+                &offerer,
+                enum_contract_info(TOTAL_COLLATERAL),
+                Amount::from_sat(50_000),
+                NETWORK,
+                vec![party.funding_input.clone()],
+            )
+        })
+        .collect();
+    assert!(matches!(
+        create_batch_offer(params),
+        Err(ContractError::InvalidOffer(_))
+    ));
+}
@@ -0,0 +1,164 @@
+//! The record tying together the offers of contracts funded by a single fund
+//! transaction.
+//!
+//! Each [`OfferDlc`](crate::OfferDlc) of a batch carries a
+//! [`BatchFundingGroup`] listing the temporary ids of all the offers of the
+//! batch, itself included. The offers share the funding inputs, change script
+//! pubkey and change serial id of the offer party, and the accept party
+//! answers each of them with the same inputs and change of its own.
+//!
+//! The fields are those of the batch funding group proposed in
+//! dlcspecs PR #163. The encoding was not checked against other
+//! implementations.
+
+use crate::contract_msgs::ContractInfo;
+use crate::oracle_msgs::OracleInfo;
+use crate::ser_impls::{read_strings, write_strings};
+use crate::OfferDlc;
+use bitcoin::Amount;
+
+/// TLV record type of [`BatchFundingGroup`]. The type is even, so that a peer
+/// unable to fund a batch refuses the offers instead of funding a single
+/// contract with inputs shared by the others.
+pub const BATCH_FUNDING_GROUP_TYPE: u16 = 65430;
+
+/// The offers of a batch funded contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct BatchFundingGroup {
+    /// The temporary contract ids of the offers of the batch.
+    pub temporary_contract_ids: Vec<[u8; 32]>,
+    /// The ids of the oracle events the contracts of the batch depend on.
+    pub event_ids: Vec<String>,
+    /// The collateral the offer party allocates to the contracts of the batch.
+    pub allocated_collateral: Amount,
+}
+
+impl_dlc_writeable!(BatchFundingGroup, {
+    (temporary_contract_ids, vec),
+    (event_ids, {cb_writeable, write_strings, read_strings}),
+    (allocated_collateral, writeable)
+});
+impl_dlc_tlv_record!(BatchFundingGroup, BATCH_FUNDING_GROUP_TYPE);
+
+impl BatchFundingGroup {
+    /// Creates the group of the given offers: their temporary ids, the ids of
+    /// the events their oracles announced, in order of first appearance, and
+    /// the sum of their offer collaterals.
+    pub fn new(offers: &[OfferDlc]) -> Self {
+        let mut event_ids = Vec::<String>::new();
+        for offer in offers {
+            let inners = match &offer.contract_info {
+                ContractInfo::SingleContractInfo(s) => std::slice::from_ref(&s.contract_info),
+                ContractInfo::DisjointContractInfo(d) => &d.contract_infos[..],
+            };
+            for inner in inners {
+                let announcements = match &inner.oracle_info {
+                    OracleInfo::Single(s) => std::slice::from_ref(&s.oracle_announcement),
+                    OracleInfo::Multi(m) => &m.oracle_announcements[..],
+                };
+                for announcement in announcements {
+                    let event_id = &announcement.oracle_event.event_id;
+                    if !event_ids.contains(event_id) {
+                        event_ids.push(event_id.clone());
+                    }
+                }
+            }
+        }
+        BatchFundingGroup {
+            temporary_contract_ids: offers
+                .iter()
+                .map(|offer| offer.temporary_contract_id)
+                .collect(),
+            event_ids,
+            allocated_collateral: offers.iter().map(|offer| offer.offer_collateral).sum(),
+        }
+    }
+
+    /// Returns whether the group lists the given temporary contract id.
+    pub fn contains(&self, temporary_contract_id: &[u8; 32]) -> bool {
+        self.temporary_contract_ids.contains(temporary_contract_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignDlc;
+    use bitcoin::hex::FromHex;
+    use lightning::util::ser::{Readable, Writeable};
+
+    #[test]
+    fn batch_funding_group_rides_the_offer_tlv_stream() {
+        let mut offer: OfferDlc =
+            serde_json::from_str(include_str!("./test_inputs/offer_msg.json")).unwrap();
+        let mut second = offer.clone();
+        second.temporary_contract_id = [7; 32];
+        let group = BatchFundingGroup::new(&[offer.clone(), second]);
+        assert_eq!(
+            group,
+            BatchFundingGroup {
+                temporary_contract_ids: vec![offer.temporary_contract_id, [7; 32]],
+                event_ids: vec!["Test".to_string()],
+                allocated_collateral: offer.offer_collateral * 2,
+            }
+        );
+        offer.tlvs.set(&group);
+
+        let read = OfferDlc::read(&mut &offer.encode()[..]).unwrap();
+        assert_eq!(read, offer);
+        let read_group = read.tlvs.get::<BatchFundingGroup>().unwrap().unwrap();
+        assert_eq!(read_group, group);
+        assert!(read_group.contains(&offer.temporary_contract_id));
+    }
+
+    #[test]
+    fn batch_funding_group_encoding_round_trips() {
+        let offer: OfferDlc =
+            serde_json::from_str(include_str!("./test_inputs/offer_msg.json")).unwrap();
+        // The group of a batch of two contracts, the offer being the first of
+        // them, field by field.
+        let record = [
+            "fdff96", // type 65430
+            "5e",     // length
+            "02",     // temporary contract ids
+            "1212121212121212121212121212121212121212121212121212121212121212",
+            "0707070707070707070707070707070707070707070707070707070707070707",
+            "02", // event ids
+            "09",
+            "6274632d7573642d31", // btc-usd-1
+            "09",
+            "6274632d7573642d32", // btc-usd-2
+            "000000000bebc200",   // allocated collateral
+        ]
+        .concat();
+        let mut bytes = offer.encode();
+        bytes.extend(Vec::<u8>::from_hex(&record).unwrap());
+
+        let read = OfferDlc::read(&mut &bytes[..]).unwrap();
+        let group = read.tlvs.get::<BatchFundingGroup>().unwrap().unwrap();
+        assert_eq!(
+            group,
+            BatchFundingGroup {
+                temporary_contract_ids: vec![[0x12; 32], [7; 32]],
+                event_ids: vec!["btc-usd-1".to_string(), "btc-usd-2".to_string()],
+                allocated_collateral: Amount::from_sat(200_000_000),
+            }
+        );
+        assert!(group.contains(&read.temporary_contract_id));
+        assert_eq!(read.encode(), bytes);
+
+        // A `DlcSign` carries the record as it is.
+        let sign: SignDlc =
+            serde_json::from_str(include_str!("./test_inputs/sign_msg.json")).unwrap();
+        let mut bytes = sign.encode();
+        bytes.extend(Vec::<u8>::from_hex(&record).unwrap());
+        let read = SignDlc::read(&mut &bytes[..]).unwrap();
+        assert_eq!(read.tlvs.get::<BatchFundingGroup>().unwrap(), Some(group));
+        assert_eq!(read.encode(), bytes);
+    }
+}
@@ -12,7 +12,7 @@
 
 extern crate bitcoin;
 extern crate ddk_dlc;
-extern crate lightning;
+pub extern crate lightning;
 extern crate secp256k1_zkp;
 #[macro_use]
 pub mod ser_macros;
@@ -26,11 +26,14 @@ extern crate serde;
 #[cfg(test)]
 extern crate serde_json;
 
+pub mod batch_msgs;
 pub mod channel;
 pub mod contract_msgs;
 pub mod message_handler;
 pub mod oracle_msgs;
 pub mod segmentation;
+pub mod taproot_msgs;
+pub mod tlv_stream;
 pub mod types;
 
 #[cfg(any(test, feature = "use-serde"))]
@@ -56,6 +59,8 @@ use lightning::util::ser::{Readable, Writeable, Writer};
 use secp256k1_zkp::Verification;
 use secp256k1_zkp::{ecdsa::Signature, EcdsaAdaptorSignature, PublicKey, Secp256k1};
 use segmentation::{SegmentChunk, SegmentStart};
+use taproot_msgs::{TaprootAccept, TaprootOffer, TaprootSign};
+use tlv_stream::TlvStream;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
@@ -354,9 +359,28 @@ pub struct OfferDlc {
     pub cet_locktime: u32,
     /// The lock time for the refund transactions.
     pub refund_locktime: u32,
+    /// The MuSig2 nonces of the offer party, present if and only if the
+    /// contract is Taproot funded.
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub taproot: Option<TaprootOffer>,
+    /// The TLV records following the fields of the message, other than
+    /// [`OfferDlc::taproot`].
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "TlvStream::is_empty")
+    )]
+    pub tlvs: TlvStream,
 }
 
 impl OfferDlc {
+    /// Returns whether the offered contract is Taproot funded.
+    pub fn is_taproot(&self) -> bool {
+        self.contract_flags & ddk_dlc::TAPROOT_FUNDING_FLAG != 0
+    }
+
     /// Returns the total collateral locked in the contract.
     pub fn get_total_collateral(&self) -> Amount {
         match &self.contract_info {
@@ -397,6 +421,29 @@ impl OfferDlc {
             ));
         }
 
+        if self.is_taproot() != self.taproot.is_some() {
+            return Err(Error::InvalidArgument(
+                "Taproot funding flag and nonces do not match".to_string(),
+            ));
+        }
+
+        let batch_funding_group = self
+            .tlvs
+            .get::<batch_msgs::BatchFundingGroup>()
+            .map_err(|e| Error::InvalidArgument(format!("Invalid batch funding group: {e}")))?;
+        if let Some(group) = batch_funding_group {
+            if !group.contains(&self.temporary_contract_id) {
+                return Err(Error::InvalidArgument(
+                    "Batch funding group does not list the offer".to_string(),
+                ));
+            }
+            if self.is_taproot() {
+                return Err(Error::InvalidArgument(
+                    "Batch funded contracts cannot be Taproot funded".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -420,7 +467,7 @@ impl Writeable for OfferDlc {
         self.fee_rate_per_vb.write(w)?;
         self.cet_locktime.write(w)?;
         self.refund_locktime.write(w)?;
-        Ok(())
+        crate::ser_impls::write_tlv_stream(&self.tlvs, &self.taproot, w)
     }
 }
 
@@ -456,23 +503,41 @@ impl Readable for OfferDlc {
             (1u32, peek[0], chain_hash)
         };
 
+        let temporary_contract_id = Readable::read(r)?;
+        let contract_info = Readable::read(r)?;
+        let funding_pubkey = Readable::read(r)?;
+        let payout_spk = Readable::read(r)?;
+        let payout_serial_id = Readable::read(r)?;
+        let offer_collateral = Readable::read(r)?;
+        let funding_inputs = crate::ser_impls::read_vec(r)?;
+        let change_spk = Readable::read(r)?;
+        let change_serial_id = Readable::read(r)?;
+        let fund_output_serial_id = Readable::read(r)?;
+        let fee_rate_per_vb = Readable::read(r)?;
+        let cet_locktime = Readable::read(r)?;
+        let refund_locktime = Readable::read(r)?;
+        let mut tlvs = TlvStream::read_to_end(r)?;
+        let taproot = tlvs.take()?;
+
         Ok(Self {
             protocol_version,
             contract_flags,
             chain_hash,
-            temporary_contract_id: Readable::read(r)?,
-            contract_info: Readable::read(r)?,
-            funding_pubkey: Readable::read(r)?,
-            payout_spk: Readable::read(r)?,
-            payout_serial_id: Readable::read(r)?,
-            offer_collateral: Readable::read(r)?,
-            funding_inputs: crate::ser_impls::read_vec(r)?,
-            change_spk: Readable::read(r)?,
-            change_serial_id: Readable::read(r)?,
-            fund_output_serial_id: Readable::read(r)?,
-            fee_rate_per_vb: Readable::read(r)?,
-            cet_locktime: Readable::read(r)?,
-            refund_locktime: Readable::read(r)?,
+            temporary_contract_id,
+            contract_info,
+            funding_pubkey,
+            payout_spk,
+            payout_serial_id,
+            offer_collateral,
+            funding_inputs,
+            change_spk,
+            change_serial_id,
+            fund_output_serial_id,
+            fee_rate_per_vb,
+            cet_locktime,
+            refund_locktime,
+            taproot,
+            tlvs,
         })
     }
 }
@@ -519,22 +584,83 @@ pub struct AcceptDlc {
     pub refund_signature: Signature,
     /// The negotiation fields from the accept party.
     pub negotiation_fields: Option<NegotiationFields>,
+    /// The MuSig2 nonces and partial signatures of the accept party, present
+    /// if and only if the contract is Taproot funded. The adaptor signatures
+    /// are then empty and the refund signature is unused.
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub taproot: Option<TaprootAccept>,
+    /// The TLV records following the fields of the message, other than
+    /// [`AcceptDlc::taproot`].
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "TlvStream::is_empty")
+    )]
+    pub tlvs: TlvStream,
 }
 
-impl_dlc_writeable!(AcceptDlc, ACCEPT_TYPE, {
-    (protocol_version, writeable),
-    (temporary_contract_id, writeable),
-    (accept_collateral, writeable),
-    (funding_pubkey, writeable),
-    (payout_spk, writeable),
-    (payout_serial_id, writeable),
-    (funding_inputs, vec),
-    (change_spk, writeable),
-    (change_serial_id, writeable),
-    (cet_adaptor_signatures, writeable),
-    (refund_signature, writeable),
-    (negotiation_fields, option)
-});
+impl AcceptDlc {
+    /// Writes the message without its trailing TLV records, for embedding it
+    /// in a larger structure. [`AcceptDlc::read_fields`] is the matching reader.
+    pub fn write_fields<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        ACCEPT_TYPE.write(w)?;
+        self.protocol_version.write(w)?;
+        self.temporary_contract_id.write(w)?;
+        self.accept_collateral.write(w)?;
+        self.funding_pubkey.write(w)?;
+        self.payout_spk.write(w)?;
+        self.payout_serial_id.write(w)?;
+        crate::ser_impls::write_vec(&self.funding_inputs, w)?;
+        self.change_spk.write(w)?;
+        self.change_serial_id.write(w)?;
+        self.cet_adaptor_signatures.write(w)?;
+        self.refund_signature.write(w)?;
+        crate::ser_impls::write_option(&self.negotiation_fields, w)
+    }
+
+    /// Reads a message written by [`AcceptDlc::write_fields`], leaving
+    /// [`AcceptDlc::taproot`] and [`AcceptDlc::tlvs`] empty.
+    pub fn read_fields<R: lightning::io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+        let type_id: u16 = Readable::read(r)?;
+        if type_id != ACCEPT_TYPE {
+            return Err(DecodeError::UnknownRequiredFeature);
+        }
+        Ok(Self {
+            protocol_version: Readable::read(r)?,
+            temporary_contract_id: Readable::read(r)?,
+            accept_collateral: Readable::read(r)?,
+            funding_pubkey: Readable::read(r)?,
+            payout_spk: Readable::read(r)?,
+            payout_serial_id: Readable::read(r)?,
+            funding_inputs: crate::ser_impls::read_vec(r)?,
+            change_spk: Readable::read(r)?,
+            change_serial_id: Readable::read(r)?,
+            cet_adaptor_signatures: Readable::read(r)?,
+            refund_signature: Readable::read(r)?,
+            negotiation_fields: crate::ser_impls::read_option(r)?,
+            taproot: None,
+            tlvs: TlvStream::default(),
+        })
+    }
+}
+
+impl Writeable for AcceptDlc {
+    fn write<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        self.write_fields(w)?;
+        crate::ser_impls::write_tlv_stream(&self.tlvs, &self.taproot, w)
+    }
+}
+
+impl Readable for AcceptDlc {
+    fn read<R: lightning::io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+        let mut accept = Self::read_fields(r)?;
+        accept.tlvs = TlvStream::read_to_end(r)?;
+        accept.taproot = accept.tlvs.take()?;
+        Ok(accept)
+    }
+}
 
 /// Contains all the required signatures for the DLC transactions from the offering
 /// party.
@@ -562,15 +688,69 @@ pub struct SignDlc {
     pub refund_signature: Signature,
     /// The set of funding signatures from the offer party.
     pub funding_signatures: FundingSignatures,
+    /// The MuSig2 partial signatures of the offer party, present if and only
+    /// if the contract is Taproot funded. The adaptor signatures are then
+    /// empty and the refund signature is unused.
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub taproot: Option<TaprootSign>,
+    /// The TLV records following the fields of the message, other than
+    /// [`SignDlc::taproot`].
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(default, skip_serializing_if = "TlvStream::is_empty")
+    )]
+    pub tlvs: TlvStream,
 }
 
-impl_dlc_writeable!(SignDlc, SIGN_TYPE, {
-    (protocol_version, writeable),
-    (contract_id, writeable),
-    (cet_adaptor_signatures, writeable),
-    (refund_signature, writeable),
-    (funding_signatures, writeable)
-});
+impl SignDlc {
+    /// Writes the message without its trailing TLV records, for embedding it
+    /// in a larger structure. [`SignDlc::read_fields`] is the matching reader.
+    pub fn write_fields<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        SIGN_TYPE.write(w)?;
+        self.protocol_version.write(w)?;
+        self.contract_id.write(w)?;
+        self.cet_adaptor_signatures.write(w)?;
+        self.refund_signature.write(w)?;
+        self.funding_signatures.write(w)
+    }
+
+    /// Reads a message written by [`SignDlc::write_fields`], leaving
+    /// [`SignDlc::taproot`] and [`SignDlc::tlvs`] empty.
+    pub fn read_fields<R: lightning::io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+        let type_id: u16 = Readable::read(r)?;
+        if type_id != SIGN_TYPE {
+            return Err(DecodeError::UnknownRequiredFeature);
+        }
+        Ok(Self {
+            protocol_version: Readable::read(r)?,
+            contract_id: Readable::read(r)?,
+            cet_adaptor_signatures: Readable::read(r)?,
+            refund_signature: Readable::read(r)?,
+            funding_signatures: Readable::read(r)?,
+            taproot: None,
+            tlvs: TlvStream::default(),
+        })
+    }
+}
+
+impl Writeable for SignDlc {
+    fn write<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        self.write_fields(w)?;
+        crate::ser_impls::write_tlv_stream(&self.tlvs, &self.taproot, w)
+    }
+}
+
+impl Readable for SignDlc {
+    fn read<R: lightning::io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+        let mut sign = Self::read_fields(r)?;
+        sign.tlvs = TlvStream::read_to_end(r)?;
+        sign.taproot = sign.tlvs.take()?;
+        Ok(sign)
+    }
+}
 
 /// Contains information about a party wishing to close a DLC contract.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -616,6 +796,111 @@ impl_dlc_writeable!(CloseDlc, CLOSE_TYPE, {
     (funding_signatures, writeable)
 });
 
+/// Asks the accept party of a signed contract to sign its transactions again
+/// with a fund transaction paying a higher fee rate. Sent by the offer party,
+/// the accept party answers with a [`BumpFundingAccept`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct BumpFundingFee {
+    /// The version of the protocol used by the peer.
+    pub protocol_version: u32,
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(
+            serialize_with = "crate::serde_utils::serialize_hex",
+            deserialize_with = "crate::serde_utils::deserialize_hex_array"
+        )
+    )]
+    /// The id of the contract whose fund transaction to replace.
+    pub contract_id: [u8; 32],
+    /// The fee rate of the replacing fund transaction.
+    pub fee_rate_per_vb: u64,
+}
+
+impl_dlc_writeable!(BumpFundingFee, BUMP_FUNDING_FEE_TYPE, {
+    (protocol_version, writeable),
+    (contract_id, writeable),
+    (fee_rate_per_vb, writeable)
+});
+
+/// Contains the signatures of the accept party for the transactions of a
+/// contract whose fund transaction is replaced by one paying a higher fee
+/// rate. The CETs and refund transaction spend the replacing fund transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct BumpFundingAccept {
+    /// The version of the protocol used by the peer.
+    pub protocol_version: u32,
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(
+            serialize_with = "crate::serde_utils::serialize_hex",
+            deserialize_with = "crate::serde_utils::deserialize_hex_array"
+        )
+    )]
+    /// The id of the contract whose fund transaction to replace.
+    pub contract_id: [u8; 32],
+    /// The fee rate of the replacing fund transaction.
+    pub fee_rate_per_vb: u64,
+    /// The set of adaptor signatures from the accept party.
+    pub cet_adaptor_signatures: CetAdaptorSignatures,
+    /// The refund signature from the accept party.
+    pub refund_signature: Signature,
+}
+
+impl_dlc_writeable!(BumpFundingAccept, BUMP_FUNDING_ACCEPT_TYPE, {
+    (protocol_version, writeable),
+    (contract_id, writeable),
+    (fee_rate_per_vb, writeable),
+    (cet_adaptor_signatures, writeable),
+    (refund_signature, writeable)
+});
+
+/// Contains the signatures of the offer party for the transactions of a
+/// contract whose fund transaction is replaced, and for its funding inputs in
+/// the replacing fund transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct BumpFundingSign {
+    /// The version of the protocol used by the peer.
+    pub protocol_version: u32,
+    #[cfg_attr(
+        feature = "use-serde",
+        serde(
+            serialize_with = "crate::serde_utils::serialize_hex",
+            deserialize_with = "crate::serde_utils::deserialize_hex_array"
+        )
+    )]
+    /// The id of the contract whose fund transaction to replace.
+    pub contract_id: [u8; 32],
+    /// The set of adaptor signatures from the offer party.
+    pub cet_adaptor_signatures: CetAdaptorSignatures,
+    /// The refund signature from the offer party.
+    pub refund_signature: Signature,
+    /// The set of funding signatures from the offer party.
+    pub funding_signatures: FundingSignatures,
+}
+
+impl_dlc_writeable!(BumpFundingSign, BUMP_FUNDING_SIGN_TYPE, {
+    (protocol_version, writeable),
+    (contract_id, writeable),
+    (cet_adaptor_signatures, writeable),
+    (refund_signature, writeable),
+    (funding_signatures, writeable)
+});
+
 #[allow(missing_docs)]
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -623,6 +908,9 @@ pub enum Message {
     Accept(AcceptDlc),
     Sign(SignDlc),
     Close(CloseDlc),
+    BumpFundingFee(BumpFundingFee),
+    BumpFundingAccept(BumpFundingAccept),
+    BumpFundingSign(BumpFundingSign),
     OfferChannel(OfferChannel),
     AcceptChannel(AcceptChannel),
     SignChannel(SignChannel),
@@ -665,6 +953,9 @@ impl_type_writeable_for_enum!(Message,
     Accept,
     Sign,
     Close,
+    BumpFundingFee,
+    BumpFundingAccept,
+    BumpFundingSign,
     OfferChannel,
     AcceptChannel,
     SignChannel,
@@ -751,6 +1042,32 @@ mod tests {
         roundtrip_test!(CloseDlc, input);
     }
 
+    #[test]
+    fn bump_funding_msgs_roundtrip() {
+        let input = include_str!("./test_inputs/sign_msg.json");
+        let sign: SignDlc = serde_json::from_str(input).unwrap();
+
+        test_roundtrip(BumpFundingFee {
+            protocol_version: sign.protocol_version,
+            contract_id: sign.contract_id,
+            fee_rate_per_vb: 12,
+        });
+        test_roundtrip(BumpFundingAccept {
+            protocol_version: sign.protocol_version,
+            contract_id: sign.contract_id,
+            fee_rate_per_vb: 12,
+            cet_adaptor_signatures: sign.cet_adaptor_signatures.clone(),
+            refund_signature: sign.refund_signature,
+        });
+        test_roundtrip(BumpFundingSign {
+            protocol_version: sign.protocol_version,
+            contract_id: sign.contract_id,
+            cet_adaptor_signatures: sign.cet_adaptor_signatures,
+            refund_signature: sign.refund_signature,
+            funding_signatures: sign.funding_signatures,
+        });
+    }
+
     #[test]
     fn valid_offer_message_passes_validation() {
         let input = include_str!("./test_inputs/offer_msg.json");
@@ -822,4 +1139,98 @@ mod tests {
                 .expect_err("Should not pass validation of invalid offer message.");
         }
     }
+
+    fn taproot_accept() -> TaprootAccept {
+        use taproot_msgs::{MusigPartialSignature, MusigPubNonce};
+        TaprootAccept {
+            cet_nonces: vec![MusigPubNonce([2; 66]); 3],
+            refund_nonce: MusigPubNonce([3; 66]),
+            cet_partial_signatures: vec![MusigPartialSignature([4; 32]); 3],
+            refund_partial_signature: MusigPartialSignature([5; 32]),
+            refund_leaf_signature: secp256k1_zkp::schnorr::Signature::from_slice(&[6; 64]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn accept_msg_with_taproot_record_roundtrip() {
+        let input = include_str!("./test_inputs/accept_msg.json");
+        let mut accept: AcceptDlc = serde_json::from_str(input).unwrap();
+        let legacy = accept.encode();
+
+        accept.taproot = Some(taproot_accept());
+        let encoded = accept.encode();
+        assert_eq!(&encoded[..legacy.len()], &legacy[..]);
+        test_roundtrip(accept.clone());
+
+        let mut nested = Vec::new();
+        accept.write_fields(&mut nested).unwrap();
+        assert_eq!(nested, legacy);
+        let read = AcceptDlc::read_fields(&mut &nested[..]).unwrap();
+        assert_eq!(read.taproot, None);
+    }
+
+    #[test]
+    fn unknown_trailing_records_round_trip() {
+        let input = include_str!("./test_inputs/sign_msg.json");
+        let mut sign: SignDlc = serde_json::from_str(input).unwrap();
+        let legacy = sign.encode();
+
+        // Records appended by another implementation, odd and even alike.
+        let mut extended = legacy.clone();
+        extended.extend_from_slice(&[0xfd, 0xd8, 0x7d, 0x02, 0xaa, 0xbb]);
+        extended.extend_from_slice(&[0xfe, 0x00, 0x01, 0x00, 0x02, 0x01, 0xcc]);
+        let read = SignDlc::read(&mut &extended[..]).unwrap();
+        assert_eq!(read.taproot, None);
+        assert_eq!(read.tlvs.required_types().collect::<Vec<_>>(), vec![65538]);
+        assert_eq!(read.encode(), extended);
+        test_roundtrip(read.clone());
+
+        let json = serde_json::to_string(&read).unwrap();
+        assert_eq!(serde_json::from_str::<SignDlc>(&json).unwrap(), read);
+
+        sign.tlvs = read.tlvs;
+        sign.taproot = Some(TaprootSign {
+            cet_partial_signatures: Vec::new(),
+            refund_partial_signature: taproot_msgs::MusigPartialSignature([1; 32]),
+            refund_leaf_signature: secp256k1_zkp::schnorr::Signature::from_slice(&[2; 64]).unwrap(),
+        });
+        let read = SignDlc::read(&mut &sign.encode()[..]).unwrap();
+        assert_eq!(read, sign);
+        assert_eq!(
+            read.tlvs
+                .raw()
+                .map(|(tlv_type, _)| tlv_type)
+                .collect::<Vec<_>>(),
+            vec![55421, 65538]
+        );
+
+        let mut truncated = extended;
+        truncated.pop();
+        assert!(SignDlc::read(&mut &truncated[..]).is_err());
+    }
+
+    #[test]
+    fn messages_without_records_are_unchanged() {
+        let offer: OfferDlc =
+            serde_json::from_str(include_str!("./test_inputs/offer_msg.json")).unwrap();
+        assert!(offer.tlvs.is_empty());
+        assert!(!serde_json::to_string(&offer).unwrap().contains("tlvs"));
+
+        let accept: AcceptDlc =
+            serde_json::from_str(include_str!("./test_inputs/accept_msg.json")).unwrap();
+        let mut fields = Vec::new();
+        accept.write_fields(&mut fields).unwrap();
+        assert_eq!(accept.encode(), fields);
+    }
+
+    #[test]
+    fn taproot_flag_must_match_taproot_record() {
+        let input = include_str!("./test_inputs/offer_msg.json");
+        let mut offer: OfferDlc = serde_json::from_str(input).unwrap();
+        offer.contract_flags |= ddk_dlc::TAPROOT_FUNDING_FLAG;
+
+        offer
+            .validate(SECP256K1, 86400 * 7, 86400 * 14)
+            .expect_err("Should not pass validation without taproot nonces.");
+    }
 }
@@ -105,6 +105,9 @@ pub fn read_dlc_message<R: ::lightning::io::Read>(
         (OFFER_TYPE, Offer),
         (ACCEPT_TYPE, Accept),
         (SIGN_TYPE, Sign),
+        (BUMP_FUNDING_FEE_TYPE, BumpFundingFee),
+        (BUMP_FUNDING_ACCEPT_TYPE, BumpFundingAccept),
+        (BUMP_FUNDING_SIGN_TYPE, BumpFundingSign),
         (OFFER_CHANNEL_TYPE, OfferChannel),
         (ACCEPT_CHANNEL_TYPE, AcceptChannel),
         (SIGN_CHANNEL_TYPE, SignChannel),
@@ -13,6 +13,7 @@ use lightning::util::ser::{Readable, Writeable, Writer};
 use secp256k1_zkp::{ffi::ECDSA_ADAPTOR_SIGNATURE_LENGTH, EcdsaAdaptorSignature};
 use std::collections::HashMap;
 use std::hash::Hash;
+use tlv_stream::TlvStream;
 
 const MAX_VEC_SIZE: u64 = 1000000;
 
@@ -762,6 +763,27 @@ pub fn read_tlv_body<T: Readable, R: Read>(reader: &mut R, len: u64) -> Result<T
     Ok(value)
 }
 
+/// Writes the TLV stream of a message together with a record the message keeps in
+/// its own field.
+///
+/// The record is merged into the stream in type order. Nothing is written for an
+/// empty stream and an absent record, so a message that carries neither serializes
+/// exactly as it did before either existed.
+pub fn write_tlv_stream<T: TlvRecord, W: Writer>(
+    tlvs: &TlvStream,
+    record: &Option<T>,
+    writer: &mut W,
+) -> Result<(), ::lightning::io::Error> {
+    match record {
+        Some(record) => {
+            let mut tlvs = tlvs.clone();
+            tlvs.set(record);
+            tlvs.write(writer)
+        }
+        None => tlvs.write(writer),
+    }
+}
+
 /// Writes a [`HashMap`].
 pub fn write_hash_map<W: Writer, T, V>(
     input: &HashMap<T, V>,
@@ -1,10 +1,14 @@
 //! Set of macro to help implementing the [`lightning::util::ser::Writeable`] trait.
+//!
+//! [`impl_dlc_writeable!`] and [`impl_dlc_tlv_record!`] are also meant to be used by
+//! applications defining their own TLV records, so they and the macros they expand to
+//! refer to everything by its full path through this crate and need nothing in scope.
 
 /// Writes a field to a writer.
 #[macro_export]
 macro_rules! field_write {
     ($stream: expr, $field: expr, writeable) => {
-        $field.write($stream)?;
+        $crate::lightning::util::ser::Writeable::write(&$field, $stream)?;
     };
     ($stream: expr, $field: expr, {cb_writeable, $w_cb: expr, $r_cb: expr}) => {
         $w_cb(&$field, $stream)?;
@@ -36,13 +40,16 @@ macro_rules! field_write {
     ($stream: expr, $field: expr, option) => {
         $crate::ser_impls::write_option(&$field, $stream)?;
     };
+    ($stream: expr, $field: expr, tlv_stream) => {
+        $crate::lightning::util::ser::Writeable::write(&$field, $stream)?;
+    };
 }
 
 /// Reads a field from a reader.
 #[macro_export]
 macro_rules! field_read {
     ($stream: expr, writeable) => {
-        Readable::read($stream)?
+        $crate::lightning::util::ser::Readable::read($stream)?
     };
     ($stream: expr, {cb_writeable, $w_cb: expr, $r_cb: expr}) => {
         $r_cb($stream)?
@@ -74,27 +81,48 @@ macro_rules! field_read {
     ($stream: expr, option) => {
         $crate::ser_impls::read_option($stream)?
     };
+    ($stream: expr, tlv_stream) => {
+        $crate::tlv_stream::TlvStream::read_to_end($stream)?
+    };
 }
 
 /// Implements the [`lightning::util::ser::Writeable`] trait for a struct available
 /// in this crate.
+///
+/// A [`TlvStream`](crate::tlv_stream::TlvStream) field uses the `tlv_stream` kind and
+/// must be the last field, as it reads up to the end of the reader.
 #[macro_export]
 macro_rules! impl_dlc_writeable {
+    (@check_tlv_stream_last) => {};
+    (@check_tlv_stream_last ($field: ident, tlv_stream)) => {};
+    (@check_tlv_stream_last ($field: ident, tlv_stream), $($rest: tt)+) => {
+        compile_error!("a `tlv_stream` field must be the last field");
+    };
+    (@check_tlv_stream_last ($field: ident, $fieldty: tt) $(, $($rest: tt)*)?) => {
+        $crate::impl_dlc_writeable!(@check_tlv_stream_last $($($rest)*)?);
+    };
     ($st:ident, {$(($field: ident, $fieldty: tt)), *} ) => {
-        impl Writeable for $st {
-			fn write<W: Writer>(&self, w: &mut W) -> Result<(), ::lightning::io::Error> {
-				$(
-                    field_write!(w, self.$field, $fieldty);
+        $crate::impl_dlc_writeable!(@check_tlv_stream_last $(($field, $fieldty)),*);
+
+        impl $crate::lightning::util::ser::Writeable for $st {
+            fn write<W: $crate::lightning::util::ser::Writer>(
+                &self,
+                w: &mut W,
+            ) -> Result<(), $crate::lightning::io::Error> {
+                $(
+                    $crate::field_write!(w, self.$field, $fieldty);
                 )*
-				Ok(())
+                Ok(())
             }
         }
 
-        impl Readable for $st {
-			fn read<R: lightning::io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+        impl $crate::lightning::util::ser::Readable for $st {
+            fn read<R: $crate::lightning::io::Read>(
+                r: &mut R,
+            ) -> Result<Self, $crate::lightning::ln::msgs::DecodeError> {
                 Ok(Self {
                     $(
-                        $field: field_read!(r, $fieldty),
+                        $field: $crate::field_read!(r, $fieldty),
                     )*
                 })
             }
@@ -102,27 +130,34 @@ macro_rules! impl_dlc_writeable {
     };
     // Version with type_id - writes/reads type_id as first field
     ($st:ident, $type_const:ident, {$(($field: ident, $fieldty: tt)), *} ) => {
-        impl Writeable for $st {
-			fn write<W: Writer>(&self, w: &mut W) -> Result<(), ::lightning::io::Error> {
+        $crate::impl_dlc_writeable!(@check_tlv_stream_last $(($field, $fieldty)),*);
+
+        impl $crate::lightning::util::ser::Writeable for $st {
+            fn write<W: $crate::lightning::util::ser::Writer>(
+                &self,
+                w: &mut W,
+            ) -> Result<(), $crate::lightning::io::Error> {
                 // Write type_id first
-                $type_const.write(w)?;
-				$(
-                    field_write!(w, self.$field, $fieldty);
+                $crate::lightning::util::ser::Writeable::write(&$type_const, w)?;
+                $(
+                    $crate::field_write!(w, self.$field, $fieldty);
                 )*
-				Ok(())
+                Ok(())
             }
         }
 
-        impl Readable for $st {
-			fn read<R: lightning::io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+        impl $crate::lightning::util::ser::Readable for $st {
+            fn read<R: $crate::lightning::io::Read>(
+                r: &mut R,
+            ) -> Result<Self, $crate::lightning::ln::msgs::DecodeError> {
                 // Read and verify type_id first
-                let type_id: u16 = Readable::read(r)?;
+                let type_id: u16 = $crate::lightning::util::ser::Readable::read(r)?;
                 if type_id != $type_const {
-                    return Err(DecodeError::UnknownRequiredFeature);
+                    return Err($crate::lightning::ln::msgs::DecodeError::UnknownRequiredFeature);
                 }
                 Ok(Self {
                     $(
-                        $field: field_read!(r, $fieldty),
+                        $field: $crate::field_read!(r, $fieldty),
                     )*
                 })
             }
@@ -218,7 +253,7 @@ macro_rules! impl_dlc_tlv_record {
             const TYPE_ID: u16 = $type_id;
         }
 
-        impl ::lightning::ln::wire::Type for $st {
+        impl $crate::lightning::ln::wire::Type for $st {
             fn type_id(&self) -> u16 {
                 <$st as $crate::ser_impls::TlvType>::TYPE_ID
             }
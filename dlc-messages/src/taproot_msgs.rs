@@ -0,0 +1,177 @@
+//! Structures carrying the MuSig2 nonces and partial signatures of a Taproot
+//! funded contract.
+//!
+//! A contract is Taproot funded when the offer sets
+//! [`ddk_dlc::TAPROOT_FUNDING_FLAG`] in its `contract_flags`. Each of
+//! [`OfferDlc`](crate::OfferDlc), [`AcceptDlc`](crate::AcceptDlc) and
+//! [`SignDlc`](crate::SignDlc) then carries one of the records of this module
+//! as a TLV record following its regular fields. Messages of contracts that
+//! are not Taproot funded do not carry any and serialize exactly as before.
+//!
+//! The nonces and partial signatures replace the ECDSA adaptor signatures
+//! and refund signature of the message, which are left empty. The accept and
+//! sign records also carry each party's Schnorr signature for spending the
+//! fund output with the refund transaction through the refund leaf.
+
+use crate::ser_impls::{read_schnorrsig, write_schnorrsig};
+use lightning::ln::msgs::DecodeError;
+use lightning::util::ser::{Readable, Writeable, Writer};
+use secp256k1_zkp::schnorr::Signature;
+
+/// TLV record type of [`TaprootOffer`].
+pub const TAPROOT_OFFER_TYPE: u16 = 55420;
+/// TLV record type of [`TaprootAccept`].
+pub const TAPROOT_ACCEPT_TYPE: u16 = 55422;
+/// TLV record type of [`TaprootSign`].
+pub const TAPROOT_SIGN_TYPE: u16 = 55424;
+
+macro_rules! impl_byte_array_newtype {
+    ($st: ident, $len: expr) => {
+        impl Writeable for $st {
+            fn write<W: Writer>(&self, w: &mut W) -> Result<(), ::lightning::io::Error> {
+                self.0.write(w)
+            }
+        }
+
+        impl Readable for $st {
+            fn read<R: ::lightning::io::Read>(r: &mut R) -> Result<Self, DecodeError> {
+                Ok($st(Readable::read(r)?))
+            }
+        }
+
+        #[cfg(feature = "use-serde")]
+        impl serde::Serialize for $st {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                crate::serde_utils::serialize_hex(&self.0, s)
+            }
+        }
+
+        #[cfg(feature = "use-serde")]
+        impl<'de> serde::Deserialize<'de> for $st {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let bytes = crate::serde_utils::deserialize_hex_string(d)?;
+                if bytes.len() != $len {
+                    return Err(<D::Error as serde::de::Error>::invalid_length(
+                        bytes.len(),
+                        &stringify!($len),
+                    ));
+                }
+                let mut array = [0u8; $len];
+                array.copy_from_slice(&bytes);
+                Ok($st(array))
+            }
+        }
+    };
+}
+
+/// A serialized MuSig2 public nonce: two compressed points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MusigPubNonce(pub [u8; 66]);
+
+impl_byte_array_newtype!(MusigPubNonce, 66);
+
+/// A serialized MuSig2 partial signature: a scalar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MusigPartialSignature(pub [u8; 32]);
+
+impl_byte_array_newtype!(MusigPartialSignature, 32);
+
+/// The offer party's public nonces, committed to before any partial
+/// signature is produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TaprootOffer {
+    /// One nonce per CET adaptor signature, in adaptor signature order.
+    pub cet_nonces: Vec<MusigPubNonce>,
+    /// The nonce for the refund transaction signature.
+    pub refund_nonce: MusigPubNonce,
+}
+
+impl_dlc_writeable!(TaprootOffer, {
+    (cet_nonces, vec),
+    (refund_nonce, writeable)
+});
+impl_dlc_tlv_record!(TaprootOffer, TAPROOT_OFFER_TYPE);
+
+/// The accept party's public nonces and partial signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TaprootAccept {
+    /// One nonce per CET adaptor signature, in adaptor signature order.
+    pub cet_nonces: Vec<MusigPubNonce>,
+    /// The nonce for the refund transaction signature.
+    pub refund_nonce: MusigPubNonce,
+    /// The partial adaptor signatures for the CETs.
+    pub cet_partial_signatures: Vec<MusigPartialSignature>,
+    /// The partial signature for the refund transaction.
+    pub refund_partial_signature: MusigPartialSignature,
+    /// The signature for the refund transaction spending the refund leaf.
+    pub refund_leaf_signature: Signature,
+}
+
+impl_dlc_writeable!(TaprootAccept, {
+    (cet_nonces, vec),
+    (refund_nonce, writeable),
+    (cet_partial_signatures, vec),
+    (refund_partial_signature, writeable),
+    (refund_leaf_signature, {cb_writeable, write_schnorrsig, read_schnorrsig})
+});
+impl_dlc_tlv_record!(TaprootAccept, TAPROOT_ACCEPT_TYPE);
+
+/// The offer party's partial signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TaprootSign {
+    /// The partial adaptor signatures for the CETs.
+    pub cet_partial_signatures: Vec<MusigPartialSignature>,
+    /// The partial signature for the refund transaction.
+    pub refund_partial_signature: MusigPartialSignature,
+    /// The signature for the refund transaction spending the refund leaf.
+    pub refund_leaf_signature: Signature,
+}
+
+impl_dlc_writeable!(TaprootSign, {
+    (cet_partial_signatures, vec),
+    (refund_partial_signature, writeable),
+    (refund_leaf_signature, {cb_writeable, write_schnorrsig, read_schnorrsig})
+});
+impl_dlc_tlv_record!(TaprootSign, TAPROOT_SIGN_TYPE);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TlvRecord;
+
+    #[test]
+    fn taproot_records_roundtrip() {
+        let accept = TaprootAccept {
+            cet_nonces: vec![MusigPubNonce([2; 66]), MusigPubNonce([3; 66])],
+            refund_nonce: MusigPubNonce([4; 66]),
+            cet_partial_signatures: vec![MusigPartialSignature([5; 32]); 2],
+            refund_partial_signature: MusigPartialSignature([6; 32]),
+            refund_leaf_signature: Signature::from_slice(&[7; 64]).unwrap(),
+        };
+
+        let bytes = accept.to_tlv_bytes();
+        assert_eq!(TaprootAccept::from_tlv_bytes(&bytes).unwrap(), accept);
+        assert!(TaprootSign::from_tlv_bytes(&bytes).is_err());
+
+        let json = serde_json::to_string(&accept).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TaprootAccept>(&json).unwrap(),
+            accept
+        );
+    }
+}
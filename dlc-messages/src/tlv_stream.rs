@@ -0,0 +1,274 @@
+//! The stream of TLV records that follows the fields of a message.
+//!
+//! The DLC specification lets a message end with a stream of TLV records, which
+//! is how extensions and applications attach data to a message without changing
+//! its fields. [`TlvStream`] holds those records for
+//! [`OfferDlc`](crate::OfferDlc), [`AcceptDlc`](crate::AcceptDlc) and
+//! [`SignDlc`](crate::SignDlc).
+//!
+//! An application defines a record type in its own crate, and attaches it to or
+//! reads it from a message:
+//!
+//! ```
+//! use ddk_messages::tlv_stream::TlvStream;
+//! use ddk_messages::{impl_dlc_tlv_record, impl_dlc_writeable};
+//!
+//! /// Odd, so that peers which do not know it may ignore it.
+//! const LOAN_REF_TYPE: u16 = 65003;
+//!
+//! #[derive(Clone, Debug, PartialEq, Eq)]
+//! struct LoanRef {
+//!     loan_id: [u8; 16],
+//!     lender: String,
+//! }
+//!
+//! impl_dlc_writeable!(LoanRef, { (loan_id, writeable), (lender, string) });
+//! impl_dlc_tlv_record!(LoanRef, LOAN_REF_TYPE);
+//!
+//! let loan_ref = LoanRef { loan_id: [7; 16], lender: "bank".to_string() };
+//! let mut tlvs = TlvStream::default();
+//! tlvs.set(&loan_ref);
+//! assert_eq!(tlvs.get::<LoanRef>().unwrap(), Some(loan_ref));
+//! ```
+//!
+//! Applications should use odd types in the custom range (65000 and above), so
+//! that they neither collide with types the specification assigns nor make
+//! peers that do not know them reject the message.
+
+use lightning::io::Read;
+use lightning::ln::msgs::DecodeError;
+use lightning::util::ser::{Readable, Writeable, Writer};
+
+use ser_impls::{BigSize, TlvRecord};
+
+/// The TLV records at the end of a message.
+///
+/// Records whose type this crate knows are parsed into their own message
+/// fields; this holds the rest, verbatim, so that they survive a round trip.
+/// Records are kept in ascending type order, as the TLV stream rules require,
+/// so a stream always serializes to the same bytes.
+///
+/// Only the framing of a record is checked, never its meaning. This includes
+/// records of even types, which by convention a reader must understand: they
+/// are carried rather than rejected, and [`TlvStream::required_types`] lists
+/// them for callers that want to act on them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlvStream {
+    /// The type and full bytes, header included, of each record.
+    records: Vec<(u64, Vec<u8>)>,
+}
+
+impl TlvStream {
+    /// Returns whether the stream holds no record.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Reads records until the reader is exhausted.
+    ///
+    /// The reader must be bounded to the message, as it is when reading from
+    /// the wire: anything left in it is taken to be part of the stream.
+    /// Records out of ascending type order, duplicated, or cut short are
+    /// rejected.
+    pub fn read_to_end<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let mut bytes = Vec::new();
+        reader.read_to_limit(&mut bytes, u64::MAX)?;
+        Self::from_bytes(&bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut records: Vec<(u64, Vec<u8>)> = Vec::new();
+        let mut remaining = bytes;
+        while !remaining.is_empty() {
+            let record_start = remaining;
+            let tlv_type: BigSize = Readable::read(&mut remaining)?;
+            let len: BigSize = Readable::read(&mut remaining)?;
+            if len.0 > remaining.len() as u64 {
+                return Err(DecodeError::ShortRead);
+            }
+            remaining = &remaining[len.0 as usize..];
+            if let Some((previous_type, _)) = records.last() {
+                if tlv_type.0 <= *previous_type {
+                    return Err(DecodeError::InvalidValue);
+                }
+            }
+            let record_len = record_start.len() - remaining.len();
+            records.push((tlv_type.0, record_start[..record_len].to_vec()));
+        }
+        Ok(TlvStream { records })
+    }
+
+    /// Parses the record whose type is `T::TYPE_ID`, if present.
+    pub fn get<T: TlvRecord>(&self) -> Result<Option<T>, DecodeError> {
+        match self.position(T::TYPE_ID) {
+            Ok(index) => T::from_tlv_bytes(&self.records[index].1).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Writes `value`, replacing any existing record of the same type.
+    pub fn set<T: TlvRecord>(&mut self, value: &T) {
+        let record = (T::TYPE_ID as u64, value.to_tlv_bytes());
+        match self.position(T::TYPE_ID) {
+            Ok(index) => self.records[index] = record,
+            Err(index) => self.records.insert(index, record),
+        }
+    }
+
+    /// Parses and removes the record whose type is `T::TYPE_ID`, if present.
+    pub fn take<T: TlvRecord>(&mut self) -> Result<Option<T>, DecodeError> {
+        let value = self.get()?;
+        self.remove(T::TYPE_ID);
+        Ok(value)
+    }
+
+    /// Removes the record of the given type, returning whether there was one.
+    pub fn remove(&mut self, type_id: u16) -> bool {
+        match self.position(type_id) {
+            Ok(index) => {
+                self.records.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the type and full bytes, header included, of each record, for
+    /// records with no Rust type in this build.
+    pub fn raw(&self) -> impl Iterator<Item = (u64, &[u8])> + '_ {
+        self.records
+            .iter()
+            .map(|(tlv_type, bytes)| (*tlv_type, bytes.as_slice()))
+    }
+
+    /// Returns the even record types of the stream, which by convention a
+    /// reader must understand.
+    pub fn required_types(&self) -> impl Iterator<Item = u64> + '_ {
+        self.records
+            .iter()
+            .map(|(tlv_type, _)| *tlv_type)
+            .filter(|tlv_type| tlv_type % 2 == 0)
+    }
+
+    fn position(&self, type_id: u16) -> Result<usize, usize> {
+        self.records
+            .binary_search_by_key(&(type_id as u64), |(tlv_type, _)| *tlv_type)
+    }
+}
+
+impl Writeable for TlvStream {
+    fn write<W: Writer>(&self, w: &mut W) -> Result<(), lightning::io::Error> {
+        for (_, bytes) in &self.records {
+            w.write_all(bytes)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl serde::Serialize for TlvStream {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        ::serde_utils::serialize_hex(&self.encode(), s)
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl<'de> serde::Deserialize<'de> for TlvStream {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let bytes = ::serde_utils::deserialize_hex_string(d)?;
+        TlvStream::from_bytes(&bytes)
+            .map_err(|e| <D::Error as serde::de::Error>::custom(format!("{e:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_msgs::OracleAttestation;
+    use taproot_msgs::{MusigPartialSignature, TaprootSign, TAPROOT_SIGN_TYPE};
+
+    fn sign_record() -> TaprootSign {
+        TaprootSign {
+            cet_partial_signatures: vec![MusigPartialSignature([1; 32])],
+            refund_partial_signature: MusigPartialSignature([2; 32]),
+            refund_leaf_signature: secp256k1_zkp::schnorr::Signature::from_slice(&[3; 64]).unwrap(),
+        }
+    }
+
+    fn record_bytes(tlv_type: u64, body: &[u8]) -> Vec<u8> {
+        let mut bytes = BigSize(tlv_type).encode();
+        bytes.extend(BigSize(body.len() as u64).encode());
+        bytes.extend_from_slice(body);
+        bytes
+    }
+
+    #[test]
+    fn empty_stream_writes_nothing() {
+        assert!(TlvStream::default().encode().is_empty());
+        assert_eq!(
+            TlvStream::read_to_end(&mut &[][..]).unwrap(),
+            TlvStream::default()
+        );
+    }
+
+    #[test]
+    fn unknown_records_round_trip_verbatim() {
+        let bytes = [
+            record_bytes(1, &[1, 2, 3]),
+            record_bytes(64998, &[]),
+            record_bytes(65003, &[9; 300]),
+        ]
+        .concat();
+        let stream = TlvStream::read_to_end(&mut &bytes[..]).unwrap();
+        assert_eq!(stream.encode(), bytes);
+        assert_eq!(
+            stream
+                .raw()
+                .map(|(tlv_type, _)| tlv_type)
+                .collect::<Vec<_>>(),
+            vec![1, 64998, 65003]
+        );
+        assert_eq!(stream.required_types().collect::<Vec<_>>(), vec![64998]);
+    }
+
+    #[test]
+    fn records_are_kept_in_type_order() {
+        let mut stream = TlvStream::read_to_end(&mut &record_bytes(65003, &[1])[..]).unwrap();
+        stream.set(&sign_record());
+        assert_eq!(
+            stream
+                .raw()
+                .map(|(tlv_type, _)| tlv_type)
+                .collect::<Vec<_>>(),
+            vec![TAPROOT_SIGN_TYPE as u64, 65003]
+        );
+        assert_eq!(stream.get::<TaprootSign>().unwrap(), Some(sign_record()));
+        assert_eq!(stream.get::<OracleAttestation>().unwrap(), None);
+
+        assert_eq!(stream.take::<TaprootSign>().unwrap(), Some(sign_record()));
+        assert!(!stream.remove(TAPROOT_SIGN_TYPE));
+        assert!(stream.remove(65003));
+        assert!(stream.is_empty());
+    }
+
+    #[test]
+    fn malformed_streams_are_rejected() {
+        let duplicate = [record_bytes(65003, &[1]), record_bytes(65003, &[2])].concat();
+        assert!(TlvStream::read_to_end(&mut &duplicate[..]).is_err());
+
+        let unordered = [record_bytes(65005, &[1]), record_bytes(65003, &[2])].concat();
+        assert!(TlvStream::read_to_end(&mut &unordered[..]).is_err());
+
+        let mut truncated = record_bytes(65003, &[1, 2, 3]);
+        truncated.pop();
+        assert!(TlvStream::read_to_end(&mut &truncated[..]).is_err());
+    }
+
+    #[test]
+    fn stream_serializes_as_hex() {
+        let mut stream = TlvStream::default();
+        stream.set(&sign_record());
+        let json = serde_json::to_string(&stream).unwrap();
+        assert_eq!(serde_json::from_str::<TlvStream>(&json).unwrap(), stream);
+    }
+}
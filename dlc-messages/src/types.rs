@@ -17,7 +17,9 @@ macro_rules! impl_type {
 }
 
 // Re-export the types that will get impl_type
-pub use crate::{AcceptDlc, CloseDlc, OfferDlc, SignDlc};
+pub use crate::{
+    AcceptDlc, BumpFundingAccept, BumpFundingFee, BumpFundingSign, CloseDlc, OfferDlc, SignDlc,
+};
 
 pub use crate::channel::{
     AcceptChannel, CollaborativeCloseOffer, OfferChannel, Reject, RenewAccept, RenewConfirm,
@@ -30,6 +32,9 @@ impl_type!(OFFER_TYPE, OfferDlc, 42778);
 impl_type!(ACCEPT_TYPE, AcceptDlc, 42780);
 impl_type!(SIGN_TYPE, SignDlc, 42782);
 impl_type!(CLOSE_TYPE, CloseDlc, 42784);
+impl_type!(BUMP_FUNDING_FEE_TYPE, BumpFundingFee, 42786);
+impl_type!(BUMP_FUNDING_ACCEPT_TYPE, BumpFundingAccept, 42788);
+impl_type!(BUMP_FUNDING_SIGN_TYPE, BumpFundingSign, 42790);
 
 // Channel message types
 impl_type!(OFFER_CHANNEL_TYPE, OfferChannel, 43000);
@@ -122,6 +127,8 @@ mod tests {
             fee_rate_per_vb: 1,
             cet_locktime: 100,
             refund_locktime: 200,
+            taproot: None,
+            tlvs: crate::tlv_stream::TlvStream::default(),
         };
 
         // Serialize the offer
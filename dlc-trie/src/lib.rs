@@ -170,6 +170,30 @@ pub trait DlcTrie<'a, TrieIterator: Iterator<Item = TrieIterInfo>> {
         )
     }
 
+    /// Generate the trie and return the CET index and adaptor point of each
+    /// adaptor signature, ordered by adaptor index.
+    fn generate_adaptor_points(
+        &'a mut self,
+        outcomes: &[RangePayout],
+        precomputed_points: &[Vec<Vec<PublicKey>>],
+        adaptor_index_start: usize,
+    ) -> Result<Vec<(usize, PublicKey)>, Error> {
+        let trie_info = self.generate(adaptor_index_start, outcomes)?;
+        let mut unsorted = trie_info
+            .into_iter()
+            .map(|x| {
+                let adaptor_point = utils::get_adaptor_point_for_indexed_paths(
+                    &x.indexes,
+                    &x.paths,
+                    precomputed_points,
+                )?;
+                Ok((x.value.adaptor_index, (x.value.cet_index, adaptor_point)))
+            })
+            .collect::<Result<Vec<(usize, (usize, PublicKey))>, Error>>()?;
+        unsorted.sort_by_key(|(adaptor_index, _)| *adaptor_index);
+        Ok(unsorted.into_iter().map(|(_, y)| y).collect())
+    }
+
     /// Verify that the provided signatures are valid with respect to the
     /// information stored in the trie.
     #[allow(clippy::too_many_arguments)]
@@ -0,0 +1,303 @@
+//! Anchor outputs and child-pays-for-parent fee bumping.
+//!
+//! CETs and refund transactions are signed when the contract is set up, at
+//! the fee rate of the offer, and cannot be re-signed at a higher one. With
+//! [`crate::ANCHOR_OUTPUTS_FLAG`] set, each of them gets an extra
+//! pay-to-anchor output that anyone can spend with an empty witness. Either
+//! party can then get a stuck transaction confirmed with a child transaction
+//! spending the anchor and paying for both (CPFP).
+//!
+//! The value of the anchor output and the fee for its weight are paid into
+//! the fund output by the parties, in the same shares as the CET fee.
+
+use bitcoin::{
+    absolute::LockTime,
+    blockdata::{opcodes, script::Builder},
+    Amount, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+
+use crate::{util, Error, TxInputInfo, ANCHOR_OUTPUTS_FLAG, DUST_LIMIT, TX_INPUT_BASE_WEIGHT};
+
+/// Value of an anchor output, the dust limit of a pay-to-anchor output.
+pub const ANCHOR_OUTPUT_VALUE: Amount = Amount::from_sat(240);
+
+/// Weight of an anchor output: value (8) + script length (1) + script (4)
+/// bytes.
+pub(crate) const ANCHOR_OUTPUT_WEIGHT: usize = 52;
+
+/// Weight of an input spending an anchor output, with its empty witness.
+const ANCHOR_INPUT_WEIGHT: usize = TX_INPUT_BASE_WEIGHT + 1;
+
+/// Weight of a child transaction without inputs and outputs: version,
+/// lock time, segwit marker and flag, and the input and output counts.
+const CHILD_BASE_WEIGHT: usize = 42;
+
+/// Sequence of the inputs of a child transaction, signaling replaceability
+/// so it can be bumped again.
+const CHILD_SEQUENCE: Sequence = Sequence(0xfffffffd);
+
+/// The pay-to-anchor script pubkey (`OP_1 <0x4e73>`).
+pub fn anchor_script_pubkey() -> ScriptBuf {
+    Builder::new()
+        .push_opcode(opcodes::all::OP_PUSHNUM_1)
+        .push_slice([0x4e, 0x73])
+        .into_script()
+}
+
+/// An anchor output, added last to the CETs and refund transaction of a
+/// contract with [`ANCHOR_OUTPUTS_FLAG`] set.
+pub fn anchor_output() -> TxOut {
+    TxOut {
+        value: ANCHOR_OUTPUT_VALUE,
+        script_pubkey: anchor_script_pubkey(),
+    }
+}
+
+/// The anchor output of a transaction, with its index, if it has one.
+pub fn get_anchor_output(tx: &Transaction) -> Option<(usize, &TxOut)> {
+    util::get_output_for_script_pubkey(tx, &anchor_script_pubkey())
+}
+
+/// The share of the anchor output and of its fee paid by a party, zero when
+/// `contract_flags` does not set [`ANCHOR_OUTPUTS_FLAG`].
+pub fn get_anchor_cost(
+    contract_flags: u8,
+    collateral: Amount,
+    total_collateral: Amount,
+    fee_rate_per_vb: u64,
+) -> Result<Amount, Error> {
+    if contract_flags & ANCHOR_OUTPUTS_FLAG == 0 || collateral == Amount::ZERO {
+        return Ok(Amount::ZERO);
+    }
+    // Split like the base weight of the CET.
+    let (value, weight) = if collateral == total_collateral {
+        (ANCHOR_OUTPUT_VALUE, ANCHOR_OUTPUT_WEIGHT)
+    } else {
+        (ANCHOR_OUTPUT_VALUE / 2, ANCHOR_OUTPUT_WEIGHT / 2)
+    };
+    Ok(value + util::weight_to_fee(weight, fee_rate_per_vb)?)
+}
+
+/// The amount the wallet inputs of a child transaction must bring on top of
+/// their own fees, for the child to pay the fee of the package at
+/// `fee_rate_per_vb` and keep a change output.
+pub fn get_cpfp_funding_amount(
+    parent: &Transaction,
+    parent_fee: Amount,
+    change_script_pubkey: &Script,
+    fee_rate_per_vb: u64,
+) -> Result<Amount, Error> {
+    let fee = get_cpfp_fee(
+        parent,
+        parent_fee,
+        &[],
+        change_script_pubkey,
+        fee_rate_per_vb,
+    )?;
+    Ok((fee + DUST_LIMIT)
+        .checked_sub(ANCHOR_OUTPUT_VALUE)
+        .unwrap_or(Amount::ZERO))
+}
+
+/// Creates an unsigned child transaction spending the anchor output of
+/// `parent` and `inputs`, worth `input_amount`, and paying the rest to
+/// `change_script_pubkey`.
+///
+/// The child pays for the parent and itself to reach `fee_rate_per_vb`,
+/// and at least that fee rate for its own weight. The witness of the anchor
+/// input is empty, only the wallet inputs need to be signed.
+pub fn create_cpfp_transaction(
+    parent: &Transaction,
+    parent_fee: Amount,
+    inputs: &[TxInputInfo],
+    input_amount: Amount,
+    change_script_pubkey: &Script,
+    fee_rate_per_vb: u64,
+) -> Result<Transaction, Error> {
+    let (anchor_vout, anchor_output) = get_anchor_output(parent).ok_or(Error::InvalidArgument(
+        "Transaction has no anchor output".to_string(),
+    ))?;
+    let fee = get_cpfp_fee(
+        parent,
+        parent_fee,
+        inputs,
+        change_script_pubkey,
+        fee_rate_per_vb,
+    )?;
+    let change_value = anchor_output
+        .value
+        .checked_add(input_amount)
+        .and_then(|total| total.checked_sub(fee))
+        .filter(|change| *change >= DUST_LIMIT)
+        .ok_or(Error::InvalidArgument(format!(
+            "Inputs do not cover the fee of the child transaction. input_amount={input_amount} fee={fee}"
+        )))?;
+
+    let anchor_input = TxIn {
+        previous_output: OutPoint {
+            txid: parent.compute_txid(),
+            vout: anchor_vout as u32,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: CHILD_SEQUENCE,
+        witness: Witness::new(),
+    };
+    let wallet_inputs = inputs.iter().map(|input| TxIn {
+        previous_output: input.outpoint,
+        script_sig: util::redeem_script_to_script_sig(&input.redeem_script),
+        sequence: CHILD_SEQUENCE,
+        witness: Witness::new(),
+    });
+
+    Ok(Transaction {
+        version: crate::TX_VERSION,
+        lock_time: LockTime::ZERO,
+        input: std::iter::once(anchor_input).chain(wallet_inputs).collect(),
+        output: vec![TxOut {
+            value: change_value,
+            script_pubkey: change_script_pubkey.to_owned(),
+        }],
+    })
+}
+
+/// The fee of a child transaction spending the anchor of `parent`.
+fn get_cpfp_fee(
+    parent: &Transaction,
+    parent_fee: Amount,
+    inputs: &[TxInputInfo],
+    change_script_pubkey: &Script,
+    fee_rate_per_vb: u64,
+) -> Result<Amount, Error> {
+    let child_weight = CHILD_BASE_WEIGHT
+        + ANCHOR_INPUT_WEIGHT
+        + (9 + change_script_pubkey.len()) * 4
+        + inputs
+            .iter()
+            .map(|input| {
+                TX_INPUT_BASE_WEIGHT
+                    + util::redeem_script_to_script_sig(&input.redeem_script).len() * 4
+                    + input.max_witness_len
+            })
+            .sum::<usize>();
+    let package_weight = parent.weight().to_wu() as usize + child_weight;
+    let package_fee = util::weight_to_fee(package_weight, fee_rate_per_vb)?;
+    let child_fee = util::weight_to_fee(child_weight, fee_rate_per_vb)?;
+    Ok(package_fee
+        .checked_sub(parent_fee)
+        .unwrap_or(Amount::ZERO)
+        .max(child_fee))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bitcoin::hashes::Hash;
+    use bitcoin::{transaction::Version, Txid};
+
+    use super::*;
+
+    fn parent() -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_str(
+                        "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456",
+                    )
+                    .unwrap(),
+                    vout: 0,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_LOCKTIME_NO_RBF,
+                witness: Witness::from_slice(&[vec![0u8; 72], vec![0u8; 72], vec![0u8; 71]]),
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey: ScriptBuf::new_p2wsh(&bitcoin::WScriptHash::all_zeros()),
+                },
+                TxOut {
+                    value: ANCHOR_OUTPUT_VALUE,
+                    script_pubkey: anchor_script_pubkey(),
+                },
+            ],
+        }
+    }
+
+    fn wallet_input() -> TxInputInfo {
+        TxInputInfo {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "6df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456",
+                )
+                .unwrap(),
+                vout: 1,
+            },
+            max_witness_len: 108,
+            redeem_script: ScriptBuf::new(),
+            serial_id: 0,
+        }
+    }
+
+    #[test]
+    fn child_pays_for_the_package() {
+        let parent = parent();
+        let parent_fee = Amount::from_sat(200);
+        let change = ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::all_zeros());
+        let fee_rate = 20;
+
+        let funding = get_cpfp_funding_amount(&parent, parent_fee, &change, fee_rate).unwrap();
+        let input_amount = funding + util::weight_to_fee(164 + 108, fee_rate).unwrap();
+        let child = create_cpfp_transaction(
+            &parent,
+            parent_fee,
+            &[wallet_input()],
+            input_amount,
+            &change,
+            fee_rate,
+        )
+        .unwrap();
+
+        assert_eq!(child.input[0].previous_output.txid, parent.compute_txid());
+        assert_eq!(child.input[0].previous_output.vout, 1);
+        assert_eq!(child.output[0].value, DUST_LIMIT);
+
+        // Once the wallet input is signed, the package pays the fee rate.
+        let mut signed = child.clone();
+        signed.input[1].witness = Witness::from_slice(&[vec![0u8; 72], vec![0u8; 33]]);
+        let child_fee = ANCHOR_OUTPUT_VALUE + input_amount - child.output[0].value;
+        let package_vsize = (parent.weight() + signed.weight()).to_vbytes_ceil();
+        assert!((parent_fee + child_fee).to_sat() >= package_vsize * fee_rate);
+
+        assert!(create_cpfp_transaction(
+            &parent,
+            parent_fee,
+            &[wallet_input()],
+            input_amount - Amount::from_sat(1),
+            &change,
+            fee_rate,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn anchor_cost_is_split_like_the_cet_fee() {
+        let total = Amount::from_sat(200_000);
+        let half = Amount::from_sat(100_000);
+        assert_eq!(get_anchor_cost(0, total, total, 10).unwrap(), Amount::ZERO);
+        assert_eq!(
+            get_anchor_cost(ANCHOR_OUTPUTS_FLAG, total, total, 10).unwrap(),
+            ANCHOR_OUTPUT_VALUE + Amount::from_sat(130)
+        );
+        assert_eq!(
+            get_anchor_cost(ANCHOR_OUTPUTS_FLAG, half, total, 10).unwrap(),
+            ANCHOR_OUTPUT_VALUE / 2 + Amount::from_sat(70)
+        );
+        assert_eq!(
+            get_anchor_cost(ANCHOR_OUTPUTS_FLAG, Amount::ZERO, total, 10).unwrap(),
+            Amount::ZERO
+        );
+    }
+}
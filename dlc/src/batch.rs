@@ -0,0 +1,483 @@
+//! Batch funding: several contracts between the same two parties locked in a
+//! single fund transaction.
+//!
+//! The fund transaction has one 2-of-2 output per contract, and the CETs and
+//! refund transaction of each contract spend its own output, exactly as if the
+//! contract had been funded on its own. Each party contributes one set of
+//! inputs and one change output to the whole batch, so the fees for the
+//! transaction base, the inputs and the change are paid once instead of once
+//! per contract.
+
+use bitcoin::{Amount, OutPoint, TxOut};
+
+use crate::{
+    create_cets_and_refund_tx, make_funding_redeemscript, util, DlcTransactions, Error,
+    PartyParams, Payout, CET_BASE_WEIGHT, FUND_TX_BASE_WEIGHT, TAPROOT_FUNDING_FLAG,
+};
+
+/// Weight of a P2WSH fund output: value (8) + script length (1) + script (34)
+/// bytes.
+const FUND_OUTPUT_WEIGHT: usize = 172;
+
+/// The parameters of one of the contracts of a batch.
+///
+/// The inputs, change script pubkey, change serial id and input amount of
+/// each party are those of the whole batch, and must be the same for every
+/// contract. The other fields are specific to the contract.
+#[derive(Clone, Debug)]
+pub struct BatchContractParams {
+    /// The parameters of the offer party for this contract.
+    pub offer_params: PartyParams,
+    /// The parameters of the accept party for this contract.
+    pub accept_params: PartyParams,
+    /// The payouts of the contract.
+    pub payouts: Vec<Payout>,
+    /// The lock time of the refund transaction.
+    pub refund_lock_time: u32,
+    /// The lock time of the CETs.
+    pub cet_lock_time: u32,
+    /// The serial id of the fund output of the contract.
+    pub fund_output_serial_id: u64,
+    /// The flags of the contract. Taproot funding is not supported.
+    pub contract_flags: u8,
+}
+
+/// Create the transactions of a batch of contracts, in the order of
+/// `contracts`. They all share the same fund transaction, each spending the
+/// fund output paying to its own funding script.
+pub fn create_batch_dlc_transactions(
+    contracts: &[BatchContractParams],
+    fee_rate_per_vb: u64,
+    fund_lock_time: u32,
+) -> Result<Vec<DlcTransactions>, Error> {
+    validate_batch(contracts)?;
+
+    let fund_base_weight = FUND_TX_BASE_WEIGHT + (contracts.len() - 1) * FUND_OUTPUT_WEIGHT;
+    let total_collaterals = contracts
+        .iter()
+        .map(|c| {
+            c.offer_params
+                .collateral
+                .checked_add(c.accept_params.collateral)
+                .ok_or(Error::InvalidArgument(
+                    "Contract collateral overflow".to_string(),
+                ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let batch_collateral = sum(&total_collaterals)?;
+
+    let offer_params = contracts
+        .iter()
+        .map(|c| &c.offer_params)
+        .collect::<Vec<_>>();
+    let accept_params = contracts
+        .iter()
+        .map(|c| &c.accept_params)
+        .collect::<Vec<_>>();
+    let (offer_change_output, offer_cet_fees) = get_change_output_and_cet_fees(
+        &offer_params,
+        contracts,
+        &total_collaterals,
+        batch_collateral,
+        fund_base_weight,
+        fee_rate_per_vb,
+    )?;
+    let (accept_change_output, accept_cet_fees) = get_change_output_and_cet_fees(
+        &accept_params,
+        contracts,
+        &total_collaterals,
+        batch_collateral,
+        fund_base_weight,
+        fee_rate_per_vb,
+    )?;
+
+    let mut funding_witness_scripts = Vec::with_capacity(contracts.len());
+    let mut fund_outputs = Vec::with_capacity(contracts.len());
+    for (i, contract) in contracts.iter().enumerate() {
+        let funding_witness_script = make_funding_redeemscript(
+            &contract.offer_params.fund_pubkey,
+            &contract.accept_params.fund_pubkey,
+        );
+        fund_outputs.push(TxOut {
+            value: total_collaterals[i] + offer_cet_fees[i] + accept_cet_fees[i],
+            script_pubkey: funding_witness_script.to_p2wsh(),
+        });
+        funding_witness_scripts.push(funding_witness_script);
+    }
+    let fund_output_serial_ids = contracts
+        .iter()
+        .map(|c| c.fund_output_serial_id)
+        .collect::<Vec<_>>();
+
+    let fund_sequence = util::get_sequence(fund_lock_time);
+    let (offer_tx_ins, offer_inputs_serial_ids) =
+        offer_params[0].get_unsigned_tx_inputs_and_serial_ids(fund_sequence);
+    let (accept_tx_ins, accept_inputs_serial_ids) =
+        accept_params[0].get_unsigned_tx_inputs_and_serial_ids(fund_sequence);
+
+    let fund_tx = crate::build_funding_transaction(
+        fund_outputs,
+        &fund_output_serial_ids,
+        &offer_tx_ins,
+        &offer_inputs_serial_ids,
+        &accept_tx_ins,
+        &accept_inputs_serial_ids,
+        offer_change_output,
+        offer_params[0].change_serial_id,
+        accept_change_output,
+        accept_params[0].change_serial_id,
+        fund_lock_time,
+    );
+    let fund_txid = fund_tx.compute_txid();
+
+    contracts
+        .iter()
+        .zip(funding_witness_scripts)
+        .map(|(contract, funding_witness_script)| {
+            let vout =
+                util::get_output_for_script_pubkey(&fund_tx, &funding_witness_script.to_p2wsh())
+                    .expect("to find the funding script pubkey")
+                    .0 as u32;
+            let (cets, refund) = create_cets_and_refund_tx(
+                &contract.offer_params,
+                &contract.accept_params,
+                OutPoint {
+                    txid: fund_txid,
+                    vout,
+                },
+                &contract.payouts,
+                contract.refund_lock_time,
+                contract.cet_lock_time,
+                None,
+                contract.contract_flags,
+            )?;
+            Ok(DlcTransactions {
+                fund: fund_tx.clone(),
+                cets,
+                refund,
+                funding_witness_script,
+                pending_close_txs: vec![],
+            })
+        })
+        .collect()
+}
+
+/// Returns the change output of a party for the whole batch, along with the
+/// fee it pays for the CET or refund transaction of each contract.
+fn get_change_output_and_cet_fees(
+    party_params: &[&PartyParams],
+    contracts: &[BatchContractParams],
+    total_collaterals: &[Amount],
+    batch_collateral: Amount,
+    fund_base_weight: usize,
+    fee_rate_per_vb: u64,
+) -> Result<(TxOut, Vec<Amount>), Error> {
+    let params = party_params[0];
+    let collateral = sum(&party_params
+        .iter()
+        .map(|p| p.collateral)
+        .collect::<Vec<_>>())?;
+
+    // Same as for a single contract, a party that funds nothing pays nothing.
+    if collateral == Amount::ZERO {
+        let change_output = TxOut {
+            value: Amount::ZERO,
+            script_pubkey: params.change_script_pubkey.clone(),
+        };
+        return Ok((change_output, vec![Amount::ZERO; contracts.len()]));
+    }
+
+    let fund_fee = params.get_fund_fee(
+        fund_base_weight,
+        collateral == batch_collateral,
+        fee_rate_per_vb,
+    )?;
+    let cet_fees = party_params
+        .iter()
+        .zip(contracts)
+        .zip(total_collaterals)
+        .map(|((p, c), total_collateral)| {
+            p.get_cet_or_refund_fee(
+                *total_collateral,
+                fee_rate_per_vb,
+                CET_BASE_WEIGHT,
+                c.contract_flags,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let required_input_funds = sum(&[collateral, fund_fee, sum(&cet_fees)?])?;
+    if params.input_amount < required_input_funds {
+        return Err(Error::InvalidArgument(format!(
+            "Input amount is less than required input funds. input_amount={} required_input_funds={}",
+            params.input_amount, required_input_funds
+        )));
+    }
+
+    let change_output = TxOut {
+        value: params.input_amount - required_input_funds,
+        script_pubkey: params.change_script_pubkey.clone(),
+    };
+
+    Ok((change_output, cet_fees))
+}
+
+fn validate_batch(contracts: &[BatchContractParams]) -> Result<(), Error> {
+    let Some(first) = contracts.first() else {
+        return Err(Error::InvalidArgument("Empty batch".to_string()));
+    };
+
+    for contract in contracts {
+        if contract.contract_flags & TAPROOT_FUNDING_FLAG != 0 {
+            return Err(Error::InvalidArgument(
+                "Batch funded contracts cannot be Taproot funded".to_string(),
+            ));
+        }
+        for (params, first_params) in [
+            (&contract.offer_params, &first.offer_params),
+            (&contract.accept_params, &first.accept_params),
+        ] {
+            if !params.dlc_inputs.is_empty() {
+                return Err(Error::InvalidArgument(
+                    "Batch funded contracts cannot have DLC inputs".to_string(),
+                ));
+            }
+            if !is_same_funding(params, first_params) {
+                return Err(Error::InvalidArgument(
+                    "Contracts of a batch must share the inputs and change of each party"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    for (i, contract) in contracts.iter().enumerate() {
+        for other in &contracts[i + 1..] {
+            if contract.fund_output_serial_id == other.fund_output_serial_id {
+                return Err(Error::InvalidArgument(
+                    "Duplicate fund output serial id in batch".to_string(),
+                ));
+            }
+            let same_keys = contract.offer_params.fund_pubkey == other.offer_params.fund_pubkey
+                && contract.accept_params.fund_pubkey == other.accept_params.fund_pubkey;
+            if same_keys {
+                return Err(Error::InvalidArgument(
+                    "Contracts of a batch must have distinct funding scripts".to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn is_same_funding(a: &PartyParams, b: &PartyParams) -> bool {
+    a.change_script_pubkey == b.change_script_pubkey
+        && a.change_serial_id == b.change_serial_id
+        && a.input_amount == b.input_amount
+        && a.inputs.len() == b.inputs.len()
+        && a.inputs
+            .iter()
+            .zip(&b.inputs)
+            .all(|(x, y)| x.outpoint == y.outpoint && x.serial_id == y.serial_id)
+}
+
+fn sum(amounts: &[Amount]) -> Result<Amount, Error> {
+    amounts
+        .iter()
+        .try_fold(Amount::ZERO, |acc, a| acc.checked_add(*a))
+        .ok_or(Error::InvalidArgument("Amount overflow".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_dlc_transactions, TxInputInfo};
+    use bitcoin::hashes::Hash;
+    use bitcoin::{ScriptBuf, Txid};
+    use secp256k1_zkp::{PublicKey, Secp256k1, SecretKey};
+    use std::str::FromStr;
+
+    fn pubkey(byte: u8) -> PublicKey {
+        PublicKey::from_secret_key(
+            &Secp256k1::signing_only(),
+            &SecretKey::from_slice(&[byte; 32]).unwrap(),
+        )
+    }
+
+    fn party_params(
+        fund_key: u8,
+        collateral: Amount,
+        input_amount: Amount,
+        serial_base: u64,
+    ) -> PartyParams {
+        PartyParams {
+            fund_pubkey: pubkey(fund_key),
+            change_script_pubkey: ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array(
+                [serial_base as u8; 20],
+            )),
+            change_serial_id: serial_base,
+            payout_script_pubkey: ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array(
+                [fund_key; 20],
+            )),
+            payout_serial_id: serial_base + fund_key as u64,
+            inputs: vec![TxInputInfo {
+                outpoint: OutPoint {
+                    txid: Txid::from_str(
+                        "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456",
+                    )
+                    .unwrap(),
+                    vout: serial_base as u32,
+                },
+                max_witness_len: 108,
+                redeem_script: ScriptBuf::new(),
+                serial_id: serial_base + 1,
+            }],
+            dlc_inputs: vec![],
+            input_amount,
+            collateral,
+        }
+    }
+
+    fn contract(
+        offer_key: u8,
+        accept_key: u8,
+        offer_collateral: u64,
+        accept_collateral: u64,
+        fund_output_serial_id: u64,
+    ) -> BatchContractParams {
+        let offer_collateral = Amount::from_sat(offer_collateral);
+        let accept_collateral = Amount::from_sat(accept_collateral);
+        let total = offer_collateral + accept_collateral;
+        BatchContractParams {
+            offer_params: party_params(offer_key, offer_collateral, Amount::ONE_BTC, 10),
+            accept_params: party_params(accept_key, accept_collateral, Amount::ONE_BTC, 20),
+            payouts: vec![
+                Payout {
+                    offer: total,
+                    accept: Amount::ZERO,
+                },
+                Payout {
+                    offer: Amount::ZERO,
+                    accept: total,
+                },
+            ],
+            refund_lock_time: 100,
+            cet_lock_time: 10,
+            fund_output_serial_id,
+            contract_flags: 0,
+        }
+    }
+
+    #[test]
+    fn batch_of_one_matches_single_contract() {
+        let c = contract(1, 2, 100_000_000 / 4, 100_000_000 / 4, 15);
+        let batch = create_batch_dlc_transactions(std::slice::from_ref(&c), 4, 0).unwrap();
+        let single = create_dlc_transactions(
+            &c.offer_params,
+            &c.accept_params,
+            &c.payouts,
+            c.refund_lock_time,
+            4,
+            0,
+            c.cet_lock_time,
+            c.fund_output_serial_id,
+            c.contract_flags,
+        )
+        .unwrap();
+
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].fund, single.fund);
+        assert_eq!(batch[0].cets, single.cets);
+        assert_eq!(batch[0].refund, single.refund);
+    }
+
+    #[test]
+    fn batch_shares_one_fund_transaction() {
+        let contracts = vec![
+            contract(1, 2, 10_000_000, 10_000_000, 15),
+            contract(3, 4, 20_000_000, 0, 5),
+            contract(5, 6, 0, 30_000_000, 25),
+        ];
+        let txs = create_batch_dlc_transactions(&contracts, 10, 0).unwrap();
+
+        let fund = &txs[0].fund;
+        assert_eq!(fund.input.len(), 2);
+        assert_eq!(fund.output.len(), contracts.len() + 2);
+        let mut outpoints = Vec::new();
+        for (contract, dlc_txs) in contracts.iter().zip(&txs) {
+            assert_eq!(&dlc_txs.fund, fund);
+            let outpoint = dlc_txs.get_fund_outpoint();
+            for tx in dlc_txs.cets.iter().chain(std::iter::once(&dlc_txs.refund)) {
+                assert_eq!(tx.input[0].previous_output, outpoint);
+            }
+            let total = contract.offer_params.collateral + contract.accept_params.collateral;
+            assert!(dlc_txs.get_fund_output().value > total);
+            outpoints.push(outpoint);
+        }
+        outpoints.dedup();
+        assert_eq!(outpoints.len(), contracts.len());
+
+        // Fund outputs are ordered by serial id along with the change outputs,
+        // whose serial ids are 10 and 20.
+        assert_eq!(txs[1].get_fund_output_index(), 0);
+        assert_eq!(txs[0].get_fund_output_index(), 2);
+        assert_eq!(txs[2].get_fund_output_index(), 4);
+    }
+
+    #[test]
+    fn batch_pays_fund_transaction_fees_once() {
+        let contracts = vec![
+            contract(1, 2, 10_000_000, 10_000_000, 15),
+            contract(3, 4, 10_000_000, 10_000_000, 16),
+        ];
+        let batch_fund = create_batch_dlc_transactions(&contracts, 10, 0).unwrap()[0]
+            .fund
+            .clone();
+        let single_fund_fee = |c: &BatchContractParams| {
+            let txs = create_batch_dlc_transactions(std::slice::from_ref(c), 10, 0).unwrap();
+            Amount::ONE_BTC * 2 - txs[0].fund.output.iter().map(|o| o.value).sum()
+        };
+
+        let batch_fee =
+            Amount::ONE_BTC * 2 - batch_fund.output.iter().map(|o| o.value).sum::<Amount>();
+        assert!(batch_fee < single_fund_fee(&contracts[0]) + single_fund_fee(&contracts[1]));
+    }
+
+    #[test]
+    fn batch_requires_shared_funding() {
+        let mut contracts = vec![
+            contract(1, 2, 10_000_000, 10_000_000, 15),
+            contract(3, 4, 10_000_000, 10_000_000, 16),
+        ];
+        contracts[1].accept_params.change_serial_id += 1;
+        assert!(
+            create_batch_dlc_transactions(&contracts, 10, 0).is_err(),
+            "different change"
+        );
+    }
+
+    #[test]
+    fn batch_requires_distinct_funding_scripts() {
+        let contracts = vec![
+            contract(1, 2, 10_000_000, 10_000_000, 15),
+            contract(1, 2, 10_000_000, 10_000_000, 16),
+        ];
+        assert!(
+            create_batch_dlc_transactions(&contracts, 10, 0).is_err(),
+            "same funding script"
+        );
+    }
+
+    #[test]
+    fn batch_requires_enough_funds() {
+        let contracts = vec![
+            contract(1, 2, 60_000_000, 10_000_000, 15),
+            contract(3, 4, 60_000_000, 10_000_000, 16),
+        ];
+        assert!(
+            create_batch_dlc_transactions(&contracts, 10, 0).is_err(),
+            "not enough funds"
+        );
+    }
+}
@@ -11,7 +11,7 @@ use crate::{signatures_to_secret, util::get_sig_hash_msg, DlcTransactions, Party
 use super::Error;
 use bitcoin::{
     absolute::LockTime, ecdsa::Signature, sighash::EcdsaSighashType, Address, Amount, OutPoint,
-    PublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+    PublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
 };
 use miniscript::Descriptor;
 use secp256k1_zkp::{
@@ -289,6 +289,12 @@ pub fn create_renewal_channel_transactions(
     cet_nsequence: Sequence,
     contract_flags: u8,
 ) -> Result<DlcChannelTransactions, Error> {
+    if contract_flags & super::ANCHOR_OUTPUTS_FLAG != 0 {
+        return Err(Error::InvalidArgument(
+            "Anchor outputs are not supported in DLC channels".to_string(),
+        ));
+    }
+
     let extra_fee =
         super::util::weight_to_fee(BUFFER_TX_WEIGHT + CET_EXTRA_WEIGHT, fee_rate_per_vb)?;
 
@@ -415,69 +421,91 @@ pub fn create_and_sign_punish_buffer_transaction<C: Signing>(
     lock_time: u32,
     fee_rate_per_vb: u64,
 ) -> Result<Transaction, Error> {
-    let descriptor = buffer_descriptor(offer_params, accept_params);
-
-    let tx_in = TxIn {
-        previous_output: OutPoint {
-            txid: prev_tx.compute_txid(),
-            vout: 0,
-        },
-        sequence: Sequence::ZERO,
-        script_sig: ScriptBuf::default(),
-        witness: Witness::default(),
-    };
+    let buffer_value = prev_tx.output[0].value;
+    let mut tx = create_punish_buffer_transaction(
+        prev_tx.compute_txid(),
+        buffer_value,
+        dest_address,
+        lock_time,
+        fee_rate_per_vb,
+    )?;
 
-    let dest_script_pk_len = dest_address.script_pubkey().len();
-    let var_int_prefix_len = crate::util::compute_var_int_prefix_size(dest_script_pk_len);
-    let output_weight = N_VALUE_WEIGHT + var_int_prefix_len + dest_script_pk_len * 4;
-    let tx_fee =
-        crate::util::weight_to_fee(PUNISH_BUFFER_INPUT_WEIGHT + output_weight, fee_rate_per_vb)?;
+    let mut sigs = Vec::with_capacity(3);
+    for sk in [own_sk, counter_publish_sk, counter_revoke_sk] {
+        sigs.push((
+            PublicKey::new(SecpPublicKey::from_secret_key(secp, sk)),
+            get_punish_buffer_signature(secp, offer_params, accept_params, &tx, buffer_value, sk)?,
+        ));
+    }
 
-    let output_value = prev_tx.output[0].value - tx_fee;
+    sign_punish_buffer_transaction(offer_params, accept_params, &mut tx, &sigs)?;
 
-    let mut tx = Transaction {
-        version: super::TX_VERSION,
-        lock_time: LockTime::from_consensus(lock_time),
-        input: vec![tx_in],
-        output: vec![TxOut {
-            value: output_value,
-            script_pubkey: dest_address.script_pubkey(),
-        }],
-    };
+    Ok(tx)
+}
 
-    let mut sigs = BTreeMap::new();
+/// Returns the unsigned transaction claiming the output of the revoked buffer
+/// transaction `buffer_txid`, which holds `buffer_value`.
+pub fn create_punish_buffer_transaction(
+    buffer_txid: Txid,
+    buffer_value: Amount,
+    dest_address: &Address,
+    lock_time: u32,
+    fee_rate_per_vb: u64,
+) -> Result<Transaction, Error> {
+    create_punish_transaction(
+        OutPoint {
+            txid: buffer_txid,
+            vout: 0,
+        },
+        buffer_value,
+        PUNISH_BUFFER_INPUT_WEIGHT,
+        dest_address,
+        lock_time,
+        fee_rate_per_vb,
+    )
+}
 
-    for sk in &[&own_sk, &counter_publish_sk, &counter_revoke_sk] {
-        let pk = PublicKey {
-            inner: SecpPublicKey::from_secret_key(secp, sk),
-            compressed: true,
-        };
+/// Returns the signature of `sk` for the input of a transaction created with
+/// [`create_punish_buffer_transaction`].
+pub fn get_punish_buffer_signature<C: Signing>(
+    secp: &Secp256k1<C>,
+    offer_params: &RevokeParams,
+    accept_params: &RevokeParams,
+    punish_tx: &Transaction,
+    buffer_value: Amount,
+    sk: &SecretKey,
+) -> Result<Signature, Error> {
+    let descriptor = buffer_descriptor(offer_params, accept_params);
+    Ok(Signature::sighash_all(
+        super::util::get_raw_sig_for_tx_input(
+            secp,
+            punish_tx,
+            0,
+            &descriptor.script_code()?,
+            buffer_value,
+            sk,
+        )?,
+    ))
+}
 
-        let pkh = pk.pubkey_hash().to_raw_hash();
-        sigs.insert(
-            pkh,
-            (
-                pk,
-                bitcoin::ecdsa::Signature {
-                    signature: super::util::get_raw_sig_for_tx_input(
-                        secp,
-                        &tx,
-                        0,
-                        &descriptor.script_code()?,
-                        prev_tx.output[0].value,
-                        sk,
-                    )?,
-                    sighash_type: EcdsaSighashType::All,
-                },
-            ),
-        );
-    }
+/// Fills the witness of a transaction created with
+/// [`create_punish_buffer_transaction`] from the signatures of our own key and
+/// of the publish and revocation keys of the counter party.
+pub fn sign_punish_buffer_transaction(
+    offer_params: &RevokeParams,
+    accept_params: &RevokeParams,
+    punish_tx: &mut Transaction,
+    signatures: &[(PublicKey, Signature)],
+) -> Result<(), Error> {
+    let descriptor = buffer_descriptor(offer_params, accept_params);
+    let sigs: BTreeMap<_, _> = signatures
+        .iter()
+        .map(|(pk, sig)| (pk.pubkey_hash().to_raw_hash(), (*pk, *sig)))
+        .collect();
 
     descriptor
-        .satisfy(&mut tx.input[0], sigs)
-        .map_err(|_| Error::InvalidArgument("Failed to satisfy descriptor".to_string()))?;
-
-    Ok(tx)
+        .satisfy(&mut punish_tx.input[0], sigs)
+        .map_err(|_| Error::InvalidArgument("Failed to satisfy descriptor".to_string()))
 }
 
 /// Create and sign a punishment transaction for a revoked settle transaction.
@@ -496,35 +524,150 @@ pub fn create_and_sign_punish_settle_transaction<C: Signing>(
     fee_rate_per_vb: u64,
     is_offer: bool,
 ) -> Result<Transaction, Error> {
+    let settle_value = prev_tx.output[usize::from(is_offer)].value;
+    let mut tx = create_punish_settle_transaction(
+        prev_tx.compute_txid(),
+        settle_value,
+        dest_address,
+        lock_time,
+        fee_rate_per_vb,
+        is_offer,
+    )?;
+
+    let mut sigs = Vec::with_capacity(3);
+    for sk in [own_sk, counter_publish_sk, counter_revoke_sk] {
+        sigs.push((
+            PublicKey::new(SecpPublicKey::from_secret_key(secp, sk)),
+            get_punish_settle_signature(
+                secp,
+                offer_params,
+                accept_params,
+                &tx,
+                settle_value,
+                sk,
+                csv_timelock,
+                is_offer,
+            )?,
+        ));
+    }
+
+    sign_punish_settle_transaction(
+        offer_params,
+        accept_params,
+        &mut tx,
+        &sigs,
+        csv_timelock,
+        is_offer,
+    )?;
+
+    Ok(tx)
+}
+
+/// Returns the unsigned transaction claiming the output of the counter party
+/// in the revoked settle transaction `settle_txid`, which holds
+/// `settle_value`.
+pub fn create_punish_settle_transaction(
+    settle_txid: Txid,
+    settle_value: Amount,
+    dest_address: &Address,
+    lock_time: u32,
+    fee_rate_per_vb: u64,
+    is_offer: bool,
+) -> Result<Transaction, Error> {
+    create_punish_transaction(
+        OutPoint {
+            txid: settle_txid,
+            vout: u32::from(is_offer),
+        },
+        settle_value,
+        PUNISH_SETTLE_INPUT_WEIGHT,
+        dest_address,
+        lock_time,
+        fee_rate_per_vb,
+    )
+}
+
+/// Returns the signature of `sk` for the input of a transaction created with
+/// [`create_punish_settle_transaction`].
+#[allow(clippy::too_many_arguments)]
+pub fn get_punish_settle_signature<C: Signing>(
+    secp: &Secp256k1<C>,
+    offer_params: &RevokeParams,
+    accept_params: &RevokeParams,
+    punish_tx: &Transaction,
+    settle_value: Amount,
+    sk: &SecretKey,
+    csv_timelock: u32,
+    is_offer: bool,
+) -> Result<Signature, Error> {
+    let descriptor = punish_settle_descriptor(offer_params, accept_params, csv_timelock, is_offer);
+    Ok(Signature::sighash_all(
+        super::util::get_raw_sig_for_tx_input(
+            secp,
+            punish_tx,
+            0,
+            &descriptor.script_code()?,
+            settle_value,
+            sk,
+        )?,
+    ))
+}
+
+/// Fills the witness of a transaction created with
+/// [`create_punish_settle_transaction`] from the signatures of our own key and
+/// of the publish and revocation keys of the counter party.
+pub fn sign_punish_settle_transaction(
+    offer_params: &RevokeParams,
+    accept_params: &RevokeParams,
+    punish_tx: &mut Transaction,
+    signatures: &[(PublicKey, Signature)],
+    csv_timelock: u32,
+    is_offer: bool,
+) -> Result<(), Error> {
+    let descriptor = punish_settle_descriptor(offer_params, accept_params, csv_timelock, is_offer);
+    let sigs: BTreeMap<_, _> = signatures.iter().copied().collect();
+
+    descriptor
+        .satisfy(&mut punish_tx.input[0], sigs)
+        .map_err(|_| Error::InvalidArgument("Failed to satisfy descriptor".to_string()))
+}
+
+fn punish_settle_descriptor(
+    offer_params: &RevokeParams,
+    accept_params: &RevokeParams,
+    csv_timelock: u32,
+    is_offer: bool,
+) -> Descriptor<PublicKey> {
     let (own_params, counter_params) = if is_offer {
         (offer_params, accept_params)
     } else {
         (accept_params, offer_params)
     };
 
-    let descriptor = settle_descriptor(counter_params, &own_params.own_pk, csv_timelock);
-
-    let vout = u32::from(is_offer);
+    settle_descriptor(counter_params, &own_params.own_pk, csv_timelock)
+}
 
+fn create_punish_transaction(
+    revoked_outpoint: OutPoint,
+    input_value: Amount,
+    input_weight: usize,
+    dest_address: &Address,
+    lock_time: u32,
+    fee_rate_per_vb: u64,
+) -> Result<Transaction, Error> {
     let tx_in = TxIn {
-        previous_output: OutPoint {
-            txid: prev_tx.compute_txid(),
-            vout,
-        },
+        previous_output: revoked_outpoint,
         sequence: Sequence::ZERO,
         script_sig: ScriptBuf::default(),
         witness: Witness::default(),
     };
 
-    let input_value = prev_tx.output[vout as usize].value;
-
     let dest_script_pk_len = dest_address.script_pubkey().len();
     let var_int_prefix_len = crate::util::compute_var_int_prefix_size(dest_script_pk_len);
     let output_weight = N_VALUE_WEIGHT + var_int_prefix_len + dest_script_pk_len * 4;
-    let tx_fee =
-        crate::util::weight_to_fee(PUNISH_SETTLE_INPUT_WEIGHT + output_weight, fee_rate_per_vb)?;
+    let tx_fee = crate::util::weight_to_fee(input_weight + output_weight, fee_rate_per_vb)?;
 
-    let mut tx = Transaction {
+    Ok(Transaction {
         version: super::TX_VERSION,
         lock_time: LockTime::from_consensus(lock_time),
         input: vec![tx_in],
@@ -532,33 +675,7 @@ pub fn create_and_sign_punish_settle_transaction<C: Signing>(
             value: input_value - tx_fee,
             script_pubkey: dest_address.script_pubkey(),
         }],
-    };
-
-    let mut sigs = BTreeMap::new();
-
-    for sk in &[&own_sk, &counter_publish_sk, &counter_revoke_sk] {
-        let pk = PublicKey {
-            inner: SecpPublicKey::from_secret_key(secp, sk),
-            compressed: true,
-        };
-        sigs.insert(
-            pk,
-            Signature::sighash_all(super::util::get_raw_sig_for_tx_input(
-                secp,
-                &tx,
-                0,
-                &descriptor.script_code()?,
-                input_value,
-                sk,
-            )?),
-        );
-    }
-
-    descriptor
-        .satisfy(&mut tx.input[0], sigs)
-        .map_err(|_| Error::InvalidArgument("Failed to satisfy descriptor".to_string()))?;
-
-    Ok(tx)
+    })
 }
 
 /// Create a transaction for collaboratively closing a channel.
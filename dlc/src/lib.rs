@@ -17,6 +17,8 @@ extern crate alloc;
 extern crate bitcoin;
 extern crate core;
 extern crate miniscript;
+#[cfg(feature = "taproot")]
+pub extern crate musig2;
 extern crate secp256k1_sys;
 pub extern crate secp256k1_zkp;
 #[cfg(feature = "use-serde")]
@@ -45,9 +47,13 @@ use std::fmt;
 
 // use crate::dlc_input::calculate_total_dlc_input_amount;
 
+pub mod anchor;
+pub mod batch;
 pub mod channel;
 pub mod dlc_input;
 pub mod secp_utils;
+#[cfg(feature = "taproot")]
+pub mod taproot;
 pub mod util;
 
 /// Minimum value that can be included in a transaction output. Under this value,
@@ -58,6 +64,25 @@ const DUST_LIMIT: Amount = Amount::from_sat(1000);
 /// Bit 0 of `contract_flags`: redirect all refund proceeds to the accepter.
 pub const REFUND_TO_ACCEPTER_FLAG: u8 = 0x01;
 
+/// Bit 2 of `contract_flags`: lock the collateral in a Taproot output keyed by
+/// the MuSig2 aggregate of both funding keys instead of a P2WSH 2-of-2.
+///
+/// Bit 1 is left unused: stored contracts written before `contract_flags`
+/// existed are told apart by a leading `0x02`/`0x03` public key byte.
+pub const TAPROOT_FUNDING_FLAG: u8 = 0x04;
+
+/// Bit 3 of `contract_flags`: add an anchor output to the CETs and the refund
+/// transaction, so that either party can bump their fee with a child
+/// transaction. See [`anchor`].
+pub const ANCHOR_OUTPUTS_FLAG: u8 = 0x08;
+
+/// Bit 4 of `contract_flags`: the contract is funded by a fund transaction
+/// shared with other contracts. See [`batch`].
+///
+/// Only stored contracts set it, to record that a batch funding group follows
+/// their fields. Messages carry the group as a TLV record instead.
+pub const BATCH_FUNDING_FLAG: u8 = 0x10;
+
 /// The transaction version
 /// See: https://github.com/discreetlogcontracts/dlcspecs/blob/master/Transactions.md#funding-transaction
 const TX_VERSION: Version = Version::TWO;
@@ -142,7 +167,8 @@ pub struct DlcTransactions {
     /// The raw witness script of the fund output. This is not the script
     /// pubkey of the fund output. To get the script pubkey, use
     /// [`DlcTransactions::funding_script_pubkey`], which applies the P2WSH
-    /// wrapping.
+    /// wrapping. Taproot funded contracts have no witness script and store
+    /// the P2TR script pubkey of the fund output here instead.
     #[cfg_attr(feature = "use-serde", serde(alias = "funding_script_pubkey"))]
     pub funding_witness_script: ScriptBuf,
 
@@ -152,9 +178,19 @@ pub struct DlcTransactions {
 
 impl DlcTransactions {
     /// Get the script pubkey of the fund output in the fund transaction.
-    /// This is the P2WSH wrapping of [`DlcTransactions::funding_witness_script`].
+    /// This is the P2WSH wrapping of [`DlcTransactions::funding_witness_script`],
+    /// or the P2TR script pubkey itself for Taproot funded contracts.
     pub fn funding_script_pubkey(&self) -> ScriptBuf {
-        self.funding_witness_script.to_p2wsh()
+        if self.is_taproot() {
+            self.funding_witness_script.clone()
+        } else {
+            self.funding_witness_script.to_p2wsh()
+        }
+    }
+
+    /// Whether the fund output is a Taproot output rather than a P2WSH 2-of-2.
+    pub fn is_taproot(&self) -> bool {
+        self.funding_witness_script.is_p2tr()
     }
 
     /// Get the fund output in the fund transaction
@@ -320,8 +356,26 @@ impl PartyParams {
         fee_rate_per_vb: u64,
         extra_fee: Amount,
     ) -> Result<(TxOut, Amount, Amount), Error> {
-        let mut inputs_weight: usize = 0;
+        self.get_change_output_and_fees_with_cet_weight(
+            total_collateral,
+            fee_rate_per_vb,
+            extra_fee,
+            CET_BASE_WEIGHT,
+            0,
+        )
+    }
 
+    /// Same as [`PartyParams::get_change_output_and_fees`], with the base weight
+    /// of the CET or refund transaction depending on how the fund output is spent,
+    /// and the share of the anchor output when `contract_flags` asks for one.
+    pub(crate) fn get_change_output_and_fees_with_cet_weight(
+        &self,
+        total_collateral: Amount,
+        fee_rate_per_vb: u64,
+        extra_fee: Amount,
+        cet_base_weight: usize,
+        contract_flags: u8,
+    ) -> Result<(TxOut, Amount, Amount), Error> {
         // first check if a party does not need to fund the contract if so, then it is zero
         if self.collateral == Amount::ZERO {
             // We use a zero value output to indicate that the party does not need to fund the contract
@@ -332,6 +386,46 @@ impl PartyParams {
             return Ok((change_output, Amount::ZERO, Amount::ZERO));
         }
 
+        let fund_fee = self.get_fund_fee(
+            FUND_TX_BASE_WEIGHT,
+            self.collateral == total_collateral,
+            fee_rate_per_vb,
+        )?;
+        let cet_or_refund_fee = self.get_cet_or_refund_fee(
+            total_collateral,
+            fee_rate_per_vb,
+            cet_base_weight,
+            contract_flags,
+        )?;
+
+        let required_input_funds =
+            checked_add!(self.collateral, fund_fee, cet_or_refund_fee, extra_fee)?;
+        if self.input_amount < required_input_funds {
+            return Err(Error::InvalidArgument(format!(
+                "Input amount is less than required input funds. input_amount={} required_input_funds={}",
+                self.input_amount, required_input_funds
+            )));
+        }
+
+        let change_output = TxOut {
+            value: self.input_amount - required_input_funds,
+            script_pubkey: self.change_script_pubkey.clone(),
+        };
+
+        Ok((change_output, fund_fee, cet_or_refund_fee))
+    }
+
+    /// Returns the fee the party pays for its inputs and change output in the
+    /// fund transaction, plus its share of `fund_base_weight`: all of it if
+    /// the party funds the whole contract, half of it otherwise.
+    pub(crate) fn get_fund_fee(
+        &self,
+        fund_base_weight: usize,
+        funds_whole_contract: bool,
+        fee_rate_per_vb: u64,
+    ) -> Result<Amount, Error> {
+        let mut inputs_weight: usize = 0;
+
         inputs_weight += dlc_input::get_dlc_inputs_weight(&self.dlc_inputs);
 
         for w in &self.inputs {
@@ -363,10 +457,10 @@ impl PartyParams {
         // If the party is funding the whole contract, then the base weight is the full base weight
         // otherwise, the base weight (nLocktime, nVersion, ...) is distributed among parties
         // independently of inputs contributed
-        let this_party_fund_base_weight = if self.collateral == total_collateral {
-            FUND_TX_BASE_WEIGHT
+        let this_party_fund_base_weight = if funds_whole_contract {
+            fund_base_weight
         } else {
-            FUND_TX_BASE_WEIGHT / 2
+            fund_base_weight / 2
         };
 
         let total_fund_weight = checked_add!(
@@ -375,15 +469,31 @@ impl PartyParams {
             change_weight,
             36
         )?;
-        let fund_fee = util::weight_to_fee(total_fund_weight, fee_rate_per_vb)?;
+        util::weight_to_fee(total_fund_weight, fee_rate_per_vb)
+    }
+
+    /// Returns the fee the party pays for the CET or refund transaction of a
+    /// contract of `total_collateral`, including its share of the anchor
+    /// output when `contract_flags` asks for one. Zero if the party puts no
+    /// collateral in the contract.
+    pub(crate) fn get_cet_or_refund_fee(
+        &self,
+        total_collateral: Amount,
+        fee_rate_per_vb: u64,
+        cet_base_weight: usize,
+        contract_flags: u8,
+    ) -> Result<Amount, Error> {
+        if self.collateral == Amount::ZERO {
+            return Ok(Amount::ZERO);
+        }
 
         // If the party is funding the whole contract, then the base weight is the full base weight
         // otherwise, the base weight (nLocktime, nVersion, funding input ...) is distributed
         // among parties independently of output types
         let this_party_cet_base_weight = if self.collateral == total_collateral {
-            CET_BASE_WEIGHT
+            cet_base_weight
         } else {
-            CET_BASE_WEIGHT / 2
+            cet_base_weight / 2
         };
 
         // size of the payout script pubkey scaled by 4 from vBytes to weight units
@@ -396,26 +506,20 @@ impl PartyParams {
                     self.payout_script_pubkey.len()
                 )))?;
         let total_cet_weight = checked_add!(this_party_cet_base_weight, output_spk_weight)?;
-        let cet_or_refund_fee = util::weight_to_fee(total_cet_weight, fee_rate_per_vb)?;
-
-        let required_input_funds =
-            checked_add!(self.collateral, fund_fee, cet_or_refund_fee, extra_fee)?;
-        if self.input_amount < required_input_funds {
-            return Err(Error::InvalidArgument(format!(
-                "Input amount is less than required input funds. input_amount={} required_input_funds={}",
-                self.input_amount, required_input_funds
-            )));
-        }
-
-        let change_output = TxOut {
-            value: self.input_amount - required_input_funds,
-            script_pubkey: self.change_script_pubkey.clone(),
-        };
-
-        Ok((change_output, fund_fee, cet_or_refund_fee))
+        let cet_fee = util::weight_to_fee(total_cet_weight, fee_rate_per_vb)?;
+        let anchor_cost = anchor::get_anchor_cost(
+            contract_flags,
+            self.collateral,
+            total_collateral,
+            fee_rate_per_vb,
+        )?;
+        checked_add!(cet_fee, anchor_cost)
     }
 
-    fn get_unsigned_tx_inputs_and_serial_ids(&self, sequence: Sequence) -> (Vec<TxIn>, Vec<u64>) {
+    pub(crate) fn get_unsigned_tx_inputs_and_serial_ids(
+        &self,
+        sequence: Sequence,
+    ) -> (Vec<TxIn>, Vec<u64>) {
         let mut tx_ins = Vec::with_capacity(self.inputs.len());
         let mut serial_ids = Vec::with_capacity(self.inputs.len());
 
@@ -509,13 +613,18 @@ pub fn create_dlc_transactions(
     fund_output_serial_id: u64,
     contract_flags: u8,
 ) -> Result<DlcTransactions, Error> {
-    let (fund_tx, funding_witness_script) = create_fund_transaction_with_fees(
+    let funding_witness_script =
+        make_funding_redeemscript(&offer_params.fund_pubkey, &accept_params.fund_pubkey);
+    let fund_tx = create_fund_transaction_for_script_pubkey(
         offer_params,
         accept_params,
         fee_rate_per_vb,
         fund_lock_time,
         fund_output_serial_id,
         Amount::ZERO,
+        CET_BASE_WEIGHT,
+        contract_flags,
+        funding_witness_script.to_p2wsh(),
     )?;
     let fund_outpoint = OutPoint {
         txid: fund_tx.compute_txid(),
@@ -552,12 +661,57 @@ pub fn create_fund_transaction_with_fees(
     fund_output_serial_id: u64,
     extra_fee: Amount,
 ) -> Result<(Transaction, ScriptBuf), Error> {
+    let funding_witness_script =
+        make_funding_redeemscript(&offer_params.fund_pubkey, &accept_params.fund_pubkey);
+
+    let fund_tx = create_fund_transaction_for_script_pubkey(
+        offer_params,
+        accept_params,
+        fee_rate_per_vb,
+        fund_lock_time,
+        fund_output_serial_id,
+        extra_fee,
+        CET_BASE_WEIGHT,
+        0,
+        funding_witness_script.to_p2wsh(),
+    )?;
+
+    Ok((fund_tx, funding_witness_script))
+}
+
+/// Create a funding transaction with fees paying to the given fund output
+/// script pubkey, reserving fees for a CET of the given base weight and, with
+/// [`ANCHOR_OUTPUTS_FLAG`] set in `contract_flags`, for its anchor output.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_fund_transaction_for_script_pubkey(
+    offer_params: &PartyParams,
+    accept_params: &PartyParams,
+    fee_rate_per_vb: u64,
+    fund_lock_time: u32,
+    fund_output_serial_id: u64,
+    extra_fee: Amount,
+    cet_base_weight: usize,
+    contract_flags: u8,
+    fund_script_pubkey: ScriptBuf,
+) -> Result<Transaction, Error> {
     let total_collateral = checked_add!(offer_params.collateral, accept_params.collateral)?;
 
-    let (offer_change_output, offer_fund_fee, offer_cet_fee) =
-        offer_params.get_change_output_and_fees(total_collateral, fee_rate_per_vb, extra_fee)?;
-    let (accept_change_output, accept_fund_fee, accept_cet_fee) =
-        accept_params.get_change_output_and_fees(total_collateral, fee_rate_per_vb, extra_fee)?;
+    let (offer_change_output, offer_fund_fee, offer_cet_fee) = offer_params
+        .get_change_output_and_fees_with_cet_weight(
+            total_collateral,
+            fee_rate_per_vb,
+            extra_fee,
+            cet_base_weight,
+            contract_flags,
+        )?;
+    let (accept_change_output, accept_fund_fee, accept_cet_fee) = accept_params
+        .get_change_output_and_fees_with_cet_weight(
+            total_collateral,
+            fee_rate_per_vb,
+            extra_fee,
+            cet_base_weight,
+            contract_flags,
+        )?;
 
     let fund_output_value = checked_add!(offer_params.input_amount, accept_params.input_amount)?
         - offer_change_output.value
@@ -587,12 +741,12 @@ pub fn create_fund_transaction_with_fees(
     let (accept_tx_ins, accept_inputs_serial_ids) =
         accept_params.get_unsigned_tx_inputs_and_serial_ids(fund_sequence);
 
-    let funding_witness_script =
-        make_funding_redeemscript(&offer_params.fund_pubkey, &accept_params.fund_pubkey);
-
-    let fund_tx = create_funding_transaction(
-        &funding_witness_script,
-        fund_output_value,
+    let fund_tx = build_funding_transaction(
+        vec![TxOut {
+            value: fund_output_value,
+            script_pubkey: fund_script_pubkey,
+        }],
+        &[fund_output_serial_id],
         &offer_tx_ins,
         &offer_inputs_serial_ids,
         &accept_tx_ins,
@@ -601,11 +755,10 @@ pub fn create_fund_transaction_with_fees(
         offer_params.change_serial_id,
         accept_change_output,
         accept_params.change_serial_id,
-        fund_output_serial_id,
         fund_lock_time,
     );
 
-    Ok((fund_tx, funding_witness_script))
+    Ok(fund_tx)
 }
 
 /// Create the contract execution transactions and refund transaction.
@@ -652,6 +805,7 @@ pub fn create_cets_and_refund_tx(
         accept_params.payout_serial_id,
         payouts,
         cet_lock_time,
+        contract_flags,
     );
 
     let (offer_refund_value, accept_refund_value) = if contract_flags & REFUND_TO_ACCEPTER_FLAG != 0
@@ -678,13 +832,17 @@ pub fn create_cets_and_refund_tx(
         sequence: util::ENABLE_LOCKTIME,
     };
 
-    let refund_tx = create_refund_transaction(
+    let mut refund_tx = create_refund_transaction(
         offer_refund_output,
         accept_refund_ouput,
         refund_input,
         refund_lock_time,
     );
 
+    if contract_flags & ANCHOR_OUTPUTS_FLAG != 0 {
+        refund_tx.output.push(anchor::anchor_output());
+    }
+
     Ok((cets, refund_tx))
 }
 
@@ -714,6 +872,7 @@ pub fn create_cet(
 }
 
 /// Create a set of contract execution transaction for each provided outcome
+#[allow(clippy::too_many_arguments)]
 pub fn create_cets(
     fund_tx_input: &TxIn,
     offer_payout_script_pubkey: &Script,
@@ -722,6 +881,7 @@ pub fn create_cets(
     accept_payout_serial_id: u64,
     payouts: &[Payout],
     lock_time: u32,
+    contract_flags: u8,
 ) -> Vec<Transaction> {
     let mut txs: Vec<Transaction> = Vec::with_capacity(payouts.len());
     for payout in payouts {
@@ -733,7 +893,7 @@ pub fn create_cets(
             value: payout.accept,
             script_pubkey: accept_payout_script_pubkey.to_owned(),
         };
-        let tx = create_cet(
+        let mut tx = create_cet(
             offer_output,
             offer_payout_serial_id,
             accept_output,
@@ -741,6 +901,9 @@ pub fn create_cets(
             fund_tx_input,
             lock_time,
         );
+        if contract_flags & ANCHOR_OUTPUTS_FLAG != 0 {
+            tx.output.push(anchor::anchor_output());
+        }
 
         txs.push(tx);
     }
@@ -764,24 +927,46 @@ pub fn create_funding_transaction(
     fund_output_serial_id: u64,
     lock_time: u32,
 ) -> Transaction {
-    let fund_tx_out = TxOut {
-        value: output_amount,
-        script_pubkey: funding_witness_script.to_p2wsh(),
-    };
+    build_funding_transaction(
+        vec![TxOut {
+            value: output_amount,
+            script_pubkey: funding_witness_script.to_p2wsh(),
+        }],
+        &[fund_output_serial_id],
+        offer_inputs,
+        offer_inputs_serial_ids,
+        accept_inputs,
+        accept_inputs_serial_ids,
+        offer_change_output,
+        offer_change_serial_id,
+        accept_change_output,
+        accept_change_serial_id,
+        lock_time,
+    )
+}
 
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build_funding_transaction(
+    fund_outputs: Vec<TxOut>,
+    fund_output_serial_ids: &[u64],
+    offer_inputs: &[TxIn],
+    offer_inputs_serial_ids: &[u64],
+    accept_inputs: &[TxIn],
+    accept_inputs_serial_ids: &[u64],
+    offer_change_output: TxOut,
+    offer_change_serial_id: u64,
+    accept_change_output: TxOut,
+    accept_change_serial_id: u64,
+    lock_time: u32,
+) -> Transaction {
     let output: Vec<TxOut> = {
-        let serial_ids = vec![
-            fund_output_serial_id,
-            offer_change_serial_id,
-            accept_change_serial_id,
-        ];
-        util::discard_dust(
-            util::order_by_serial_ids(
-                vec![fund_tx_out, offer_change_output, accept_change_output],
-                &serial_ids,
-            ),
-            DUST_LIMIT,
-        )
+        let mut serial_ids = fund_output_serial_ids.to_vec();
+        serial_ids.push(offer_change_serial_id);
+        serial_ids.push(accept_change_serial_id);
+        let mut outputs = fund_outputs;
+        outputs.push(offer_change_output);
+        outputs.push(accept_change_output);
+        util::discard_dust(util::order_by_serial_ids(outputs, &serial_ids), DUST_LIMIT)
     };
 
     let input = util::order_by_serial_ids(
@@ -974,7 +1159,9 @@ pub fn create_cet_adaptor_sigs_from_oracle_info(
         .collect()
 }
 
-fn signatures_to_secret(signatures: &[Vec<SchnorrSignature>]) -> Result<SecretKey, Error> {
+pub(crate) fn signatures_to_secret(
+    signatures: &[Vec<SchnorrSignature>],
+) -> Result<SecretKey, Error> {
     let s_values = signatures
         .iter()
         .flatten()
@@ -1532,6 +1719,64 @@ mod tests {
             .all(|x| x.lock_time.to_consensus_u32() == 10));
     }
 
+    #[test]
+    fn create_dlc_transactions_with_anchor_outputs() {
+        // Arrange
+        let (offer_party_params, _) = get_party_params(
+            Amount::from_sat(1000000000),
+            Amount::from_sat(100000000),
+            None,
+        );
+        let (accept_party_params, _) = get_party_params(
+            Amount::from_sat(1000000000),
+            Amount::from_sat(100000000),
+            None,
+        );
+        let create = |contract_flags| {
+            create_dlc_transactions(
+                &offer_party_params,
+                &accept_party_params,
+                &payouts(),
+                100,
+                4,
+                10,
+                10,
+                0,
+                contract_flags,
+            )
+            .unwrap()
+        };
+
+        // Act
+        let plain = create(0);
+        let anchored = create(ANCHOR_OUTPUTS_FLAG);
+
+        // Assert
+        let anchor_cost = anchor::get_anchor_cost(
+            ANCHOR_OUTPUTS_FLAG,
+            offer_party_params.collateral,
+            offer_party_params.collateral + accept_party_params.collateral,
+            4,
+        )
+        .unwrap();
+        assert_eq!(
+            anchored.get_fund_output().value,
+            plain.get_fund_output().value + anchor_cost * 2
+        );
+        for (plain_tx, anchored_tx) in plain.cets.iter().chain(std::iter::once(&plain.refund)).zip(
+            anchored
+                .cets
+                .iter()
+                .chain(std::iter::once(&anchored.refund)),
+        ) {
+            assert!(anchor::get_anchor_output(plain_tx).is_none());
+            let (vout, anchor_output) = anchor::get_anchor_output(anchored_tx).unwrap();
+            assert_eq!(vout, anchored_tx.output.len() - 1);
+            assert_eq!(anchor_output.value, anchor::ANCHOR_OUTPUT_VALUE);
+            assert_eq!(&anchored_tx.output[..vout], &plain_tx.output[..]);
+        }
+    }
+
     #[test]
     fn create_cet_adaptor_sig_is_valid() {
         // Arrange
@@ -10,7 +10,7 @@ use secp256k1_sys::{
 };
 use secp256k1_zkp::{
     schnorr::Signature as SchnorrSignature, Keypair, Message, PublicKey, Scalar, Secp256k1,
-    Signing, Verification, XOnlyPublicKey,
+    SecretKey, Signing, Verification, XOnlyPublicKey,
 };
 
 const BIP340_MIDSTATE: [u8; 32] = [
@@ -77,6 +77,66 @@ pub fn schnorrsig_decompose(
     Ok((XOnlyPublicKey::from_slice(&bytes[0..32])?, &bytes[32..64]))
 }
 
+/// Extract the secret key of `pubkey` from two signatures over different
+/// messages that use the same nonce.
+///
+/// With `s = k + e * x`, two signatures sharing `k` give
+/// `x = (s1 - s2) / (e1 - e2)`.
+pub fn schnorrsig_extract_secret_key<C: Signing>(
+    secp: &Secp256k1<C>,
+    pubkey: &XOnlyPublicKey,
+    first: (&SchnorrSignature, &Message),
+    second: (&SchnorrSignature, &Message),
+) -> Result<SecretKey, Error> {
+    let (nonce, first_s) = schnorrsig_decompose(first.0)?;
+    let (second_nonce, second_s) = schnorrsig_decompose(second.0)?;
+    if nonce != second_nonce {
+        return Err(Error::InvalidArgument(
+            "Signatures do not use the same nonce".to_string(),
+        ));
+    }
+    if first.1 == second.1 {
+        return Err(Error::InvalidArgument(
+            "Signatures are over the same message".to_string(),
+        ));
+    }
+
+    let first_e = SecretKey::from_slice(&create_schnorr_hash(first.1, &nonce, pubkey))?;
+    let second_e = SecretKey::from_slice(&create_schnorr_hash(second.1, &nonce, pubkey))?;
+    let s = SecretKey::from_slice(first_s)?
+        .add_tweak(&Scalar::from(SecretKey::from_slice(second_s)?.negate()))?;
+    let e = first_e.add_tweak(&Scalar::from(second_e.negate()))?;
+    let secret_key = s.mul_tweak(&Scalar::from(scalar_inverse(&e)?))?;
+
+    if secret_key.x_only_public_key(secp).0 != *pubkey {
+        return Err(Error::InvalidArgument(
+            "Signatures are not from the given public key".to_string(),
+        ));
+    }
+    Ok(secret_key)
+}
+
+/// Compute `a^-1` as `a^(n - 2)`, `n` being the order of the curve.
+fn scalar_inverse(a: &SecretKey) -> Result<SecretKey, Error> {
+    let mut exponent = secp256k1_zkp::constants::CURVE_ORDER;
+    exponent[31] -= 2;
+    let mut result: Option<SecretKey> = None;
+    for byte in exponent {
+        for bit in (0..8).rev() {
+            if let Some(r) = result {
+                result = Some(r.mul_tweak(&Scalar::from(r))?);
+            }
+            if (byte >> bit) & 1 == 1 {
+                result = Some(match result {
+                    Some(r) => r.mul_tweak(&Scalar::from(*a))?,
+                    None => *a,
+                });
+            }
+        }
+    }
+    result.ok_or_else(|| Error::InvalidArgument("Cannot invert zero".to_string()))
+}
+
 extern "C" fn constant_nonce_fn(
     nonce32: *mut c_uchar,
     _msg32: *const c_uchar,
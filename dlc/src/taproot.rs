@@ -0,0 +1,984 @@
+//! # Taproot funding
+//! Creation, signing and verification of DLC transactions whose fund output
+//! is a Taproot output instead of a P2WSH 2-of-2 multisig.
+//!
+//! The internal key of the fund output is the MuSig2 aggregate of both
+//! parties' funding public keys, and the output commits to a single refund
+//! leaf: `<refund_locktime> OP_CHECKLOCKTIMEVERIFY OP_DROP <key_a>
+//! OP_CHECKSIGVERIFY <key_b> OP_CHECKSIG`. CETs and the refund transaction are
+//! spent through the key path with a MuSig2 signature, so on chain a DLC
+//! looks like any other single-key Taproot spend. Each party's CET partial
+//! signature is an adaptor signature encrypted under the outcome's oracle
+//! adaptor point, and the aggregate signature can only be completed with the
+//! oracle attestation. The refund leaf lets the parties spend the fund output
+//! after the refund locktime with plain Schnorr signatures, without a MuSig2
+//! session. Both parties sign the leaf spend of the refund transaction when
+//! they sign the contract: see [`create_refund_leaf_signature`] and
+//! [`sign_refund_leaf`].
+//!
+//! MuSig2 requires both parties to commit to their public nonces before any
+//! partial signature is produced. Signing twice with the same secret nonce
+//! and different messages reveals the funding secret key, so every
+//! [`SecNonce`] must be used for a single signing session: see
+//! [`derive_secret_nonces`].
+
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
+use bitcoin::taproot::{ControlBlock, LeafVersion, TapLeafHash, TaprootBuilder, TaprootSpendInfo};
+use bitcoin::{Amount, OutPoint, Script, ScriptBuf, Transaction, TxOut, Witness};
+use musig2::secp::{MaybePoint, Point, Scalar};
+use musig2::{AggNonce, KeyAggContext, PartialSignature, PubNonce, SecNonce};
+use secp256k1_zkp::schnorr::Signature as SchnorrSignature;
+use secp256k1_zkp::{
+    Keypair, Message, PublicKey, Secp256k1, SecretKey, Signing, Verification, XOnlyPublicKey,
+};
+use {
+    create_cets_and_refund_tx, create_fund_transaction_for_script_pubkey, signatures_to_secret,
+    util, DlcTransactions, Error, PartyParams, Payout,
+};
+
+/// Weight of a CET spending a Taproot fund output through the key path,
+/// without the payout outputs. A key path witness is a single 64 byte
+/// signature, against ~220 weight units for the P2WSH 2-of-2 witness.
+const TAPROOT_CET_BASE_WEIGHT: usize = 346;
+
+/// Tag mixed into the seed of the MuSig2 nonces derived from a funding key.
+const NONCE_SEED_TAG: &[u8] = b"DDK/taproot-funding/nonce";
+
+/// The Taproot fund output of a DLC: the MuSig2 key aggregation context of
+/// the two funding keys, tweaked with the refund leaf, and the spend
+/// information needed to use the refund leaf.
+#[derive(Clone, Debug)]
+pub struct TaprootFunding {
+    key_agg_ctx: KeyAggContext,
+    spend_info: TaprootSpendInfo,
+    refund_leaf: ScriptBuf,
+    leaf_keys: [XOnlyPublicKey; 2],
+}
+
+impl TaprootFunding {
+    /// Creates the fund output information for the given funding public keys
+    /// and refund locktime. The order of the keys does not matter.
+    pub fn new<C: Verification>(
+        secp: &Secp256k1<C>,
+        offer_fund_pubkey: &PublicKey,
+        accept_fund_pubkey: &PublicKey,
+        refund_lock_time: u32,
+    ) -> Result<TaprootFunding, Error> {
+        let mut keys = [*offer_fund_pubkey, *accept_fund_pubkey];
+        keys.sort_by_key(|key| key.serialize());
+
+        let leaf_keys = [keys[0].x_only_public_key().0, keys[1].x_only_public_key().0];
+        let refund_leaf = make_refund_leaf_script(&leaf_keys, refund_lock_time);
+
+        let untweaked = KeyAggContext::new(keys.iter().map(|key| Point::from(*key)))
+            .map_err(|e| Error::InvalidArgument(format!("Invalid funding keys: {e}")))?;
+        let internal_key: XOnlyPublicKey = untweaked.aggregated_pubkey_untweaked();
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, refund_leaf.clone())
+            .map_err(|e| Error::InvalidArgument(format!("Invalid refund leaf: {e}")))?
+            .finalize(secp, internal_key)
+            .map_err(|_| Error::InvalidArgument("Incomplete taproot tree".to_string()))?;
+        let merkle_root = spend_info
+            .merkle_root()
+            .expect("a tree with a leaf to have a merkle root");
+        let key_agg_ctx = untweaked
+            .with_taproot_tweak(&merkle_root.to_byte_array())
+            .map_err(|e| Error::InvalidArgument(format!("Invalid taproot tweak: {e}")))?;
+
+        Ok(TaprootFunding {
+            key_agg_ctx,
+            spend_info,
+            refund_leaf,
+            leaf_keys,
+        })
+    }
+
+    /// The script pubkey of the fund output.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        ScriptBuf::new_p2tr_tweaked(self.spend_info.output_key())
+    }
+
+    /// The tweaked output key of the fund output, which key path signatures
+    /// are verified against.
+    pub fn output_key(&self) -> XOnlyPublicKey {
+        self.spend_info.output_key().to_x_only_public_key()
+    }
+
+    /// The MuSig2 key aggregation context, tweaked for the fund output.
+    pub fn key_agg_ctx(&self) -> &KeyAggContext {
+        &self.key_agg_ctx
+    }
+
+    /// The refund leaf script.
+    pub fn refund_leaf(&self) -> &Script {
+        &self.refund_leaf
+    }
+
+    /// The control block proving the refund leaf is committed to by the fund
+    /// output.
+    pub fn refund_control_block(&self) -> ControlBlock {
+        self.spend_info
+            .control_block(&(self.refund_leaf.clone(), LeafVersion::TapScript))
+            .expect("the refund leaf to be part of the tree")
+    }
+}
+
+fn make_refund_leaf_script(keys: &[XOnlyPublicKey; 2], refund_lock_time: u32) -> ScriptBuf {
+    bitcoin::blockdata::script::Builder::new()
+        .push_lock_time(bitcoin::absolute::LockTime::from_consensus(
+            refund_lock_time,
+        ))
+        .push_opcode(bitcoin::opcodes::all::OP_CLTV)
+        .push_opcode(bitcoin::opcodes::all::OP_DROP)
+        .push_x_only_key(&keys[0])
+        .push_opcode(bitcoin::opcodes::all::OP_CHECKSIGVERIFY)
+        .push_x_only_key(&keys[1])
+        .push_opcode(bitcoin::opcodes::all::OP_CHECKSIG)
+        .into_script()
+}
+
+/// Create the transactions for a Taproot funded DLC contract. Takes the same
+/// parameters as [`crate::create_dlc_transactions`]. The returned
+/// [`DlcTransactions::funding_witness_script`] holds the P2TR script pubkey
+/// of the fund output.
+#[allow(clippy::too_many_arguments)]
+pub fn create_taproot_dlc_transactions(
+    offer_params: &PartyParams,
+    accept_params: &PartyParams,
+    payouts: &[Payout],
+    refund_lock_time: u32,
+    fee_rate_per_vb: u64,
+    fund_lock_time: u32,
+    cet_lock_time: u32,
+    fund_output_serial_id: u64,
+    contract_flags: u8,
+) -> Result<DlcTransactions, Error> {
+    if !offer_params.dlc_inputs.is_empty() || !accept_params.dlc_inputs.is_empty() {
+        return Err(Error::InvalidArgument(
+            "DLC inputs are not supported with Taproot funding".to_string(),
+        ));
+    }
+    let funding = TaprootFunding::new(
+        &Secp256k1::verification_only(),
+        &offer_params.fund_pubkey,
+        &accept_params.fund_pubkey,
+        refund_lock_time,
+    )?;
+    let fund_script_pubkey = funding.script_pubkey();
+    let fund_tx = create_fund_transaction_for_script_pubkey(
+        offer_params,
+        accept_params,
+        fee_rate_per_vb,
+        fund_lock_time,
+        fund_output_serial_id,
+        Amount::ZERO,
+        TAPROOT_CET_BASE_WEIGHT,
+        contract_flags,
+        fund_script_pubkey.clone(),
+    )?;
+    let fund_outpoint = OutPoint {
+        txid: fund_tx.compute_txid(),
+        vout: util::get_output_for_script_pubkey(&fund_tx, &fund_script_pubkey)
+            .expect("to find the funding script pubkey")
+            .0 as u32,
+    };
+    let (cets, refund_tx) = create_cets_and_refund_tx(
+        offer_params,
+        accept_params,
+        fund_outpoint,
+        payouts,
+        refund_lock_time,
+        cet_lock_time,
+        None,
+        contract_flags,
+    )?;
+
+    Ok(DlcTransactions {
+        fund: fund_tx,
+        cets,
+        refund: refund_tx,
+        funding_witness_script: fund_script_pubkey,
+        pending_close_txs: vec![],
+    })
+}
+
+/// Derives `count` MuSig2 secret nonces from a funding secret key and a
+/// session identifier.
+///
+/// The session identifier must be drawn at random for each signing session,
+/// and the nonces stored until they sign, then discarded: they must never be
+/// derived again for a second set of transactions, as signing twice with the
+/// same nonces reveals the funding secret key.
+pub fn derive_secret_nonces(
+    fund_secret_key: &SecretKey,
+    session_id: &[u8],
+    count: usize,
+) -> Vec<SecNonce> {
+    let mut engine = sha256::Hash::engine();
+    engine.input(NONCE_SEED_TAG);
+    engine.input(&fund_secret_key.secret_bytes());
+    let seed = sha256::Hash::from_engine(engine).to_byte_array();
+
+    (0..count as u64)
+        .map(|index| {
+            let index = index.to_be_bytes();
+            SecNonce::build_with_seckey(seed, *fund_secret_key)
+                .with_extra_input(&session_id)
+                .with_extra_input(&index)
+                .build()
+        })
+        .collect()
+}
+
+/// Computes the key path signature hash of a transaction spending the fund
+/// output as its only input.
+pub fn get_key_spend_sighash(tx: &Transaction, fund_output: &TxOut) -> Result<Message, Error> {
+    let sighash = SighashCache::new(tx)
+        .taproot_key_spend_signature_hash(
+            0,
+            &Prevouts::All(&[fund_output]),
+            TapSighashType::Default,
+        )
+        .map_err(|e| Error::InvalidArgument(format!("Could not compute sighash: {e}")))?;
+    Ok(Message::from_digest(sighash.to_byte_array()))
+}
+
+fn get_refund_leaf_sighash(
+    funding: &TaprootFunding,
+    refund: &Transaction,
+    fund_output: &TxOut,
+) -> Result<Message, Error> {
+    let sighash = SighashCache::new(refund)
+        .taproot_script_spend_signature_hash(
+            0,
+            &Prevouts::All(&[fund_output]),
+            TapLeafHash::from_script(&funding.refund_leaf, LeafVersion::TapScript),
+            TapSighashType::Default,
+        )
+        .map_err(|e| Error::InvalidArgument(format!("Could not compute sighash: {e}")))?;
+    Ok(Message::from_digest(sighash.to_byte_array()))
+}
+
+fn aggregate_nonce(nonces: &[PubNonce]) -> AggNonce {
+    AggNonce::sum(nonces)
+}
+
+fn check_lengths(adaptor_points: usize, items: usize, name: &str) -> Result<(), Error> {
+    if adaptor_points != items {
+        return Err(Error::InvalidArgument(format!(
+            "Expected {adaptor_points} {name}, got {items}"
+        )));
+    }
+    Ok(())
+}
+
+fn bad_signature() -> Error {
+    Error::from(secp256k1_zkp::UpstreamError::IncorrectSignature)
+}
+
+/// Creates this party's MuSig2 partial adaptor signatures for the CETs.
+///
+/// `adaptor_points` holds the CET index and the oracle adaptor point of each
+/// signature, and `secret_nonces` and `counterparty_nonces` one nonce per
+/// adaptor point, in the same order.
+#[allow(clippy::too_many_arguments)]
+pub fn create_cet_partial_adaptor_signatures(
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    cets: &[Transaction],
+    adaptor_points: &[(usize, PublicKey)],
+    fund_secret_key: &SecretKey,
+    secret_nonces: Vec<SecNonce>,
+    counterparty_nonces: &[PubNonce],
+) -> Result<Vec<PartialSignature>, Error> {
+    check_lengths(adaptor_points.len(), secret_nonces.len(), "secret nonces")?;
+    check_lengths(adaptor_points.len(), counterparty_nonces.len(), "nonces")?;
+
+    adaptor_points
+        .iter()
+        .zip(secret_nonces)
+        .zip(counterparty_nonces)
+        .map(
+            |(((cet_index, adaptor_point), secret_nonce), counterparty_nonce)| {
+                let cet = cets.get(*cet_index).ok_or_else(|| {
+                    Error::InvalidArgument(format!("No CET at index {cet_index}"))
+                })?;
+                let sighash = get_key_spend_sighash(cet, fund_output)?;
+                let nonce =
+                    aggregate_nonce(&[secret_nonce.public_nonce(), counterparty_nonce.clone()]);
+                musig2::adaptor::sign_partial(
+                    &funding.key_agg_ctx,
+                    *fund_secret_key,
+                    secret_nonce,
+                    &nonce,
+                    *adaptor_point,
+                    sighash.as_ref(),
+                )
+                .map_err(|e| Error::InvalidArgument(format!("Could not sign CET: {e}")))
+            },
+        )
+        .collect()
+}
+
+/// Verifies a party's MuSig2 partial adaptor signatures for the CETs.
+///
+/// `signer_nonces` are the public nonces of the party that produced
+/// `partial_signatures`, `counterparty_nonces` those of the other party.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_cet_partial_adaptor_signatures(
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    cets: &[Transaction],
+    adaptor_points: &[(usize, PublicKey)],
+    signer_pubkey: &PublicKey,
+    signer_nonces: &[PubNonce],
+    counterparty_nonces: &[PubNonce],
+    partial_signatures: &[PartialSignature],
+) -> Result<(), Error> {
+    check_lengths(adaptor_points.len(), signer_nonces.len(), "nonces")?;
+    check_lengths(adaptor_points.len(), counterparty_nonces.len(), "nonces")?;
+    check_lengths(
+        adaptor_points.len(),
+        partial_signatures.len(),
+        "partial signatures",
+    )?;
+
+    for (i, (cet_index, adaptor_point)) in adaptor_points.iter().enumerate() {
+        let cet = cets
+            .get(*cet_index)
+            .ok_or_else(|| Error::InvalidArgument(format!("No CET at index {cet_index}")))?;
+        let sighash = get_key_spend_sighash(cet, fund_output)?;
+        let nonce = aggregate_nonce(&[signer_nonces[i].clone(), counterparty_nonces[i].clone()]);
+        musig2::adaptor::verify_partial(
+            &funding.key_agg_ctx,
+            partial_signatures[i],
+            &nonce,
+            *adaptor_point,
+            *signer_pubkey,
+            &signer_nonces[i],
+            sighash.as_ref(),
+        )
+        .map_err(|_| bad_signature())?;
+    }
+
+    Ok(())
+}
+
+/// Aggregates both parties' partial adaptor signatures for a CET, adapts the
+/// result with the oracle signatures and places the final Schnorr signature
+/// on the key path witness of the CET.
+///
+/// `nonces` and `partial_signatures` hold each party's public nonce and
+/// partial signature for the adaptor point of the attested outcome.
+pub fn sign_cet(
+    cet: &mut Transaction,
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    oracle_signatures: &[Vec<SchnorrSignature>],
+    nonces: &[PubNonce; 2],
+    partial_signatures: &[PartialSignature; 2],
+) -> Result<(), Error> {
+    let adaptor_secret = signatures_to_secret(oracle_signatures)?;
+    let adaptor_point = Scalar::from(adaptor_secret).base_point_mul();
+    let sighash = get_key_spend_sighash(cet, fund_output)?;
+    let adaptor_signature = musig2::adaptor::aggregate_partial_signatures(
+        &funding.key_agg_ctx,
+        &aggregate_nonce(nonces),
+        MaybePoint::Valid(adaptor_point),
+        partial_signatures.iter().copied(),
+        sighash.as_ref(),
+    )
+    .map_err(|_| bad_signature())?;
+    let signature: SchnorrSignature = adaptor_signature
+        .adapt(adaptor_secret)
+        .ok_or_else(bad_signature)?;
+    Secp256k1::verification_only()
+        .verify_schnorr(&signature, &sighash, &funding.output_key())
+        .map_err(|_| bad_signature())?;
+
+    cet.input[0].witness = key_spend_witness(signature);
+    Ok(())
+}
+
+/// Creates this party's MuSig2 partial signature for the key path spend of
+/// the refund transaction.
+pub fn create_refund_partial_signature(
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    refund: &Transaction,
+    fund_secret_key: &SecretKey,
+    secret_nonce: SecNonce,
+    counterparty_nonce: &PubNonce,
+) -> Result<PartialSignature, Error> {
+    let sighash = get_key_spend_sighash(refund, fund_output)?;
+    let nonce = aggregate_nonce(&[secret_nonce.public_nonce(), counterparty_nonce.clone()]);
+    musig2::sign_partial(
+        &funding.key_agg_ctx,
+        *fund_secret_key,
+        secret_nonce,
+        &nonce,
+        sighash.as_ref(),
+    )
+    .map_err(|e| Error::InvalidArgument(format!("Could not sign refund: {e}")))
+}
+
+/// Verifies a party's MuSig2 partial signature for the key path spend of the
+/// refund transaction.
+pub fn verify_refund_partial_signature(
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    refund: &Transaction,
+    signer_pubkey: &PublicKey,
+    signer_nonce: &PubNonce,
+    counterparty_nonce: &PubNonce,
+    partial_signature: PartialSignature,
+) -> Result<(), Error> {
+    let sighash = get_key_spend_sighash(refund, fund_output)?;
+    let nonce = aggregate_nonce(&[signer_nonce.clone(), counterparty_nonce.clone()]);
+    musig2::verify_partial(
+        &funding.key_agg_ctx,
+        partial_signature,
+        &nonce,
+        *signer_pubkey,
+        signer_nonce,
+        sighash.as_ref(),
+    )
+    .map_err(|_| bad_signature())
+}
+
+/// Aggregates both parties' partial signatures for the refund transaction and
+/// places the final Schnorr signature on its key path witness.
+pub fn sign_refund(
+    refund: &mut Transaction,
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    nonces: &[PubNonce; 2],
+    partial_signatures: &[PartialSignature; 2],
+) -> Result<(), Error> {
+    let sighash = get_key_spend_sighash(refund, fund_output)?;
+    let signature: SchnorrSignature = musig2::aggregate_partial_signatures(
+        &funding.key_agg_ctx,
+        &aggregate_nonce(nonces),
+        partial_signatures.iter().copied(),
+        sighash.as_ref(),
+    )
+    .map_err(|_| bad_signature())?;
+
+    refund.input[0].witness = key_spend_witness(signature);
+    Ok(())
+}
+
+fn key_spend_witness(signature: SchnorrSignature) -> Witness {
+    Witness::p2tr_key_spend(&bitcoin::taproot::Signature {
+        signature,
+        sighash_type: TapSighashType::Default,
+    })
+}
+
+/// Creates this party's Schnorr signature for spending the fund output with
+/// the refund transaction through the refund leaf.
+pub fn create_refund_leaf_signature<C: Signing>(
+    secp: &Secp256k1<C>,
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    refund: &Transaction,
+    fund_secret_key: &SecretKey,
+) -> Result<SchnorrSignature, Error> {
+    let sighash = get_refund_leaf_sighash(funding, refund, fund_output)?;
+    let keypair = Keypair::from_secret_key(secp, fund_secret_key);
+    Ok(secp.sign_schnorr_no_aux_rand(&sighash, &keypair))
+}
+
+/// Verifies a party's Schnorr signature for the refund leaf spend.
+pub fn verify_refund_leaf_signature<C: Verification>(
+    secp: &Secp256k1<C>,
+    funding: &TaprootFunding,
+    fund_output: &TxOut,
+    refund: &Transaction,
+    signer_pubkey: &PublicKey,
+    signature: &SchnorrSignature,
+) -> Result<(), Error> {
+    let sighash = get_refund_leaf_sighash(funding, refund, fund_output)?;
+    secp.verify_schnorr(signature, &sighash, &signer_pubkey.x_only_public_key().0)
+        .map_err(|_| bad_signature())
+}
+
+/// Places both parties' refund leaf signatures, the refund leaf and its
+/// control block on the witness of the refund transaction.
+pub fn sign_refund_leaf(
+    refund: &mut Transaction,
+    funding: &TaprootFunding,
+    signatures: &[(PublicKey, SchnorrSignature); 2],
+) -> Result<(), Error> {
+    let signature_for = |key: &XOnlyPublicKey| {
+        signatures
+            .iter()
+            .find(|(pubkey, _)| pubkey.x_only_public_key().0 == *key)
+            .map(|(_, signature)| *signature)
+            .ok_or_else(|| Error::InvalidArgument("Missing refund leaf signature".to_string()))
+    };
+    // The first key of the leaf is checked first, so its signature must be
+    // on top of the stack.
+    let first = signature_for(&funding.leaf_keys[0])?;
+    let second = signature_for(&funding.leaf_keys[1])?;
+
+    let mut witness = Witness::new();
+    witness.push(second.as_ref());
+    witness.push(first.as_ref());
+    witness.push(funding.refund_leaf.as_bytes());
+    witness.push(funding.refund_control_block().serialize());
+    refund.input[0].witness = witness;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{Sequence, TxIn};
+    use secp256k1_zkp::rand::{thread_rng, RngCore};
+    use secp256k1_zkp::All;
+    use {secp_utils, OracleInfo, TxInputInfo};
+
+    fn p2wpkh_script_pubkey(secp: &Secp256k1<All>) -> ScriptBuf {
+        let sk = bitcoin::PrivateKey {
+            inner: SecretKey::new(&mut thread_rng()),
+            network: bitcoin::Network::Testnet.into(),
+            compressed: true,
+        };
+        let pk = bitcoin::CompressedPublicKey::from_private_key(secp, &sk).unwrap();
+        bitcoin::Address::p2wpkh(&pk, bitcoin::Network::Testnet).script_pubkey()
+    }
+
+    fn party_params(secp: &Secp256k1<All>, fund_sk: &SecretKey, serial_id: u64) -> PartyParams {
+        PartyParams {
+            fund_pubkey: PublicKey::from_secret_key(secp, fund_sk),
+            change_script_pubkey: p2wpkh_script_pubkey(secp),
+            change_serial_id: serial_id,
+            payout_script_pubkey: p2wpkh_script_pubkey(secp),
+            payout_serial_id: serial_id + 1,
+            inputs: vec![TxInputInfo {
+                outpoint: OutPoint::null(),
+                max_witness_len: 108,
+                redeem_script: ScriptBuf::new(),
+                serial_id: serial_id + 2,
+            }],
+            dlc_inputs: vec![],
+            input_amount: Amount::from_sat(300_000_000),
+            collateral: Amount::from_sat(100_000_000),
+        }
+    }
+
+    fn payouts() -> Vec<Payout> {
+        vec![
+            Payout {
+                offer: Amount::from_sat(200_000_000),
+                accept: Amount::ZERO,
+            },
+            Payout {
+                offer: Amount::ZERO,
+                accept: Amount::from_sat(200_000_000),
+            },
+        ]
+    }
+
+    struct Setup {
+        secp: Secp256k1<All>,
+        offer_sk: SecretKey,
+        accept_sk: SecretKey,
+        funding: TaprootFunding,
+        txs: DlcTransactions,
+    }
+
+    fn setup() -> Setup {
+        let secp = Secp256k1::new();
+        let offer_sk = SecretKey::new(&mut thread_rng());
+        let accept_sk = SecretKey::new(&mut thread_rng());
+        let offer_params = party_params(&secp, &offer_sk, 1);
+        let accept_params = party_params(&secp, &accept_sk, 10);
+        let txs = create_taproot_dlc_transactions(
+            &offer_params,
+            &accept_params,
+            &payouts(),
+            1_000,
+            4,
+            10,
+            10,
+            0,
+            crate::TAPROOT_FUNDING_FLAG,
+        )
+        .unwrap();
+        let funding = TaprootFunding::new(
+            &secp,
+            &offer_params.fund_pubkey,
+            &accept_params.fund_pubkey,
+            1_000,
+        )
+        .unwrap();
+        Setup {
+            secp,
+            offer_sk,
+            accept_sk,
+            funding,
+            txs,
+        }
+    }
+
+    #[test]
+    fn funding_output_is_taproot() {
+        let Setup { funding, txs, .. } = setup();
+
+        assert!(txs.is_taproot());
+        assert_eq!(txs.funding_script_pubkey(), funding.script_pubkey());
+        assert_eq!(txs.get_fund_output().script_pubkey, funding.script_pubkey());
+        assert_eq!(
+            funding.key_agg_ctx().aggregated_pubkey::<XOnlyPublicKey>(),
+            funding.output_key()
+        );
+        assert!(funding.refund_control_block().verify_taproot_commitment(
+            &Secp256k1::verification_only(),
+            funding.output_key(),
+            funding.refund_leaf(),
+        ));
+    }
+
+    #[test]
+    fn taproot_funding_reserves_less_fee_than_p2wsh() {
+        let secp = Secp256k1::new();
+        let offer_params = party_params(&secp, &SecretKey::new(&mut thread_rng()), 1);
+        let accept_params = party_params(&secp, &SecretKey::new(&mut thread_rng()), 10);
+        let taproot = create_taproot_dlc_transactions(
+            &offer_params,
+            &accept_params,
+            &payouts(),
+            1_000,
+            4,
+            10,
+            10,
+            0,
+            0,
+        )
+        .unwrap();
+        let p2wsh = crate::create_dlc_transactions(
+            &offer_params,
+            &accept_params,
+            &payouts(),
+            1_000,
+            4,
+            10,
+            10,
+            0,
+            0,
+        )
+        .unwrap();
+
+        assert!(taproot.get_fund_output().value < p2wsh.get_fund_output().value);
+    }
+
+    #[test]
+    fn same_funding_for_either_key_order() {
+        let secp = Secp256k1::new();
+        let a = PublicKey::from_secret_key(&secp, &SecretKey::new(&mut thread_rng()));
+        let b = PublicKey::from_secret_key(&secp, &SecretKey::new(&mut thread_rng()));
+
+        assert_eq!(
+            TaprootFunding::new(&secp, &a, &b, 100)
+                .unwrap()
+                .script_pubkey(),
+            TaprootFunding::new(&secp, &b, &a, 100)
+                .unwrap()
+                .script_pubkey()
+        );
+    }
+
+    #[test]
+    fn derived_nonces_are_deterministic_per_session() {
+        let sk = SecretKey::new(&mut thread_rng());
+        let first = derive_secret_nonces(&sk, b"session", 2);
+        let again = derive_secret_nonces(&sk, b"session", 2);
+        let other = derive_secret_nonces(&sk, b"other", 2);
+
+        assert_eq!(first[0].public_nonce(), again[0].public_nonce());
+        assert_ne!(first[0].public_nonce(), first[1].public_nonce());
+        assert_ne!(first[0].public_nonce(), other[0].public_nonce());
+    }
+
+    #[test]
+    fn cet_adaptor_signatures_complete_with_oracle_signature() {
+        let Setup {
+            secp,
+            offer_sk,
+            accept_sk,
+            funding,
+            txs,
+        } = setup();
+        let fund_output = txs.get_fund_output().clone();
+
+        let oracle_kp = Keypair::new(&secp, &mut thread_rng());
+        let mut nonce_sk = [0u8; 32];
+        thread_rng().fill_bytes(&mut nonce_sk);
+        let nonce_kp = Keypair::from_seckey_slice(&secp, &nonce_sk).unwrap();
+        let oracle_info = OracleInfo {
+            public_key: oracle_kp.x_only_public_key().0,
+            nonces: vec![nonce_kp.x_only_public_key().0],
+        };
+        let messages: Vec<Vec<Vec<Message>>> = ["1", "2"]
+            .iter()
+            .map(|outcome| {
+                vec![vec![Message::from_digest(
+                    sha256::Hash::hash(outcome.as_bytes()).to_byte_array(),
+                )]]
+            })
+            .collect();
+        let adaptor_points: Vec<(usize, PublicKey)> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, msgs)| {
+                (
+                    i,
+                    crate::get_adaptor_point_from_oracle_info(
+                        &secp,
+                        std::slice::from_ref(&oracle_info),
+                        msgs,
+                    )
+                    .unwrap(),
+                )
+            })
+            .collect();
+
+        let offer_nonces = derive_secret_nonces(&offer_sk, b"offer", adaptor_points.len());
+        let offer_pub_nonces: Vec<PubNonce> =
+            offer_nonces.iter().map(SecNonce::public_nonce).collect();
+        let accept_nonces = derive_secret_nonces(&accept_sk, b"accept", adaptor_points.len());
+        let accept_pub_nonces: Vec<PubNonce> =
+            accept_nonces.iter().map(SecNonce::public_nonce).collect();
+
+        let accept_partials = create_cet_partial_adaptor_signatures(
+            &funding,
+            &fund_output,
+            &txs.cets,
+            &adaptor_points,
+            &accept_sk,
+            accept_nonces,
+            &offer_pub_nonces,
+        )
+        .unwrap();
+        let offer_partials = create_cet_partial_adaptor_signatures(
+            &funding,
+            &fund_output,
+            &txs.cets,
+            &adaptor_points,
+            &offer_sk,
+            offer_nonces,
+            &accept_pub_nonces,
+        )
+        .unwrap();
+
+        verify_cet_partial_adaptor_signatures(
+            &funding,
+            &fund_output,
+            &txs.cets,
+            &adaptor_points,
+            &PublicKey::from_secret_key(&secp, &accept_sk),
+            &accept_pub_nonces,
+            &offer_pub_nonces,
+            &accept_partials,
+        )
+        .unwrap();
+        verify_cet_partial_adaptor_signatures(
+            &funding,
+            &fund_output,
+            &txs.cets,
+            &adaptor_points,
+            &PublicKey::from_secret_key(&secp, &offer_sk),
+            &offer_pub_nonces,
+            &accept_pub_nonces,
+            &offer_partials,
+        )
+        .unwrap();
+        // A partial signature attributed to the wrong party does not verify.
+        assert!(verify_cet_partial_adaptor_signatures(
+            &funding,
+            &fund_output,
+            &txs.cets,
+            &adaptor_points,
+            &PublicKey::from_secret_key(&secp, &offer_sk),
+            &offer_pub_nonces,
+            &accept_pub_nonces,
+            &accept_partials,
+        )
+        .is_err());
+
+        let oracle_signature = secp_utils::schnorrsig_sign_with_nonce(
+            &secp,
+            &messages[1][0][0],
+            &oracle_kp,
+            &nonce_sk,
+        );
+        let mut cet = txs.cets[1].clone();
+        sign_cet(
+            &mut cet,
+            &funding,
+            &fund_output,
+            &[vec![oracle_signature]],
+            &[offer_pub_nonces[1].clone(), accept_pub_nonces[1].clone()],
+            &[offer_partials[1], accept_partials[1]],
+        )
+        .unwrap();
+        assert_eq!(cet.input[0].witness.len(), 1);
+
+        // The attestation of one outcome cannot complete the CET of another.
+        let mut other_cet = txs.cets[0].clone();
+        assert!(sign_cet(
+            &mut other_cet,
+            &funding,
+            &fund_output,
+            &[vec![oracle_signature]],
+            &[offer_pub_nonces[0].clone(), accept_pub_nonces[0].clone()],
+            &[offer_partials[0], accept_partials[0]],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn refund_key_path_and_leaf_signatures() {
+        let Setup {
+            secp,
+            offer_sk,
+            accept_sk,
+            funding,
+            txs,
+        } = setup();
+        let fund_output = txs.get_fund_output().clone();
+        let offer_pk = PublicKey::from_secret_key(&secp, &offer_sk);
+        let accept_pk = PublicKey::from_secret_key(&secp, &accept_sk);
+
+        let offer_nonce = derive_secret_nonces(&offer_sk, b"refund", 1).remove(0);
+        let accept_nonce = derive_secret_nonces(&accept_sk, b"refund", 1).remove(0);
+        let nonces = [offer_nonce.public_nonce(), accept_nonce.public_nonce()];
+        let offer_partial = create_refund_partial_signature(
+            &funding,
+            &fund_output,
+            &txs.refund,
+            &offer_sk,
+            offer_nonce,
+            &nonces[1],
+        )
+        .unwrap();
+        let accept_partial = create_refund_partial_signature(
+            &funding,
+            &fund_output,
+            &txs.refund,
+            &accept_sk,
+            accept_nonce,
+            &nonces[0],
+        )
+        .unwrap();
+        verify_refund_partial_signature(
+            &funding,
+            &fund_output,
+            &txs.refund,
+            &offer_pk,
+            &nonces[0],
+            &nonces[1],
+            offer_partial,
+        )
+        .unwrap();
+
+        let mut refund = txs.refund.clone();
+        sign_refund(
+            &mut refund,
+            &funding,
+            &fund_output,
+            &nonces,
+            &[offer_partial, accept_partial],
+        )
+        .unwrap();
+        let signature =
+            SchnorrSignature::from_slice(refund.input[0].witness.nth(0).unwrap()).unwrap();
+        secp.verify_schnorr(
+            &signature,
+            &get_key_spend_sighash(&refund, &fund_output).unwrap(),
+            &funding.output_key(),
+        )
+        .unwrap();
+
+        let offer_leaf_sig =
+            create_refund_leaf_signature(&secp, &funding, &fund_output, &txs.refund, &offer_sk)
+                .unwrap();
+        let accept_leaf_sig =
+            create_refund_leaf_signature(&secp, &funding, &fund_output, &txs.refund, &accept_sk)
+                .unwrap();
+        verify_refund_leaf_signature(
+            &secp,
+            &funding,
+            &fund_output,
+            &txs.refund,
+            &accept_pk,
+            &accept_leaf_sig,
+        )
+        .unwrap();
+        assert!(verify_refund_leaf_signature(
+            &secp,
+            &funding,
+            &fund_output,
+            &txs.refund,
+            &offer_pk,
+            &accept_leaf_sig,
+        )
+        .is_err());
+
+        let mut leaf_refund = txs.refund.clone();
+        sign_refund_leaf(
+            &mut leaf_refund,
+            &funding,
+            &[(offer_pk, offer_leaf_sig), (accept_pk, accept_leaf_sig)],
+        )
+        .unwrap();
+        let witness = &leaf_refund.input[0].witness;
+        assert_eq!(witness.len(), 4);
+        assert_eq!(witness.nth(2).unwrap(), funding.refund_leaf().as_bytes());
+    }
+
+    #[test]
+    fn taproot_rejects_dlc_inputs() {
+        let secp = Secp256k1::new();
+        let mut offer_params = party_params(&secp, &SecretKey::new(&mut thread_rng()), 1);
+        let accept_params = party_params(&secp, &SecretKey::new(&mut thread_rng()), 10);
+        offer_params
+            .dlc_inputs
+            .push(crate::dlc_input::DlcInputInfo {
+                fund_tx: Transaction {
+                    version: bitcoin::transaction::Version::TWO,
+                    lock_time: bitcoin::absolute::LockTime::ZERO,
+                    input: vec![TxIn {
+                        previous_output: OutPoint::null(),
+                        script_sig: ScriptBuf::new(),
+                        sequence: Sequence::MAX,
+                        witness: Witness::new(),
+                    }],
+                    output: vec![],
+                },
+                fund_vout: 0,
+                local_fund_pubkey: offer_params.fund_pubkey,
+                remote_fund_pubkey: accept_params.fund_pubkey,
+                fund_amount: Amount::from_sat(1),
+                max_witness_len: 220,
+                input_serial_id: 3,
+                contract_id: [0; 32],
+            });
+
+        assert!(create_taproot_dlc_transactions(
+            &offer_params,
+            &accept_params,
+            &payouts(),
+            1_000,
+            4,
+            10,
+            10,
+            0,
+            0,
+        )
+        .is_err());
+    }
+}
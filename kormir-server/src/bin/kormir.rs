@@ -0,0 +1,83 @@
+use anyhow::Context;
+use bitcoin::bip32::Xpriv;
+use bitcoin::key::rand::{self, Fill};
+use bitcoin::Network;
+use clap::Parser;
+use kormir::storage::postgres::PostgresStorage;
+use kormir::storage::sled::SledStorage;
+use kormir::Oracle;
+use kormir_server::opts::ServerOpts;
+use kormir_server::storage::ServerStorage;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use tracing_subscriber::EnvFilter;
+
+/// Reads the oracle seed from `seed.kormir` in `dir`, creating it on first run.
+fn seed_from_dir(dir: &Path) -> anyhow::Result<[u8; 64]> {
+    let seed_path = dir.join("seed.kormir");
+    if seed_path.exists() {
+        let seed = std::fs::read(&seed_path)?;
+        return seed
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("{} is not a 64 byte seed", seed_path.display()));
+    }
+
+    let mut seed = [0u8; 64];
+    seed.try_fill(&mut rand::thread_rng())?;
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&seed_path)?.write_all(&seed)?;
+    Ok(seed)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = ServerOpts::parse();
+    let filter = match opts.log.as_str() {
+        "debug" => "debug,hyper_util=info,sqlx=info,sled=info",
+        _ => "info",
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(filter))
+        .init();
+
+    let storage_dir = match opts.storage_dir {
+        Some(dir) => dir,
+        None => homedir::my_home()?
+            .context("Could not find the home directory.")?
+            .join(".kormir"),
+    };
+    std::fs::create_dir_all(&storage_dir)?;
+
+    let storage = match &opts.postgres_url {
+        Some(url) => ServerStorage::Postgres(
+            PostgresStorage::new(url, true)
+                .await
+                .map_err(|e| anyhow::anyhow!("Could not open postgres storage: {e}"))?,
+        ),
+        None => {
+            let path = storage_dir.join("sled");
+            ServerStorage::Sled(
+                SledStorage::new(&path.to_string_lossy())
+                    .map_err(|e| anyhow::anyhow!("Could not open sled storage: {e}"))?,
+            )
+        }
+    };
+
+    let xpriv = Xpriv::new_master(Network::Bitcoin, &seed_from_dir(&storage_dir)?)?;
+    let oracle = Oracle::from_xpriv(storage, xpriv)
+        .map_err(|e| anyhow::anyhow!("Could not create the oracle: {e}"))?;
+
+    let listener = tokio::net::TcpListener::bind(&opts.host).await?;
+    tracing::info!(
+        host = opts.host,
+        pubkey = oracle.public_key().to_string(),
+        "Kormir oracle listening."
+    );
+    kormir_server::serve(listener, oracle, opts.hmac_secret.map(String::into_bytes)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,299 @@
+//! HTTP server for a [`kormir`] oracle.
+//!
+//! The API is the one DDK's `KormirOracleClient` speaks. Requests and responses
+//! are JSON; announcements and attestations use the serde representation of
+//! `ddk-messages`.
+//!
+//! | Method | Path | Response |
+//! | --- | --- | --- |
+//! | `GET` | `/health-check` | `OK` |
+//! | `GET` | `/pubkey` | [`PubkeyResponse`] |
+//! | `GET` | `/list-events` | every stored event |
+//! | `POST` | `/create-enum` | the announcement of a [`CreateEnumEvent`] |
+//! | `POST` | `/sign-enum` | the attestation of a [`SignEnumEvent`] |
+//! | `POST` | `/create-numeric` | the announcement of a [`CreateNumericEvent`] |
+//! | `POST` | `/sign-numeric` | the attestation of a [`SignNumericEvent`] |
+//! | `GET` | `/announcement/{event_id}` | the announcement of the event |
+//! | `GET` | `/attestation/{event_id}` | the attestation of the event, once signed |
+//!
+//! When the server has an HMAC secret, a `POST` must carry the hex encoded
+//! HMAC-SHA256 of its body under that secret in an `X-Signature` header. A
+//! `POST` makes the oracle announce or attest, so [`serve`] only goes without
+//! a secret on a loopback address.
+
+pub mod opts;
+pub mod storage;
+
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use bitcoin::key::XOnlyPublicKey;
+use hmac::{Hmac, KeyInit, Mac};
+use kormir::error::Error;
+use kormir::storage::Storage;
+use kormir::Oracle;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+use storage::ServerStorage;
+use tokio::net::TcpListener;
+
+/// Number of binary digits of a numeric event when the request leaves it out.
+pub const DEFAULT_NUM_DIGITS: u16 = 18;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PubkeyResponse {
+    pub pubkey: XOnlyPublicKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateEnumEvent {
+    pub event_id: String,
+    pub outcomes: Vec<String>,
+    pub event_maturity_epoch: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignEnumEvent {
+    pub event_id: String,
+    pub outcome: String,
+}
+
+/// Omitted fields default to [`DEFAULT_NUM_DIGITS`] unsigned digits and a
+/// precision of zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateNumericEvent {
+    pub event_id: String,
+    pub num_digits: Option<u16>,
+    pub is_signed: Option<bool>,
+    pub precision: Option<i32>,
+    pub unit: String,
+    pub event_maturity_epoch: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignNumericEvent {
+    pub event_id: String,
+    pub outcome: i64,
+}
+
+struct ServerState {
+    oracle: Oracle<ServerStorage>,
+    hmac_secret: Option<Vec<u8>>,
+}
+
+/// Builds the routes of the server.
+pub fn router(oracle: Oracle<ServerStorage>, hmac_secret: Option<Vec<u8>>) -> Router {
+    let state = Arc::new(ServerState {
+        oracle,
+        hmac_secret,
+    });
+    Router::new()
+        .route("/health-check", get(health_check))
+        .route("/pubkey", get(pubkey))
+        .route("/list-events", get(list_events))
+        .route("/create-enum", post(create_enum_event))
+        .route("/sign-enum", post(sign_enum_event))
+        .route("/create-numeric", post(create_numeric_event))
+        .route("/sign-numeric", post(sign_numeric_event))
+        .route("/announcement/{event_id}", get(get_announcement))
+        .route("/attestation/{event_id}", get(get_attestation))
+        .with_state(state)
+}
+
+/// Serves the oracle on `listener` until the process stops.
+///
+/// Fails without serving when `listener` is not bound to a loopback address
+/// and there is no HMAC secret, since anyone reaching the port could then have
+/// the oracle attest to outcomes of their choosing.
+pub async fn serve(
+    listener: TcpListener,
+    oracle: Oracle<ServerStorage>,
+    hmac_secret: Option<Vec<u8>>,
+) -> std::io::Result<()> {
+    let addr = listener.local_addr()?;
+    if hmac_secret.is_none() && !addr.ip().is_loopback() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("an HMAC secret is required to serve on the non-loopback address {addr}"),
+        ));
+    }
+    axum::serve(listener, router(oracle, hmac_secret)).await
+}
+
+/// An error response, with the error message as its body.
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        #[allow(deprecated)]
+        let status = match error {
+            Error::NotFound => StatusCode::NOT_FOUND,
+            Error::EventAlreadySigned | Error::EventAlreadyExists | Error::NonceReused => {
+                StatusCode::CONFLICT
+            }
+            Error::StorageFailure | Error::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::InvalidArgument
+            | Error::InvalidEventId
+            | Error::InvalidOutcomes
+            | Error::InvalidBase
+            | Error::InvalidNumberOfDigits
+            | Error::InvalidNonces
+            | Error::InvalidOutcome
+            | Error::InvalidEventDescriptor
+            | Error::InvalidAnnouncement => StatusCode::BAD_REQUEST,
+        };
+        ApiError(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.0.is_server_error() {
+            tracing::error!(error = self.1, "Oracle request failed.");
+        }
+        (self.0, self.1).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Checks the signature of a `POST` body and parses it.
+fn authenticated_body<T: DeserializeOwned>(
+    state: &ServerState,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<T, ApiError> {
+    if let Some(secret) = &state.hmac_secret {
+        let unauthorized = || ApiError(StatusCode::UNAUTHORIZED, "Invalid signature".to_string());
+        let signature = headers
+            .get("X-Signature")
+            .and_then(|value| hex::decode(value.as_bytes()).ok())
+            .ok_or_else(unauthorized)?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret)
+            .map_err(|_| ApiError(StatusCode::INTERNAL_SERVER_ERROR, "Bad secret".to_string()))?;
+        mac.update(body);
+        mac.verify_slice(&signature).map_err(|_| unauthorized())?;
+    }
+    serde_json::from_slice(body).map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+async fn pubkey(State(state): State<Arc<ServerState>>) -> Json<PubkeyResponse> {
+    Json(PubkeyResponse {
+        pubkey: state.oracle.public_key(),
+    })
+}
+
+async fn list_events(
+    State(state): State<Arc<ServerState>>,
+) -> ApiResult<Vec<kormir::storage::OracleEventData>> {
+    Ok(Json(state.oracle.storage.list_events().await?))
+}
+
+async fn create_enum_event(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<kormir::OracleAnnouncement> {
+    let request: CreateEnumEvent = authenticated_body(&state, &headers, &body)?;
+    let announcement = state
+        .oracle
+        .create_enum_event(
+            request.event_id,
+            request.outcomes,
+            request.event_maturity_epoch,
+        )
+        .await?;
+    tracing::info!(
+        event_id = announcement.oracle_event.event_id,
+        "Created enum event."
+    );
+    Ok(Json(announcement))
+}
+
+async fn sign_enum_event(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<kormir::OracleAttestation> {
+    let request: SignEnumEvent = authenticated_body(&state, &headers, &body)?;
+    let attestation = state
+        .oracle
+        .sign_enum_event(request.event_id, request.outcome)
+        .await?;
+    tracing::info!(event_id = attestation.event_id, "Signed enum event.");
+    Ok(Json(attestation))
+}
+
+async fn create_numeric_event(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<kormir::OracleAnnouncement> {
+    let request: CreateNumericEvent = authenticated_body(&state, &headers, &body)?;
+    let announcement = state
+        .oracle
+        .create_numeric_event(
+            request.event_id,
+            request.num_digits.unwrap_or(DEFAULT_NUM_DIGITS),
+            request.is_signed.unwrap_or(false),
+            request.precision.unwrap_or(0),
+            request.unit,
+            request.event_maturity_epoch,
+        )
+        .await?;
+    tracing::info!(
+        event_id = announcement.oracle_event.event_id,
+        "Created numeric event."
+    );
+    Ok(Json(announcement))
+}
+
+async fn sign_numeric_event(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<kormir::OracleAttestation> {
+    let request: SignNumericEvent = authenticated_body(&state, &headers, &body)?;
+    let attestation = state
+        .oracle
+        .sign_numeric_event(request.event_id, request.outcome)
+        .await?;
+    tracing::info!(event_id = attestation.event_id, "Signed numeric event.");
+    Ok(Json(attestation))
+}
+
+async fn get_announcement(
+    State(state): State<Arc<ServerState>>,
+    Path(event_id): Path<String>,
+) -> ApiResult<kormir::OracleAnnouncement> {
+    let event = state
+        .oracle
+        .storage
+        .get_event(event_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    Ok(Json(event.announcement))
+}
+
+async fn get_attestation(
+    State(state): State<Arc<ServerState>>,
+    Path(event_id): Path<String>,
+) -> ApiResult<kormir::OracleAttestation> {
+    let event = state
+        .oracle
+        .storage
+        .get_event(event_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    let attestation = event.attestation().ok_or(Error::NotFound)?;
+    Ok(Json(attestation))
+}
@@ -0,0 +1,33 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser, Clone, Debug)]
+#[clap(name = "kormir")]
+#[clap(about = "Kormir DLC oracle server")]
+#[clap(version = option_env ! ("CARGO_PKG_VERSION").unwrap_or("unknown"))]
+pub struct ServerOpts {
+    #[arg(long)]
+    #[arg(help = "Set the log level.")]
+    #[arg(default_value = "info")]
+    #[arg(value_parser = ["info", "debug"])]
+    pub log: String,
+    #[arg(long)]
+    #[arg(default_value = "127.0.0.1:8082")]
+    #[arg(
+        help = "Host and port the HTTP server will run on. A non-loopback address requires an HMAC secret."
+    )]
+    pub host: String,
+    #[arg(short, long)]
+    #[arg(
+        help = "The path where kormir stores its seed, and its sled database when no postgres url is given. Defaults to ~/.kormir."
+    )]
+    pub storage_dir: Option<PathBuf>,
+    #[arg(long, env = "DATABASE_URL")]
+    #[arg(help = "Url for the postgres database connection. Uses sled when not set.")]
+    pub postgres_url: Option<String>,
+    #[arg(long, env = "KORMIR_HMAC_SECRET", hide_env_values = true)]
+    #[arg(
+        help = "Secret that POST requests must be signed with. Required to listen on a non-loopback address."
+    )]
+    pub hmac_secret: Option<String>,
+}
@@ -0,0 +1,60 @@
+use kormir::error::Error;
+use kormir::storage::postgres::PostgresStorage;
+use kormir::storage::sled::SledStorage;
+use kormir::storage::{OracleEventData, Storage};
+use kormir::{OracleAnnouncement, Signature};
+
+/// The storage backends the server can run on.
+#[derive(Debug, Clone)]
+pub enum ServerStorage {
+    Sled(SledStorage),
+    Postgres(PostgresStorage),
+}
+
+impl ServerStorage {
+    /// Returns every event, in the order they were announced.
+    pub async fn list_events(&self) -> Result<Vec<OracleEventData>, Error> {
+        match self {
+            ServerStorage::Sled(s) => s.list_events(),
+            ServerStorage::Postgres(s) => s.list_events().await,
+        }
+    }
+}
+
+impl Storage for ServerStorage {
+    async fn get_next_nonce_indexes(&self, num: usize) -> Result<Vec<u32>, Error> {
+        match self {
+            ServerStorage::Sled(s) => s.get_next_nonce_indexes(num).await,
+            ServerStorage::Postgres(s) => s.get_next_nonce_indexes(num).await,
+        }
+    }
+
+    async fn save_announcement(
+        &self,
+        announcement: OracleAnnouncement,
+        indexes: Vec<u32>,
+    ) -> Result<String, Error> {
+        match self {
+            ServerStorage::Sled(s) => s.save_announcement(announcement, indexes).await,
+            ServerStorage::Postgres(s) => s.save_announcement(announcement, indexes).await,
+        }
+    }
+
+    async fn save_signatures(
+        &self,
+        event_id: String,
+        sigs: Vec<(String, Signature)>,
+    ) -> Result<OracleEventData, Error> {
+        match self {
+            ServerStorage::Sled(s) => s.save_signatures(event_id, sigs).await,
+            ServerStorage::Postgres(s) => s.save_signatures(event_id, sigs).await,
+        }
+    }
+
+    async fn get_event(&self, event_id: String) -> Result<Option<OracleEventData>, Error> {
+        match self {
+            ServerStorage::Sled(s) => s.get_event(event_id).await,
+            ServerStorage::Postgres(s) => s.get_event(event_id).await,
+        }
+    }
+}
@@ -18,6 +18,11 @@ pub enum Error {
     InvalidNonces,
     /// Attempted to sign an event that was already signed
     EventAlreadySigned,
+    /// Attempted to announce an event whose ID is already taken
+    EventAlreadyExists,
+    /// Attempted to announce an event with nonce indexes that were not
+    /// reserved for it, or that another event already uses
+    NonceReused,
     /// Event data was not found
     NotFound,
     /// The storage failed to read/save the data
@@ -43,6 +48,8 @@ impl Display for Error {
             Error::InvalidNumberOfDigits => write!(f, "Invalid number of digits given"),
             Error::InvalidNonces => write!(f, "Invalid nonces given"),
             Error::EventAlreadySigned => write!(f, "Event already signed"),
+            Error::EventAlreadyExists => write!(f, "Event already exists"),
+            Error::NonceReused => write!(f, "Nonce indexes are not available"),
             Error::NotFound => write!(f, "Event data not found"),
             Error::StorageFailure => write!(f, "Storage failure"),
             Error::InvalidOutcome => write!(f, "Invalid outcome"),
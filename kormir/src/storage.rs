@@ -6,12 +6,32 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
 
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sled")]
+pub mod sled;
+
+/// Persistence for an [`Oracle`](crate::Oracle).
+///
+/// Signing two outcomes with the same nonce reveals the oracle's signing key,
+/// so an implementation must make sure a nonce index signs at most once:
+///
+/// - `get_next_nonce_indexes` never hands out an index twice, including
+///   across restarts.
+/// - `save_announcement` only accepts indexes that were handed out and that
+///   no other event uses, and never replaces an existing event.
+/// - `save_signatures` succeeds at most once per event, even when called
+///   concurrently. The oracle only releases an attestation once it is saved.
 pub trait Storage {
     /// Get the next `num` nonce indexes
     async fn get_next_nonce_indexes(&self, num: usize) -> Result<Vec<u32>, Error>;
 
     /// Save the announcement and return the identifier
     /// for the announcement
+    ///
+    /// Fails with [`Error::EventAlreadyExists`] if an event with the same ID
+    /// was saved before, and with [`Error::NonceReused`] if the indexes are not
+    /// available.
     async fn save_announcement(
         &self,
         announcement: OracleAnnouncement,
@@ -19,6 +39,8 @@ pub trait Storage {
     ) -> Result<String, Error>;
 
     /// Save signatures and outcomes for a given event
+    ///
+    /// Fails with [`Error::EventAlreadySigned`] if the event was signed before.
     async fn save_signatures(
         &self,
         event_id: String,
@@ -103,6 +125,16 @@ impl Storage for MemoryStorage {
         indexes: Vec<u32>,
     ) -> Result<String, Error> {
         let event_id = announcement.oracle_event.event_id.clone();
+        let mut data = self.data.try_write().unwrap();
+        if data.contains_key(&event_id) {
+            return Err(Error::EventAlreadyExists);
+        }
+        let next_index = self.current_index.load(Ordering::Relaxed);
+        let in_use = |index: &u32| data.values().any(|e| e.indexes.contains(index));
+        if indexes.iter().any(|i| *i >= next_index || in_use(i)) {
+            return Err(Error::NonceReused);
+        }
+
         let event = OracleEventData {
             event_id: event_id.clone(),
             announcement,
@@ -113,8 +145,6 @@ impl Storage for MemoryStorage {
             #[cfg(feature = "nostr")]
             attestation_event_id: None,
         };
-
-        let mut data = self.data.try_write().unwrap();
         data.insert(event_id.clone(), event);
 
         Ok(event_id)
@@ -145,3 +175,104 @@ impl Storage for MemoryStorage {
         Ok(data.get(&event_id).cloned())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::Oracle;
+    use bitcoin::bip32::Xpriv;
+    use bitcoin::Network;
+
+    pub(crate) fn oracle<S: Storage>(storage: S) -> Oracle<S> {
+        let xpriv = Xpriv::new_master(Network::Regtest, &[7; 32]).unwrap();
+        Oracle::from_xpriv(storage, xpriv).unwrap()
+    }
+
+    /// Checks the guarantees documented on [`Storage`], which every backend
+    /// must uphold. `storage` must be empty.
+    pub(crate) async fn check_storage<S: Storage>(storage: S) {
+        let oracle = oracle(storage);
+        assert_eq!(
+            oracle.storage.get_next_nonce_indexes(2).await.unwrap(),
+            vec![0, 1]
+        );
+
+        let announcement = oracle
+            .create_numeric_event("price".to_string(), 3, false, 0, "usd".to_string(), 100)
+            .await
+            .unwrap();
+        let event = oracle
+            .storage
+            .get_event("price".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.announcement, announcement);
+        assert_eq!(event.indexes, vec![2, 3, 4]);
+        assert!(event.attestation().is_none());
+
+        let duplicate = oracle
+            .storage
+            .save_announcement(announcement.clone(), vec![5, 6, 7])
+            .await;
+        assert!(matches!(duplicate, Err(Error::EventAlreadyExists)));
+
+        let mut other = announcement.clone();
+        other.oracle_event.event_id = "other".to_string();
+        let reused = oracle
+            .storage
+            .save_announcement(other.clone(), vec![4])
+            .await;
+        assert!(matches!(reused, Err(Error::NonceReused)));
+        let unreserved = oracle.storage.save_announcement(other, vec![1000]).await;
+        assert!(matches!(unreserved, Err(Error::NonceReused)));
+
+        let attestation = oracle
+            .sign_numeric_event("price".to_string(), 5)
+            .await
+            .unwrap();
+        let event = oracle
+            .storage
+            .get_event("price".to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.attestation(), Some(attestation));
+
+        let sigs = event.signatures.clone();
+        let resigned = oracle
+            .storage
+            .save_signatures("price".to_string(), sigs)
+            .await;
+        assert!(matches!(resigned, Err(Error::EventAlreadySigned)));
+        let missing = oracle
+            .storage
+            .save_signatures("missing".to_string(), vec![])
+            .await;
+        assert!(matches!(missing, Err(Error::NotFound)));
+        assert!(oracle
+            .storage
+            .get_event("missing".to_string())
+            .await
+            .unwrap()
+            .is_none());
+
+        oracle
+            .create_enum_event(
+                "coin".to_string(),
+                vec!["h".to_string(), "t".to_string()],
+                100,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            oracle.storage.get_next_nonce_indexes(1).await.unwrap(),
+            vec![6]
+        );
+    }
+
+    #[tokio::test]
+    async fn memory_storage_upholds_the_storage_guarantees() {
+        check_storage(MemoryStorage::default()).await;
+    }
+}
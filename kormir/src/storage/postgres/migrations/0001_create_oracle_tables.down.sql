@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS event_signatures;
+DROP TABLE IF EXISTS event_nonces;
+DROP TABLE IF EXISTS events;
+DROP TABLE IF EXISTS nonce_index;
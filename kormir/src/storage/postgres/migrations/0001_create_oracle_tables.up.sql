@@ -0,0 +1,35 @@
+-- Single row holding the next nonce index to hand out. Nonce keys are derived
+-- at hardened indexes, so no index may reach 2^31.
+CREATE TABLE IF NOT EXISTS nonce_index (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    next_index BIGINT NOT NULL CHECK (next_index >= 0 AND next_index <= 2147483648)
+);
+INSERT INTO nonce_index (id, next_index) VALUES (TRUE, 0) ON CONFLICT DO NOTHING;
+
+-- Announcement is the TLV encoded oracle announcement,
+-- signed_at is set once, when the attestation is saved
+CREATE TABLE IF NOT EXISTS events (
+    event_id TEXT PRIMARY KEY,
+    announcement BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    signed_at TIMESTAMPTZ,
+    announcement_event_id TEXT,
+    attestation_event_id TEXT
+);
+
+-- Each nonce index belongs to at most one event
+CREATE TABLE IF NOT EXISTS event_nonces (
+    nonce_index INTEGER PRIMARY KEY CHECK (nonce_index >= 0),
+    event_id TEXT NOT NULL REFERENCES events (event_id),
+    position INTEGER NOT NULL CHECK (position >= 0),
+    UNIQUE (event_id, position)
+);
+
+-- Signature is the 64 byte schnorr signature of the outcome at position
+CREATE TABLE IF NOT EXISTS event_signatures (
+    event_id TEXT NOT NULL REFERENCES events (event_id),
+    position INTEGER NOT NULL CHECK (position >= 0),
+    outcome TEXT NOT NULL,
+    signature BYTEA NOT NULL,
+    PRIMARY KEY (event_id, position)
+);
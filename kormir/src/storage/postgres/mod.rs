@@ -0,0 +1,328 @@
+//! [`Storage`] backed by a Postgres database.
+
+use crate::error::Error;
+use crate::storage::{OracleEventData, Storage};
+use bitcoin::secp256k1::schnorr::Signature;
+use ddk_messages::oracle_msgs::OracleAnnouncement;
+use ddk_messages::TlvRecord;
+use sqlx::postgres::{PgPoolOptions, PgRow};
+use sqlx::{Pool, Postgres, Row};
+
+/// The embedded schema migrations for the Postgres storage backend.
+///
+/// The migration files are compiled into the crate, so the schema can be
+/// applied or reverted without access to the source tree:
+///
+/// ```ignore
+/// kormir::storage::postgres::MIGRATOR.run(&pool).await?;          // apply up
+/// kormir::storage::postgres::MIGRATOR.undo(&pool, version).await?; // revert down to `version`
+/// ```
+///
+/// [`PostgresStorage::new`] runs this same migrator when `migrations` is true.
+/// The oracle needs a database of its own: its migrations are numbered
+/// independently of any other schema's, including DDK's.
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("src/storage/postgres/migrations");
+
+/// Oracle storage in a Postgres database.
+///
+/// Nonce indexes are reserved by a single `UPDATE` of a counter row, each index
+/// belongs to at most one event by primary key, and an event is marked signed by
+/// a conditional `UPDATE`, so the guarantees of [`Storage`] hold across any
+/// number of oracle processes sharing the database.
+#[derive(Debug, Clone)]
+pub struct PostgresStorage {
+    pool: Pool<Postgres>,
+}
+
+impl PostgresStorage {
+    /// Connects to the database at `url`, running [`MIGRATOR`] first if
+    /// `migrations` is true.
+    pub async fn new(url: &str, migrations: bool) -> Result<Self, Error> {
+        let pool = PgPoolOptions::new()
+            .connect(url)
+            .await
+            .map_err(storage_failure)?;
+        if migrations {
+            MIGRATOR.run(&pool).await.map_err(|e| {
+                log::error!("Could not migrate the oracle database: {e}");
+                Error::StorageFailure
+            })?;
+        }
+        Ok(Self { pool })
+    }
+
+    /// Uses an existing pool, whose database must already be migrated.
+    pub fn from_pool(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Returns every event, in the order they were announced.
+    pub async fn list_events(&self) -> Result<Vec<OracleEventData>, Error> {
+        let rows = sqlx::query(
+            "SELECT event_id, announcement, announcement_event_id, attestation_event_id \
+             FROM events ORDER BY created_at, event_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage_failure)?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            events.push(self.event_from_row(row).await?);
+        }
+        Ok(events)
+    }
+
+    async fn event_from_row(&self, row: PgRow) -> Result<OracleEventData, Error> {
+        let event_id: String = row.try_get("event_id").map_err(storage_failure)?;
+        let announcement: Vec<u8> = row.try_get("announcement").map_err(storage_failure)?;
+        let announcement = OracleAnnouncement::from_tlv_bytes(&announcement).map_err(|e| {
+            log::error!("Could not read stored announcement of {event_id}: {e:?}");
+            Error::StorageFailure
+        })?;
+
+        let indexes = sqlx::query_scalar::<_, i32>(
+            "SELECT nonce_index FROM event_nonces WHERE event_id = $1 ORDER BY position",
+        )
+        .bind(&event_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage_failure)?
+        .into_iter()
+        .map(|index| index as u32)
+        .collect();
+
+        let signatures = sqlx::query(
+            "SELECT outcome, signature FROM event_signatures \
+             WHERE event_id = $1 ORDER BY position",
+        )
+        .bind(&event_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage_failure)?
+        .into_iter()
+        .map(|row| {
+            let outcome: String = row.try_get("outcome").map_err(storage_failure)?;
+            let signature: Vec<u8> = row.try_get("signature").map_err(storage_failure)?;
+            let signature = Signature::from_slice(&signature).map_err(|_| Error::StorageFailure)?;
+            Ok((outcome, signature))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(OracleEventData {
+            #[cfg(feature = "nostr")]
+            announcement_event_id: row
+                .try_get("announcement_event_id")
+                .map_err(storage_failure)?,
+            #[cfg(feature = "nostr")]
+            attestation_event_id: row
+                .try_get("attestation_event_id")
+                .map_err(storage_failure)?,
+            event_id,
+            announcement,
+            indexes,
+            signatures,
+        })
+    }
+}
+
+impl Storage for PostgresStorage {
+    async fn get_next_nonce_indexes(&self, num: usize) -> Result<Vec<u32>, Error> {
+        let num = i64::try_from(num).map_err(|_| Error::Internal)?;
+        let start: i64 = sqlx::query_scalar(
+            "UPDATE nonce_index SET next_index = next_index + $1 RETURNING next_index - $1",
+        )
+        .bind(num)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(storage_failure)?;
+        Ok((start..start + num).map(|index| index as u32).collect())
+    }
+
+    async fn save_announcement(
+        &self,
+        announcement: OracleAnnouncement,
+        indexes: Vec<u32>,
+    ) -> Result<String, Error> {
+        let event_id = announcement.oracle_event.event_id.clone();
+        let mut tx = self.pool.begin().await.map_err(storage_failure)?;
+
+        let inserted = sqlx::query(
+            "INSERT INTO events (event_id, announcement) VALUES ($1, $2) \
+             ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(&event_id)
+        .bind(announcement.to_tlv_bytes())
+        .execute(&mut *tx)
+        .await
+        .map_err(storage_failure)?;
+        if inserted.rows_affected() == 0 {
+            return Err(Error::EventAlreadyExists);
+        }
+
+        let next_index: i64 = sqlx::query_scalar("SELECT next_index FROM nonce_index")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(storage_failure)?;
+        if indexes.iter().any(|index| i64::from(*index) >= next_index) {
+            return Err(Error::NonceReused);
+        }
+
+        for (position, index) in indexes.iter().enumerate() {
+            let inserted = sqlx::query(
+                "INSERT INTO event_nonces (nonce_index, event_id, position) VALUES ($1, $2, $3) \
+                 ON CONFLICT (nonce_index) DO NOTHING",
+            )
+            .bind(*index as i32)
+            .bind(&event_id)
+            .bind(position as i32)
+            .execute(&mut *tx)
+            .await
+            .map_err(storage_failure)?;
+            if inserted.rows_affected() == 0 {
+                return Err(Error::NonceReused);
+            }
+        }
+
+        tx.commit().await.map_err(storage_failure)?;
+        Ok(event_id)
+    }
+
+    async fn save_signatures(
+        &self,
+        event_id: String,
+        sigs: Vec<(String, Signature)>,
+    ) -> Result<OracleEventData, Error> {
+        let mut tx = self.pool.begin().await.map_err(storage_failure)?;
+
+        let signed = sqlx::query(
+            "UPDATE events SET signed_at = now() WHERE event_id = $1 AND signed_at IS NULL",
+        )
+        .bind(&event_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(storage_failure)?;
+        if signed.rows_affected() == 0 {
+            let exists: bool =
+                sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM events WHERE event_id = $1)")
+                    .bind(&event_id)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(storage_failure)?;
+            return Err(if exists {
+                Error::EventAlreadySigned
+            } else {
+                Error::NotFound
+            });
+        }
+
+        for (position, (outcome, signature)) in sigs.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO event_signatures (event_id, position, outcome, signature) \
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(&event_id)
+            .bind(position as i32)
+            .bind(outcome)
+            .bind(signature.as_ref().to_vec())
+            .execute(&mut *tx)
+            .await
+            .map_err(storage_failure)?;
+        }
+
+        tx.commit().await.map_err(storage_failure)?;
+        self.get_event(event_id).await?.ok_or(Error::Internal)
+    }
+
+    async fn get_event(&self, event_id: String) -> Result<Option<OracleEventData>, Error> {
+        let row = sqlx::query(
+            "SELECT event_id, announcement, announcement_event_id, attestation_event_id \
+             FROM events WHERE event_id = $1",
+        )
+        .bind(&event_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(storage_failure)?;
+        match row {
+            Some(row) => Ok(Some(self.event_from_row(row).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+fn storage_failure(error: sqlx::Error) -> Error {
+    log::error!("Oracle postgres storage failure: {error}");
+    Error::StorageFailure
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::{check_storage, oracle};
+    use ddk_testenv::postgres::TestPostgres;
+
+    #[tokio::test]
+    async fn postgres_storage_upholds_the_storage_guarantees() {
+        let server = TestPostgres::start("kormir").await;
+        check_storage(PostgresStorage::new(server.url(), true).await.unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn nonce_indexes_and_events_survive_a_restart() {
+        let server = TestPostgres::start("kormir").await;
+        {
+            let oracle = oracle(PostgresStorage::new(server.url(), true).await.unwrap());
+            oracle
+                .create_enum_event("a".to_string(), vec!["x".to_string()], 100)
+                .await
+                .unwrap();
+            oracle
+                .sign_enum_event("a".to_string(), "x".to_string())
+                .await
+                .unwrap();
+            oracle
+                .create_enum_event("b".to_string(), vec!["y".to_string()], 100)
+                .await
+                .unwrap();
+        }
+
+        let storage = PostgresStorage::new(server.url(), true).await.unwrap();
+        assert_eq!(storage.get_next_nonce_indexes(1).await.unwrap(), vec![2]);
+        let events = storage.list_events().await.unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .map(|e| e.event_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert!(events[0].attestation().is_some());
+        assert!(events[1].attestation().is_none());
+
+        let oracle = oracle(storage);
+        let resigned = oracle
+            .sign_enum_event("a".to_string(), "x".to_string())
+            .await;
+        assert!(matches!(resigned, Err(Error::EventAlreadySigned)));
+    }
+
+    #[tokio::test]
+    async fn concurrent_signing_succeeds_once() {
+        let server = TestPostgres::start("kormir").await;
+        let oracle = oracle(PostgresStorage::new(server.url(), true).await.unwrap());
+        oracle
+            .create_enum_event(
+                "race".to_string(),
+                vec!["x".to_string(), "y".to_string()],
+                100,
+            )
+            .await
+            .unwrap();
+
+        let (x, y) = tokio::join!(
+            oracle.sign_enum_event("race".to_string(), "x".to_string()),
+            oracle.sign_enum_event("race".to_string(), "y".to_string()),
+        );
+        assert!(x.is_ok() != y.is_ok());
+    }
+}
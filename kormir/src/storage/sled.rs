@@ -0,0 +1,271 @@
+//! [`Storage`] backed by a sled database.
+
+use crate::error::Error;
+use crate::storage::{OracleEventData, Storage};
+use bitcoin::secp256k1::schnorr::Signature;
+use ddk_messages::oracle_msgs::OracleAnnouncement;
+use sled::transaction::{ConflictableTransactionError, TransactionError, Transactional};
+use sled::{Db, Tree};
+
+const EVENT_TREE: &str = "events";
+const NONCE_TREE: &str = "nonces";
+const NEXT_NONCE_INDEX_KEY: &str = "next_nonce_index";
+/// Nonce keys are derived at hardened indexes, which stop below 2^31.
+const NONCE_INDEX_LIMIT: u32 = 1 << 31;
+
+/// Oracle storage in a sled database.
+///
+/// Events are kept as JSON by event ID, alongside a tree mapping each nonce
+/// index in use to its event and the next index to hand out. Every write is
+/// flushed to disk before it returns, so a nonce index handed out before a
+/// crash is never handed out again.
+#[derive(Debug, Clone)]
+pub struct SledStorage {
+    db: Db,
+    events: Tree,
+    nonces: Tree,
+}
+
+impl SledStorage {
+    /// Opens the database at `path`, creating it if needed.
+    pub fn new(path: &str) -> Result<Self, Error> {
+        let db = sled::open(path).map_err(storage_failure)?;
+        Self::from_db(db)
+    }
+
+    /// Uses an already opened database. Kormir only touches its own trees.
+    pub fn from_db(db: Db) -> Result<Self, Error> {
+        Ok(Self {
+            events: db.open_tree(EVENT_TREE).map_err(storage_failure)?,
+            nonces: db.open_tree(NONCE_TREE).map_err(storage_failure)?,
+            db,
+        })
+    }
+
+    /// Returns every event, in the order they were announced.
+    pub fn list_events(&self) -> Result<Vec<OracleEventData>, Error> {
+        let mut events = self
+            .events
+            .iter()
+            .values()
+            .map(|value| deserialize_event(&value.map_err(storage_failure)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        events.sort_by_key(|event| event.indexes.first().copied());
+        Ok(events)
+    }
+
+    fn next_nonce_index(&self) -> Result<u32, Error> {
+        match self.db.get(NEXT_NONCE_INDEX_KEY).map_err(storage_failure)? {
+            Some(bytes) => decode_index(&bytes),
+            None => Ok(0),
+        }
+    }
+
+    async fn flush(&self) -> Result<(), Error> {
+        self.db.flush_async().await.map_err(storage_failure)?;
+        Ok(())
+    }
+}
+
+impl Storage for SledStorage {
+    async fn get_next_nonce_indexes(&self, num: usize) -> Result<Vec<u32>, Error> {
+        let start = loop {
+            let current = self.db.get(NEXT_NONCE_INDEX_KEY).map_err(storage_failure)?;
+            let start = current.as_deref().map_or(Ok(0), decode_index)?;
+            let next = u32::try_from(num)
+                .ok()
+                .and_then(|num| start.checked_add(num))
+                .filter(|next| *next <= NONCE_INDEX_LIMIT)
+                .ok_or(Error::Internal)?;
+            let swapped = self
+                .db
+                .compare_and_swap(
+                    NEXT_NONCE_INDEX_KEY,
+                    current,
+                    Some(next.to_be_bytes().to_vec()),
+                )
+                .map_err(storage_failure)?;
+            if swapped.is_ok() {
+                break start;
+            }
+        };
+        self.flush().await?;
+        Ok((start..start + num as u32).collect())
+    }
+
+    async fn save_announcement(
+        &self,
+        announcement: OracleAnnouncement,
+        indexes: Vec<u32>,
+    ) -> Result<String, Error> {
+        let event_id = announcement.oracle_event.event_id.clone();
+        let next_index = self.next_nonce_index()?;
+        let unreserved = indexes.iter().any(|index| *index >= next_index);
+        let event = serialize_event(&OracleEventData {
+            event_id: event_id.clone(),
+            announcement,
+            indexes: indexes.clone(),
+            signatures: Default::default(),
+            #[cfg(feature = "nostr")]
+            announcement_event_id: None,
+            #[cfg(feature = "nostr")]
+            attestation_event_id: None,
+        })?;
+
+        (&self.events, &self.nonces)
+            .transaction(|(events, nonces)| {
+                if events.get(event_id.as_bytes())?.is_some() {
+                    return abort(Error::EventAlreadyExists);
+                }
+                if unreserved {
+                    return abort(Error::NonceReused);
+                }
+                for index in &indexes {
+                    if nonces
+                        .insert(&index.to_be_bytes(), event_id.as_bytes())?
+                        .is_some()
+                    {
+                        return abort(Error::NonceReused);
+                    }
+                }
+                events.insert(event_id.as_bytes(), event.as_slice())?;
+                Ok(())
+            })
+            .map_err(transaction_error)?;
+        self.flush().await?;
+
+        Ok(event_id)
+    }
+
+    async fn save_signatures(
+        &self,
+        event_id: String,
+        sigs: Vec<(String, Signature)>,
+    ) -> Result<OracleEventData, Error> {
+        let event = self
+            .events
+            .transaction(|events| {
+                let Some(bytes) = events.get(event_id.as_bytes())? else {
+                    return abort(Error::NotFound);
+                };
+                let mut event = deserialize_event(&bytes).or_else(abort)?;
+                if !event.signatures.is_empty() {
+                    return abort(Error::EventAlreadySigned);
+                }
+                event.signatures = sigs.clone();
+                events.insert(event_id.as_bytes(), serialize_event(&event).or_else(abort)?)?;
+                Ok(event)
+            })
+            .map_err(transaction_error)?;
+        self.flush().await?;
+
+        Ok(event)
+    }
+
+    async fn get_event(&self, event_id: String) -> Result<Option<OracleEventData>, Error> {
+        self.events
+            .get(event_id.as_bytes())
+            .map_err(storage_failure)?
+            .map(|bytes| deserialize_event(&bytes))
+            .transpose()
+    }
+}
+
+fn abort<T>(error: Error) -> Result<T, ConflictableTransactionError<Error>> {
+    Err(ConflictableTransactionError::Abort(error))
+}
+
+fn transaction_error(error: TransactionError<Error>) -> Error {
+    match error {
+        TransactionError::Abort(error) => error,
+        TransactionError::Storage(error) => storage_failure(error),
+    }
+}
+
+fn storage_failure(error: sled::Error) -> Error {
+    log::error!("Oracle sled storage failure: {error}");
+    Error::StorageFailure
+}
+
+fn decode_index(bytes: &[u8]) -> Result<u32, Error> {
+    let bytes = <[u8; 4]>::try_from(bytes).map_err(|_| Error::StorageFailure)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn serialize_event(event: &OracleEventData) -> Result<Vec<u8>, Error> {
+    serde_json::to_vec(event).map_err(|_| Error::Internal)
+}
+
+fn deserialize_event(bytes: &[u8]) -> Result<OracleEventData, Error> {
+    serde_json::from_slice(bytes).map_err(|e| {
+        log::error!("Could not read stored oracle event: {e}");
+        Error::StorageFailure
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tests::{check_storage, oracle};
+
+    struct TempDb(String);
+
+    impl TempDb {
+        fn new(name: &str) -> Self {
+            let path = format!("{}/kormir_sled_{name}", std::env::temp_dir().display());
+            let _ = std::fs::remove_dir_all(&path);
+            TempDb(path)
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn sled_storage_upholds_the_storage_guarantees() {
+        let db = TempDb::new("guarantees");
+        check_storage(SledStorage::new(&db.0).unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn nonce_indexes_and_events_survive_a_restart() {
+        let db = TempDb::new("restart");
+        {
+            let oracle = oracle(SledStorage::new(&db.0).unwrap());
+            oracle
+                .create_enum_event("a".to_string(), vec!["x".to_string()], 100)
+                .await
+                .unwrap();
+            oracle
+                .sign_enum_event("a".to_string(), "x".to_string())
+                .await
+                .unwrap();
+            oracle
+                .create_enum_event("b".to_string(), vec!["y".to_string()], 100)
+                .await
+                .unwrap();
+        }
+
+        let storage = SledStorage::new(&db.0).unwrap();
+        assert_eq!(storage.get_next_nonce_indexes(1).await.unwrap(), vec![2]);
+        let events = storage.list_events().unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .map(|e| e.event_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert!(events[0].attestation().is_some());
+        assert!(events[1].attestation().is_none());
+
+        let oracle = oracle(storage);
+        let resigned = oracle
+            .sign_enum_event("a".to_string(), "x".to_string())
+            .await;
+        assert!(matches!(resigned, Err(Error::EventAlreadySigned)));
+    }
+}
@@ -0,0 +1,388 @@
+//! Contracts for difference, forwards and hedges on a USD quoted bitcoin price.
+//!
+//! The oracle attests to the price of bitcoin in USD and payouts are in sats. Two payoffs are
+//! supported, both settled like a forward at the attested price:
+//!
+//! - Linear: the long gains a fixed amount of sats per dollar the price moves above the entry.
+//! - Inverse: the position is sized in USD, as inverse perpetuals are. The long gains
+//!   `quantity * (1 / entry - 1 / price)` BTC, so the payout curve is a hyperbola.
+//!
+//! Payouts are bounded by the collateral. A party whose collateral is exhausted is liquidated and
+//! receives nothing, and the curve is flat past the liquidation prices.
+use bitcoin::Amount;
+use ddk_manager::contract::contract_input::{ContractInput, ContractInputInfo, OracleInput};
+use ddk_messages::oracle_msgs::{EventDescriptor, OracleAnnouncement};
+use ddk_trie::OracleNumericInfo;
+
+use crate::curve::{Interpolation, PayoutCurveBuilder};
+use crate::options::Direction;
+
+const SATS_PER_BTC: f64 = 100_000_000.0;
+
+/// Terms of a position between the offer and accept party.
+pub struct CfdTerms {
+    /// Side taken by the offer party. The accept party takes the other side.
+    pub direction: Direction,
+    /// Price in USD the position is opened at.
+    pub entry_price: u64,
+    pub offer_collateral: Amount,
+    pub accept_collateral: Amount,
+    pub fee_rate: u64,
+}
+
+/// A linear CFD where the long gains `sats_per_dollar` for every dollar the price settles above
+/// the entry price, and loses as much for every dollar below it.
+pub fn linear_cfd(
+    announcement: &OracleAnnouncement,
+    terms: &CfdTerms,
+    sats_per_dollar: Amount,
+) -> anyhow::Result<ContractInput> {
+    if sats_per_dollar == Amount::ZERO {
+        anyhow::bail!("A linear CFD needs a non zero size.");
+    }
+    let (long_collateral, short_collateral) = collateral_by_side(terms);
+    let entry = terms.entry_price as f64;
+    let size = sats_per_dollar.to_sat() as f64;
+    build_contract(
+        announcement,
+        terms,
+        Interpolation::Linear,
+        entry - long_collateral / size,
+        entry + short_collateral / size,
+        |price| long_collateral + size * (price - entry),
+    )
+}
+
+/// An inverse CFD of `quantity` USD, where the long gains `quantity * (1 / entry - 1 / price)` BTC.
+pub fn inverse_cfd(
+    announcement: &OracleAnnouncement,
+    terms: &CfdTerms,
+    quantity: u64,
+) -> anyhow::Result<ContractInput> {
+    if quantity == 0 || terms.entry_price == 0 {
+        anyhow::bail!("An inverse CFD needs a non zero quantity and entry price.");
+    }
+    let (long_collateral, short_collateral) = collateral_by_side(terms);
+    let entry = terms.entry_price as f64;
+    let quantity = quantity as f64 * SATS_PER_BTC;
+    let upper = 1.0 / entry - short_collateral / quantity;
+    build_contract(
+        announcement,
+        terms,
+        Interpolation::Hyperbola,
+        1.0 / (1.0 / entry + long_collateral / quantity),
+        // A short with at least the quantity in collateral is never liquidated.
+        if upper > 0.0 { 1.0 / upper } else { f64::MAX },
+        |price| long_collateral + quantity * (1.0 / entry - 1.0 / price),
+    )
+}
+
+/// An inverse position of the offer party, opened with `margin` at `leverage` times its value.
+/// The accept party takes the other side with `counterparty_collateral`.
+pub fn leveraged_position(
+    announcement: &OracleAnnouncement,
+    direction: Direction,
+    entry_price: u64,
+    margin: Amount,
+    leverage: u64,
+    counterparty_collateral: Amount,
+    fee_rate: u64,
+) -> anyhow::Result<ContractInput> {
+    if leverage == 0 {
+        anyhow::bail!("Leverage must be at least 1.");
+    }
+    let quantity = margin.to_sat() as f64 * leverage as f64 * entry_price as f64 / SATS_PER_BTC;
+    let terms = CfdTerms {
+        direction,
+        entry_price,
+        offer_collateral: margin,
+        accept_collateral: counterparty_collateral,
+        fee_rate,
+    };
+    inverse_cfd(announcement, &terms, quantity.round() as u64)
+}
+
+/// Price at which a leveraged inverse position loses its margin, if it can be liquidated. A short
+/// without leverage is fully hedged and never liquidated.
+pub fn liquidation_price(direction: Direction, entry_price: u64, leverage: u64) -> Option<u64> {
+    let entry = entry_price as f64;
+    let leverage = leverage as f64;
+    match direction {
+        Direction::Long => Some((entry * leverage / (leverage + 1.0)).ceil() as u64),
+        Direction::Short if leverage > 1.0 => {
+            Some((entry * leverage / (leverage - 1.0)).floor() as u64)
+        }
+        Direction::Short => None,
+    }
+}
+
+/// A hedge keeping the USD value of `hedged` sats of the offer party stable, as long as the
+/// `counterparty_collateral` of the accept party covers the drop in price.
+pub fn stable_hedge(
+    announcement: &OracleAnnouncement,
+    entry_price: u64,
+    hedged: Amount,
+    counterparty_collateral: Amount,
+    fee_rate: u64,
+) -> anyhow::Result<ContractInput> {
+    leveraged_position(
+        announcement,
+        Direction::Short,
+        entry_price,
+        hedged,
+        1,
+        counterparty_collateral,
+        fee_rate,
+    )
+}
+
+/// Collateral of the long and the short, in sats.
+fn collateral_by_side(terms: &CfdTerms) -> (f64, f64) {
+    let offer = terms.offer_collateral.to_sat() as f64;
+    let accept = terms.accept_collateral.to_sat() as f64;
+    match terms.direction {
+        Direction::Long => (offer, accept),
+        Direction::Short => (accept, offer),
+    }
+}
+
+/// Build the contract from the payout of the long, between the prices where the long and the
+/// short are liquidated.
+fn build_contract(
+    announcement: &OracleAnnouncement,
+    terms: &CfdTerms,
+    interpolation: Interpolation,
+    long_liquidation: f64,
+    short_liquidation: f64,
+    long_payout: impl Fn(f64) -> f64,
+) -> anyhow::Result<ContractInput> {
+    let EventDescriptor::DigitDecompositionEvent(descriptor) =
+        &announcement.oracle_event.event_descriptor
+    else {
+        anyhow::bail!("The announcement is not for a numerical event.");
+    };
+    let oracle_numeric_info = OracleNumericInfo {
+        base: descriptor.base as usize,
+        nb_digits: vec![descriptor.nb_digits as usize],
+    };
+    let max_price = (descriptor.base as u64)
+        .checked_pow(descriptor.nb_digits as u32)
+        .map(|outcomes| outcomes - 1)
+        .ok_or_else(|| anyhow::anyhow!("The oracle outcomes do not fit in a u64."))?;
+    if terms.entry_price == 0 || terms.entry_price > max_price {
+        anyhow::bail!(
+            "Entry price {} is outside of the prices the oracle can attest to.",
+            terms.entry_price
+        );
+    }
+
+    let total_collateral = terms.offer_collateral + terms.accept_collateral;
+    let total = total_collateral.to_sat() as f64;
+    let offer_payout = |price: u64| {
+        let long = long_payout(price as f64).clamp(0.0, total);
+        let offer = match terms.direction {
+            Direction::Long => long,
+            Direction::Short => total - long,
+        };
+        Amount::from_sat(offer.round() as u64)
+    };
+
+    // The curve follows the payoff between the last price where the long is liquidated and the
+    // first price where the short is, and is flat outside of them.
+    let start = (long_liquidation.ceil().max(1.0) as u64).min(max_price);
+    let end = (short_liquidation.floor().min(max_price as f64) as u64).max(start);
+    if start == end {
+        anyhow::bail!("The collateral does not cover a single price move.");
+    }
+    let mut builder = PayoutCurveBuilder::new(total_collateral);
+    builder.set_oracle_numeric_info(oracle_numeric_info);
+    if start > 1 {
+        builder.add_point(start - 1, offer_payout(start - 1), Interpolation::Linear);
+    }
+    builder.add_point(start, offer_payout(start), interpolation);
+    builder.add_point(end, offer_payout(end), Interpolation::Linear);
+    if end < max_price {
+        builder.add_point(end + 1, offer_payout(end + 1), Interpolation::Linear);
+    }
+    let curve = builder.build()?;
+
+    let oracles = OracleInput {
+        public_keys: vec![announcement.oracle_public_key],
+        event_id: announcement.oracle_event.event_id.clone(),
+        threshold: 1,
+    };
+
+    Ok(ContractInput {
+        offer_collateral: terms.offer_collateral,
+        accept_collateral: terms.accept_collateral,
+        fee_rate: terms.fee_rate,
+        contract_flags: 0,
+        contract_infos: vec![ContractInputInfo {
+            contract_descriptor: curve.contract_descriptor(),
+            oracles,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bitcoin::key::XOnlyPublicKey;
+    use bitcoin::secp256k1::schnorr::Signature;
+    use ddk_manager::contract::ContractDescriptor;
+    use ddk_messages::oracle_msgs::{DigitDecompositionEventDescriptor, OracleEvent};
+
+    use super::*;
+
+    const ENTRY: u64 = 60_000;
+
+    fn announcement() -> OracleAnnouncement {
+        let pubkey = XOnlyPublicKey::from_str(
+            "e6642fd69bd211f93f7f1f36ca51a26a5290eb2dd1b0d8279a87bb0d480c8443",
+        )
+        .unwrap();
+        OracleAnnouncement {
+            announcement_signature: Signature::from_slice(&[1; 64]).unwrap(),
+            oracle_public_key: pubkey,
+            oracle_event: OracleEvent {
+                oracle_nonces: vec![pubkey; 17],
+                event_maturity_epoch: 1_000,
+                event_descriptor: EventDescriptor::DigitDecompositionEvent(
+                    DigitDecompositionEventDescriptor {
+                        base: 2,
+                        is_signed: false,
+                        unit: "usd/btc".to_string(),
+                        precision: 0,
+                        nb_digits: 17,
+                    },
+                ),
+                event_id: "btcusd".to_string(),
+            },
+        }
+    }
+
+    /// Asserts the offer payout at each price is within the rounding of the closed form.
+    fn assert_payouts(contract: &ContractInput, closed_form: impl Fn(f64) -> f64) {
+        let total_collateral = contract.offer_collateral + contract.accept_collateral;
+        let ContractDescriptor::Numerical(descriptor) =
+            &contract.contract_infos[0].contract_descriptor
+        else {
+            panic!("Expected a numerical descriptor.");
+        };
+        let ranges = descriptor
+            .payout_function
+            .to_range_payouts(total_collateral, &descriptor.rounding_intervals)
+            .unwrap();
+        let rounding_mod = descriptor.rounding_intervals.intervals[0].rounding_mod as f64;
+        let total = total_collateral.to_sat() as f64;
+        for price in [
+            1, 20_000, 40_000, 45_000, 55_000, 60_000, 75_000, 90_000, 131_071,
+        ] {
+            let payout = ranges
+                .iter()
+                .find(|r| r.start as u64 <= price && price < (r.start + r.count) as u64)
+                .unwrap()
+                .payout
+                .offer
+                .to_sat() as f64;
+            let expected = closed_form(price as f64).clamp(0.0, total);
+            assert!(
+                (payout - expected).abs() <= rounding_mod / 2.0 + 1.0,
+                "price {}: payout {} expected {}",
+                price,
+                payout,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn linear_cfd_pays_per_dollar() {
+        let terms = CfdTerms {
+            direction: Direction::Short,
+            entry_price: ENTRY,
+            offer_collateral: Amount::from_sat(1_000_000),
+            accept_collateral: Amount::from_sat(500_000),
+            fee_rate: 2,
+        };
+        let contract = linear_cfd(&announcement(), &terms, Amount::from_sat(50)).unwrap();
+
+        // The short offer is liquidated 20_000 dollars above the entry, the long 10_000 below.
+        assert_payouts(&contract, |price| {
+            1_000_000.0 - 50.0 * (price - ENTRY as f64)
+        });
+    }
+
+    #[test]
+    fn inverse_cfd_pays_in_btc() {
+        let terms = CfdTerms {
+            direction: Direction::Long,
+            entry_price: ENTRY,
+            offer_collateral: Amount::from_sat(500_000),
+            accept_collateral: Amount::from_sat(2_000_000),
+            fee_rate: 2,
+        };
+        let contract = inverse_cfd(&announcement(), &terms, 600).unwrap();
+
+        assert_payouts(&contract, |price| {
+            500_000.0 + 600.0 * SATS_PER_BTC * (1.0 / ENTRY as f64 - 1.0 / price)
+        });
+    }
+
+    #[test]
+    fn leveraged_long_is_liquidated() {
+        let margin = Amount::from_sat(1_000_000);
+        let contract = leveraged_position(
+            &announcement(),
+            Direction::Long,
+            ENTRY,
+            margin,
+            3,
+            Amount::from_sat(3_000_000),
+            2,
+        )
+        .unwrap();
+
+        assert_payouts(&contract, |price| {
+            1_000_000.0 * (1.0 + 3.0 * (1.0 - ENTRY as f64 / price))
+        });
+        assert_eq!(liquidation_price(Direction::Long, ENTRY, 3), Some(45_000));
+        assert_eq!(liquidation_price(Direction::Short, ENTRY, 3), Some(90_000));
+        assert_eq!(liquidation_price(Direction::Short, ENTRY, 1), None);
+    }
+
+    #[test]
+    fn leveraged_short_is_liquidated() {
+        let contract = leveraged_position(
+            &announcement(),
+            Direction::Short,
+            ENTRY,
+            Amount::from_sat(1_000_000),
+            3,
+            Amount::from_sat(3_000_000),
+            2,
+        )
+        .unwrap();
+
+        assert_payouts(&contract, |price| {
+            1_000_000.0 * (1.0 - 3.0 * (1.0 - ENTRY as f64 / price))
+        });
+    }
+
+    #[test]
+    fn stable_hedge_keeps_usd_value() {
+        let hedged = Amount::from_sat(1_000_000);
+        let contract = stable_hedge(
+            &announcement(),
+            ENTRY,
+            hedged,
+            Amount::from_sat(1_000_000),
+            2,
+        )
+        .unwrap();
+
+        // 600 dollars worth of sats until the counterparty runs out below 30_000.
+        assert_payouts(&contract, |price| 600.0 * SATS_PER_BTC / price);
+    }
+}
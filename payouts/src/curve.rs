@@ -0,0 +1,608 @@
+//! Payout curves built from a table of outcome and payout points.
+//!
+//! Payoffs designed in a spreadsheet can be exported as CSV and turned into a [`PayoutFunction`]
+//! with [`PayoutCurveBuilder`]. Each point carries an [`Interpolation`] hint describing how the
+//! payout moves to the next point:
+//!
+//! ```csv
+//! outcome,payout,interpolation
+//! 0,0,linear
+//! 20000,0,linear
+//! 60000,100000,hyperbola
+//! 100000,150000
+//! ```
+//!
+//! The same points can be given as JSON:
+//!
+//! ```json
+//! [
+//!   { "outcome": 20000, "payout": 0 },
+//!   { "outcome": 60000, "payout": 100000, "interpolation": "hyperbola" },
+//!   { "outcome": 100000, "payout": 150000 }
+//! ]
+//! ```
+//!
+//! Payouts are to the offer party, in sats. The curve is held flat before the first point and
+//! after the last one, so it covers every outcome the oracle can attest to.
+use std::str::FromStr;
+
+use bitcoin::Amount;
+use ddk_manager::contract::numerical_descriptor::NumericalDescriptor;
+use ddk_manager::contract::ContractDescriptor;
+use ddk_manager::payout_curve::{
+    HyperbolaPayoutCurvePiece, PayoutFunction, PayoutFunctionPiece, PayoutPoint,
+    PolynomialPayoutCurvePiece, RoundingInterval, RoundingIntervals,
+};
+use ddk_trie::digit_decomposition::group_by_ignoring_digits;
+use ddk_trie::OracleNumericInfo;
+use serde::{Deserialize, Serialize};
+
+/// How the payout moves from a point to the next one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interpolation {
+    /// A straight line between the two points.
+    #[default]
+    Linear,
+    /// The payout of the point holds until the next point, where it jumps.
+    Step,
+    /// A curve of the form `k / outcome + m` through the two points, as the payout of inverse
+    /// contracts. The outcome of the point must be greater than zero.
+    Hyperbola,
+}
+
+impl FromStr for Interpolation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "linear" => Ok(Interpolation::Linear),
+            "step" => Ok(Interpolation::Step),
+            "hyperbola" => Ok(Interpolation::Hyperbola),
+            _ => Err(anyhow::anyhow!("Unknown interpolation: {}", s)),
+        }
+    }
+}
+
+/// A point of the payout curve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    /// The outcome attested by the oracle.
+    pub outcome: u64,
+    /// The payout to the offer party in sats.
+    pub payout: u64,
+    /// How the payout moves from this point to the next one. Ignored on the last point.
+    #[serde(default)]
+    pub interpolation: Interpolation,
+}
+
+/// A payout curve built by [`PayoutCurveBuilder`], with statistics about the CETs it produces.
+#[derive(Debug, Clone)]
+pub struct PayoutCurve {
+    pub payout_function: PayoutFunction,
+    pub rounding_intervals: RoundingIntervals,
+    pub oracle_numeric_info: OracleNumericInfo,
+    /// Number of CETs needed with a single oracle.
+    pub cet_count: usize,
+    /// Largest difference between the rounded payouts and the input curve.
+    pub max_error: Amount,
+}
+
+impl PayoutCurve {
+    /// The numerical contract descriptor for the curve, to use in a `ContractInput`.
+    pub fn contract_descriptor(&self) -> ContractDescriptor {
+        ContractDescriptor::Numerical(NumericalDescriptor {
+            payout_function: self.payout_function.clone(),
+            rounding_intervals: self.rounding_intervals.clone(),
+            difference_params: None,
+            oracle_numeric_infos: self.oracle_numeric_info.clone(),
+        })
+    }
+}
+
+/// Builds a [`PayoutFunction`] from a list of [`CurvePoint`]s.
+///
+/// Consecutive linear segments that lie on the same line, within the tolerance, are merged into a
+/// single piece. Unless set, the rounding modulus is the largest power of ten below a thousandth
+/// of the total collateral.
+#[derive(Debug, Clone)]
+pub struct PayoutCurveBuilder {
+    total_collateral: Amount,
+    points: Vec<CurvePoint>,
+    oracle_numeric_info: OracleNumericInfo,
+    rounding_intervals: Option<RoundingIntervals>,
+    tolerance: Amount,
+}
+
+/// The columns of the CSV rows read by [`PayoutCurveBuilder::from_csv`].
+const CSV_COLUMNS: [&str; 3] = ["outcome", "payout", "interpolation"];
+
+impl PayoutCurveBuilder {
+    /// Create a builder for a contract with the given total collateral, and an oracle attesting
+    /// to 20 binary digits.
+    pub fn new(total_collateral: Amount) -> Self {
+        PayoutCurveBuilder {
+            total_collateral,
+            points: Vec::new(),
+            oracle_numeric_info: OracleNumericInfo {
+                base: 2,
+                nb_digits: vec![20],
+            },
+            rounding_intervals: None,
+            tolerance: Amount::ZERO,
+        }
+    }
+
+    /// Load the points from CSV rows of `outcome,payout[,interpolation]`. A first row naming
+    /// these columns, empty lines and lines starting with `#` are skipped.
+    pub fn from_csv(total_collateral: Amount, csv: &str) -> anyhow::Result<Self> {
+        let mut builder = Self::new(total_collateral);
+        let mut first_row = true;
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+            let is_header = first_row
+                && (2..=3).contains(&fields.len())
+                && fields
+                    .iter()
+                    .zip(CSV_COLUMNS)
+                    .all(|(field, column)| field.eq_ignore_ascii_case(column));
+            first_row = false;
+            if is_header {
+                continue;
+            }
+            if fields.len() < 2 || fields.len() > 3 {
+                anyhow::bail!(
+                    "Line {}: expected outcome,payout[,interpolation].",
+                    index + 1
+                );
+            }
+            let outcome = fields[0]
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Line {}: invalid outcome. {}", index + 1, e))?;
+            let payout = fields[1]
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Line {}: invalid payout. {}", index + 1, e))?;
+            let interpolation = fields
+                .get(2)
+                .map(|i| i.parse())
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Line {}: {}", index + 1, e))?
+                .unwrap_or_default();
+            builder.add_point(outcome, Amount::from_sat(payout), interpolation);
+        }
+        Ok(builder)
+    }
+
+    /// Load the points from a JSON array of [`CurvePoint`]s.
+    pub fn from_json(total_collateral: Amount, json: &str) -> anyhow::Result<Self> {
+        let mut builder = Self::new(total_collateral);
+        builder.points = serde_json::from_str(json)?;
+        Ok(builder)
+    }
+
+    /// Add a point to the curve. Points must be added in ascending order of outcome.
+    pub fn add_point(
+        &mut self,
+        outcome: u64,
+        payout: Amount,
+        interpolation: Interpolation,
+    ) -> &mut Self {
+        self.points.push(CurvePoint {
+            outcome,
+            payout: payout.to_sat(),
+            interpolation,
+        });
+        self
+    }
+
+    /// Set the base and number of digits the oracle attests to.
+    pub fn set_oracle_numeric_info(&mut self, oracle_numeric_info: OracleNumericInfo) -> &mut Self {
+        self.oracle_numeric_info = oracle_numeric_info;
+        self
+    }
+
+    /// Set the rounding intervals instead of deriving them from the total collateral.
+    pub fn set_rounding_intervals(&mut self, rounding_intervals: RoundingIntervals) -> &mut Self {
+        self.rounding_intervals = Some(rounding_intervals);
+        self
+    }
+
+    /// Set how far from the input points linear segments may move when merged into one piece.
+    pub fn set_tolerance(&mut self, tolerance: Amount) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn build(&self) -> anyhow::Result<PayoutCurve> {
+        let max_outcome = self.max_outcome()?;
+        self.validate(max_outcome)?;
+
+        let segments = self.segments(max_outcome);
+        let pieces = self.pieces(&segments)?;
+        let payout_function = PayoutFunction::new(pieces)?;
+        payout_function.validate(max_outcome)?;
+
+        let rounding_intervals = self
+            .rounding_intervals
+            .clone()
+            .unwrap_or_else(|| default_rounding_intervals(self.total_collateral));
+        rounding_intervals.validate()?;
+
+        let range_payouts =
+            payout_function.to_range_payouts(self.total_collateral, &rounding_intervals)?;
+        let nb_digits = self.oracle_numeric_info.get_min_nb_digits();
+        let mut cet_count = 0;
+        let mut max_error = 0.0_f64;
+        for range in &range_payouts {
+            let start = range.start as u64;
+            let end = start + range.count as u64 - 1;
+            cet_count += group_by_ignoring_digits(
+                range.start,
+                end as usize,
+                self.oracle_numeric_info.base,
+                nb_digits,
+            )
+            .len();
+
+            // The input curve is monotonic between knots, so its largest distance to a constant
+            // payout over the range is at the range ends or at a knot inside it.
+            let payout = range.payout.offer.to_sat() as f64;
+            let knots = segments
+                .iter()
+                .flat_map(|s| [s.start.0, s.end.0.saturating_sub(1), s.end.0])
+                .filter(|knot| (start..=end).contains(knot));
+            for outcome in [start, end].into_iter().chain(knots) {
+                max_error = max_error.max((evaluate(&segments, outcome) - payout).abs());
+            }
+        }
+
+        Ok(PayoutCurve {
+            payout_function,
+            rounding_intervals,
+            oracle_numeric_info: self.oracle_numeric_info.clone(),
+            cet_count,
+            max_error: Amount::from_sat(max_error.round() as u64),
+        })
+    }
+
+    fn max_outcome(&self) -> anyhow::Result<u64> {
+        let base = self.oracle_numeric_info.base as u64;
+        let nb_digits = self.oracle_numeric_info.get_min_nb_digits() as u32;
+        base.checked_pow(nb_digits)
+            .map(|outcomes| outcomes - 1)
+            .ok_or_else(|| anyhow::anyhow!("The oracle outcomes do not fit in a u64."))
+    }
+
+    fn validate(&self, max_outcome: u64) -> anyhow::Result<()> {
+        if self.points.len() < 2 {
+            anyhow::bail!("A payout curve needs at least two points.");
+        }
+        for pair in self.points.windows(2) {
+            if pair[0].outcome >= pair[1].outcome {
+                anyhow::bail!(
+                    "Point outcomes must be strictly ascending. {} is followed by {}.",
+                    pair[0].outcome,
+                    pair[1].outcome
+                );
+            }
+            if pair[0].interpolation == Interpolation::Hyperbola && pair[0].outcome == 0 {
+                anyhow::bail!("A hyperbola cannot start at outcome 0.");
+            }
+        }
+        if let Some(point) = self
+            .points
+            .iter()
+            .find(|p| p.payout > self.total_collateral.to_sat())
+        {
+            anyhow::bail!(
+                "Payout {} at outcome {} exceeds the total collateral {}.",
+                point.payout,
+                point.outcome,
+                self.total_collateral.to_sat()
+            );
+        }
+        let last = self.points.last().expect("at least two points").outcome;
+        if last > max_outcome {
+            anyhow::bail!(
+                "Outcome {} exceeds the largest outcome the oracle can attest to, {}.",
+                last,
+                max_outcome
+            );
+        }
+        Ok(())
+    }
+
+    /// The input curve as segments covering every outcome, with steps expanded into a flat
+    /// segment and a jump over the last outcome before the next point.
+    fn segments(&self, max_outcome: u64) -> Vec<Segment> {
+        let first = &self.points[0];
+        let last = &self.points[self.points.len() - 1];
+        let mut segments = Vec::new();
+        if first.outcome > 0 {
+            segments.push(Segment::linear(
+                (0, first.payout),
+                (first.outcome, first.payout),
+            ));
+        }
+        for pair in self.points.windows(2) {
+            let start = (pair[0].outcome, pair[0].payout);
+            let end = (pair[1].outcome, pair[1].payout);
+            match pair[0].interpolation {
+                _ if start.1 == end.1 => segments.push(Segment::linear(start, end)),
+                Interpolation::Step if end.0 - start.0 > 1 => {
+                    let corner = (end.0 - 1, start.1);
+                    segments.push(Segment::linear(start, corner));
+                    segments.push(Segment::linear(corner, end));
+                }
+                Interpolation::Hyperbola => segments.push(Segment::hyperbola(start, end)),
+                _ => segments.push(Segment::linear(start, end)),
+            }
+        }
+        if last.outcome < max_outcome {
+            segments.push(Segment::linear(
+                (last.outcome, last.payout),
+                (max_outcome, last.payout),
+            ));
+        }
+        segments
+    }
+
+    fn pieces(&self, segments: &[Segment]) -> anyhow::Result<Vec<PayoutFunctionPiece>> {
+        let tolerance = self.tolerance.to_sat() as f64;
+        let mut pieces = Vec::new();
+        // Points of the run of linear segments being merged into one piece.
+        let mut run: Vec<(u64, u64)> = Vec::new();
+        for segment in segments {
+            match segment.kind {
+                SegmentKind::Linear => {
+                    if run.is_empty() {
+                        run.push(segment.start);
+                    } else if !within_tolerance(&run, segment.end, tolerance) {
+                        let start = *run.last().expect("run is not empty");
+                        pieces.push(linear_piece(run[0], start)?);
+                        run = vec![start];
+                    }
+                    run.push(segment.end);
+                }
+                SegmentKind::Hyperbola { k, m } => {
+                    if let (Some(first), Some(last)) = (run.first(), run.last()) {
+                        pieces.push(linear_piece(*first, *last)?);
+                        run.clear();
+                    }
+                    let piece = HyperbolaPayoutCurvePiece::new(
+                        payout_point(segment.start),
+                        payout_point(segment.end),
+                        true,
+                        0.0,
+                        m,
+                        1.0,
+                        0.0,
+                        0.0,
+                        k,
+                    )?;
+                    pieces.push(PayoutFunctionPiece::HyperbolaPayoutCurvePiece(piece));
+                }
+            }
+        }
+        if let (Some(first), Some(last)) = (run.first(), run.last()) {
+            pieces.push(linear_piece(*first, *last)?);
+        }
+        Ok(pieces)
+    }
+}
+
+/// Round to the largest power of ten below a thousandth of the total collateral.
+fn default_rounding_intervals(total_collateral: Amount) -> RoundingIntervals {
+    let mut rounding_mod = 1;
+    while rounding_mod * 10 <= total_collateral.to_sat() / 1000 {
+        rounding_mod *= 10;
+    }
+    RoundingIntervals {
+        intervals: vec![RoundingInterval {
+            begin_interval: 0,
+            rounding_mod,
+        }],
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SegmentKind {
+    Linear,
+    /// `payout = k / outcome + m`
+    Hyperbola {
+        k: f64,
+        m: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: (u64, u64),
+    end: (u64, u64),
+    kind: SegmentKind,
+}
+
+impl Segment {
+    fn linear(start: (u64, u64), end: (u64, u64)) -> Self {
+        Segment {
+            start,
+            end,
+            kind: SegmentKind::Linear,
+        }
+    }
+
+    fn hyperbola(start: (u64, u64), end: (u64, u64)) -> Self {
+        let (x1, y1) = (start.0 as f64, start.1 as f64);
+        let (x2, y2) = (end.0 as f64, end.1 as f64);
+        let k = (y1 - y2) / (1.0 / x1 - 1.0 / x2);
+        let m = y1 - k / x1;
+        Segment {
+            start,
+            end,
+            kind: SegmentKind::Hyperbola { k, m },
+        }
+    }
+
+    fn evaluate(&self, outcome: u64) -> f64 {
+        match self.kind {
+            SegmentKind::Linear => interpolate(self.start, self.end, outcome),
+            SegmentKind::Hyperbola { k, m } => k / outcome as f64 + m,
+        }
+    }
+}
+
+/// Payout of the input curve at the outcome.
+fn evaluate(segments: &[Segment], outcome: u64) -> f64 {
+    segments
+        .iter()
+        .find(|s| s.start.0 <= outcome && outcome <= s.end.0)
+        .map(|s| s.evaluate(outcome))
+        .unwrap_or_default()
+}
+
+fn interpolate(start: (u64, u64), end: (u64, u64), outcome: u64) -> f64 {
+    let slope = (end.1 as f64 - start.1 as f64) / (end.0 - start.0) as f64;
+    start.1 as f64 + slope * (outcome - start.0) as f64
+}
+
+/// Whether the line from the start of the run to `end` passes within the tolerance of every
+/// point of the run.
+fn within_tolerance(run: &[(u64, u64)], end: (u64, u64), tolerance: f64) -> bool {
+    run[1..]
+        .iter()
+        .all(|p| (interpolate(run[0], end, p.0) - p.1 as f64).abs() <= tolerance + 1e-6)
+}
+
+fn payout_point((outcome, payout): (u64, u64)) -> PayoutPoint {
+    PayoutPoint {
+        event_outcome: outcome,
+        outcome_payout: Amount::from_sat(payout),
+        extra_precision: 0,
+    }
+}
+
+fn linear_piece(start: (u64, u64), end: (u64, u64)) -> anyhow::Result<PayoutFunctionPiece> {
+    let piece = PolynomialPayoutCurvePiece::new(vec![payout_point(start), payout_point(end)])?;
+    Ok(PayoutFunctionPiece::PolynomialPayoutCurvePiece(piece))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piece_count(curve: &PayoutCurve) -> usize {
+        let function = serde_json::to_value(&curve.payout_function).unwrap();
+        function["payoutFunctionPieces"].as_array().unwrap().len()
+    }
+
+    fn payout_at(curve: &PayoutCurve, total_collateral: Amount, outcome: u64) -> u64 {
+        curve
+            .payout_function
+            .to_range_payouts(total_collateral, &curve.rounding_intervals)
+            .unwrap()
+            .into_iter()
+            .find(|r| r.start as u64 <= outcome && outcome < (r.start + r.count) as u64)
+            .unwrap()
+            .payout
+            .offer
+            .to_sat()
+    }
+
+    #[test]
+    fn collinear_points_merge_into_one_piece() {
+        let total_collateral = Amount::from_sat(102_300);
+        let mut builder = PayoutCurveBuilder::new(total_collateral);
+        builder
+            .set_oracle_numeric_info(OracleNumericInfo {
+                base: 2,
+                nb_digits: vec![10],
+            })
+            .add_point(0, Amount::ZERO, Interpolation::Linear)
+            .add_point(341, Amount::from_sat(34_100), Interpolation::Linear)
+            .add_point(682, Amount::from_sat(68_200), Interpolation::Linear)
+            .add_point(1023, Amount::from_sat(102_300), Interpolation::Linear);
+        let curve = builder.build().unwrap();
+
+        assert_eq!(piece_count(&curve), 1);
+        assert_eq!(curve.rounding_intervals.intervals[0].rounding_mod, 100);
+        assert!(curve.cet_count > 0);
+        assert!(curve.max_error <= Amount::from_sat(50));
+
+        let mut builder = PayoutCurveBuilder::new(total_collateral);
+        builder
+            .set_oracle_numeric_info(OracleNumericInfo {
+                base: 2,
+                nb_digits: vec![10],
+            })
+            .add_point(0, Amount::ZERO, Interpolation::Linear)
+            .add_point(512, Amount::from_sat(51_300), Interpolation::Linear)
+            .add_point(1023, Amount::from_sat(102_300), Interpolation::Linear);
+        assert_eq!(piece_count(&builder.build().unwrap()), 2);
+
+        builder.set_tolerance(Amount::from_sat(200));
+        let curve = builder.build().unwrap();
+        assert_eq!(piece_count(&curve), 1);
+        assert!(curve.max_error >= Amount::from_sat(100));
+    }
+
+    #[test]
+    fn csv_steps_and_hyperbolas() {
+        let total_collateral = Amount::from_sat(200_000);
+        let csv = "outcome,payout,interpolation\n\
+                   # long below 1000, capped above 4000\n\
+                   1000,0,step\n\
+                   2000,100000,hyperbola\n\
+                   4000,150000\n";
+        let mut builder = PayoutCurveBuilder::from_csv(total_collateral, csv).unwrap();
+        builder
+            .set_oracle_numeric_info(OracleNumericInfo {
+                base: 10,
+                nb_digits: vec![4],
+            })
+            .set_rounding_intervals(RoundingIntervals {
+                intervals: vec![RoundingInterval {
+                    begin_interval: 0,
+                    rounding_mod: 1,
+                }],
+            });
+        let curve = builder.build().unwrap();
+
+        assert_eq!(payout_at(&curve, total_collateral, 500), 0);
+        assert_eq!(payout_at(&curve, total_collateral, 1998), 0);
+        assert_eq!(payout_at(&curve, total_collateral, 2000), 100_000);
+        // k = -200_000_000 and m = 200_000 through (2000, 100_000) and (4000, 150_000).
+        assert_eq!(payout_at(&curve, total_collateral, 2500), 120_000);
+        assert_eq!(payout_at(&curve, total_collateral, 9999), 150_000);
+        assert!(curve.max_error <= Amount::from_sat(1));
+
+        let json = r#"[
+            { "outcome": 1000, "payout": 0, "interpolation": "step" },
+            { "outcome": 2000, "payout": 100000, "interpolation": "hyperbola" },
+            { "outcome": 4000, "payout": 150000 }
+        ]"#;
+        let from_json = PayoutCurveBuilder::from_json(total_collateral, json).unwrap();
+        assert_eq!(from_json.points, builder.points);
+    }
+
+    #[test]
+    fn invalid_points_are_rejected() {
+        let total_collateral = Amount::from_sat(100_000);
+        let build = |csv: &str| {
+            PayoutCurveBuilder::from_csv(total_collateral, csv).and_then(|builder| builder.build())
+        };
+
+        assert!(build("10,0\n").is_err());
+        assert!(build("10,0\n5,100\n").is_err());
+        assert!(build("0,0\n10,100001\n").is_err());
+        assert!(build("0,0,hyperbola\n10,100\n").is_err());
+        assert!(build("0,0,curve\n10,100\n").is_err());
+        assert!(build("0,0\n2000000,100\n").is_err());
+        // Only a row naming the columns is a header, a malformed first point is an error.
+        assert!(build("x0,0\n10,100\n").is_err());
+        assert!(build("Outcome,Payout\n0,0\n10,100\n").is_ok());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod cfd;
+pub mod curve;
 pub mod enumeration;
 pub mod options;
 pub(crate) mod options_builder;
@@ -0,0 +1,54 @@
+use anyhow::Context;
+use clap::Parser;
+use ddk::chain::EsploraClient;
+use ddk::logger::{LogLevel, Logger};
+use ddk_watchtower::opts::WatchtowerOpts;
+use ddk_watchtower::store::SledBlobStore;
+use ddk_watchtower::Watchtower;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = WatchtowerOpts::parse();
+    let filter = match opts.log.as_str() {
+        "debug" => "debug,hyper_util=info,sled=info",
+        _ => "info",
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(filter))
+        .init();
+
+    let storage_dir = match opts.storage_dir {
+        Some(dir) => dir,
+        None => homedir::my_home()?
+            .context("Could not find the home directory.")?
+            .join(".ddk-watchtower"),
+    };
+    std::fs::create_dir_all(&storage_dir)?;
+    let store = SledBlobStore::new(&storage_dir.join("sled").to_string_lossy())
+        .map_err(|e| anyhow::anyhow!("Could not open sled storage: {e}"))?;
+
+    let logger = Arc::new(Logger::console(
+        "watchtower".to_string(),
+        LogLevel::from(opts.log.clone()),
+    ));
+    let blockchain = EsploraClient::new(&opts.esplora, opts.network, logger)
+        .map_err(|e| anyhow::anyhow!("Could not create the esplora client: {e}"))?;
+
+    let watchtower = Arc::new(Watchtower::new(Arc::new(blockchain), Arc::new(store)));
+    let scanner = watchtower.clone();
+    let scan_interval = Duration::from_secs(opts.scan_interval);
+    tokio::spawn(async move { scanner.run(scan_interval).await });
+
+    let listener = tokio::net::TcpListener::bind(&opts.host).await?;
+    tracing::info!(
+        host = opts.host,
+        network = opts.network.to_string(),
+        "Watchtower listening."
+    );
+    ddk_watchtower::serve(listener, watchtower, opts.api_token).await?;
+
+    Ok(())
+}
@@ -0,0 +1,419 @@
+//! Watchtower punishing revoked DLC channel states on behalf of offline nodes.
+//!
+//! A node exports the justice data of its revoked channel states with
+//! `Manager::export_justice_blobs` and uploads it here. Every blob is sealed
+//! with the id of the revoked transaction it punishes, so the watchtower only
+//! learns about a channel when the counter party cheats. The [`Watchtower`]
+//! scans every block through the [`Blockchain`] trait, opens the blobs filed
+//! under the hint of each transaction and broadcasts the punishment
+//! transactions.
+//!
+//! The HTTP API takes JSON, using the serde representation of
+//! [`EncryptedJusticeBlob`]. Uploads must carry the API token of the
+//! watchtower as an `Authorization: Bearer <token>` header, and are answered
+//! with `401 Unauthorized` otherwise.
+//!
+//! | Method | Path | Body | Response |
+//! | --- | --- | --- | --- |
+//! | `GET` | `/health-check` | | `OK` |
+//! | `POST` | `/blobs` | a list of blobs | the number of stored blobs |
+
+pub mod opts;
+pub mod store;
+
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use bitcoin::secp256k1::{All, Secp256k1};
+use bitcoin::{Transaction, Txid};
+use ddk_manager::error::Error;
+use ddk_manager::watchtower::{justice_hint, EncryptedJusticeBlob};
+use ddk_manager::Blockchain;
+use store::BlobStore;
+use tokio::net::TcpListener;
+
+/// Watches the chain for revoked transactions and punishes them.
+pub struct Watchtower<B: Deref, S: Deref>
+where
+    B::Target: Blockchain,
+    S::Target: BlobStore,
+{
+    blockchain: B,
+    store: S,
+    secp: Secp256k1<All>,
+}
+
+impl<B: Deref, S: Deref> Watchtower<B, S>
+where
+    B::Target: Blockchain,
+    S::Target: BlobStore,
+{
+    /// Creates a watchtower fetching blocks from `blockchain` and keeping its
+    /// blobs in `store`.
+    pub fn new(blockchain: B, store: S) -> Self {
+        Self {
+            blockchain,
+            store,
+            secp: Secp256k1::new(),
+        }
+    }
+
+    /// Stores `blob` next to the blobs already filed under its hint.
+    pub async fn add_blob(&self, blob: EncryptedJusticeBlob) -> Result<(), Error> {
+        self.store.put_blob(blob).await
+    }
+
+    /// Scans the blocks mined since the last scan and returns the ids of the
+    /// punishment transactions that were broadcast.
+    ///
+    /// The first scan starts at the current tip. A block is only recorded as
+    /// scanned once every punishment transaction found in it was broadcast, so
+    /// a failed scan is resumed from the block it failed at.
+    pub async fn scan(&self) -> Result<Vec<Txid>, Error> {
+        let tip = self.blockchain.get_blockchain_height().await?;
+        let mut height = match self.store.get_last_scanned_height().await? {
+            Some(last_scanned) => last_scanned + 1,
+            None => tip,
+        };
+
+        let mut punish_txids = Vec::new();
+        while height <= tip {
+            let block = self.blockchain.get_block_at_height(height).await?;
+            for tx in &block.txdata {
+                if let Some(txid) = self.punish(tx).await? {
+                    punish_txids.push(txid);
+                }
+            }
+            self.store.set_last_scanned_height(height).await?;
+            height += 1;
+        }
+
+        Ok(punish_txids)
+    }
+
+    /// Scans the chain every `interval`, until the task is dropped.
+    pub async fn run(&self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            match self.scan().await {
+                Ok(punish_txids) => {
+                    for txid in punish_txids {
+                        tracing::info!(%txid, "Broadcast punishment transaction.");
+                    }
+                }
+                Err(e) => tracing::error!(error = e.to_string(), "Could not scan the chain."),
+            }
+        }
+    }
+
+    async fn punish(&self, tx: &Transaction) -> Result<Option<Txid>, Error> {
+        let txid = tx.compute_txid();
+        // Blobs are only filed under half of the txid, so blobs of other
+        // transactions can share the hint: only the ones opening with the
+        // txid are for this transaction.
+        let justice = self
+            .store
+            .get_blobs(&justice_hint(&txid))
+            .await?
+            .into_iter()
+            .filter_map(|blob| blob.decrypt(&txid).ok().map(|data| (blob, data)))
+            .collect::<Vec<_>>();
+        if justice.is_empty() {
+            return Ok(None);
+        }
+
+        let mut punish_tx = None;
+        for (_, data) in &justice {
+            match data.punish_transaction(&self.secp, tx) {
+                Ok(tx) => {
+                    punish_tx = Some(tx);
+                    break;
+                }
+                Err(e) => tracing::error!(
+                    %txid,
+                    error = e.to_string(),
+                    "Could not build the punishment transaction."
+                ),
+            }
+        }
+        if let Some(punish_tx) = &punish_tx {
+            self.blockchain.send_transaction(punish_tx).await?;
+        }
+
+        // The revoked transaction is punished once, the other blobs sealed
+        // for it are of no use anymore.
+        for (blob, _) in &justice {
+            self.store.remove_blob(blob).await?;
+        }
+
+        Ok(punish_tx.map(|tx| tx.compute_txid()))
+    }
+}
+
+/// Builds the routes of the server, accepting uploads bearing `api_token`.
+pub fn router<B, S>(watchtower: Arc<Watchtower<B, S>>, api_token: String) -> Router
+where
+    B: Deref + Send + Sync + 'static,
+    B::Target: Blockchain,
+    S: Deref + Send + Sync + 'static,
+    S::Target: BlobStore,
+{
+    let uploads = Router::new()
+        .route("/blobs", post(add_blobs::<B, S>))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::new(api_token),
+            authenticate,
+        ));
+
+    Router::new()
+        .route("/health-check", get(health_check))
+        .merge(uploads)
+        .with_state(watchtower)
+}
+
+/// Serves the API of `watchtower` on `listener` until the process stops.
+pub async fn serve<B, S>(
+    listener: TcpListener,
+    watchtower: Arc<Watchtower<B, S>>,
+    api_token: String,
+) -> std::io::Result<()>
+where
+    B: Deref + Send + Sync + 'static,
+    B::Target: Blockchain,
+    S: Deref + Send + Sync + 'static,
+    S::Target: BlobStore,
+{
+    axum::serve(listener, router(watchtower, api_token)).await
+}
+
+/// An error response, with the error message as its body.
+struct ApiError(Error);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        tracing::error!(error = self.0.to_string(), "Watchtower request failed.");
+        (StatusCode::INTERNAL_SERVER_ERROR, self.0.to_string()).into_response()
+    }
+}
+
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+async fn authenticate(
+    State(api_token): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        Some(token) if constant_time_eq(token.as_bytes(), api_token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn add_blobs<B, S>(
+    State(watchtower): State<Arc<Watchtower<B, S>>>,
+    Json(blobs): Json<Vec<EncryptedJusticeBlob>>,
+) -> Result<Json<usize>, ApiError>
+where
+    B: Deref + Send + Sync,
+    B::Target: Blockchain,
+    S: Deref + Send + Sync,
+    S::Target: BlobStore,
+{
+    let count = blobs.len();
+    for blob in blobs {
+        watchtower.add_blob(blob).await.map_err(ApiError)?;
+    }
+    Ok(Json(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::block::{Header, Version};
+    use bitcoin::hashes::Hash;
+    use bitcoin::{Block, BlockHash, CompactTarget, Network, TxMerkleNode};
+    use ddk_manager::ConfirmationStatus;
+    use std::sync::Mutex;
+    use store::MemoryBlobStore;
+    use tower::ServiceExt;
+
+    /// Serves the same block at every height and records the broadcasts.
+    struct MockBlockchain {
+        height: u64,
+        block: Block,
+        sent: Mutex<Vec<Transaction>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Blockchain for MockBlockchain {
+        async fn send_transaction(&self, transaction: &Transaction) -> Result<(), Error> {
+            self.sent.lock().unwrap().push(transaction.clone());
+            Ok(())
+        }
+        fn get_network(&self) -> Result<Network, Error> {
+            Ok(Network::Regtest)
+        }
+        async fn get_blockchain_height(&self) -> Result<u64, Error> {
+            Ok(self.height)
+        }
+        async fn get_block_at_height(&self, _height: u64) -> Result<Block, Error> {
+            Ok(self.block.clone())
+        }
+        async fn get_transaction(&self, _tx_id: &Txid) -> Result<Transaction, Error> {
+            Err(Error::BlockchainError("Not found".to_string()))
+        }
+        async fn get_transaction_confirmations(
+            &self,
+            _tx_id: &Txid,
+        ) -> Result<ConfirmationStatus, Error> {
+            Ok(ConfirmationStatus::NotFound)
+        }
+    }
+
+    type TestWatchtower = Watchtower<Arc<MockBlockchain>, Arc<MemoryBlobStore>>;
+
+    fn watchtower_seeing(
+        tx: &Transaction,
+    ) -> (
+        Arc<MockBlockchain>,
+        Arc<MemoryBlobStore>,
+        Arc<TestWatchtower>,
+    ) {
+        let block = Block {
+            header: Header {
+                version: Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata: vec![tx.clone()],
+        };
+        let blockchain = Arc::new(MockBlockchain {
+            height: 10,
+            block,
+            sent: Mutex::new(vec![]),
+        });
+        let store = Arc::new(MemoryBlobStore::new());
+        let watchtower = Arc::new(Watchtower::new(blockchain.clone(), store.clone()));
+        (blockchain, store, watchtower)
+    }
+
+    fn revoked_tx() -> Transaction {
+        Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        }
+    }
+
+    fn upload(blob: &EncryptedJusticeBlob, token: Option<&str>) -> axum::http::Request<Body> {
+        let mut request =
+            axum::http::Request::post("/blobs").header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        request
+            .body(Body::from(serde_json::to_vec(&[blob]).unwrap()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn scan_skips_blobs_sharing_only_the_hint() {
+        let tx = revoked_tx();
+        let (blockchain, store, watchtower) = watchtower_seeing(&tx);
+
+        let blob = EncryptedJusticeBlob {
+            hint: justice_hint(&tx.compute_txid()),
+            blob: vec![0; 64],
+        };
+        watchtower.add_blob(blob.clone()).await.unwrap();
+
+        store.set_last_scanned_height(7).await.unwrap();
+        assert!(watchtower.scan().await.unwrap().is_empty());
+        assert!(blockchain.sent.lock().unwrap().is_empty());
+        assert_eq!(Some(10), store.get_last_scanned_height().await.unwrap());
+        assert_eq!(
+            vec![blob.clone()],
+            store.get_blobs(&blob.hint).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn uploads_need_the_api_token() {
+        let (_, store, watchtower) = watchtower_seeing(&revoked_tx());
+        let router = router(watchtower, "secret".to_string());
+        let blob = EncryptedJusticeBlob {
+            hint: [1; 16],
+            blob: vec![1; 64],
+        };
+
+        for token in [None, Some("wrong")] {
+            let response = router.clone().oneshot(upload(&blob, token)).await.unwrap();
+            assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        }
+        assert!(store.get_blobs(&blob.hint).await.unwrap().is_empty());
+
+        let response = router.oneshot(upload(&blob, Some("secret"))).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            vec![blob.clone()],
+            store.get_blobs(&blob.hint).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn second_upload_with_the_same_hint_does_not_shadow_the_first() {
+        let tx = revoked_tx();
+        let (_, store, watchtower) = watchtower_seeing(&tx);
+        let router = router(watchtower, "secret".to_string());
+        let hint = justice_hint(&tx.compute_txid());
+        let first = EncryptedJusticeBlob {
+            hint,
+            blob: vec![1; 64],
+        };
+        let second = EncryptedJusticeBlob {
+            hint,
+            blob: vec![2; 64],
+        };
+
+        for blob in [&first, &second] {
+            let response = router
+                .clone()
+                .oneshot(upload(blob, Some("secret")))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+        }
+
+        let blobs = store.get_blobs(&hint).await.unwrap();
+        assert_eq!(2, blobs.len());
+        assert!(blobs.contains(&first));
+        assert!(blobs.contains(&second));
+    }
+}
@@ -0,0 +1,37 @@
+use bitcoin::Network;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser, Clone, Debug)]
+#[clap(name = "ddk-watchtower")]
+#[clap(about = "Watchtower punishing revoked DLC channel states")]
+#[clap(version = option_env ! ("CARGO_PKG_VERSION").unwrap_or("unknown"))]
+pub struct WatchtowerOpts {
+    #[arg(long)]
+    #[arg(help = "Set the log level.")]
+    #[arg(default_value = "info")]
+    #[arg(value_parser = ["info", "debug"])]
+    pub log: String,
+    #[arg(long)]
+    #[arg(default_value = "0.0.0.0:8090")]
+    #[arg(help = "Host and port the HTTP server will run on.")]
+    pub host: String,
+    #[arg(short, long)]
+    #[arg(default_value = "signet")]
+    #[arg(help = "Set the Bitcoin network the watchtower watches.")]
+    pub network: Network,
+    #[arg(long)]
+    #[arg(default_value = "https://mutinynet.com/api")]
+    #[arg(help = "Esplora server the blocks are fetched from.")]
+    pub esplora: String,
+    #[arg(short, long)]
+    #[arg(help = "The path where the blobs are stored. Defaults to ~/.ddk-watchtower.")]
+    pub storage_dir: Option<PathBuf>,
+    #[arg(long, env = "DDK_WATCHTOWER_API_TOKEN")]
+    #[arg(help = "Token nodes must present as a bearer token to upload blobs.")]
+    pub api_token: String,
+    #[arg(long)]
+    #[arg(default_value_t = 30)]
+    #[arg(help = "Seconds between two scans of the chain.")]
+    pub scan_interval: u64,
+}
@@ -0,0 +1,226 @@
+//! Storage of the justice blobs handed to the watchtower.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use bitcoin::hashes::{sha256, Hash};
+use ddk_manager::error::Error;
+use ddk_manager::watchtower::{EncryptedJusticeBlob, JUSTICE_HINT_LEN};
+use lightning::util::ser::{Readable, Writeable};
+
+/// Hint of the revoked transaction a blob is filed under.
+pub type Hint = [u8; JUSTICE_HINT_LEN];
+
+const LAST_SCANNED_HEIGHT_KEY: &[u8] = b"last_scanned_height";
+const BLOB_TREE: &[u8] = b"blobs";
+
+/// Keeps the justice blobs and how far the chain has been scanned.
+///
+/// Several blobs can be filed under the same hint: revoked transactions can
+/// share one, and a node uploads new blobs for the same revoked transaction
+/// at every export. A blob never replaces another, so that an upload cannot
+/// shadow the blob of someone else.
+#[async_trait::async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Stores `blob` next to the blobs filed under the same hint. Storing a
+    /// blob twice keeps a single copy of it.
+    async fn put_blob(&self, blob: EncryptedJusticeBlob) -> Result<(), Error>;
+    /// Returns every blob filed under `hint`.
+    async fn get_blobs(&self, hint: &Hint) -> Result<Vec<EncryptedJusticeBlob>, Error>;
+    /// Removes `blob`.
+    async fn remove_blob(&self, blob: &EncryptedJusticeBlob) -> Result<(), Error>;
+    /// Returns the height of the last block that was scanned.
+    async fn get_last_scanned_height(&self) -> Result<Option<u64>, Error>;
+    /// Records `height` as the last block that was scanned.
+    async fn set_last_scanned_height(&self, height: u64) -> Result<(), Error>;
+}
+
+/// Blob store kept in memory, for tests and short lived watchtowers.
+#[derive(Debug, Default)]
+pub struct MemoryBlobStore {
+    blobs: Mutex<HashMap<Hint, Vec<EncryptedJusticeBlob>>>,
+    last_scanned_height: Mutex<Option<u64>>,
+}
+
+impl MemoryBlobStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn put_blob(&self, blob: EncryptedJusticeBlob) -> Result<(), Error> {
+        let mut blobs = self.blobs.lock().unwrap();
+        let filed = blobs.entry(blob.hint).or_default();
+        if !filed.contains(&blob) {
+            filed.push(blob);
+        }
+        Ok(())
+    }
+
+    async fn get_blobs(&self, hint: &Hint) -> Result<Vec<EncryptedJusticeBlob>, Error> {
+        Ok(self
+            .blobs
+            .lock()
+            .unwrap()
+            .get(hint)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn remove_blob(&self, blob: &EncryptedJusticeBlob) -> Result<(), Error> {
+        let mut blobs = self.blobs.lock().unwrap();
+        if let Some(filed) = blobs.get_mut(&blob.hint) {
+            filed.retain(|b| b != blob);
+            if filed.is_empty() {
+                blobs.remove(&blob.hint);
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_last_scanned_height(&self) -> Result<Option<u64>, Error> {
+        Ok(*self.last_scanned_height.lock().unwrap())
+    }
+
+    async fn set_last_scanned_height(&self, height: u64) -> Result<(), Error> {
+        *self.last_scanned_height.lock().unwrap() = Some(height);
+        Ok(())
+    }
+}
+
+/// Blob store persisted in a sled database.
+///
+/// Blobs are keyed by their hint followed by the hash of their content, so
+/// that the blobs filed under a hint are found with a prefix scan.
+#[derive(Debug, Clone)]
+pub struct SledBlobStore {
+    db: sled::Db,
+}
+
+impl SledBlobStore {
+    /// Opens, or creates, the database at `path`.
+    pub fn new(path: &str) -> Result<Self, Error> {
+        let db = sled::open(path).map_err(to_storage_error)?;
+        Ok(Self { db })
+    }
+
+    fn blob_tree(&self) -> Result<sled::Tree, Error> {
+        self.db.open_tree(BLOB_TREE).map_err(to_storage_error)
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobStore for SledBlobStore {
+    async fn put_blob(&self, blob: EncryptedJusticeBlob) -> Result<(), Error> {
+        self.blob_tree()?
+            .insert(blob_key(&blob), blob.encode())
+            .map_err(to_storage_error)?;
+        Ok(())
+    }
+
+    async fn get_blobs(&self, hint: &Hint) -> Result<Vec<EncryptedJusticeBlob>, Error> {
+        self.blob_tree()?
+            .scan_prefix(hint)
+            .values()
+            .map(|bytes| {
+                let bytes = bytes.map_err(to_storage_error)?;
+                EncryptedJusticeBlob::read(&mut lightning::io::Cursor::new(&bytes))
+                    .map_err(|e| Error::StorageError(format!("Invalid stored blob: {e}")))
+            })
+            .collect()
+    }
+
+    async fn remove_blob(&self, blob: &EncryptedJusticeBlob) -> Result<(), Error> {
+        self.blob_tree()?
+            .remove(blob_key(blob))
+            .map_err(to_storage_error)?;
+        Ok(())
+    }
+
+    async fn get_last_scanned_height(&self) -> Result<Option<u64>, Error> {
+        let height = self
+            .db
+            .get(LAST_SCANNED_HEIGHT_KEY)
+            .map_err(to_storage_error)?;
+        height
+            .map(|bytes| {
+                let bytes: [u8; 8] = bytes
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| Error::StorageError("Invalid last scanned height".to_string()))?;
+                Ok(u64::from_be_bytes(bytes))
+            })
+            .transpose()
+    }
+
+    async fn set_last_scanned_height(&self, height: u64) -> Result<(), Error> {
+        self.db
+            .insert(LAST_SCANNED_HEIGHT_KEY, &height.to_be_bytes())
+            .map_err(to_storage_error)?;
+        Ok(())
+    }
+}
+
+fn blob_key(blob: &EncryptedJusticeBlob) -> Vec<u8> {
+    let mut key = blob.hint.to_vec();
+    key.extend_from_slice(sha256::Hash::hash(&blob.blob).as_byte_array());
+    key
+}
+
+fn to_storage_error(e: sled::Error) -> Error {
+    Error::StorageError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn same_hint_uploads_are_all_kept(store: impl BlobStore) {
+        let first = EncryptedJusticeBlob {
+            hint: [1; JUSTICE_HINT_LEN],
+            blob: vec![1; 64],
+        };
+        let second = EncryptedJusticeBlob {
+            hint: [1; JUSTICE_HINT_LEN],
+            blob: vec![2; 64],
+        };
+
+        store.put_blob(first.clone()).await.unwrap();
+        store.put_blob(second.clone()).await.unwrap();
+        store.put_blob(second.clone()).await.unwrap();
+
+        let blobs = store.get_blobs(&first.hint).await.unwrap();
+        assert_eq!(2, blobs.len());
+        assert!(blobs.contains(&first));
+        assert!(blobs.contains(&second));
+
+        store.remove_blob(&second).await.unwrap();
+        assert_eq!(
+            vec![first.clone()],
+            store.get_blobs(&first.hint).await.unwrap()
+        );
+        store.remove_blob(&first).await.unwrap();
+        assert!(store.get_blobs(&first.hint).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn memory_store_keeps_every_blob_of_a_hint() {
+        same_hint_uploads_are_all_kept(MemoryBlobStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn sled_store_keeps_every_blob_of_a_hint() {
+        let path = std::env::temp_dir().join(format!(
+            "ddk-watchtower-store-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        same_hint_uploads_are_all_kept(SledBlobStore::new(&path.to_string_lossy()).unwrap()).await;
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}